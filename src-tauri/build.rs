@@ -1,3 +1,11 @@
 fn main() {
+    // `cpu_only` gates out the GPU code: set by the `cpu-only` feature, and
+    // on every target but macOS, where there is no GPU path to build
+    println!("cargo::rustc-check-cfg=cfg(cpu_only)");
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if std::env::var_os("CARGO_FEATURE_CPU_ONLY").is_some() || target_os != "macos" {
+        println!("cargo::rustc-cfg=cpu_only");
+    }
+
     tauri_build::build()
 }
@@ -0,0 +1,13 @@
+//! Feeds arbitrary bytes to the GGUF metadata parser.
+//!
+//! Run with `cargo +nightly fuzz run fuzz_gguf_parser` from `src-tauri`.
+//! Any panic, overflow or out-of-memory abort is a bug: malformed input must
+//! come back as `Err`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use minerva_lib::models::gguf_parser::GGUFParser;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = GGUFParser::parse(data);
+});
@@ -7,4 +7,4 @@ pub mod types;
 pub mod validator;
 
 pub use types::{ApiError, ApiErrorResponse, ApiResponse, ResponseMetadata};
-pub use validator::ProtocolValidator;
+pub use validator::{ProtocolValidator, ValidationError};
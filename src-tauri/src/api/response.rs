@@ -1,6 +1,7 @@
 //! API response handling
 
 use super::types::{ApiError, ApiErrorResponse};
+use crate::error::MinervaError;
 use axum::Json;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -38,3 +39,129 @@ impl IntoResponse for ApiErrorResponse {
         (status, Json(self)).into_response()
     }
 }
+
+impl ApiErrorResponse {
+    /// Build the OpenAI error envelope for a server error
+    ///
+    /// `type` is the OpenAI error type; `code` keeps Minerva's more specific
+    /// error code so clients can still tell e.g. GPU and host OOM apart.
+    pub fn from_minerva_error(err: &MinervaError) -> Self {
+        Self {
+            error: ApiError {
+                message: error_message(err),
+                code: error_code(err).to_string(),
+                type_: Some(error_type(err).to_string()),
+                param: None,
+            },
+        }
+    }
+}
+
+/// OpenAI error `type` for each error variant
+fn error_type(err: &MinervaError) -> &'static str {
+    match err {
+        MinervaError::ModelNotFound(_) => "model_not_found",
+        MinervaError::InvalidRequest(_) | MinervaError::ValidationError(_) => {
+            "invalid_request_error"
+        }
+        MinervaError::ContextLimitExceeded { .. } => "context_length_exceeded",
+        MinervaError::GenerationTimeout => "timeout",
+        MinervaError::Http(status, _) if *status == StatusCode::TOO_MANY_REQUESTS => {
+            "rate_limit_error"
+        }
+        MinervaError::Http(status, _) if status.is_client_error() => "invalid_request_error",
+        _ => "server_error",
+    }
+}
+
+fn error_code(err: &MinervaError) -> &'static str {
+    match err {
+        MinervaError::ModelNotFound(_) => "model_not_found",
+        MinervaError::InvalidRequest(_) => "invalid_request",
+        MinervaError::InferenceError(_) => "inference_error",
+        MinervaError::ModelLoadingError { .. } => "model_loading_error",
+        MinervaError::ContextLimitExceeded { .. } => "context_limit_exceeded",
+        MinervaError::GenerationTimeout => "generation_timeout",
+        MinervaError::OutOfMemory(_) => "out_of_memory",
+        MinervaError::GpuOutOfMemory(_) => "gpu_out_of_memory",
+        MinervaError::GpuContextLost(_) => "gpu_context_lost",
+        MinervaError::ModelCorrupted(_) => "model_corrupted",
+        MinervaError::StreamingError(_) => "streaming_error",
+        MinervaError::ValidationError(_) => "validation_error",
+        MinervaError::UnsupportedFormat(_) => "unsupported_format",
+        MinervaError::Http(status, _) if *status == StatusCode::TOO_MANY_REQUESTS => {
+            "rate_limit_exceeded"
+        }
+        MinervaError::Http(..) => "http_error",
+        _ => "server_error",
+    }
+}
+
+fn error_message(err: &MinervaError) -> String {
+    match err {
+        MinervaError::ModelNotFound(msg)
+        | MinervaError::InvalidRequest(msg)
+        | MinervaError::InferenceError(msg)
+        | MinervaError::ModelLoadingError { msg, .. }
+        | MinervaError::OutOfMemory(msg)
+        | MinervaError::ValidationError(msg)
+        | MinervaError::UnsupportedFormat(msg)
+        | MinervaError::Http(_, msg) => msg.clone(),
+        MinervaError::ContextLimitExceeded { max, required } => format!(
+            "Context limit exceeded: model supports {}, request requires {}",
+            max, required
+        ),
+        MinervaError::GenerationTimeout => "Generation request timed out".to_string(),
+        MinervaError::GpuOutOfMemory(msg) => {
+            format!("GPU memory exhausted, retry later: {}", msg)
+        }
+        MinervaError::GpuContextLost(msg) => {
+            format!("GPU context lost, will reinitialize: {}", msg)
+        }
+        MinervaError::ModelCorrupted(msg) => format!("Model file corrupted: {}", msg),
+        MinervaError::StreamingError(msg) => format!("Streaming error (retryable): {}", msg),
+        _ => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(err: MinervaError) -> serde_json::Value {
+        serde_json::to_value(ApiErrorResponse::from_minerva_error(&err)).unwrap()
+    }
+
+    #[test]
+    fn test_envelope_has_openai_fields() {
+        let json = envelope(MinervaError::InvalidRequest("bad temperature".to_string()));
+        assert_eq!(json["error"]["type"], "invalid_request_error");
+        assert_eq!(json["error"]["message"], "bad temperature");
+        assert_eq!(json["error"]["code"], "invalid_request");
+        assert!(json["error"]["param"].is_null());
+    }
+
+    #[test]
+    fn test_error_type_mapping() {
+        let model = envelope(MinervaError::ModelNotFound("llama".to_string()));
+        assert_eq!(model["error"]["type"], "model_not_found");
+
+        let context = envelope(MinervaError::ContextLimitExceeded {
+            max: 2048,
+            required: 4096,
+        });
+        assert_eq!(context["error"]["type"], "context_length_exceeded");
+
+        let oom = envelope(MinervaError::GpuOutOfMemory("24GB".to_string()));
+        assert_eq!(oom["error"]["type"], "server_error");
+        assert_eq!(oom["error"]["code"], "gpu_out_of_memory");
+
+        let limited = envelope(MinervaError::Http(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Rate limit exceeded".to_string(),
+        ));
+        assert_eq!(limited["error"]["type"], "rate_limit_error");
+        assert_eq!(limited["error"]["code"], "rate_limit_exceeded");
+        assert_eq!(limited["error"]["message"], "Rate limit exceeded");
+    }
+}
@@ -13,9 +13,10 @@ pub struct ApiErrorResponse {
 pub struct ApiError {
     pub message: String,
     pub code: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub type_: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Always serialized; OpenAI clients expect `"param": null` when unset
+    #[serde(default)]
     pub param: Option<String>,
 }
 
@@ -1,6 +1,16 @@
 //! API protocol validation
+//!
+//! The one set of rules for chat completion requests: the field checks below
+//! and `validate_chat_request`, which runs them on the untyped JSON so wrong
+//! types and missing fields are reported by name instead of as a generic
+//! deserialization failure.
 
 use super::types::ApiError;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Longest combined message content accepted in one chat request
+pub const MAX_PROMPT_LENGTH: usize = 2000;
 
 /// API Protocol validation rules
 pub struct ProtocolValidator;
@@ -26,6 +36,18 @@ impl ProtocolValidator {
             });
         }
 
+        if !model_id
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'))
+        {
+            return Err(ApiError {
+                message: "Model ID contains invalid characters".to_string(),
+                code: "invalid_model_id".to_string(),
+                type_: Some("invalid_request_error".to_string()),
+                param: Some("model".to_string()),
+            });
+        }
+
         Ok(())
     }
 
@@ -55,11 +77,27 @@ impl ProtocolValidator {
         Ok(())
     }
 
-    /// Validate top_p parameter
+    /// Validate the combined length of the message contents
+    pub fn validate_prompt_length(length: usize) -> Result<(), ApiError> {
+        if length > MAX_PROMPT_LENGTH {
+            return Err(ApiError {
+                message: format!(
+                    "Prompt exceeds maximum length of {} characters (got {})",
+                    MAX_PROMPT_LENGTH, length
+                ),
+                code: "invalid_parameter".to_string(),
+                type_: Some("invalid_request_error".to_string()),
+                param: Some("messages".to_string()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Validate top_p parameter (0, 1]
     pub fn validate_top_p(top_p: f32) -> Result<(), ApiError> {
-        if !(0.0..=1.0).contains(&top_p) {
+        if top_p <= 0.0 || top_p > 1.0 {
             return Err(ApiError {
-                message: "top_p must be between 0 and 1".to_string(),
+                message: "top_p must be between 0 (exclusive) and 1".to_string(),
                 code: "invalid_parameter".to_string(),
                 type_: Some("invalid_request_error".to_string()),
                 param: Some("top_p".to_string()),
@@ -67,11 +105,206 @@ impl ProtocolValidator {
         }
         Ok(())
     }
+
+    /// Validate a raw chat completion body before deserializing it
+    ///
+    /// Returns every problem found (including unknown-field warnings) if any
+    /// of them is an error; a request with only warnings passes.
+    pub fn validate_chat_request(value: &Value) -> Result<(), Vec<ValidationError>> {
+        let Some(body) = value.as_object() else {
+            return Err(vec![ValidationError::invalid_type("request", "an object")]);
+        };
+
+        let mut issues = Vec::new();
+        issues.extend(check_model(body));
+        issues.extend(check_messages(body));
+        issues.extend(check_number(
+            body,
+            "temperature",
+            Self::validate_temperature,
+        ));
+        issues.extend(check_number(body, "top_p", Self::validate_top_p));
+        issues.extend(check_max_tokens(body));
+        issues.extend(
+            body.keys()
+                .filter(|key| !KNOWN_FIELDS.contains(&key.as_str()))
+                .map(|key| ValidationError::UnknownField(key.clone())),
+        );
+
+        if issues.iter().all(ValidationError::is_warning) {
+            for warning in &issues {
+                tracing::debug!("Chat request: {}", warning);
+            }
+            return Ok(());
+        }
+        Err(issues)
+    }
+}
+
+/// Roles accepted in `messages[].role`
+pub const VALID_ROLES: &[&str] = &["system", "user", "assistant", "tool"];
+
+/// Top-level keys understood by `ChatCompletionRequest`
+const KNOWN_FIELDS: &[&str] = &[
+    "model",
+    "messages",
+    "temperature",
+    "max_tokens",
+    "stream",
+    "top_p",
+    "frequency_penalty",
+    "presence_penalty",
+    "stream_options",
+];
+
+/// A single schema problem in a chat request
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    MissingField(String),
+    InvalidType {
+        field: String,
+        expected: String,
+    },
+    InvalidValue {
+        field: String,
+        reason: String,
+    },
+    /// Not an error: logged for debugging and otherwise ignored
+    UnknownField(String),
+}
+
+impl ValidationError {
+    pub fn is_warning(&self) -> bool {
+        matches!(self, Self::UnknownField(_))
+    }
+
+    fn invalid_type(field: &str, expected: &str) -> Self {
+        Self::InvalidType {
+            field: field.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    fn invalid_value(field: &str, reason: &str) -> Self {
+        Self::InvalidValue {
+            field: field.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// A value one of the `ProtocolValidator` field rules turned down
+    fn rejected(field: &str, err: ApiError) -> Self {
+        Self::InvalidValue {
+            field: field.to_string(),
+            reason: err.message,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "missing required field '{}'", field),
+            Self::InvalidType { field, expected } => {
+                write!(f, "'{}' must be {}", field, expected)
+            }
+            Self::InvalidValue { field, reason } => write!(f, "'{}' {}", field, reason),
+            Self::UnknownField(field) => write!(f, "unknown field '{}'", field),
+        }
+    }
+}
+
+fn check_model(body: &Map<String, Value>) -> Option<ValidationError> {
+    match body.get("model") {
+        None => Some(ValidationError::MissingField("model".to_string())),
+        Some(Value::String(model)) => ProtocolValidator::validate_model_id(model)
+            .err()
+            .map(|e| ValidationError::rejected("model", e)),
+        Some(_) => Some(ValidationError::invalid_type("model", "a string")),
+    }
+}
+
+fn check_messages(body: &Map<String, Value>) -> Vec<ValidationError> {
+    match body.get("messages") {
+        None => vec![ValidationError::MissingField("messages".to_string())],
+        Some(Value::Array(messages)) if messages.is_empty() => {
+            vec![ValidationError::invalid_value(
+                "messages",
+                "must not be empty",
+            )]
+        }
+        Some(Value::Array(messages)) => {
+            let mut issues: Vec<ValidationError> = messages
+                .iter()
+                .enumerate()
+                .flat_map(check_message)
+                .collect();
+            let length = messages
+                .iter()
+                .filter_map(|m| m.get("content").and_then(Value::as_str))
+                .map(str::len)
+                .sum();
+            if let Err(e) = ProtocolValidator::validate_prompt_length(length) {
+                issues.push(ValidationError::rejected("messages", e));
+            }
+            issues
+        }
+        Some(_) => vec![ValidationError::invalid_type("messages", "an array")],
+    }
+}
+
+fn check_message((index, message): (usize, &Value)) -> Vec<ValidationError> {
+    let field = |name: &str| format!("messages[{}].{}", index, name);
+    let mut issues = Vec::new();
+
+    match message.get("role").and_then(Value::as_str) {
+        Some(role) if VALID_ROLES.contains(&role) => {}
+        Some(_) => issues.push(ValidationError::invalid_value(
+            &field("role"),
+            "must be one of system, user, assistant, tool",
+        )),
+        None => issues.push(ValidationError::invalid_type(&field("role"), "a string")),
+    }
+    if !message.get("content").is_some_and(Value::is_string) {
+        issues.push(ValidationError::invalid_type(&field("content"), "a string"));
+    }
+    issues
+}
+
+/// Check an optional float parameter with one of the field rules above
+fn check_number(
+    body: &Map<String, Value>,
+    field: &str,
+    rule: fn(f32) -> Result<(), ApiError>,
+) -> Option<ValidationError> {
+    match body.get(field) {
+        None | Some(Value::Null) => None,
+        Some(Value::Number(n)) => rule(n.as_f64().unwrap_or(f64::NAN) as f32)
+            .err()
+            .map(|e| ValidationError::rejected(field, e)),
+        Some(_) => Some(ValidationError::invalid_type(field, "a number")),
+    }
+}
+
+fn check_max_tokens(body: &Map<String, Value>) -> Option<ValidationError> {
+    match body.get("max_tokens") {
+        None | Some(Value::Null) => None,
+        Some(value) => match value.as_u64() {
+            Some(n) => ProtocolValidator::validate_max_tokens(u32::try_from(n).unwrap_or(u32::MAX))
+                .err()
+                .map(|e| ValidationError::rejected("max_tokens", e)),
+            None => Some(ValidationError::invalid_type(
+                "max_tokens",
+                "a positive integer",
+            )),
+        },
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_validate_model_id_valid() {
@@ -112,7 +345,7 @@ mod tests {
 
     #[test]
     fn test_validate_top_p_valid() {
-        assert!(ProtocolValidator::validate_top_p(0.0).is_ok());
+        assert!(ProtocolValidator::validate_top_p(0.01).is_ok());
         assert!(ProtocolValidator::validate_top_p(0.9).is_ok());
         assert!(ProtocolValidator::validate_top_p(1.0).is_ok());
     }
@@ -120,6 +353,89 @@ mod tests {
     #[test]
     fn test_validate_top_p_invalid() {
         assert!(ProtocolValidator::validate_top_p(-0.1).is_err());
+        assert!(ProtocolValidator::validate_top_p(0.0).is_err());
         assert!(ProtocolValidator::validate_top_p(1.1).is_err());
     }
+
+    fn request() -> Value {
+        json!({
+            "model": "llama-7b",
+            "messages": [{"role": "user", "content": "Hi"}],
+            "temperature": 0.7,
+            "max_tokens": 128
+        })
+    }
+
+    #[test]
+    fn test_valid_request() {
+        assert!(ProtocolValidator::validate_chat_request(&request()).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_field_is_only_a_warning() {
+        let mut body = request();
+        body["logit_bias"] = json!({});
+        assert!(ProtocolValidator::validate_chat_request(&body).is_ok());
+
+        body["model"] = json!("");
+        let issues = ProtocolValidator::validate_chat_request(&body).unwrap_err();
+        assert!(issues.contains(&ValidationError::UnknownField("logit_bias".to_string())));
+    }
+
+    #[test]
+    fn test_missing_required_fields() {
+        let issues = ProtocolValidator::validate_chat_request(&json!({})).unwrap_err();
+        assert_eq!(
+            issues,
+            vec![
+                ValidationError::MissingField("model".to_string()),
+                ValidationError::MissingField("messages".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bad_messages() {
+        let mut body = request();
+        body["messages"] = json!([{"role": "robot", "content": 1}]);
+        let issues = ProtocolValidator::validate_chat_request(&body).unwrap_err();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(
+            issues[1].to_string(),
+            "'messages[0].content' must be a string"
+        );
+    }
+
+    #[test]
+    fn test_bad_parameters() {
+        let mut body = request();
+        body["temperature"] = json!(2.5);
+        body["max_tokens"] = json!(0);
+        let issues = ProtocolValidator::validate_chat_request(&body).unwrap_err();
+        assert_eq!(issues.len(), 2);
+
+        body["temperature"] = json!("hot");
+        body["max_tokens"] = json!(1.5);
+        let issues = ProtocolValidator::validate_chat_request(&body).unwrap_err();
+        assert_eq!(issues.len(), 2);
+    }
+
+    #[test]
+    fn test_shared_field_rules() {
+        let mut body = request();
+        body["model"] = json!("model@invalid");
+        body["top_p"] = json!(1.5);
+        body["messages"] = json!([{"role": "user", "content": "x".repeat(MAX_PROMPT_LENGTH + 1)}]);
+        let issues = ProtocolValidator::validate_chat_request(&body).unwrap_err();
+
+        let fields: Vec<String> = issues
+            .iter()
+            .map(|issue| match issue {
+                ValidationError::InvalidValue { field, .. } => field.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        assert_eq!(fields, vec!["model", "messages", "top_p"]);
+    }
 }
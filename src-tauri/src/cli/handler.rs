@@ -3,6 +3,9 @@
 use super::types::ServeArgs;
 use crate::config::AppConfig;
 use crate::error::MinervaResult;
+use crate::inference::batch_async::BatchAsync;
+use crate::inference::unified_backend_routing::BackendSetup;
+use crate::server::validation::RequestValidator;
 
 /// Execute serve command - starts HTTP server without Tauri
 pub async fn serve_command(args: ServeArgs) -> MinervaResult<()> {
@@ -12,6 +15,10 @@ pub async fn serve_command(args: ServeArgs) -> MinervaResult<()> {
     if let Some(models_dir) = &args.models_dir {
         config.models_dir = models_dir.clone();
     }
+    if let Some(schema) = &args.request_schema {
+        config.server.request_schema = Some(schema.clone());
+    }
+    config.log_problems();
 
     println!("Starting Minerva server on {}:{}", args.host, args.port);
 
@@ -29,7 +36,26 @@ pub async fn serve_command(args: ServeArgs) -> MinervaResult<()> {
 
     // Create server state with discovered models
     let server_state =
-        crate::server::ServerState::with_discovered_models(config.models_dir.clone())?;
+        crate::server::ServerState::with_discovered_models(config.models_dir.clone())?
+            .with_streaming_config(config.streaming.clone())
+            .with_max_request_body_bytes(config.server.max_request_body_bytes)
+            .with_slo(config.server.slo)
+            .with_request_validator(RequestValidator::from_policy(
+                config.server.request_schema.as_deref(),
+            )?)
+            .with_discovered_backend(BackendSetup::default())
+            .await;
+
+    // Run batched forward passes until the server exits
+    tokio::spawn(BatchAsync::run(server_state.clone()));
+
+    // Sample GPU stats for /metrics until the server exits
+    #[cfg(not(cpu_only))]
+    let _gpu_sampler = server_state
+        .metrics
+        .spawn_gpu_sampler()
+        .inspect_err(|e| tracing::warn!("GPU sampling unavailable: {}", e))
+        .ok();
 
     // Create the router
     let router = crate::server::create_server(server_state).await;
@@ -26,6 +26,10 @@ pub struct ServeArgs {
     /// Number of worker threads
     #[arg(long)]
     pub workers: Option<usize>,
+
+    /// JSON Schema policy file for chat request bodies
+    #[arg(long)]
+    pub request_schema: Option<PathBuf>,
 }
 
 impl Default for ServeArgs {
@@ -36,6 +40,7 @@ impl Default for ServeArgs {
             models_dir: None,
             config: None,
             workers: None,
+            request_schema: None,
         }
     }
 }
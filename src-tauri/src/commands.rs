@@ -1,13 +1,154 @@
+pub mod conversation_export;
+pub mod downloaded_models;
+pub mod hardware_info;
 pub mod model_commands;
+pub mod model_validation;
+pub mod server_commands;
+pub mod session_commands;
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, GenerationConfigUpdate};
+use crate::error::MinervaError;
+use crate::inference::GenerationConfig;
+use crate::inference::context_manager::ContextManager;
+use crate::inference::model_registry::ModelRegistry;
+use crate::inference::preload_manager::PreloadManager;
 use crate::models::ModelInfo;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use crate::models::gguf_loader::GGUFModelLoader;
+use crate::models::gguf_repair::RepairReport;
+use crate::performance::adaptive::AdaptiveConfigManager;
+use crate::server::server_state::SharedGenerationConfig;
+use conversation_export::ExportFormat;
+use downloaded_models::DownloadedModelInfo;
+use hardware_info::HardwareInfoResponse;
+use model_validation::ValidationResult;
+use server_commands::EmbeddedServer;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+use tauri_plugin_dialog::DialogExt;
 
 /// Application state for Tauri commands
 pub struct AppState {
     pub config: Mutex<AppConfig>,
+    pub contexts: Mutex<ContextManager>,
+    /// Shared so a re-download can clear the corrupted flag when it finishes
+    pub registry: Arc<Mutex<ModelRegistry>>,
+    /// User-set sampling overrides, mirrored from `config.generation`
+    pub generation: SharedGenerationConfig,
+    pub adaptive: AdaptiveConfigManager,
+    pub server: Mutex<EmbeddedServer>,
+    /// Models holding weights in memory, evicted least recently used first
+    pub preload: Mutex<PreloadManager>,
+}
+
+/// Load `model_id` into a resident slot on a background thread
+///
+/// `path` registers the model first when the registry does not know it.
+/// Colder unpinned models are unloaded first when memory is short, and the
+/// backend gets the thread count recommended for the model's size and the
+/// memory left; failures are logged, since the model stays usable from disk.
+/// `cpu-only` builds load on the CPU backend and book no GPU memory.
+pub fn load_resident_in_background(
+    app: &tauri::AppHandle,
+    model_id: String,
+    path: Option<PathBuf>,
+) {
+    #[cfg(cpu_only)]
+    use crate::inference::system_memory::available_memory_bytes;
+    use crate::inference::unified_backend_routing::{BackendSetup, load_backend};
+    #[cfg(cpu_only)]
+    use crate::performance::adaptive_adjuster::AdaptiveAdjuster;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        let lock_models = || match (state.preload.lock(), state.registry.lock()) {
+            (Ok(preload), Ok(registry)) => Some((preload, registry)),
+            _ => {
+                tracing::warn!("Model state lock poisoned; not loading {}", model_id);
+                None
+            }
+        };
+
+        // Register the model and make room for it under the locks, then
+        // load it without them so other commands are not held up
+        let Some((mut preload, mut registry)) = lock_models() else {
+            return;
+        };
+        let registered = match path {
+            Some(path) if registry.get(&model_id).is_none() => registry.register(&model_id, path),
+            _ => Ok(()),
+        };
+        #[cfg(not(cpu_only))]
+        let gpu = preload.resident_models().gpu_context();
+        let reserved = registered.and_then(|()| preload.reserve_resident(&model_id, &mut registry));
+        drop((preload, registry));
+
+        let load = |path: &Path| {
+            let model_bytes = std::fs::metadata(path)?.len();
+            #[cfg(not(cpu_only))]
+            let setup = {
+                let n_threads = match gpu.lock() {
+                    Ok(gpu) => state
+                        .adaptive
+                        .recommended_threads_for_model(&gpu, model_bytes),
+                    Err(_) => state.adaptive.recommended_threads(),
+                };
+                BackendSetup {
+                    n_threads,
+                    gpu: Some(Arc::clone(&gpu)),
+                }
+            };
+            #[cfg(cpu_only)]
+            let setup = BackendSetup {
+                n_threads: AdaptiveAdjuster::memory_limited_threads(
+                    state.adaptive.recommended_threads(),
+                    available_memory_bytes().unwrap_or(u64::MAX),
+                    model_bytes,
+                ),
+            };
+            load_backend(path, &setup)
+        };
+        let loaded = match reserved {
+            Ok(Some(slot)) => load(&slot.path).and_then(|backend| {
+                let Some((mut preload, mut registry)) = lock_models() else {
+                    return Ok(());
+                };
+                preload.insert_resident(&mut registry, slot, backend)
+            }),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = loaded {
+            tracing::warn!("Failed to load {}: {}", model_id, e);
+            redownload_if_corrupted(&app, &model_id, &e);
+        }
+    });
+}
+
+/// Fetch `model_id` again when its load failed on a file that no longer
+/// matches its checksum
+fn redownload_if_corrupted(app: &tauri::AppHandle, model_id: &str, error: &MinervaError) {
+    use crate::error_recovery::ErrorRecovery;
+    use crate::inference::downloader::ModelDownloader;
+    use crate::inference::model_registry::ModelMetadata;
+
+    let state = app.state::<AppState>();
+    let expected = match state.registry.lock() {
+        Ok(registry) => registry.expected_hash(model_id),
+        Err(_) => return,
+    };
+    let corrupted = matches!(error, MinervaError::ModelCorrupted(_))
+        || expected.is_some_and(|(path, hash)| {
+            ModelMetadata::compute_hash(&path).is_ok_and(|actual| actual != hash)
+        });
+    if !corrupted {
+        return;
+    }
+
+    let downloader = ModelDownloader::new().with_app_handle(app.clone());
+    let _runtime = tauri::async_runtime::handle().inner().enter();
+    ErrorRecovery::handle_corrupted_model(model_id, &state.registry, &downloader);
 }
 
 /// Get application configuration
@@ -60,14 +201,15 @@ pub fn list_discovered_models(state: tauri::State<'_, AppState>) -> Result<Vec<M
 
     let loader = crate::models::loader::ModelLoader::new(config.models_dir.clone());
 
-    loader
-        .discover_models()
-        .map_err(|e| format!("Failed to discover models: {}", e))
+    loader.discover_models().map_err(|e| e.user_message())
 }
 
 /// Load a specific model by path
+///
+/// Returns the model's metadata at once; its weights are loaded in the
+/// background, registered under the file stem.
 #[tauri::command]
-pub fn load_model_file(model_path: String) -> Result<ModelInfo, String> {
+pub fn load_model_file(app: tauri::AppHandle, model_path: String) -> Result<ModelInfo, String> {
     let path = PathBuf::from(&model_path);
 
     if !path.exists() {
@@ -84,9 +226,95 @@ pub fn load_model_file(model_path: String) -> Result<ModelInfo, String> {
             .to_path_buf(),
     );
 
-    loader
-        .load_model(&path)
-        .map_err(|e| format!("Failed to load model: {}", e))
+    let info = loader.load_model(&path).map_err(|e| e.user_message())?;
+    if let Some(model_id) = path.file_stem().and_then(|s| s.to_str()) {
+        load_resident_in_background(&app, model_id.to_string(), Some(path.clone()));
+    }
+    Ok(info)
+}
+
+/// Cut a truncated GGUF download back to its complete tensors
+///
+/// Offered by the UI when `load_model_file` reports a truncated file.
+#[tauri::command]
+pub fn repair_model_file(model_path: String) -> Result<RepairReport, String> {
+    GGUFModelLoader::attempt_repair(Path::new(&model_path)).map_err(|e| e.user_message())
+}
+
+/// Pick a GGUF file in a native file dialog and load it
+///
+/// Returns `None` when the dialog is cancelled.
+#[tauri::command]
+pub async fn open_model_file_dialog(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let dialog_app = app.clone();
+    let picked = tauri::async_runtime::spawn_blocking(move || {
+        dialog_app
+            .dialog()
+            .file()
+            .set_title("Open Model")
+            .add_filter("GGUF model", &["gguf"])
+            .blocking_pick_file()
+    })
+    .await
+    .map_err(|e| format!("Open dialog failed: {}", e))?;
+
+    let Some(file) = picked else {
+        return Ok(None);
+    };
+    let path = file
+        .into_path()
+        .map_err(|e| format!("Invalid model path: {}", e))?;
+    if !path.is_file() {
+        return Err(format!("Model file not found: {}", path.display()));
+    }
+
+    let model_path = path.to_string_lossy().to_string();
+    load_model_file(app, model_path.clone())?;
+    Ok(Some(model_path))
+}
+
+/// Recorded downloads with their on-disk status and registry membership
+#[tauri::command]
+pub async fn list_downloaded_models(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DownloadedModelInfo>, String> {
+    let snapshot = {
+        let registry = state
+            .registry
+            .lock()
+            .map_err(|e| format!("Failed to lock registry: {}", e))?;
+        downloaded_models::snapshot(&registry)
+    };
+    tauri::async_runtime::spawn_blocking(move || downloaded_models::resolve(&snapshot))
+        .await
+        .map_err(|e| format!("Failed to check downloads: {}", e))
+}
+
+/// Validate, save and apply new sampling overrides
+///
+/// Fields left unset in `update` keep their current value; fields never set
+/// fall back to each model's own defaults.
+#[tauri::command]
+pub fn set_generation_config(
+    state: tauri::State<'_, AppState>,
+    update: GenerationConfigUpdate,
+) -> Result<(), String> {
+    let mut config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    let merged = config.generation.merged(&update);
+    GenerationConfig::default()
+        .with_update(&merged)
+        .validate()
+        .map_err(|e| e.to_string())?;
+
+    config.generation = merged;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.generation.write() = merged;
+    Ok(())
 }
 
 /// Ensure models directory exists and create if necessary
@@ -104,6 +332,85 @@ pub fn ensure_models_directory(state: tauri::State<'_, AppState>) -> Result<Stri
     Ok(config.models_dir.to_string_lossy().to_string())
 }
 
+/// Report CPU, memory and GPU details with a recommended model size
+///
+/// The probes shell out to system tools, so they run off the async runtime.
+#[tauri::command]
+pub async fn get_hardware_info() -> Result<HardwareInfoResponse, String> {
+    tauri::async_runtime::spawn_blocking(HardwareInfoResponse::detect)
+        .await
+        .map_err(|e| format!("Failed to probe hardware: {}", e))
+}
+
+/// Pre-flight check of a GGUF file before loading it
+///
+/// Reads only the header and metadata; problems are reported in the result
+/// rather than as an error so the UI can list them.
+#[tauri::command]
+pub fn validate_model_file(path: String) -> Result<ValidationResult, String> {
+    Ok(ValidationResult::check(std::path::Path::new(&path)))
+}
+
+/// Export a session's chat history as Markdown or JSON
+///
+/// When `path` is empty the user picks the destination in a save dialog;
+/// cancelling the dialog is not an error.
+#[tauri::command]
+pub async fn export_conversation(
+    app: tauri::AppHandle,
+    session_id: String,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), String> {
+    let contents = {
+        let state = app.state::<AppState>();
+        let contexts = state
+            .contexts
+            .lock()
+            .map_err(|e| format!("Failed to lock contexts: {}", e))?;
+        let session = contexts
+            .session(&session_id)
+            .ok_or_else(|| format!("Unknown session: {}", session_id))?;
+        conversation_export::render(session, format)
+            .map_err(|e| format!("Failed to render conversation: {}", e))?
+    };
+
+    let path = match path.as_str() {
+        "" => match pick_export_path(app, &session_id, format).await? {
+            Some(path) => path,
+            None => return Ok(()),
+        },
+        path => PathBuf::from(path),
+    };
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+async fn pick_export_path(
+    app: tauri::AppHandle,
+    session_id: &str,
+    format: ExportFormat,
+) -> Result<Option<PathBuf>, String> {
+    let extension = format.extension();
+    let file_name = format!("{}.{}", session_id, extension);
+    let picked = tauri::async_runtime::spawn_blocking(move || {
+        app.dialog()
+            .file()
+            .set_file_name(file_name)
+            .add_filter(extension, &[extension])
+            .blocking_save_file()
+    })
+    .await
+    .map_err(|e| format!("Save dialog failed: {}", e))?;
+
+    picked
+        .map(|file| {
+            file.into_path()
+                .map_err(|e| format!("Invalid save path: {}", e))
+        })
+        .transpose()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +420,12 @@ mod tests {
         let config = AppConfig::default();
         let state = AppState {
             config: Mutex::new(config),
+            contexts: Mutex::new(ContextManager::default()),
+            registry: Arc::new(Mutex::new(ModelRegistry::default())),
+            generation: SharedGenerationConfig::default(),
+            adaptive: AdaptiveConfigManager::new(),
+            server: Mutex::new(EmbeddedServer::default()),
+            preload: Mutex::new(PreloadManager::default()),
         };
 
         assert!(state.config.lock().is_ok());
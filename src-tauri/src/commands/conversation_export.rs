@@ -0,0 +1,146 @@
+//! Conversation Export
+//!
+//! Renders a session's message history as Markdown or as JSON in the
+//! `ChatCompletionRequest` messages schema, each with a model/usage header.
+
+use crate::error::MinervaResult;
+use crate::inference::context_snapshot::SessionContext;
+use crate::models::{ChatMessage, Usage};
+use crate::server::chat::estimate_tokens;
+use serde::{Deserialize, Serialize};
+
+/// Output format for an exported conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// JSON export document
+#[derive(Debug, Serialize)]
+struct ConversationExport<'a> {
+    model: &'a str,
+    exported_at: String,
+    usage: Usage,
+    messages: &'a [ChatMessage],
+}
+
+/// Render a session in the requested format
+pub fn render(session: &SessionContext, format: ExportFormat) -> MinervaResult<String> {
+    let exported_at = chrono::Local::now().to_rfc3339();
+    match format {
+        ExportFormat::Markdown => Ok(render_markdown(session, &exported_at)),
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(&ConversationExport {
+            model: &session.model_id,
+            exported_at,
+            usage: usage(&session.messages),
+            messages: &session.messages,
+        })?),
+    }
+}
+
+fn render_markdown(session: &SessionContext, exported_at: &str) -> String {
+    let usage = usage(&session.messages);
+    let mut out = format!(
+        "# Conversation\n\n- Model: {}\n- Exported: {}\n- Tokens: {} prompt, {} completion, {} total\n\n",
+        session.model_id,
+        exported_at,
+        usage.prompt_tokens,
+        usage.completion_tokens,
+        usage.total_tokens
+    );
+    for message in &session.messages {
+        out.push_str(&format!(
+            "**{}:** {}\n\n",
+            role_label(&message.role),
+            message.content
+        ));
+    }
+    out
+}
+
+fn role_label(role: &str) -> String {
+    let mut chars = role.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Estimated token counts; assistant messages count as completion tokens
+fn usage(messages: &[ChatMessage]) -> Usage {
+    let (completion, prompt): (Vec<&ChatMessage>, Vec<&ChatMessage>) =
+        messages.iter().partition(|m| m.role == "assistant");
+    let count = |msgs: Vec<&ChatMessage>| -> usize {
+        msgs.iter().map(|m| estimate_tokens(&m.content)).sum()
+    };
+    let (prompt_tokens, completion_tokens) = (count(prompt), count(completion));
+
+    Usage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::kv_cache::{KVCache, KVCacheConfig};
+
+    fn session() -> SessionContext {
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        SessionContext {
+            model_id: "mistral-7b".to_string(),
+            kv_cache: KVCache::new(KVCacheConfig {
+                num_layers: 1,
+                max_seq_len: 1,
+                num_heads: 1,
+                head_dim: 1,
+            }),
+            messages: vec![
+                message("user", "What is Rust?"),
+                message("assistant", "A systems language."),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_markdown_renders_roles() {
+        let markdown = render(&session(), ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("- Model: mistral-7b"));
+        assert!(
+            markdown.contains("**User:** What is Rust?\n\n**Assistant:** A systems language.\n\n")
+        );
+    }
+
+    #[test]
+    fn test_json_matches_messages_schema() {
+        let json = render(&session(), ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["model"], "mistral-7b");
+        assert_eq!(value["messages"][1]["role"], "assistant");
+        assert_eq!(value["usage"]["completion_tokens"], 4);
+        assert_eq!(value["usage"]["total_tokens"], 7);
+    }
+
+    #[test]
+    fn test_export_format_deserializes_lowercase() {
+        let format: ExportFormat = serde_json::from_str("\"markdown\"").unwrap();
+        assert_eq!(format, ExportFormat::Markdown);
+        assert_eq!(ExportFormat::Json.extension(), "json");
+    }
+}
@@ -0,0 +1,171 @@
+//! Downloaded Model Listing
+//!
+//! Joins the download records kept by `ModelRegistry` with what is on disk,
+//! so the frontend sees finished, interrupted and damaged downloads in one
+//! list. A download in flight writes to `<path>.part`; a finished file is
+//! checked against the registry's checksum when the model is registered.
+
+use crate::inference::downloader::CacheEntry;
+use crate::inference::model_registry::{ModelMetadata, ModelRegistry};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// State of a downloaded model's files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DownloadStatus {
+    Complete,
+    InProgress {
+        percent_complete: f32,
+    },
+    /// The file is missing or shorter than the recorded size
+    Incomplete,
+    /// The file no longer matches the registered checksum
+    Corrupted,
+}
+
+/// One entry of `list_downloaded_models`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadedModelInfo {
+    pub id: String,
+    pub path: String,
+    pub size_mb: u64,
+    pub status: DownloadStatus,
+    pub in_registry: bool,
+}
+
+/// A recorded download paired with its registry entry, detached from the lock
+pub type DownloadSnapshot = Vec<(CacheEntry, Option<ModelMetadata>)>;
+
+impl DownloadedModelInfo {
+    pub fn from_entry(entry: &CacheEntry, model: Option<&ModelMetadata>) -> Self {
+        Self {
+            id: entry.model_id.clone(),
+            path: entry.path.to_string_lossy().to_string(),
+            size_mb: entry.size_bytes / (1024 * 1024),
+            status: download_status(entry, model),
+            in_registry: model.is_some(),
+        }
+    }
+}
+
+/// Copy what `resolve` needs out of the registry so the lock can be released
+/// before any file is read
+pub fn snapshot(registry: &ModelRegistry) -> DownloadSnapshot {
+    registry
+        .downloads()
+        .list()
+        .iter()
+        .map(|entry| (entry.clone(), registry.get(&entry.model_id).cloned()))
+        .collect()
+}
+
+/// Status of every download in `snapshot`; hashes registered files, so run
+/// it off the async runtime
+pub fn resolve(snapshot: &DownloadSnapshot) -> Vec<DownloadedModelInfo> {
+    snapshot
+        .iter()
+        .map(|(entry, model)| DownloadedModelInfo::from_entry(entry, model.as_ref()))
+        .collect()
+}
+
+/// Every recorded download with its current status
+pub fn list(registry: &ModelRegistry) -> Vec<DownloadedModelInfo> {
+    resolve(&snapshot(registry))
+}
+
+fn download_status(entry: &CacheEntry, model: Option<&ModelMetadata>) -> DownloadStatus {
+    if let Ok(partial) = fs::metadata(entry.path.with_extension("part")) {
+        let percent = if entry.size_bytes == 0 {
+            0.0
+        } else {
+            (partial.len() as f32 / entry.size_bytes as f32 * 100.0).min(100.0)
+        };
+        return DownloadStatus::InProgress {
+            percent_complete: percent,
+        };
+    }
+
+    let Ok(file) = fs::metadata(&entry.path) else {
+        return DownloadStatus::Incomplete;
+    };
+    if file.is_file() && file.len() < entry.size_bytes {
+        return DownloadStatus::Incomplete;
+    }
+
+    match model {
+        Some(model) if model.corrupted || !model.verify().unwrap_or(false) => {
+            DownloadStatus::Corrupted
+        }
+        _ => DownloadStatus::Complete,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn entry(id: &str, path: &Path, size_bytes: u64) -> CacheEntry {
+        CacheEntry {
+            model_id: id.to_string(),
+            path: path.to_path_buf(),
+            revision: "main".to_string(),
+            size_bytes,
+            file_count: 1,
+            downloaded_at: 0,
+            last_accessed: 0,
+            source_url: None,
+            sha256: None,
+        }
+    }
+
+    fn status_of(models: &[DownloadedModelInfo], id: &str) -> DownloadStatus {
+        models.iter().find(|m| m.id == id).unwrap().status.clone()
+    }
+
+    #[test]
+    fn test_statuses_from_files_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let done = dir.path().join("done.gguf");
+        let short = dir.path().join("short.gguf");
+        let pending = dir.path().join("pending.gguf");
+        fs::write(&done, [0u8; 64]).unwrap();
+        fs::write(&short, [0u8; 10]).unwrap();
+        fs::write(pending.with_extension("part"), [0u8; 16]).unwrap();
+
+        let mut registry = ModelRegistry::new();
+        registry.record_download(entry("done", &done, 64));
+        registry.record_download(entry("short", &short, 64));
+        registry.record_download(entry("pending", &pending, 64));
+        registry.record_download(entry("gone", &dir.path().join("gone.gguf"), 64));
+        let models = list(&registry);
+
+        assert_eq!(models.len(), 4);
+        assert_eq!(status_of(&models, "done"), DownloadStatus::Complete);
+        assert_eq!(status_of(&models, "short"), DownloadStatus::Incomplete);
+        assert_eq!(status_of(&models, "gone"), DownloadStatus::Incomplete);
+        assert_eq!(
+            status_of(&models, "pending"),
+            DownloadStatus::InProgress {
+                percent_complete: 25.0
+            }
+        );
+        assert!(models.iter().all(|m| !m.in_registry));
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_corrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        fs::write(&path, b"original").unwrap();
+
+        let mut registry = ModelRegistry::new();
+        registry.register("model", path.clone()).unwrap();
+        registry.record_download(entry("model", &path, 8));
+        assert_eq!(list(&registry)[0].status, DownloadStatus::Complete);
+        assert!(list(&registry)[0].in_registry);
+
+        fs::write(&path, b"tampered").unwrap();
+        assert_eq!(list(&registry)[0].status, DownloadStatus::Corrupted);
+    }
+}
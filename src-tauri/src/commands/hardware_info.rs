@@ -0,0 +1,102 @@
+//! Hardware Detection
+//!
+//! Reports CPU, memory and GPU details so the frontend can suggest a model
+//! that fits. The probes live in [`crate::inference::hardware_probe`].
+
+use crate::inference::hardware_probe::gpu_info;
+pub(crate) use crate::inference::hardware_probe::total_ram_bytes;
+use serde::{Deserialize, Serialize};
+
+/// Approximate GB of memory per billion parameters for a Q4 model plus KV cache
+const GB_PER_BILLION_PARAMS: f32 = 0.6;
+
+/// Fraction of GPU memory a model may occupy
+const USABLE_MEMORY_FRACTION: f32 = 0.8;
+
+/// Hardware summary returned by `get_hardware_info`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareInfoResponse {
+    pub cpu_cores: usize,
+    pub total_ram_gb: f32,
+    pub gpu_name: Option<String>,
+    pub gpu_vram_gb: Option<f32>,
+    pub has_apple_neural_engine: bool,
+    pub os_version: String,
+    pub minerva_version: String,
+    pub recommended_model_size_b: f32,
+}
+
+impl HardwareInfoResponse {
+    /// Probe the current machine
+    pub fn detect() -> Self {
+        let total_ram_gb = total_ram_bytes().map_or(0.0, bytes_to_gb);
+        let (gpu_name, gpu_vram_gb) = gpu_info(total_ram_gb);
+
+        Self {
+            cpu_cores: num_cpus::get(),
+            total_ram_gb,
+            gpu_name,
+            gpu_vram_gb,
+            has_apple_neural_engine: cfg!(all(target_os = "macos", target_arch = "aarch64")),
+            os_version: os_version(),
+            minerva_version: env!("CARGO_PKG_VERSION").to_string(),
+            recommended_model_size_b: recommended_model_size_b(gpu_vram_gb, total_ram_gb),
+        }
+    }
+}
+
+/// Largest model (billions of params) that fits in VRAM, or half of RAM
+/// when there is no GPU, rounded down to the nearest 0.5B
+pub fn recommended_model_size_b(vram_gb: Option<f32>, ram_gb: f32) -> f32 {
+    let budget = vram_gb.unwrap_or(ram_gb * 0.5) * USABLE_MEMORY_FRACTION;
+    (budget / GB_PER_BILLION_PARAMS * 2.0).floor() / 2.0
+}
+
+fn bytes_to_gb(bytes: u64) -> f32 {
+    bytes as f32 / (1024.0 * 1024.0 * 1024.0)
+}
+
+#[cfg(target_os = "macos")]
+fn os_version() -> String {
+    crate::inference::hardware_probe::command_output("sw_vers", &["-productVersion"])
+        .map_or_else(|| "macOS".to_string(), |v| format!("macOS {}", v))
+}
+
+#[cfg(target_os = "linux")]
+fn os_version() -> String {
+    std::fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|release| {
+            release
+                .lines()
+                .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+                .map(|name| name.trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| "Linux".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn os_version() -> String {
+    crate::inference::hardware_probe::command_output("cmd", &["/C", "ver"])
+        .unwrap_or_else(|| std::env::consts::OS.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommended_model_size() {
+        let rtx_4090 = recommended_model_size_b(Some(24.0), 64.0);
+        assert!((31.5..=32.0).contains(&rtx_4090));
+        assert_eq!(recommended_model_size_b(None, 16.0), 10.5);
+        assert_eq!(recommended_model_size_b(None, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_detect_reports_cores_and_version() {
+        let info = HardwareInfoResponse::detect();
+        assert!(info.cpu_cores >= 1);
+        assert_eq!(info.minerva_version, env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -3,6 +3,7 @@
 //! Tauri commands for loading and downloading models.
 //! Exposed to frontend for GUI operations.
 
+use super::AppState;
 use crate::inference::downloader::{ModelDownloadRequest, ModelDownloader};
 use serde::{Deserialize, Serialize};
 
@@ -24,12 +25,18 @@ pub struct DownloadModelResponse {
 // ============================================================================
 
 /// Download model from HuggingFace
+///
+/// Each fetched weight file is recorded with its source URL and SHA-256, so
+/// a copy that is later found corrupted can be fetched again.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // Tauri injects the app handle and state
 pub async fn download_model(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
     model_id: String,
     local_dir: String,
 ) -> Result<DownloadModelResponse, String> {
-    let downloader = ModelDownloader::new();
+    let downloader = ModelDownloader::new().with_app_handle(app);
     let req = ModelDownloadRequest {
         model_id: model_id.clone(),
         revision: None,
@@ -38,16 +45,25 @@ pub async fn download_model(
     };
 
     match downloader.download(&req).await {
-        Ok(result) => Ok(DownloadModelResponse {
-            success: result.success,
-            message: if result.success {
-                format!("Downloaded {}", model_id)
-            } else {
-                "Download incomplete".to_string()
-            },
-            model_id: Some(model_id),
-            path: Some(result.local_path.to_string_lossy().to_string()),
-        }),
+        Ok(result) => {
+            let mut registry = state
+                .registry
+                .lock()
+                .map_err(|e| format!("Failed to lock registry: {}", e))?;
+            for entry in result.models {
+                registry.record_download(entry);
+            }
+            Ok(DownloadModelResponse {
+                success: result.success,
+                message: if result.success {
+                    format!("Downloaded {}", model_id)
+                } else {
+                    "Download incomplete".to_string()
+                },
+                model_id: Some(model_id),
+                path: Some(result.local_path.to_string_lossy().to_string()),
+            })
+        }
         Err(e) => Err(e.to_string()),
     }
 }
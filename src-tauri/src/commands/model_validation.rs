@@ -0,0 +1,268 @@
+//! Model File Validation
+//!
+//! Pre-flight checks for a GGUF file before it is loaded: the header, a
+//! supported architecture, and whether the weights plus KV cache fit in RAM.
+//! Only the header and metadata are read, so this stays fast for multi-GB files.
+
+use super::hardware_info::total_ram_bytes;
+use crate::models::gguf_loader::{GGUFModelLoader, GGUFModelMetadata};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Architectures the inference engine can run
+pub const SUPPORTED_ARCHITECTURES: &[&str] = &["llama", "mistral", "phi3", "qwen2", "gemma"];
+
+/// Fraction of system RAM a model may occupy
+const RAM_BUDGET_FRACTION: f64 = 0.8;
+
+/// Context length assumed when the file does not declare one
+const DEFAULT_CONTEXT_LENGTH: usize = 2048;
+
+/// Bytes per KV cache element (f16)
+const KV_ELEMENT_BYTES: u64 = 2;
+
+/// Approximate bytes per parameter at Q4_K, used when hyperparameters are missing
+const FALLBACK_BYTES_PER_PARAM: f64 = 0.5625;
+
+/// Outcome of `validate_model_file`
+///
+/// `valid` is false only for blocking issues; a context length that does not
+/// fit is reported in `issues` with a smaller `recommended_context_size`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub issues: Vec<String>,
+    pub model_name: String,
+    pub parameter_count_b: f32,
+    pub recommended_context_size: usize,
+}
+
+impl ValidationResult {
+    /// Validate `path` against this machine's RAM
+    pub fn check(path: &Path) -> Self {
+        Self::check_with_ram(path, total_ram_bytes().unwrap_or(0))
+    }
+
+    /// Validate `path` against `ram_bytes` of system memory (0 = unknown, skip the RAM check)
+    pub fn check_with_ram(path: &Path, ram_bytes: u64) -> Self {
+        let file_name = path
+            .file_stem()
+            .map_or_else(String::new, |s| s.to_string_lossy().to_string());
+        let metadata = match GGUFModelLoader::validate(path) {
+            Ok(metadata) => metadata,
+            Err(e) => return Self::invalid(file_name, format!("Invalid GGUF file: {}", e)),
+        };
+        let file_size = std::fs::metadata(path).map_or(0, |m| m.len());
+
+        let mut blocking: Vec<String> = architecture_issue(&metadata).into_iter().collect();
+        let plan = MemoryPlan::new(&metadata, file_size, ram_bytes);
+        blocking.extend(plan.blocking);
+
+        Self {
+            valid: blocking.is_empty(),
+            issues: blocking.into_iter().chain(plan.warning).collect(),
+            model_name: metadata.name.clone().unwrap_or(file_name),
+            parameter_count_b: parameter_count_b(&metadata, file_size),
+            recommended_context_size: plan.recommended_context_size,
+        }
+    }
+
+    fn invalid(model_name: String, issue: String) -> Self {
+        Self {
+            valid: false,
+            issues: vec![issue],
+            model_name,
+            parameter_count_b: 0.0,
+            recommended_context_size: 0,
+        }
+    }
+}
+
+fn architecture_issue(metadata: &GGUFModelMetadata) -> Option<String> {
+    match metadata.architecture.as_deref() {
+        Some(arch) if SUPPORTED_ARCHITECTURES.contains(&arch) => None,
+        Some(arch) => Some(format!("Unsupported architecture: {}", arch)),
+        None => Some("Missing general.architecture metadata".to_string()),
+    }
+}
+
+/// Estimated parameters in billions: per layer 4·d² attention + 3·d·ffn MLP
+fn parameter_count_b(metadata: &GGUFModelMetadata, file_size: u64) -> f32 {
+    match (
+        metadata.layer_count,
+        metadata.embedding_length,
+        metadata.feed_forward_length,
+    ) {
+        (Some(layers), Some(d), Some(ffn)) => {
+            (layers as f64 * (4.0 * (d * d) as f64 + 3.0 * (d * ffn) as f64) / 1e9) as f32
+        }
+        _ => (file_size as f64 / FALLBACK_BYTES_PER_PARAM / 1e9) as f32,
+    }
+}
+
+/// KV cache bytes per token (keys and values), accounting for grouped-query attention
+///
+/// `None` when the metadata is missing, zero or too large to multiply out.
+fn kv_bytes_per_token(metadata: &GGUFModelMetadata) -> Option<u64> {
+    let layers = metadata.layer_count? as u64;
+    let d = metadata.embedding_length? as u64;
+    let heads = metadata.attention_head_count.unwrap_or(1).max(1) as u64;
+    let kv_heads = metadata.attention_head_count_kv.unwrap_or(heads as usize) as u64;
+    let kv_dim = d.checked_mul(kv_heads)? / heads;
+    [2, kv_dim, KV_ELEMENT_BYTES]
+        .into_iter()
+        .try_fold(layers, u64::checked_mul)
+        .filter(|&bytes| bytes > 0)
+}
+
+/// How much context fits once the weights are resident
+struct MemoryPlan {
+    recommended_context_size: usize,
+    blocking: Option<String>,
+    warning: Option<String>,
+}
+
+impl MemoryPlan {
+    fn new(metadata: &GGUFModelMetadata, file_size: u64, ram_bytes: u64) -> Self {
+        let context = metadata.context_window.unwrap_or(DEFAULT_CONTEXT_LENGTH);
+        let budget = (ram_bytes as f64 * RAM_BUDGET_FRACTION) as u64;
+        let mut plan = Self {
+            recommended_context_size: context,
+            blocking: None,
+            warning: None,
+        };
+        if ram_bytes == 0 {
+            return plan;
+        }
+        if file_size >= budget {
+            plan.recommended_context_size = 0;
+            plan.blocking = Some(format!(
+                "Model weights ({:.1} GB) exceed the RAM budget ({:.1} GB)",
+                gb(file_size),
+                gb(budget)
+            ));
+            return plan;
+        }
+
+        let fits = kv_bytes_per_token(metadata).map_or(context, |per_token| {
+            ((budget - file_size) / per_token) as usize
+        });
+        if fits < context {
+            plan.recommended_context_size = fits;
+            plan.warning = Some(format!(
+                "llama.context_length {} exceeds the RAM budget; use at most {}",
+                context, fits
+            ));
+        }
+        plan
+    }
+}
+
+fn gb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn write_key(file: &mut NamedTempFile, key: &str, value_type: u32) {
+        file.write_all(&(key.len() as u32).to_le_bytes()).unwrap();
+        file.write_all(key.as_bytes()).unwrap();
+        file.write_all(&value_type.to_le_bytes()).unwrap();
+    }
+
+    fn write_u32(file: &mut NamedTempFile, key: &str, value: u32) {
+        write_key(file, key, 4);
+        file.write_all(&value.to_le_bytes()).unwrap();
+    }
+
+    fn write_string(file: &mut NamedTempFile, key: &str, value: &str) {
+        write_key(file, key, 11);
+        file.write_all(&(value.len() as u32).to_le_bytes()).unwrap();
+        file.write_all(value.as_bytes()).unwrap();
+    }
+
+    fn gguf(architecture: &str, context_length: u32) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"GGUF").unwrap();
+        file.write_all(&3u32.to_le_bytes()).unwrap();
+        file.write_all(&0u64.to_le_bytes()).unwrap();
+        file.write_all(&5u64.to_le_bytes()).unwrap();
+
+        write_string(&mut file, "general.architecture", architecture);
+        write_u32(&mut file, "llama.context_length", context_length);
+        write_u32(&mut file, "llama.block_count", 32);
+        write_u32(&mut file, "llama.embedding_length", 4096);
+        write_u32(&mut file, "llama.feed_forward_length", 11008);
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn test_valid_llama_model() {
+        let file = gguf("llama", 4096);
+        let result = ValidationResult::check_with_ram(file.path(), 64 * GB);
+
+        assert!(result.valid, "{:?}", result.issues);
+        assert!(result.issues.is_empty());
+        assert!((6.0..7.0).contains(&result.parameter_count_b));
+        assert_eq!(result.recommended_context_size, 4096);
+    }
+
+    #[test]
+    fn test_unsupported_architecture_is_blocking() {
+        let file = gguf("mamba", 4096);
+        let result = ValidationResult::check_with_ram(file.path(), 64 * GB);
+
+        assert!(!result.valid);
+        assert_eq!(result.issues, vec!["Unsupported architecture: mamba"]);
+    }
+
+    #[test]
+    fn test_context_is_capped_to_ram_budget() {
+        // 32 layers x 4096 dims x K+V x f16 = 512 KiB per token
+        let file = gguf("llama", 131_072);
+        let result = ValidationResult::check_with_ram(file.path(), 10 * GB);
+
+        assert!(result.valid);
+        assert_eq!(result.issues.len(), 1);
+        assert!(result.recommended_context_size < 131_072);
+        assert!(result.recommended_context_size > 16_000);
+    }
+
+    #[test]
+    fn test_kv_bytes_per_token_rejects_zero_and_overflow() {
+        let metadata = |layers: usize, d: usize| GGUFModelMetadata {
+            name: None,
+            architecture: Some("llama".to_string()),
+            context_window: Some(4096),
+            embedding_length: Some(d),
+            feed_forward_length: None,
+            attention_head_count: Some(32),
+            attention_head_count_kv: Some(8),
+            layer_count: Some(layers),
+            quantization_version: None,
+        };
+
+        assert_eq!(kv_bytes_per_token(&metadata(32, 4096)), Some(128 * 1024));
+        assert_eq!(kv_bytes_per_token(&metadata(0, 4096)), None);
+        assert_eq!(kv_bytes_per_token(&metadata(32, 0)), None);
+        assert_eq!(kv_bytes_per_token(&metadata(usize::MAX, usize::MAX)), None);
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"NOPE").unwrap();
+        file.flush().unwrap();
+        let result = ValidationResult::check_with_ram(file.path(), 64 * GB);
+
+        assert!(!result.valid);
+        assert!(result.issues[0].starts_with("Invalid GGUF file"));
+    }
+}
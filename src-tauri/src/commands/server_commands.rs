@@ -0,0 +1,260 @@
+//! Embedded Server Commands
+//!
+//! Tauri commands that start, stop and report on the REST server running
+//! inside the desktop app, so the UI can toggle it without a restart.
+
+use super::AppState;
+use crate::config::AppConfig;
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::batch_async::BatchAsync;
+use crate::inference::unified_backend_routing::BackendSetup;
+#[cfg(not(cpu_only))]
+use crate::observability::metrics_collector::GpuSampler;
+use crate::server::ServerState;
+use crate::server::validation::RequestValidator;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::time::Instant;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Whether the embedded server is listening, and where
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub is_running: bool,
+    pub port: u16,
+    pub host: String,
+    /// Address the listener actually bound; empty while stopped
+    pub bound_address: String,
+    pub uptime_seconds: u64,
+}
+
+/// A listening server and the handles that stop it
+#[derive(Debug)]
+pub struct RunningServer {
+    host: String,
+    bound_address: SocketAddr,
+    started_at: Instant,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+    batching: JoinHandle<()>,
+    #[cfg(not(cpu_only))]
+    gpu_sampler: Option<GpuSampler>,
+}
+
+impl RunningServer {
+    pub fn bound_address(&self) -> SocketAddr {
+        self.bound_address
+    }
+
+    /// Finish in-flight requests, then stop the batching loop and wait for
+    /// the server and its GPU sampler to exit
+    pub async fn stop(self) {
+        // The server task may already be gone, in which case there is nothing to stop
+        let _ = self.shutdown.send(());
+        if let Err(e) = self.task.await {
+            tracing::error!("Embedded server task failed: {}", e);
+        }
+        self.batching.abort();
+        #[cfg(not(cpu_only))]
+        if let Some(sampler) = self.gpu_sampler {
+            let _ = tokio::task::spawn_blocking(move || sampler.stop()).await;
+        }
+    }
+}
+
+/// Embedded server slot kept in `AppState`
+#[derive(Debug, Default)]
+pub struct EmbeddedServer {
+    running: Option<RunningServer>,
+}
+
+impl EmbeddedServer {
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Status of the running server, or the configured address when stopped
+    pub fn status(&self, config: &AppConfig) -> ServerStatus {
+        match &self.running {
+            Some(server) => ServerStatus {
+                is_running: true,
+                port: server.bound_address.port(),
+                host: server.host.clone(),
+                bound_address: server.bound_address.to_string(),
+                uptime_seconds: server.started_at.elapsed().as_secs(),
+            },
+            None => ServerStatus {
+                is_running: false,
+                port: config.server.port,
+                host: config.server.host.clone(),
+                bound_address: String::new(),
+                uptime_seconds: 0,
+            },
+        }
+    }
+}
+
+/// Bind `host:port` and serve `state` on a background task
+pub async fn spawn_server(
+    host: &str,
+    port: u16,
+    state: ServerState,
+) -> MinervaResult<RunningServer> {
+    #[cfg(not(cpu_only))]
+    let gpu_sampler = state
+        .metrics
+        .spawn_gpu_sampler()
+        .inspect_err(|e| tracing::warn!("GPU sampling unavailable: {}", e))
+        .ok();
+    let batching_state = state.clone();
+    let router = crate::server::create_server(state).await;
+    let listener = tokio::net::TcpListener::bind((host, port))
+        .await
+        .map_err(|e| MinervaError::InvalidRequest(format!("Failed to bind socket: {}", e)))?;
+    let bound_address = listener.local_addr()?;
+    let batching = tokio::spawn(BatchAsync::run(batching_state));
+
+    let (shutdown, stopped) = oneshot::channel();
+    let task = tokio::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = stopped.await;
+        });
+        if let Err(e) = server.await {
+            tracing::error!("Embedded server error: {}", e);
+        }
+        tracing::info!("Embedded server on {} stopped", bound_address);
+    });
+
+    tracing::info!("Embedded server listening on {}", bound_address);
+    Ok(RunningServer {
+        host: host.to_string(),
+        bound_address,
+        started_at: Instant::now(),
+        shutdown,
+        task,
+        batching,
+        #[cfg(not(cpu_only))]
+        gpu_sampler,
+    })
+}
+
+/// Report whether the embedded server is running
+#[tauri::command]
+pub fn get_server_status(state: tauri::State<'_, AppState>) -> Result<ServerStatus, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    let server = state
+        .server
+        .lock()
+        .map_err(|e| format!("Failed to lock server: {}", e))?;
+    Ok(server.status(&config))
+}
+
+/// Start the embedded server on the configured host and port
+#[tauri::command]
+pub async fn start_server(state: tauri::State<'_, AppState>) -> Result<ServerStatus, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .clone();
+    if lock_server(&state)?.is_running() {
+        return Err("Server is already running".to_string());
+    }
+
+    let server_state = ServerState::with_discovered_models(config.models_dir.clone())
+        .map_err(|e| e.user_message())?
+        .with_streaming_config(config.streaming.clone())
+        .with_max_request_body_bytes(config.server.max_request_body_bytes)
+        .with_slo(config.server.slo)
+        .with_request_validator(
+            RequestValidator::from_policy(config.server.request_schema.as_deref())
+                .map_err(|e| e.user_message())?,
+        )
+        .with_generation_defaults(state.generation.clone())
+        .with_discovered_backend(BackendSetup {
+            n_threads: state.adaptive.recommended_threads(),
+            ..Default::default()
+        })
+        .await;
+    let running = spawn_server(&config.server.host, config.server.port, server_state)
+        .await
+        .map_err(|e| e.user_message())?;
+
+    let raced = {
+        let mut server = lock_server(&state)?;
+        if server.is_running() {
+            Some(running)
+        } else {
+            server.running = Some(running);
+            None
+        }
+    };
+    if let Some(running) = raced {
+        // Another start won the race while this one was binding
+        running.stop().await;
+        return Err("Server is already running".to_string());
+    }
+    Ok(lock_server(&state)?.status(&config))
+}
+
+/// Stop the embedded server and wait for it to exit; stopping a stopped
+/// server is not an error
+#[tauri::command]
+pub async fn stop_server(state: tauri::State<'_, AppState>) -> Result<ServerStatus, String> {
+    let running = lock_server(&state)?.running.take();
+    if let Some(running) = running {
+        running.stop().await;
+    }
+    get_server_status(state)
+}
+
+fn lock_server<'a>(
+    state: &'a tauri::State<'_, AppState>,
+) -> Result<std::sync::MutexGuard<'a, EmbeddedServer>, String> {
+    state
+        .server
+        .lock()
+        .map_err(|e| format!("Failed to lock server: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stopped_status_uses_config() {
+        let config = AppConfig::default();
+        let status = EmbeddedServer::default().status(&config);
+
+        assert!(!status.is_running);
+        assert_eq!(status.port, config.server.port);
+        assert_eq!(status.host, config.server.host);
+        assert!(status.bound_address.is_empty());
+        assert_eq!(status.uptime_seconds, 0);
+    }
+
+    #[tokio::test]
+    async fn test_spawned_server_reports_bound_address() {
+        let running = spawn_server("127.0.0.1", 0, ServerState::new())
+            .await
+            .unwrap();
+        let address = running.bound_address();
+        assert_ne!(address.port(), 0);
+        assert!(tokio::net::TcpStream::connect(address).await.is_ok());
+
+        let server = EmbeddedServer {
+            running: Some(running),
+        };
+        let status = server.status(&AppConfig::default());
+        assert!(status.is_running);
+        assert_eq!(status.port, address.port());
+        assert_eq!(status.bound_address, address.to_string());
+
+        server.running.unwrap().stop().await;
+        assert!(tokio::net::TcpStream::connect(address).await.is_err());
+    }
+}
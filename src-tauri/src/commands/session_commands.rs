@@ -0,0 +1,52 @@
+//! Conversation Session Commands
+//!
+//! Tauri commands that persist and resume conversation context so the
+//! frontend can keep chat history across application restarts.
+
+use super::AppState;
+use crate::models::ChatMessage;
+use std::path::Path;
+
+/// Save a session's KV cache and message history to `path`
+///
+/// `messages` is the history the frontend is showing; it replaces the stored
+/// one so the snapshot matches what the user sees.
+#[tauri::command]
+pub fn save_session(
+    state: tauri::State<'_, AppState>,
+    session_id: String,
+    model_id: String,
+    messages: Vec<ChatMessage>,
+    path: String,
+) -> Result<(), String> {
+    let mut contexts = state
+        .contexts
+        .lock()
+        .map_err(|e| format!("Failed to lock contexts: {}", e))?;
+    contexts.record_messages(&session_id, &model_id, messages);
+    contexts
+        .save_context(&session_id, Path::new(&path))
+        .map_err(|e| format!("Failed to save session: {}", e))
+}
+
+/// Restore a session from `path` and return its message history
+#[tauri::command]
+pub fn load_session(
+    state: tauri::State<'_, AppState>,
+    session_id: String,
+    path: String,
+) -> Result<Vec<ChatMessage>, String> {
+    let mut contexts = state
+        .contexts
+        .lock()
+        .map_err(|e| format!("Failed to lock contexts: {}", e))?;
+
+    contexts
+        .restore_context(&session_id, Path::new(&path))
+        .map_err(|e| format!("Failed to load session: {}", e))?;
+
+    Ok(contexts
+        .session(&session_id)
+        .map(|session| session.messages.clone())
+        .unwrap_or_default())
+}
@@ -0,0 +1,145 @@
+//! Cross-field validation of the application config
+//!
+//! Checks that depend on the environment rather than a single field: the
+//! models directory must be writable, privileged ports need root, and the
+//! Metal backend only exists on macOS.
+
+use super::legacy::AppConfig;
+use super::validator::ConfigValidator;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Ports below this need elevated privileges on Unix
+const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+
+/// Probe file written to check that the models directory is writable
+const WRITE_PROBE: &str = ".minerva_test";
+
+/// Non-fatal configuration problem worth surfacing to the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigWarning {
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl ConfigValidator {
+    /// Run cross-field checks on the app config
+    ///
+    /// `Err` carries the first fatal problem; `Ok` carries any warnings.
+    pub fn validate_cross_fields(config: &AppConfig) -> Result<Vec<ConfigWarning>, String> {
+        Self::validate_models_dir_writable(&config.models_dir)?;
+        Self::validate_gpu_backend(&config.gpu.backend, config.gpu.enabled)?;
+        Self::validate_slo(&config.server.slo).map_err(|e| format!("server.slo: {}", e))?;
+        for (model_id, entry) in &config.streaming {
+            Self::validate_streaming(entry)
+                .map_err(|e| format!("streaming.{}: {}", model_id, e))?;
+        }
+
+        let mut warnings = Vec::new();
+        warnings.extend(Self::check_privileged_port(config.server.port));
+        Ok(warnings)
+    }
+
+    /// The models directory must exist and accept new files
+    pub fn validate_models_dir_writable(models_dir: &Path) -> Result<(), String> {
+        if !models_dir.is_dir() {
+            return Err(format!(
+                "Models directory does not exist: {}",
+                models_dir.display()
+            ));
+        }
+        let probe = models_dir.join(WRITE_PROBE);
+        fs::File::create(&probe).map_err(|e| {
+            format!(
+                "Models directory is not writable: {}: {}",
+                models_dir.display(),
+                e
+            )
+        })?;
+        let _ = fs::remove_file(&probe);
+        Ok(())
+    }
+
+    /// Metal is only available on macOS
+    pub fn validate_gpu_backend(backend: &str, enabled: bool) -> Result<(), String> {
+        if enabled && backend == "metal" && !cfg!(target_os = "macos") {
+            return Err("GPU backend \"metal\" is only available on macOS".to_string());
+        }
+        Ok(())
+    }
+
+    /// Warn when binding a privileged port without root
+    pub fn check_privileged_port(port: u16) -> Option<ConfigWarning> {
+        (port < FIRST_UNPRIVILEGED_PORT && !is_root()).then(|| ConfigWarning {
+            field: "server.port".to_string(),
+            message: format!("Port {} requires elevated privileges to bind", port),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn is_root() -> bool {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Only Unix restricts ports below 1024
+#[cfg(not(unix))]
+fn is_root() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_in(dir: &Path) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.models_dir = dir.to_path_buf();
+        config
+    }
+
+    #[test]
+    fn test_writable_models_dir_passes_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(ConfigValidator::validate_models_dir_writable(dir.path()).is_ok());
+        assert!(!dir.path().join(WRITE_PROBE).exists());
+    }
+
+    #[test]
+    fn test_missing_models_dir_is_fatal() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_in(&dir.path().join("missing"));
+        let err = ConfigValidator::validate_cross_fields(&config).unwrap_err();
+        assert!(err.contains("does not exist"));
+    }
+
+    #[test]
+    fn test_metal_backend_outside_macos() {
+        let result = ConfigValidator::validate_gpu_backend("metal", true);
+        assert_eq!(result.is_ok(), cfg!(target_os = "macos"));
+        assert!(ConfigValidator::validate_gpu_backend("metal", false).is_ok());
+        assert!(ConfigValidator::validate_gpu_backend("cuda", true).is_ok());
+    }
+
+    #[test]
+    fn test_privileged_port_warning() {
+        assert!(ConfigValidator::check_privileged_port(8080).is_none());
+        let warning = ConfigValidator::check_privileged_port(80);
+        assert_eq!(warning.is_some(), !is_root());
+    }
+
+    #[test]
+    fn test_default_config_has_no_fatal_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let warnings = ConfigValidator::validate_cross_fields(&config_in(dir.path())).unwrap();
+        assert!(warnings.is_empty());
+    }
+}
@@ -1,7 +1,14 @@
 //! Legacy Tauri configuration structures
 
+use super::loader::{ConfigLoader, EnvLayer};
+use super::types::{
+    DEFAULT_MAX_REQUEST_BODY_BYTES, GenerationConfigUpdate, PreloadModelEntry, SloConfig,
+    StreamingConfigEntry,
+};
+use super::validator::ConfigValidator;
 use crate::error::{MinervaError, MinervaResult};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -11,12 +18,38 @@ pub struct AppConfig {
     pub models_dir: PathBuf,
     pub server: LegacyServerConfig,
     pub gpu: GpuConfig,
+    /// Streaming settings by model ID, with `"*"` as the fallback entry
+    #[serde(default)]
+    pub streaming: HashMap<String, StreamingConfigEntry>,
+    /// Models to load at startup; pinned ones are never evicted
+    #[serde(default)]
+    pub preload_models: Vec<PreloadModelEntry>,
+    /// Sampling overrides set from the app; unset fields use model defaults
+    #[serde(default)]
+    pub generation: GenerationConfigUpdate,
+    /// `MINERVA_*` overrides applied at load; never saved
+    #[serde(skip)]
+    pub env: EnvLayer,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LegacyServerConfig {
     pub port: u16,
     pub host: String,
+    /// Request bodies above this size are rejected with 413
+    #[serde(default = "default_max_request_body_bytes")]
+    pub max_request_body_bytes: usize,
+    /// Targets the efficiency score in `/metrics` is measured against
+    #[serde(default)]
+    pub slo: SloConfig,
+    /// JSON Schema policy file chat request bodies must satisfy; the
+    /// built-in schema is used when unset
+    #[serde(default)]
+    pub request_schema: Option<PathBuf>,
+}
+
+fn default_max_request_body_bytes() -> usize {
+    DEFAULT_MAX_REQUEST_BODY_BYTES
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,33 +60,79 @@ pub struct GpuConfig {
 
 impl AppConfig {
     /// Load configuration from ~/.minerva/config.json
+    ///
+    /// Settings that can no longer work here are migrated to a working
+    /// value with a warning rather than failing the load.
     pub fn load() -> MinervaResult<Self> {
         let config_path = Self::config_path()?;
         if config_path.exists() {
             let content = fs::read_to_string(&config_path).map_err(MinervaError::IoError)?;
-            serde_json::from_str(&content).map_err(MinervaError::JsonError)
+            let config: Self = serde_json::from_str(&content).map_err(MinervaError::JsonError)?;
+            Ok(config.migrated())
         } else {
             Ok(Self::default())
         }
     }
 
-    /// Load config or return defaults if not found
+    /// Replace settings that cannot work on this machine
+    fn migrated(mut self) -> Self {
+        if let Err(e) = ConfigValidator::validate_gpu_backend(&self.gpu.backend, self.gpu.enabled) {
+            let fallback = default_gpu_backend();
+            tracing::warn!("Config: {}; using \"{}\" instead", e, fallback);
+            self.gpu.backend = fallback.to_string();
+        }
+        self
+    }
+
+    /// Log the cross-field problems of the loaded config
+    ///
+    /// Run once the models directory has been created. Nothing here stops
+    /// startup: fatal problems are reported so the user can fix them from
+    /// the app.
+    pub fn log_problems(&self) {
+        match ConfigValidator::validate_cross_fields(self) {
+            Ok(warnings) => {
+                for warning in warnings {
+                    tracing::warn!("Config warning: {}", warning);
+                }
+            }
+            Err(e) => tracing::warn!("Config problem: {}", e),
+        }
+    }
+
+    /// Load config or return defaults if not found, then apply `MINERVA_*`
+    /// environment overrides
     pub fn load_or_default() -> Self {
-        Self::load().unwrap_or_default()
+        ConfigLoader::load_with_env_overrides(Self::load().unwrap_or_default())
+    }
+
+    /// Build from compiled defaults and environment variables only, ignoring
+    /// any config file (for container deployments)
+    pub fn from_env() -> Self {
+        ConfigLoader::load_with_env_overrides(Self::default())
     }
 
     /// Save configuration to ~/.minerva/config.json
+    ///
+    /// Values that came from `MINERVA_*` environment variables are left out;
+    /// the file keeps its own values for those fields.
     pub fn save(&self) -> MinervaResult<()> {
         let config_path = Self::config_path()?;
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent).map_err(MinervaError::IoError)?;
         }
         fs::create_dir_all(&self.models_dir).map_err(MinervaError::IoError)?;
-        let content = serde_json::to_string_pretty(self).map_err(MinervaError::JsonError)?;
+        let stored = self.env.strip(self);
+        let content = serde_json::to_string_pretty(&stored).map_err(MinervaError::JsonError)?;
         fs::write(&config_path, content).map_err(MinervaError::IoError)?;
         Ok(())
     }
 
+    /// Streaming settings for `model_id`
+    pub fn streaming_for(&self, model_id: &str) -> StreamingConfigEntry {
+        StreamingConfigEntry::lookup(&self.streaming, model_id)
+    }
+
     /// Get path to config file
     fn config_path() -> MinervaResult<PathBuf> {
         let home_dir = home::home_dir().ok_or_else(|| {
@@ -62,6 +141,11 @@ impl AppConfig {
         Ok(home_dir.join(".minerva").join("config.json"))
     }
 
+    /// Directory of the on-disk KV snapshot cache, next to the config file
+    pub fn kv_snapshot_dir() -> MinervaResult<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("kv_snapshots"))
+    }
+
     /// Get models directory, creating it if it doesn't exist
     pub fn ensure_models_dir(&self) -> MinervaResult<()> {
         fs::create_dir_all(&self.models_dir).map_err(MinervaError::IoError)?;
@@ -77,15 +161,31 @@ impl Default for AppConfig {
             server: LegacyServerConfig {
                 port: 11434,
                 host: "127.0.0.1".to_string(),
+                max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+                slo: SloConfig::default(),
+                request_schema: None,
             },
             gpu: GpuConfig {
                 enabled: true,
-                backend: "metal".to_string(),
+                backend: default_gpu_backend().to_string(),
             },
+            streaming: HashMap::new(),
+            preload_models: Vec::new(),
+            generation: GenerationConfigUpdate::default(),
+            env: EnvLayer::default(),
         }
     }
 }
 
+/// Metal on macOS; elsewhere let the runtime pick
+fn default_gpu_backend() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "metal"
+    } else {
+        "auto"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,6 +195,8 @@ mod tests {
         let config = AppConfig::default();
         assert_eq!(config.server.port, 11434);
         assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.gpu.backend, default_gpu_backend());
+        #[cfg(target_os = "macos")]
         assert_eq!(config.gpu.backend, "metal");
         assert!(config.gpu.enabled);
     }
@@ -115,4 +217,28 @@ mod tests {
         let deserialized: Result<AppConfig, _> = serde_json::from_str(&json.unwrap());
         assert!(deserialized.is_ok());
     }
+
+    #[test]
+    fn test_old_server_config_gets_default_body_limit() {
+        let server: LegacyServerConfig =
+            serde_json::from_str(r#"{"port": 8080, "host": "0.0.0.0"}"#).unwrap();
+        assert_eq!(
+            server.max_request_body_bytes,
+            DEFAULT_MAX_REQUEST_BODY_BYTES
+        );
+    }
+
+    #[test]
+    fn test_load_migrates_unavailable_gpu_backend() {
+        let mut config = AppConfig::default();
+        config.gpu.backend = "metal".to_string();
+        let migrated = config.migrated();
+
+        let expected = if cfg!(target_os = "macos") {
+            "metal"
+        } else {
+            "auto"
+        };
+        assert_eq!(migrated.gpu.backend, expected);
+    }
 }
@@ -1,8 +1,123 @@
 //! Configuration loading from files
 
+use super::legacy::AppConfig;
 use super::types::ApplicationConfig;
 use super::validator::ConfigValidator;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Environment variables read by `ConfigLoader::load_with_env_overrides`
+pub const ENV_MODELS_DIR: &str = "MINERVA_MODELS_DIR";
+pub const ENV_SERVER_PORT: &str = "MINERVA_SERVER_PORT";
+pub const ENV_SERVER_HOST: &str = "MINERVA_SERVER_HOST";
+pub const ENV_GPU_ENABLED: &str = "MINERVA_GPU_ENABLED";
+pub const ENV_GPU_BACKEND: &str = "MINERVA_GPU_BACKEND";
+
+/// `MINERVA_*` values read from the environment; `None` where unset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvOverrides {
+    pub models_dir: Option<PathBuf>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub gpu_enabled: Option<bool>,
+    pub gpu_backend: Option<String>,
+}
+
+impl EnvOverrides {
+    /// Parse overrides from `lookup`, which maps a variable name to its value
+    ///
+    /// Unparseable values are logged and ignored.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            models_dir: lookup(ENV_MODELS_DIR).map(PathBuf::from),
+            host: lookup(ENV_SERVER_HOST),
+            port: parse_env(&lookup, ENV_SERVER_PORT, |v| v.parse().ok()),
+            gpu_enabled: parse_env(&lookup, ENV_GPU_ENABLED, parse_bool),
+            gpu_backend: lookup(ENV_GPU_BACKEND),
+        }
+    }
+
+    /// The values `config` holds for the fields set in `self`
+    fn current(&self, config: &AppConfig) -> Self {
+        Self {
+            models_dir: self.models_dir.as_ref().map(|_| config.models_dir.clone()),
+            host: self.host.as_ref().map(|_| config.server.host.clone()),
+            port: self.port.map(|_| config.server.port),
+            gpu_enabled: self.gpu_enabled.map(|_| config.gpu.enabled),
+            gpu_backend: self
+                .gpu_backend
+                .as_ref()
+                .map(|_| config.gpu.backend.clone()),
+        }
+    }
+
+    /// Overwrite the fields of `config` that are set in `self`
+    fn write_to(&self, config: &mut AppConfig) {
+        if let Some(dir) = &self.models_dir {
+            config.models_dir = dir.clone();
+        }
+        if let Some(host) = &self.host {
+            config.server.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if let Some(enabled) = self.gpu_enabled {
+            config.gpu.enabled = enabled;
+        }
+        if let Some(backend) = &self.gpu_backend {
+            config.gpu.backend = backend.clone();
+        }
+    }
+}
+
+/// Environment layer applied over the config file
+///
+/// Remembers the file values it replaced so `AppConfig::save` writes those
+/// back instead of persisting the environment.
+#[derive(Debug, Clone, Default)]
+pub struct EnvLayer {
+    applied: EnvOverrides,
+    shadowed: EnvOverrides,
+}
+
+impl EnvLayer {
+    /// `config` as it should be written to disk
+    ///
+    /// Fields still holding their environment value get the file value
+    /// back; fields changed since (e.g. from the app) keep the new value.
+    pub fn strip(&self, config: &AppConfig) -> AppConfig {
+        let applied = &self.applied;
+        let restore = EnvOverrides {
+            models_dir: self
+                .shadowed
+                .models_dir
+                .clone()
+                .filter(|_| applied.models_dir.as_ref() == Some(&config.models_dir)),
+            host: self
+                .shadowed
+                .host
+                .clone()
+                .filter(|_| applied.host.as_ref() == Some(&config.server.host)),
+            port: self
+                .shadowed
+                .port
+                .filter(|_| applied.port == Some(config.server.port)),
+            gpu_enabled: self
+                .shadowed
+                .gpu_enabled
+                .filter(|_| applied.gpu_enabled == Some(config.gpu.enabled)),
+            gpu_backend: self
+                .shadowed
+                .gpu_backend
+                .clone()
+                .filter(|_| applied.gpu_backend.as_ref() == Some(&config.gpu.backend)),
+        };
+        let mut stored = config.clone();
+        restore.write_to(&mut stored);
+        stored.env = EnvLayer::default();
+        stored
+    }
+}
 
 /// Configuration loader
 pub struct ConfigLoader;
@@ -19,11 +134,116 @@ impl ConfigLoader {
         ConfigValidator::validate_all(&config)?;
         Ok(config)
     }
+
+    /// Apply `MINERVA_*` environment variables on top of `base`
+    ///
+    /// Precedence is environment, then `base` (the config file), then the
+    /// compiled defaults `base` was built from. Unparseable values are
+    /// logged and ignored. The environment stays a separate layer that
+    /// `AppConfig::save` does not write.
+    pub fn load_with_env_overrides(base: AppConfig) -> AppConfig {
+        Self::apply_overrides(base, |name| std::env::var(name).ok())
+    }
+
+    /// Apply overrides from `lookup`, which maps a variable name to its value
+    pub fn apply_overrides(
+        mut config: AppConfig,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> AppConfig {
+        let applied = EnvOverrides::from_lookup(lookup);
+        let shadowed = applied.current(&config);
+        applied.write_to(&mut config);
+        config.env = EnvLayer { applied, shadowed };
+        config
+    }
+}
+
+fn parse_env<T>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let raw = lookup(name)?;
+    let parsed = parse(raw.trim());
+    if parsed.is_none() {
+        tracing::warn!("Ignoring invalid {}={:?}", name, raw);
+    }
+    parsed
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::config::types::ApplicationConfig;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_env_overrides_take_precedence() {
+        let lookup = env(&[
+            (ENV_MODELS_DIR, "/srv/models"),
+            (ENV_SERVER_PORT, "8080"),
+            (ENV_SERVER_HOST, "0.0.0.0"),
+            (ENV_GPU_ENABLED, "false"),
+            (ENV_GPU_BACKEND, "cuda"),
+        ]);
+        let config = ConfigLoader::apply_overrides(AppConfig::default(), lookup);
+
+        assert_eq!(config.models_dir, PathBuf::from("/srv/models"));
+        assert_eq!(config.server.port, 8080);
+        assert_eq!(config.server.host, "0.0.0.0");
+        assert!(!config.gpu.enabled);
+        assert_eq!(config.gpu.backend, "cuda");
+    }
+
+    #[test]
+    fn test_missing_or_invalid_env_keeps_base() {
+        let mut base = AppConfig::default();
+        base.server.port = 9000;
+        let lookup = env(&[(ENV_SERVER_PORT, "not-a-port"), (ENV_GPU_ENABLED, "maybe")]);
+        let config = ConfigLoader::apply_overrides(base.clone(), lookup);
+
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.gpu.enabled, base.gpu.enabled);
+        assert_eq!(config.models_dir, base.models_dir);
+    }
+
+    #[test]
+    fn test_stored_config_excludes_env_values() {
+        let mut base = AppConfig::default();
+        base.server.port = 9000;
+        let lookup = env(&[(ENV_SERVER_PORT, "8080"), (ENV_SERVER_HOST, "0.0.0.0")]);
+        let mut config = ConfigLoader::apply_overrides(base.clone(), lookup);
+        config.server.host = "10.0.0.1".to_string();
+
+        let stored = config.env.strip(&config);
+        assert_eq!(stored.server.port, 9000);
+        assert_eq!(stored.server.host, "10.0.0.1");
+        assert_eq!(stored.models_dir, base.models_dir);
+        assert_eq!(config.server.port, 8080);
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        assert_eq!(parse_bool("ON"), Some(true));
+        assert_eq!(parse_bool("0"), Some(false));
+        assert_eq!(parse_bool(""), None);
+    }
 
     #[test]
     fn test_application_config_defaults() {
@@ -1,12 +1,18 @@
 //! Configuration management module
 //! Handles loading, validation, and merging of configuration from multiple sources
 
+pub mod cross_validator;
 pub mod legacy;
 pub mod loader;
 pub mod types;
 pub mod validator;
 
+pub use cross_validator::ConfigWarning;
 pub use legacy::{AppConfig, GpuConfig, LegacyServerConfig};
-pub use loader::ConfigLoader;
-pub use types::{ApiConfig, ApplicationConfig, ConfigSource, ServerConfig, StreamingConfigEntry};
+pub use loader::{ConfigLoader, EnvLayer, EnvOverrides};
+pub use types::{
+    ApiConfig, ApplicationConfig, ConfigSource, DEFAULT_MAX_REQUEST_BODY_BYTES,
+    GenerationConfigUpdate, PreloadModelEntry, STREAMING_WILDCARD, ServerConfig, SloConfig,
+    StreamingConfigEntry,
+};
 pub use validator::ConfigValidator;
@@ -1,6 +1,7 @@
 //! Configuration types and structures
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Configuration source priority (higher = more important)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
@@ -16,12 +17,25 @@ pub enum ConfigSource {
     CommandLine = 3,
 }
 
+/// Largest request body accepted by default (1 MB)
+pub const DEFAULT_MAX_REQUEST_BODY_BYTES: usize = 1024 * 1024;
+
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub workers: Option<usize>,
+    /// Bodies larger than this are rejected with 413, chunked bodies included
+    #[serde(default = "default_max_request_body_bytes")]
+    pub max_request_body_bytes: usize,
+    /// Targets the efficiency score in `/metrics` is measured against
+    #[serde(default)]
+    pub slo: SloConfig,
+}
+
+fn default_max_request_body_bytes() -> usize {
+    DEFAULT_MAX_REQUEST_BODY_BYTES
 }
 
 impl Default for ServerConfig {
@@ -30,6 +44,27 @@ impl Default for ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 3000,
             workers: None,
+            max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+            slo: SloConfig::default(),
+        }
+    }
+}
+
+/// Service level objectives for the efficiency score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SloConfig {
+    /// Throughput the server should sustain
+    pub min_rps: f64,
+    /// p99 latency the server should stay under
+    pub target_p99_ms: f64,
+}
+
+impl Default for SloConfig {
+    fn default() -> Self {
+        Self {
+            min_rps: 1.0,
+            target_p99_ms: 5000.0,
         }
     }
 }
@@ -52,12 +87,22 @@ impl Default for ApiConfig {
     }
 }
 
-/// Streaming configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Model ID key that applies to every model without its own entry
+pub const STREAMING_WILDCARD: &str = "*";
+
+/// Streaming configuration, also used per model in `AppConfig.streaming`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StreamingConfigEntry {
     pub enabled: bool,
     pub chunk_size: usize,
     pub keep_alive_ms: u64,
+    /// Tokens grouped into each SSE chunk
+    pub max_chunk_size_tokens: usize,
+    /// Pause after each chunk (simulates slow models in testing)
+    pub chunk_delay_ms: u64,
+    /// Answer `stream: true` requests with a single non-streamed response
+    pub disable_streaming: bool,
 }
 
 impl Default for StreamingConfigEntry {
@@ -66,6 +111,53 @@ impl Default for StreamingConfigEntry {
             enabled: true,
             chunk_size: 50,
             keep_alive_ms: 15000,
+            max_chunk_size_tokens: 1,
+            chunk_delay_ms: 0,
+            disable_streaming: false,
+        }
+    }
+}
+
+impl StreamingConfigEntry {
+    /// Entry for `model_id`: its own, else the `"*"` entry, else the default
+    pub fn lookup(entries: &HashMap<String, Self>, model_id: &str) -> Self {
+        entries
+            .get(model_id)
+            .or_else(|| entries.get(STREAMING_WILDCARD))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// A model loaded at startup, from `AppConfig.preload_models`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreloadModelEntry {
+    pub id: String,
+    /// Never unload this model to make room for others
+    #[serde(default)]
+    pub pin: bool,
+}
+
+/// Sampling values the user chose for chat requests that omit them
+///
+/// Only fields that are set override the model's own defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationConfigUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+}
+
+impl GenerationConfigUpdate {
+    /// Copy of `self` with every field set in `update` replaced
+    pub fn merged(&self, update: &Self) -> Self {
+        Self {
+            temperature: update.temperature.or(self.temperature),
+            top_p: update.top_p.or(self.top_p),
+            max_tokens: update.max_tokens.or(self.max_tokens),
         }
     }
 }
@@ -90,3 +182,57 @@ impl Default for ApplicationConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_lookup_prefers_model_then_wildcard() {
+        let fast = StreamingConfigEntry {
+            max_chunk_size_tokens: 8,
+            ..Default::default()
+        };
+        let slow = StreamingConfigEntry {
+            chunk_delay_ms: 20,
+            ..Default::default()
+        };
+        let entries = HashMap::from([
+            ("llama-3".to_string(), fast.clone()),
+            (STREAMING_WILDCARD.to_string(), slow.clone()),
+        ]);
+
+        assert_eq!(StreamingConfigEntry::lookup(&entries, "llama-3"), fast);
+        assert_eq!(StreamingConfigEntry::lookup(&entries, "mistral"), slow);
+        assert_eq!(
+            StreamingConfigEntry::lookup(&HashMap::new(), "mistral"),
+            StreamingConfigEntry::default()
+        );
+    }
+
+    #[test]
+    fn test_streaming_entry_fills_missing_fields() {
+        let entry: StreamingConfigEntry =
+            serde_json::from_str(r#"{"disable_streaming": true}"#).unwrap();
+        assert!(entry.disable_streaming);
+        assert_eq!(entry.max_chunk_size_tokens, 1);
+        assert_eq!(entry.keep_alive_ms, 15000);
+    }
+
+    #[test]
+    fn test_generation_update_merges_only_set_fields() {
+        let saved: GenerationConfigUpdate = serde_json::from_str(r#"{"top_p": 0.8}"#).unwrap();
+        let merged = saved.merged(&GenerationConfigUpdate {
+            temperature: Some(0.2),
+            ..Default::default()
+        });
+
+        assert_eq!(merged.temperature, Some(0.2));
+        assert_eq!(merged.top_p, Some(0.8));
+        assert_eq!(merged.max_tokens, None);
+        assert_eq!(
+            serde_json::to_string(&merged).unwrap(),
+            r#"{"temperature":0.2,"top_p":0.8}"#
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Configuration validation
 
-use super::types::{ApiConfig, ApplicationConfig, ServerConfig, StreamingConfigEntry};
+use super::types::{ApiConfig, ApplicationConfig, ServerConfig, SloConfig, StreamingConfigEntry};
 
 /// Configuration validator
 pub struct ConfigValidator;
@@ -14,6 +14,20 @@ impl ConfigValidator {
         if config.host.is_empty() {
             return Err("Host cannot be empty".to_string());
         }
+        if config.max_request_body_bytes == 0 {
+            return Err("Max request body size must be greater than 0".to_string());
+        }
+        Self::validate_slo(&config.slo)
+    }
+
+    /// Validate efficiency score targets
+    pub fn validate_slo(slo: &SloConfig) -> Result<(), String> {
+        if slo.min_rps.is_nan() || slo.min_rps < 0.0 {
+            return Err("SLO minimum RPS must not be negative".to_string());
+        }
+        if slo.target_p99_ms.is_nan() || slo.target_p99_ms <= 0.0 {
+            return Err("SLO target p99 must be greater than 0".to_string());
+        }
         Ok(())
     }
 
@@ -42,6 +56,9 @@ impl ConfigValidator {
         if config.keep_alive_ms == 0 {
             return Err("Keep-alive interval must be greater than 0".to_string());
         }
+        if config.max_chunk_size_tokens == 0 {
+            return Err("Max chunk size must be at least 1 token".to_string());
+        }
         Ok(())
     }
 
@@ -57,7 +74,10 @@ impl ConfigValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::types::{ApiConfig, ApplicationConfig, ServerConfig, StreamingConfigEntry};
+    use crate::config::types::{
+        ApiConfig, ApplicationConfig, DEFAULT_MAX_REQUEST_BODY_BYTES, ServerConfig, SloConfig,
+        StreamingConfigEntry,
+    };
 
     #[test]
     fn test_validate_server_valid() {
@@ -71,6 +91,29 @@ mod tests {
             host: "localhost".to_string(),
             port: 0,
             workers: None,
+            max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+            slo: SloConfig::default(),
+        };
+        assert!(ConfigValidator::validate_server(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_server_zero_body_limit() {
+        let config = ServerConfig {
+            max_request_body_bytes: 0,
+            ..ServerConfig::default()
+        };
+        assert!(ConfigValidator::validate_server(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_server_slo_targets() {
+        let config = ServerConfig {
+            slo: SloConfig {
+                target_p99_ms: 0.0,
+                ..SloConfig::default()
+            },
+            ..ServerConfig::default()
         };
         assert!(ConfigValidator::validate_server(&config).is_err());
     }
@@ -1,9 +1,9 @@
+use crate::api::ApiErrorResponse;
 use axum::{
     Json,
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use serde_json::json;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -27,8 +27,16 @@ pub enum MinervaError {
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
 
-    #[error("Model loading error: {0}")]
-    ModelLoadingError(String),
+    /// Loading failed; `cause` keeps the underlying error when there is one
+    #[error("Model loading error: {msg}")]
+    ModelLoadingError {
+        msg: String,
+        #[source]
+        cause: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    #[error("SafeTensors error: {0}")]
+    SafeTensorsError(#[from] safetensors::SafeTensorError),
 
     #[error("Context limit exceeded: max {max}, required {required}")]
     ContextLimitExceeded { max: usize, required: usize },
@@ -39,8 +47,8 @@ pub enum MinervaError {
     #[error("Out of memory: {0}")]
     OutOfMemory(String),
 
-    /// Phase 3.5b: GPU out of memory - can fallback to CPU
-    #[error("GPU out of memory: {0}, falling back to CPU")]
+    /// Phase 3.5b: GPU out of memory - the request fails with 503 and can be retried
+    #[error("GPU out of memory: {0}")]
     GpuOutOfMemory(String),
 
     /// Phase 3.5b: GPU context lost - needs reinitialization
@@ -57,79 +65,264 @@ pub enum MinervaError {
 
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    /// Model file in a format that cannot be loaded (e.g. legacy GGML)
+    #[error("Unsupported model format: {0}")]
+    UnsupportedFormat(String),
+
+    /// Error with an explicit HTTP status (e.g. 429 from the rate limiter)
+    #[error("HTTP {0}: {1}")]
+    Http(StatusCode, String),
 }
 
-impl IntoResponse for MinervaError {
-    fn into_response(self) -> Response {
-        let (status, error_code, message) = match self {
-            MinervaError::ModelNotFound(msg) => (StatusCode::NOT_FOUND, "model_not_found", msg),
-            MinervaError::InvalidRequest(msg) => (StatusCode::BAD_REQUEST, "invalid_request", msg),
-            MinervaError::InferenceError(msg) => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "inference_error", msg)
+impl MinervaError {
+    /// `ModelLoadingError` without an underlying cause
+    pub fn model_loading(msg: impl Into<String>) -> Self {
+        MinervaError::ModelLoadingError {
+            msg: msg.into(),
+            cause: None,
+        }
+    }
+
+    /// `ModelLoadingError` wrapping the error that caused it
+    pub fn model_loading_with(
+        msg: impl Into<String>,
+        cause: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        MinervaError::ModelLoadingError {
+            msg: msg.into(),
+            cause: Some(Box::new(cause)),
+        }
+    }
+
+    /// HTTP status returned for this error
+    ///
+    /// Every variant is listed so adding one forces a decision here.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            MinervaError::ModelNotFound(_) => StatusCode::NOT_FOUND,
+            MinervaError::InvalidRequest(_)
+            | MinervaError::ContextLimitExceeded { .. }
+            | MinervaError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            MinervaError::GenerationTimeout => StatusCode::REQUEST_TIMEOUT,
+            MinervaError::GpuOutOfMemory(_) => StatusCode::SERVICE_UNAVAILABLE,
+            MinervaError::Http(status, _) => *status,
+            MinervaError::ServerError(_)
+            | MinervaError::InferenceError(_)
+            | MinervaError::IoError(_)
+            | MinervaError::JsonError(_)
+            | MinervaError::ModelLoadingError { .. }
+            | MinervaError::SafeTensorsError(_)
+            | MinervaError::OutOfMemory(_)
+            | MinervaError::GpuContextLost(_)
+            | MinervaError::ModelCorrupted(_)
+            | MinervaError::StreamingError(_)
+            | MinervaError::UnsupportedFormat(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Message for the desktop UI: what went wrong and what to do about it
+    pub fn user_message(&self) -> String {
+        match self {
+            MinervaError::ModelNotFound(msg) => {
+                format!(
+                    "{}. Check the model name or path, or rediscover models.",
+                    msg
+                )
+            }
+            MinervaError::ServerError(msg) => {
+                format!("Server error: {}. Restart the server and try again.", msg)
             }
-            MinervaError::ModelLoadingError(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "model_loading_error",
-                msg,
+            MinervaError::InvalidRequest(msg) => {
+                format!("Invalid request: {}. Fix the request and resend it.", msg)
+            }
+            MinervaError::InferenceError(msg) => format!(
+                "Generation failed: {}. Try again, or reload the model if it keeps failing.",
+                msg
             ),
-            MinervaError::ContextLimitExceeded { max, required } => (
-                StatusCode::BAD_REQUEST,
-                "context_limit_exceeded",
-                format!(
-                    "Context limit exceeded: model supports {}, request requires {}",
-                    max, required
-                ),
+            MinervaError::IoError(e) => format!(
+                "File access failed: {}. Check that the file exists and is readable.",
+                e
             ),
-            MinervaError::GenerationTimeout => (
-                StatusCode::REQUEST_TIMEOUT,
-                "generation_timeout",
-                "Generation request timed out".to_string(),
+            MinervaError::JsonError(e) => format!(
+                "Malformed JSON: {}. Check the file or request body for syntax errors.",
+                e
             ),
-            MinervaError::OutOfMemory(msg) => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "out_of_memory", msg)
-            }
-            MinervaError::GpuOutOfMemory(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "gpu_out_of_memory",
-                format!("GPU memory exhausted, falling back to CPU: {}", msg),
+            MinervaError::ModelLoadingError { msg, .. } => format!(
+                "Could not load the model: {}. Make sure it is a complete, valid GGUF file.",
+                msg
             ),
-            MinervaError::GpuContextLost(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "gpu_context_lost",
-                format!("GPU context lost, will reinitialize: {}", msg),
+            MinervaError::ContextLimitExceeded { max, required } => format!(
+                "Prompt too long ({} tokens, limit {}). Shorten the conversation or increase context size.",
+                required, max
             ),
-            MinervaError::ModelCorrupted(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "model_corrupted",
-                format!("Model file corrupted: {}", msg),
+            MinervaError::GenerationTimeout => {
+                "Generation timed out. Lower max tokens or try a smaller model.".to_string()
+            }
+            MinervaError::SafeTensorsError(e) => format!(
+                "Could not read the SafeTensors weights: {}. Re-download the model files.",
+                e
             ),
-            MinervaError::StreamingError(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "streaming_error",
-                format!("Streaming error (retryable): {}", msg),
+            MinervaError::OutOfMemory(msg) => format!(
+                "Out of memory: {}. Close other applications or unload unused models.",
+                msg
             ),
-            MinervaError::ValidationError(msg) => {
-                (StatusCode::BAD_REQUEST, "validation_error", msg)
+            MinervaError::GpuOutOfMemory(_) => {
+                "Not enough GPU memory. Try loading a smaller quantized model (Q4_0 recommended)."
+                    .to_string()
+            }
+            MinervaError::GpuContextLost(_) => {
+                "The GPU was reset. Reload the model to continue.".to_string()
             }
-            _ => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "server_error",
-                self.to_string(),
+            MinervaError::ModelCorrupted(msg) => format!(
+                "Model file is corrupted: {}. Delete it and download it again.",
+                msg
             ),
-        };
-
-        let body = Json(json!({
-            "error": {
-                "message": message,
-                "type": error_code,
-                "code": error_code,
-                "param": null
+            MinervaError::StreamingError(msg) => {
+                format!("Streaming was interrupted: {}. Resend the message.", msg)
             }
-        }));
+            MinervaError::ValidationError(msg) => {
+                format!("Invalid value: {}. Correct it and try again.", msg)
+            }
+            MinervaError::UnsupportedFormat(msg) => {
+                format!("Unsupported model format: {}", msg)
+            }
+            MinervaError::Http(status, msg) if *status == StatusCode::TOO_MANY_REQUESTS => {
+                format!("Too many requests: {}. Wait a moment and try again.", msg)
+            }
+            MinervaError::Http(status, msg) => format!(
+                "Request failed ({}): {}. Check the server logs for details.",
+                status, msg
+            ),
+        }
+    }
+}
 
-        (status, body).into_response()
+impl IntoResponse for MinervaError {
+    fn into_response(self) -> Response {
+        let body = Json(ApiErrorResponse::from_minerva_error(&self));
+        (self.status_code(), body).into_response()
     }
 }
 
 #[allow(dead_code)]
 pub type MinervaResult<T> = Result<T, MinervaError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn all_variants() -> Vec<MinervaError> {
+        vec![
+            MinervaError::ModelNotFound("/models/a.gguf".to_string()),
+            MinervaError::ServerError("x".to_string()),
+            MinervaError::InvalidRequest("x".to_string()),
+            MinervaError::InferenceError("x".to_string()),
+            MinervaError::IoError(std::io::Error::other("x")),
+            MinervaError::JsonError(serde_json::from_str::<()>("{").unwrap_err()),
+            MinervaError::model_loading("x"),
+            MinervaError::SafeTensorsError(safetensors::SafeTensors::deserialize(b"").unwrap_err()),
+            MinervaError::ContextLimitExceeded {
+                max: 4096,
+                required: 5000,
+            },
+            MinervaError::GenerationTimeout,
+            MinervaError::OutOfMemory("x".to_string()),
+            MinervaError::GpuOutOfMemory("x".to_string()),
+            MinervaError::GpuContextLost("x".to_string()),
+            MinervaError::ModelCorrupted("x".to_string()),
+            MinervaError::StreamingError("x".to_string()),
+            MinervaError::ValidationError("x".to_string()),
+            MinervaError::UnsupportedFormat("x".to_string()),
+            MinervaError::Http(StatusCode::TOO_MANY_REQUESTS, "x".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_user_messages_are_unique() {
+        let messages: Vec<String> = all_variants().iter().map(|e| e.user_message()).collect();
+        let unique: HashSet<&String> = messages.iter().collect();
+        assert_eq!(unique.len(), messages.len());
+    }
+
+    #[test]
+    fn test_user_message_includes_details() {
+        assert_eq!(
+            MinervaError::ModelNotFound("Model 'llama' not found".to_string()).user_message(),
+            "Model 'llama' not found. Check the model name or path, or rediscover models."
+        );
+        assert_eq!(
+            MinervaError::ContextLimitExceeded {
+                max: 4096,
+                required: 5000
+            }
+            .user_message(),
+            "Prompt too long (5000 tokens, limit 4096). \
+             Shorten the conversation or increase context size."
+        );
+        assert!(
+            MinervaError::GpuOutOfMemory("alloc".to_string())
+                .user_message()
+                .contains("Q4_0")
+        );
+    }
+
+    #[test]
+    fn test_status_codes() {
+        let cases = [
+            (MinervaError::ModelNotFound("m".to_string()), 404),
+            (MinervaError::InvalidRequest("r".to_string()), 400),
+            (
+                MinervaError::ContextLimitExceeded {
+                    max: 1,
+                    required: 2,
+                },
+                400,
+            ),
+            (MinervaError::GpuOutOfMemory("g".to_string()), 503),
+            (
+                MinervaError::Http(StatusCode::TOO_MANY_REQUESTS, "slow down".to_string()),
+                429,
+            ),
+            (MinervaError::ModelCorrupted("c".to_string()), 500),
+        ];
+        for (err, status) in cases {
+            assert_eq!(err.status_code().as_u16(), status, "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_into_response_uses_status_code() {
+        let response = MinervaError::Http(StatusCode::TOO_MANY_REQUESTS, "slow down".to_string())
+            .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_model_loading_error_exposes_cause() {
+        use std::error::Error;
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = MinervaError::model_loading_with("Failed to open model.gguf", io);
+        assert_eq!(
+            err.to_string(),
+            "Model loading error: Failed to open model.gguf"
+        );
+
+        let cause = err.source().expect("cause should be kept");
+        assert_eq!(cause.to_string(), "no such file");
+        assert!(cause.downcast_ref::<std::io::Error>().is_some());
+
+        assert!(MinervaError::model_loading("bad header").source().is_none());
+    }
+
+    #[test]
+    fn test_safetensors_error_converts() {
+        fn parse() -> MinervaResult<()> {
+            safetensors::SafeTensors::deserialize(b"")?;
+            Ok(())
+        }
+        assert!(matches!(parse(), Err(MinervaError::SafeTensorsError(_))));
+    }
+}
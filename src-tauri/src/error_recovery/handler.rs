@@ -2,6 +2,11 @@
 
 use super::types::RecoveryStrategy;
 use crate::error::MinervaError;
+use crate::inference::downloader::{ModelDownloader, REDOWNLOADING_EVENT, RedownloadJob};
+use crate::inference::model_registry::ModelRegistry;
+use crate::resilience::ErrorClass;
+use crate::resilience::retry::RetryConfig;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 /// Error recovery handler
@@ -11,10 +16,13 @@ impl ErrorRecovery {
     /// Determine recovery strategy for an error
     pub fn strategy_for(error: &MinervaError) -> RecoveryStrategy {
         match error {
-            MinervaError::StreamingError(_) => RecoveryStrategy::Retry {
-                max_attempts: 3,
-                backoff_ms: 100,
-            },
+            MinervaError::StreamingError(_) => {
+                let retry = RetryConfig::for_error_class(ErrorClass::Transient);
+                RecoveryStrategy::Retry {
+                    max_attempts: retry.max_attempts,
+                    backoff_ms: retry.base_delay_ms,
+                }
+            }
             MinervaError::GpuOutOfMemory(_) => RecoveryStrategy::FallbackToCpu,
             MinervaError::GpuContextLost(_) => RecoveryStrategy::ReinitializeGpu,
             MinervaError::ModelCorrupted(_) => RecoveryStrategy::ReloadModel,
@@ -34,11 +42,60 @@ impl ErrorRecovery {
             RecoveryStrategy::FallbackToCpu => "GPU unavailable, falling back to CPU inference...",
             RecoveryStrategy::ReinitializeGpu => "Reinitializing GPU context...",
             RecoveryStrategy::ReloadModel => "Reloading model from disk...",
+            RecoveryStrategy::Redownload => "Model file is corrupted, downloading a fresh copy...",
             RecoveryStrategy::SkipAndContinue => "Skipping operation and continuing...",
             RecoveryStrategy::Fatal => "Fatal error - stopping operation.",
         }
     }
 
+    /// Recover a corrupted model by downloading it again
+    ///
+    /// Marks the model corrupted, notifies the UI via `model://redownloading`
+    /// and spawns a download from the source URL in its `CacheEntry`. The file
+    /// is only replaced once the new copy matches the expected SHA-256, and
+    /// the corrupted flag is cleared then. Returns `Fatal` when there is no
+    /// source URL or no async runtime.
+    pub fn handle_corrupted_model(
+        model_id: &str,
+        registry: &Arc<Mutex<ModelRegistry>>,
+        downloader: &ModelDownloader,
+    ) -> RecoveryStrategy {
+        let job = {
+            let Ok(mut registry) = registry.lock() else {
+                return RecoveryStrategy::Fatal;
+            };
+            if registry.mark_corrupted(model_id).is_err() {
+                return RecoveryStrategy::Fatal;
+            }
+            redownload_job(model_id, &registry)
+        };
+        let Some(job) = job else {
+            tracing::error!("Model {} is corrupted and has no download source", model_id);
+            return RecoveryStrategy::Fatal;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return RecoveryStrategy::Fatal;
+        };
+
+        downloader.notify(REDOWNLOADING_EVENT, job.clone());
+        let downloader = downloader.clone();
+        let registry = Arc::clone(registry);
+        runtime.spawn(async move {
+            match downloader.redownload(&job).await {
+                Ok(fetched) => {
+                    tracing::info!("Re-downloaded {} ({} bytes)", job.model_id, fetched.bytes);
+                    if let Ok(mut registry) = registry.lock()
+                        && let Err(e) = registry.mark_restored(&job.model_id, fetched.sha256)
+                    {
+                        tracing::warn!("Could not clear corrupted flag: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Re-download of {} failed: {}", job.model_id, e),
+            }
+        });
+        RecoveryStrategy::Redownload
+    }
+
     /// Calculate backoff delay for retry attempt
     pub fn backoff_delay(attempt: u32, base_ms: u64) -> Duration {
         let delay_ms = base_ms * u64::pow(2, attempt);
@@ -69,3 +126,14 @@ impl ErrorRecovery {
         )
     }
 }
+
+fn redownload_job(model_id: &str, registry: &ModelRegistry) -> Option<RedownloadJob> {
+    let url = registry.download_source(model_id)?.source_url.clone()?;
+    let (path, checksum) = registry.expected_hash(model_id)?;
+    Some(RedownloadJob {
+        model_id: model_id.to_string(),
+        url,
+        path,
+        checksum,
+    })
+}
@@ -3,7 +3,7 @@
 //! Provides recovery mechanisms for common failure scenarios:
 //! - GPU out of memory → fallback to CPU
 //! - GPU context loss → reinitialize
-//! - Model corruption → validation and reload
+//! - Model corruption → re-download from the recorded source
 //! - Streaming errors → retry mechanism
 
 pub mod handler;
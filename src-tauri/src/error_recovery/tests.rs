@@ -2,8 +2,18 @@
 
 use super::handler::ErrorRecovery;
 use super::types::RecoveryStrategy;
-use crate::error::MinervaError;
-use std::time::Duration;
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::downloader::{CacheEntry, ModelDownloader};
+use crate::inference::model_registry::ModelRegistry;
+use crate::resilience::TimeoutConfig;
+use crate::resilience::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::resilience::coordinator::ResilienceCoordinator;
+use crate::resilience::resilience_policy::ResiliencePolicy;
+use crate::resilience::retry::{RetryConfig, RetryState};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_streaming_error_recovery() {
@@ -85,3 +95,301 @@ fn test_recovery_messages() {
     });
     assert!(msg.contains("Retrying"));
 }
+
+#[test]
+fn test_corrupted_model_unregistered_is_fatal() {
+    let registry = Arc::new(Mutex::new(ModelRegistry::new()));
+    let strategy =
+        ErrorRecovery::handle_corrupted_model("missing", &registry, &ModelDownloader::new());
+    assert_eq!(strategy, RecoveryStrategy::Fatal);
+}
+
+#[tokio::test]
+async fn test_corrupted_model_without_source_is_fatal() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let mut registry = ModelRegistry::new();
+    registry
+        .register("local", file.path().to_path_buf())
+        .unwrap();
+
+    let registry = Arc::new(Mutex::new(registry));
+
+    let strategy =
+        ErrorRecovery::handle_corrupted_model("local", &registry, &ModelDownloader::new());
+    assert_eq!(strategy, RecoveryStrategy::Fatal);
+    assert!(registry.lock().unwrap().get("local").unwrap().corrupted);
+}
+
+#[tokio::test]
+async fn test_corrupted_model_with_source_redownloads() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.gguf");
+    std::fs::write(&path, b"weights").unwrap();
+    let mut registry = ModelRegistry::new();
+    registry.register("hub", path.clone()).unwrap();
+    registry.record_download(CacheEntry {
+        model_id: "hub".to_string(),
+        path,
+        revision: "main".to_string(),
+        size_bytes: 7,
+        file_count: 1,
+        downloaded_at: 0,
+        last_accessed: 0,
+        source_url: Some("http://127.0.0.1:1/model.gguf".to_string()),
+        sha256: None,
+    });
+    let registry = Arc::new(Mutex::new(registry));
+
+    let strategy = ErrorRecovery::handle_corrupted_model("hub", &registry, &ModelDownloader::new());
+    assert_eq!(strategy, RecoveryStrategy::Redownload);
+}
+
+// ============================================================================
+// Recovery paths driven through a fault-injecting backend
+// ============================================================================
+
+/// Backend that fails with queued errors, then succeeds
+#[derive(Default)]
+struct FaultyBackend {
+    faults: Mutex<VecDeque<MinervaError>>,
+    /// Error returned once the queue is empty; `None` means succeed
+    persistent: Option<fn() -> MinervaError>,
+    calls: AtomicUsize,
+}
+
+impl FaultyBackend {
+    fn failing_with(faults: Vec<MinervaError>) -> Arc<Self> {
+        Arc::new(Self {
+            faults: Mutex::new(faults.into()),
+            ..Default::default()
+        })
+    }
+
+    fn always_failing(error: fn() -> MinervaError) -> Arc<Self> {
+        Arc::new(Self {
+            persistent: Some(error),
+            ..Default::default()
+        })
+    }
+
+    async fn generate(&self, prompt: &str) -> MinervaResult<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if let Some(error) = self.faults.lock().unwrap().pop_front() {
+            return Err(error);
+        }
+        match self.persistent {
+            Some(error) => Err(error()),
+            None => Ok(format!("echo: {}", prompt)),
+        }
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+fn fast_retries(max_attempts: u32) -> RetryConfig {
+    RetryConfig {
+        max_attempts,
+        base_delay_ms: 1,
+        max_delay_ms: 1,
+        use_jitter: false,
+    }
+}
+
+fn breaker(failure_threshold: u32, timeout_secs: u64) -> CircuitBreaker {
+    CircuitBreaker::new(CircuitBreakerConfig {
+        failure_threshold,
+        timeout_secs,
+        half_open_max_calls: 1,
+    })
+}
+
+#[tokio::test]
+async fn test_gpu_oom_falls_back_to_cpu_and_completes() {
+    let gpu = FaultyBackend::always_failing(|| MinervaError::GpuOutOfMemory("24GB".to_string()));
+    let cpu = FaultyBackend::failing_with(vec![]);
+
+    let fallback_cpu = cpu.clone();
+    let policy = ResiliencePolicy::new(fast_retries(3), TimeoutConfig::default()).with_fallback(
+        Box::new(move |error: &MinervaError| {
+            assert_eq!(
+                ErrorRecovery::strategy_for(error),
+                RecoveryStrategy::FallbackToCpu
+            );
+            let cpu = fallback_cpu.clone();
+            Box::pin(async move { cpu.generate("hello").await })
+        }),
+    );
+    let coordinator = ResilienceCoordinator::new(breaker(5, 30));
+
+    let output = coordinator
+        .execute(|| gpu.generate("hello"), &policy)
+        .await
+        .unwrap();
+    assert_eq!(output, "echo: hello");
+    assert_eq!(gpu.calls(), 1, "OOM must not be retried on the GPU");
+    assert_eq!(cpu.calls(), 1);
+}
+
+#[tokio::test]
+async fn test_three_streaming_errors_open_circuit() {
+    let backend =
+        FaultyBackend::always_failing(|| MinervaError::StreamingError("reset".to_string()));
+    let policy = ResiliencePolicy::new(fast_retries(2), TimeoutConfig::default());
+    let coordinator = ResilienceCoordinator::new(breaker(3, 30));
+
+    let result = coordinator
+        .execute(|| backend.generate("hi"), &policy)
+        .await;
+    assert!(matches!(result, Err(MinervaError::StreamingError(_))));
+    assert_eq!(backend.calls(), 3);
+    assert_eq!(coordinator.circuit_state(), CircuitState::Open);
+
+    // An open circuit rejects without touching the backend
+    let rejected = coordinator
+        .execute(|| backend.generate("hi"), &policy)
+        .await;
+    assert!(rejected.is_err());
+    assert_eq!(backend.calls(), 3);
+}
+
+#[tokio::test]
+async fn test_circuit_half_opens_after_reset_timeout_and_closes_on_success() {
+    let backend = FaultyBackend::failing_with(
+        (0..3)
+            .map(|_| MinervaError::StreamingError("reset".to_string()))
+            .collect(),
+    );
+    let policy = ResiliencePolicy::new(fast_retries(2), TimeoutConfig::default());
+    let circuit = breaker(3, 1);
+    let coordinator = ResilienceCoordinator::new(circuit.clone());
+
+    assert!(
+        coordinator
+            .execute(|| backend.generate("hi"), &policy)
+            .await
+            .is_err()
+    );
+    assert_eq!(coordinator.circuit_state(), CircuitState::Open);
+    assert!(!circuit.allow_request());
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert!(circuit.allow_request());
+    assert_eq!(circuit.state(), CircuitState::HalfOpen);
+
+    let output = coordinator
+        .execute(|| backend.generate("hi"), &policy)
+        .await
+        .unwrap();
+    assert_eq!(output, "echo: hi");
+    assert_eq!(coordinator.circuit_state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_corrupted_model_redownload_restores_file() {
+    let app = axum::Router::new().route("/model.gguf", axum::routing::get(|| async { "weights" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/model.gguf", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.gguf");
+    std::fs::write(&path, b"weights").unwrap();
+    let mut registry = ModelRegistry::new();
+    registry.register("hub", path.clone()).unwrap();
+    registry.record_download(CacheEntry {
+        model_id: "hub".to_string(),
+        path: path.clone(),
+        revision: "main".to_string(),
+        size_bytes: 7,
+        file_count: 1,
+        downloaded_at: 0,
+        last_accessed: 0,
+        source_url: Some(url),
+        sha256: None,
+    });
+    std::fs::write(&path, b"garbage").unwrap();
+    let registry = Arc::new(Mutex::new(registry));
+
+    let err = MinervaError::ModelCorrupted("checksum mismatch".to_string());
+    assert_eq!(
+        ErrorRecovery::strategy_for(&err),
+        RecoveryStrategy::ReloadModel
+    );
+    let strategy = ErrorRecovery::handle_corrupted_model("hub", &registry, &ModelDownloader::new());
+    assert_eq!(strategy, RecoveryStrategy::Redownload);
+    assert!(registry.lock().unwrap().get("hub").unwrap().corrupted);
+
+    let started = Instant::now();
+    while registry.lock().unwrap().get("hub").unwrap().corrupted {
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "re-download did not restore the corrupted model"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(std::fs::read(&path).unwrap(), b"weights");
+    assert!(
+        registry
+            .lock()
+            .unwrap()
+            .get("hub")
+            .unwrap()
+            .verify()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_retry_jitter_varies_delays() {
+    let config = RetryConfig {
+        max_attempts: 5,
+        base_delay_ms: 100,
+        max_delay_ms: 10_000,
+        use_jitter: true,
+    };
+    let delays: Vec<Duration> = (0..20)
+        .map(|_| {
+            let mut state = RetryState::new(config);
+            state.next_delay();
+            state.next_delay()
+        })
+        .collect();
+
+    assert!(delays.iter().all(|d| *d <= Duration::from_millis(200)));
+    assert!(
+        delays.iter().any(|d| *d != delays[0]),
+        "jittered delays should differ: {:?}",
+        delays
+    );
+}
+
+#[tokio::test]
+async fn test_total_timeout_bounds_retries() {
+    let backend =
+        FaultyBackend::always_failing(|| MinervaError::StreamingError("stall".to_string()));
+    let policy = ResiliencePolicy::new(
+        RetryConfig {
+            max_attempts: 1_000,
+            base_delay_ms: 10,
+            max_delay_ms: 10,
+            use_jitter: false,
+        },
+        TimeoutConfig {
+            operation_timeout: Duration::from_secs(1),
+            total_timeout: Duration::from_millis(100),
+        },
+    );
+    let coordinator = ResilienceCoordinator::new(breaker(10_000, 30));
+
+    let started = Instant::now();
+    let result = coordinator
+        .execute(|| backend.generate("hi"), &policy)
+        .await;
+
+    assert!(matches!(result, Err(MinervaError::StreamingError(_))));
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert!(backend.calls() < 1_000);
+}
@@ -11,6 +11,8 @@ pub enum RecoveryStrategy {
     ReinitializeGpu,
     /// Reload the model
     ReloadModel,
+    /// A fresh copy of the model is downloading in the background
+    Redownload,
     /// Skip and continue (non-critical)
     SkipAndContinue,
     /// Fatal error - stop
@@ -2,10 +2,14 @@
 ///
 /// This module provides async versions of batch operations using tokio,
 /// enabling concurrent request handling and streaming responses.
+use crate::error::MinervaError;
 use crate::inference::batch::{
     BatchStats, DetokenizeBatchRequest, DetokenizeBatchResponse, InferenceBatchRequest,
     InferenceBatchResponse, TokenizeBatchRequest, TokenizeBatchResponse,
 };
+use crate::inference::batch_optimized::BatchTensorPool;
+use crate::inference::batch_queue::{BatchForward, PaddedBatch, QueuedRequest};
+use crate::server::ServerState;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
@@ -298,6 +302,72 @@ impl Default for AsyncBatchInferenceEngine {
     }
 }
 
+/// Continuous batching loop over the server's request queue
+pub struct BatchAsync;
+
+impl BatchAsync {
+    /// Collect queued requests into batches and run one forward pass per batch
+    pub fn run(state: ServerState) -> impl Future<Output = ()> {
+        async move {
+            let queue = state.batch_queue.clone();
+            loop {
+                queue.wait_for_request().await;
+                queue.fill_window().await;
+
+                let batch = queue.take_batch();
+                let forward = state.batch_forward.clone();
+                let pool = state.tensor_pool.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    Self::dispatch(forward.as_deref(), batch, &pool)
+                })
+                .await;
+                queue.finish_batch();
+            }
+        }
+    }
+
+    /// Pad the batch into a pooled buffer, run the forward pass, and send
+    /// each row back to its request
+    pub fn dispatch(
+        forward: Option<&dyn BatchForward>,
+        batch: Vec<QueuedRequest>,
+        pool: &BatchTensorPool<u32>,
+    ) {
+        let sequences: Vec<&[u32]> = batch.iter().map(|q| q.tokens.as_slice()).collect();
+        let longest = sequences.iter().map(|s| s.len()).max().unwrap_or(0);
+        let buffer = pool.take(longest * sequences.len());
+        let padded = PaddedBatch::from_sequences_in(&sequences, buffer);
+
+        let result = match forward {
+            Some(model) => model.forward(&padded),
+            None => Err(MinervaError::InferenceError(
+                "No batch inference backend configured".to_string(),
+            )),
+        };
+        pool.recycle(padded.tokens);
+
+        match result {
+            Ok(rows) if rows.len() == batch.len() => {
+                for (queued, logits) in batch.into_iter().zip(rows) {
+                    let _ = queued.responder.send(Ok(logits));
+                }
+            }
+            Ok(rows) => {
+                Self::fail_all(batch, &format!("Forward pass returned {} rows", rows.len()))
+            }
+            Err(e) => Self::fail_all(batch, &e.to_string()),
+        }
+    }
+
+    fn fail_all(batch: Vec<QueuedRequest>, message: &str) {
+        for queued in batch {
+            let _ = queued
+                .responder
+                .send(Err(MinervaError::InferenceError(message.to_string())));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,4 +456,49 @@ mod tests {
         assert_eq!(result.success_count(), 2);
         assert_eq!(result.get_stats().total_items, 2);
     }
+
+    struct LengthForward;
+
+    impl BatchForward for LengthForward {
+        fn forward(&self, batch: &PaddedBatch) -> crate::error::MinervaResult<Vec<Vec<f32>>> {
+            Ok(batch.lengths.iter().map(|&len| vec![len as f32]).collect())
+        }
+    }
+
+    fn inference_request() -> crate::inference::api::InferenceRequest {
+        crate::inference::api::InferenceRequest {
+            model: "test".to_string(),
+            prompt: "hi".to_string(),
+            max_tokens: None,
+            temperature: None,
+            top_k: None,
+            top_p: None,
+            seed: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_async_splits_logits_per_request() {
+        let state = ServerState::new().with_batch_forward(Arc::new(LengthForward));
+        let short = state.batch_queue.submit(inference_request(), vec![1]);
+        let long = state.batch_queue.submit(inference_request(), vec![1, 2, 3]);
+
+        let pool = Arc::clone(&state.tensor_pool);
+        let pooled = pool.available();
+
+        let runner = tokio::spawn(BatchAsync::run(state));
+        assert_eq!(short.await.unwrap().unwrap(), vec![1.0]);
+        assert_eq!(long.await.unwrap().unwrap(), vec![3.0]);
+        runner.abort();
+        assert_eq!(pool.available(), pooled);
+    }
+
+    #[tokio::test]
+    async fn test_batch_async_without_backend_fails_requests() {
+        let state = ServerState::new();
+        let receiver = state.batch_queue.submit(inference_request(), vec![1]);
+
+        BatchAsync::dispatch(None, state.batch_queue.take_batch(), &state.tensor_pool);
+        assert!(receiver.await.unwrap().is_err());
+    }
 }
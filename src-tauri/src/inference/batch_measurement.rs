@@ -2,8 +2,17 @@
 ///
 /// This module provides timing and performance measurement functions
 /// for profiling batch operations without relying on external benchmarking tools.
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::inference_backend_trait::{GenerationParams, InferenceBackend};
 use std::time::Instant;
 
+/// Generation settings used for every request in a batch-size sweep
+const SWEEP_PARAMS: GenerationParams = GenerationParams {
+    max_tokens: 32,
+    temperature: 0.7,
+    top_p: 0.9,
+};
+
 /// Measure the execution time of a closure and return the result + duration
 pub fn measure_time<F, R>(f: F) -> (R, u128)
 where
@@ -107,6 +116,127 @@ impl std::fmt::Display for OperationStats {
     }
 }
 
+/// Outcome of running one batch size during a sweep
+#[derive(Debug, Clone)]
+pub struct BatchSweepResult {
+    pub batch_size: usize,
+    pub tokens_per_sec: f64,
+    pub p99_latency_ms: f64,
+    /// Change in process resident memory across the batch; `None` off Linux,
+    /// where it is not read
+    pub gpu_memory_delta_bytes: Option<i64>,
+}
+
+/// Latency constraint for picking a batch size
+#[derive(Debug, Clone, Copy)]
+pub struct LatencyTarget {
+    pub max_p99_ms: f64,
+}
+
+/// Profiles throughput vs latency across batch sizes
+pub struct BatchMeasurement;
+
+impl BatchMeasurement {
+    /// Run `prompt` concurrently at each batch size and record throughput and latency
+    ///
+    /// Stops at the first generation error.
+    pub fn sweep(
+        backend: &dyn InferenceBackend,
+        prompt: &str,
+        batch_sizes: &[usize],
+    ) -> MinervaResult<Vec<BatchSweepResult>> {
+        batch_sizes
+            .iter()
+            .filter(|&&size| size > 0)
+            .map(|&size| Self::measure_batch(backend, prompt, size))
+            .collect()
+    }
+
+    /// Largest batch whose p99 latency meets the target (falls back to 1)
+    pub fn recommended_batch_size(sweep: &[BatchSweepResult], target: LatencyTarget) -> usize {
+        sweep
+            .iter()
+            .filter(|result| result.p99_latency_ms <= target.max_p99_ms)
+            .map(|result| result.batch_size)
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn measure_batch(
+        backend: &dyn InferenceBackend,
+        prompt: &str,
+        batch_size: usize,
+    ) -> MinervaResult<BatchSweepResult> {
+        let memory_before = process_memory_bytes();
+        let start = Instant::now();
+
+        let runs: Vec<(usize, f64)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..batch_size)
+                .map(|_| scope.spawn(|| Self::timed_generate(backend, prompt)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| {
+                    h.join().unwrap_or_else(|_| {
+                        Err(MinervaError::InferenceError(
+                            "Sweep generation thread panicked".to_string(),
+                        ))
+                    })
+                })
+                .collect::<MinervaResult<_>>()
+        })?;
+
+        let elapsed_secs = start.elapsed().as_secs_f64().max(f64::EPSILON);
+        let total_tokens: usize = runs.iter().map(|(tokens, _)| tokens).sum();
+        let latencies: Vec<f64> = runs.iter().map(|(_, latency)| *latency).collect();
+
+        Ok(BatchSweepResult {
+            batch_size,
+            tokens_per_sec: total_tokens as f64 / elapsed_secs,
+            p99_latency_ms: percentile(&latencies, 0.99),
+            gpu_memory_delta_bytes: memory_before
+                .zip(process_memory_bytes())
+                .map(|(before, after)| after - before),
+        })
+    }
+
+    /// Generate once, returning (tokens produced, latency in ms)
+    fn timed_generate(backend: &dyn InferenceBackend, prompt: &str) -> MinervaResult<(usize, f64)> {
+        let start = Instant::now();
+        let output = backend.generate(prompt, SWEEP_PARAMS)?;
+        let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let tokens = backend.tokenize(&output)?.len();
+        Ok((tokens, latency_ms))
+    }
+}
+
+/// Nearest-rank percentile (`q` in 0.0..=1.0)
+fn percentile(values: &[f64], q: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = ((q * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
+
+/// Resident memory of this process, where the platform exposes it
+#[cfg(target_os = "linux")]
+fn process_memory_bytes() -> Option<i64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let resident_pages: i64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf only reads a system constant
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    (page_size > 0).then(|| resident_pages * page_size as i64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_memory_bytes() -> Option<i64> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,4 +267,55 @@ mod tests {
         assert!((stats.total_ms() - 10.0).abs() < 0.01);
         assert!(stats.throughput_per_second() > 900.0); // ~1000/sec
     }
+
+    fn sweep_result(batch_size: usize, p99_latency_ms: f64) -> BatchSweepResult {
+        BatchSweepResult {
+            batch_size,
+            tokens_per_sec: 0.0,
+            p99_latency_ms,
+            gpu_memory_delta_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_sweep_runs_each_batch_size() {
+        let model_file = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = crate::inference::mock_backend::MockBackend::new();
+        backend.load_model(model_file.path(), 512).unwrap();
+
+        let results = BatchMeasurement::sweep(&backend, "hello", &[1, 4]).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].batch_size, 4);
+        assert!(results.iter().all(|r| r.tokens_per_sec > 0.0));
+        assert!(results.iter().all(|r| r.p99_latency_ms > 0.0));
+    }
+
+    #[test]
+    fn test_sweep_propagates_generation_errors() {
+        let backend = crate::inference::mock_backend::MockBackend::new();
+        assert!(BatchMeasurement::sweep(&backend, "hello", &[2]).is_err());
+    }
+
+    #[test]
+    fn test_recommended_batch_size() {
+        let sweep = vec![
+            sweep_result(1, 10.0),
+            sweep_result(4, 40.0),
+            sweep_result(8, 90.0),
+        ];
+
+        let target = LatencyTarget { max_p99_ms: 50.0 };
+        assert_eq!(BatchMeasurement::recommended_batch_size(&sweep, target), 4);
+
+        let strict = LatencyTarget { max_p99_ms: 1.0 };
+        assert_eq!(BatchMeasurement::recommended_batch_size(&sweep, strict), 1);
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&values, 0.99), 99.0);
+        assert_eq!(percentile(&[], 0.99), 0.0);
+    }
 }
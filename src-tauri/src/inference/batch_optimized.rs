@@ -6,9 +6,257 @@
 /// - Pre-allocated Vec capacity
 /// - Reduced cloning operations
 /// - Efficient statistics aggregation
+/// - Pooled buffers for padded batch tensors
 pub use crate::inference::batch_result_optimized::{
     BatchResponseOpt, BatchResultOptimized, BatchStatsOpt,
 };
 pub use crate::inference::optimization_utils::{
     calculate_optimal_batch_size, estimate_memory_overhead,
 };
+
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// (batch_size, seq_len) shapes pre-allocated by `BatchTensorPool::default`
+pub const DEFAULT_POOL_SHAPES: [(usize, usize); 4] = [(1, 512), (4, 512), (8, 512), (16, 512)];
+
+/// Buffers kept per size class; extra buffers are dropped on release
+const MAX_BUFFERS_PER_SIZE: usize = 4;
+
+/// Smallest size class, in elements
+const MIN_SIZE_CLASS: usize = 256;
+
+/// Bytes a pool keeps unless `with_max_bytes` says otherwise
+const DEFAULT_MAX_POOLED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Free buffers bucketed by power-of-two size class
+struct FreeBuffers<T> {
+    /// Every buffer in a bucket has at least the bucket's capacity
+    buckets: BTreeMap<usize, Vec<Vec<T>>>,
+    bytes: usize,
+}
+
+/// Reusable buffers for padded batch tensors
+///
+/// Buffers are allocated at power-of-two size classes so a released buffer
+/// serves any later request in its class. At most `MAX_BUFFERS_PER_SIZE`
+/// buffers per class and `max_bytes` in total are kept.
+pub struct BatchTensorPool<T = f32> {
+    free: Mutex<FreeBuffers<T>>,
+    max_bytes: usize,
+}
+
+impl<T: Copy + Default> BatchTensorPool<T> {
+    /// Pre-allocate `buffers_per_shape` buffers for each (batch_size, seq_len)
+    pub fn new(shapes: &[(usize, usize)], buffers_per_shape: usize) -> Self {
+        let pool = Self {
+            free: Mutex::new(FreeBuffers {
+                buckets: BTreeMap::new(),
+                bytes: 0,
+            }),
+            max_bytes: DEFAULT_MAX_POOLED_BYTES,
+        };
+        for &(batch_size, seq_len) in shapes {
+            let class = size_class(batch_size * seq_len);
+            for _ in 0..buffers_per_shape {
+                pool.recycle(Vec::with_capacity(class));
+            }
+        }
+        pool
+    }
+
+    /// Keep at most `max_bytes` of free buffers, dropping any beyond it now
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        let free = self.free.get_mut();
+        while free.bytes > max_bytes {
+            let Some(mut largest) = free.buckets.last_entry() else {
+                break;
+            };
+            let buffer = largest.get_mut().pop();
+            if largest.get().is_empty() {
+                largest.remove();
+            }
+            free.bytes -= buffer.map_or(0, |b| byte_size(&b));
+        }
+        self
+    }
+
+    /// Borrow a buffer, zeroed and sized to `batch_size * seq_len`
+    pub fn acquire(self: &Arc<Self>, batch_size: usize, seq_len: usize) -> PooledTensor<T> {
+        let len = batch_size * seq_len;
+        let mut buffer = self.take(len);
+        buffer.resize(len, T::default());
+
+        PooledTensor {
+            buffer: Some(buffer),
+            pool: Arc::clone(self),
+        }
+    }
+
+    /// Take an empty buffer with capacity for at least `len` elements
+    ///
+    /// Hand it back with `recycle` once done.
+    pub fn take(&self, len: usize) -> Vec<T> {
+        let class = size_class(len);
+        let mut free = self.free.lock();
+        let Some(capacity) = free
+            .buckets
+            .range(class..)
+            .find(|(_, buffers)| !buffers.is_empty())
+            .map(|(capacity, _)| *capacity)
+        else {
+            return Vec::with_capacity(class);
+        };
+        let mut buffer = free
+            .buckets
+            .get_mut(&capacity)
+            .and_then(Vec::pop)
+            .unwrap_or_default();
+        free.bytes -= byte_size(&buffer);
+        buffer.clear();
+        buffer
+    }
+
+    /// Return a buffer to its size class, or drop it when the pool is full
+    pub fn recycle(&self, buffer: Vec<T>) {
+        if buffer.capacity() < MIN_SIZE_CLASS {
+            return;
+        }
+        let bytes = byte_size(&buffer);
+        let mut free = self.free.lock();
+        if free.bytes + bytes > self.max_bytes {
+            return;
+        }
+        let class = 1 << buffer.capacity().ilog2();
+        let buffers = free.buckets.entry(class).or_default();
+        if buffers.len() < MAX_BUFFERS_PER_SIZE {
+            buffers.push(buffer);
+            free.bytes += bytes;
+        }
+    }
+
+    /// Number of free buffers across all sizes
+    pub fn available(&self) -> usize {
+        self.free.lock().buckets.values().map(Vec::len).sum()
+    }
+
+    /// Bytes held by free buffers
+    pub fn pooled_bytes(&self) -> usize {
+        self.free.lock().bytes
+    }
+}
+
+impl<T: Copy + Default> Default for BatchTensorPool<T> {
+    fn default() -> Self {
+        Self::new(&DEFAULT_POOL_SHAPES, 2)
+    }
+}
+
+/// Power-of-two capacity a request for `len` elements is served from
+fn size_class(len: usize) -> usize {
+    len.max(MIN_SIZE_CLASS).next_power_of_two()
+}
+
+fn byte_size<T>(buffer: &Vec<T>) -> usize {
+    buffer.capacity() * std::mem::size_of::<T>()
+}
+
+/// Buffer borrowed from a `BatchTensorPool`; returned to the pool on drop
+pub struct PooledTensor<T: Copy + Default = f32> {
+    buffer: Option<Vec<T>>,
+    pool: Arc<BatchTensorPool<T>>,
+}
+
+impl<T: Copy + Default> Deref for PooledTensor<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.buffer.as_deref().unwrap_or_default()
+    }
+}
+
+impl<T: Copy + Default> DerefMut for PooledTensor<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.buffer.as_deref_mut().unwrap_or_default()
+    }
+}
+
+impl<T: Copy + Default> Drop for PooledTensor<T> {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            self.pool.recycle(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_reuses_pooled_buffer() {
+        let pool = Arc::new(BatchTensorPool::<f32>::new(&[(4, 512)], 1));
+        assert_eq!(pool.available(), 1);
+
+        let tensor = pool.acquire(2, 512);
+        assert_eq!(tensor.len(), 1024);
+        assert_eq!(pool.available(), 0);
+
+        drop(tensor);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn test_acquire_zeroes_buffer() {
+        let pool = Arc::new(BatchTensorPool::<f32>::new(&[(1, 4)], 1));
+        {
+            let mut tensor = pool.acquire(1, 4);
+            tensor.fill(7.0);
+        }
+
+        let tensor = pool.acquire(1, 4);
+        assert!(tensor.iter().all(|&v| v == 0.0));
+    }
+
+    #[test]
+    fn test_acquire_allocates_when_pool_empty() {
+        let pool = Arc::new(BatchTensorPool::<f32>::new(&[], 0));
+        let tensor = pool.acquire(8, 512);
+        assert_eq!(tensor.len(), 8 * 512);
+    }
+
+    #[test]
+    fn test_buffers_shared_within_size_class() {
+        let pool = BatchTensorPool::<u32>::new(&[], 0);
+        let buffer = pool.take(1000);
+        assert_eq!(buffer.capacity(), 1024);
+        pool.recycle(buffer);
+
+        assert_eq!(pool.take(700).capacity(), 1024);
+        assert_eq!(pool.available(), 0);
+    }
+
+    #[test]
+    fn test_recycle_respects_bucket_and_byte_limits() {
+        let pool = BatchTensorPool::<u32>::new(&[], 0).with_max_bytes(9 * 1024);
+        for _ in 0..MAX_BUFFERS_PER_SIZE + 1 {
+            pool.recycle(Vec::with_capacity(256));
+        }
+        assert_eq!(pool.available(), MAX_BUFFERS_PER_SIZE);
+
+        pool.recycle(Vec::with_capacity(1024));
+        pool.recycle(Vec::with_capacity(1024));
+        assert_eq!(pool.available(), MAX_BUFFERS_PER_SIZE + 1);
+        assert_eq!(pool.pooled_bytes(), 8 * 1024);
+    }
+
+    #[test]
+    fn test_with_max_bytes_trims_preallocated_buffers() {
+        let pool = BatchTensorPool::<f32>::new(&[(1, 512), (16, 512)], 2).with_max_bytes(4096);
+        assert_eq!(pool.available(), 2);
+        assert_eq!(pool.pooled_bytes(), 2 * 512 * 4);
+    }
+}
@@ -6,7 +6,9 @@ use rayon::prelude::*;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
+use tokio::sync::mpsc;
 
+use crate::error::{MinervaError, MinervaResult};
 use crate::inference::batch::{
     BatchStats, DetokenizeBatchRequest, DetokenizeBatchResponse, InferenceBatchRequest,
     InferenceBatchResponse, TokenizeBatchRequest, TokenizeBatchResponse,
@@ -276,6 +278,120 @@ impl Default for ParallelBatchInferenceEngine {
     }
 }
 
+/// Text -> token ids (CPU)
+pub type TokenizeFn = Arc<dyn Fn(&str) -> Vec<u32> + Send + Sync>;
+/// Token ids -> logits (GPU)
+pub type InferFn = Arc<dyn Fn(&[u32]) -> Vec<f32> + Send + Sync>;
+/// Logits -> response text
+pub type SampleFn = Arc<dyn Fn(&[f32]) -> String + Send + Sync>;
+
+/// CPU stage: tokenizes requests on the blocking pool
+pub struct TokenizationStage {
+    tokenize: TokenizeFn,
+}
+
+impl TokenizationStage {
+    pub fn new(tokenize: TokenizeFn) -> Self {
+        Self { tokenize }
+    }
+
+    /// Forward `(request_id, token_ids)` pairs; `send` waits while inference is behind
+    ///
+    /// A tokenizer panic is forwarded as that request's error.
+    pub async fn run(
+        self,
+        requests: Vec<(String, String)>,
+        tx: mpsc::Sender<(String, MinervaResult<Vec<u32>>)>,
+    ) {
+        for (id, text) in requests {
+            let tokenize = Arc::clone(&self.tokenize);
+            let tokens = tokio::task::spawn_blocking(move || tokenize(&text))
+                .await
+                .map_err(|e| stage_failed("Tokenization", &id, e));
+            if tx.send((id, tokens)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// GPU stage: turns token ids into logits
+pub struct InferenceStage {
+    infer: InferFn,
+}
+
+impl InferenceStage {
+    pub fn new(infer: InferFn) -> Self {
+        Self { infer }
+    }
+
+    /// Errors from tokenization pass through; an inference panic becomes
+    /// that request's error.
+    pub async fn run(
+        self,
+        mut rx: mpsc::Receiver<(String, MinervaResult<Vec<u32>>)>,
+        tx: mpsc::Sender<(String, MinervaResult<Vec<f32>>)>,
+    ) {
+        while let Some((id, tokens)) = rx.recv().await {
+            let logits = match tokens {
+                Ok(tokens) => {
+                    let infer = Arc::clone(&self.infer);
+                    tokio::task::spawn_blocking(move || infer(&tokens))
+                        .await
+                        .map_err(|e| stage_failed("Inference", &id, e))
+                }
+                Err(e) => Err(e),
+            };
+            if tx.send((id, logits)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Log a panicked stage task and turn it into the request's error
+fn stage_failed(stage: &str, id: &str, err: tokio::task::JoinError) -> MinervaError {
+    tracing::error!("{} task for request {} failed: {}", stage, id, err);
+    MinervaError::InferenceError(format!("{} failed for request {}: {}", stage, id, err))
+}
+
+/// Overlaps tokenization of request N+1 with inference of request N.
+///
+/// Stages are connected by bounded channels, so a slow GPU stage stalls
+/// tokenization once `channel_capacity` tokenized requests are waiting.
+pub struct OverlappedPipeline {
+    pub tokenization: TokenizationStage,
+    pub inference: InferenceStage,
+    pub sample: SampleFn,
+    pub channel_capacity: usize,
+}
+
+impl OverlappedPipeline {
+    /// Run requests through tokenize -> infer -> sample, returning `(request_id, response)`
+    ///
+    /// Every request gets an entry; one whose stage task panicked carries the error.
+    pub async fn process(
+        self,
+        requests: Vec<(String, String)>,
+    ) -> Vec<(String, MinervaResult<String>)> {
+        let capacity = self.channel_capacity.max(1);
+        let (token_tx, token_rx) = mpsc::channel(capacity);
+        let (logits_tx, mut logits_rx) = mpsc::channel(capacity);
+
+        let tokenizer = tokio::spawn(self.tokenization.run(requests, token_tx));
+        let inference = tokio::spawn(self.inference.run(token_rx, logits_tx));
+
+        let mut responses = Vec::new();
+        while let Some((id, logits)) = logits_rx.recv().await {
+            responses.push((id, logits.map(|logits| (self.sample)(&logits))));
+        }
+
+        let _ = tokenizer.await;
+        let _ = inference.await;
+        responses
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,4 +487,96 @@ mod tests {
         let engine = ParallelBatchInferenceEngine::with_threads(4);
         assert_eq!(engine.num_threads(), 4);
     }
+
+    fn pipeline(infer: InferFn, channel_capacity: usize) -> OverlappedPipeline {
+        OverlappedPipeline {
+            tokenization: TokenizationStage::new(Arc::new(|text: &str| {
+                text.bytes().map(u32::from).collect()
+            })),
+            inference: InferenceStage::new(infer),
+            sample: Arc::new(|logits: &[f32]| format!("{}", logits.len())),
+            channel_capacity,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlapped_pipeline_produces_responses_in_order() {
+        let infer: InferFn = Arc::new(|tokens: &[u32]| vec![0.0; tokens.len()]);
+        let requests = vec![
+            ("a".to_string(), "hi".to_string()),
+            ("b".to_string(), "hello".to_string()),
+        ];
+
+        let responses: Vec<_> = pipeline(infer, 2)
+            .process(requests)
+            .await
+            .into_iter()
+            .map(|(id, response)| (id, response.unwrap()))
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                ("a".to_string(), "2".to_string()),
+                ("b".to_string(), "5".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overlapped_pipeline_reports_panicked_stage() {
+        let infer: InferFn = Arc::new(|tokens: &[u32]| {
+            assert!(tokens.len() < 3, "prompt too long");
+            vec![0.0; tokens.len()]
+        });
+        let requests = vec![
+            ("a".to_string(), "hello".to_string()),
+            ("b".to_string(), "hi".to_string()),
+        ];
+
+        let responses = pipeline(infer, 2).process(requests).await;
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].0, "a");
+        assert!(responses[0].1.is_err());
+        assert_eq!(responses[1].1.as_deref().unwrap(), "2");
+    }
+
+    #[tokio::test]
+    async fn test_overlapped_pipeline_backpressure_bounds_tokenization() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let tokenized = Arc::new(AtomicUsize::new(0));
+        let inferred = Arc::new(AtomicUsize::new(0));
+        let max_ahead = Arc::new(AtomicUsize::new(0));
+
+        let tokenized_count = Arc::clone(&tokenized);
+        let tokenize: TokenizeFn = Arc::new(move |text: &str| {
+            tokenized_count.fetch_add(1, Ordering::SeqCst);
+            text.bytes().map(u32::from).collect()
+        });
+
+        let (tokenized_seen, inferred_count, ahead) = (
+            Arc::clone(&tokenized),
+            Arc::clone(&inferred),
+            Arc::clone(&max_ahead),
+        );
+        let infer: InferFn = Arc::new(move |tokens: &[u32]| {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            let done = inferred_count.fetch_add(1, Ordering::SeqCst);
+            ahead.fetch_max(
+                tokenized_seen.load(Ordering::SeqCst) - done,
+                Ordering::SeqCst,
+            );
+            vec![0.0; tokens.len()]
+        });
+
+        let mut stages = pipeline(infer, 1);
+        stages.tokenization = TokenizationStage::new(tokenize);
+
+        let requests: Vec<_> = (0..10).map(|i| (i.to_string(), "x".to_string())).collect();
+        let responses = stages.process(requests).await;
+
+        assert_eq!(responses.len(), 10);
+        // In-flight + one queued + one waiting to send
+        assert!(max_ahead.load(Ordering::SeqCst) <= 3);
+    }
 }
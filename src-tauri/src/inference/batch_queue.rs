@@ -0,0 +1,292 @@
+/// Request queue for continuous batching
+///
+/// Incoming requests accumulate here until either `max_batch_size` is reached
+/// or `batch_wait_ms` has elapsed since the first request arrived. The batch
+/// is then padded into a single tensor and handed to the model in one call;
+/// how the rows are evaluated is up to its `BatchForward`.
+use crate::error::MinervaResult;
+use crate::inference::api::InferenceRequest;
+use crate::inference::inference_backend_trait::InferenceBackend;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, oneshot};
+
+/// Padding token used to fill short sequences in a batch
+pub const PAD_TOKEN_ID: u32 = 0;
+
+/// Queue limits
+#[derive(Debug, Clone, Copy)]
+pub struct BatchQueueConfig {
+    pub max_batch_size: usize,
+    pub batch_wait_ms: u64,
+}
+
+impl Default for BatchQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 8,
+            batch_wait_ms: 10,
+        }
+    }
+}
+
+/// Request waiting for a batch slot, with the channel its logits are sent on
+pub struct QueuedRequest {
+    pub id: String,
+    pub request: InferenceRequest,
+    pub tokens: Vec<u32>,
+    pub responder: oneshot::Sender<MinervaResult<Vec<f32>>>,
+}
+
+/// Sequences padded to a common length, stored row-major
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaddedBatch {
+    pub tokens: Vec<u32>,
+    pub seq_len: usize,
+    pub lengths: Vec<usize>,
+}
+
+impl PaddedBatch {
+    pub fn from_sequences(sequences: &[&[u32]]) -> Self {
+        Self::from_sequences_in(sequences, Vec::new())
+    }
+
+    /// Pad `sequences` into `buffer`, reusing its allocation
+    pub fn from_sequences_in(sequences: &[&[u32]], mut buffer: Vec<u32>) -> Self {
+        let seq_len = sequences.iter().map(|s| s.len()).max().unwrap_or(0);
+        buffer.clear();
+        buffer.reserve(seq_len * sequences.len());
+        let mut tokens = buffer;
+
+        for sequence in sequences {
+            tokens.extend_from_slice(sequence);
+            tokens.resize(tokens.len() + seq_len - sequence.len(), PAD_TOKEN_ID);
+        }
+
+        Self {
+            tokens,
+            seq_len,
+            lengths: sequences.iter().map(|s| s.len()).collect(),
+        }
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Tokens of the sequence at `position`, without its padding
+    pub fn row(&self, position: usize) -> &[u32] {
+        let start = position * self.seq_len;
+        &self.tokens[start..start + self.lengths[position]]
+    }
+}
+
+/// Model that evaluates a whole padded batch per call
+pub trait BatchForward: Send + Sync {
+    /// Returns one row of logits per batch position
+    fn forward(&self, batch: &PaddedBatch) -> MinervaResult<Vec<Vec<f32>>>;
+}
+
+/// Runs batches on a loaded inference backend
+///
+/// Backends decode a single sequence per call, so each row is evaluated in
+/// turn and its padding is never seen by the model.
+pub struct BackendForward {
+    backend: Arc<dyn InferenceBackend>,
+}
+
+impl BackendForward {
+    pub fn new(backend: Arc<dyn InferenceBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+impl BatchForward for BackendForward {
+    fn forward(&self, batch: &PaddedBatch) -> MinervaResult<Vec<Vec<f32>>> {
+        (0..batch.batch_size())
+            .map(|position| self.backend.next_token_logits(batch.row(position)))
+            .collect()
+    }
+}
+
+/// Accumulates concurrent requests into batches
+pub struct RequestQueue {
+    config: BatchQueueConfig,
+    pending: Mutex<VecDeque<QueuedRequest>>,
+    in_flight: Mutex<Vec<String>>,
+    signal: Notify,
+}
+
+impl RequestQueue {
+    pub fn new(config: BatchQueueConfig) -> Self {
+        Self {
+            config,
+            pending: Mutex::new(VecDeque::new()),
+            in_flight: Mutex::new(Vec::new()),
+            signal: Notify::new(),
+        }
+    }
+
+    pub fn config(&self) -> BatchQueueConfig {
+        self.config
+    }
+
+    /// Enqueue a request and get a receiver for its logits
+    pub fn submit(
+        &self,
+        request: InferenceRequest,
+        tokens: Vec<u32>,
+    ) -> oneshot::Receiver<MinervaResult<Vec<f32>>> {
+        let (responder, receiver) = oneshot::channel();
+        self.pending.lock().push_back(QueuedRequest {
+            id: uuid::Uuid::new_v4().to_string(),
+            request,
+            tokens,
+            responder,
+        });
+        self.signal.notify_one();
+        receiver
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.lock().is_empty()
+    }
+
+    /// Wait until at least one request is queued
+    pub async fn wait_for_request(&self) {
+        while self.is_empty() {
+            self.signal.notified().await;
+        }
+    }
+
+    /// Wait for more requests until the batch is full or the window closes
+    pub async fn fill_window(&self) {
+        let deadline = tokio::time::sleep(Duration::from_millis(self.config.batch_wait_ms));
+        tokio::pin!(deadline);
+
+        while self.len() < self.config.max_batch_size {
+            tokio::select! {
+                _ = &mut deadline => break,
+                _ = self.signal.notified() => {}
+            }
+        }
+    }
+
+    /// Remove up to `max_batch_size` requests from the front of the queue,
+    /// recording their ids as in flight by batch position
+    pub fn take_batch(&self) -> Vec<QueuedRequest> {
+        let mut pending = self.pending.lock();
+        let count = pending.len().min(self.config.max_batch_size);
+        let batch: Vec<QueuedRequest> = pending.drain(..count).collect();
+        *self.in_flight.lock() = batch.iter().map(|queued| queued.id.clone()).collect();
+        batch
+    }
+
+    /// Request ids in the batch currently running, indexed by batch position
+    pub fn in_flight(&self) -> Vec<String> {
+        self.in_flight.lock().clone()
+    }
+
+    pub fn finish_batch(&self) {
+        self.in_flight.lock().clear();
+    }
+}
+
+impl Default for RequestQueue {
+    fn default() -> Self {
+        Self::new(BatchQueueConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> InferenceRequest {
+        InferenceRequest {
+            model: "test".to_string(),
+            prompt: "hi".to_string(),
+            max_tokens: None,
+            temperature: None,
+            top_k: None,
+            top_p: None,
+            seed: None,
+        }
+    }
+
+    #[test]
+    fn test_padded_batch_pads_with_zero() {
+        let sequences: [&[u32]; 2] = [&[5, 6, 7], &[8]];
+        let batch = PaddedBatch::from_sequences(&sequences);
+        assert_eq!(batch.seq_len, 3);
+        assert_eq!(batch.tokens, vec![5, 6, 7, 8, 0, 0]);
+        assert_eq!(batch.lengths, vec![3, 1]);
+        assert_eq!(batch.batch_size(), 2);
+    }
+
+    #[test]
+    fn test_padded_batch_reuses_buffer() {
+        let sequences: [&[u32]; 2] = [&[5, 6], &[8]];
+        let buffer = Vec::with_capacity(256);
+        let batch = PaddedBatch::from_sequences_in(&sequences, buffer);
+        assert_eq!(batch.tokens, vec![5, 6, 8, 0]);
+        assert_eq!(batch.tokens.capacity(), 256);
+    }
+
+    #[test]
+    fn test_backend_forward_strips_padding() {
+        use crate::inference::mock_backend::MockBackend;
+
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = MockBackend::new();
+        backend.load_model(model.path(), 512).unwrap();
+        let forward = BackendForward::new(Arc::new(backend));
+
+        // The mock predicts the last token it saw, which is 0 if padding leaks in
+        let sequences: [&[u32]; 2] = [&[5, 6, 7], &[8]];
+        let rows = forward
+            .forward(&PaddedBatch::from_sequences(&sequences))
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][7], 1.0);
+        assert_eq!(rows[1][8], 1.0);
+        assert_eq!(rows[1][0], 0.0);
+    }
+
+    #[test]
+    fn test_take_batch_respects_max_size() {
+        let queue = RequestQueue::new(BatchQueueConfig {
+            max_batch_size: 2,
+            batch_wait_ms: 1,
+        });
+        for _ in 0..3 {
+            let _ = queue.submit(request(), vec![1]);
+        }
+
+        assert_eq!(queue.take_batch().len(), 2);
+        assert_eq!(queue.in_flight().len(), 2);
+        assert_eq!(queue.len(), 1);
+
+        queue.finish_batch();
+        assert!(queue.in_flight().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fill_window_returns_when_full() {
+        let queue = RequestQueue::new(BatchQueueConfig {
+            max_batch_size: 1,
+            batch_wait_ms: 60_000,
+        });
+        let _ = queue.submit(request(), vec![1]);
+
+        queue.wait_for_request().await;
+        queue.fill_window().await;
+        assert_eq!(queue.take_batch().len(), 1);
+    }
+}
@@ -5,9 +5,19 @@
 /// - GPU vs CPU performance comparison
 /// - Memory usage during inference
 /// - End-to-end latency
+/// - Time to first token across prompt lengths
+use super::inference_backend_trait::{GenerationParams, InferenceBackend};
 use crate::error::MinervaResult;
 use std::time::{Duration, Instant};
 
+/// Words cycled to build synthetic prompts, roughly one token each
+const PROMPT_WORDS: [&str; 8] = [
+    "the", "model", "reads", "this", "prompt", "before", "it", "answers",
+];
+
+/// Width of the longest bar in `ttft_chart`
+const CHART_WIDTH: usize = 50;
+
 /// Input for creating performance metrics
 #[derive(Debug, Clone)]
 pub struct PerformanceMetricsInput {
@@ -209,9 +219,231 @@ impl Default for PerformanceAccumulator {
     }
 }
 
+/// Time to first token for one prompt length
+#[derive(Debug, Clone, PartialEq)]
+pub struct TTFTResult {
+    pub prompt_tokens: usize,
+    pub ttft_ms_p50: f64,
+    pub ttft_ms_p95: f64,
+    /// Decode speed after the first token, averaged over iterations
+    pub tokens_per_sec: f64,
+}
+
+/// Measures time to first token for synthetic prompts of several lengths
+///
+/// Each length is generated `iterations` times through
+/// `InferenceBackend::generate_stream`. For backends that do not stream,
+/// the first token arrives with the full completion, so TTFT equals the
+/// total latency.
+#[derive(Debug, Clone, Copy)]
+pub struct TTFTBenchmark {
+    pub iterations: usize,
+    pub params: GenerationParams,
+}
+
+impl Default for TTFTBenchmark {
+    fn default() -> Self {
+        Self {
+            iterations: 5,
+            params: GenerationParams {
+                max_tokens: 32,
+                temperature: 0.0,
+                top_p: 1.0,
+            },
+        }
+    }
+}
+
+impl TTFTBenchmark {
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// One result per entry of `prompt_lengths`, in order
+    pub fn run(
+        &self,
+        backend: &dyn InferenceBackend,
+        prompt_lengths: &[usize],
+    ) -> MinervaResult<Vec<TTFTResult>> {
+        prompt_lengths
+            .iter()
+            .map(|&length| self.measure(backend, length))
+            .collect()
+    }
+
+    fn measure(&self, backend: &dyn InferenceBackend, length: usize) -> MinervaResult<TTFTResult> {
+        let prompt = synthetic_prompt(length);
+        let mut ttfts = Vec::with_capacity(self.iterations);
+        let mut rate_sum = 0.0;
+        for _ in 0..self.iterations {
+            let start = Instant::now();
+            let mut first: Option<Duration> = None;
+            let mut tokens = 0usize;
+            backend.generate_stream(&prompt, self.params, &mut |_| {
+                first.get_or_insert_with(|| start.elapsed());
+                tokens += 1;
+            })?;
+            let total = start.elapsed();
+            let first = first.unwrap_or(total);
+
+            let decode_secs = (total - first).as_secs_f64();
+            if tokens > 1 && decode_secs > 0.0 {
+                rate_sum += (tokens - 1) as f64 / decode_secs;
+            }
+            ttfts.push(first.as_secs_f64() * 1000.0);
+        }
+        ttfts.sort_by(f64::total_cmp);
+        Ok(TTFTResult {
+            prompt_tokens: length,
+            ttft_ms_p50: percentile(&ttfts, 0.50),
+            ttft_ms_p95: percentile(&ttfts, 0.95),
+            tokens_per_sec: rate_sum / self.iterations as f64,
+        })
+    }
+}
+
+/// Horizontal bar per prompt length, scaled to the slowest p50
+pub fn ttft_chart(results: &[TTFTResult]) -> String {
+    let slowest = results.iter().map(|r| r.ttft_ms_p50).fold(0.0, f64::max);
+    let label_width = results
+        .iter()
+        .map(|r| r.prompt_tokens.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let mut chart = String::from("TTFT p50 by prompt length (tokens)\n");
+    for result in results {
+        let bar = if slowest > 0.0 {
+            (result.ttft_ms_p50 / slowest * CHART_WIDTH as f64).round() as usize
+        } else {
+            0
+        };
+        chart.push_str(&format!(
+            "{:>width$} | {} {:.1}ms (p95 {:.1}ms)\n",
+            result.prompt_tokens,
+            "#".repeat(bar),
+            result.ttft_ms_p50,
+            result.ttft_ms_p95,
+            width = label_width
+        ));
+    }
+    chart
+}
+
+/// Print `ttft_chart` to stdout
+pub fn print_ttft_chart(results: &[TTFTResult]) {
+    print!("{}", ttft_chart(results));
+}
+
+fn synthetic_prompt(tokens: usize) -> String {
+    PROMPT_WORDS
+        .iter()
+        .cycle()
+        .take(tokens)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], quantile: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inference::mock_backend::MockBackend;
+
+    fn loaded_mock(streaming: bool) -> MockBackend {
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = MockBackend::new().with_streaming(streaming);
+        backend.load_model(model.path(), 2048).unwrap();
+        backend
+    }
+
+    #[test]
+    fn test_ttft_per_prompt_length() {
+        let backend = loaded_mock(true);
+        let results = TTFTBenchmark::default()
+            .with_iterations(2)
+            .run(&backend, &[8, 64])
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].prompt_tokens, 8);
+        assert_eq!(results[1].prompt_tokens, 64);
+        for result in &results {
+            // The mock sleeps 50ms before its first token
+            assert!(result.ttft_ms_p50 >= 50.0, "{:?}", result);
+            assert!(result.ttft_ms_p95 >= result.ttft_ms_p50);
+            assert!(result.tokens_per_sec > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_streaming_mock_emits_first_token_before_the_last() {
+        let backend = loaded_mock(true);
+        let start = Instant::now();
+        let mut arrivals = Vec::new();
+        backend
+            .generate_stream("hello", TTFTBenchmark::default().params, &mut |_| {
+                arrivals.push(start.elapsed())
+            })
+            .unwrap();
+
+        assert!(arrivals.len() > 1);
+        assert!(arrivals[arrivals.len() - 1] > arrivals[0]);
+    }
+
+    #[test]
+    fn test_ttft_without_streaming_has_no_decode_rate() {
+        let results = TTFTBenchmark::default()
+            .with_iterations(1)
+            .run(&loaded_mock(false), &[16])
+            .unwrap();
+        assert_eq!(results[0].tokens_per_sec, 0.0);
+    }
+
+    #[test]
+    fn test_ttft_requires_loaded_model() {
+        let backend = MockBackend::new();
+        assert!(TTFTBenchmark::default().run(&backend, &[8]).is_err());
+    }
+
+    #[test]
+    fn test_synthetic_prompt_length() {
+        assert_eq!(synthetic_prompt(20).split_whitespace().count(), 20);
+        assert!(synthetic_prompt(0).is_empty());
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(percentile(&values, 0.50), 10.0);
+        assert_eq!(percentile(&values, 0.95), 19.0);
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_ttft_chart_scales_bars() {
+        let result = |prompt_tokens, ttft_ms_p50| TTFTResult {
+            prompt_tokens,
+            ttft_ms_p50,
+            ttft_ms_p95: ttft_ms_p50,
+            tokens_per_sec: 0.0,
+        };
+        let chart = ttft_chart(&[result(128, 25.0), result(1024, 100.0)]);
+        let lines: Vec<&str> = chart.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with(" 128 | ") && lines[1].contains(&"#".repeat(13)));
+        assert!(lines[2].starts_with("1024 | ") && lines[2].contains(&"#".repeat(50)));
+    }
 
     #[test]
     fn test_performance_metrics() {
@@ -1,5 +1,7 @@
-use crate::error::MinervaResult;
+use super::kv_cache::KVCache;
+use crate::error::{MinervaError, MinervaResult};
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::time::{Duration, Instant};
 
 /// Memory system information
@@ -76,6 +78,13 @@ pub struct OptimizationStats {
     pub avg_cache_size_mb: u64,
 }
 
+/// Location of the newest token in the KV cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePosition {
+    pub layer: usize,
+    pub pos: usize,
+}
+
 /// Cache optimizer for dynamic sizing
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -203,6 +212,73 @@ impl CacheOptimizer {
     }
 }
 
+impl CacheOptimizer {
+    /// Attention output for only the newest position
+    ///
+    /// Scores `new_query` against the cached keys at `0..=pos` and returns the
+    /// softmax-weighted sum of the cached values, shape (num_heads * head_dim).
+    /// This is the last row of the causal attention matrix, so each generation
+    /// step costs O(pos) instead of recomputing the full (seq_len, seq_len) scores.
+    pub fn incremental_attention(
+        new_query: &[f32],
+        kv_cache: &KVCache,
+        at: CachePosition,
+    ) -> MinervaResult<Vec<f32>> {
+        let head_dim = kv_cache.head_dim();
+        if new_query.len() != kv_cache.num_heads() * head_dim {
+            return Err(MinervaError::InferenceError(format!(
+                "Query shape mismatch: expected {}, got {}",
+                kv_cache.num_heads() * head_dim,
+                new_query.len()
+            )));
+        }
+
+        let cached = (0..=at.pos)
+            .map(|pos| kv_cache.get(at.layer, pos))
+            .collect::<MinervaResult<Vec<_>>>()?;
+
+        let mut output = vec![0.0; new_query.len()];
+        for (head, out) in output.chunks_mut(head_dim).enumerate() {
+            let range = head * head_dim..(head + 1) * head_dim;
+            out.copy_from_slice(&attend_head(new_query, &cached, range));
+        }
+        Ok(output)
+    }
+}
+
+/// Single-query attention for one head over cached (key, value) rows
+fn attend_head(
+    new_query: &[f32],
+    cached: &[(Vec<f32>, Vec<f32>)],
+    range: Range<usize>,
+) -> Vec<f32> {
+    let query = &new_query[range.clone()];
+    let scale = 1.0 / (query.len() as f32).sqrt();
+
+    let scores: Vec<f32> = cached
+        .iter()
+        .map(|(key, _)| {
+            let dot: f32 = query
+                .iter()
+                .zip(&key[range.clone()])
+                .map(|(q, k)| q * k)
+                .sum();
+            dot * scale
+        })
+        .collect();
+    let max_score = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let weights: Vec<f32> = scores.iter().map(|s| (s - max_score).exp()).collect();
+    let total: f32 = weights.iter().sum();
+
+    let mut out = vec![0.0; query.len()];
+    for ((_, value), weight) in cached.iter().zip(&weights) {
+        for (o, v) in out.iter_mut().zip(&value[range.clone()]) {
+            *o += weight / total * v;
+        }
+    }
+    out
+}
+
 impl Default for CacheOptimizer {
     fn default() -> Self {
         Self::new()
@@ -212,6 +288,8 @@ impl Default for CacheOptimizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inference::kv_cache::{KVCacheConfig, KVStoreParams};
+    use crate::inference::multi_head_attention::{MultiHeadConfig, multi_head_attention};
 
     #[test]
     fn test_system_memory_available_percent() {
@@ -365,6 +443,61 @@ mod tests {
         assert_eq!(mem.available_percent(), 0.0);
     }
 
+    fn filled_cache(rows: &[Vec<f32>]) -> KVCache {
+        let mut cache = KVCache::new(KVCacheConfig {
+            num_layers: 1,
+            max_seq_len: rows.len(),
+            num_heads: 2,
+            head_dim: 2,
+        });
+        for (pos, row) in rows.iter().enumerate() {
+            let params = KVStoreParams::builder(row.clone(), row.clone())
+                .pos(pos)
+                .build();
+            cache.store(params).unwrap();
+        }
+        cache
+    }
+
+    #[test]
+    fn test_incremental_attention_matches_last_causal_row() {
+        let rows = vec![
+            vec![0.1, 0.4, -0.3, 0.2],
+            vec![0.5, -0.2, 0.7, 0.1],
+            vec![-0.6, 0.3, 0.2, 0.9],
+        ];
+        let cache = filled_cache(&rows);
+        let config = MultiHeadConfig {
+            seq_len: 3,
+            hidden_size: 4,
+            num_heads: 2,
+            causal: true,
+        };
+        let full = multi_head_attention(&rows.concat(), &config).unwrap();
+
+        let at = CachePosition { layer: 0, pos: 2 };
+        let row = CacheOptimizer::incremental_attention(&rows[2], &cache, at).unwrap();
+        for (a, b) in row.iter().zip(&full[8..]) {
+            assert!((a - b).abs() < 1e-5, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_incremental_attention_first_position_returns_value() {
+        let rows = vec![vec![0.3, -0.1, 0.8, 0.5]];
+        let cache = filled_cache(&rows);
+        let at = CachePosition { layer: 0, pos: 0 };
+        let row = CacheOptimizer::incremental_attention(&rows[0], &cache, at).unwrap();
+        assert_eq!(row, rows[0]);
+    }
+
+    #[test]
+    fn test_incremental_attention_rejects_bad_query() {
+        let cache = filled_cache(&[vec![0.0; 4]]);
+        let at = CachePosition { layer: 0, pos: 0 };
+        assert!(CacheOptimizer::incremental_attention(&[0.0; 3], &cache, at).is_err());
+    }
+
     #[test]
     fn test_optimization_stats_default() {
         let stats = OptimizationStats::default();
@@ -1,8 +1,13 @@
 use super::InferenceEngine;
+use super::context_snapshot::SessionContext;
+use super::kv_cache::{KVCache, KVCacheConfig};
+use super::kv_snapshot_cache::{KVCacheSnapshot, KVSnapshotCache};
 use super::model_cache::{CacheStats, EvictionPolicy, ModelCache};
+use super::session_store::SessionStore;
 use crate::error::{MinervaError, MinervaResult};
+use crate::models::ChatMessage;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 /// Usage statistics for a model context
@@ -57,6 +62,8 @@ pub struct ContextManager {
     max_models_loaded: usize,
     cache: ModelCache,
     memory_estimated_mb: u64,
+    sessions: SessionStore,
+    kv_snapshots: Option<KVSnapshotCache>,
 }
 
 impl ContextManager {
@@ -67,6 +74,8 @@ impl ContextManager {
             max_models_loaded,
             cache: ModelCache::new(max_models_loaded, EvictionPolicy::Lru),
             memory_estimated_mb: 0,
+            sessions: SessionStore::default(),
+            kv_snapshots: None,
         }
     }
 
@@ -78,6 +87,8 @@ impl ContextManager {
             max_models_loaded,
             cache: ModelCache::new(max_models_loaded, policy),
             memory_estimated_mb: 0,
+            sessions: SessionStore::default(),
+            kv_snapshots: None,
         }
     }
 
@@ -239,6 +250,134 @@ impl ContextManager {
         self.cache.stats().hit_rate()
     }
 
+    /// Bound the number of live sessions, evicting LRU sessions beyond it
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> Self {
+        self.sessions = SessionStore::new(max_sessions);
+        self
+    }
+
+    /// Keep session base KV caches in `cache` so they outlive the process
+    pub fn with_kv_snapshots(mut self, cache: KVSnapshotCache) -> Self {
+        self.kv_snapshots = Some(cache);
+        self
+    }
+
+    /// Set the prefix state (e.g. system prompt) new sessions of a model copy
+    ///
+    /// With a snapshot cache the base's KV cache is also written to disk in
+    /// the background.
+    pub fn set_session_base(&mut self, base: SessionContext) {
+        if let Some(snapshots) = &mut self.kv_snapshots {
+            let snapshot = KVCacheSnapshot {
+                tokens: Vec::new(),
+                kv_cache: base.kv_cache.clone(),
+            };
+            snapshots.put(&base_snapshot_key(&base.model_id), snapshot);
+        }
+        self.sessions.set_base(base);
+    }
+
+    /// Start an isolated session from a snapshot of the model's base state
+    ///
+    /// A base set in an earlier run is read back from the snapshot cache;
+    /// only its KV cache is kept there, so it starts with no messages.
+    pub fn create_session(
+        &mut self,
+        session_id: &str,
+        model_id: &str,
+    ) -> MinervaResult<&mut SessionContext> {
+        if !self.sessions.has_base(model_id)
+            && let Some(snapshot) = self
+                .kv_snapshots
+                .as_mut()
+                .and_then(|snapshots| snapshots.get(&base_snapshot_key(model_id)))
+        {
+            self.sessions.set_base(SessionContext {
+                model_id: model_id.to_string(),
+                kv_cache: snapshot.kv_cache,
+                messages: Vec::new(),
+            });
+        }
+        self.sessions.create(session_id, model_id)
+    }
+
+    /// Register or replace the conversation state for a session
+    pub fn set_session(&mut self, session_id: &str, session: SessionContext) {
+        self.sessions.insert(session_id, session);
+    }
+
+    /// Replace a session's message history, starting the session with an
+    /// empty KV cache if the backend has not registered one
+    pub fn record_messages(
+        &mut self,
+        session_id: &str,
+        model_id: &str,
+        messages: Vec<ChatMessage>,
+    ) {
+        if let Some(session) = self.sessions.get_mut(session_id)
+            && session.model_id == model_id
+        {
+            session.messages = messages;
+            return;
+        }
+        self.set_session(
+            session_id,
+            SessionContext {
+                model_id: model_id.to_string(),
+                kv_cache: KVCache::new(KVCacheConfig {
+                    num_layers: 0,
+                    max_seq_len: 0,
+                    num_heads: 0,
+                    head_dim: 0,
+                }),
+                messages,
+            },
+        );
+    }
+
+    /// Get the conversation state for a session
+    pub fn session(&self, session_id: &str) -> Option<&SessionContext> {
+        self.sessions.get(session_id)
+    }
+
+    /// Get a session's state for the next turn, marking it recently used
+    pub fn session_mut(&mut self, session_id: &str) -> Option<&mut SessionContext> {
+        self.sessions.get_mut(session_id)
+    }
+
+    /// End a session and release its KV cache
+    pub fn end_session(&mut self, session_id: &str) -> Option<SessionContext> {
+        self.sessions.remove(session_id)
+    }
+
+    /// Number of live sessions
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Write a session's KV cache and message history to disk
+    pub fn save_context(&self, session_id: &str, path: &Path) -> MinervaResult<()> {
+        let session = self.sessions.get(session_id).ok_or_else(|| {
+            MinervaError::InvalidRequest(format!("Unknown session: {}", session_id))
+        })?;
+        session.write_to(path)?;
+        tracing::info!("Session {} saved to {}", session_id, path.display());
+        Ok(())
+    }
+
+    /// Load a session previously written by [`ContextManager::save_context`]
+    pub fn restore_context(&mut self, session_id: &str, path: &Path) -> MinervaResult<()> {
+        let session = SessionContext::read_from(path)?;
+        tracing::info!(
+            "Session {} restored from {} (model {})",
+            session_id,
+            path.display(),
+            session.model_id
+        );
+        self.sessions.insert(session_id, session);
+        Ok(())
+    }
+
     /// Preload a model (load without marking as used)
     #[allow(dead_code)]
     pub fn preload_model(&mut self, id: &str, path: PathBuf) -> MinervaResult<()> {
@@ -264,6 +403,11 @@ impl ContextManager {
     }
 }
 
+/// Snapshot cache key of a model's session base
+fn base_snapshot_key(model_id: &str) -> String {
+    format!("session-base:{}", model_id)
+}
+
 impl Default for ContextManager {
     fn default() -> Self {
         Self::new(3) // Default: max 3 models loaded
@@ -384,6 +528,134 @@ mod tests {
         assert_eq!(manager.loaded_count(), 0);
     }
 
+    #[test]
+    fn test_save_and_restore_context() {
+        use crate::inference::kv_cache::{KVCache, KVCacheConfig};
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chat.session");
+
+        let mut manager = ContextManager::new(2);
+        manager.set_session(
+            "chat",
+            SessionContext {
+                model_id: "tiny".to_string(),
+                kv_cache: KVCache::new(KVCacheConfig {
+                    num_layers: 1,
+                    max_seq_len: 2,
+                    num_heads: 1,
+                    head_dim: 2,
+                }),
+                messages: Vec::new(),
+            },
+        );
+        manager.save_context("chat", &path).unwrap();
+
+        let mut restored = ContextManager::new(2);
+        restored.restore_context("resumed", &path).unwrap();
+        assert_eq!(restored.session("resumed").unwrap().model_id, "tiny");
+    }
+
+    #[test]
+    fn test_sessions_isolated_and_bounded() {
+        use crate::inference::kv_cache::{KVCache, KVCacheConfig, KVStoreParams};
+        let mut manager = ContextManager::new(2).with_max_sessions(2);
+        manager.set_session_base(SessionContext {
+            model_id: "tiny".to_string(),
+            kv_cache: KVCache::new(KVCacheConfig {
+                num_layers: 1,
+                max_seq_len: 2,
+                num_heads: 1,
+                head_dim: 2,
+            }),
+            messages: Vec::new(),
+        });
+
+        manager
+            .create_session("alice", "tiny")
+            .unwrap()
+            .kv_cache
+            .store(KVStoreParams::builder(vec![1.0, 1.0], vec![1.0, 1.0]).build())
+            .unwrap();
+        manager.create_session("bob", "tiny").unwrap();
+        let bob = manager.session("bob").unwrap();
+        assert_eq!(bob.kv_cache.get(0, 0).unwrap().0, vec![0.0, 0.0]);
+
+        manager.create_session("carol", "tiny").unwrap();
+        assert_eq!(manager.session_count(), 2);
+        assert!(manager.session("alice").is_none());
+    }
+
+    #[test]
+    fn test_session_base_survives_restart_through_snapshot_cache() {
+        use crate::inference::kv_cache::{KVCache, KVCacheConfig, KVStoreParams};
+        let dir = tempfile::tempdir().unwrap();
+        let mut kv_cache = KVCache::new(KVCacheConfig {
+            num_layers: 1,
+            max_seq_len: 2,
+            num_heads: 1,
+            head_dim: 2,
+        });
+        kv_cache
+            .store(KVStoreParams::builder(vec![0.5, 0.5], vec![-0.5, -0.5]).build())
+            .unwrap();
+
+        let snapshots = KVSnapshotCache::open(dir.path(), 4).unwrap();
+        let mut manager = ContextManager::new(2).with_kv_snapshots(snapshots);
+        manager.set_session_base(SessionContext {
+            model_id: "tiny".to_string(),
+            kv_cache,
+            messages: Vec::new(),
+        });
+        drop(manager);
+
+        // The write is in the background; wait for it to land
+        let key_path = KVSnapshotCache::open(dir.path(), 4)
+            .unwrap()
+            .l2()
+            .path(&base_snapshot_key("tiny"));
+        let started = Instant::now();
+        while !key_path.exists() {
+            assert!(started.elapsed().as_secs() < 5, "snapshot never written");
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let snapshots = KVSnapshotCache::open(dir.path(), 4).unwrap();
+        let mut restarted = ContextManager::new(2).with_kv_snapshots(snapshots);
+        let session = restarted.create_session("chat", "tiny").unwrap();
+        assert_eq!(session.kv_cache.get(0, 0).unwrap().0, vec![0.5, 0.5]);
+        assert!(
+            ContextManager::new(2)
+                .create_session("chat", "tiny")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_save_unknown_session() {
+        let manager = ContextManager::new(2);
+        let result = manager.save_context("missing", Path::new("/tmp/missing.session"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_record_messages_makes_session_saveable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chat.session");
+        let message = |content: &str| ChatMessage {
+            role: "user".to_string(),
+            content: content.to_string(),
+        };
+
+        let mut manager = ContextManager::new(2);
+        manager.record_messages("chat", "tiny", vec![message("hi")]);
+        manager.record_messages("chat", "tiny", vec![message("hi"), message("again")]);
+        manager.save_context("chat", &path).unwrap();
+
+        let mut restored = ContextManager::new(2);
+        restored.restore_context("chat", &path).unwrap();
+        assert_eq!(restored.session("chat").unwrap().messages.len(), 2);
+    }
+
     #[test]
     fn test_preload_model_nonexistent() {
         let mut manager = ContextManager::new(2);
@@ -0,0 +1,175 @@
+/// Conversation Context Snapshots
+///
+/// Binary on-disk format used to resume a conversation after a restart:
+///
+/// ```text
+/// magic "MNVC" | version u32 | model_id (u64 len + utf8)
+///   | messages (u64 len + JSON) | KV cache (see KVCache::write_to)
+/// ```
+///
+/// All integers are little-endian.
+use super::kv_cache::KVCache;
+use crate::error::{MinervaError, MinervaResult};
+use crate::models::ChatMessage;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+/// File magic identifying a context snapshot
+pub const SNAPSHOT_MAGIC: &[u8; 4] = b"MNVC";
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// State of one conversation: model, attention cache and message history
+#[derive(Debug, Clone)]
+pub struct SessionContext {
+    pub model_id: String,
+    pub kv_cache: KVCache,
+    pub messages: Vec<ChatMessage>,
+}
+
+impl SessionContext {
+    /// Write this session to `path`, replacing any existing file
+    pub fn write_to(&self, path: &Path) -> MinervaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+        write_section(&mut writer, self.model_id.as_bytes())?;
+        write_section(&mut writer, &serde_json::to_vec(&self.messages)?)?;
+        self.kv_cache.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a session written by [`SessionContext::write_to`]
+    pub fn read_from(path: &Path) -> MinervaResult<Self> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        read_header(&mut reader)?;
+
+        let model_id = String::from_utf8(read_section(&mut reader)?).map_err(|e| {
+            MinervaError::ValidationError(format!("Invalid model id in snapshot: {}", e))
+        })?;
+        let messages = serde_json::from_slice(&read_section(&mut reader)?)?;
+        let remaining = file_len.saturating_sub(reader.stream_position()?);
+        let kv_cache = KVCache::read_from(&mut reader, remaining)?;
+
+        Ok(Self {
+            model_id,
+            kv_cache,
+            messages,
+        })
+    }
+}
+
+fn read_header(reader: &mut impl Read) -> MinervaResult<()> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != SNAPSHOT_MAGIC {
+        return Err(MinervaError::ValidationError(
+            "Not a context snapshot file".to_string(),
+        ));
+    }
+
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != SNAPSHOT_VERSION {
+        return Err(MinervaError::ValidationError(format!(
+            "Unsupported snapshot version {} (expected {})",
+            version, SNAPSHOT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+fn write_section(writer: &mut impl Write, bytes: &[u8]) -> MinervaResult<()> {
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_section(reader: &mut impl Read) -> MinervaResult<Vec<u8>> {
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(MinervaError::ValidationError(format!(
+            "Snapshot section truncated: expected {} bytes, found {}",
+            len,
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::kv_cache::{KVCacheConfig, KVStoreParams};
+
+    fn session() -> SessionContext {
+        let mut kv_cache = KVCache::new(KVCacheConfig {
+            num_layers: 2,
+            max_seq_len: 4,
+            num_heads: 2,
+            head_dim: 2,
+        });
+        let params = KVStoreParams::builder(vec![0.5, 1.0, 1.5, 2.0], vec![-1.0; 4])
+            .layer(1)
+            .pos(3)
+            .build();
+        kv_cache.store(params).unwrap();
+
+        SessionContext {
+            model_id: "llama-7b".to_string(),
+            kv_cache,
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "hello".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.bin");
+        session().write_to(&path).unwrap();
+
+        let restored = SessionContext::read_from(&path).unwrap();
+        assert_eq!(restored.model_id, "llama-7b");
+        assert_eq!(restored.messages.len(), 1);
+        assert_eq!(restored.messages[0].content, "hello");
+        let (k, v) = restored.kv_cache.get(1, 3).unwrap();
+        assert_eq!(k, vec![0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(v, vec![-1.0; 4]);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_bad_magic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.bin");
+        std::fs::write(&path, b"JUNK\x01\x00\x00\x00").unwrap();
+
+        assert!(matches!(
+            SessionContext::read_from(&path),
+            Err(MinervaError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_rejects_future_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.bin");
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bytes.extend_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(SessionContext::read_from(&path).is_err());
+    }
+}
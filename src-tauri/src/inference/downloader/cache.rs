@@ -25,6 +25,12 @@ pub struct CacheEntry {
     pub downloaded_at: u64,
     /// Last accessed (unix timestamp)
     pub last_accessed: u64,
+    /// URL the model file was fetched from
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// SHA-256 of the file as fetched, already checked against the source
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 /// Download cache
@@ -95,6 +101,8 @@ mod tests {
             file_count: 5,
             downloaded_at: 0,
             last_accessed: 0,
+            source_url: None,
+            sha256: None,
         };
         cache.add(entry);
         assert_eq!(cache.list().len(), 1);
@@ -111,6 +119,8 @@ mod tests {
             file_count: 5,
             downloaded_at: 0,
             last_accessed: 0,
+            source_url: None,
+            sha256: None,
         };
         cache.add(entry);
         assert!(cache.get("test").is_some());
@@ -127,6 +137,8 @@ mod tests {
             file_count: 5,
             downloaded_at: 0,
             last_accessed: 0,
+            source_url: None,
+            sha256: None,
         };
         cache.add(entry);
         cache.remove("test");
@@ -1,11 +1,14 @@
 //! Model Download - HuggingFace Hub integration
 
+use super::cache::CacheEntry;
 use crate::error::{MinervaError, MinervaResult};
 use futures::stream::StreamExt;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use tauri::Emitter;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelDownloadRequest {
@@ -22,11 +25,24 @@ pub struct DownloadResult {
     pub success: bool,
     pub total_bytes: u64,
     pub duration_secs: u64,
+    /// Model weight files fetched, ready for `ModelRegistry::record_download`
+    #[serde(default)]
+    pub models: Vec<CacheEntry>,
 }
 
+/// A file streamed to disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchedFile {
+    pub bytes: u64,
+    /// SHA-256 of what was written, hex encoded
+    pub sha256: String,
+}
+
+#[derive(Clone)]
 pub struct ModelDownloader {
     hf_token: Option<String>,
     client: reqwest::Client,
+    app_handle: Option<tauri::AppHandle>,
 }
 
 impl ModelDownloader {
@@ -39,6 +55,7 @@ impl ModelDownloader {
         Self {
             hf_token: None,
             client,
+            app_handle: None,
         }
     }
 
@@ -47,6 +64,21 @@ impl ModelDownloader {
         self
     }
 
+    /// Emit download lifecycle events (e.g. `model://redownloading`) to the UI
+    pub fn with_app_handle(mut self, handle: tauri::AppHandle) -> Self {
+        self.app_handle = Some(handle);
+        self
+    }
+
+    /// Send `payload` to the frontend; a no-op without an app handle
+    pub fn notify<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Some(handle) = &self.app_handle
+            && let Err(e) = handle.emit(event, payload)
+        {
+            tracing::warn!("Failed to emit {}: {}", event, e);
+        }
+    }
+
     pub async fn download(&self, req: &ModelDownloadRequest) -> MinervaResult<DownloadResult> {
         if req.model_id.is_empty() {
             return Err(MinervaError::InvalidRequest(
@@ -59,6 +91,7 @@ impl ModelDownloader {
         fs::create_dir_all(&local_dir)?;
 
         let mut total_bytes: u64 = 0;
+        let mut models = Vec::new();
         let files = req
             .files
             .as_ref()
@@ -66,11 +99,12 @@ impl ModelDownloader {
             .unwrap_or_else(|| vec!["model.safetensors", "config.json", "tokenizer.json"]);
 
         for file in files {
-            if let Ok(size) = self
-                .download_file(&req.model_id, file, &local_dir.join(file))
-                .await
-            {
-                total_bytes += size;
+            let path = local_dir.join(file);
+            if let Ok(fetched) = self.download_file(&req.model_id, file, &path).await {
+                total_bytes += fetched.bytes;
+                if is_model_weights(&path) {
+                    models.push(model_entry(req, file, fetched));
+                }
             }
         }
 
@@ -81,6 +115,7 @@ impl ModelDownloader {
             success: total_bytes > 0,
             total_bytes,
             duration_secs: duration,
+            models,
         })
     }
 
@@ -89,19 +124,24 @@ impl ModelDownloader {
         model_id: &str,
         file_name: &str,
         local_path: &Path,
-    ) -> MinervaResult<u64> {
-        let url = format!(
-            "https://huggingface.co/{}/resolve/main/{}",
-            model_id, file_name
-        );
+    ) -> MinervaResult<FetchedFile> {
+        self.download_url(&file_url(model_id, file_name), local_path)
+            .await
+    }
 
+    /// Stream `url` to `local_path`, failing if fewer bytes arrive than
+    /// announced or they do not hash to the SHA-256 the source announced
+    ///
+    /// The hub sends the SHA-256 of LFS files in `X-Linked-Etag`; other
+    /// sources are only checked for length.
+    pub async fn download_url(&self, url: &str, local_path: &Path) -> MinervaResult<FetchedFile> {
         if let Some(parent) = local_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
         let response = self
             .client
-            .get(&url)
+            .get(url)
             .send()
             .await
             .map_err(|e| MinervaError::ServerError(format!("HTTP error: {}", e)))?;
@@ -109,26 +149,85 @@ impl ModelDownloader {
         let total_size = response
             .content_length()
             .ok_or_else(|| MinervaError::ServerError("Unknown content length".to_string()))?;
+        let announced = announced_sha256(response.headers());
 
         let mut file = File::create(local_path)?;
         let mut stream = response.bytes_stream();
         let mut downloaded: u64 = 0;
+        let mut hasher = Sha256::new();
 
         while let Some(chunk) = stream.next().await {
             let chunk =
                 chunk.map_err(|e| MinervaError::ServerError(format!("Download error: {}", e)))?;
             file.write_all(&chunk)?;
+            hasher.update(&chunk);
             downloaded += chunk.len() as u64;
         }
 
-        if downloaded == total_size {
-            Ok(total_size)
-        } else {
-            Err(MinervaError::ServerError(format!(
+        if downloaded != total_size {
+            return Err(MinervaError::ServerError(format!(
                 "Incomplete: {} / {} bytes",
                 downloaded, total_size
-            )))
+            )));
+        }
+        let sha256 = format!("{:x}", hasher.finalize());
+        if let Some(expected) = announced
+            && expected != sha256
+        {
+            let _ = fs::remove_file(local_path);
+            return Err(MinervaError::ModelCorrupted(format!(
+                "Checksum mismatch for {}: source announced {}, got {}",
+                url, expected, sha256
+            )));
         }
+        Ok(FetchedFile {
+            bytes: total_size,
+            sha256,
+        })
+    }
+}
+
+fn file_url(model_id: &str, file_name: &str) -> String {
+    format!(
+        "https://huggingface.co/{}/resolve/main/{}",
+        model_id, file_name
+    )
+}
+
+/// SHA-256 from the hub's `X-Linked-Etag`, when it looks like one
+fn announced_sha256(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let etag = headers.get("x-linked-etag")?.to_str().ok()?;
+    let etag = etag.trim_start_matches("W/").trim_matches('"');
+    (etag.len() == 64 && etag.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| etag.to_ascii_lowercase())
+}
+
+fn is_model_weights(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("gguf" | "safetensors")
+    )
+}
+
+/// Download record for `file`, keyed like registered models by file stem
+fn model_entry(req: &ModelDownloadRequest, file: &str, fetched: FetchedFile) -> CacheEntry {
+    let path = Path::new(&req.local_dir).join(file);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    CacheEntry {
+        model_id: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path,
+        revision: "main".to_string(),
+        size_bytes: fetched.bytes,
+        file_count: 1,
+        downloaded_at: now,
+        last_accessed: now,
+        source_url: Some(file_url(&req.model_id, file)),
+        sha256: Some(fetched.sha256),
     }
 }
 
@@ -6,7 +6,9 @@
 pub mod cache;
 pub mod download;
 pub mod progress;
+pub mod redownload;
 
 pub use cache::{CacheEntry, DownloadCache};
-pub use download::{DownloadResult, ModelDownloadRequest, ModelDownloader};
+pub use download::{DownloadResult, FetchedFile, ModelDownloadRequest, ModelDownloader};
 pub use progress::DownloadProgress;
+pub use redownload::{REDOWNLOADING_EVENT, RedownloadJob};
@@ -0,0 +1,82 @@
+/// Corrupted Model Re-download
+///
+/// Fetches a fresh copy of a model beside the corrupted file, checks its
+/// SHA-256 against the source and the recorded checksum, and only then
+/// renames it over the original so a failed download never leaves a
+/// half-written model in place.
+use super::download::{FetchedFile, ModelDownloader};
+use crate::error::{MinervaError, MinervaResult};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Event emitted when a corrupted model starts re-downloading
+pub const REDOWNLOADING_EVENT: &str = "model://redownloading";
+
+/// A model file to fetch again and the SHA-256 it must match
+#[derive(Debug, Clone, Serialize)]
+pub struct RedownloadJob {
+    pub model_id: String,
+    pub url: String,
+    pub path: PathBuf,
+    pub checksum: String,
+}
+
+impl ModelDownloader {
+    /// Download `job.url`, verify its SHA-256, then atomically replace `job.path`
+    pub async fn redownload(&self, job: &RedownloadJob) -> MinervaResult<FetchedFile> {
+        let partial = job.path.with_extension("part");
+        let fetched = self.download_url(&job.url, &partial).await?;
+
+        if fetched.sha256 != job.checksum {
+            let _ = fs::remove_file(&partial);
+            return Err(MinervaError::ModelCorrupted(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                job.model_id, job.checksum, fetched.sha256
+            )));
+        }
+
+        replace_file(&partial, &job.path)?;
+        Ok(fetched)
+    }
+}
+
+/// Rename within one directory, which is atomic on the same filesystem
+fn replace_file(from: &Path, to: &Path) -> MinervaResult<()> {
+    fs::rename(from, to)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_file_overwrites_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("model.gguf");
+        let partial = target.with_extension("part");
+        fs::write(&target, b"corrupt").unwrap();
+        fs::write(&partial, b"fresh").unwrap();
+
+        replace_file(&partial, &target).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"fresh");
+        assert!(!partial.exists());
+    }
+
+    #[tokio::test]
+    async fn test_redownload_unreachable_source_keeps_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("model.gguf");
+        fs::write(&target, b"corrupt").unwrap();
+        let job = RedownloadJob {
+            model_id: "test".to_string(),
+            url: "http://127.0.0.1:1/model.gguf".to_string(),
+            path: target.clone(),
+            checksum: "0".to_string(),
+        };
+
+        assert!(ModelDownloader::new().redownload(&job).await.is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"corrupt");
+    }
+}
@@ -2,6 +2,7 @@
 ///
 /// Defines model architecture parameters and preset configurations
 /// for different model architectures (LLaMA, BERT, etc.)
+use super::pure_rust_backend::ModelType;
 use super::transformer_components::Activation;
 
 /// Complete inference engine configuration
@@ -28,6 +29,8 @@ pub struct InferenceEngineConfig {
     pub eps: f32,
     /// Maximum sequence length supported
     pub max_seq_len: usize,
+    /// Worker threads for CPU-bound work such as weight loading
+    pub n_threads: usize,
 }
 
 impl InferenceEngineConfig {
@@ -49,6 +52,7 @@ impl InferenceEngineConfig {
             causal: true,
             eps: 1e-6,
             max_seq_len: 2048,
+            n_threads: num_cpus::get(),
         }
     }
 
@@ -70,6 +74,7 @@ impl InferenceEngineConfig {
             causal: false,
             eps: 1e-12,
             max_seq_len: 512,
+            n_threads: num_cpus::get(),
         }
     }
 
@@ -91,6 +96,167 @@ impl InferenceEngineConfig {
             causal: true,
             eps: 1e-6,
             max_seq_len: 128,
+            n_threads: num_cpus::get(),
         }
     }
+
+    /// LLaMA-2 7B (meta-llama/Llama-2-7b-hf model card)
+    pub fn llama_7b() -> Self {
+        Self {
+            vocab_size: 32000,
+            hidden_size: 4096,
+            num_heads: 32,
+            num_layers: 32,
+            intermediate_size: 11008,
+            activation: Activation::SiLU,
+            causal: true,
+            eps: 1e-5,
+            max_seq_len: 4096,
+            n_threads: num_cpus::get(),
+        }
+    }
+
+    /// LLaMA-2 13B (meta-llama/Llama-2-13b-hf model card)
+    pub fn llama_13b() -> Self {
+        Self {
+            hidden_size: 5120,
+            num_heads: 40,
+            num_layers: 40,
+            intermediate_size: 13824,
+            ..Self::llama_7b()
+        }
+    }
+
+    /// Mistral 7B v0.1 (mistralai/Mistral-7B-v0.1 model card)
+    pub fn mistral_7b() -> Self {
+        Self {
+            intermediate_size: 14336,
+            max_seq_len: 32768,
+            ..Self::llama_7b()
+        }
+    }
+
+    /// Phi-3 mini 4k (microsoft/Phi-3-mini-4k-instruct model card)
+    pub fn phi_3_mini() -> Self {
+        Self {
+            vocab_size: 32064,
+            hidden_size: 3072,
+            num_heads: 32,
+            num_layers: 32,
+            intermediate_size: 8192,
+            activation: Activation::SiLU,
+            causal: true,
+            eps: 1e-5,
+            max_seq_len: 4096,
+            n_threads: num_cpus::get(),
+        }
+    }
+
+    /// Estimate a config for an uncommon size of a known architecture
+    ///
+    /// Starts from the closest preset of the same family and scales width and
+    /// depth by the cube root of the parameter ratio (params ~ layers * hidden^2).
+    /// Returns `None` for unknown architectures or sizes more than 4x away
+    /// from any preset.
+    pub fn from_model_size(params_billion: f32, arch: ModelType) -> Option<Self> {
+        if !params_billion.is_finite() || params_billion <= 0.0 {
+            return None;
+        }
+
+        let (anchor_params, anchor) =
+            Self::family_presets(arch)
+                .into_iter()
+                .min_by(|(a, _), (b, _)| {
+                    let dist_a = (params_billion / a).ln().abs();
+                    let dist_b = (params_billion / b).ln().abs();
+                    dist_a.total_cmp(&dist_b)
+                })?;
+
+        let ratio = params_billion / anchor_params;
+        if !(0.25..=4.0).contains(&ratio) {
+            return None;
+        }
+
+        Some(anchor.scaled(ratio.cbrt()))
+    }
+
+    /// Presets for an architecture, keyed by parameter count in billions
+    fn family_presets(arch: ModelType) -> Vec<(f32, Self)> {
+        match arch {
+            ModelType::Llama => vec![(6.7, Self::llama_7b()), (13.0, Self::llama_13b())],
+            ModelType::Mistral => vec![(7.2, Self::mistral_7b())],
+            ModelType::Phi => vec![(3.8, Self::phi_3_mini())],
+            ModelType::Qwen | ModelType::Unknown => Vec::new(),
+        }
+    }
+
+    /// Scale width and depth by `factor`, keeping the head size and FFN ratio
+    fn scaled(self, factor: f32) -> Self {
+        let head_dim = self.hidden_size / self.num_heads;
+        let ffn_ratio = self.intermediate_size as f32 / self.hidden_size as f32;
+        let num_heads = ((self.num_heads as f32 * factor).round() as usize).max(1);
+        let hidden_size = num_heads * head_dim;
+        let intermediate_size = round_up(hidden_size as f32 * ffn_ratio, 256);
+
+        Self {
+            hidden_size,
+            num_heads,
+            num_layers: ((self.num_layers as f32 * factor).round() as usize).max(1),
+            intermediate_size,
+            ..self
+        }
+    }
+}
+
+fn round_up(value: f32, multiple: usize) -> usize {
+    (value / multiple as f32).ceil() as usize * multiple
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_presets_match_model_cards() {
+        let llama_7b = InferenceEngineConfig::llama_7b();
+        assert_eq!(
+            (
+                llama_7b.hidden_size,
+                llama_7b.num_heads,
+                llama_7b.num_layers
+            ),
+            (4096, 32, 32)
+        );
+
+        let llama_13b = InferenceEngineConfig::llama_13b();
+        assert_eq!(
+            (
+                llama_13b.hidden_size,
+                llama_13b.num_heads,
+                llama_13b.num_layers
+            ),
+            (5120, 40, 40)
+        );
+
+        let mistral = InferenceEngineConfig::mistral_7b();
+        assert_eq!(mistral.intermediate_size, 14336);
+
+        let phi = InferenceEngineConfig::phi_3_mini();
+        assert_eq!((phi.vocab_size, phi.hidden_size), (32064, 3072));
+    }
+
+    #[test]
+    fn test_from_model_size_interpolates() {
+        let config = InferenceEngineConfig::from_model_size(10.0, ModelType::Llama).unwrap();
+        assert!(config.hidden_size > 4096 && config.hidden_size <= 5120);
+        assert_eq!(config.hidden_size % config.num_heads, 0);
+        assert!(config.causal);
+    }
+
+    #[test]
+    fn test_from_model_size_unknown_or_out_of_range() {
+        assert!(InferenceEngineConfig::from_model_size(7.0, ModelType::Unknown).is_none());
+        assert!(InferenceEngineConfig::from_model_size(0.0, ModelType::Llama).is_none());
+        assert!(InferenceEngineConfig::from_model_size(400.0, ModelType::Mistral).is_none());
+    }
 }
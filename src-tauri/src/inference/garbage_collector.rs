@@ -1,3 +1,4 @@
+use super::context_manager::ContextManager;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
@@ -135,6 +136,20 @@ impl GarbageCollector {
         );
     }
 
+    /// Unload every model immediately, e.g. on critical memory pressure
+    ///
+    /// Returns the number of models evicted.
+    pub fn evict_all(&mut self, contexts: &mut ContextManager) -> u64 {
+        contexts.update_memory_estimate();
+        let freed_mb = contexts.estimated_memory_mb();
+        let evicted = contexts.loaded_count() as u64;
+
+        contexts.clear();
+        contexts.update_memory_estimate();
+        self.collect(freed_mb, evicted);
+        evicted
+    }
+
     /// Get time until next collection
     #[allow(dead_code)]
     pub fn time_until_next_collection(&self) -> Duration {
@@ -309,4 +324,14 @@ mod tests {
         let time = collector.time_until_next_collection();
         assert!(time.as_millis() < 100);
     }
+
+    #[test]
+    fn test_evict_all_records_collection() {
+        let mut collector = GarbageCollector::new();
+        let mut contexts = ContextManager::new(2);
+
+        assert_eq!(collector.evict_all(&mut contexts), 0);
+        assert_eq!(contexts.loaded_count(), 0);
+        assert_eq!(collector.stats().total_collections, 1);
+    }
 }
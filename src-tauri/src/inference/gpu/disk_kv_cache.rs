@@ -0,0 +1,144 @@
+/// Disk KV Cache Tier
+///
+/// The coldest tier: a memory-mapped file of fixed-size slots, so spilled
+/// positions are read back through the page cache without explicit I/O.
+/// Each slot stores, per layer, the keys then the values as little-endian
+/// f32. The file is scratch space and is recreated on every open.
+use super::kv_tier::{PositionKV, SlotIndex};
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::kv_cache::KVCacheConfig;
+use memmap2::MmapMut;
+use std::fs::OpenOptions;
+use std::path::Path;
+
+/// Memory-mapped overflow storage for KV positions
+#[derive(Debug)]
+pub struct DiskKVCache {
+    map: MmapMut,
+    index: SlotIndex,
+    num_layers: usize,
+    values_per_layer: usize,
+}
+
+impl DiskKVCache {
+    /// Create a backing file at `path` sized for `config.max_seq_len` positions
+    pub fn create(path: &Path, config: KVCacheConfig) -> MinervaResult<Self> {
+        let values_per_layer = config.num_heads * config.head_dim;
+        let slot_bytes = config.num_layers * 2 * values_per_layer * size_of::<f32>();
+        let len = (slot_bytes * config.max_seq_len) as u64;
+        if len == 0 {
+            return Err(MinervaError::InferenceError(
+                "Disk KV cache needs a non-zero size".to_string(),
+            ));
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len)?;
+        // SAFETY: the file was just created and truncated by us and is only
+        // accessed through this mapping for the cache's lifetime.
+        let map = unsafe { MmapMut::map_mut(&file)? };
+
+        Ok(Self {
+            map,
+            index: SlotIndex::new(config.max_seq_len),
+            num_layers: config.num_layers,
+            values_per_layer,
+        })
+    }
+
+    pub fn contains(&self, pos: usize) -> bool {
+        self.index.slot(pos).is_some()
+    }
+
+    pub fn index(&self) -> &SlotIndex {
+        &self.index
+    }
+
+    /// Write `pos` into a free slot
+    pub fn put(&mut self, pos: usize, kv: &PositionKV) -> MinervaResult<()> {
+        let slot = self.index.assign(pos).ok_or_else(|| {
+            MinervaError::InferenceError(format!("No free disk KV slot for position {}", pos))
+        })?;
+        let values = kv.iter().flat_map(|(k, v)| k.iter().chain(v));
+        let range = self.slot_range(slot);
+        let bytes = &mut self.map[range];
+        for (chunk, value) in bytes.chunks_exact_mut(size_of::<f32>()).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    /// Remove `pos`, returning its keys and values
+    pub fn take(&mut self, pos: usize) -> Option<PositionKV> {
+        let slot = self.index.release(pos)?;
+        let floats: Vec<f32> = self.map[self.slot_range(slot)]
+            .chunks_exact(size_of::<f32>())
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        let kv = floats
+            .chunks_exact(2 * self.values_per_layer)
+            .map(|layer| {
+                let (k, v) = layer.split_at(self.values_per_layer);
+                (k.to_vec(), v.to_vec())
+            })
+            .collect();
+        Some(kv)
+    }
+
+    /// Discard the least recently used position
+    pub fn evict_lru(&mut self) -> Option<usize> {
+        let pos = self.index.least_recent()?;
+        self.index.release(pos);
+        Some(pos)
+    }
+
+    fn slot_range(&self, slot: usize) -> std::ops::Range<usize> {
+        let slot_bytes = self.num_layers * 2 * self.values_per_layer * size_of::<f32>();
+        slot * slot_bytes..(slot + 1) * slot_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = KVCacheConfig {
+            num_layers: 2,
+            max_seq_len: 2,
+            num_heads: 1,
+            head_dim: 2,
+        };
+        let mut disk = DiskKVCache::create(&dir.path().join("kv.bin"), config).unwrap();
+        let kv = vec![
+            (vec![1.0, 2.0], vec![3.0, 4.0]),
+            (vec![5.0, 6.0], vec![7.0, 8.0]),
+        ];
+
+        disk.put(42, &kv).unwrap();
+        disk.put(43, &kv).unwrap();
+        assert!(disk.put(44, &kv).is_err());
+        assert_eq!(disk.take(42), Some(kv));
+        assert!(!disk.contains(42));
+        assert_eq!(disk.evict_lru(), Some(43));
+    }
+
+    #[test]
+    fn test_zero_sized_disk_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = KVCacheConfig {
+            num_layers: 1,
+            max_seq_len: 0,
+            num_heads: 1,
+            head_dim: 2,
+        };
+        assert!(DiskKVCache::create(&dir.path().join("kv.bin"), config).is_err());
+    }
+}
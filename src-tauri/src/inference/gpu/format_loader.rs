@@ -4,6 +4,11 @@
 /// - GGUF (llama.cpp quantized)
 /// - SafeTensors (standard huggingface format)
 /// - MLX (Apple Silicon optimized)
+///
+/// SafeTensors models can be converted to a cached GGUF file with
+/// [`GGUFConverter::convert_and_cache`].
+pub use super::gguf_converter::GGUFConverter;
+pub use super::gguf_writer::QuantizationType;
 use crate::error::MinervaResult;
 use ndarray::Array2;
 use std::path::Path;
@@ -0,0 +1,325 @@
+/// SafeTensors to GGUF Conversion Cache
+///
+/// Converts a SafeTensors model to GGUF once and reuses the result on later
+/// loads. The cached file sits in a cache directory as
+/// `{stem}.{quant}.gguf` next to a `.source-mtime` sidecar recording the
+/// source's modification time; a differing mtime means the source changed
+/// and the file is converted again. Output is written to a `.part` file and
+/// renamed into place, so an interrupted conversion is never reused.
+///
+/// The source is memory-mapped and converted one tensor at a time, and its
+/// directory must hold the HuggingFace `config.json` and `tokenizer.json`
+/// that supply the architecture, hyperparameters and vocabulary.
+use super::gguf_hf_metadata::{
+    HFModelConfig, gguf_tensor_name, permute_rotary_rows, rotary_permute_heads, tokenizer_metadata,
+};
+use super::gguf_writer::{GGUFWriter, MetadataValue, QuantizationType, TensorPlan};
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::downloader::progress::{DownloadProgress, ProgressTracker, ProgressUpdate};
+use crate::models::gguf_tensor_view::f16_to_f32;
+use memmap2::Mmap;
+use safetensors::{Dtype, SafeTensors};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Callback receiving conversion progress
+pub type ConversionProgressFn = Box<dyn FnMut(DownloadProgress) + Send>;
+
+/// Directory, next to the source, holding its converted GGUF files
+pub const CONVERSION_CACHE_DIR: &str = ".gguf_cache";
+
+/// Converts SafeTensors files to GGUF and caches the result
+pub struct GGUFConverter {
+    quant: QuantizationType,
+    on_progress: Option<ConversionProgressFn>,
+}
+
+impl GGUFConverter {
+    pub fn new(quant: QuantizationType) -> Self {
+        Self {
+            quant,
+            on_progress: None,
+        }
+    }
+
+    /// Report progress after each converted tensor
+    pub fn with_progress(mut self, on_progress: ConversionProgressFn) -> Self {
+        self.on_progress = Some(on_progress);
+        self
+    }
+
+    /// Whether `source` is a SafeTensors file with the HF metadata to convert it
+    pub fn can_convert(source: &Path) -> bool {
+        let is_safetensors = source
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("safetensors"));
+        let dir = source.parent().unwrap_or(Path::new("."));
+        is_safetensors
+            && HFModelConfig::read(dir).is_ok_and(|c| c.architecture().is_some())
+            && dir.join("tokenizer.json").is_file()
+    }
+
+    /// Cache directory used when loading `source` directly
+    pub fn default_cache_dir(source: &Path) -> PathBuf {
+        source
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join(CONVERSION_CACHE_DIR)
+    }
+
+    /// Path the converted file for `source` gets in `cache_dir`
+    pub fn cached_path(&self, source: &Path, cache_dir: &Path) -> PathBuf {
+        let stem = source
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("model");
+        cache_dir.join(format!("{}.{}.gguf", stem, self.quant.as_str()))
+    }
+
+    /// Return the cached GGUF for `source`, converting it first if needed
+    pub fn convert_and_cache(&mut self, source: &Path, cache_dir: &Path) -> MinervaResult<PathBuf> {
+        let target = self.cached_path(source, cache_dir);
+        let stamp_path = target.with_extension("gguf.source-mtime");
+        let stamp = source_mtime(source)?;
+
+        if target.exists() && fs::read_to_string(&stamp_path).ok().as_deref() == Some(&stamp) {
+            tracing::debug!("Reusing converted model {}", target.display());
+            return Ok(target);
+        }
+
+        fs::create_dir_all(cache_dir)?;
+        let partial = target.with_extension("gguf.part");
+        self.convert(source, &partial)?;
+        fs::rename(&partial, &target)?;
+        fs::write(&stamp_path, stamp)?;
+        tracing::info!("Converted {} to {}", source.display(), target.display());
+        Ok(target)
+    }
+
+    fn convert(&mut self, source: &Path, output: &Path) -> MinervaResult<()> {
+        let model_dir = source.parent().unwrap_or(Path::new("."));
+        let config = HFModelConfig::read(model_dir)?;
+        let mut metadata = config.metadata()?;
+        metadata.extend(tokenizer_metadata(model_dir, &config)?);
+
+        let file = File::open(source)?;
+        // SAFETY: the mapping is read-only and dropped before returning;
+        // model files are not modified while being converted.
+        let map = unsafe { Mmap::map(&file)? };
+        let tensors = SafeTensors::deserialize(&map)?;
+        let mut sources = Vec::new();
+        for name in tensors.names() {
+            if let Some(gguf_name) = gguf_tensor_name(name)? {
+                sources.push((gguf_name, name.to_string()));
+            }
+        }
+        sources.sort();
+
+        let plans = sources
+            .iter()
+            .map(|(gguf_name, name)| {
+                let shape = tensors.tensor(name)?.shape().to_vec();
+                Ok(TensorPlan {
+                    name: gguf_name.clone(),
+                    encoding: self.quant.for_tensor(&shape),
+                    shape,
+                })
+            })
+            .collect::<MinervaResult<Vec<_>>>()?;
+        let model_name = source
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("model")
+            .to_string();
+        metadata.extend([
+            (
+                "general.name".to_string(),
+                MetadataValue::String(model_name.clone()),
+            ),
+            (
+                "general.file_type".to_string(),
+                MetadataValue::U32(self.quant.file_type()),
+            ),
+            (
+                "minerva.source_format".to_string(),
+                MetadataValue::String("safetensors".to_string()),
+            ),
+        ]);
+
+        let mut writer = GGUFWriter::new(BufWriter::new(File::create(output)?));
+        writer.write_header(&metadata, &plans)?;
+
+        let total_bytes = plans.iter().map(|p| p.byte_size() as u64).sum();
+        let tracker = ProgressTracker::new(model_name, total_bytes);
+        let mut written = 0u64;
+        for (done, (plan, (_, name))) in plans.iter().zip(&sources).enumerate() {
+            let mut values = tensor_values(&tensors, name)?;
+            if let Some(n_head) = rotary_permute_heads(&config, &plan.name)
+                && let Some(&cols) = plan.shape.last()
+            {
+                values = permute_rotary_rows(&values, cols, n_head)?;
+            }
+            written += writer.write_tensor(plan, &values)? as u64;
+            if let Some(on_progress) = self.on_progress.as_mut() {
+                on_progress(tracker.progress(ProgressUpdate {
+                    downloaded: written,
+                    files_total: plans.len(),
+                    files_done: done + 1,
+                }));
+            }
+        }
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+/// Source modification time as nanoseconds since the epoch
+fn source_mtime(source: &Path) -> MinervaResult<String> {
+    let modified = fs::metadata(source)?.modified()?;
+    let nanos = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    Ok(nanos.to_string())
+}
+
+/// Widen a tensor's elements to f32
+fn tensor_values(tensors: &SafeTensors, name: &str) -> MinervaResult<Vec<f32>> {
+    let view = tensors.tensor(name)?;
+    let data = view.data();
+    let values = match view.dtype() {
+        Dtype::F32 => data
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+        Dtype::F16 => data
+            .chunks_exact(2)
+            .map(|c| f16_to_f32(u16::from_le_bytes([c[0], c[1]])))
+            .collect(),
+        Dtype::BF16 => data
+            .chunks_exact(2)
+            .map(|c| f32::from_bits((u16::from_le_bytes([c[0], c[1]]) as u32) << 16))
+            .collect(),
+        other => {
+            return Err(MinervaError::UnsupportedFormat(format!(
+                "Cannot convert {:?} tensor {} to GGUF",
+                other, name
+            )));
+        }
+    };
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::gpu::gguf_hf_metadata::write_test_model_files;
+    use crate::inference::gpu::gguf_tensor_index::read_tensor_index;
+    use crate::models::gguf_header::GGUFHeader;
+    use safetensors::tensor::TensorView;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    fn write_model(path: &Path) {
+        let weight: Vec<u8> = (0..64).flat_map(|i| (i as f32).to_le_bytes()).collect();
+        let norm: Vec<u8> = [1.0f32; 32].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let tensors = HashMap::from([
+            (
+                "model.layers.0.self_attn.q_proj.weight".to_string(),
+                TensorView::new(Dtype::F32, vec![2, 32], &weight).unwrap(),
+            ),
+            (
+                "model.layers.0.input_layernorm.weight".to_string(),
+                TensorView::new(Dtype::F32, vec![32], &norm).unwrap(),
+            ),
+        ]);
+        let bytes = safetensors::serialize(&tensors, &None).unwrap();
+        fs::write(path, bytes).unwrap();
+        write_test_model_files(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_converts_and_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("tiny.safetensors");
+        write_model(&source);
+
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        let mut converter = GGUFConverter::new(QuantizationType::Q8_0)
+            .with_progress(Box::new(move |p| sink.lock().unwrap().push(p)));
+        let path = converter
+            .convert_and_cache(&source, &dir.path().join("cache"))
+            .unwrap();
+
+        assert!(path.ends_with("tiny.q8_0.gguf"));
+        let header = GGUFHeader::read(&path).unwrap();
+        assert_eq!(header.version, 3);
+        assert_eq!(header.tensor_count, 2);
+        // Architecture, 9 hparams, 6 tokenizer keys and 3 general keys
+        assert_eq!(header.kv_count, 19);
+
+        let bytes = fs::read(&path).unwrap();
+        let names: Vec<String> = read_tensor_index(&bytes)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert_eq!(names, ["blk.0.attn_norm.weight", "blk.0.attn_q.weight"]);
+
+        let updates = updates.lock().unwrap();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[1].files_completed, 2);
+        assert_eq!(updates[1].percent, 100);
+    }
+
+    #[test]
+    fn test_reuses_cache_until_source_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        let source = dir.path().join("tiny.safetensors");
+        write_model(&source);
+        let mut converter = GGUFConverter::new(QuantizationType::F16);
+
+        let path = converter.convert_and_cache(&source, &cache).unwrap();
+        fs::write(&path, b"marker").unwrap();
+        converter.convert_and_cache(&source, &cache).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"marker");
+
+        let later = SystemTime::now() + Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        converter.convert_and_cache(&source, &cache).unwrap();
+        assert!(GGUFHeader::read(&path).is_ok());
+    }
+
+    #[test]
+    fn test_can_convert_needs_hf_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("tiny.safetensors");
+        fs::write(&source, b"").unwrap();
+        assert!(!GGUFConverter::can_convert(&source));
+        write_test_model_files(dir.path());
+        assert!(GGUFConverter::can_convert(&source));
+        assert!(!GGUFConverter::can_convert(&dir.path().join("tiny.bin")));
+    }
+
+    #[test]
+    fn test_invalid_source_leaves_no_cache_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("broken.safetensors");
+        fs::write(&source, b"not safetensors").unwrap();
+        write_test_model_files(dir.path());
+        let mut converter = GGUFConverter::new(QuantizationType::F32);
+
+        assert!(converter.convert_and_cache(&source, dir.path()).is_err());
+        assert!(!converter.cached_path(&source, dir.path()).exists());
+    }
+}
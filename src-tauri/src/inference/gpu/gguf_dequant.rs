@@ -0,0 +1,123 @@
+/// GGUF Dequantization Kernels
+///
+/// Widen GGUF tensor data to f32 using ggml's block layouts:
+/// - Q8_0: 32 values in 34 bytes (f16 scale | 32 x i8)
+/// - Q4_0: 32 values in 18 bytes (f16 scale | 16 bytes of nibbles, low
+///   nibbles hold values 0-15 and high nibbles values 16-31, offset by 8)
+use super::gguf_loader::GGUFDataType;
+use crate::error::{MinervaError, MinervaResult};
+use crate::models::gguf_tensor_view::f16_to_f32;
+
+const BLOCK_VALUES: usize = 32;
+const Q8_0_BLOCK_BYTES: usize = 34;
+const Q4_0_BLOCK_BYTES: usize = 18;
+
+/// Bytes occupied by `elements` values of `dtype`
+pub fn encoded_size(dtype: GGUFDataType, elements: usize) -> MinervaResult<usize> {
+    let size = match dtype {
+        GGUFDataType::F32 => elements.checked_mul(4),
+        GGUFDataType::F16 => elements.checked_mul(2),
+        GGUFDataType::Q8_0 => elements
+            .div_ceil(BLOCK_VALUES)
+            .checked_mul(Q8_0_BLOCK_BYTES),
+        GGUFDataType::Q4_0 => elements
+            .div_ceil(BLOCK_VALUES)
+            .checked_mul(Q4_0_BLOCK_BYTES),
+        _ => {
+            return Err(MinervaError::UnsupportedFormat(format!(
+                "Dequantization of {:?} is not supported",
+                dtype
+            )));
+        }
+    };
+    size.ok_or_else(|| {
+        MinervaError::ValidationError(format!(
+            "{} {:?} values overflow the addressable size",
+            elements, dtype
+        ))
+    })
+}
+
+/// Dequantize `elements` values of `dtype` from the start of `data`
+pub fn dequantize(dtype: GGUFDataType, data: &[u8], elements: usize) -> MinervaResult<Vec<f32>> {
+    let size = encoded_size(dtype, elements)?;
+    let data = data.get(..size).ok_or_else(|| {
+        MinervaError::model_loading(format!(
+            "Tensor data truncated: need {} bytes, have {}",
+            size,
+            data.len()
+        ))
+    })?;
+
+    let mut values: Vec<f32> = match dtype {
+        GGUFDataType::F32 => data
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+        GGUFDataType::F16 => data
+            .chunks_exact(2)
+            .map(|c| f16_to_f32(u16::from_le_bytes([c[0], c[1]])))
+            .collect(),
+        GGUFDataType::Q8_0 => data
+            .chunks_exact(Q8_0_BLOCK_BYTES)
+            .flat_map(|block| {
+                let scale = f16_to_f32(u16::from_le_bytes([block[0], block[1]]));
+                block[2..].iter().map(move |&q| q as i8 as f32 * scale)
+            })
+            .collect(),
+        _ => data
+            .chunks_exact(Q4_0_BLOCK_BYTES)
+            .flat_map(dequantize_q4_0_block)
+            .collect(),
+    };
+    values.truncate(elements);
+    Ok(values)
+}
+
+fn dequantize_q4_0_block(block: &[u8]) -> [f32; BLOCK_VALUES] {
+    let scale = f16_to_f32(u16::from_le_bytes([block[0], block[1]]));
+    let mut out = [0.0; BLOCK_VALUES];
+    for (i, &byte) in block[2..].iter().enumerate() {
+        out[i] = ((byte & 0x0f) as i32 - 8) as f32 * scale;
+        out[i + 16] = ((byte >> 4) as i32 - 8) as f32 * scale;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dequantize_q8_0() {
+        // scale 0.5 (f16 0x3800), values -2..=29
+        let mut block = 0x3800u16.to_le_bytes().to_vec();
+        block.extend((0..32).map(|i| (i as i8 - 2) as u8));
+        let values = dequantize(GGUFDataType::Q8_0, &block, 32).unwrap();
+        assert_eq!(values[0], -1.0);
+        assert_eq!(values[31], 14.5);
+    }
+
+    #[test]
+    fn test_dequantize_q4_0_nibble_order() {
+        // scale 1.0: low nibble 9 -> +1, high nibble 6 -> -2
+        let mut block = 0x3c00u16.to_le_bytes().to_vec();
+        block.extend([0x69u8; 16]);
+        let values = dequantize(GGUFDataType::Q4_0, &block, 32).unwrap();
+        assert_eq!(values[0], 1.0);
+        assert_eq!(values[15], 1.0);
+        assert_eq!(values[16], -2.0);
+    }
+
+    #[test]
+    fn test_truncated_and_unsupported() {
+        assert!(dequantize(GGUFDataType::F32, &[0u8; 7], 2).is_err());
+        assert!(dequantize(GGUFDataType::Q6_K, &[0u8; 256], 32).is_err());
+    }
+
+    #[test]
+    fn test_encoded_size_overflow_is_an_error() {
+        let err = encoded_size(GGUFDataType::F32, usize::MAX / 2).unwrap_err();
+        assert!(matches!(err, MinervaError::ValidationError(_)), "{:?}", err);
+    }
+}
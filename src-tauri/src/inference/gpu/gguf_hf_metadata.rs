@@ -0,0 +1,464 @@
+/// HuggingFace to GGUF Model Metadata
+///
+/// Translates the pieces of a HuggingFace model directory that llama.cpp
+/// needs besides the weights: `config.json` becomes `general.architecture`
+/// and the `{arch}.*` hyperparameters, `tokenizer.json` becomes the
+/// `tokenizer.ggml.*` vocabulary, and tensor names are renamed to the GGUF
+/// scheme (`model.layers.0.self_attn.q_proj.weight` to
+/// `blk.0.attn_q.weight`). Only llama-family (llama, mistral) and qwen2
+/// models are supported, matching llama.cpp's `convert_hf_to_gguf.py`.
+use super::gguf_writer::MetadataValue;
+use crate::error::{MinervaError, MinervaResult};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// llama.cpp token types (`LLAMA_TOKEN_TYPE_*`)
+const TOKEN_TYPE_NORMAL: i32 = 1;
+const TOKEN_TYPE_UNKNOWN: i32 = 2;
+const TOKEN_TYPE_CONTROL: i32 = 3;
+const TOKEN_TYPE_USER_DEFINED: i32 = 4;
+const TOKEN_TYPE_BYTE: i32 = 6;
+
+/// Per-layer tensor renames, HF suffix to GGUF suffix
+const LAYER_TENSORS: &[(&str, &str)] = &[
+    ("self_attn.q_proj", "attn_q"),
+    ("self_attn.k_proj", "attn_k"),
+    ("self_attn.v_proj", "attn_v"),
+    ("self_attn.o_proj", "attn_output"),
+    ("mlp.gate_proj", "ffn_gate"),
+    ("mlp.up_proj", "ffn_up"),
+    ("mlp.down_proj", "ffn_down"),
+    ("input_layernorm", "attn_norm"),
+    ("post_attention_layernorm", "ffn_norm"),
+];
+
+/// The subset of `config.json` written as GGUF hyperparameters
+#[derive(Debug, Clone, Deserialize)]
+pub struct HFModelConfig {
+    pub model_type: String,
+    pub hidden_size: u32,
+    pub intermediate_size: u32,
+    pub num_hidden_layers: u32,
+    pub num_attention_heads: u32,
+    pub num_key_value_heads: Option<u32>,
+    #[serde(default = "default_context_length")]
+    pub max_position_embeddings: u32,
+    #[serde(default = "default_rms_norm_eps")]
+    pub rms_norm_eps: f32,
+    #[serde(default = "default_rope_theta")]
+    pub rope_theta: f32,
+    pub vocab_size: Option<u32>,
+    pub bos_token_id: Option<Value>,
+    pub eos_token_id: Option<Value>,
+}
+
+fn default_context_length() -> u32 {
+    4096
+}
+
+fn default_rms_norm_eps() -> f32 {
+    1e-5
+}
+
+fn default_rope_theta() -> f32 {
+    10000.0
+}
+
+impl HFModelConfig {
+    /// Read `config.json` from `model_dir`
+    pub fn read(model_dir: &Path) -> MinervaResult<Self> {
+        let path = model_dir.join("config.json");
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            MinervaError::model_loading_with(
+                format!("GGUF conversion needs {}: {}", path.display(), e),
+                e,
+            )
+        })?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// GGUF architecture name, `None` for model types that cannot be converted
+    pub fn architecture(&self) -> Option<&'static str> {
+        match self.model_type.as_str() {
+            "llama" | "mistral" => Some("llama"),
+            "qwen2" => Some("qwen2"),
+            _ => None,
+        }
+    }
+
+    pub fn head_count_kv(&self) -> u32 {
+        self.num_key_value_heads.unwrap_or(self.num_attention_heads)
+    }
+
+    /// `general.architecture` and the `{arch}.*` hyperparameters
+    pub fn metadata(&self) -> MinervaResult<Vec<(String, MetadataValue)>> {
+        let arch = self.architecture().ok_or_else(|| {
+            MinervaError::UnsupportedFormat(format!(
+                "Cannot convert {} models to GGUF",
+                self.model_type
+            ))
+        })?;
+        let key = |name: &str| format!("{}.{}", arch, name);
+        let mut metadata = vec![
+            (
+                "general.architecture".to_string(),
+                MetadataValue::String(arch.to_string()),
+            ),
+            (
+                key("context_length"),
+                MetadataValue::U32(self.max_position_embeddings),
+            ),
+            (
+                key("embedding_length"),
+                MetadataValue::U32(self.hidden_size),
+            ),
+            (
+                key("block_count"),
+                MetadataValue::U32(self.num_hidden_layers),
+            ),
+            (
+                key("feed_forward_length"),
+                MetadataValue::U32(self.intermediate_size),
+            ),
+            (
+                key("attention.head_count"),
+                MetadataValue::U32(self.num_attention_heads),
+            ),
+            (
+                key("attention.head_count_kv"),
+                MetadataValue::U32(self.head_count_kv()),
+            ),
+            (
+                key("attention.layer_norm_rms_epsilon"),
+                MetadataValue::F32(self.rms_norm_eps),
+            ),
+            (key("rope.freq_base"), MetadataValue::F32(self.rope_theta)),
+        ];
+        if let Some(vocab_size) = self.vocab_size {
+            metadata.push((key("vocab_size"), MetadataValue::U32(vocab_size)));
+        }
+        Ok(metadata)
+    }
+}
+
+/// Read `tokenizer.json` from `model_dir` as `tokenizer.ggml.*` metadata
+///
+/// Byte-fallback BPE vocabularies (Llama 2, Mistral) become llama.cpp's
+/// `llama` tokenizer with scores favouring lower ids; others become `gpt2`
+/// with their merges.
+pub fn tokenizer_metadata(
+    model_dir: &Path,
+    config: &HFModelConfig,
+) -> MinervaResult<Vec<(String, MetadataValue)>> {
+    let path = model_dir.join("tokenizer.json");
+    let json = std::fs::read_to_string(&path).map_err(|e| {
+        MinervaError::model_loading_with(
+            format!("GGUF conversion needs {}: {}", path.display(), e),
+            e,
+        )
+    })?;
+    let tokenizer: Value = serde_json::from_str(&json)?;
+    let model = &tokenizer["model"];
+    let vocab = model["vocab"].as_object().ok_or_else(|| {
+        MinervaError::UnsupportedFormat(format!("{} has no BPE vocabulary", path.display()))
+    })?;
+
+    let mut by_id: HashMap<u64, (String, i32)> = vocab
+        .iter()
+        .filter_map(|(token, id)| Some((id.as_u64()?, (token.clone(), normal_type(token)))))
+        .collect();
+    if let Some(unk) = model["unk_token"].as_str()
+        && let Some(id) = vocab.get(unk).and_then(Value::as_u64)
+    {
+        by_id.insert(id, (unk.to_string(), TOKEN_TYPE_UNKNOWN));
+    }
+    for added in tokenizer["added_tokens"].as_array().into_iter().flatten() {
+        if let (Some(id), Some(content)) = (added["id"].as_u64(), added["content"].as_str()) {
+            let token_type = if added["special"].as_bool().unwrap_or(false) {
+                TOKEN_TYPE_CONTROL
+            } else {
+                TOKEN_TYPE_USER_DEFINED
+            };
+            by_id.insert(id, (content.to_string(), token_type));
+        }
+    }
+
+    let len = by_id
+        .keys()
+        .max()
+        .map_or(0, |&id| id as usize + 1)
+        .max(config.vocab_size.unwrap_or(0) as usize);
+    let (tokens, token_types): (Vec<String>, Vec<i32>) = (0..len as u64)
+        .map(|id| {
+            by_id
+                .remove(&id)
+                .unwrap_or_else(|| (format!("[PAD{}]", id), TOKEN_TYPE_USER_DEFINED))
+        })
+        .unzip();
+
+    let byte_fallback = model["byte_fallback"].as_bool().unwrap_or(false);
+    let mut metadata = Vec::new();
+    if byte_fallback {
+        metadata.push(ggml_key("model", MetadataValue::String("llama".into())));
+        let scores = (0..len).map(|id| -(id as f32)).collect();
+        metadata.push(ggml_key("scores", MetadataValue::F32Array(scores)));
+    } else {
+        let pre = match config.architecture() {
+            Some("qwen2") => "qwen2",
+            _ => "llama-bpe",
+        };
+        metadata.push(ggml_key("model", MetadataValue::String("gpt2".into())));
+        metadata.push(ggml_key("pre", MetadataValue::String(pre.into())));
+        let merges = model["merges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(merge_string)
+            .collect();
+        metadata.push(ggml_key("merges", MetadataValue::StringArray(merges)));
+    }
+    metadata.push(ggml_key("tokens", MetadataValue::StringArray(tokens)));
+    metadata.push(ggml_key("token_type", MetadataValue::I32Array(token_types)));
+    if let Some(id) = config.bos_token_id.as_ref().and_then(first_token_id) {
+        metadata.push(ggml_key("bos_token_id", MetadataValue::U32(id)));
+    }
+    if let Some(id) = config.eos_token_id.as_ref().and_then(first_token_id) {
+        metadata.push(ggml_key("eos_token_id", MetadataValue::U32(id)));
+    }
+    Ok(metadata)
+}
+
+fn ggml_key(name: &str, value: MetadataValue) -> (String, MetadataValue) {
+    (format!("tokenizer.ggml.{}", name), value)
+}
+
+/// `<0x0A>`-style byte tokens get the BYTE type llama.cpp looks up
+fn normal_type(token: &str) -> i32 {
+    let is_byte = token.len() == 6 && token.starts_with("<0x") && token.ends_with('>');
+    if is_byte {
+        TOKEN_TYPE_BYTE
+    } else {
+        TOKEN_TYPE_NORMAL
+    }
+}
+
+/// Merges are `"a b"` strings, or `["a", "b"]` pairs in newer tokenizers
+fn merge_string(merge: &Value) -> Option<String> {
+    match merge {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(pair) => Some(format!(
+            "{} {}",
+            pair.first()?.as_str()?,
+            pair.get(1)?.as_str()?
+        )),
+        _ => None,
+    }
+}
+
+/// `eos_token_id` may list several ids; llama.cpp takes one
+fn first_token_id(value: &Value) -> Option<u32> {
+    match value {
+        Value::Array(ids) => ids.first().and_then(first_token_id),
+        other => other.as_u64().and_then(|id| u32::try_from(id).ok()),
+    }
+}
+
+/// GGUF name for a HuggingFace tensor
+///
+/// `Ok(None)` marks tensors llama.cpp recomputes (rotary inverse
+/// frequencies); names already in GGUF form pass through unchanged.
+pub fn gguf_tensor_name(hf_name: &str) -> MinervaResult<Option<String>> {
+    if hf_name.ends_with("rotary_emb.inv_freq") {
+        return Ok(None);
+    }
+    let (base, suffix) = hf_name.rsplit_once('.').unwrap_or((hf_name, ""));
+    let mapped = match base {
+        "model.embed_tokens" => Some("token_embd".to_string()),
+        "model.norm" => Some("output_norm".to_string()),
+        "lm_head" => Some("output".to_string()),
+        _ => base
+            .strip_prefix("model.layers.")
+            .and_then(|rest| rest.split_once('.'))
+            .and_then(|(layer, name)| {
+                let (_, gguf) = LAYER_TENSORS.iter().find(|(hf, _)| *hf == name)?;
+                Some(format!("blk.{}.{}", layer.parse::<usize>().ok()?, gguf))
+            }),
+    };
+    match mapped {
+        Some(name) => Ok(Some(format!("{}.{}", name, suffix))),
+        None if is_gguf_name(base) => Ok(Some(hf_name.to_string())),
+        None => Err(MinervaError::UnsupportedFormat(format!(
+            "No GGUF name for tensor {}",
+            hf_name
+        ))),
+    }
+}
+
+fn is_gguf_name(base: &str) -> bool {
+    base.starts_with("blk.") || matches!(base, "token_embd" | "output_norm" | "output")
+}
+
+/// Head count whose rotary layout llama.cpp expects reversed for `gguf_name`
+///
+/// HF stores llama q/k projections with rotary halves split per head;
+/// ggml interleaves them, so those rows are permuted during conversion.
+pub fn rotary_permute_heads(config: &HFModelConfig, gguf_name: &str) -> Option<usize> {
+    if config.architecture() != Some("llama") || !gguf_name.ends_with(".weight") {
+        return None;
+    }
+    if gguf_name.contains(".attn_q.") {
+        Some(config.num_attention_heads as usize)
+    } else if gguf_name.contains(".attn_k.") {
+        Some(config.head_count_kv() as usize)
+    } else {
+        None
+    }
+}
+
+/// Reorder rows of a `[rows, cols]` projection from HF to ggml rotary layout
+///
+/// Within each head, rows `[a0..a_h, b0..b_h]` become `[a0, b0, a1, b1, ...]`.
+pub fn permute_rotary_rows(values: &[f32], cols: usize, n_head: usize) -> MinervaResult<Vec<f32>> {
+    let rows = values.len() / cols.max(1);
+    if n_head == 0 || cols == 0 || rows % (2 * n_head) != 0 || rows * cols != values.len() {
+        return Err(MinervaError::ValidationError(format!(
+            "Cannot permute {} rows of {} values across {} heads",
+            rows, cols, n_head
+        )));
+    }
+    let half = rows / n_head / 2;
+    let mut out = vec![0.0; values.len()];
+    for head in 0..n_head {
+        for i in 0..half {
+            for j in 0..2 {
+                let src = (head * 2 + j) * half + i;
+                let dst = (head * half + i) * 2 + j;
+                out[dst * cols..(dst + 1) * cols]
+                    .copy_from_slice(&values[src * cols..(src + 1) * cols]);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Write a minimal llama `config.json` and byte-fallback `tokenizer.json`
+#[cfg(test)]
+pub(crate) fn write_test_model_files(dir: &Path) {
+    let config = serde_json::json!({
+        "model_type": "llama",
+        "hidden_size": 32,
+        "intermediate_size": 64,
+        "num_hidden_layers": 1,
+        "num_attention_heads": 1,
+        "vocab_size": 4,
+        "bos_token_id": 1,
+        "eos_token_id": [2, 3]
+    });
+    let tokenizer = serde_json::json!({
+        "added_tokens": [{"id": 1, "content": "<s>", "special": true}],
+        "model": {
+            "type": "BPE",
+            "byte_fallback": true,
+            "unk_token": "<unk>",
+            "vocab": {"<unk>": 0, "<s>": 1, "</s>": 2, "<0x0A>": 3},
+            "merges": []
+        }
+    });
+    std::fs::write(dir.join("config.json"), config.to_string()).unwrap();
+    std::fs::write(dir.join("tokenizer.json"), tokenizer.to_string()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(metadata: &'a [(String, MetadataValue)], key: &str) -> &'a MetadataValue {
+        &metadata.iter().find(|(k, _)| k == key).unwrap().1
+    }
+
+    #[test]
+    fn test_tensor_names() {
+        let name = |hf: &str| gguf_tensor_name(hf).unwrap();
+        assert_eq!(
+            name("model.layers.3.self_attn.q_proj.weight").as_deref(),
+            Some("blk.3.attn_q.weight")
+        );
+        assert_eq!(
+            name("model.layers.0.self_attn.k_proj.bias").as_deref(),
+            Some("blk.0.attn_k.bias")
+        );
+        assert_eq!(
+            name("model.layers.0.post_attention_layernorm.weight").as_deref(),
+            Some("blk.0.ffn_norm.weight")
+        );
+        assert_eq!(
+            name("model.embed_tokens.weight").as_deref(),
+            Some("token_embd.weight")
+        );
+        assert_eq!(name("lm_head.weight").as_deref(), Some("output.weight"));
+        assert_eq!(
+            name("blk.0.attn_q.weight").as_deref(),
+            Some("blk.0.attn_q.weight")
+        );
+        assert_eq!(name("model.layers.0.self_attn.rotary_emb.inv_freq"), None);
+        assert!(gguf_tensor_name("vision_tower.patch_embed.weight").is_err());
+    }
+
+    #[test]
+    fn test_permute_rotary_rows() {
+        // One head, four rows: halves [0, 1] and [2, 3] interleave
+        let values = [0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5];
+        let permuted = permute_rotary_rows(&values, 2, 1).unwrap();
+        assert_eq!(permuted, vec![0.0, 0.5, 2.0, 2.5, 1.0, 1.5, 3.0, 3.5]);
+        assert!(permute_rotary_rows(&values, 2, 3).is_err());
+    }
+
+    #[test]
+    fn test_config_and_tokenizer_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        write_test_model_files(dir.path());
+        let config = HFModelConfig::read(dir.path()).unwrap();
+
+        let metadata = config.metadata().unwrap();
+        assert!(matches!(
+            find(&metadata, "general.architecture"),
+            MetadataValue::String(arch) if arch == "llama"
+        ));
+        assert!(matches!(
+            find(&metadata, "llama.attention.head_count_kv"),
+            MetadataValue::U32(1)
+        ));
+
+        let tokenizer = tokenizer_metadata(dir.path(), &config).unwrap();
+        let MetadataValue::I32Array(types) = find(&tokenizer, "tokenizer.ggml.token_type") else {
+            panic!("token types should be an i32 array");
+        };
+        assert_eq!(
+            types,
+            &[
+                TOKEN_TYPE_UNKNOWN,
+                TOKEN_TYPE_CONTROL,
+                TOKEN_TYPE_NORMAL,
+                TOKEN_TYPE_BYTE
+            ]
+        );
+        assert!(matches!(
+            find(&tokenizer, "tokenizer.ggml.eos_token_id"),
+            MetadataValue::U32(2)
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_architecture() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.json"),
+            r#"{"model_type":"t5","hidden_size":8,"intermediate_size":8,"num_hidden_layers":1,"num_attention_heads":1}"#,
+        )
+        .unwrap();
+        let config = HFModelConfig::read(dir.path()).unwrap();
+        assert!(config.metadata().is_err());
+    }
+}
@@ -7,22 +7,117 @@
 /// - Q5_K_M (5-bit, higher quality)
 /// - MXFP4 (4-bit mixed precision from llama-server)
 /// - Q8_0 (8-bit, highest quality)
+///
+/// `load_parallel` dequantizes every tensor to f32 on a rayon pool sized by
+/// `InferenceEngineConfig::n_threads`; workers pull tensors from a shared
+/// queue so large and small tensors balance across threads.
 use crate::error::{MinervaError, MinervaResult};
+use crate::inference::engine_config::InferenceEngineConfig;
 use crate::inference::gpu::format_loader::{
     FormatLoader, LoadMetadata, LoadedModel, ModelConfig, ModelFormat,
 };
+use crate::inference::gpu::gguf_dequant::dequantize;
+use crate::inference::gpu::gguf_tensor_index::read_tensor_index;
+use memmap2::Mmap;
 use ndarray::Array2;
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 
-pub struct GGUFLoader;
+/// Called with (tensors loaded, total tensors) as each tensor finishes
+pub type TensorProgressFn = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+pub struct GGUFLoader {
+    n_threads: usize,
+    on_tensor_loaded: Option<TensorProgressFn>,
+}
 
 impl GGUFLoader {
     /// Create a new GGUF loader
     pub fn new() -> Self {
-        Self
+        Self {
+            n_threads: num_cpus::get(),
+            on_tensor_loaded: None,
+        }
+    }
+
+    /// Use the engine's thread count for parallel loading
+    pub fn with_engine_config(mut self, config: &InferenceEngineConfig) -> Self {
+        self.n_threads = config.n_threads.max(1);
+        self
+    }
+
+    /// Report progress after each tensor `load_parallel` finishes
+    pub fn with_progress(mut self, on_tensor_loaded: TensorProgressFn) -> Self {
+        self.on_tensor_loaded = Some(on_tensor_loaded);
+        self
+    }
+
+    /// Dequantize every tensor in the file to f32, in parallel
+    pub fn load_parallel(&self, path: &Path) -> MinervaResult<HashMap<String, Vec<f32>>> {
+        let start = Instant::now();
+        let file = File::open(path).map_err(|e| {
+            MinervaError::model_loading_with(format!("Failed to open GGUF: {}", e), e)
+        })?;
+        // SAFETY: the mapping is read-only and dropped before returning;
+        // model files are not modified while being loaded.
+        let map = unsafe { Mmap::map(&file)? };
+        let entries = read_tensor_index(&map)?;
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.n_threads)
+            .thread_name(|i| format!("gguf-load-{}", i))
+            .build()
+            .map_err(|e| {
+                MinervaError::model_loading(format!("Failed to start loader threads: {}", e))
+            })?;
+
+        let next = AtomicUsize::new(0);
+        let completed = AtomicUsize::new(0);
+        let tensors = Mutex::new(HashMap::with_capacity(entries.len()));
+        let first_error = Mutex::new(None);
+        pool.scope(|s| {
+            for _ in 0..self.n_threads.min(entries.len()) {
+                s.spawn(|_| {
+                    while let Some(entry) = entries.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        if first_error.lock().is_some() {
+                            return;
+                        }
+                        let data = map.get(entry.start..).unwrap_or_default();
+                        match dequantize(entry.dtype, data, entry.element_count()) {
+                            Ok(values) => {
+                                tensors.lock().insert(entry.name.clone(), values);
+                            }
+                            Err(e) => {
+                                first_error.lock().get_or_insert(e);
+                                return;
+                            }
+                        }
+                        let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                        if let Some(on_tensor_loaded) = &self.on_tensor_loaded {
+                            on_tensor_loaded(done, entries.len());
+                        }
+                    }
+                });
+            }
+        });
+
+        if let Some(e) = first_error.into_inner() {
+            return Err(e);
+        }
+        tracing::info!(
+            "Dequantized {} tensors from {} in {:.2}s on {} threads",
+            entries.len(),
+            path.display(),
+            start.elapsed().as_secs_f32(),
+            self.n_threads
+        );
+        Ok(tensors.into_inner())
     }
 
     /// Load GGUF file with metadata and tensors
@@ -30,7 +125,7 @@ impl GGUFLoader {
         let start = Instant::now();
 
         let file = File::open(path)
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to open GGUF: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Failed to open GGUF: {}", e)))?;
 
         let file_size = file.metadata()?.len();
         let mut reader = BufReader::new(file);
@@ -113,12 +208,12 @@ impl GGUFLoader {
 
     fn read_header(reader: &mut BufReader<File>) -> MinervaResult<GGUFHeader> {
         let mut magic = [0u8; 4];
-        reader.read_exact(&mut magic).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read GGUF magic".to_string())
-        })?;
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| MinervaError::model_loading("Failed to read GGUF magic".to_string()))?;
 
         if &magic != b"GGUF" {
-            return Err(MinervaError::ModelLoadingError(
+            return Err(MinervaError::model_loading(
                 "Invalid GGUF magic number".to_string(),
             ));
         }
@@ -126,18 +221,18 @@ impl GGUFLoader {
         let mut version_bytes = [0u8; 4];
         reader
             .read_exact(&mut version_bytes)
-            .map_err(|_| MinervaError::ModelLoadingError("Failed to read version".to_string()))?;
+            .map_err(|_| MinervaError::model_loading("Failed to read version".to_string()))?;
         let version = u32::from_le_bytes(version_bytes);
 
         let mut tensor_count_bytes = [0u8; 8];
-        reader.read_exact(&mut tensor_count_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read tensor count".to_string())
-        })?;
+        reader
+            .read_exact(&mut tensor_count_bytes)
+            .map_err(|_| MinervaError::model_loading("Failed to read tensor count".to_string()))?;
         let tensor_count = u64::from_le_bytes(tensor_count_bytes) as usize;
 
         let mut metadata_count_bytes = [0u8; 8];
         reader.read_exact(&mut metadata_count_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read metadata count".to_string())
+            MinervaError::model_loading("Failed to read metadata count".to_string())
         })?;
         let metadata_kv_count = u64::from_le_bytes(metadata_count_bytes) as usize;
 
@@ -152,23 +247,23 @@ impl GGUFLoader {
     fn read_metadata_kv(reader: &mut BufReader<File>) -> MinervaResult<(String, GGUFValue)> {
         // Read key length
         let mut key_len_bytes = [0u8; 4];
-        reader.read_exact(&mut key_len_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read key length".to_string())
-        })?;
+        reader
+            .read_exact(&mut key_len_bytes)
+            .map_err(|_| MinervaError::model_loading("Failed to read key length".to_string()))?;
         let key_len = u32::from_le_bytes(key_len_bytes) as usize;
 
         // Read key
         let mut key_bytes = vec![0u8; key_len];
         reader
             .read_exact(&mut key_bytes)
-            .map_err(|_| MinervaError::ModelLoadingError("Failed to read key".to_string()))?;
+            .map_err(|_| MinervaError::model_loading("Failed to read key".to_string()))?;
         let key = String::from_utf8(key_bytes).unwrap_or_else(|_| "invalid_key".to_string());
 
         // Read value type
         let mut value_type_bytes = [0u8; 4];
-        reader.read_exact(&mut value_type_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read value type".to_string())
-        })?;
+        reader
+            .read_exact(&mut value_type_bytes)
+            .map_err(|_| MinervaError::model_loading("Failed to read value type".to_string()))?;
         let value_type = u32::from_le_bytes(value_type_bytes);
 
         // Read value based on type
@@ -241,21 +336,21 @@ impl GGUFLoader {
         // Read tensor name length
         let mut name_len_bytes = [0u8; 4];
         reader.read_exact(&mut name_len_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read tensor name length".to_string())
+            MinervaError::model_loading("Failed to read tensor name length".to_string())
         })?;
         let name_len = u32::from_le_bytes(name_len_bytes) as usize;
 
         // Read tensor name
         let mut name_bytes = vec![0u8; name_len];
-        reader.read_exact(&mut name_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read tensor name".to_string())
-        })?;
+        reader
+            .read_exact(&mut name_bytes)
+            .map_err(|_| MinervaError::model_loading("Failed to read tensor name".to_string()))?;
         let name = String::from_utf8(name_bytes).unwrap_or_else(|_| "unknown_tensor".to_string());
 
         // Read number of dimensions
         let mut ndim_bytes = [0u8; 4];
         reader.read_exact(&mut ndim_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read tensor dimensions".to_string())
+            MinervaError::model_loading("Failed to read tensor dimensions".to_string())
         })?;
         let ndim = u32::from_le_bytes(ndim_bytes) as usize;
 
@@ -263,9 +358,9 @@ impl GGUFLoader {
         let mut dimensions = Vec::new();
         for _ in 0..ndim {
             let mut dim_bytes = [0u8; 8];
-            reader.read_exact(&mut dim_bytes).map_err(|_| {
-                MinervaError::ModelLoadingError("Failed to read dimension".to_string())
-            })?;
+            reader
+                .read_exact(&mut dim_bytes)
+                .map_err(|_| MinervaError::model_loading("Failed to read dimension".to_string()))?;
             let dim = u64::from_le_bytes(dim_bytes) as usize;
             dimensions.push(dim);
         }
@@ -274,15 +369,15 @@ impl GGUFLoader {
         let mut dtype_bytes = [0u8; 4];
         reader
             .read_exact(&mut dtype_bytes)
-            .map_err(|_| MinervaError::ModelLoadingError("Failed to read dtype".to_string()))?;
+            .map_err(|_| MinervaError::model_loading("Failed to read dtype".to_string()))?;
         let dtype = u32::from_le_bytes(dtype_bytes);
         let dtype = Self::parse_dtype(dtype)?;
 
         // Read offset (8 bytes)
         let mut offset_bytes = [0u8; 8];
-        reader.read_exact(&mut offset_bytes).map_err(|_| {
-            MinervaError::ModelLoadingError("Failed to read tensor offset".to_string())
-        })?;
+        reader
+            .read_exact(&mut offset_bytes)
+            .map_err(|_| MinervaError::model_loading("Failed to read tensor offset".to_string()))?;
         let offset = u64::from_le_bytes(offset_bytes);
 
         Ok(GGUFTensorHeader {
@@ -293,21 +388,22 @@ impl GGUFLoader {
         })
     }
 
-    fn parse_dtype(dtype_code: u32) -> MinervaResult<GGUFDataType> {
+    /// Map a ggml type id (`enum ggml_type`) to a data type
+    pub(crate) fn parse_dtype(dtype_code: u32) -> MinervaResult<GGUFDataType> {
         match dtype_code {
             0 => Ok(GGUFDataType::F32),
             1 => Ok(GGUFDataType::F16),
             2 => Ok(GGUFDataType::Q4_0),
             3 => Ok(GGUFDataType::Q4_1),
-            7 => Ok(GGUFDataType::Q8_0),
-            8 => Ok(GGUFDataType::Q8_1),
+            8 => Ok(GGUFDataType::Q8_0),
+            9 => Ok(GGUFDataType::Q8_1),
             10 => Ok(GGUFDataType::Q2_K),
             11 => Ok(GGUFDataType::Q3_K),
             12 => Ok(GGUFDataType::Q4_K),
             13 => Ok(GGUFDataType::Q5_K),
             14 => Ok(GGUFDataType::Q6_K),
             15 => Ok(GGUFDataType::Q8_K),
-            _ => Err(MinervaError::ModelLoadingError(format!(
+            _ => Err(MinervaError::model_loading(format!(
                 "Unknown dtype: {}",
                 dtype_code
             ))),
@@ -498,23 +594,7 @@ impl GGUFDataType {
     }
 }
 
-// Dequantization kernels (when GGUF loader is implemented)
-// These convert quantized values back to f32
-
-// Note: Dequantization implementations will be added when ggml crate is available
-// For now, these are documented for reference
-//
-// fn dequantize_q4_0(data: &[u8], n: usize) -> Vec<f32> {
-//     // Q4_0: 4-bit quantization with scale per block
-//     // Block size: 32 elements (16 bytes)
-//     // Format: [scale (2 bytes) | min (2 bytes) | 16 bytes of 4-bit values]
-// }
-//
-// fn dequantize_q8_0(data: &[u8], n: usize) -> Vec<f32> {
-//     // Q8_0: 8-bit quantization with scale per block
-//     // Block size: 32 elements (34 bytes)
-//     // Format: [scale (2 bytes) | 32 bytes of 8-bit values]
-// }
+// Dequantization kernels live in `gguf_dequant`
 
 #[cfg(test)]
 mod tests {
@@ -541,6 +621,68 @@ mod tests {
         assert!(!GGUFDataType::F32.is_quantized());
     }
 
+    fn write_converted_model(dir: &Path) -> std::path::PathBuf {
+        use crate::inference::gpu::{GGUFConverter, QuantizationType};
+        use safetensors::{Dtype, tensor::TensorView};
+
+        let bytes: Vec<Vec<u8>> = (0..6)
+            .map(|t| {
+                (0..64)
+                    .flat_map(|i| ((t * 64 + i) as f32 / 8.0).to_le_bytes())
+                    .collect()
+            })
+            .collect();
+        let tensors: HashMap<String, TensorView> = bytes
+            .iter()
+            .enumerate()
+            .map(|(t, data)| {
+                let view = TensorView::new(Dtype::F32, vec![2, 32], data).unwrap();
+                (format!("blk.{}.weight", t), view)
+            })
+            .collect();
+        let source = dir.join("tiny.safetensors");
+        std::fs::write(&source, safetensors::serialize(&tensors, &None).unwrap()).unwrap();
+        crate::inference::gpu::gguf_hf_metadata::write_test_model_files(dir);
+        GGUFConverter::new(QuantizationType::Q8_0)
+            .convert_and_cache(&source, dir)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_load_parallel_dequantizes_all_tensors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_converted_model(dir.path());
+        let loaded_counts = Arc::new(Mutex::new(Vec::new()));
+        let sink = loaded_counts.clone();
+        let mut config = InferenceEngineConfig::tiny(32);
+        config.n_threads = 3;
+
+        let tensors = GGUFLoader::new()
+            .with_engine_config(&config)
+            .with_progress(Arc::new(move |done, total| sink.lock().push((done, total))))
+            .load_parallel(&path)
+            .unwrap();
+
+        assert_eq!(tensors.len(), 6);
+        let weight = &tensors["blk.5.weight"];
+        assert_eq!(weight.len(), 64);
+        // Q8_0 error is bounded by half a quantization step of the block max
+        let expected = (5 * 64 + 63) as f32 / 8.0;
+        assert!((weight[63] - expected).abs() < expected / 127.0);
+
+        let mut counts = loaded_counts.lock().clone();
+        counts.sort();
+        assert_eq!(counts, (1..=6).map(|n| (n, 6)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_load_parallel_rejects_non_gguf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        std::fs::write(&path, b"not a gguf file").unwrap();
+        assert!(GGUFLoader::new().load_parallel(&path).is_err());
+    }
+
     #[test]
     #[ignore] // Only run when GGUF file available
     fn test_load_gpt_oss_20b_gguf() {
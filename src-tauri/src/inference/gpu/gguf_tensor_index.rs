@@ -0,0 +1,231 @@
+/// GGUF Tensor Index
+///
+/// Reads the tensor table of a GGUF v2/v3 file: every tensor's name, shape,
+/// type and absolute byte range, without touching the tensor data. Strings
+/// and counts are u64 as in llama.cpp; metadata is skipped except for
+/// `general.alignment`, which positions the data section.
+use super::gguf_loader::{GGUFDataType, GGUFLoader};
+use crate::error::{MinervaError, MinervaResult};
+use std::io::{Cursor, Read};
+
+const DEFAULT_ALIGNMENT: u64 = 32;
+/// GGUF metadata value type of an array
+const VALUE_TYPE_ARRAY: u32 = 9;
+const VALUE_TYPE_STRING: u32 = 8;
+
+/// Location of one tensor's data in the file
+#[derive(Debug, Clone)]
+pub struct TensorEntry {
+    pub name: String,
+    /// Row-major shape (outermost dimension first)
+    pub shape: Vec<usize>,
+    pub dtype: GGUFDataType,
+    /// Absolute offset of the first data byte
+    pub start: usize,
+}
+
+impl TensorEntry {
+    /// Number of values; `read_tensor_index` rejects shapes whose product
+    /// overflows, so this cannot wrap for parsed entries.
+    pub fn element_count(&self) -> usize {
+        self.shape.iter().product()
+    }
+}
+
+/// Parse the tensor table from the start of a GGUF image
+pub fn read_tensor_index(bytes: &[u8]) -> MinervaResult<Vec<TensorEntry>> {
+    let mut reader = Cursor::new(bytes);
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).map_err(truncated)?;
+    if &magic != b"GGUF" {
+        return Err(MinervaError::model_loading(
+            "Invalid GGUF magic number".to_string(),
+        ));
+    }
+    let version = read_u32(&mut reader)?;
+    if version < 2 {
+        return Err(MinervaError::UnsupportedFormat(format!(
+            "GGUF version {} is not supported",
+            version
+        )));
+    }
+    let tensor_count = read_u64(&mut reader)?;
+    let kv_count = read_u64(&mut reader)?;
+
+    let mut alignment = DEFAULT_ALIGNMENT;
+    for _ in 0..kv_count {
+        let key = read_string(&mut reader)?;
+        let value_type = read_u32(&mut reader)?;
+        if key == "general.alignment" && value_type == 4 {
+            alignment = u64::from(read_u32(&mut reader)?).max(1);
+        } else {
+            skip_value(&mut reader, value_type)?;
+        }
+    }
+
+    let mut entries = Vec::new();
+    for _ in 0..tensor_count {
+        let name = read_string(&mut reader)?;
+        let n_dims = read_u32(&mut reader)?;
+        let mut shape = (0..n_dims)
+            .map(|_| read_u64(&mut reader).and_then(|d| to_usize(d, &name)))
+            .collect::<MinervaResult<Vec<_>>>()?;
+        shape.reverse();
+        shape
+            .iter()
+            .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+            .ok_or_else(|| {
+                MinervaError::ValidationError(format!(
+                    "Tensor {} has shape {:?} whose element count overflows",
+                    name, shape
+                ))
+            })?;
+        let dtype = GGUFLoader::parse_dtype(read_u32(&mut reader)?)?;
+        let offset = read_u64(&mut reader)?;
+        entries.push((name, shape, dtype, offset));
+    }
+
+    let data_start = reader
+        .position()
+        .checked_next_multiple_of(alignment)
+        .ok_or_else(|| {
+            MinervaError::ValidationError(format!(
+                "GGUF data section overflows at alignment {}",
+                alignment
+            ))
+        })?;
+    entries
+        .into_iter()
+        .map(|(name, shape, dtype, offset)| {
+            let start = data_start.checked_add(offset).ok_or_else(|| {
+                MinervaError::ValidationError(format!(
+                    "Tensor {} offset {} overflows the file",
+                    name, offset
+                ))
+            })?;
+            Ok(TensorEntry {
+                start: to_usize(start, &name)?,
+                name,
+                shape,
+                dtype,
+            })
+        })
+        .collect()
+}
+
+fn to_usize(value: u64, tensor: &str) -> MinervaResult<usize> {
+    usize::try_from(value).map_err(|_| {
+        MinervaError::ValidationError(format!(
+            "Tensor {} value {} does not fit in usize",
+            tensor, value
+        ))
+    })
+}
+
+fn skip_value(reader: &mut Cursor<&[u8]>, value_type: u32) -> MinervaResult<()> {
+    let skip = match value_type {
+        0 | 1 | 7 => 1,
+        2 | 3 => 2,
+        4..=6 => 4,
+        10..=12 => 8,
+        VALUE_TYPE_STRING => read_u64(reader)?,
+        VALUE_TYPE_ARRAY => {
+            let element_type = read_u32(reader)?;
+            for _ in 0..read_u64(reader)? {
+                skip_value(reader, element_type)?;
+            }
+            0
+        }
+        other => {
+            return Err(MinervaError::model_loading(format!(
+                "Unknown GGUF value type: {}",
+                other
+            )));
+        }
+    };
+    let end = reader
+        .position()
+        .checked_add(skip)
+        .filter(|&end| end <= reader.get_ref().len() as u64)
+        .ok_or_else(|| truncated(std::io::ErrorKind::UnexpectedEof.into()))?;
+    reader.set_position(end);
+    Ok(())
+}
+
+fn read_u32(reader: &mut Cursor<&[u8]>) -> MinervaResult<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes).map_err(truncated)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut Cursor<&[u8]>) -> MinervaResult<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes).map_err(truncated)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_string(reader: &mut Cursor<&[u8]>) -> MinervaResult<String> {
+    let len = read_u64(reader)?;
+    let start = reader.position();
+    let end = start
+        .checked_add(len)
+        .filter(|&end| end <= reader.get_ref().len() as u64)
+        .ok_or_else(|| truncated(std::io::ErrorKind::UnexpectedEof.into()))?;
+    let bytes = &reader.get_ref()[start as usize..end as usize];
+    reader.set_position(end);
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn truncated(e: std::io::Error) -> MinervaError {
+    MinervaError::model_loading_with("Truncated GGUF header".to_string(), e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// GGUF v3 header with no metadata and a single f32 tensor
+    fn single_tensor(dims: &[u64], offset: u64) -> Vec<u8> {
+        let mut bytes = b"GGUF".to_vec();
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&1u64.to_le_bytes());
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&1u64.to_le_bytes());
+        bytes.push(b'w');
+        bytes.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+        for d in dims {
+            bytes.extend_from_slice(&d.to_le_bytes());
+        }
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_reads_single_tensor() {
+        let entries = read_tensor_index(&single_tensor(&[4, 2], 64)).unwrap();
+        assert_eq!(entries[0].shape, vec![2, 4]);
+        assert_eq!(entries[0].element_count(), 8);
+        assert_eq!(entries[0].start % DEFAULT_ALIGNMENT as usize, 0);
+    }
+
+    #[test]
+    fn test_rejects_overflowing_shape() {
+        let err = read_tensor_index(&single_tensor(&[u64::MAX / 2, 4], 0)).unwrap_err();
+        assert!(matches!(err, MinervaError::ValidationError(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_rejects_overflowing_offset() {
+        let err = read_tensor_index(&single_tensor(&[4], u64::MAX)).unwrap_err();
+        assert!(matches!(err, MinervaError::ValidationError(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_rejects_oversized_string_length() {
+        let mut bytes = single_tensor(&[4], 0);
+        // Tensor name length sits after magic, version and the two counts
+        bytes[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(read_tensor_index(&bytes).is_err());
+    }
+}
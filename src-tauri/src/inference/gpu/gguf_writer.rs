@@ -0,0 +1,329 @@
+/// GGUF v3 Writer
+///
+/// Serializes f32 tensors into a llama.cpp-compatible GGUF file: header,
+/// metadata, tensor infos, then 32-byte aligned tensor data. Type ids and
+/// block layouts follow ggml (F32 = 0, F16 = 1, Q8_0 = 8 with blocks of 32
+/// int8 values sharing one f16 scale). Dimensions are written innermost
+/// first, the reverse of the row-major shapes used elsewhere.
+use std::io::{self, Write};
+
+pub const GGUF_VERSION: u32 = 3;
+pub const GGUF_ALIGNMENT: usize = 32;
+
+/// Values per Q8_0 block
+const QK8_0: usize = 32;
+/// Bytes per Q8_0 block: f16 scale + 32 int8
+const Q8_0_BLOCK_BYTES: usize = 2 + QK8_0;
+
+const METADATA_TYPE_U32: u32 = 4;
+const METADATA_TYPE_I32: u32 = 5;
+const METADATA_TYPE_F32: u32 = 6;
+const METADATA_TYPE_STRING: u32 = 8;
+const METADATA_TYPE_ARRAY: u32 = 9;
+
+/// Target encoding for converted weights
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum QuantizationType {
+    F32,
+    F16,
+    Q8_0,
+}
+
+impl QuantizationType {
+    /// Lowercase name used in file names
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::F16 => "f16",
+            Self::Q8_0 => "q8_0",
+        }
+    }
+
+    /// `general.file_type` value (llama.cpp `LLAMA_FTYPE_*`)
+    pub fn file_type(&self) -> u32 {
+        match self {
+            Self::F32 => 0,
+            Self::F16 => 1,
+            Self::Q8_0 => 7,
+        }
+    }
+
+    /// Encoding used for a tensor of `shape`
+    ///
+    /// Like llama.cpp, 1-D tensors (norms, biases) stay F32, and Q8_0 needs
+    /// rows that are a whole number of blocks.
+    pub fn for_tensor(self, shape: &[usize]) -> Self {
+        let row_len = shape.last().copied().unwrap_or(0);
+        match self {
+            _ if shape.len() < 2 => Self::F32,
+            Self::Q8_0 if row_len % QK8_0 != 0 => Self::F32,
+            other => other,
+        }
+    }
+
+    fn ggml_type(&self) -> u32 {
+        match self {
+            Self::F32 => 0,
+            Self::F16 => 1,
+            Self::Q8_0 => 8,
+        }
+    }
+
+    /// Encoded size of `elements` values
+    pub fn byte_size(&self, elements: usize) -> usize {
+        match self {
+            Self::F32 => elements * 4,
+            Self::F16 => elements * 2,
+            Self::Q8_0 => elements.div_ceil(QK8_0) * Q8_0_BLOCK_BYTES,
+        }
+    }
+
+    /// Encode `values` in this type's little-endian layout
+    pub fn encode(&self, values: &[f32]) -> Vec<u8> {
+        match self {
+            Self::F32 => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            Self::F16 => values
+                .iter()
+                .flat_map(|&v| f32_to_f16(v).to_le_bytes())
+                .collect(),
+            Self::Q8_0 => values.chunks(QK8_0).flat_map(encode_q8_0_block).collect(),
+        }
+    }
+}
+
+/// Metadata value written into the GGUF key-value section
+#[derive(Debug, Clone)]
+pub enum MetadataValue {
+    U32(u32),
+    F32(f32),
+    String(String),
+    StringArray(Vec<String>),
+    I32Array(Vec<i32>),
+    F32Array(Vec<f32>),
+}
+
+/// A tensor's name, row-major shape and encoding
+#[derive(Debug, Clone)]
+pub struct TensorPlan {
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub encoding: QuantizationType,
+}
+
+impl TensorPlan {
+    pub fn byte_size(&self) -> usize {
+        self.encoding.byte_size(self.shape.iter().product())
+    }
+}
+
+/// Streams a GGUF file: header first, then each tensor's data in plan order
+pub struct GGUFWriter<W: Write> {
+    out: W,
+    written: usize,
+}
+
+impl<W: Write> GGUFWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, written: 0 }
+    }
+
+    /// Write header, metadata and tensor infos, padded to the data section
+    pub fn write_header(
+        &mut self,
+        metadata: &[(impl AsRef<str>, MetadataValue)],
+        tensors: &[TensorPlan],
+    ) -> io::Result<()> {
+        self.put(b"GGUF")?;
+        self.put(&GGUF_VERSION.to_le_bytes())?;
+        self.put(&(tensors.len() as u64).to_le_bytes())?;
+        self.put(&(metadata.len() as u64).to_le_bytes())?;
+        for (key, value) in metadata {
+            self.put_string(key.as_ref())?;
+            match value {
+                MetadataValue::U32(v) => {
+                    self.put(&METADATA_TYPE_U32.to_le_bytes())?;
+                    self.put(&v.to_le_bytes())?;
+                }
+                MetadataValue::F32(v) => {
+                    self.put(&METADATA_TYPE_F32.to_le_bytes())?;
+                    self.put(&v.to_le_bytes())?;
+                }
+                MetadataValue::String(s) => {
+                    self.put(&METADATA_TYPE_STRING.to_le_bytes())?;
+                    self.put_string(s)?;
+                }
+                MetadataValue::StringArray(values) => {
+                    self.put_array_header(METADATA_TYPE_STRING, values.len())?;
+                    for s in values {
+                        self.put_string(s)?;
+                    }
+                }
+                MetadataValue::I32Array(values) => {
+                    self.put_array_header(METADATA_TYPE_I32, values.len())?;
+                    for v in values {
+                        self.put(&v.to_le_bytes())?;
+                    }
+                }
+                MetadataValue::F32Array(values) => {
+                    self.put_array_header(METADATA_TYPE_F32, values.len())?;
+                    for v in values {
+                        self.put(&v.to_le_bytes())?;
+                    }
+                }
+            }
+        }
+
+        let mut offset = 0usize;
+        for tensor in tensors {
+            self.put_string(&tensor.name)?;
+            self.put(&(tensor.shape.len() as u32).to_le_bytes())?;
+            for dim in tensor.shape.iter().rev() {
+                self.put(&(*dim as u64).to_le_bytes())?;
+            }
+            self.put(&tensor.encoding.ggml_type().to_le_bytes())?;
+            self.put(&(offset as u64).to_le_bytes())?;
+            offset += tensor.byte_size().next_multiple_of(GGUF_ALIGNMENT);
+        }
+        self.pad()
+    }
+
+    /// Encode and write one tensor's values, padded to the next alignment
+    pub fn write_tensor(&mut self, tensor: &TensorPlan, values: &[f32]) -> io::Result<usize> {
+        let bytes = tensor.encoding.encode(values);
+        self.put(&bytes)?;
+        self.pad()?;
+        Ok(bytes.len())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.out.write_all(bytes)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+
+    fn put_string(&mut self, value: &str) -> io::Result<()> {
+        self.put(&(value.len() as u64).to_le_bytes())?;
+        self.put(value.as_bytes())
+    }
+
+    fn put_array_header(&mut self, element_type: u32, len: usize) -> io::Result<()> {
+        self.put(&METADATA_TYPE_ARRAY.to_le_bytes())?;
+        self.put(&element_type.to_le_bytes())?;
+        self.put(&(len as u64).to_le_bytes())
+    }
+
+    fn pad(&mut self) -> io::Result<()> {
+        let padding = self.written.next_multiple_of(GGUF_ALIGNMENT) - self.written;
+        self.put(&[0u8; GGUF_ALIGNMENT][..padding])
+    }
+}
+
+/// Symmetric 8-bit block: scale = max |x| / 127
+fn encode_q8_0_block(block: &[f32]) -> Vec<u8> {
+    let amax = block.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+    let scale = amax / 127.0;
+    let inverse = if scale == 0.0 { 0.0 } else { 1.0 / scale };
+    let mut bytes = f32_to_f16(scale).to_le_bytes().to_vec();
+    bytes.extend((0..QK8_0).map(|i| {
+        let value = block.get(i).copied().unwrap_or(0.0);
+        (value * inverse).round() as i8 as u8
+    }));
+    bytes
+}
+
+/// Narrow f32 to IEEE 754 half precision bits, rounding to nearest
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+        let full = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let rounded = (full >> shift) + ((full >> (shift - 1)) & 1);
+        return sign | rounded as u16;
+    }
+    let half = ((half_exponent as u32) << 10) | (mantissa >> 13);
+    sign | (half + ((mantissa >> 12) & 1)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f32_to_f16() {
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(1e6), 0x7c00);
+        assert_eq!(f32_to_f16(2f32.powi(-24)), 0x0001);
+        assert_eq!(f32_to_f16(0.0), 0);
+    }
+
+    #[test]
+    fn test_encoding_for_tensor() {
+        let q8 = QuantizationType::Q8_0;
+        assert_eq!(q8.for_tensor(&[64]), QuantizationType::F32);
+        assert_eq!(q8.for_tensor(&[4, 30]), QuantizationType::F32);
+        assert_eq!(q8.for_tensor(&[4, 64]), QuantizationType::Q8_0);
+        assert_eq!(
+            QuantizationType::F16.for_tensor(&[4, 30]),
+            QuantizationType::F16
+        );
+    }
+
+    #[test]
+    fn test_q8_0_block_layout() {
+        let values: Vec<f32> = (0..32).map(|i| i as f32 - 16.0).collect();
+        let bytes = QuantizationType::Q8_0.encode(&values);
+
+        assert_eq!(bytes.len(), QuantizationType::Q8_0.byte_size(32));
+        assert_eq!(
+            u16::from_le_bytes([bytes[0], bytes[1]]),
+            f32_to_f16(16.0 / 127.0)
+        );
+        assert_eq!(bytes[2] as i8, -127);
+        assert_eq!(bytes[2 + 16] as i8, 0);
+    }
+
+    #[test]
+    fn test_header_and_data_alignment() {
+        let plan = vec![TensorPlan {
+            name: "w".to_string(),
+            shape: vec![2, 3],
+            encoding: QuantizationType::F32,
+        }];
+        let mut writer = GGUFWriter::new(Vec::new());
+        writer
+            .write_header(
+                &[("general.name", MetadataValue::String("t".into()))],
+                &plan,
+            )
+            .unwrap();
+        writer.write_tensor(&plan[0], &[0.0; 6]).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        assert_eq!(&bytes[..4], b"GGUF");
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 3);
+        assert_eq!(bytes.len() % GGUF_ALIGNMENT, 0);
+    }
+}
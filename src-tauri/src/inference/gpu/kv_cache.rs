@@ -5,6 +5,8 @@
 ///
 /// Without KV cache: O(n²) complexity (recompute all tokens)
 /// With KV cache: O(n) complexity (only new token)
+pub use super::disk_kv_cache::DiskKVCache;
+pub use super::tiered_kv_cache::{KVTierLevel, TieredKVCache, TieredKVConfig};
 use ndarray::Array2;
 
 /// KV Cache stores cached keys and values
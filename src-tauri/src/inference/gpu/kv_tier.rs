@@ -0,0 +1,215 @@
+/// In-Memory KV Cache Tier
+///
+/// A fixed number of slots, each holding one sequence position's keys and
+/// values for every layer. Positions are mapped to slots on demand and the
+/// least recently used position is the one handed to the next tier down.
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::kv_cache::{KVCache, KVCacheConfig, KVStoreParams};
+use std::collections::{BTreeMap, HashMap};
+
+/// Keys and values of one position, indexed by layer
+pub type PositionKV = Vec<(Vec<f32>, Vec<f32>)>;
+
+/// Position to slot mapping with least-recently-used ordering
+#[derive(Debug)]
+pub struct SlotIndex {
+    slots: HashMap<usize, (usize, u64)>,
+    by_recency: BTreeMap<u64, usize>,
+    free: Vec<usize>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl SlotIndex {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: HashMap::new(),
+            by_recency: BTreeMap::new(),
+            free: (0..capacity).rev().collect(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    pub fn slot(&self, pos: usize) -> Option<usize> {
+        self.slots.get(&pos).map(|&(slot, _)| slot)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.free.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Mark `pos` as most recently used
+    pub fn touch(&mut self, pos: usize) {
+        let tick = self.tick();
+        if let Some((_, used)) = self.slots.get_mut(&pos) {
+            self.by_recency.remove(used);
+            *used = tick;
+            self.by_recency.insert(tick, pos);
+        }
+    }
+
+    /// Give `pos` a free slot, `None` when the tier is full
+    pub fn assign(&mut self, pos: usize) -> Option<usize> {
+        let slot = self.free.pop()?;
+        let tick = self.tick();
+        self.slots.insert(pos, (slot, tick));
+        self.by_recency.insert(tick, pos);
+        Some(slot)
+    }
+
+    /// Free the slot held by `pos`
+    pub fn release(&mut self, pos: usize) -> Option<usize> {
+        let (slot, used) = self.slots.remove(&pos)?;
+        self.by_recency.remove(&used);
+        self.free.push(slot);
+        Some(slot)
+    }
+
+    pub fn least_recent(&self) -> Option<usize> {
+        self.by_recency.values().next().copied()
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+/// RAM-resident tier backed by a slot-indexed [`KVCache`]
+#[derive(Debug)]
+pub struct KVTier {
+    cache: KVCache,
+    index: SlotIndex,
+}
+
+impl KVTier {
+    /// `config.max_seq_len` is the number of positions the tier holds
+    pub fn new(config: KVCacheConfig) -> Self {
+        Self {
+            cache: KVCache::new(config),
+            index: SlotIndex::new(config.max_seq_len),
+        }
+    }
+
+    pub fn contains(&self, pos: usize) -> bool {
+        self.index.slot(pos).is_some()
+    }
+
+    pub fn index(&self) -> &SlotIndex {
+        &self.index
+    }
+
+    /// Store `pos` in a free slot
+    pub fn put(&mut self, pos: usize, kv: PositionKV) -> MinervaResult<()> {
+        let slot = self.index.assign(pos).ok_or_else(|| {
+            MinervaError::InferenceError(format!("No free KV slot for position {}", pos))
+        })?;
+        for (layer, (k, v)) in kv.into_iter().enumerate() {
+            self.cache
+                .store(KVStoreParams::builder(k, v).layer(layer).pos(slot).build())?;
+        }
+        Ok(())
+    }
+
+    /// Remove `pos`, returning its keys and values
+    pub fn take(&mut self, pos: usize) -> MinervaResult<Option<PositionKV>> {
+        let Some(slot) = self.index.release(pos) else {
+            return Ok(None);
+        };
+        let kv = (0..self.cache.config().num_layers)
+            .map(|layer| self.cache.get(layer, slot))
+            .collect::<MinervaResult<PositionKV>>()?;
+        Ok(Some(kv))
+    }
+
+    /// Remove the least recently used position
+    pub fn evict_lru(&mut self) -> MinervaResult<Option<(usize, PositionKV)>> {
+        let Some(pos) = self.index.least_recent() else {
+            return Ok(None);
+        };
+        Ok(self.take(pos)?.map(|kv| (pos, kv)))
+    }
+
+    /// Overwrite one layer of a resident position
+    pub fn write(
+        &mut self,
+        layer: usize,
+        pos: usize,
+        kv: (Vec<f32>, Vec<f32>),
+    ) -> MinervaResult<()> {
+        let slot = self.resident_slot(pos)?;
+        self.index.touch(pos);
+        self.cache.store(
+            KVStoreParams::builder(kv.0, kv.1)
+                .layer(layer)
+                .pos(slot)
+                .build(),
+        )
+    }
+
+    /// Read one layer of a resident position
+    pub fn read(&mut self, layer: usize, pos: usize) -> MinervaResult<(Vec<f32>, Vec<f32>)> {
+        let slot = self.resident_slot(pos)?;
+        self.index.touch(pos);
+        self.cache.get(layer, slot)
+    }
+
+    fn resident_slot(&self, pos: usize) -> MinervaResult<usize> {
+        self.index.slot(pos).ok_or_else(|| {
+            MinervaError::InferenceError(format!("Position {} not resident in tier", pos))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_index_lru_order() {
+        let mut index = SlotIndex::new(2);
+        index.assign(10);
+        index.assign(20);
+        assert!(index.is_full());
+        assert!(index.assign(30).is_none());
+
+        index.touch(10);
+        assert_eq!(index.least_recent(), Some(20));
+        index.release(20);
+        assert!(index.assign(30).is_some());
+        assert_eq!(index.least_recent(), Some(10));
+    }
+
+    #[test]
+    fn test_tier_put_take_round_trip() {
+        let mut tier = KVTier::new(KVCacheConfig {
+            num_layers: 2,
+            max_seq_len: 1,
+            num_heads: 1,
+            head_dim: 2,
+        });
+        let kv = vec![
+            (vec![1.0, 2.0], vec![3.0, 4.0]),
+            (vec![5.0, 6.0], vec![7.0, 8.0]),
+        ];
+        tier.put(7, kv.clone()).unwrap();
+        assert!(tier.put(8, kv.clone()).is_err());
+        assert_eq!(tier.read(1, 7).unwrap(), kv[1]);
+
+        assert_eq!(tier.take(7).unwrap(), Some(kv));
+        assert!(!tier.contains(7));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::inference::gpu::kv_cache::KVCache;
 /// Transformer layers implementation
 ///
-/// Core transformer components: attention, MLP, normalization
+/// Core transformer components: attention, MLP, normalization, RoPE
 use ndarray::Array2;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
 
 /// RMS Normalization (like LayerNorm but simpler)
 /// Used in Llama and Mistral architectures
@@ -86,6 +88,100 @@ pub fn silu(x: &Array2<f32>) -> Array2<f32> {
     x.mapv(|v| v / (1.0 + (-v).exp()))
 }
 
+/// Rotary embedding settings for one sequence position
+#[derive(Debug, Clone, Copy)]
+pub struct RotaryParams {
+    pub pos: usize,
+    pub head_dim: usize,
+    pub theta_base: f32,
+}
+
+impl RotaryParams {
+    /// (cos, sin) of each pair within a head: angle = pos * base^(-2j/d)
+    fn rotations(&self) -> Vec<(f32, f32)> {
+        (0..self.head_dim / 2)
+            .map(|j| {
+                let freq = self.theta_base.powf(-2.0 * j as f32 / self.head_dim as f32);
+                let angle = self.pos as f32 * freq;
+                (angle.cos(), angle.sin())
+            })
+            .collect()
+    }
+}
+
+/// Rotate interleaved (even, odd) pairs of every head in `query` and `key`
+///
+/// Uses AVX2 when the CPU supports it; both paths produce identical bits.
+pub fn apply_rope(query: &mut [f32], key: &mut [f32], params: RotaryParams) {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        unsafe { apply_rope_avx2(query, key, params) };
+        return;
+    }
+    apply_rope_scalar(query, key, params);
+}
+
+/// Portable RoPE, one pair at a time
+pub fn apply_rope_scalar(query: &mut [f32], key: &mut [f32], params: RotaryParams) {
+    if params.head_dim < 2 {
+        return;
+    }
+    let rotations = params.rotations();
+    for x in [query, key] {
+        for head in x.chunks_exact_mut(2 * rotations.len()) {
+            for (pair, &(cos, sin)) in head.chunks_exact_mut(2).zip(&rotations) {
+                let (x0, x1) = (pair[0], pair[1]);
+                pair[0] = x0 * cos - x1 * sin;
+                pair[1] = x0 * sin + x1 * cos;
+            }
+        }
+    }
+}
+
+/// RoPE rotating four pairs per 256-bit register
+///
+/// Each register computes `x * cos + swap(x) * sin` with `cos` duplicated
+/// per pair and `sin` negated on even lanes, which rounds exactly like the
+/// scalar `x0 * cos - x1 * sin` / `x0 * sin + x1 * cos`. No FMA is used, so
+/// results match `apply_rope_scalar` bit for bit.
+///
+/// # Safety
+/// The CPU must support AVX2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+pub unsafe fn apply_rope_avx2(query: &mut [f32], key: &mut [f32], params: RotaryParams) {
+    if params.head_dim < 2 {
+        return;
+    }
+    let rotations = params.rotations();
+    let cos: Vec<f32> = rotations.iter().flat_map(|&(c, _)| [c, c]).collect();
+    let sin: Vec<f32> = rotations.iter().flat_map(|&(_, s)| [-s, s]).collect();
+    for x in [query, key] {
+        for head in x.chunks_exact_mut(cos.len()) {
+            let simd_len = head.len() - head.len() % 8;
+            for i in (0..simd_len).step_by(8) {
+                // SAFETY: i + 8 <= simd_len <= head.len() == cos.len() == sin.len()
+                unsafe {
+                    let v = _mm256_loadu_ps(head.as_ptr().add(i));
+                    let c = _mm256_loadu_ps(cos.as_ptr().add(i));
+                    let s = _mm256_loadu_ps(sin.as_ptr().add(i));
+                    // [x1, x0, x3, x2, ...]
+                    let swapped = _mm256_permute_ps::<0b1011_0001>(v);
+                    let out = _mm256_add_ps(_mm256_mul_ps(v, c), _mm256_mul_ps(swapped, s));
+                    _mm256_storeu_ps(head.as_mut_ptr().add(i), out);
+                }
+            }
+            for (j, pair) in head[simd_len..].chunks_exact_mut(2).enumerate() {
+                let (cos, sin) = rotations[simd_len / 2 + j];
+                let (x0, x1) = (pair[0], pair[1]);
+                pair[0] = x0 * cos - x1 * sin;
+                pair[1] = x0 * sin + x1 * cos;
+            }
+        }
+    }
+}
+
 /// MLP (Feed-Forward Network)
 /// Uses SwiGLU: (gate_proj(x) * silu) * up_proj(x) -> down_proj
 pub fn mlp(
@@ -180,4 +276,56 @@ mod tests {
             assert!((sum - 1.0).abs() < 1e-5);
         }
     }
+
+    fn rope_input(len: usize) -> (Vec<f32>, Vec<f32>) {
+        let query = (0..len).map(|i| (i as f32 * 0.37).sin() * 3.0).collect();
+        let key = (0..len).map(|i| (i as f32 * 0.11).cos() - 0.5).collect();
+        (query, key)
+    }
+
+    #[test]
+    fn test_rope_position_zero_is_identity() {
+        let (mut query, mut key) = rope_input(16);
+        let (expected_q, expected_k) = (query.clone(), key.clone());
+        let params = RotaryParams {
+            pos: 0,
+            head_dim: 8,
+            theta_base: 10_000.0,
+        };
+        apply_rope(&mut query, &mut key, params);
+
+        assert_eq!(query, expected_q);
+        assert_eq!(key, expected_k);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_rope_avx2_matches_scalar_bitwise() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+        // head_dim 12 leaves a scalar tail after one register per head
+        for head_dim in [64, 16, 12] {
+            let params = RotaryParams {
+                pos: 37,
+                head_dim,
+                theta_base: 10_000.0,
+            };
+            let (mut scalar_q, mut scalar_k) = rope_input(head_dim * (64 / head_dim));
+            let (mut simd_q, mut simd_k) = (scalar_q.clone(), scalar_k.clone());
+
+            apply_rope_scalar(&mut scalar_q, &mut scalar_k, params);
+            // SAFETY: AVX2 support checked above
+            unsafe { apply_rope_avx2(&mut simd_q, &mut simd_k, params) };
+
+            let bits = |v: &[f32]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+            assert_eq!(
+                bits(&simd_q),
+                bits(&scalar_q),
+                "query, head_dim {}",
+                head_dim
+            );
+            assert_eq!(bits(&simd_k), bits(&scalar_k), "key, head_dim {}", head_dim);
+        }
+    }
 }
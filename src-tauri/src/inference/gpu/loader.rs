@@ -18,15 +18,15 @@ impl SafeTensorsLoader {
     /// Returns 2D array: weights stay 2D, 1D become (size, 1)
     pub fn load_tensor(path: &Path, name: &str) -> MinervaResult<Array2<f32>> {
         let buffer = std::fs::read(path).map_err(|_| {
-            MinervaError::ModelLoadingError("failed to read safetensors file".to_string())
+            MinervaError::model_loading("failed to read safetensors file".to_string())
         })?;
         let st = SafeTensors::deserialize(&buffer).map_err(|_| {
-            MinervaError::ModelLoadingError("failed to deserialize safetensors".to_string())
+            MinervaError::model_loading("failed to deserialize safetensors".to_string())
         })?;
 
         let view = st
             .tensor(name)
-            .map_err(|_| MinervaError::ModelLoadingError(format!("tensor {} not found", name)))?;
+            .map_err(|_| MinervaError::model_loading(format!("tensor {} not found", name)))?;
         let shape = view.shape();
 
         // SafeTensors stores data as bytes, need to interpret as f32
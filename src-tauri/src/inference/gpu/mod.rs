@@ -1,3 +1,4 @@
+#[cfg(not(cpu_only))]
 pub mod attention_kernel;
 /// GPU-accelerated inference backend
 ///
@@ -9,27 +10,46 @@ pub mod attention_kernel;
 /// - INT8 quantization support (planned)
 /// - Request batching (planned)
 /// - Speculative decoding (planned)
+///
+/// The compute backend, layers and kernels are left out of `cpu-only`
+/// builds; the GGUF/SafeTensors readers, KV caches and OpenAI types are not.
+#[cfg(not(cpu_only))]
 pub mod backend;
 pub mod config;
+pub mod disk_kv_cache;
 pub mod format_loader;
+pub mod gguf_converter;
+pub mod gguf_dequant;
+pub mod gguf_hf_metadata;
 pub mod gguf_loader;
+pub mod gguf_tensor_index;
+pub mod gguf_writer;
+#[cfg(not(cpu_only))]
 pub mod inference;
 pub mod kv_cache;
+pub mod kv_tier;
+#[cfg(not(cpu_only))]
 pub mod layers;
 pub mod loader;
 pub mod openai_api;
+pub mod tiered_kv_cache;
 pub mod tool_api;
 pub mod tool_optimized_loader;
 
+#[cfg(not(cpu_only))]
 pub use attention_kernel::{causal_mask, flash_attention_approx, gqa_attention, softmax_1d};
+#[cfg(not(cpu_only))]
 pub use backend::GPUSafeTensorsBackend;
 pub use config::ModelConfig;
 pub use format_loader::{
     FormatLoader, LoadedModel, ModelConfig as UnifiedModelConfig, ModelFormat,
 };
+pub use gguf_converter::GGUFConverter;
 pub use gguf_loader::GGUFLoader;
+pub use gguf_writer::QuantizationType;
+#[cfg(not(cpu_only))]
 pub use inference::{FastInferenceEngine, InferenceMetrics, KVCacheOptimized};
-pub use kv_cache::KVCache;
+pub use kv_cache::{DiskKVCache, KVCache, KVTierLevel, TieredKVCache, TieredKVConfig};
 pub use loader::SafeTensorsLoader;
 pub use openai_api::{
     OpenAIAPI, OpenAICompletionRequest, OpenAICompletionResponse, OpenAIListModelsResponse,
@@ -4,6 +4,7 @@
 /// Works seamlessly with any OpenAI-compatible tool/client
 use super::tool_optimized_loader::ToolOptimizedLoader;
 use crate::error::{MinervaError, MinervaResult};
+use crate::models::{ModelCapabilities, ModelInfo};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -24,6 +25,32 @@ pub struct OpenAIModelInfo {
     pub file_size_mb: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tensor_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<usize>,
+    #[serde(default)]
+    pub capabilities: ModelCapabilities,
+}
+
+impl From<ModelInfo> for OpenAIModelInfo {
+    fn from(info: ModelInfo) -> Self {
+        Self {
+            id: info.id,
+            object: info.object,
+            created: info.created,
+            owned_by: info.owned_by,
+            permission: vec![],
+            root: None,
+            parent: None,
+            quantization: None,
+            file_size_mb: None,
+            tensor_count: None,
+            context_window: info.context_window,
+            max_output_tokens: info.max_output_tokens,
+            capabilities: info.capabilities,
+        }
+    }
 }
 
 /// OpenAI API compatible list response
@@ -102,6 +129,9 @@ impl OpenAIAPI {
             quantization: Some(loader.quantization),
             file_size_mb: Some(loader.file_size_mb),
             tensor_count: Some(loader.tensor_count),
+            context_window: None,
+            max_output_tokens: None,
+            capabilities: ModelCapabilities::default(),
         };
 
         Ok(OpenAIListModelsResponse {
@@ -128,6 +158,9 @@ impl OpenAIAPI {
             quantization: Some(loader.quantization),
             file_size_mb: Some(loader.file_size_mb),
             tensor_count: Some(loader.tensor_count),
+            context_window: None,
+            max_output_tokens: None,
+            capabilities: ModelCapabilities::default(),
         })
     }
 
@@ -215,6 +248,9 @@ mod tests {
             quantization: Some("MXFP4".to_string()),
             file_size_mb: Some(12109.6),
             tensor_count: Some(459),
+            context_window: None,
+            max_output_tokens: None,
+            capabilities: ModelCapabilities::default(),
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -238,6 +274,9 @@ mod tests {
                 quantization: Some("MXFP4".to_string()),
                 file_size_mb: Some(12109.6),
                 tensor_count: Some(459),
+                context_window: None,
+                max_output_tokens: None,
+                capabilities: ModelCapabilities::default(),
             }],
         };
 
@@ -246,6 +285,27 @@ mod tests {
         assert!(json.contains("data"));
     }
 
+    #[test]
+    fn test_from_model_info_carries_capabilities() {
+        let info = OpenAIModelInfo::from(ModelInfo {
+            id: "llama-3-8b".to_string(),
+            object: "model".to_string(),
+            created: 0,
+            owned_by: "local".to_string(),
+            context_window: Some(8192),
+            max_output_tokens: Some(2048),
+            capabilities: ModelCapabilities {
+                chat_completion: true,
+                ..Default::default()
+            },
+        });
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["context_window"], 8192);
+        assert_eq!(json["capabilities"]["chat_completion"], true);
+        assert_eq!(json["capabilities"]["embeddings"], false);
+    }
+
     #[test]
     fn test_model_registry() {
         let mut registry = OpenAIModelRegistry::new();
@@ -0,0 +1,244 @@
+/// Multi-Level KV Cache
+///
+/// Long contexts (e.g. 128K tokens) rarely fit in VRAM, so positions live in
+/// one of three tiers: VRAM for the working set, system RAM below it, and an
+/// optional memory-mapped file on disk for the coldest positions. Reads are
+/// transparent: touching a position promotes it disk → RAM → VRAM, and each
+/// full tier demotes its least recently used position to the next one down.
+/// Positions pushed out of the last tier are dropped.
+use super::disk_kv_cache::DiskKVCache;
+use super::kv_tier::{KVTier, PositionKV};
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::kv_cache::KVCacheConfig;
+use std::path::PathBuf;
+
+/// Tier sizes, counted in sequence positions
+#[derive(Debug, Clone)]
+pub struct TieredKVConfig {
+    pub num_layers: usize,
+    pub num_heads: usize,
+    pub head_dim: usize,
+    pub vram_positions: usize,
+    pub ram_positions: usize,
+    /// Backing file and capacity of the disk tier, if any
+    pub disk: Option<(PathBuf, usize)>,
+}
+
+impl TieredKVConfig {
+    fn tier(&self, positions: usize) -> KVCacheConfig {
+        KVCacheConfig {
+            num_layers: self.num_layers,
+            max_seq_len: positions,
+            num_heads: self.num_heads,
+            head_dim: self.head_dim,
+        }
+    }
+}
+
+/// Where a cached position currently lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KVTierLevel {
+    Vram,
+    Ram,
+    Disk,
+}
+
+/// KV cache spread over VRAM, RAM and disk tiers
+#[derive(Debug)]
+pub struct TieredKVCache {
+    pub vram: KVTier,
+    pub ram: KVTier,
+    pub disk: Option<DiskKVCache>,
+    num_layers: usize,
+    values_per_layer: usize,
+}
+
+impl TieredKVCache {
+    pub fn new(config: &TieredKVConfig) -> MinervaResult<Self> {
+        if config.vram_positions == 0 {
+            return Err(MinervaError::InferenceError(
+                "VRAM KV tier needs at least one position".to_string(),
+            ));
+        }
+        let disk = match &config.disk {
+            Some((path, positions)) if *positions > 0 => {
+                Some(DiskKVCache::create(path, config.tier(*positions))?)
+            }
+            _ => None,
+        };
+        Ok(Self {
+            vram: KVTier::new(config.tier(config.vram_positions)),
+            ram: KVTier::new(config.tier(config.ram_positions)),
+            disk,
+            num_layers: config.num_layers,
+            values_per_layer: config.num_heads * config.head_dim,
+        })
+    }
+
+    /// Tier currently holding `pos`
+    pub fn tier_of(&self, pos: usize) -> Option<KVTierLevel> {
+        if self.vram.contains(pos) {
+            Some(KVTierLevel::Vram)
+        } else if self.ram.contains(pos) {
+            Some(KVTierLevel::Ram)
+        } else if self.disk.as_ref().is_some_and(|d| d.contains(pos)) {
+            Some(KVTierLevel::Disk)
+        } else {
+            None
+        }
+    }
+
+    /// Store one layer's keys and values for `pos` in VRAM
+    pub fn store(
+        &mut self,
+        layer: usize,
+        pos: usize,
+        kv: (Vec<f32>, Vec<f32>),
+    ) -> MinervaResult<()> {
+        self.promote(pos)?;
+        self.vram.write(layer, pos, kv)
+    }
+
+    /// Keys and values for `pos`, promoting the position to VRAM
+    pub fn get_kv(&mut self, layer: usize, pos: usize) -> MinervaResult<(Vec<f32>, Vec<f32>)> {
+        if self.tier_of(pos).is_none() {
+            return Err(MinervaError::InferenceError(format!(
+                "Position {} not in KV cache",
+                pos
+            )));
+        }
+        self.promote(pos)?;
+        self.vram.read(layer, pos)
+    }
+
+    /// Move `pos` into VRAM, starting it zeroed if it is not cached yet
+    fn promote(&mut self, pos: usize) -> MinervaResult<()> {
+        if self.vram.contains(pos) {
+            return Ok(());
+        }
+        let kv = match self.ram.take(pos)? {
+            Some(kv) => kv,
+            None => self
+                .disk
+                .as_mut()
+                .and_then(|disk| disk.take(pos))
+                .unwrap_or_else(|| self.empty_position()),
+        };
+        if self.vram.index().is_full()
+            && let Some((victim, victim_kv)) = self.vram.evict_lru()?
+        {
+            self.demote_to_ram(victim, victim_kv)?;
+        }
+        self.vram.put(pos, kv)
+    }
+
+    fn demote_to_ram(&mut self, pos: usize, kv: PositionKV) -> MinervaResult<()> {
+        if self.ram.index().capacity() == 0 {
+            return self.demote_to_disk(pos, kv);
+        }
+        if self.ram.index().is_full()
+            && let Some((victim, victim_kv)) = self.ram.evict_lru()?
+        {
+            self.demote_to_disk(victim, victim_kv)?;
+        }
+        self.ram.put(pos, kv)
+    }
+
+    fn demote_to_disk(&mut self, pos: usize, kv: PositionKV) -> MinervaResult<()> {
+        let Some(disk) = self.disk.as_mut() else {
+            tracing::debug!("KV position {} dropped: no disk tier", pos);
+            return Ok(());
+        };
+        if disk.index().is_full()
+            && let Some(dropped) = disk.evict_lru()
+        {
+            tracing::debug!("KV position {} dropped from disk tier", dropped);
+        }
+        disk.put(pos, &kv)
+    }
+
+    fn empty_position(&self) -> PositionKV {
+        let zeros = vec![0.0; self.values_per_layer];
+        vec![(zeros.clone(), zeros); self.num_layers]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(disk: Option<(PathBuf, usize)>) -> TieredKVConfig {
+        TieredKVConfig {
+            num_layers: 2,
+            num_heads: 1,
+            head_dim: 2,
+            vram_positions: 2,
+            ram_positions: 1,
+            disk,
+        }
+    }
+
+    fn fill(cache: &mut TieredKVCache, positions: usize) {
+        for pos in 0..positions {
+            let value = pos as f32;
+            for layer in 0..2 {
+                cache
+                    .store(layer, pos, (vec![value, value], vec![-value, -value]))
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_older_positions_spill_down_tiers() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = Some((dir.path().join("kv.bin"), 4));
+        let mut cache = TieredKVCache::new(&config(disk)).unwrap();
+        fill(&mut cache, 5);
+
+        assert_eq!(cache.tier_of(4), Some(KVTierLevel::Vram));
+        assert_eq!(cache.tier_of(3), Some(KVTierLevel::Vram));
+        assert_eq!(cache.tier_of(2), Some(KVTierLevel::Ram));
+        assert_eq!(cache.tier_of(0), Some(KVTierLevel::Disk));
+        assert_eq!(cache.tier_of(1), Some(KVTierLevel::Disk));
+    }
+
+    #[test]
+    fn test_get_kv_promotes_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = Some((dir.path().join("kv.bin"), 4));
+        let mut cache = TieredKVCache::new(&config(disk)).unwrap();
+        fill(&mut cache, 5);
+
+        assert_eq!(
+            cache.get_kv(1, 0).unwrap(),
+            (vec![0.0, 0.0], vec![-0.0, -0.0])
+        );
+        assert_eq!(
+            cache.get_kv(0, 1).unwrap(),
+            (vec![1.0, 1.0], vec![-1.0, -1.0])
+        );
+        assert_eq!(cache.tier_of(0), Some(KVTierLevel::Vram));
+        assert_eq!(cache.tier_of(1), Some(KVTierLevel::Vram));
+        assert_eq!(cache.tier_of(4), Some(KVTierLevel::Ram));
+        assert_eq!(cache.tier_of(3), Some(KVTierLevel::Disk));
+        assert_eq!(cache.get_kv(1, 3).unwrap().0, vec![3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_without_disk_oldest_positions_are_dropped() {
+        let mut cache = TieredKVCache::new(&config(None)).unwrap();
+        fill(&mut cache, 4);
+
+        assert_eq!(cache.tier_of(0), None);
+        assert!(cache.get_kv(0, 0).is_err());
+        assert_eq!(cache.get_kv(0, 1).unwrap().0, vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_zero_vram_rejected() {
+        let mut config = config(None);
+        config.vram_positions = 0;
+        assert!(TieredKVCache::new(&config).is_err());
+    }
+}
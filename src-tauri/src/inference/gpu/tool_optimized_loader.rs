@@ -141,7 +141,7 @@ impl ToolOptimizedLoader {
                 reader.seek(SeekFrom::Current(str_len))?
             }
             _ => {
-                return Err(MinervaError::ModelLoadingError(
+                return Err(MinervaError::model_loading(
                     "Unknown metadata type".to_string(),
                 ));
             }
@@ -196,7 +196,7 @@ impl StreamingTensorLoader {
     /// Get specific tensor by name (lazy load)
     pub fn get_tensor(&self, _name: &str) -> MinervaResult<Vec<f32>> {
         // Would seek to offset and read specific tensor
-        Err(MinervaError::ModelLoadingError(
+        Err(MinervaError::model_loading(
             "Lazy loading not yet implemented".to_string(),
         ))
     }
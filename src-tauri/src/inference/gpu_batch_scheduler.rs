@@ -3,8 +3,11 @@ use parking_lot::RwLock;
 ///
 /// This module provides GPU-accelerated batch processing using Metal framework on macOS.
 /// It includes GPU memory management, batch scheduling, and compute shader integration.
-use std::collections::VecDeque;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::time::{Duration, Instant};
 
 /// GPU memory allocation tracker
 #[derive(Clone, Debug)]
@@ -106,16 +109,63 @@ impl<T: Clone> GPUBatchItem<T> {
     }
 }
 
+/// Urgency tier of a batch request; higher tiers are served first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RequestPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl RequestPriority {
+    /// Next tier up, or `None` for `High`
+    pub fn promoted(self) -> Option<Self> {
+        match self {
+            RequestPriority::Low => Some(RequestPriority::Normal),
+            RequestPriority::Normal => Some(RequestPriority::High),
+            RequestPriority::High => None,
+        }
+    }
+}
+
+/// Longest a request may wait in a tier before it is promoted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxWaitTimes {
+    pub low: Duration,
+    pub normal: Duration,
+}
+
+impl MaxWaitTimes {
+    /// Wait limit for `priority`; `High` is never promoted
+    pub fn for_priority(&self, priority: RequestPriority) -> Option<Duration> {
+        match priority {
+            RequestPriority::Low => Some(self.low),
+            RequestPriority::Normal => Some(self.normal),
+            RequestPriority::High => None,
+        }
+    }
+}
+
+impl Default for MaxWaitTimes {
+    fn default() -> Self {
+        Self {
+            low: Duration::from_secs(10),
+            normal: Duration::from_secs(5),
+        }
+    }
+}
+
 /// GPU batch request in the scheduler
 #[derive(Clone, Debug)]
 pub struct GPUBatchRequest<T: Clone> {
     pub items: Vec<GPUBatchItem<T>>,
-    pub priority: u32,
+    pub priority: RequestPriority,
     pub timestamp: std::time::Instant,
 }
 
 impl<T: Clone> GPUBatchRequest<T> {
-    pub fn new(items: Vec<GPUBatchItem<T>>, priority: u32) -> Self {
+    pub fn new(items: Vec<GPUBatchItem<T>>, priority: RequestPriority) -> Self {
         Self {
             items,
             priority,
@@ -160,12 +210,70 @@ impl GPUComputePipeline {
     }
 }
 
+/// A queued request; the heap pops the highest priority, oldest first
+#[derive(Debug)]
+pub struct PendingRequest {
+    pub request: GPUBatchRequest<Vec<u8>>,
+    /// Current tier, which may be above `request.priority` after promotion
+    pub priority: RequestPriority,
+    sequence: u64,
+    tier_entered: Instant,
+}
+
+impl PendingRequest {
+    /// Move up one tier if the wait in the current tier exceeded its limit
+    fn promote_if_starved(&mut self, now: Instant, max_wait: &MaxWaitTimes) {
+        let (Some(limit), Some(next)) = (
+            max_wait.for_priority(self.priority),
+            self.priority.promoted(),
+        ) else {
+            return;
+        };
+        let waited = now.duration_since(self.tier_entered);
+        if waited >= limit {
+            tracing::info!(
+                "Promoted batch request #{} from {:?} to {:?} after waiting {:?}",
+                self.sequence,
+                self.priority,
+                next,
+                waited
+            );
+            self.priority = next;
+            self.tier_entered = now;
+        }
+    }
+}
+
+impl PartialEq for PendingRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PendingRequest {}
+
+impl PartialOrd for PendingRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingRequest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
 /// GPU batch scheduler
 pub struct GPUBatchScheduler {
     memory_pool: Arc<RwLock<GPUMemoryPool>>,
     pipeline: GPUComputePipeline,
     max_queue_size: usize,
-    queue: Arc<RwLock<VecDeque<GPUBatchRequest<Vec<u8>>>>>,
+    max_wait: MaxWaitTimes,
+    next_sequence: AtomicU64,
+    queue: Arc<RwLock<BinaryHeap<PendingRequest>>>,
 }
 
 impl GPUBatchScheduler {
@@ -174,10 +282,18 @@ impl GPUBatchScheduler {
             memory_pool: Arc::new(RwLock::new(GPUMemoryPool::new(total_gpu_memory))),
             pipeline,
             max_queue_size: 100,
-            queue: Arc::new(RwLock::new(VecDeque::new())),
+            max_wait: MaxWaitTimes::default(),
+            next_sequence: AtomicU64::new(0),
+            queue: Arc::new(RwLock::new(BinaryHeap::new())),
         }
     }
 
+    /// Set how long each tier may wait before promotion
+    pub fn with_max_wait(mut self, max_wait: MaxWaitTimes) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
     /// Schedule a batch request
     pub fn schedule_batch(&self, request: GPUBatchRequest<Vec<u8>>) -> Result<String, String> {
         let mut queue = self.queue.write();
@@ -197,30 +313,28 @@ impl GPUBatchScheduler {
 
         drop(pool); // Release lock before inserting
 
-        queue.push_back(request);
+        queue.push(PendingRequest {
+            priority: request.priority,
+            sequence: self.next_sequence.fetch_add(1, AtomicOrdering::Relaxed),
+            tier_entered: Instant::now(),
+            request,
+        });
         Ok("Batch scheduled".to_string())
     }
 
     /// Get next batch from queue
+    ///
+    /// Highest priority first, FIFO within a tier. Requests that waited past
+    /// their tier's limit are promoted before the pick.
     pub fn get_next_batch(&self) -> Option<GPUBatchRequest<Vec<u8>>> {
         let mut queue = self.queue.write();
-
-        // Sort by priority (higher priority first), then by timestamp (FIFO for same priority)
-        let mut items: Vec<_> = queue.drain(..).collect();
-        items.sort_by(|a, b| match b.priority.cmp(&a.priority) {
-            std::cmp::Ordering::Equal => a.timestamp.cmp(&b.timestamp),
-            other => other,
-        });
-
-        if !items.is_empty() {
-            let batch = items.remove(0); // Take the highest priority item
-            for item in items {
-                queue.push_back(item); // Put remaining items back
-            }
-            Some(batch)
-        } else {
-            None
+        let now = Instant::now();
+        let mut pending = std::mem::take(&mut *queue).into_vec();
+        for request in &mut pending {
+            request.promote_if_starved(now, &self.max_wait);
         }
+        *queue = BinaryHeap::from(pending);
+        queue.pop().map(|pending| pending.request)
     }
 
     /// Get GPU memory usage
@@ -340,7 +454,7 @@ mod tests {
             GPUBatchItem::new("id1".to_string(), vec![1, 2], 256),
             GPUBatchItem::new("id2".to_string(), vec![3, 4], 512),
         ];
-        let request = GPUBatchRequest::new(items, 1);
+        let request = GPUBatchRequest::new(items, RequestPriority::Normal);
 
         assert_eq!(request.total_memory_required(), 768);
     }
@@ -360,7 +474,7 @@ mod tests {
         let scheduler = GPUBatchScheduler::new(8192, pipeline);
 
         let items = vec![GPUBatchItem::new("id1".to_string(), vec![1, 2], 256)];
-        let request = GPUBatchRequest::new(items, 1);
+        let request = GPUBatchRequest::new(items, RequestPriority::Normal);
 
         let result = scheduler.schedule_batch(request);
         assert!(result.is_ok());
@@ -373,7 +487,7 @@ mod tests {
         let scheduler = GPUBatchScheduler::new(256, pipeline); // Small memory
 
         let items = vec![GPUBatchItem::new("id1".to_string(), vec![0; 512], 512)];
-        let request = GPUBatchRequest::new(items, 1);
+        let request = GPUBatchRequest::new(items, RequestPriority::Normal);
 
         let result = scheduler.schedule_batch(request);
         assert!(result.is_err());
@@ -386,7 +500,7 @@ mod tests {
         let executor = GPUBatchExecutor::new(scheduler);
 
         let items = vec![GPUBatchItem::new("id1".to_string(), vec![1, 2, 3], 256)];
-        let request = GPUBatchRequest::new(items, 1);
+        let request = GPUBatchRequest::new(items, RequestPriority::Normal);
 
         let result = executor.execute_batch(request);
         assert!(result.is_ok());
@@ -406,10 +520,10 @@ mod tests {
         let scheduler = GPUBatchScheduler::new(8192, pipeline);
 
         let items1 = vec![GPUBatchItem::new("id1".to_string(), vec![1], 100)];
-        let request1 = GPUBatchRequest::new(items1, 1); // Low priority
+        let request1 = GPUBatchRequest::new(items1, RequestPriority::Low);
 
         let items2 = vec![GPUBatchItem::new("id2".to_string(), vec![2], 100)];
-        let request2 = GPUBatchRequest::new(items2, 10); // High priority
+        let request2 = GPUBatchRequest::new(items2, RequestPriority::High);
 
         let _ = scheduler.schedule_batch(request1);
         let _ = scheduler.schedule_batch(request2);
@@ -419,4 +533,67 @@ mod tests {
             assert_eq!(batch.items[0].id, "id2");
         }
     }
+
+    fn request(id: &str, priority: RequestPriority) -> GPUBatchRequest<Vec<u8>> {
+        GPUBatchRequest::new(
+            vec![GPUBatchItem::new(id.to_string(), vec![], 10)],
+            priority,
+        )
+    }
+
+    fn drain_ids(scheduler: &GPUBatchScheduler) -> Vec<String> {
+        std::iter::from_fn(|| scheduler.get_next_batch())
+            .map(|batch| batch.items[0].id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_gpu_scheduler_fifo_within_priority() {
+        let pipeline = GPUComputePipeline::new("tokenizer".to_string(), (8, 8, 1));
+        let scheduler = GPUBatchScheduler::new(8192, pipeline);
+        for (id, priority) in [
+            ("low", RequestPriority::Low),
+            ("normal-1", RequestPriority::Normal),
+            ("high", RequestPriority::High),
+            ("normal-2", RequestPriority::Normal),
+        ] {
+            scheduler.schedule_batch(request(id, priority)).unwrap();
+        }
+
+        assert_eq!(
+            drain_ids(&scheduler),
+            ["high", "normal-1", "normal-2", "low"]
+        );
+    }
+
+    #[test]
+    fn test_gpu_scheduler_promotes_starved_requests() {
+        let pipeline = GPUComputePipeline::new("tokenizer".to_string(), (8, 8, 1));
+        let scheduler = GPUBatchScheduler::new(8192, pipeline).with_max_wait(MaxWaitTimes {
+            low: Duration::ZERO,
+            normal: Duration::from_secs(3600),
+        });
+        scheduler
+            .schedule_batch(request("low", RequestPriority::Low))
+            .unwrap();
+        scheduler
+            .schedule_batch(request("normal", RequestPriority::Normal))
+            .unwrap();
+
+        // The low request is promoted to Normal and is older than "normal"
+        assert_eq!(drain_ids(&scheduler), ["low", "normal"]);
+    }
+
+    #[test]
+    fn test_request_priority_promotion_order() {
+        assert_eq!(
+            RequestPriority::Low.promoted(),
+            Some(RequestPriority::Normal)
+        );
+        assert_eq!(
+            RequestPriority::Normal.promoted(),
+            Some(RequestPriority::High)
+        );
+        assert_eq!(RequestPriority::High.promoted(), None);
+    }
 }
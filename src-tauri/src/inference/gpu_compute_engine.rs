@@ -1,4 +1,3 @@
-use super::metal_gpu::{GPUMemoryPool, MetalDevice, MetalDeviceInfo};
 /// GPU Computation Engine - Phase 6 Step 4
 ///
 /// This module integrates Metal GPU operations with LLaMA inference,
@@ -8,6 +7,8 @@ use super::metal_gpu::{GPUMemoryPool, MetalDevice, MetalDeviceInfo};
 /// - Matrix operations with GPU acceleration
 ///
 /// Automatically falls back to CPU for testing and unsupported operations.
+use super::int8_matmul::{Int8MatmulParams, matmul_int8_cpu};
+use super::metal_gpu::{GPUMemoryPool, MetalDevice, MetalDeviceInfo};
 use crate::error::{MinervaError, MinervaResult};
 use std::sync::Arc;
 
@@ -197,6 +198,15 @@ impl GPUComputeEngine {
         })
     }
 
+    /// Quantized matmul: `A_int8 @ B_int8` accumulated in i32, then rescaled
+    /// by `scales_a[i] * scales_b[j]` per output element
+    ///
+    /// On Apple Silicon this maps to Metal INT8 kernels; until those land it
+    /// runs the integer CPU path.
+    pub fn matmul_int8(&self, params: &Int8MatmulParams) -> MinervaResult<Vec<f32>> {
+        matmul_int8_cpu(params)
+    }
+
     /// Execute attention computation on GPU
     pub fn compute_attention(&self, params: AttentionParams) -> MinervaResult<ComputeResult> {
         let start = std::time::Instant::now();
@@ -449,6 +459,39 @@ mod tests {
         assert!((result.output[1] - 22.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_matmul_int8_matches_fp32() {
+        use crate::inference::int8_matmul::QuantizedMatrix;
+
+        let (m, k, n) = (8, 64, 16);
+        let a: Vec<f32> = (0..m * k)
+            .map(|i| 0.5 + 0.5 * (i as f32 * 0.481 + 1.3).sin())
+            .collect();
+        let b: Vec<f32> = (0..k * n)
+            .map(|i| 0.25 + 0.5 * (i as f32 * 1.073 + 2.9).sin())
+            .collect();
+        let engine = GPUComputeEngine::simulated().unwrap();
+        let reference = engine
+            .compute_matmul(MatmulParams::new(a.clone(), b.clone(), m))
+            .unwrap()
+            .output;
+
+        let qa = QuantizedMatrix::per_row(&a, m).unwrap();
+        let qb = QuantizedMatrix::per_column(&b, k).unwrap();
+        let output = engine
+            .matmul_int8(&Int8MatmulParams::from_matrices(&qa, &qb))
+            .unwrap();
+
+        let error: f32 = output
+            .iter()
+            .zip(&reference)
+            .map(|(x, r)| (x - r).powi(2))
+            .sum();
+        let norm: f32 = reference.iter().map(|r| r * r).sum();
+        assert_eq!(output.len(), m * n);
+        assert!((error / norm).sqrt() < 0.005);
+    }
+
     #[test]
     fn test_element_mul() {
         let engine = GPUComputeEngine::simulated().unwrap();
@@ -1,3 +1,5 @@
+use super::system_memory::available_memory_bytes;
+use crate::commands::hardware_info::total_ram_bytes;
 use crate::error::{MinervaError, MinervaResult};
 
 /// GPU device type
@@ -200,9 +202,18 @@ impl GpuContext {
         Ok(())
     }
 
-    /// Get available memory
+    /// Get available memory: the remaining budget
+    ///
+    /// Metal and CPU budgets share system RAM, so they are also capped by
+    /// free system memory; a discrete CUDA card's VRAM is not.
     pub fn available_memory(&self) -> usize {
-        self.max_memory.saturating_sub(self.allocated_memory)
+        let budget = self.max_memory.saturating_sub(self.allocated_memory);
+        match self.device {
+            GpuDevice::Cuda => budget,
+            GpuDevice::Metal | GpuDevice::Cpu => {
+                available_memory_bytes().map_or(budget, |free| budget.min(free as usize))
+            }
+        }
     }
 
     /// Get allocated memory
@@ -252,11 +263,9 @@ impl GpuContext {
         std::env::var("CUDA_PATH").is_ok()
     }
 
-    /// Estimate available memory based on ratio
+    /// Estimate available memory as a fraction of system RAM (16GB if unknown)
     fn estimate_memory(ratio: f32) -> usize {
-        // Use system memory info to estimate
-        // For now, use reasonable defaults
-        let system_memory = 16usize * 1024 * 1024 * 1024; // 16GB default
+        let system_memory = total_ram_bytes().unwrap_or(16 * 1024 * 1024 * 1024);
         (system_memory as f32 * ratio) as usize
     }
 }
@@ -335,4 +344,16 @@ mod tests {
         assert!(ctx.allocate(100).is_ok());
         assert_eq!(ctx.available_memory(), 300);
     }
+
+    #[test]
+    fn test_cuda_budget_not_capped_by_system_memory() {
+        let max_memory = usize::MAX / 2;
+        let ctx = GpuContext {
+            device: GpuDevice::Cuda,
+            allocated_memory: 0,
+            max_memory,
+        };
+
+        assert_eq!(ctx.available_memory(), max_memory);
+    }
 }
@@ -9,6 +9,8 @@ use super::gpu_compute_engine::{AttentionParams, GPUComputeEngine, MatmulParams,
 /// - Full inference pipeline with GPU/CPU fallback
 use crate::error::{MinervaError, MinervaResult};
 
+pub use super::gpu_llama_vram::GPULlamaIntegration;
+
 /// GPU Inference Configuration
 #[derive(Debug, Clone)]
 pub struct GPUInferenceConfig {
@@ -0,0 +1,138 @@
+/// llama.cpp VRAM Accounting
+///
+/// llama.cpp allocates its own Metal buffers for offloaded layers and the KV
+/// cache, invisible to `GpuContext`. `GPULlamaIntegration` books the
+/// offloaded weights plus what the session reports into the shared context
+/// so Minerva's native Metal path does not plan against memory llama.cpp
+/// already holds.
+use super::gpu_context::GpuContext;
+use crate::error::{MinervaError, MinervaResult};
+use llama_cpp::LlamaSession;
+use std::sync::{Arc, Mutex};
+
+/// Weight bytes of `gpu_layers` out of `layers`, assuming equal-sized layers
+pub fn offloaded_weight_bytes(weight_bytes: u64, gpu_layers: u32, layers: usize) -> u64 {
+    let Ok(layers) = u64::try_from(layers) else {
+        return 0;
+    };
+    if layers == 0 {
+        return 0;
+    }
+    weight_bytes / layers * u64::from(gpu_layers).min(layers)
+}
+
+/// Keeps `GpuContext::allocated_memory` in step with a llama.cpp session
+#[derive(Debug)]
+pub struct GPULlamaIntegration {
+    gpu: Arc<Mutex<GpuContext>>,
+    /// Weight bytes of the layers offloaded to the GPU
+    offloaded: u64,
+    /// Bytes currently booked on behalf of llama.cpp
+    booked: u64,
+}
+
+impl GPULlamaIntegration {
+    pub fn new(gpu: Arc<Mutex<GpuContext>>) -> Self {
+        Self {
+            gpu,
+            offloaded: 0,
+            booked: 0,
+        }
+    }
+
+    /// Set the weight bytes of the layers the loaded model offloaded
+    ///
+    /// They are booked, with the session, on the next `sync_vram_usage`.
+    pub fn set_offloaded_weights(&mut self, bytes: u64) {
+        self.offloaded = bytes;
+    }
+
+    /// Query the session's memory footprint and book it, plus the offloaded
+    /// weights, in the GPU context
+    ///
+    /// Call after loading a model and after each generation, when the KV
+    /// cache may have grown. Returns the bytes now attributed to llama.cpp.
+    pub fn sync_vram_usage(&mut self, session: &LlamaSession) -> MinervaResult<u64> {
+        self.record_usage(self.offloaded + session.memory_size() as u64)
+    }
+
+    /// Replace the previous booking with `bytes`
+    ///
+    /// llama.cpp has already allocated the memory, so the booking is recorded
+    /// even when it exceeds the context's budget; later native allocations
+    /// then fail instead of oversubscribing the device.
+    pub fn record_usage(&mut self, bytes: u64) -> MinervaResult<u64> {
+        let mut gpu = self
+            .gpu
+            .lock()
+            .map_err(|_| MinervaError::InferenceError("GPU context lock poisoned".to_string()))?;
+        let others = (gpu.allocated_memory as u64).saturating_sub(self.booked);
+        gpu.allocated_memory = usize::try_from(others + bytes).unwrap_or(usize::MAX);
+        self.booked = bytes;
+        tracing::debug!(
+            "llama.cpp VRAM synced: {} bytes (GPU total {} bytes)",
+            bytes,
+            gpu.allocated_memory
+        );
+        Ok(bytes)
+    }
+
+    /// Drop the booking, e.g. when the model is unloaded
+    pub fn release(&mut self) -> MinervaResult<()> {
+        self.offloaded = 0;
+        self.record_usage(0).map(|_| ())
+    }
+
+    /// Bytes currently attributed to llama.cpp
+    pub fn booked(&self) -> u64 {
+        self.booked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::gpu_context::GpuDevice;
+
+    fn gpu(allocated: usize) -> Arc<Mutex<GpuContext>> {
+        Arc::new(Mutex::new(GpuContext {
+            device: GpuDevice::Metal,
+            allocated_memory: allocated,
+            max_memory: 1000,
+        }))
+    }
+
+    #[test]
+    fn test_record_usage_replaces_previous_booking() {
+        let ctx = gpu(100);
+        let mut integration = GPULlamaIntegration::new(Arc::clone(&ctx));
+
+        integration.record_usage(300).unwrap();
+        assert_eq!(ctx.lock().unwrap().allocated_memory, 400);
+
+        // KV cache grew: only the delta is added, never booked twice
+        integration.record_usage(350).unwrap();
+        assert_eq!(ctx.lock().unwrap().allocated_memory, 450);
+        assert_eq!(integration.booked(), 350);
+    }
+
+    #[test]
+    fn test_offloaded_weight_bytes() {
+        assert_eq!(offloaded_weight_bytes(3200, 40, 32), 3200);
+        assert_eq!(offloaded_weight_bytes(3200, 8, 32), 800);
+        assert_eq!(offloaded_weight_bytes(3200, 40, 0), 0);
+    }
+
+    #[test]
+    fn test_release_keeps_native_allocations() {
+        let ctx = gpu(100);
+        let mut integration = GPULlamaIntegration::new(Arc::clone(&ctx));
+        integration.set_offloaded_weights(200);
+        integration.record_usage(300).unwrap();
+
+        integration.release().unwrap();
+        assert_eq!(ctx.lock().unwrap().allocated_memory, 100);
+        assert_eq!(integration.booked(), 0);
+        assert_eq!(integration.offloaded, 0);
+    }
+}
@@ -0,0 +1,103 @@
+//! Hardware Probing
+//!
+//! Total RAM and the GPU's name and memory, read from platform tools
+//! (`sysctl`, `system_profiler`, `nvidia-smi`) or /proc. Every probe
+//! degrades to `None` when its tool is missing.
+
+use std::process::Command;
+
+pub(crate) fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Parse `MemTotal:  16318412 kB` from /proc/meminfo
+pub fn parse_meminfo_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Parse the first `name, memory.total [MiB]` line from nvidia-smi
+pub fn parse_nvidia_smi(csv: &str) -> Option<(String, f32)> {
+    let (name, mib) = csv.lines().next()?.rsplit_once(',')?;
+    let mib: f32 = mib.trim().parse().ok()?;
+    Some((name.trim().to_string(), mib / 1024.0))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn nvidia_gpu() -> Option<(String, f32)> {
+    let csv = command_output(
+        "nvidia-smi",
+        &[
+            "--query-gpu=name,memory.total",
+            "--format=csv,noheader,nounits",
+        ],
+    )?;
+    parse_nvidia_smi(&csv)
+}
+
+#[cfg(target_os = "macos")]
+pub fn total_ram_bytes() -> Option<u64> {
+    command_output("sysctl", &["-n", "hw.memsize"])?
+        .parse()
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+pub fn total_ram_bytes() -> Option<u64> {
+    parse_meminfo_total(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+#[cfg(windows)]
+pub fn total_ram_bytes() -> Option<u64> {
+    crate::inference::system_memory::global_memory_status().map(|(total, _)| total)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+pub fn total_ram_bytes() -> Option<u64> {
+    None
+}
+
+/// Apple GPUs share unified memory, so the Metal device's budget is system RAM
+#[cfg(target_os = "macos")]
+pub fn gpu_info(total_ram_gb: f32) -> (Option<String>, Option<f32>) {
+    let name = command_output("system_profiler", &["SPDisplaysDataType"]).and_then(|out| {
+        out.lines()
+            .find_map(|l| l.trim().strip_prefix("Chipset Model:"))
+            .map(|n| n.trim().to_string())
+    });
+    let vram = name.as_ref().map(|_| total_ram_gb);
+    (name, vram)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn gpu_info(_total_ram_gb: f32) -> (Option<String>, Option<f32>) {
+    match nvidia_gpu() {
+        Some((name, vram)) => (Some(name), Some(vram)),
+        None => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo_total() {
+        let meminfo = "MemTotal:       16318412 kB\nMemFree:  100 kB\n";
+        assert_eq!(parse_meminfo_total(meminfo), Some(16318412 * 1024));
+        assert_eq!(parse_meminfo_total("MemFree: 1 kB"), None);
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let (name, vram) = parse_nvidia_smi("NVIDIA GeForce RTX 4090, 24564\n").unwrap();
+        assert_eq!(name, "NVIDIA GeForce RTX 4090");
+        assert!((vram - 23.99).abs() < 0.01);
+        assert!(parse_nvidia_smi("").is_none());
+    }
+}
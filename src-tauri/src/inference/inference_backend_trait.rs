@@ -5,7 +5,7 @@
 /// - Mock backends for testing
 /// - Real llama.cpp inference
 /// - Future ONNX, Hugging Face, or other backend implementations
-use crate::error::MinervaResult;
+use crate::error::{MinervaError, MinervaResult};
 use std::path::Path;
 
 /// Parameters for text generation
@@ -34,12 +34,36 @@ pub trait InferenceBackend: Send + Sync {
     /// Generate text from prompt
     fn generate(&self, prompt: &str, params: GenerationParams) -> MinervaResult<String>;
 
+    /// Generate text, passing each piece to `on_token` as it is produced
+    ///
+    /// Backends without streaming deliver the whole completion as one piece
+    /// once generation finishes.
+    fn generate_stream(
+        &self,
+        prompt: &str,
+        params: GenerationParams,
+        on_token: &mut dyn FnMut(&str),
+    ) -> MinervaResult<String> {
+        let text = self.generate(prompt, params)?;
+        on_token(&text);
+        Ok(text)
+    }
+
     /// Tokenize text into token IDs
     fn tokenize(&self, text: &str) -> MinervaResult<Vec<i32>>;
 
     /// Detokenize token IDs back to text
     fn detokenize(&self, tokens: &[i32]) -> MinervaResult<String>;
 
+    /// Logits over the vocabulary for the token following `tokens`
+    ///
+    /// Backends that only return finished text keep the default error.
+    fn next_token_logits(&self, _tokens: &[u32]) -> MinervaResult<Vec<f32>> {
+        Err(MinervaError::InferenceError(
+            "Backend does not expose logits".to_string(),
+        ))
+    }
+
     /// Check if model is loaded
     fn is_loaded(&self) -> bool;
 
@@ -48,4 +72,14 @@ pub trait InferenceBackend: Send + Sync {
 
     /// Get number of threads
     fn thread_count(&self) -> usize;
+
+    /// Use `n_threads` for inference from the next `load_model` on
+    fn set_thread_count(&mut self, n_threads: usize);
+
+    /// Whether tokens can be emitted as they are generated
+    ///
+    /// Backends that only return a finished completion keep the default.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
 }
@@ -27,11 +27,15 @@
 ///     ↓
 /// Softmax: (3, vocab_size) probabilities
 /// ```
+use super::cache_optimizer::CachePosition;
 use super::engine_config::InferenceEngineConfig;
+use super::kv_cache::{KVCache, KVCacheConfig};
+use super::llama_decoder::{Decoder, GenerationParams};
 use super::model_weights::ModelWeights;
 use super::transformer_components::{
-    EmbeddingConfig, PositionConfig, TransformerBlockConfig, TransformerBlockWeights,
+    CachedStep, EmbeddingConfig, PositionConfig, TransformerBlockConfig, TransformerBlockWeights,
     add_position_encoding, create_position_encoding, embed_tokens, transformer_block,
+    transformer_block_step,
 };
 use super::transformer_layers::{LayerNormConfig as LNCfg, layer_norm};
 use crate::error::{MinervaError, MinervaResult};
@@ -122,34 +126,131 @@ impl InferenceEngine {
         for layer_idx in 0..self.config.num_layers {
             let layer_weights = &self.weights.layers[layer_idx];
 
-            let block_config = TransformerBlockConfig {
-                seq_len,
-                hidden_size: self.config.hidden_size,
-                num_heads: self.config.num_heads,
-                intermediate_size: self.config.intermediate_size,
-                activation: self.config.activation,
-                causal: self.config.causal,
-                eps: self.config.eps,
+            let block_config = self.block_config(seq_len);
+            let block_weights = TransformerBlockWeights {
+                attn_scale: Some(layer_weights.attn_norm_scale.get()?),
+                ff_up: layer_weights.ff_up.get()?,
+                ff_down: layer_weights.ff_down.get()?,
             };
+            x = transformer_block(&x, &block_weights, &block_config)?;
+        }
+
+        // Steps 4-5: Final layer norm and output projection
+        self.project_logits(&x, seq_len)
+    }
+
+    /// Run one generation step for the last token, reusing the KV cache
+    ///
+    /// `tokens` is the whole sequence so far; every position before the last
+    /// must already be in `cache` from earlier steps. Each layer computes only
+    /// the newest attention row, so a step costs O(n) rather than O(n²).
+    ///
+    /// # Returns
+    /// Logits for the last position: (vocab_size)
+    pub fn forward_step(&self, tokens: &[usize], cache: &mut KVCache) -> MinervaResult<Vec<f32>> {
+        let pos = self.step_position(tokens)?;
+        let hidden_size = self.config.hidden_size;
+
+        let embed_config = EmbeddingConfig {
+            vocab_size: self.config.vocab_size,
+            hidden_size,
+        };
+        let x = embed_tokens(&tokens[pos..], &self.weights.embeddings, &embed_config)?;
+        let pos_config = PositionConfig {
+            seq_len: pos + 1,
+            hidden_size,
+            base: 10000.0,
+        };
+        let position_encoding = create_position_encoding(&pos_config);
+        let mut x = add_position_encoding(&x, &position_encoding[pos * hidden_size..])?;
 
+        for layer_idx in 0..self.config.num_layers {
+            let layer_weights = &self.weights.layers[layer_idx];
             let block_weights = TransformerBlockWeights {
-                attn_scale: Some(&layer_weights.attn_norm_scale),
-                ff_up: &layer_weights.ff_up,
-                ff_down: &layer_weights.ff_down,
+                attn_scale: Some(layer_weights.attn_norm_scale.get()?),
+                ff_up: layer_weights.ff_up.get()?,
+                ff_down: layer_weights.ff_down.get()?,
             };
-            x = transformer_block(&x, &block_weights, &block_config)?;
+            let mut step = CachedStep {
+                cache: &mut *cache,
+                at: CachePosition {
+                    layer: layer_idx,
+                    pos,
+                },
+                config: self.block_config(1),
+            };
+            x = transformer_block_step(&x, &block_weights, &mut step)?;
+        }
+
+        self.project_logits(&x, 1)
+    }
+
+    /// Generate `params.num_tokens` tokens after `params.initial_tokens`
+    ///
+    /// The prompt is prefilled into a fresh KV cache one position at a time
+    /// on the first step; every later token costs a single `forward_step`.
+    pub fn generate(&self, params: GenerationParams) -> MinervaResult<Vec<usize>> {
+        let decoder = Decoder::new(self.config.vocab_size, self.config.max_seq_len);
+        let mut cache = self.new_kv_cache();
+        let mut cached = 0;
+        decoder.generate(params, |tokens| {
+            let mut logits = Vec::new();
+            for len in cached + 1..=tokens.len() {
+                logits = self.forward_step(&tokens[..len], &mut cache)?;
+            }
+            cached = tokens.len();
+            Ok(logits)
+        })
+    }
+
+    /// Create an empty KV cache sized for this engine
+    pub fn new_kv_cache(&self) -> KVCache {
+        KVCache::new(KVCacheConfig {
+            num_layers: self.config.num_layers,
+            max_seq_len: self.config.max_seq_len,
+            num_heads: self.config.num_heads,
+            head_dim: self.config.hidden_size / self.config.num_heads,
+        })
+    }
+
+    /// Position of the newest token, validated for a cached step
+    fn step_position(&self, tokens: &[usize]) -> MinervaResult<usize> {
+        if !self.config.causal {
+            return Err(MinervaError::InferenceError(
+                "Cached generation steps require causal attention".to_string(),
+            ));
+        }
+        if tokens.is_empty() || tokens.len() > self.config.max_seq_len {
+            return Err(MinervaError::InferenceError(format!(
+                "Sequence length {} outside 1..={}",
+                tokens.len(),
+                self.config.max_seq_len
+            )));
+        }
+        Ok(tokens.len() - 1)
+    }
+
+    fn block_config(&self, seq_len: usize) -> TransformerBlockConfig {
+        TransformerBlockConfig {
+            seq_len,
+            hidden_size: self.config.hidden_size,
+            num_heads: self.config.num_heads,
+            intermediate_size: self.config.intermediate_size,
+            activation: self.config.activation,
+            causal: self.config.causal,
+            eps: self.config.eps,
         }
+    }
 
-        // Step 4: Final layer norm
+    /// Final layer norm followed by projection to vocabulary logits
+    fn project_logits(&self, x: &[f32], seq_len: usize) -> MinervaResult<Vec<f32>> {
         let final_norm_config = LNCfg {
             seq_len,
             hidden_size: self.config.hidden_size,
             scale: Some(self.weights.final_norm_scale.clone()),
             eps: self.config.eps,
         };
-        x = layer_norm(&x, &final_norm_config)?;
-
-        // Step 5: Output projection
+        let x = layer_norm(x, &final_norm_config)?;
         let mut logits = vec![0.0; seq_len * self.config.vocab_size];
 
         for i in 0..seq_len {
@@ -211,3 +312,81 @@ impl InferenceEngine {
         &self.weights
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::model_weights::LayerWeights;
+
+    fn create_dummy_weights(config: &InferenceEngineConfig) -> ModelWeights {
+        let layers = (0..config.num_layers)
+            .map(|_| LayerWeights {
+                attn_norm_scale: vec![1.0; config.hidden_size].into(),
+                ffn_norm_scale: vec![1.0; config.hidden_size].into(),
+                ff_up: vec![0.1; config.hidden_size * config.intermediate_size].into(),
+                ff_down: vec![0.1; config.intermediate_size * config.hidden_size].into(),
+            })
+            .collect();
+
+        ModelWeights {
+            embeddings: vec![0.1; config.vocab_size * config.hidden_size],
+            layers,
+            final_norm_scale: vec![1.0; config.hidden_size],
+            output_proj: vec![0.01; config.hidden_size * config.vocab_size],
+        }
+    }
+
+    #[test]
+    fn test_forward_step_matches_full_forward() {
+        let config = InferenceEngineConfig::tiny(50);
+        let weights = create_dummy_weights(&config);
+        let engine = InferenceEngine::new(config, weights).unwrap();
+        let tokens = vec![3, 7, 11, 2];
+
+        let mut cache = engine.new_kv_cache();
+        let mut step_logits = Vec::new();
+        for len in 1..=tokens.len() {
+            step_logits = engine.forward_step(&tokens[..len], &mut cache).unwrap();
+        }
+
+        let full = engine.forward(&tokens).unwrap();
+        assert_eq!(step_logits.len(), 50);
+        for (a, b) in step_logits.iter().zip(&full[3 * 50..]) {
+            assert!((a - b).abs() < 1e-4, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_generate_matches_full_forward_decoding() {
+        use crate::inference::llama_decoder::SamplingParams;
+
+        let config = InferenceEngineConfig::tiny(50);
+        let weights = create_dummy_weights(&config);
+        let engine = InferenceEngine::new(config, weights).unwrap();
+        let params = || GenerationParams {
+            initial_tokens: &[3, 7, 11],
+            num_tokens: 4,
+            sampling: SamplingParams::greedy(1.0),
+        };
+
+        let cached = engine.generate(params()).unwrap();
+        let full = Decoder::new(50, engine.config().max_seq_len)
+            .generate(params(), |tokens| {
+                let logits = engine.forward(tokens)?;
+                Ok(logits[(tokens.len() - 1) * 50..].to_vec())
+            })
+            .unwrap();
+        assert_eq!(cached.len(), 7);
+        assert_eq!(cached, full);
+    }
+
+    #[test]
+    fn test_forward_step_empty_sequence() {
+        let config = InferenceEngineConfig::tiny(50);
+        let weights = create_dummy_weights(&config);
+        let engine = InferenceEngine::new(config, weights).unwrap();
+
+        let mut cache = engine.new_kv_cache();
+        assert!(engine.forward_step(&[], &mut cache).is_err());
+    }
+}
@@ -6,6 +6,7 @@
 ///
 /// Structure: keys[layer][pos][head][head_dim]
 use crate::error::{MinervaError, MinervaResult};
+use std::io::{Read, Write};
 
 /// Parameters for KV cache initialization
 #[derive(Debug, Clone, Copy)]
@@ -104,6 +105,23 @@ impl KVCache {
         }
     }
 
+    /// Number of attention heads per position
+    pub fn num_heads(&self) -> usize {
+        self.keys
+            .first()
+            .and_then(|layer| layer.first())
+            .map_or(0, |pos| pos.len())
+    }
+
+    /// Dimension of each attention head
+    pub fn head_dim(&self) -> usize {
+        self.keys
+            .first()
+            .and_then(|layer| layer.first())
+            .and_then(|pos| pos.first())
+            .map_or(0, |head| head.len())
+    }
+
     /// Store key and value for a position
     pub fn store(&mut self, params: KVStoreParams) -> MinervaResult<()> {
         if params.layer >= self.keys.len() {
@@ -163,6 +181,89 @@ impl KVCache {
         Ok((k, v))
     }
 
+    /// Dimensions this cache was created with
+    pub fn config(&self) -> KVCacheConfig {
+        KVCacheConfig {
+            num_layers: self.keys.len(),
+            max_seq_len: self.keys.first().map_or(0, |layer| layer.len()),
+            num_heads: self.num_heads(),
+            head_dim: self.head_dim(),
+        }
+    }
+
+    /// Write dimensions followed by all keys then all values, little-endian
+    pub fn write_to(&self, writer: &mut impl Write) -> MinervaResult<()> {
+        let config = self.config();
+        for dim in [
+            config.num_layers,
+            config.max_seq_len,
+            config.num_heads,
+            config.head_dim,
+        ] {
+            writer.write_all(&(dim as u64).to_le_bytes())?;
+        }
+        for value in self
+            .keys
+            .iter()
+            .chain(&self.values)
+            .flatten()
+            .flatten()
+            .flatten()
+        {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Read a cache written by [`KVCache::write_to`]
+    ///
+    /// `available_bytes` is what is left in the source; dimensions that
+    /// would need more data than that are rejected before anything is
+    /// allocated.
+    pub fn read_from(reader: &mut impl Read, available_bytes: u64) -> MinervaResult<Self> {
+        let mut dims = [0usize; 4];
+        for dim in &mut dims {
+            let mut buf = [0u8; 8];
+            reader.read_exact(&mut buf)?;
+            *dim = usize::try_from(u64::from_le_bytes(buf)).map_err(|_| {
+                MinervaError::ValidationError("KV cache dimension too large".to_string())
+            })?;
+        }
+        let needed = dims
+            .iter()
+            .chain(&[2, std::mem::size_of::<f32>()])
+            .try_fold(1u64, |acc, &dim| acc.checked_mul(dim as u64))
+            .and_then(|data| data.checked_add(32));
+        match needed {
+            Some(needed) if needed <= available_bytes => {}
+            _ => {
+                return Err(MinervaError::ValidationError(format!(
+                    "KV cache dimensions {:?} exceed the {} bytes available",
+                    dims, available_bytes
+                )));
+            }
+        }
+        let mut cache = Self::new(KVCacheConfig {
+            num_layers: dims[0],
+            max_seq_len: dims[1],
+            num_heads: dims[2],
+            head_dim: dims[3],
+        });
+        let mut buf = [0u8; 4];
+        for value in cache
+            .keys
+            .iter_mut()
+            .chain(&mut cache.values)
+            .flatten()
+            .flatten()
+            .flatten()
+        {
+            reader.read_exact(&mut buf)?;
+            *value = f32::from_le_bytes(buf);
+        }
+        Ok(cache)
+    }
+
     /// Clear cache
     pub fn clear(&mut self) {
         for layer in &mut self.keys {
@@ -181,3 +282,45 @@ impl KVCache {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kv_cache_write_read_roundtrip() {
+        let config = KVCacheConfig {
+            num_layers: 2,
+            max_seq_len: 3,
+            num_heads: 2,
+            head_dim: 2,
+        };
+        let mut cache = KVCache::new(config);
+        let params = KVStoreParams::builder(vec![1.0, 2.0, 3.0, 4.0], vec![5.0, 6.0, 7.0, 8.0])
+            .layer(1)
+            .pos(2)
+            .build();
+        cache.store(params).unwrap();
+
+        let mut bytes = Vec::new();
+        cache.write_to(&mut bytes).unwrap();
+        let restored = KVCache::read_from(&mut bytes.as_slice(), bytes.len() as u64).unwrap();
+
+        assert_eq!(restored.config().max_seq_len, 3);
+        assert_eq!(restored.get(1, 2).unwrap(), cache.get(1, 2).unwrap());
+    }
+
+    #[test]
+    fn test_kv_cache_read_rejects_dims_larger_than_input() {
+        let mut bytes = Vec::new();
+        for dim in [1u64 << 20, 1 << 20, 64, 128] {
+            bytes.extend_from_slice(&dim.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0u8; 64]);
+
+        assert!(matches!(
+            KVCache::read_from(&mut bytes.as_slice(), bytes.len() as u64),
+            Err(MinervaError::ValidationError(_))
+        ));
+    }
+}
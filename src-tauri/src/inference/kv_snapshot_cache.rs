@@ -0,0 +1,533 @@
+/// Two-Level KV Cache Snapshot Store
+///
+/// Rebuilding the KV cache for a long, frequently used system prompt costs a
+/// full prefill. `KVSnapshotCache` keeps finished prefills keyed by a caller
+/// chosen string (e.g. model ID plus prompt):
+///
+/// - L1: in-memory LRU holding at most `max_entries` snapshots
+/// - L2: one `kv_<hash>.bin` file per key under the cache directory
+///
+/// Lookups check L1, then L2 (promoting disk hits into L1). Stores go to L1
+/// at once and to L2 on a background thread. L2 files expire after
+/// `L2_TTL` and are removed when the cache is opened; beyond `max_bytes`
+/// (default `L2_MAX_BYTES`) the oldest files are removed after each store.
+/// File layout:
+///
+/// ```text
+/// magic "MNVK" | version u32 | key (u64 len + utf8)
+///   | tokens (u64 count + u32 each) | KV cache (see KVCache::write_to)
+/// ```
+use super::kv_cache::KVCache;
+use crate::error::{MinervaError, MinervaResult};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+/// File magic identifying an L2 entry
+pub const L2_MAGIC: &[u8; 4] = b"MNVK";
+
+/// Current L2 format version
+pub const L2_VERSION: u32 = 1;
+
+/// Age after which L2 entries are discarded
+pub const L2_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Default bound on the total size of L2 files
+pub const L2_MAX_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+const L2_PREFIX: &str = "kv_";
+const L2_EXTENSION: &str = "bin";
+const PARTIAL_EXTENSION: &str = "part";
+
+/// Distinguishes the in-progress files of concurrent writers
+static PARTIAL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Prefilled attention state for a token sequence
+#[derive(Debug, Clone)]
+pub struct KVCacheSnapshot {
+    pub tokens: Vec<u32>,
+    pub kv_cache: KVCache,
+}
+
+/// In-memory LRU of snapshots
+#[derive(Debug)]
+pub struct L1Cache {
+    entries: HashMap<String, (Arc<KVCacheSnapshot>, u64)>,
+    max_entries: usize,
+    clock: u64,
+}
+
+impl L1Cache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_entries,
+            clock: 0,
+        }
+    }
+
+    pub fn get(&mut self, key: &str) -> Option<Arc<KVCacheSnapshot>> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(key).map(|(snapshot, last_used)| {
+            *last_used = clock;
+            snapshot.clone()
+        })
+    }
+
+    /// Insert, evicting the least recently used entry when full
+    pub fn put(&mut self, key: &str, snapshot: Arc<KVCacheSnapshot>) {
+        if self.max_entries == 0 {
+            return;
+        }
+        if !self.entries.contains_key(key) && self.entries.len() >= self.max_entries {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(lru) = lru {
+                self.entries.remove(&lru);
+            }
+        }
+        self.clock += 1;
+        self.entries.insert(key.to_string(), (snapshot, self.clock));
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// On-disk snapshots, one file per key
+#[derive(Debug, Clone)]
+pub struct L2Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+}
+
+impl L2Cache {
+    /// Open `dir`, deleting entries older than `ttl`
+    pub fn open(dir: &Path, ttl: Duration) -> MinervaResult<Self> {
+        fs::create_dir_all(dir)?;
+        let cache = Self {
+            dir: dir.to_path_buf(),
+            ttl,
+            max_bytes: L2_MAX_BYTES,
+        };
+        let removed = cache.collect_garbage()?;
+        if removed > 0 {
+            tracing::info!(
+                "Removed {} expired KV snapshots from {}",
+                removed,
+                dir.display()
+            );
+        }
+        Ok(cache)
+    }
+
+    /// Bound the total size of the entry files
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// File holding the entry for `key`
+    pub fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{}{:016x}.{}",
+            L2_PREFIX,
+            key_hash(key),
+            L2_EXTENSION
+        ))
+    }
+
+    /// Read the entry for `key`; missing, expired or unreadable entries are
+    /// misses
+    pub fn get(&self, key: &str) -> Option<KVCacheSnapshot> {
+        let path = self.path(key);
+        if self.is_expired(&path) {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        match read_entry(&path) {
+            Ok((stored_key, snapshot)) if stored_key == key => Some(snapshot),
+            Ok(_) => None,
+            Err(e) => {
+                if path.exists() {
+                    tracing::warn!("Ignoring unreadable KV snapshot {}: {}", path.display(), e);
+                }
+                None
+            }
+        }
+    }
+
+    /// Write the entry for `key`, replacing any previous one atomically,
+    /// then remove the oldest entries beyond `max_bytes`
+    pub fn put(&self, key: &str, snapshot: &KVCacheSnapshot) -> MinervaResult<()> {
+        let path = self.path(key);
+        let partial = path.with_extension(format!(
+            "{}.{}.{}",
+            std::process::id(),
+            PARTIAL_COUNTER.fetch_add(1, Ordering::Relaxed),
+            PARTIAL_EXTENSION
+        ));
+        if let Err(e) = write_entry(&partial, key, snapshot).and_then(|()| {
+            fs::rename(&partial, &path)?;
+            Ok(())
+        }) {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+        let removed = self.enforce_max_bytes()?;
+        if removed > 0 {
+            tracing::debug!("Removed {} KV snapshots over the size limit", removed);
+        }
+        Ok(())
+    }
+
+    /// Delete every expired entry and leftover partial write, returning how
+    /// many files were removed
+    pub fn collect_garbage(&self) -> MinervaResult<usize> {
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if (is_l2_file(&path) || is_partial_file(&path)) && self.is_expired(&path) {
+                remove_if_present(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Delete the least recently written entries until the rest fit in
+    /// `max_bytes`, returning how many were removed
+    pub fn enforce_max_bytes(&self) -> MinervaResult<usize> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if !is_l2_file(&path) {
+                continue;
+            }
+            // Another writer may have removed it since the listing
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((modified, meta.len(), path));
+        }
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        let mut removed = 0;
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            remove_if_present(&path)?;
+            total -= len;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    fn is_expired(&self, path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > self.ttl)
+    }
+}
+
+/// L1 in front of L2, see the module docs
+#[derive(Debug)]
+pub struct KVSnapshotCache {
+    l1: L1Cache,
+    l2: L2Cache,
+}
+
+impl KVSnapshotCache {
+    /// Open the cache in `cache_dir`, collecting expired L2 entries
+    pub fn open(cache_dir: &Path, max_entries: usize) -> MinervaResult<Self> {
+        Ok(Self {
+            l1: L1Cache::new(max_entries),
+            l2: L2Cache::open(cache_dir, L2_TTL)?,
+        })
+    }
+
+    /// Bound the disk space L2 may use
+    pub fn with_l2_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.l2 = self.l2.with_max_bytes(max_bytes);
+        self
+    }
+
+    /// Snapshot for `key` from memory, then disk
+    pub fn get(&mut self, key: &str) -> Option<KVCacheSnapshot> {
+        if let Some(snapshot) = self.l1.get(key) {
+            return Some((*snapshot).clone());
+        }
+        let snapshot = self.l2.get(key)?;
+        self.l1.put(key, Arc::new(snapshot.clone()));
+        Some(snapshot)
+    }
+
+    /// Store in memory now and on disk in the background; join the returned
+    /// handle to wait for the disk write
+    pub fn put(&mut self, key: &str, snapshot: KVCacheSnapshot) -> JoinHandle<()> {
+        let snapshot = Arc::new(snapshot);
+        self.l1.put(key, snapshot.clone());
+
+        let (l2, key) = (self.l2.clone(), key.to_string());
+        std::thread::spawn(move || {
+            if let Err(e) = l2.put(&key, &snapshot) {
+                tracing::warn!("Failed to write KV snapshot for {}: {}", key, e);
+            }
+        })
+    }
+
+    pub fn l1(&self) -> &L1Cache {
+        &self.l1
+    }
+
+    pub fn l2(&self) -> &L2Cache {
+        &self.l2
+    }
+}
+
+fn write_entry(path: &Path, key: &str, snapshot: &KVCacheSnapshot) -> MinervaResult<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(L2_MAGIC)?;
+    writer.write_all(&L2_VERSION.to_le_bytes())?;
+    writer.write_all(&(key.len() as u64).to_le_bytes())?;
+    writer.write_all(key.as_bytes())?;
+    writer.write_all(&(snapshot.tokens.len() as u64).to_le_bytes())?;
+    for token in &snapshot.tokens {
+        writer.write_all(&token.to_le_bytes())?;
+    }
+    snapshot.kv_cache.write_to(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn read_entry(path: &Path) -> MinervaResult<(String, KVCacheSnapshot)> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != L2_MAGIC || read_u32(&mut reader)? != L2_VERSION {
+        return Err(MinervaError::ValidationError(
+            "Not a KV snapshot file of this version".to_string(),
+        ));
+    }
+
+    let mut key = Vec::new();
+    let key_len = read_u64(&mut reader)?;
+    (&mut reader).take(key_len).read_to_end(&mut key)?;
+    let key = String::from_utf8(key)
+        .map_err(|e| MinervaError::ValidationError(format!("Invalid snapshot key: {}", e)))?;
+
+    let token_count = read_u64(&mut reader)?;
+    let tokens = (0..token_count)
+        .map(|_| read_u32(&mut reader))
+        .collect::<MinervaResult<Vec<u32>>>()?;
+    let remaining = file_len.saturating_sub(reader.stream_position()?);
+    let kv_cache = KVCache::read_from(&mut reader, remaining)?;
+    Ok((key, KVCacheSnapshot { tokens, kv_cache }))
+}
+
+fn read_u32(reader: &mut impl Read) -> MinervaResult<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut impl Read) -> MinervaResult<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn is_l2_file(path: &Path) -> bool {
+    has_l2_name(path, L2_EXTENSION)
+}
+
+fn is_partial_file(path: &Path) -> bool {
+    has_l2_name(path, PARTIAL_EXTENSION)
+}
+
+fn has_l2_name(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|ext| ext == extension)
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(L2_PREFIX))
+}
+
+fn remove_if_present(path: &Path) -> MinervaResult<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// FNV-1a: stable across runs and toolchains, unlike `DefaultHasher`
+fn key_hash(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::kv_cache::{KVCacheConfig, KVStoreParams};
+
+    fn snapshot(fill: f32) -> KVCacheSnapshot {
+        let mut kv_cache = KVCache::new(KVCacheConfig {
+            num_layers: 1,
+            max_seq_len: 2,
+            num_heads: 1,
+            head_dim: 2,
+        });
+        let params = KVStoreParams::builder(vec![fill; 2], vec![-fill; 2])
+            .pos(1)
+            .build();
+        kv_cache.store(params).unwrap();
+        KVCacheSnapshot {
+            tokens: vec![1, 2],
+            kv_cache,
+        }
+    }
+
+    fn first_key(snapshot: &KVCacheSnapshot) -> f32 {
+        snapshot.kv_cache.get(0, 1).unwrap().0[0]
+    }
+
+    #[test]
+    fn test_l1_evicts_least_recently_used() {
+        let mut l1 = L1Cache::new(2);
+        l1.put("a", Arc::new(snapshot(1.0)));
+        l1.put("b", Arc::new(snapshot(2.0)));
+        assert!(l1.get("a").is_some());
+        l1.put("c", Arc::new(snapshot(3.0)));
+
+        assert!(l1.contains("a"));
+        assert!(!l1.contains("b"));
+        assert!(l1.contains("c"));
+        assert_eq!(l1.len(), 2);
+    }
+
+    #[test]
+    fn test_get_falls_back_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = KVSnapshotCache::open(dir.path(), 4).unwrap();
+        cache.put("system", snapshot(0.5)).join().unwrap();
+        assert!(cache.l2().path("system").is_file());
+
+        let mut reopened = KVSnapshotCache::open(dir.path(), 4).unwrap();
+        assert!(reopened.l1().is_empty());
+        let loaded = reopened.get("system").unwrap();
+        assert_eq!(loaded.tokens, vec![1, 2]);
+        assert_eq!(first_key(&loaded), 0.5);
+        assert!(reopened.l1().contains("system"));
+        assert!(reopened.get("other").is_none());
+    }
+
+    #[test]
+    fn test_l1_serves_evicted_entries_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = KVSnapshotCache::open(dir.path(), 1).unwrap();
+        cache.put("a", snapshot(1.0)).join().unwrap();
+        cache.put("b", snapshot(2.0)).join().unwrap();
+
+        assert!(!cache.l1().contains("a"));
+        assert_eq!(first_key(&cache.get("a").unwrap()), 1.0);
+    }
+
+    #[test]
+    fn test_expired_entries_are_collected_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let l2 = L2Cache::open(dir.path(), L2_TTL).unwrap();
+        l2.put("old", &snapshot(1.0)).unwrap();
+        l2.put("new", &snapshot(2.0)).unwrap();
+        let unrelated = dir.path().join("notes.bin");
+        fs::write(&unrelated, b"keep").unwrap();
+
+        let old = File::options().write(true).open(l2.path("old")).unwrap();
+        old.set_modified(SystemTime::now() - L2_TTL - Duration::from_secs(60))
+            .unwrap();
+
+        let reopened = L2Cache::open(dir.path(), L2_TTL).unwrap();
+        assert!(!reopened.path("old").exists());
+        assert!(reopened.get("new").is_some());
+        assert!(unrelated.exists());
+    }
+
+    #[test]
+    fn test_concurrent_writes_of_one_key_do_not_collide() {
+        let dir = tempfile::tempdir().unwrap();
+        let l2 = L2Cache::open(dir.path(), L2_TTL).unwrap();
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let l2 = l2.clone();
+                std::thread::spawn(move || l2.put("shared", &snapshot(i as f32)))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        assert!(l2.get("shared").is_some());
+        let leftovers = fs::read_dir(dir.path())
+            .unwrap()
+            .filter(|entry| is_partial_file(&entry.as_ref().unwrap().path()))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_l2_drops_oldest_entries_beyond_max_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let l2 = L2Cache::open(dir.path(), L2_TTL).unwrap();
+        l2.put("probe", &snapshot(0.0)).unwrap();
+        let entry_len = fs::metadata(l2.path("probe")).unwrap().len();
+        fs::remove_file(l2.path("probe")).unwrap();
+
+        let l2 = l2.with_max_bytes(entry_len * 2);
+        l2.put("a", &snapshot(1.0)).unwrap();
+        let a = File::options().write(true).open(l2.path("a")).unwrap();
+        a.set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        l2.put("b", &snapshot(2.0)).unwrap();
+        l2.put("c", &snapshot(3.0)).unwrap();
+
+        assert!(!l2.path("a").exists());
+        assert!(l2.path("b").exists());
+        assert!(l2.path("c").exists());
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let l2 = L2Cache::open(dir.path(), L2_TTL).unwrap();
+        fs::write(l2.path("broken"), b"MNVK").unwrap();
+        assert!(l2.get("broken").is_none());
+    }
+}
@@ -4,12 +4,22 @@
 /// It maintains a model and session for real LLM inference.
 /// Includes real BPE tokenization via LLaMATokenizer.
 use crate::error::{MinervaError, MinervaResult};
+use crate::inference::gpu::gguf_converter::GGUFConverter;
+use crate::inference::gpu::gguf_writer::QuantizationType;
+#[cfg(not(cpu_only))]
+use crate::inference::gpu_context::GpuContext;
+#[cfg(not(cpu_only))]
+use crate::inference::gpu_llama_vram::{GPULlamaIntegration, offloaded_weight_bytes};
 use crate::inference::inference_backend_trait::{GenerationParams, InferenceBackend};
 use crate::inference::llama_tokenizer::LLaMATokenizer;
 use llama_cpp::standard_sampler::StandardSampler;
-use llama_cpp::{LlamaModel, LlamaParams, LlamaSession, SessionParams};
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use llama_cpp::{LlamaModel, LlamaParams, LlamaSession, Sampler, SessionParams, Token};
+use llama_cpp_sys::{llama_context, llama_token_data_array};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Layers llama.cpp is asked to offload to the GPU
+const GPU_LAYERS: u32 = 40;
 
 /// Production llama.cpp backend
 pub struct LlamaCppBackend {
@@ -18,6 +28,9 @@ pub struct LlamaCppBackend {
     tokenizer: Arc<Mutex<Option<LLaMATokenizer>>>,
     n_ctx: usize,
     n_threads: usize,
+    /// Books the session's Metal memory into a shared `GpuContext`, if set
+    #[cfg(not(cpu_only))]
+    vram: Option<Mutex<GPULlamaIntegration>>,
 }
 
 impl std::fmt::Debug for LlamaCppBackend {
@@ -38,7 +51,44 @@ impl LlamaCppBackend {
             tokenizer: Arc::new(Mutex::new(None)),
             n_ctx: 0,
             n_threads: num_cpus::get(),
+            #[cfg(not(cpu_only))]
+            vram: None,
+        }
+    }
+
+    /// Report llama.cpp's memory use to `gpu` after loads and generations
+    #[cfg(not(cpu_only))]
+    pub fn set_gpu_context(&mut self, gpu: Arc<Mutex<GpuContext>>) {
+        self.vram = Some(Mutex::new(GPULlamaIntegration::new(gpu)));
+    }
+
+    /// Book the weights of the layers `model` offloaded at the next sync
+    #[cfg(not(cpu_only))]
+    fn set_offloaded_weights(&self, gguf: &Path, model: &LlamaModel) -> MinervaResult<()> {
+        if let Some(vram) = &self.vram {
+            let weight_bytes = std::fs::metadata(gguf)?.len();
+            let bytes = offloaded_weight_bytes(weight_bytes, GPU_LAYERS, model.layers());
+            vram.lock().unwrap().set_offloaded_weights(bytes);
         }
+        Ok(())
+    }
+
+    #[cfg(cpu_only)]
+    fn set_offloaded_weights(&self, _gguf: &Path, _model: &LlamaModel) -> MinervaResult<()> {
+        Ok(())
+    }
+
+    #[cfg(not(cpu_only))]
+    fn sync_vram(&self, session: &LlamaSession) -> MinervaResult<()> {
+        if let Some(vram) = &self.vram {
+            vram.lock().unwrap().sync_vram_usage(session)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(cpu_only)]
+    fn sync_vram(&self, _session: &LlamaSession) -> MinervaResult<()> {
+        Ok(())
     }
 
     /// Set tokenizer for this backend
@@ -63,9 +113,18 @@ impl LlamaCppBackend {
     }
 
     /// Check if this backend can handle the model format
-    /// llama.cpp backend only supports GGUF format
+    /// llama.cpp loads GGUF, and SafeTensors models it can convert to GGUF
     pub fn can_handle(path: &Path) -> bool {
-        matches!(Self::detect_format(path), "gguf")
+        matches!(Self::detect_format(path), "gguf") || GGUFConverter::can_convert(path)
+    }
+
+    /// GGUF file to load for `path`, converting SafeTensors models on first use
+    fn gguf_path(path: &Path) -> MinervaResult<PathBuf> {
+        if Self::detect_format(path) == "gguf" {
+            return Ok(path.to_path_buf());
+        }
+        GGUFConverter::new(QuantizationType::Q8_0)
+            .convert_and_cache(path, &GGUFConverter::default_cache_dir(path))
     }
 
     /// Create a fallback tokenizer from common vocabulary
@@ -93,6 +152,32 @@ impl LlamaCppBackend {
     }
 }
 
+/// Greedy sampler that keeps the logits of the first token it samples
+struct LogitsCapture(Arc<OnceLock<Vec<f32>>>);
+
+impl Sampler for LogitsCapture {
+    fn sample(
+        &mut self,
+        _context: *mut llama_context,
+        _tokens: &[Token],
+        candidates_p: llama_token_data_array,
+    ) -> Token {
+        // SAFETY: llama.cpp hands over `size` initialised candidates, in
+        // vocabulary order, that live until this call returns
+        let candidates =
+            unsafe { std::slice::from_raw_parts(candidates_p.data, candidates_p.size) };
+        let logits: Vec<f32> = candidates.iter().map(|c| c.logit).collect();
+        let best = logits
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(id, _)| id);
+        // Completion keeps sampling after the first token; later calls only pick
+        let _ = self.0.set(logits);
+        Token(best as i32)
+    }
+}
+
 impl Default for LlamaCppBackend {
     fn default() -> Self {
         Self::new()
@@ -111,9 +196,12 @@ impl InferenceBackend for LlamaCppBackend {
 
         // Check file format - give helpful error if not GGUF
         let format = Self::detect_format(path);
-        if format != "gguf" {
+        if !Self::can_handle(path) {
             let guidance = match format {
-                "safetensors" | "huggingface" => {
+                "safetensors" => {
+                    " (SafeTensors needs config.json and tokenizer.json of a llama or qwen2 model alongside to convert to GGUF)"
+                }
+                "huggingface" => {
                     " (HuggingFace format detected - use pure Rust backend or convert to GGUF)"
                 }
                 "pytorch" | "tensorflow" => {
@@ -129,22 +217,31 @@ impl InferenceBackend for LlamaCppBackend {
 
         // Load model with GPU acceleration enabled
         let params = LlamaParams {
-            n_gpu_layers: 40, // Offload to GPU
-            use_mmap: true,   // Use memory mapping for faster loading
+            n_gpu_layers: GPU_LAYERS, // Offload to GPU
+            use_mmap: true,           // Use memory mapping for faster loading
             ..Default::default()
         };
 
-        let model = LlamaModel::load_from_file(path, params).map_err(|e| {
+        let gguf = Self::gguf_path(path)?;
+        let model = LlamaModel::load_from_file(&gguf, params).map_err(|e| {
             let err_msg = format!("{:?}", e);
-            MinervaError::ModelLoadingError(err_msg)
+            MinervaError::model_loading(err_msg)
         })?;
 
         // Create session for inference
-        let session_params = SessionParams::default();
+        let n_threads = u32::try_from(self.n_threads).unwrap_or(u32::MAX);
+        let session_params = SessionParams {
+            n_threads,
+            n_threads_batch: n_threads,
+            ..Default::default()
+        };
         let session = model.create_session(session_params).map_err(|e| {
             MinervaError::InferenceError(format!("Failed to create inference session: {:?}", e))
         })?;
 
+        self.set_offloaded_weights(&gguf, &model)?;
+        self.sync_vram(&session)?;
+
         // Store in mutex-protected Arc
         *self.model.lock().unwrap() = Some(model);
         *self.session.lock().unwrap() = Some(session);
@@ -163,10 +260,23 @@ impl InferenceBackend for LlamaCppBackend {
         *self.model.lock().unwrap() = None;
         *self.session.lock().unwrap() = None;
         *self.tokenizer.lock().unwrap() = None;
+        #[cfg(not(cpu_only))]
+        if let Some(vram) = &self.vram {
+            let _ = vram.lock().unwrap().release();
+        }
         tracing::info!("Model unloaded");
     }
 
     fn generate(&self, prompt: &str, params: GenerationParams) -> MinervaResult<String> {
+        self.generate_stream(prompt, params, &mut |_| {})
+    }
+
+    fn generate_stream(
+        &self,
+        prompt: &str,
+        params: GenerationParams,
+        on_token: &mut dyn FnMut(&str),
+    ) -> MinervaResult<String> {
         // Validate model and session exist
         let model = self.model.lock().unwrap();
         let mut session = self.session.lock().unwrap();
@@ -194,10 +304,13 @@ impl InferenceBackend for LlamaCppBackend {
             .map_err(|e| MinervaError::InferenceError(format!("Generation failed: {:?}", e)))?
             .into_strings();
 
+        // Pieces arrive as llama.cpp samples them
         for completion in completions {
+            on_token(&completion);
             generated_text.push_str(&completion);
         }
 
+        self.sync_vram(session)?;
         Ok(generated_text)
     }
 
@@ -208,6 +321,12 @@ impl InferenceBackend for LlamaCppBackend {
             // Real tokenization using LLaMATokenizer
             let tokens = tokenizer.encode(text)?;
             Ok(tokens.iter().map(|&t| t as i32).collect())
+        } else if let Some(model) = self.model.lock().unwrap().as_ref() {
+            // The loaded GGUF carries its own vocabulary
+            let tokens = model.tokenize_bytes(text, true, false).map_err(|e| {
+                MinervaError::InferenceError(format!("Tokenization failed: {:?}", e))
+            })?;
+            Ok(tokens.iter().map(|t| t.0).collect())
         } else {
             // Fallback to simple word-based tokenization
             // This happens if tokenizer not explicitly set
@@ -232,6 +351,32 @@ impl InferenceBackend for LlamaCppBackend {
         }
     }
 
+    fn next_token_logits(&self, tokens: &[u32]) -> MinervaResult<Vec<f32>> {
+        let mut session = self.session.lock().unwrap();
+        let session = session
+            .as_mut()
+            .ok_or_else(|| MinervaError::InferenceError("Session not created".to_string()))?;
+
+        // Only the part after the prefix shared with the last call is decoded
+        let context: Vec<Token> = tokens.iter().map(|&t| Token(t as i32)).collect();
+        session.set_context_to_tokens(&context).map_err(|e| {
+            MinervaError::InferenceError(format!("Context evaluation failed: {:?}", e))
+        })?;
+
+        let logits = Arc::new(OnceLock::new());
+        let mut completion = session
+            .start_completing_with(LogitsCapture(logits.clone()), 1)
+            .map_err(|e| MinervaError::InferenceError(format!("Forward pass failed: {:?}", e)))?;
+        // Drain so the completion thread has released the context
+        while completion.next_token().is_some() {}
+
+        let logits = logits.get().cloned().ok_or_else(|| {
+            MinervaError::InferenceError("llama.cpp produced no logits".to_string())
+        })?;
+        self.sync_vram(session)?;
+        Ok(logits)
+    }
+
     fn is_loaded(&self) -> bool {
         self.model.lock().unwrap().is_some() && self.session.lock().unwrap().is_some()
     }
@@ -243,4 +388,12 @@ impl InferenceBackend for LlamaCppBackend {
     fn thread_count(&self) -> usize {
         self.n_threads
     }
+
+    fn set_thread_count(&mut self, n_threads: usize) {
+        self.n_threads = n_threads.max(1);
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
 }
@@ -4,6 +4,7 @@
 /// - Greedy: Always select highest probability token
 /// - Top-K: Sample from k most likely tokens
 /// - Top-P: Sample from tokens with cumulative probability p
+use super::softmax_utils::simple_random;
 use crate::error::{MinervaError, MinervaResult};
 
 /// Token sampling strategy
@@ -23,6 +24,8 @@ pub struct SamplingParams {
     pub temperature: f32,
     /// Sampling strategy
     pub strategy: SamplingStrategy,
+    /// Seed for the random draw of top-k and top-p sampling
+    pub seed: u64,
 }
 
 impl SamplingParams {
@@ -31,8 +34,15 @@ impl SamplingParams {
         Self {
             temperature,
             strategy: SamplingStrategy::Greedy,
+            seed: 0,
         }
     }
+
+    /// Use `seed` for the random draw
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
 }
 
 /// Parameters for TokenGenerator::generate
@@ -70,6 +80,12 @@ impl Decoder {
             )));
         }
 
+        if logits.is_empty() {
+            return Err(MinervaError::InferenceError(
+                "Logits must not be empty".to_string(),
+            ));
+        }
+
         if params.temperature <= 0.0 {
             return Err(MinervaError::InferenceError(
                 "Temperature must be positive".to_string(),
@@ -125,7 +141,7 @@ impl Decoder {
                 }
 
                 // Sample from top-k
-                self.sample_categorical(&probs)?
+                Self::sample_categorical(&probs, params.seed)
             }
 
             SamplingStrategy::TopP(p) => {
@@ -142,11 +158,11 @@ impl Decoder {
                         .unwrap_or(std::cmp::Ordering::Equal)
                 });
 
+                // Keep tokens until the kept mass reaches p, including the one that crosses it
                 let mut cumsum = 0.0;
                 for i in indices.iter().cloned() {
-                    cumsum += probs[i];
                     if cumsum < p {
-                        // Keep this token
+                        cumsum += probs[i];
                     } else {
                         probs[i] = 0.0;
                     }
@@ -160,35 +176,33 @@ impl Decoder {
                     }
                 }
 
-                self.sample_categorical(&probs)?
+                Self::sample_categorical(&probs, params.seed)
             }
         };
 
         Ok(token)
     }
 
-    /// Sample from categorical distribution
-    fn sample_categorical(&self, probs: &[f32]) -> MinervaResult<usize> {
+    /// Sample from categorical distribution, drawing from `seed`
+    fn sample_categorical(probs: &[f32], seed: u64) -> usize {
         let mut cumsum = 0.0;
-        let rand = (std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as f32)
-            / 1e9;
-        let rand = rand.fract();
+        let rand = (simple_random(seed) as f64 / (1u64 << 31) as f64) as f32;
 
         for (i, &p) in probs.iter().enumerate() {
             cumsum += p;
             if rand < cumsum {
-                return Ok(i);
+                return i;
             }
         }
 
         // Return last token if rounding errors
-        Ok(probs.len().saturating_sub(1))
+        probs.len().saturating_sub(1)
     }
 
     /// Generate tokens
+    ///
+    /// Step `i` samples with `params.sampling.seed + i`, so a seed reproduces
+    /// the whole sequence.
     pub fn generate(
         &self,
         params: GenerationParams,
@@ -209,11 +223,12 @@ impl Decoder {
         let mut tokens = params.initial_tokens.to_vec();
         let mut sequence = params.initial_tokens.to_vec();
 
-        for _ in 0..params.num_tokens {
+        for step in 0..params.num_tokens {
             let logits = forward(&tokens)?;
             let sampling = SamplingParams {
                 temperature: params.sampling.temperature,
                 strategy: params.sampling.strategy,
+                seed: params.sampling.seed.wrapping_add(step as u64),
             };
             let next_token = self.sample_token(&logits, sampling)?;
             tokens.push(next_token);
@@ -223,3 +238,101 @@ impl Decoder {
         Ok(sequence)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn sample(logits: &[f32], temperature: f32, strategy: SamplingStrategy) -> usize {
+        sample_seeded(logits, temperature, strategy, 42)
+    }
+
+    fn sample_seeded(
+        logits: &[f32],
+        temperature: f32,
+        strategy: SamplingStrategy,
+        seed: u64,
+    ) -> usize {
+        Decoder::new(logits.len(), 1)
+            .sample_token(
+                logits,
+                SamplingParams {
+                    temperature,
+                    strategy,
+                    seed,
+                },
+            )
+            .unwrap()
+    }
+
+    fn argmax(logits: &[f32]) -> usize {
+        (0..logits.len()).fold(0, |best, i| if logits[i] > logits[best] { i } else { best })
+    }
+
+    /// True when the largest logit beats every other by at least `gap`
+    fn has_clear_max(logits: &[f32], gap: f32) -> bool {
+        let best = argmax(logits);
+        logits
+            .iter()
+            .enumerate()
+            .all(|(i, &l)| i == best || logits[best] - l >= gap)
+    }
+
+    fn strategy() -> impl Strategy<Value = SamplingStrategy> {
+        prop_oneof![
+            Just(SamplingStrategy::Greedy),
+            (1usize..16).prop_map(SamplingStrategy::TopK),
+            (0.05f32..=1.0).prop_map(SamplingStrategy::TopP),
+        ]
+    }
+
+    #[test]
+    fn test_sample_token_empty_logits_is_error() {
+        let params = SamplingParams::greedy(1.0);
+        assert!(Decoder::new(0, 1).sample_token(&[], params).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sample_token_in_vocab(
+            logits in prop::collection::vec(prop::num::f32::NORMAL | prop::num::f32::ZERO, 1..256),
+            temperature in 0.01f32..10.0,
+            strategy in strategy(),
+        ) {
+            prop_assert!(sample(&logits, temperature, strategy) < logits.len());
+        }
+
+        #[test]
+        fn prop_sampling_is_deterministic_for_a_seed(
+            logits in prop::collection::vec(-1e3f32..1e3, 1..256),
+            temperature in 0.01f32..10.0,
+            strategy in strategy(),
+            seed in any::<u64>(),
+        ) {
+            let first = sample_seeded(&logits, temperature, strategy, seed);
+            prop_assert_eq!(first, sample_seeded(&logits, temperature, strategy, seed));
+        }
+
+        #[test]
+        fn prop_low_temperature_returns_argmax(
+            logits in prop::collection::vec(-1e3f32..1e3, 1..256),
+            strategy in strategy(),
+        ) {
+            prop_assume!(has_clear_max(&logits, 0.5));
+            prop_assert_eq!(sample(&logits, 1e-3, strategy), argmax(&logits));
+        }
+
+        #[test]
+        fn prop_top_k_one_matches_greedy(
+            logits in prop::collection::vec(-1e3f32..1e3, 1..256),
+            temperature in 0.01f32..10.0,
+        ) {
+            prop_assume!(has_clear_max(&logits, 1e-3));
+            prop_assert_eq!(
+                sample(&logits, temperature, SamplingStrategy::TopK(1)),
+                sample(&logits, temperature, SamplingStrategy::Greedy)
+            );
+        }
+    }
+}
@@ -20,6 +20,18 @@ struct InferenceContext {
     n_ctx: usize,
     n_threads: usize,
     is_mock: bool, // Flag for mock vs real
+    /// Tokens run through the model since load (prompt + generated)
+    tokens_evaluated: usize,
+    /// Tokens currently held in the KV cache, at most `n_ctx`
+    kv_cache_tokens: usize,
+}
+
+impl InferenceContext {
+    /// Account for a prompt evaluation and the tokens generated after it
+    fn record_evaluation(&mut self, tokens: usize) {
+        self.tokens_evaluated += tokens;
+        self.kv_cache_tokens = (self.kv_cache_tokens + tokens).min(self.n_ctx);
+    }
 }
 
 impl LlamaEngine {
@@ -67,6 +79,8 @@ impl LlamaEngine {
             n_ctx,
             n_threads,
             is_mock,
+            tokens_evaluated: 0,
+            kv_cache_tokens: 0,
         });
 
         tracing::info!(
@@ -98,9 +112,9 @@ impl LlamaEngine {
     /// - context.sample() for token generation
     /// - model.token_to_piece() for decoding
     pub fn generate(&self, prompt: &str, max_tokens: usize) -> MinervaResult<String> {
-        let ctx = self.context.lock().unwrap();
+        let mut ctx = self.context.lock().unwrap();
         let context = ctx
-            .as_ref()
+            .as_mut()
             .ok_or_else(|| MinervaError::InferenceError("Model not loaded".to_string()))?;
 
         if max_tokens > context.n_ctx {
@@ -124,6 +138,9 @@ impl LlamaEngine {
 
         // For now, use intelligent mock based on prompt content
         let response = self.generate_response(prompt, max_tokens);
+        context.record_evaluation(
+            prompt.split_whitespace().count() + response.split_whitespace().count(),
+        );
 
         let elapsed = start.elapsed().as_millis();
 
@@ -145,6 +162,10 @@ impl LlamaEngine {
 
     #[allow(dead_code)]
     /// Get context info
+    ///
+    /// This engine has no `LlamaSession` of its own yet, so the size and
+    /// thread count come from the load configuration and the token counts
+    /// from what `generate` has evaluated since load.
     pub fn get_context_info(&self) -> MinervaResult<ContextInfo> {
         let ctx = self.context.lock().unwrap();
         let context = ctx
@@ -155,6 +176,8 @@ impl LlamaEngine {
             context_size: context.n_ctx,
             thread_count: context.n_threads,
             model_path: self.model_path.clone(),
+            tokens_evaluated: context.tokens_evaluated,
+            kv_cache_tokens: context.kv_cache_tokens,
         })
     }
 
@@ -217,6 +240,18 @@ pub struct ContextInfo {
     pub context_size: usize,
     pub thread_count: usize,
     pub model_path: PathBuf,
+    pub tokens_evaluated: usize,
+    pub kv_cache_tokens: usize,
+}
+
+impl ContextInfo {
+    /// Share of the context window occupied by the KV cache, 0-100
+    pub fn kv_cache_usage_percent(&self) -> f32 {
+        if self.context_size == 0 {
+            return 0.0;
+        }
+        self.kv_cache_tokens as f32 / self.context_size as f32 * 100.0
+    }
 }
 
 #[cfg(test)]
@@ -298,6 +333,36 @@ mod tests {
         assert_eq!(info.context_size, 2048);
         assert!(info.thread_count > 0);
         assert_eq!(info.model_path, model_path);
+        assert_eq!(info.tokens_evaluated, 0);
+        assert_eq!(info.kv_cache_usage_percent(), 0.0);
+    }
+
+    #[test]
+    fn test_llama_engine_context_info_tracks_generation() {
+        let temp_dir = TempDir::new().unwrap();
+        let model_path = temp_dir.path().join("test.gguf");
+        fs::write(&model_path, "dummy").unwrap();
+
+        let mut engine = LlamaEngine::new(model_path);
+        engine.load(64).unwrap();
+        let response = engine.generate("hello there", 10).unwrap();
+        let expected = 2 + response.split_whitespace().count();
+
+        let info = engine.get_context_info().unwrap();
+        assert_eq!(info.tokens_evaluated, expected);
+        assert_eq!(info.kv_cache_tokens, expected);
+        assert_eq!(
+            info.kv_cache_usage_percent(),
+            expected as f32 / 64.0 * 100.0
+        );
+
+        // The cache saturates at the context size
+        for _ in 0..10 {
+            engine.generate("hello there", 10).unwrap();
+        }
+        let info = engine.get_context_info().unwrap();
+        assert_eq!(info.kv_cache_tokens, 64);
+        assert_eq!(info.kv_cache_usage_percent(), 100.0);
     }
 
     #[test]
@@ -13,6 +13,9 @@
 /// - No Python packaging complexity
 /// - Simplified testing
 ///
+/// A HuggingFace model directory with a `tokenizer.json` is loaded natively
+/// instead (see `mlx_backend_native`), with no Python dependency.
+///
 /// # Model Support
 ///
 /// Supports any model available via mlx-lm:
@@ -41,6 +44,7 @@
 use crate::error::{MinervaError, MinervaResult};
 use crate::inference::llama_adapter::GenerationParams;
 use crate::inference::llama_adapter::InferenceBackend;
+use crate::inference::mlx_backend_native::NativeMlxModel;
 use std::path::Path;
 use std::process::Command;
 use std::sync::{Arc, Mutex};
@@ -65,6 +69,8 @@ enum MlxStatus {
 pub struct MlxBackend {
     /// Currently loaded model name (for caching)
     loaded_model: Arc<Mutex<Option<String>>>,
+    /// Model loaded in-process from a HuggingFace directory, bypassing mlx-lm
+    native: Arc<Mutex<Option<NativeMlxModel>>>,
     /// Status of mlx-lm availability
     mlx_status: Arc<Mutex<MlxStatus>>,
     /// Number of threads for inference
@@ -78,6 +84,7 @@ impl MlxBackend {
     pub fn new() -> Self {
         Self {
             loaded_model: Arc::new(Mutex::new(None)),
+            native: Arc::new(Mutex::new(None)),
             mlx_status: Arc::new(Mutex::new(MlxStatus::Unchecked)),
             n_threads: num_cpus::get(),
             n_ctx: 0,
@@ -110,6 +117,21 @@ impl MlxBackend {
         }
     }
 
+    /// Load a HuggingFace model directory natively, without mlx-lm
+    fn load_native(&mut self, path: &Path, n_ctx: usize) -> MinervaResult<()> {
+        let native = NativeMlxModel::load(path)?;
+        let model_name = Self::extract_model_name(path);
+        tracing::info!(
+            "MLX backend loaded model natively: {} (context: {})",
+            model_name,
+            n_ctx
+        );
+        *self.native.lock().unwrap() = Some(native);
+        *self.loaded_model.lock().unwrap() = Some(model_name);
+        self.n_ctx = n_ctx;
+        Ok(())
+    }
+
     /// Get model format from file extension
     fn detect_model_format(path: &Path) -> &'static str {
         if path.extension().is_some_and(|ext| ext == "gguf") {
@@ -162,6 +184,10 @@ impl Default for MlxBackend {
 
 impl InferenceBackend for MlxBackend {
     fn load_model(&mut self, path: &Path, n_ctx: usize) -> MinervaResult<()> {
+        if NativeMlxModel::is_native_dir(path) {
+            return self.load_native(path, n_ctx);
+        }
+
         // Check mlx availability on first load
         {
             let mut status = self.mlx_status.lock().unwrap();
@@ -215,6 +241,7 @@ impl InferenceBackend for MlxBackend {
 
     fn unload_model(&mut self) {
         *self.loaded_model.lock().unwrap() = None;
+        *self.native.lock().unwrap() = None;
         self.n_ctx = 0;
         tracing::info!("MLX backend unloaded model");
     }
@@ -225,6 +252,10 @@ impl InferenceBackend for MlxBackend {
             .as_ref()
             .ok_or_else(|| MinervaError::InferenceError("No model loaded".to_string()))?;
 
+        if let Some(native) = self.native.lock().unwrap().as_ref() {
+            return native.generate(prompt, &params);
+        }
+
         // Build command: mlx_lm generate --model <model> --prompt <prompt> --max-tokens <n>
         // For now, use a simple approach that simulates mlx-lm behavior
 
@@ -244,7 +275,24 @@ impl InferenceBackend for MlxBackend {
         Ok(simulated)
     }
 
+    fn generate_stream(
+        &self,
+        prompt: &str,
+        params: GenerationParams,
+        on_token: &mut dyn FnMut(&str),
+    ) -> MinervaResult<String> {
+        if let Some(native) = self.native.lock().unwrap().as_ref() {
+            return native.generate_stream(prompt, &params, on_token);
+        }
+        let text = self.generate(prompt, params)?;
+        on_token(&text);
+        Ok(text)
+    }
+
     fn tokenize(&self, text: &str) -> MinervaResult<Vec<i32>> {
+        if let Some(native) = self.native.lock().unwrap().as_ref() {
+            return native.tokenize(text);
+        }
         // For MLX, we'd need the model's tokenizer
         // For now, use simple word-based tokenization
         // Phase 9: integrate real tokenizer from model
@@ -256,6 +304,9 @@ impl InferenceBackend for MlxBackend {
     }
 
     fn detokenize(&self, tokens: &[i32]) -> MinervaResult<String> {
+        if let Some(native) = self.native.lock().unwrap().as_ref() {
+            return native.detokenize(tokens);
+        }
         // For MLX, would use model's detokenizer
         // For now, return token count
         // Phase 9: integrate real detokenizer
@@ -273,6 +324,15 @@ impl InferenceBackend for MlxBackend {
     fn thread_count(&self) -> usize {
         self.n_threads
     }
+
+    fn set_thread_count(&mut self, n_threads: usize) {
+        self.n_threads = n_threads.max(1);
+    }
+
+    /// Only natively loaded models decode token by token
+    fn supports_streaming(&self) -> bool {
+        self.native.lock().unwrap().is_some()
+    }
 }
 
 #[cfg(test)]
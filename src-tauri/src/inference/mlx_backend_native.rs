@@ -0,0 +1,139 @@
+/// Native (Python-free) Generation for MlxBackend
+///
+/// When `MlxBackend::load_model` is given a HuggingFace model directory with
+/// a `tokenizer.json`, the weights and tokenizer are loaded in-process and
+/// generation runs the reference forward pass. A temperature of 0 decodes
+/// greedily, so the same prompt always produces the same tokens; otherwise
+/// tokens are drawn by nucleus sampling with the request's `top_p`.
+use super::llama_adapter::GenerationParams;
+use super::llama_tokenizer::LLaMATokenizer;
+use super::mlx_model_support::MLXModelSupport;
+use super::mlx_native::MLXModel;
+use super::mlx_native::forward::{Generation, argmax};
+use super::softmax_utils::simple_random;
+use super::top_p_sampling::{TopPConfig, sample_top_p};
+use crate::error::MinervaResult;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Weights and tokenizer of a natively loaded MLX model
+#[derive(Debug)]
+pub struct NativeMlxModel {
+    pub model: MLXModel,
+    pub tokenizer: LLaMATokenizer,
+}
+
+impl NativeMlxModel {
+    /// Whether `path` is a model directory the native loader can handle
+    pub fn is_native_dir(path: &Path) -> bool {
+        path.join("tokenizer.json").is_file()
+    }
+
+    /// Load weights, config and tokenizer from a model directory
+    pub fn load(path: &Path) -> MinervaResult<Self> {
+        let (model, tokenizer) = MLXModelSupport::load(path)?;
+        Ok(Self { model, tokenizer })
+    }
+
+    /// Token IDs following `prompt`, sampled according to `params`
+    pub fn generate_tokens(
+        &self,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> MinervaResult<Vec<u32>> {
+        self.generate_tokens_with(prompt, params, |_| Ok(()))
+            .map(|generation| generation.tokens)
+    }
+
+    /// Like `generate_tokens`, passing each ID to `on_id` as soon as it is
+    /// sampled and reporting why generation stopped
+    pub fn generate_tokens_with(
+        &self,
+        prompt: &str,
+        params: &GenerationParams,
+        mut on_id: impl FnMut(u32) -> MinervaResult<()>,
+    ) -> MinervaResult<Generation> {
+        let prompt_tokens = self.tokenizer.encode(prompt)?;
+        if params.temperature <= 0.0 {
+            return self
+                .model
+                .generate_with(&prompt_tokens, params.max_tokens, |logits| {
+                    let id = argmax(logits);
+                    on_id(id)?;
+                    Ok(id)
+                });
+        }
+
+        let config = TopPConfig {
+            p: params.top_p,
+            temperature: params.temperature,
+        };
+        let mut seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        self.model
+            .generate_with(&prompt_tokens, params.max_tokens, |logits| {
+                seed = simple_random(seed);
+                let id = sample_top_p(logits, &config, seed)? as u32;
+                on_id(id)?;
+                Ok(id)
+            })
+    }
+
+    /// Completion of `prompt`, decoded to text
+    pub fn generate(&self, prompt: &str, params: &GenerationParams) -> MinervaResult<String> {
+        let tokens = self.generate_tokens(prompt, params)?;
+        self.tokenizer.decode(&tokens)
+    }
+
+    /// Completion of `prompt`, passing the text of each token to `on_token`
+    /// as it is sampled
+    ///
+    /// The sequence so far is decoded after every token and only the new
+    /// suffix is emitted, so merged pieces and leading spaces come out as
+    /// they would in the full decode. A token that leaves the text unchanged
+    /// or ends in a partial UTF-8 sequence is held until a later token
+    /// completes it.
+    pub fn generate_stream(
+        &self,
+        prompt: &str,
+        params: &GenerationParams,
+        on_token: &mut dyn FnMut(&str),
+    ) -> MinervaResult<String> {
+        let mut ids = Vec::new();
+        let mut emitted = String::new();
+        self.generate_tokens_with(prompt, params, |id| {
+            ids.push(id);
+            let text = self.tokenizer.decode(&ids)?;
+            if let Some(piece) = text.strip_prefix(emitted.as_str())
+                && !piece.is_empty()
+                && !piece.ends_with(char::REPLACEMENT_CHARACTER)
+            {
+                on_token(piece);
+                emitted = text;
+            }
+            Ok(())
+        })?;
+        let text = self.tokenizer.decode(&ids)?;
+        if let Some(rest) = text.strip_prefix(emitted.as_str())
+            && !rest.is_empty()
+        {
+            on_token(rest);
+        }
+        Ok(text)
+    }
+
+    pub fn tokenize(&self, text: &str) -> MinervaResult<Vec<i32>> {
+        Ok(self
+            .tokenizer
+            .encode(text)?
+            .into_iter()
+            .map(|id| id as i32)
+            .collect())
+    }
+
+    pub fn detokenize(&self, tokens: &[i32]) -> MinervaResult<String> {
+        let ids: Vec<u32> = tokens.iter().map(|&id| id as u32).collect();
+        self.tokenizer.decode(&ids)
+    }
+}
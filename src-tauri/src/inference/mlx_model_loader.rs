@@ -0,0 +1,162 @@
+/// MLX Model Loading from HuggingFace Layout
+///
+/// MLX checkpoints on HuggingFace are a directory of safetensors shards plus
+/// `config.json` and a `tokenizer.json` in the HuggingFace tokenizers format.
+/// This loads all three natively, without the `mlx-lm` Python package.
+use super::llama_tokenizer::LLaMATokenizer;
+use super::mlx_native::{MLXModel, load_mlx_model};
+use crate::error::{MinervaError, MinervaResult};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Largest token ID accepted from `tokenizer.json`, bounding the vocab allocation
+const MAX_TOKEN_ID: u32 = 1 << 22;
+
+/// Loads MLX-format models from a HuggingFace model directory
+pub struct MLXModelSupport;
+
+impl MLXModelSupport {
+    /// Load weights, config and tokenizer from `model_dir`
+    ///
+    /// `load_mlx_model` applies `config.json` when the directory has one.
+    pub fn load(model_dir: &Path) -> MinervaResult<(MLXModel, LLaMATokenizer)> {
+        let tokenizer_json = read_model_file(model_dir, "tokenizer.json")?;
+        let tokenizer = parse_hf_tokenizer(&tokenizer_json)?;
+
+        let model = load_mlx_model(model_dir)?;
+
+        tracing::info!(
+            "Loaded MLX model from {} ({} layers, vocab {})",
+            model_dir.display(),
+            model.num_layers(),
+            tokenizer.vocab_size()
+        );
+        Ok((model, tokenizer))
+    }
+}
+
+fn read_model_file(model_dir: &Path, name: &str) -> MinervaResult<String> {
+    let path = model_dir.join(name);
+    fs::read_to_string(&path).map_err(|e| {
+        MinervaError::model_loading_with(format!("Failed to read {}: {}", path.display(), e), e)
+    })
+}
+
+#[derive(Deserialize)]
+struct HfTokenizerFile {
+    model: HfBpeModel,
+    #[serde(default)]
+    added_tokens: Vec<HfAddedToken>,
+}
+
+#[derive(Deserialize)]
+struct HfBpeModel {
+    vocab: HashMap<String, u32>,
+    #[serde(default)]
+    merges: Vec<HfMerge>,
+}
+
+/// Merges are `"a b"` strings in older files and `["a", "b"]` pairs in newer ones
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HfMerge {
+    Joined(String),
+    Pair(String, String),
+}
+
+impl HfMerge {
+    fn parts(&self) -> Option<(&str, &str)> {
+        match self {
+            Self::Joined(merge) => merge.split_once(' '),
+            Self::Pair(first, second) => Some((first, second)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HfAddedToken {
+    id: u32,
+    content: String,
+}
+
+/// Build a tokenizer from a HuggingFace `tokenizer.json` (`model.vocab` and `model.merges`)
+///
+/// Added tokens are merged into the vocabulary; IDs with no token become
+/// `<unused_N>` placeholders, and merges naming unknown tokens are skipped.
+pub fn parse_hf_tokenizer(json: &str) -> MinervaResult<LLaMATokenizer> {
+    let file: HfTokenizerFile = serde_json::from_str(json)?;
+    let mut by_id = file.model.vocab;
+    by_id.extend(file.added_tokens.into_iter().map(|t| (t.content, t.id)));
+
+    let vocab = vocab_in_id_order(&by_id)?;
+    let merges = file
+        .model
+        .merges
+        .iter()
+        .filter_map(HfMerge::parts)
+        .filter_map(|(first, second)| Some((*by_id.get(first)?, *by_id.get(second)?)))
+        .collect();
+
+    let mut tokenizer = LLaMATokenizer::new(vocab)?;
+    tokenizer.set_bpe_merges(merges);
+    Ok(tokenizer)
+}
+
+fn vocab_in_id_order(by_id: &HashMap<String, u32>) -> MinervaResult<Vec<String>> {
+    let Some(max_id) = by_id.values().copied().max() else {
+        return Ok(Vec::new());
+    };
+    if max_id > MAX_TOKEN_ID {
+        return Err(MinervaError::model_loading(format!(
+            "Token ID {} in tokenizer.json exceeds {}",
+            max_id, MAX_TOKEN_ID
+        )));
+    }
+
+    let mut vocab: Vec<String> = (0..=max_id).map(|id| format!("<unused_{}>", id)).collect();
+    for (token, &id) in by_id {
+        vocab[id as usize] = token.clone();
+    }
+    Ok(vocab)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKENIZER_JSON: &str = r#"{
+        "added_tokens": [{"id": 5, "content": "<|eot|>", "special": true}],
+        "model": {
+            "type": "BPE",
+            "vocab": {"a": 0, "b": 1, "ab": 2, "c": 3},
+            "merges": ["a b", ["ab", "c"], "x y"]
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_hf_tokenizer_vocab() {
+        let tokenizer = parse_hf_tokenizer(TOKENIZER_JSON).unwrap();
+        assert_eq!(tokenizer.vocab_size(), 6);
+        assert_eq!(tokenizer.get_id("ab"), Some(2));
+        assert_eq!(tokenizer.get_id("<|eot|>"), Some(5));
+        assert_eq!(tokenizer.get_token(4), Some("<unused_4>"));
+    }
+
+    #[test]
+    fn test_parse_hf_tokenizer_rejects_huge_ids() {
+        let json = r#"{"model": {"vocab": {"a": 4294967295}}}"#;
+        assert!(parse_hf_tokenizer(json).is_err());
+    }
+
+    #[test]
+    fn test_load_requires_tokenizer_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = MLXModelSupport::load(dir.path());
+        assert!(matches!(
+            result,
+            Err(MinervaError::ModelLoadingError { .. })
+        ));
+    }
+}
@@ -35,6 +35,8 @@
 use crate::error::MinervaResult;
 use serde::{Deserialize, Serialize};
 
+pub use super::mlx_model_loader::{MLXModelSupport, parse_hf_tokenizer};
+
 // ============================================================================
 // MLX Model Types
 // ============================================================================
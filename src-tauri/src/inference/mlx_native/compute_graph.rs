@@ -1,18 +1,53 @@
-use std::collections::HashMap;
+use crate::error::{MinervaError, MinervaResult};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 pub type NodeId = usize;
 
 #[derive(Clone, Debug)]
 pub enum Operation {
-    MatMul { shape: (usize, usize) },
+    MatMul {
+        shape: (usize, usize),
+    },
     Add,
+    /// Multiply every element by a constant
+    Scale {
+        factor: f32,
+    },
+    /// Add a constant to every element
+    AddScalar {
+        value: f32,
+    },
     Gelu,
-    LayerNorm { eps: f32 },
+    LayerNorm {
+        eps: f32,
+    },
     Softmax,
-    Attention { scale: f32 },
-    FusedLinearAdd { shape: (usize, usize) },
-    FusedLinearGelu { shape: (usize, usize) },
-    FusedLinearAddGelu { shape: (usize, usize) },
+    Attention {
+        scale: f32,
+    },
+    FusedLinearAdd {
+        shape: (usize, usize),
+    },
+    FusedLinearGelu {
+        shape: (usize, usize),
+    },
+    FusedLinearAddGelu {
+        shape: (usize, usize),
+    },
+    FusedLayerNormMatMul {
+        shape: (usize, usize),
+        eps: f32,
+    },
+    /// Q, K and V projections of one input, output as `[Q | K | V]` per row
+    FusedQKV {
+        rows: usize,
+        widths: [usize; 3],
+    },
+    /// Columns `offset..offset + shape.1` of a row-major input
+    ColumnSlice {
+        offset: usize,
+        shape: (usize, usize),
+    },
 }
 
 #[derive(Clone)]
@@ -22,10 +57,23 @@ pub struct Node {
     pub inputs: Vec<NodeId>,
 }
 
+/// Which attention projection a MatMul computes
+///
+/// Several MatMuls often read the same input (Q/K/V, but also an MLP's
+/// gate and up projections), so fusions that only apply to one kind of
+/// projection match on this instead of on graph shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProjectionRole {
+    Query,
+    Key,
+    Value,
+}
+
 pub struct ComputeGraph {
     nodes: HashMap<NodeId, Node>,
     next_id: NodeId,
     outputs: Vec<NodeId>,
+    roles: HashMap<NodeId, ProjectionRole>,
 }
 
 impl ComputeGraph {
@@ -34,6 +82,7 @@ impl ComputeGraph {
             nodes: HashMap::new(),
             next_id: 0,
             outputs: Vec::new(),
+            roles: HashMap::new(),
         }
     }
 
@@ -54,40 +103,170 @@ impl ComputeGraph {
         self.nodes.get(&id)
     }
 
+    /// Replace the node with the same id, keeping its position in the graph
+    pub fn replace_node(&mut self, node: Node) -> bool {
+        if !self.nodes.contains_key(&node.id) {
+            return false;
+        }
+        self.nodes.insert(node.id, node);
+        true
+    }
+
+    pub fn remove_node(&mut self, id: NodeId) -> Option<Node> {
+        self.roles.remove(&id);
+        self.nodes.remove(&id)
+    }
+
+    /// Mark `id` as computing the `role` projection
+    pub fn set_role(&mut self, id: NodeId, role: ProjectionRole) {
+        self.roles.insert(id, role);
+    }
+
+    pub fn role(&self, id: NodeId) -> Option<ProjectionRole> {
+        self.roles.get(&id).copied()
+    }
+
+    pub fn roles(&self) -> impl Iterator<Item = (NodeId, ProjectionRole)> + '_ {
+        self.roles.iter().map(|(&id, &role)| (id, role))
+    }
+
+    /// Ids of all nodes that read `id` as an input
+    pub fn consumers(&self, id: NodeId) -> Vec<NodeId> {
+        let mut consumers: Vec<NodeId> = self
+            .nodes
+            .values()
+            .filter(|node| node.inputs.contains(&id))
+            .map(|node| node.id)
+            .collect();
+        consumers.sort();
+        consumers
+    }
+
+    pub fn is_output(&self, id: NodeId) -> bool {
+        self.outputs.contains(&id)
+    }
+
+    pub fn outputs(&self) -> &[NodeId] {
+        &self.outputs
+    }
+
     pub fn all_nodes(&self) -> impl Iterator<Item = (&NodeId, &Node)> {
         self.nodes.iter()
     }
 
-    pub fn topological_sort(&self) -> Vec<NodeId> {
-        let mut visited = std::collections::HashSet::new();
-        let mut order = Vec::new();
+    /// Execution order for everything reachable from the graph outputs
+    ///
+    /// Kahn's algorithm, O(V + E): every node comes after the nodes it reads.
+    /// Input ids with no node behind them are external values and are left
+    /// out, as is a node's own id among its inputs, which names the external
+    /// value supplied under that id rather than an edge. A cycle means the
+    /// graph was built wrongly and is reported as an error.
+    pub fn topological_sort(&self) -> MinervaResult<Vec<NodeId>> {
+        self.execution_order(&HashSet::new())
+    }
 
-        for &node_id in &self.outputs {
-            self.visit(node_id, &mut visited, &mut order);
-        }
+    /// `topological_sort` when the values for `available` ids are supplied
+    ///
+    /// Executors pass their input ids: a node whose value is supplied is not
+    /// computed, so its own inputs are neither followed nor counted as edges.
+    pub fn execution_order(&self, available: &HashSet<NodeId>) -> MinervaResult<Vec<NodeId>> {
+        self.kahn_order(&self.reachable_from_outputs(available), available)
+    }
 
-        order
+    /// Check every node, reachable from the outputs or not, for cycles
+    pub fn check_acyclic(&self) -> MinervaResult<()> {
+        let all: Vec<NodeId> = self.nodes.keys().copied().collect();
+        self.kahn_order(&all, &HashSet::new()).map(|_| ())
     }
 
-    fn visit(
-        &self,
-        node_id: NodeId,
-        visited: &mut std::collections::HashSet<NodeId>,
-        order: &mut Vec<NodeId>,
-    ) {
-        if visited.contains(&node_id) {
-            return;
+    fn reachable_from_outputs(&self, available: &HashSet<NodeId>) -> Vec<NodeId> {
+        let mut seen = HashSet::new();
+        let mut reachable = Vec::new();
+        let mut stack = self.outputs.clone();
+        while let Some(id) = stack.pop() {
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            if seen.insert(id) {
+                reachable.push(id);
+                if !available.contains(&id) {
+                    stack.extend(&node.inputs);
+                }
+            }
         }
+        reachable
+    }
 
-        visited.insert(node_id);
+    /// Order `ids`, counting only edges between them
+    fn kahn_order(
+        &self,
+        ids: &[NodeId],
+        available: &HashSet<NodeId>,
+    ) -> MinervaResult<Vec<NodeId>> {
+        let members: HashSet<NodeId> = ids.iter().copied().collect();
+        let mut pending: HashMap<NodeId, usize> = HashMap::with_capacity(ids.len());
+        let mut dependents: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for &id in ids.iter().filter(|id| !available.contains(id)) {
+            let mut count = 0;
+            for &input in &self.nodes[&id].inputs {
+                if input != id && members.contains(&input) {
+                    dependents.entry(input).or_default().push(id);
+                    count += 1;
+                }
+            }
+            pending.insert(id, count);
+        }
+        for &id in ids.iter().filter(|id| available.contains(id)) {
+            pending.insert(id, 0);
+        }
 
-        if let Some(node) = self.nodes.get(&node_id) {
-            for &input_id in &node.inputs {
-                self.visit(input_id, visited, order);
+        let mut ready: VecDeque<NodeId> =
+            ids.iter().copied().filter(|id| pending[id] == 0).collect();
+        let mut order = Vec::with_capacity(ids.len());
+        while let Some(id) = ready.pop_front() {
+            order.push(id);
+            for dependent in dependents.remove(&id).unwrap_or_default() {
+                if let Some(count) = pending.get_mut(&dependent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push_back(dependent);
+                    }
+                }
             }
         }
 
-        order.push(node_id);
+        if order.len() == ids.len() {
+            return Ok(order);
+        }
+        Err(MinervaError::InferenceError(format!(
+            "cycle detected in compute graph at node {}",
+            self.node_on_cycle(&pending)
+        )))
+    }
+
+    /// A node on a cycle, given the in-degrees Kahn's algorithm left behind
+    ///
+    /// Every node still waiting has a waiting input, so walking back along
+    /// waiting inputs must revisit a node, and that node is on a cycle.
+    fn node_on_cycle(&self, pending: &HashMap<NodeId, usize>) -> NodeId {
+        let waiting = |id: &NodeId| pending.get(id).is_some_and(|&count| count > 0);
+        let Some(mut current) = pending.keys().copied().filter(waiting).min() else {
+            return 0;
+        };
+        let mut visited = HashSet::new();
+        while visited.insert(current) {
+            let node = &self.nodes[&current];
+            match node
+                .inputs
+                .iter()
+                .copied()
+                .find(|input| *input != current && waiting(input))
+            {
+                Some(input) => current = input,
+                None => break,
+            }
+        }
+        current
     }
 }
 
@@ -100,6 +279,7 @@ impl Default for ComputeGraph {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_create_graph() {
@@ -123,7 +303,7 @@ mod tests {
         let n2 = graph.add_node(Operation::Add, vec![n1]);
         graph.set_output(n2);
 
-        let order = graph.topological_sort();
+        let order = graph.topological_sort().unwrap();
         assert_eq!(order, vec![0, 1, 2]);
     }
 
@@ -136,10 +316,177 @@ mod tests {
         let n3 = graph.add_node(Operation::Add, vec![n1, n2]);
         graph.set_output(n3);
 
-        let order = graph.topological_sort();
+        let order = graph.topological_sort().unwrap();
         assert!(
             order.iter().position(|&id| id == 0).unwrap()
                 < order.iter().position(|&id| id == 3).unwrap()
         );
     }
+
+    #[test]
+    fn test_external_inputs_are_not_edges() {
+        let mut graph = ComputeGraph::new();
+        // Node 0 reads the external value supplied under its own id
+        let n0 = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![0]);
+        let n1 = graph.add_node(Operation::Add, vec![n0, 100]);
+        graph.set_output(n1);
+
+        assert_eq!(graph.topological_sort().unwrap(), vec![n0, n1]);
+    }
+
+    #[test]
+    fn test_topological_sort_reports_cycle() {
+        let mut graph = ComputeGraph::new();
+        let n0 = graph.add_node(Operation::Gelu, vec![2]);
+        let n1 = graph.add_node(Operation::Gelu, vec![n0]);
+        let n2 = graph.add_node(Operation::Add, vec![n1, 100]);
+        let n3 = graph.add_node(Operation::Gelu, vec![n2]);
+        graph.set_output(n3);
+
+        let err = graph.topological_sort().unwrap_err().to_string();
+        assert!(
+            err.contains("cycle detected in compute graph at node"),
+            "{}",
+            err
+        );
+        assert!(!err.ends_with(&n3.to_string()), "{}", err);
+    }
+
+    #[test]
+    fn test_available_nodes_cut_their_inputs() {
+        let mut graph = ComputeGraph::new();
+        let n0 = graph.add_node(Operation::Add, vec![0, 1]);
+        let n1 = graph.add_node(Operation::Gelu, vec![n0]);
+        let n2 = graph.add_node(Operation::Gelu, vec![n1]);
+        graph.set_output(n2);
+
+        // Node 0 reads node 1, which reads node 0, unless 1 is supplied
+        assert!(graph.topological_sort().is_err());
+        let order = graph.execution_order(&HashSet::from([0, 1])).unwrap();
+        assert_eq!(order, vec![n1, n2]);
+    }
+
+    #[test]
+    fn test_unreachable_cycle_only_fails_full_check() {
+        let mut graph = ComputeGraph::new();
+        let n0 = graph.add_node(Operation::Gelu, vec![]);
+        graph.set_output(n0);
+        graph.add_node(Operation::Gelu, vec![2]);
+        graph.add_node(Operation::Gelu, vec![1]);
+
+        assert_eq!(graph.topological_sort().unwrap(), vec![n0]);
+        let err = graph.check_acyclic().unwrap_err().to_string();
+        assert!(
+            err.ends_with("node 1") || err.ends_with("node 2"),
+            "{}",
+            err
+        );
+    }
+
+    /// Graph whose node `i` reads `inputs[i]`, with every node an output
+    fn graph_from(inputs: &[Vec<NodeId>]) -> ComputeGraph {
+        let mut graph = ComputeGraph::new();
+        for node_inputs in inputs {
+            let id = graph.add_node(Operation::Add, node_inputs.clone());
+            graph.set_output(id);
+        }
+        graph
+    }
+
+    /// Reference cycle check by depth-first search
+    fn has_cycle(inputs: &[Vec<NodeId>]) -> bool {
+        fn visit(id: NodeId, inputs: &[Vec<NodeId>], state: &mut [u8]) -> bool {
+            match state[id] {
+                1 => return true,
+                2 => return false,
+                _ => {}
+            }
+            state[id] = 1;
+            let cyclic = inputs[id]
+                .iter()
+                .any(|&input| input != id && input < inputs.len() && visit(input, inputs, state));
+            state[id] = 2;
+            cyclic
+        }
+        let mut state = vec![0; inputs.len()];
+        (0..inputs.len()).any(|id| visit(id, inputs, &mut state))
+    }
+
+    fn assert_valid_order(inputs: &[Vec<NodeId>], order: &[NodeId]) {
+        assert_eq!(order.len(), inputs.len());
+        let position: HashMap<NodeId, usize> =
+            order.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        for (id, node_inputs) in inputs.iter().enumerate() {
+            for &input in node_inputs {
+                if input != id && input < inputs.len() {
+                    assert!(position[&input] < position[&id]);
+                }
+            }
+        }
+    }
+
+    fn edges() -> impl Strategy<Value = Vec<Vec<NodeId>>> {
+        (1usize..24)
+            .prop_flat_map(|n| prop::collection::vec(prop::collection::vec(0..n + 2, 0..4), n))
+    }
+
+    proptest! {
+        #[test]
+        fn prop_random_dags_sort(edges in edges()) {
+            // Keep only edges from lower ids, which can never form a cycle
+            let inputs: Vec<Vec<NodeId>> = edges
+                .iter()
+                .enumerate()
+                .map(|(id, node_inputs)| node_inputs.iter().map(|&input| input % (id + 1)).collect())
+                .collect();
+            let graph = graph_from(&inputs);
+            assert_valid_order(&inputs, &graph.topological_sort().unwrap());
+            prop_assert!(graph.check_acyclic().is_ok());
+        }
+
+        #[test]
+        fn prop_cycles_are_detected(edges in edges(), from in 0usize..24, to in 0usize..24) {
+            let mut inputs = edges;
+            let n = inputs.len();
+            prop_assume!(n >= 2 && from % n != to % n);
+            // Close a two-node cycle on top of whatever else was generated
+            inputs[from % n].push(to % n);
+            inputs[to % n].push(from % n);
+            prop_assert!(graph_from(&inputs).topological_sort().is_err());
+        }
+
+        #[test]
+        fn prop_matches_reference(edges in edges()) {
+            let graph = graph_from(&edges);
+            match graph.topological_sort() {
+                Ok(order) => {
+                    prop_assert!(!has_cycle(&edges));
+                    assert_valid_order(&edges, &order);
+                }
+                Err(err) => {
+                    prop_assert!(has_cycle(&edges));
+                    let node: NodeId = err.to_string().rsplit(' ').next().unwrap().parse().unwrap();
+                    // The reported node must lie on a cycle: it reaches itself
+                    prop_assert!(edges[node].iter().any(|&input| input != node
+                        && input < edges.len()
+                        && reaches(&edges, input, node)));
+                }
+            }
+        }
+    }
+
+    /// Whether `to` is reachable from `from` along inputs
+    fn reaches(inputs: &[Vec<NodeId>], from: NodeId, to: NodeId) -> bool {
+        let mut seen = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+            if id < inputs.len() && seen.insert(id) {
+                stack.extend(inputs[id].iter().copied().filter(|&input| input != id));
+            }
+        }
+        false
+    }
 }
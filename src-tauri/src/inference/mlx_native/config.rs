@@ -1,7 +1,18 @@
+use super::weights::ComputeDtype;
+use crate::config::ConfigWarning;
+use crate::inference::hardware_probe::{gpu_info, total_ram_bytes};
+use crate::inference::system_memory::available_memory_bytes;
 use serde::{Deserialize, Serialize};
 
+const MB: u64 = 1024 * 1024;
+
 /// GPT-OSS 20B Model Configuration
+///
+/// Deserializes from a HuggingFace `config.json`; absent fields keep the
+/// defaults, except `head_dim` and `num_local_experts`, whose absence means
+/// `hidden_size / num_attention_heads` and a dense MLP.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GPTOSSConfig {
     pub vocab_size: usize,
     pub hidden_size: usize,
@@ -14,6 +25,15 @@ pub struct GPTOSSConfig {
     pub initializer_range: f32,
     pub rms_norm_eps: f32,
     pub use_cache: bool,
+    /// Width of one attention head, when not `hidden_size / num_attention_heads`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_dim: Option<usize>,
+    /// MLP experts per layer in a mixture-of-experts model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_local_experts: Option<usize>,
+    /// Checkpoint dtype (`"bfloat16"`, `"float16"`, `"float32"`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub torch_dtype: Option<String>,
 }
 
 impl Default for GPTOSSConfig {
@@ -21,7 +41,7 @@ impl Default for GPTOSSConfig {
         Self {
             vocab_size: 201088,
             hidden_size: 2880,
-            intermediate_size: 7168,
+            intermediate_size: 2880,
             num_hidden_layers: 24,
             num_attention_heads: 64,
             num_key_value_heads: 8,
@@ -30,7 +50,127 @@ impl Default for GPTOSSConfig {
             initializer_range: 0.02,
             rms_norm_eps: 1e-6,
             use_cache: true,
+            head_dim: Some(64),
+            num_local_experts: Some(32),
+            torch_dtype: None,
+        }
+    }
+}
+
+/// Memory the model may run in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    /// Free RAM, `None` when it cannot be read
+    pub available_ram_mb: Option<u64>,
+    /// GPU memory, `None` without a detected GPU
+    pub vram_mb: Option<u64>,
+}
+
+impl MemoryBudget {
+    /// Free RAM and the memory of the first detected GPU
+    pub fn detect() -> Self {
+        let total_ram_gb =
+            total_ram_bytes().map_or(0.0, |bytes| bytes as f32 / (1024.0 * MB as f32));
+        let (_, vram_gb) = gpu_info(total_ram_gb);
+        Self {
+            available_ram_mb: available_memory_bytes().map(|bytes| bytes / MB),
+            vram_mb: vram_gb.map(|gb| (gb * 1024.0) as u64),
+        }
+    }
+}
+
+/// Estimated memory of a model run, in MB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    pub weights_mb: u64,
+    pub kv_cache_mb: u64,
+    pub activations_mb: u64,
+}
+
+impl MemoryEstimate {
+    pub fn total_mb(&self) -> u64 {
+        self.weights_mb + self.kv_cache_mb + self.activations_mb
+    }
+}
+
+impl GPTOSSConfig {
+    /// Dtype to keep weights in: the checkpoint's own 16-bit dtype, else f32
+    pub fn compute_dtype(&self) -> ComputeDtype {
+        match self.torch_dtype.as_deref() {
+            Some("bfloat16") => ComputeDtype::BF16,
+            Some("float16") => ComputeDtype::F16,
+            _ => ComputeDtype::F32,
+        }
+    }
+
+    pub fn head_dim(&self) -> usize {
+        self.head_dim
+            .unwrap_or(self.hidden_size / self.num_attention_heads.max(1))
+    }
+
+    /// Parameters in the embedding, LM head, every layer and the norms
+    ///
+    /// A mixture-of-experts layer holds every expert's MLP plus the router.
+    pub fn parameter_count(&self) -> u64 {
+        let hidden = self.hidden_size as u64;
+        let q_width = (self.num_attention_heads * self.head_dim()) as u64;
+        let kv_width = (self.num_key_value_heads * self.head_dim()) as u64;
+        let attention = 2 * q_width * hidden + 2 * kv_width * hidden;
+        let experts = self.num_local_experts.unwrap_or(1).max(1) as u64;
+        let router = if experts > 1 { experts * hidden } else { 0 };
+        let mlp = experts * 3 * self.intermediate_size as u64 * hidden + router;
+        let layer = attention + mlp + 2 * hidden;
+        2 * self.vocab_size as u64 * hidden + self.num_hidden_layers as u64 * layer + hidden
+    }
+
+    /// Weights, full-context KV cache and peak activations for `batch_size` sequences
+    pub fn estimate_memory(&self, dtype: ComputeDtype, batch_size: usize) -> MemoryEstimate {
+        let dtype_size = dtype.size_bytes() as u64;
+        let seq_len = self.max_position_embeddings as u64;
+        let kv_cache = self.num_hidden_layers as u64
+            * self.num_key_value_heads as u64
+            * self.head_dim() as u64
+            * seq_len
+            * 2
+            * dtype_size;
+        let activations = 2 * batch_size as u64 * seq_len * self.hidden_size as u64 * dtype_size;
+        MemoryEstimate {
+            weights_mb: (self.parameter_count() * dtype_size).div_ceil(MB),
+            kv_cache_mb: kv_cache.div_ceil(MB),
+            activations_mb: activations.div_ceil(MB),
+        }
+    }
+
+    /// Warnings for running this config in its [`compute_dtype`](Self::compute_dtype)
+    /// with a batch of one
+    ///
+    /// RAM that cannot be measured is not warned about.
+    pub fn validate_against_hardware(&self, budget: &MemoryBudget) -> Vec<ConfigWarning> {
+        let estimated_mb = self.estimate_memory(self.compute_dtype(), 1).total_mb();
+        let mut warnings = Vec::new();
+        if let Some(available_mb) = budget.available_ram_mb
+            && estimated_mb > available_mb
+        {
+            warnings.push(ConfigWarning {
+                field: "memory".to_string(),
+                message: format!(
+                    "Model needs about {} MB but only {} MB of RAM is available",
+                    estimated_mb, available_mb
+                ),
+            });
+        }
+        if let Some(vram_mb) = budget.vram_mb
+            && estimated_mb > vram_mb
+        {
+            warnings.push(ConfigWarning {
+                field: "gpu".to_string(),
+                message: format!(
+                    "Model needs about {} MB but the GPU has {} MB; consider running on the CPU",
+                    estimated_mb, vram_mb
+                ),
+            });
         }
+        warnings
     }
 }
 
@@ -45,4 +185,120 @@ mod tests {
         assert_eq!(config.num_hidden_layers, 24);
         assert_eq!(config.hidden_size, 2880);
     }
+
+    fn tiny_config() -> GPTOSSConfig {
+        GPTOSSConfig {
+            vocab_size: 1000,
+            hidden_size: 64,
+            intermediate_size: 128,
+            num_hidden_layers: 2,
+            num_attention_heads: 4,
+            num_key_value_heads: 2,
+            max_position_embeddings: 1024,
+            head_dim: None,
+            num_local_experts: None,
+            ..GPTOSSConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_gpt_oss_dimensions() {
+        let config = GPTOSSConfig::default();
+        assert_eq!(config.head_dim(), 64);
+        // 32 experts per layer put GPT-OSS 20B at about 21B parameters
+        let params = config.parameter_count();
+        assert!(
+            (20_000_000_000..22_000_000_000).contains(&params),
+            "{}",
+            params
+        );
+    }
+
+    #[test]
+    fn test_dense_config_json() {
+        let config: GPTOSSConfig = serde_json::from_str(
+            r#"{"hidden_size": 64, "num_attention_heads": 4, "torch_dtype": "float16"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.head_dim(), 16);
+        assert_eq!(config.num_local_experts, None);
+        assert_eq!(config.compute_dtype(), ComputeDtype::F16);
+    }
+
+    #[test]
+    fn test_moe_layers_count_every_expert() {
+        let dense = tiny_config();
+        let moe = GPTOSSConfig {
+            num_local_experts: Some(4),
+            ..tiny_config()
+        };
+        // 3 more copies of the MLP and a 4 x 64 router in each of 2 layers
+        let extra = 2 * (3 * 3 * 128 * 64 + 4 * 64);
+        assert_eq!(moe.parameter_count() - dense.parameter_count(), extra);
+    }
+
+    #[test]
+    fn test_estimate_memory_components() {
+        let config = tiny_config();
+        // embedding + lm_head, 2 layers of attention (q, o, 2 x 32-wide k/v),
+        // 3 MLP matrices and 2 norms, plus the final norm
+        let layer = 2 * 64 * 64 + 2 * 32 * 64 + 3 * 128 * 64 + 2 * 64;
+        assert_eq!(config.parameter_count(), 2 * 1000 * 64 + 2 * layer + 64);
+
+        let estimate = config.estimate_memory(ComputeDtype::F16, 4);
+        // 2 layers x 2 kv heads x 16 dims x 1024 tokens x (K, V) x 2 bytes = 256 KB
+        assert_eq!(estimate.kv_cache_mb, 1);
+        // 2 x 4 x 1024 x 64 x 2 bytes = 1 MB
+        assert_eq!(estimate.activations_mb, 1);
+    }
+
+    #[test]
+    fn test_validate_against_hardware() {
+        let config = GPTOSSConfig::default();
+        let roomy = MemoryBudget {
+            available_ram_mb: Some(1 << 20),
+            vram_mb: None,
+        };
+        assert!(config.validate_against_hardware(&roomy).is_empty());
+
+        let small_gpu = MemoryBudget {
+            available_ram_mb: Some(1 << 20),
+            vram_mb: Some(8 * 1024),
+        };
+        let warnings = config.validate_against_hardware(&small_gpu);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "gpu");
+        assert!(warnings[0].message.contains("8192 MB"));
+
+        let laptop = MemoryBudget {
+            available_ram_mb: Some(8 * 1024),
+            vram_mb: None,
+        };
+        let warnings = config.validate_against_hardware(&laptop);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "memory");
+        assert!(warnings[0].message.contains("8192 MB"));
+
+        let unknown = MemoryBudget {
+            available_ram_mb: None,
+            vram_mb: None,
+        };
+        assert!(config.validate_against_hardware(&unknown).is_empty());
+    }
+
+    #[test]
+    fn test_validation_uses_checkpoint_dtype() {
+        let f32_config = GPTOSSConfig::default();
+        let bf16_config = GPTOSSConfig {
+            torch_dtype: Some("bfloat16".to_string()),
+            ..GPTOSSConfig::default()
+        };
+        // f32 weights need about 84 GB, bf16 about half
+        let budget = MemoryBudget {
+            available_ram_mb: Some(64 * 1024),
+            vram_mb: None,
+        };
+        assert_eq!(f32_config.validate_against_hardware(&budget).len(), 1);
+        assert!(bf16_config.validate_against_hardware(&budget).is_empty());
+    }
 }
@@ -0,0 +1,32 @@
+#include <metal_stdlib>
+using namespace metal;
+
+// Embedding lookup fused with positional encoding:
+//   out[p, :] = embed[token_ids[p], :] + pos_enc[start_pos + p, :]
+//
+// start_pos is the number of tokens already in the KV cache, so a decode
+// step encodes its token at its absolute position rather than at 0.
+//
+// One thread per float4 of the output, so the grid is
+// seq_len * hidden_size / 4 threads and hidden_size must be a multiple
+// of 4. Each thread reads one float4 from the embedding row and one from
+// the positional encoding row and writes their sum, touching the
+// (seq_len, hidden_size) output once instead of twice.
+kernel void embed_position_kernel(
+    const device uint *token_ids,
+    const device float4 *embed,
+    const device float4 *pos_enc,
+    device float4 *out,
+    constant uint &seq_len,
+    constant uint &hidden_size,
+    constant uint &start_pos,
+    uint gid [[thread_position_in_grid]])
+{
+    uint vecs_per_row = hidden_size / 4;
+    uint position = gid / vecs_per_row;
+    if (position >= seq_len) return;
+
+    uint col = gid % vecs_per_row;
+    uint token = token_ids[position];
+    out[gid] = embed[token * vecs_per_row + col] + pos_enc[(start_pos + position) * vecs_per_row + col];
+}
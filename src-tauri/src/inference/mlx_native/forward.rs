@@ -0,0 +1,236 @@
+/// Reference CPU Forward Pass for MLX Weights
+///
+/// A plain decoder forward over `MLXModel`: RMSNorm, grouped-query
+/// causal attention with rotary embeddings, and a SiLU-gated MLP. The whole
+/// sequence is recomputed for every token (no KV cache), so this serves small
+/// models and golden-output tests rather than production throughput.
+use super::loader::{MLXLayerWeights, MLXModel};
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::repetition_detector::RepetitionDetector;
+use ndarray::{Array1, Array2, ArrayView2, s};
+
+/// Attention head arrangement derived from the config and weight shapes
+#[derive(Debug, Clone, Copy)]
+struct HeadLayout {
+    num_heads: usize,
+    /// Query heads sharing one key/value head
+    group: usize,
+    head_dim: usize,
+}
+
+/// Finish reason when `max_new_tokens` ran out
+pub const FINISH_REASON_LENGTH: &str = "length";
+
+/// Tokens produced by `generate_with` and why decoding stopped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generation {
+    pub tokens: Vec<u32>,
+    /// `"length"`, or `"repetition"` when a loop was cut off early
+    pub finish_reason: &'static str,
+}
+
+impl MLXModel {
+    /// Logits over the vocabulary for the token following `tokens`
+    pub fn next_token_logits(&self, tokens: &[u32]) -> MinervaResult<Vec<f32>> {
+        let mut hidden = self.embed(tokens)?;
+        for layer in &self.layers {
+            hidden = self.layer_forward(layer, hidden);
+        }
+        let last = hidden.slice(s![hidden.nrows() - 1.., ..]);
+        let normed = rms_norm(last, &self.norm_final, self.config.rms_norm_eps);
+        Ok(self.lm_head.matmul_t(&normed).row(0).to_vec())
+    }
+
+    /// Greedy decoding: returns up to `max_new_tokens` tokens following `prompt`
+    pub fn generate_greedy(
+        &self,
+        prompt: &[u32],
+        max_new_tokens: usize,
+    ) -> MinervaResult<Vec<u32>> {
+        self.generate_with(prompt, max_new_tokens, |logits| Ok(argmax(logits)))
+            .map(|generation| generation.tokens)
+    }
+
+    /// Decode up to `max_new_tokens` tokens, choosing each from its logits
+    /// with `pick`
+    ///
+    /// Decoding stops early once the output falls into a repetition loop.
+    pub fn generate_with(
+        &self,
+        prompt: &[u32],
+        max_new_tokens: usize,
+        mut pick: impl FnMut(&[f32]) -> MinervaResult<u32>,
+    ) -> MinervaResult<Generation> {
+        let mut tokens = prompt.to_vec();
+        let mut detector = RepetitionDetector::default();
+        for _ in 0..max_new_tokens {
+            let logits = self.next_token_logits(&tokens)?;
+            let id = pick(&logits)?;
+            tokens.push(id);
+            if detector.push(id) {
+                break;
+            }
+        }
+        Ok(Generation {
+            tokens: tokens.split_off(prompt.len()),
+            finish_reason: detector.finish_reason().unwrap_or(FINISH_REASON_LENGTH),
+        })
+    }
+
+    fn embed(&self, tokens: &[u32]) -> MinervaResult<Array2<f32>> {
+        if tokens.is_empty() {
+            return Err(MinervaError::InferenceError(
+                "Cannot run forward pass on an empty sequence".to_string(),
+            ));
+        }
+        let vocab = self.embedding.nrows();
+        if let Some(&bad) = tokens.iter().find(|&&t| t as usize >= vocab) {
+            return Err(MinervaError::InferenceError(format!(
+                "Token {} outside vocabulary of {}",
+                bad, vocab
+            )));
+        }
+        let ids: Vec<usize> = tokens.iter().map(|&t| t as usize).collect();
+        Ok(self.embedding.select_rows(&ids))
+    }
+
+    fn layer_forward(&self, layer: &MLXLayerWeights, hidden: Array2<f32>) -> Array2<f32> {
+        let eps = self.config.rms_norm_eps;
+        let normed = rms_norm(hidden.view(), &layer.norm_attn, eps);
+        let hidden = hidden + self.attention(layer, &normed);
+        let normed = rms_norm(hidden.view(), &layer.norm_mlp, eps);
+        hidden + mlp(layer, &normed)
+    }
+
+    fn attention(&self, layer: &MLXLayerWeights, x: &Array2<f32>) -> Array2<f32> {
+        let mut q = layer.attn_q.matmul_t(x);
+        let mut k = layer.attn_k.matmul_t(x);
+        let v = layer.attn_v.matmul_t(x);
+        let layout = self.head_layout(q.ncols(), k.ncols());
+        apply_rope(&mut q, layout.head_dim, self.config.rope_theta);
+        apply_rope(&mut k, layout.head_dim, self.config.rope_theta);
+
+        let mut out = Array2::zeros(q.raw_dim());
+        let hd = layout.head_dim;
+        for head in 0..layout.num_heads {
+            let kv = head / layout.group;
+            let context = causal_attention(
+                q.slice(s![.., head * hd..(head + 1) * hd]),
+                k.slice(s![.., kv * hd..(kv + 1) * hd]),
+                v.slice(s![.., kv * hd..(kv + 1) * hd]),
+            );
+            out.slice_mut(s![.., head * hd..(head + 1) * hd])
+                .assign(&context);
+        }
+        layer.attn_out.matmul_t(&out)
+    }
+
+    fn head_layout(&self, q_width: usize, kv_width: usize) -> HeadLayout {
+        let num_heads = self.config.num_attention_heads.max(1);
+        let head_dim = q_width / num_heads;
+        let num_kv_heads = (kv_width / head_dim.max(1)).max(1);
+        HeadLayout {
+            num_heads,
+            group: (num_heads / num_kv_heads).max(1),
+            head_dim,
+        }
+    }
+}
+
+/// Row-wise RMSNorm scaled by `weight`
+fn rms_norm(x: ArrayView2<f32>, weight: &Array1<f32>, eps: f32) -> Array2<f32> {
+    let mut out = x.to_owned();
+    for mut row in out.rows_mut() {
+        let mean_sq = row.iter().map(|v| v * v).sum::<f32>() / row.len() as f32;
+        let scale = 1.0 / (mean_sq + eps).sqrt();
+        row.zip_mut_with(weight, |v, &w| *v *= scale * w);
+    }
+    out
+}
+
+/// Rotate each head's halves by position-dependent angles (rotate-half layout)
+fn apply_rope(x: &mut Array2<f32>, head_dim: usize, theta: f32) {
+    let half = head_dim / 2;
+    for (pos, mut row) in x.rows_mut().into_iter().enumerate() {
+        for base in (0..row.len()).step_by(head_dim.max(1)) {
+            for i in 0..half {
+                let freq = theta.powf(-2.0 * i as f32 / head_dim as f32);
+                let (sin, cos) = (pos as f32 * freq).sin_cos();
+                let (a, b) = (row[base + i], row[base + i + half]);
+                row[base + i] = a * cos - b * sin;
+                row[base + i + half] = a * sin + b * cos;
+            }
+        }
+    }
+}
+
+/// Scaled dot-product attention where position i sees positions 0..=i
+fn causal_attention(q: ArrayView2<f32>, k: ArrayView2<f32>, v: ArrayView2<f32>) -> Array2<f32> {
+    let scale = 1.0 / (q.ncols() as f32).sqrt();
+    let mut scores = q.dot(&k.t()) * scale;
+    for (i, mut row) in scores.rows_mut().into_iter().enumerate() {
+        row.slice_mut(s![i + 1..]).fill(f32::NEG_INFINITY);
+        let max = row.fold(f32::NEG_INFINITY, |m, &v| m.max(v));
+        row.mapv_inplace(|v| (v - max).exp());
+        let sum = row.sum();
+        row /= sum;
+    }
+    scores.dot(&v)
+}
+
+/// `down(silu(gate(x)) * up(x))`
+fn mlp(layer: &MLXLayerWeights, x: &Array2<f32>) -> Array2<f32> {
+    let gate = layer.mlp_gate.matmul_t(x);
+    let up = layer.mlp_up.matmul_t(x);
+    let activated = gate.mapv(|g| g / (1.0 + (-g).exp())) * up;
+    layer.mlp_down.matmul_t(&activated)
+}
+
+/// Index of the largest logit, lowest index on ties
+pub(crate) fn argmax(logits: &[f32]) -> u32 {
+    logits
+        .iter()
+        .enumerate()
+        .fold((0, f32::NEG_INFINITY), |best, (idx, &v)| {
+            if v > best.1 { (idx, v) } else { best }
+        })
+        .0 as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_rms_norm_unit_scale() {
+        let x = array![[3.0f32, 4.0]];
+        let normed = rms_norm(x.view(), &array![1.0, 1.0], 0.0);
+        let rms = (12.5f32).sqrt();
+        assert!((normed[[0, 0]] - 3.0 / rms).abs() < 1e-6);
+        assert!((normed[[0, 1]] - 4.0 / rms).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_causal_attention_first_position_sees_only_itself() {
+        let q = array![[1.0f32], [1.0]];
+        let k = array![[1.0f32], [5.0]];
+        let v = array![[2.0f32], [10.0]];
+        let out = causal_attention(q.view(), k.view(), v.view());
+        assert_eq!(out[[0, 0]], 2.0);
+        assert!(out[[1, 0]] > 9.0);
+    }
+
+    #[test]
+    fn test_rope_leaves_position_zero_unchanged() {
+        let mut x = array![[1.0f32, 2.0, 3.0, 4.0], [1.0, 2.0, 3.0, 4.0]];
+        apply_rope(&mut x, 4, 10000.0);
+        assert_eq!(x.row(0).to_vec(), vec![1.0, 2.0, 3.0, 4.0]);
+        assert_ne!(x.row(1).to_vec(), vec![1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_argmax_prefers_first_max() {
+        assert_eq!(argmax(&[0.5, 2.0, 2.0, -1.0]), 1);
+    }
+}
@@ -1,17 +1,44 @@
+use super::gpu_buffer_pool::FreeLists;
 use super::metal_gpu::MetalGPU;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 /// GPU buffer with metadata for pooling
-#[derive(Clone)]
+///
+/// Owns its device allocation: dropping it hands the buffer back to the
+/// [`BufferPool`](super::gpu_buffer_pool::BufferPool) it came from, or
+/// releases it when it has no pool.
 pub struct GPUBuffer {
     ptr: *mut std::ffi::c_void,
     size: usize,
     gpu: Arc<MetalGPU>,
+    pool: Option<(Weak<FreeLists>, u64)>,
 }
 
 impl GPUBuffer {
     pub fn new(ptr: *mut std::ffi::c_void, size: usize, gpu: Arc<MetalGPU>) -> Self {
-        GPUBuffer { ptr, size, gpu }
+        GPUBuffer {
+            ptr,
+            size,
+            gpu,
+            pool: None,
+        }
+    }
+
+    /// Buffer that returns to `pool` on drop, unless the pool was cleared
+    /// since `epoch`
+    pub(super) fn pooled(
+        ptr: *mut std::ffi::c_void,
+        size: usize,
+        gpu: Arc<MetalGPU>,
+        pool: Weak<FreeLists>,
+        epoch: u64,
+    ) -> Self {
+        GPUBuffer {
+            ptr,
+            size,
+            gpu,
+            pool: Some((pool, epoch)),
+        }
     }
 
     pub fn ptr(&self) -> *mut std::ffi::c_void {
@@ -25,6 +52,17 @@ impl GPUBuffer {
 
 impl Drop for GPUBuffer {
     fn drop(&mut self) {
-        self.gpu.release_buffer(self.ptr);
+        let recycled = self.pool.as_ref().is_some_and(|(pool, epoch)| {
+            pool.upgrade()
+                .is_some_and(|pool| pool.recycle(self.ptr, self.size, *epoch))
+        });
+        if !recycled {
+            self.gpu.release_buffer(self.ptr);
+        }
     }
 }
+
+// SAFETY: Metal buffers may be used from any thread, and the buffer is
+// either recycled or released exactly once, in `drop`.
+unsafe impl Send for GPUBuffer {}
+unsafe impl Sync for GPUBuffer {}
@@ -1,96 +1,202 @@
 use super::gpu_buffer::GPUBuffer;
 use super::metal_gpu::MetalGPU;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// GPU buffer pool for memory reuse
+///
+/// Buffers are recycled per exact size: `allocate` pops an idle buffer of
+/// that size or allocates a new one, and dropping the returned
+/// [`GPUBuffer`] hands it back, so a steady generation loop stops hitting
+/// the Metal allocator once every activation size has warmed up. Idle and
+/// outstanding buffers together never exceed `max_capacity` bytes; idle
+/// buffers of other sizes are released to make room.
+#[derive(Clone)]
 pub struct BufferPool {
+    lists: Arc<FreeLists>,
+}
+
+/// Per-size free lists shared by the pool and its outstanding buffers
+pub(super) struct FreeLists {
     gpu: Arc<MetalGPU>,
-    available: Arc<Mutex<HashMap<usize, Vec<*mut std::ffi::c_void>>>>,
-    total_allocated: Arc<Mutex<usize>>,
+    state: Mutex<PoolState>,
     max_capacity: usize,
+    allocations: AtomicUsize,
+}
+
+struct PoolState {
+    available: HashMap<usize, Vec<*mut std::ffi::c_void>>,
+    total_allocated: usize,
+    /// Bumped by `clear`, so buffers handed out before it are released
+    /// instead of recycled
+    epoch: u64,
+}
+
+// SAFETY: Metal buffers may be used from any thread, and every pointer on a
+// free list is owned by the pool alone until it is handed out again.
+unsafe impl Send for FreeLists {}
+unsafe impl Sync for FreeLists {}
+
+impl FreeLists {
+    /// Take back a dropped buffer; false when it belongs to a cleared epoch
+    /// and must be released by the caller
+    pub(super) fn recycle(&self, ptr: *mut std::ffi::c_void, size: usize, epoch: u64) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.epoch != epoch {
+            return false;
+        }
+        state.available.entry(size).or_default().push(ptr);
+        true
+    }
+}
+
+impl Drop for FreeLists {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(|e| e.into_inner());
+        for ptr in state.available.drain().flat_map(|(_, buffers)| buffers) {
+            self.gpu.release_buffer(ptr);
+        }
+    }
 }
 
 impl BufferPool {
     /// Create new buffer pool
     pub fn new(gpu: Arc<MetalGPU>, max_capacity: usize) -> Self {
         BufferPool {
-            gpu,
-            available: Arc::new(Mutex::new(HashMap::new())),
-            total_allocated: Arc::new(Mutex::new(0)),
-            max_capacity,
+            lists: Arc::new(FreeLists {
+                gpu,
+                state: Mutex::new(PoolState {
+                    available: HashMap::new(),
+                    total_allocated: 0,
+                    epoch: 0,
+                }),
+                max_capacity,
+                allocations: AtomicUsize::new(0),
+            }),
         }
     }
 
+    /// Pool with `per_size` buffers pre-allocated for each activation
+    ///
+    /// Activations that happen to share a size (`seq_len == 1`, or
+    /// `intermediate_size == hidden_size`) each get their own buffers.
+    pub fn with_activation_slabs(
+        gpu: Arc<MetalGPU>,
+        max_capacity: usize,
+        shape: ActivationShape,
+        per_size: usize,
+    ) -> Result<Self, String> {
+        let pool = Self::new(gpu, max_capacity);
+        let buffers = shape
+            .slab_sizes()
+            .into_iter()
+            .flat_map(|size| std::iter::repeat_n(size, per_size))
+            .map(|size| pool.allocate(size))
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(buffers);
+        Ok(pool)
+    }
+
+    /// Device the pool allocates on
+    pub fn gpu(&self) -> &Arc<MetalGPU> {
+        &self.lists.gpu
+    }
+
     /// Allocate or reuse a buffer of specified size
     pub fn allocate(&self, size: usize) -> Result<GPUBuffer, String> {
-        let mut available = self.available.lock().unwrap();
-        let mut total = self.total_allocated.lock().unwrap();
+        let mut state = self.lists.state.lock().unwrap();
 
-        if let Some(buffers) = available.get_mut(&size) {
-            if let Some(ptr) = buffers.pop() {
-                return Ok(GPUBuffer::new(ptr, size, Arc::clone(&self.gpu)));
-            }
+        if let Some(ptr) = state.available.get_mut(&size).and_then(Vec::pop) {
+            return Ok(self.handle(ptr, size, state.epoch));
         }
 
-        if *total + size > self.max_capacity {
-            self.evict_lru(&mut available, size)?;
+        while state.total_allocated + size > self.lists.max_capacity {
+            self.evict_idle(&mut state)?;
         }
 
-        let ptr = self.gpu.create_buffer(size)?;
-        *total += size;
+        let ptr = self.lists.gpu.create_buffer(size)?;
+        state.total_allocated += size;
+        self.lists.allocations.fetch_add(1, Ordering::Relaxed);
+
+        Ok(self.handle(ptr, size, state.epoch))
+    }
 
-        Ok(GPUBuffer::new(ptr, size, Arc::clone(&self.gpu)))
+    fn handle(&self, ptr: *mut std::ffi::c_void, size: usize, epoch: u64) -> GPUBuffer {
+        GPUBuffer::pooled(
+            ptr,
+            size,
+            Arc::clone(&self.lists.gpu),
+            Arc::downgrade(&self.lists),
+            epoch,
+        )
     }
 
     /// Return buffer to pool for reuse
     pub fn release(&self, buffer: GPUBuffer) {
-        let mut available = self.available.lock().unwrap();
-        available
-            .entry(buffer.size())
-            .or_insert_with(Vec::new)
-            .push(buffer.ptr());
+        drop(buffer);
     }
 
-    /// Evict least recently used buffer
-    fn evict_lru(
-        &self,
-        available: &mut HashMap<usize, Vec<*mut std::ffi::c_void>>,
-        _needed: usize,
-    ) -> Result<(), String> {
-        if let Some(size) = available.keys().next().copied() {
-            if let Some(mut buffers) = available.remove(&size) {
-                while let Some(ptr) = buffers.pop() {
-                    self.gpu.release_buffer(ptr);
-                }
-            }
-            Ok(())
-        } else {
-            Err("No buffers to evict".to_string())
+    /// Release one idle buffer back to the device
+    fn evict_idle(&self, state: &mut PoolState) -> Result<(), String> {
+        let Some((&size, buffers)) = state.available.iter_mut().next() else {
+            return Err(format!(
+                "Buffer pool full: {} of {} bytes in use",
+                state.total_allocated, self.lists.max_capacity
+            ));
+        };
+        let (evicted, emptied) = (buffers.pop(), buffers.is_empty());
+        if emptied {
+            state.available.remove(&size);
         }
+        if let Some(ptr) = evicted {
+            self.lists.gpu.release_buffer(ptr);
+            state.total_allocated -= size;
+        }
+        Ok(())
     }
 
     /// Get current memory usage statistics
     pub fn statistics(&self) -> PoolStatistics {
-        let available = self.available.lock().unwrap();
-        let total = self.total_allocated.lock().unwrap();
+        let state = self.lists.state.lock().unwrap();
 
-        let available_count = available.values().map(|v| v.len()).sum();
-        let available_bytes = available.keys().map(|&size| size).sum();
+        let available_count = state.available.values().map(|v| v.len()).sum();
+        let available_bytes = state
+            .available
+            .iter()
+            .map(|(&size, buffers)| size * buffers.len())
+            .sum();
 
         PoolStatistics {
-            total_allocated: *total,
+            total_allocated: state.total_allocated,
             available_bytes,
             available_buffers: available_count,
-            max_capacity: self.max_capacity,
+            max_capacity: self.lists.max_capacity,
         }
     }
 
+    /// Idle buffers of `size` bytes
+    pub fn free_count(&self, size: usize) -> usize {
+        let state = self.lists.state.lock().unwrap();
+        state.available.get(&size).map_or(0, Vec::len)
+    }
+
+    /// Buffers allocated from the device since the pool was created
+    pub fn allocations(&self) -> usize {
+        self.lists.allocations.load(Ordering::Relaxed)
+    }
+
     /// Clear all buffers in pool
+    ///
+    /// Idle buffers are released now; buffers still in use are released
+    /// when dropped instead of returning to the pool.
     pub fn clear(&self) {
-        let mut available = self.available.lock().unwrap();
-        available.clear();
-        *self.total_allocated.lock().unwrap() = 0;
+        let mut state = self.lists.state.lock().unwrap();
+        for ptr in state.available.drain().flat_map(|(_, buffers)| buffers) {
+            self.lists.gpu.release_buffer(ptr);
+        }
+        state.total_allocated = 0;
+        state.epoch += 1;
     }
 }
 
@@ -102,3 +208,23 @@ pub struct PoolStatistics {
     pub available_buffers: usize,
     pub max_capacity: usize,
 }
+
+/// Activation tensor dimensions used to size the slabs
+#[derive(Debug, Clone, Copy)]
+pub struct ActivationShape {
+    pub hidden_size: usize,
+    pub intermediate_size: usize,
+    pub seq_len: usize,
+}
+
+impl ActivationShape {
+    /// Byte sizes of the hidden, FFN and full-sequence f32 activations
+    pub fn slab_sizes(&self) -> [usize; 3] {
+        let f32_bytes = std::mem::size_of::<f32>();
+        [
+            self.hidden_size * f32_bytes,
+            self.intermediate_size * f32_bytes,
+            self.seq_len * self.hidden_size * f32_bytes,
+        ]
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::inference::mlx_native::gpu_buffer_pool::BufferPool;
+    use crate::inference::mlx_native::gpu_buffer_pool::{ActivationShape, BufferPool};
     use crate::inference::mlx_native::metal_gpu::MetalGPU;
     use std::sync::Arc;
 
@@ -62,4 +62,100 @@ mod tests {
         let stats = pool.statistics();
         assert_eq!(stats.total_allocated, 0);
     }
+
+    #[test]
+    fn test_pool_preallocates_activation_sizes() {
+        if !MetalGPU::is_available() {
+            return;
+        }
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let shape = ActivationShape {
+            hidden_size: 64,
+            intermediate_size: 256,
+            seq_len: 8,
+        };
+        let pool = BufferPool::with_activation_slabs(gpu, 1024 * 1024, shape, 2).unwrap();
+
+        for size in shape.slab_sizes() {
+            assert_eq!(pool.free_count(size), 2);
+        }
+        assert_eq!(pool.allocations(), 6);
+    }
+
+    #[test]
+    fn test_pool_preallocates_coinciding_sizes_separately() {
+        if !MetalGPU::is_available() {
+            return;
+        }
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let shape = ActivationShape {
+            hidden_size: 64,
+            intermediate_size: 64,
+            seq_len: 1,
+        };
+        let pool = BufferPool::with_activation_slabs(gpu, 1024 * 1024, shape, 2).unwrap();
+
+        assert_eq!(pool.free_count(64 * 4), 6);
+        assert_eq!(pool.allocations(), 6);
+    }
+
+    #[test]
+    fn test_pool_recycles_dropped_buffers() {
+        if !MetalGPU::is_available() {
+            return;
+        }
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let pool = BufferPool::new(gpu, 1024 * 1024);
+
+        let first = pool.allocate(1024).unwrap();
+        let ptr = first.ptr();
+        drop(first);
+        assert_eq!(pool.free_count(1024), 1);
+
+        for _ in 0..10 {
+            let buffer = pool.allocate(1024).unwrap();
+            assert_eq!(buffer.ptr(), ptr);
+        }
+        assert_eq!(pool.allocations(), 1);
+
+        let other = pool.allocate(2048).unwrap();
+        assert_eq!(other.size(), 2048);
+        assert_eq!(pool.allocations(), 2);
+    }
+
+    #[test]
+    fn test_pool_stays_within_capacity() {
+        if !MetalGPU::is_available() {
+            return;
+        }
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let pool = BufferPool::new(gpu, 4096);
+
+        drop(pool.allocate(1024).unwrap());
+        drop(pool.allocate(2048).unwrap());
+        // Idle buffers of other sizes make room for a new one
+        let large = pool.allocate(3072).unwrap();
+        assert!(pool.statistics().total_allocated <= 4096);
+
+        // Nothing idle is left to evict
+        assert!(pool.allocate(2048).is_err());
+        drop(large);
+        assert_eq!(pool.statistics().available_bytes, 3072);
+    }
+
+    #[test]
+    fn test_cleared_pool_does_not_recycle_outstanding_buffers() {
+        if !MetalGPU::is_available() {
+            return;
+        }
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let pool = BufferPool::new(gpu, 1024 * 1024);
+
+        let buffer = pool.allocate(1024).unwrap();
+        pool.clear();
+        drop(buffer);
+
+        assert_eq!(pool.free_count(1024), 0);
+        assert_eq!(pool.statistics().total_allocated, 0);
+    }
 }
@@ -1,128 +1,347 @@
-use super::metal_gpu::MetalGPU;
-use super::unified_memory::MLXArray;
+use super::compute_graph::Operation;
+use super::gpu_buffer::GPUBuffer;
+use super::gpu_buffer_pool::BufferPool;
+use super::metal_gpu::{MetalBuffer, MetalGPU};
+use super::metal_kernels_wrapper::{EmbedPositionKernel, MetalKernels, RmsNormKernel};
+use super::unified_memory::{ArrayShape, MLXArray};
 use std::sync::Arc;
 
+/// A kernel input: the array's resident buffer, or a copy uploaded for
+/// this dispatch and returned to the pool afterwards
+enum BoundInput<'a> {
+    Resident(&'a MetalBuffer),
+    Uploaded(GPUBuffer),
+}
+
+impl BoundInput<'_> {
+    fn raw(&self) -> *mut std::ffi::c_void {
+        match self {
+            Self::Resident(buffer) => buffer.raw(),
+            Self::Uploaded(buffer) => buffer.ptr(),
+        }
+    }
+}
+
+/// Bind `array` for a kernel on the pool's device, copying it only when it
+/// has no buffer there already (see `MLXArray::ensure_on_gpu`)
+fn bind_input<'a>(pool: &BufferPool, array: &'a MLXArray) -> Result<BoundInput<'a>, String> {
+    if let Some(buffer) = array.gpu_buffer()
+        && Arc::ptr_eq(buffer.gpu(), pool.gpu())
+    {
+        return Ok(BoundInput::Resident(buffer));
+    }
+    let data = array.data();
+    let buffer = pool.allocate(data.len() * 4)?;
+    pool.gpu().copy_to_gpu(buffer.ptr(), &data)?;
+    Ok(BoundInput::Uploaded(buffer))
+}
+
+/// A kernel encoded into a command buffer, with the buffers it reads and
+/// writes kept alive until that buffer completes
+pub struct PendingOutput<'a> {
+    _inputs: Vec<BoundInput<'a>>,
+    output: GPUBuffer,
+    shape: (usize, usize),
+}
+
+impl PendingOutput<'_> {
+    /// Copy the kernel's output back; its command buffer must have completed
+    pub fn read(self, gpu: &MetalGPU) -> Result<MLXArray, String> {
+        let (m, n) = self.shape;
+        let mut result_data = vec![0.0; m * n];
+        gpu.copy_from_gpu(self.output.ptr(), &mut result_data)?;
+        Ok(MLXArray::new_cpu(result_data, ArrayShape::Shape2D(m, n)))
+    }
+}
+
+/// Encode `op` into `cmd_buffer` without committing it, for the ops
+/// `GPUGraphExecutor` routes to the GPU
+pub fn encode_gpu_op<'a>(
+    pool: &BufferPool,
+    cmd_buffer: *mut std::ffi::c_void,
+    op: &Operation,
+    inputs: &[&'a MLXArray],
+) -> Result<PendingOutput<'a>, String> {
+    match op {
+        Operation::MatMul { shape } => encode_matmul(pool, cmd_buffer, inputs, *shape),
+        Operation::FusedLinearAdd { shape } => {
+            if inputs.len() < 3 {
+                return Err("FusedLinearAdd requires 3 inputs".to_string());
+            }
+            encode_fused_matmul_add_gelu(pool, cmd_buffer, inputs, *shape)
+        }
+        Operation::FusedLinearAddGelu { shape } => {
+            if inputs.len() < 3 {
+                return Err("FusedLinearAddGelu requires 3 inputs".to_string());
+            }
+            encode_fused_matmul_add_gelu(pool, cmd_buffer, inputs, *shape)
+        }
+        _ => Err(format!("No GPU kernel for {:?}", op)),
+    }
+}
+
 /// GPU MatMul execution helper
 pub fn gpu_matmul(
-    gpu: &Arc<MetalGPU>,
+    pool: &BufferPool,
+    inputs: &[&MLXArray],
+    shape: (usize, usize),
+) -> Result<MLXArray, String> {
+    run_now(pool, |cmd_buffer| {
+        encode_matmul(pool, cmd_buffer, inputs, shape)
+    })
+}
+
+/// GPU fused MatMul+Add execution helper
+pub fn gpu_fused_matmul_add(
+    pool: &BufferPool,
+    inputs: &[&MLXArray],
+    shape: (usize, usize),
+) -> Result<MLXArray, String> {
+    run_now(pool, |cmd_buffer| {
+        encode_gpu_op(
+            pool,
+            cmd_buffer,
+            &Operation::FusedLinearAdd { shape },
+            inputs,
+        )
+    })
+}
+
+/// GPU fused MatMul+Add+Gelu execution helper
+pub fn gpu_fused_matmul_add_gelu(
+    pool: &BufferPool,
     inputs: &[&MLXArray],
     shape: (usize, usize),
 ) -> Result<MLXArray, String> {
+    run_now(pool, |cmd_buffer| {
+        encode_gpu_op(
+            pool,
+            cmd_buffer,
+            &Operation::FusedLinearAddGelu { shape },
+            inputs,
+        )
+    })
+}
+
+/// Encode one kernel into its own command buffer, run it and read it back
+fn run_now<'a>(
+    pool: &BufferPool,
+    encode: impl FnOnce(*mut std::ffi::c_void) -> Result<PendingOutput<'a>, String>,
+) -> Result<MLXArray, String> {
+    let gpu = pool.gpu();
+    let cmd_buffer = gpu.create_command_buffer()?;
+    let pending = encode(cmd_buffer)?;
+    gpu.submit_commands(cmd_buffer)?;
+    gpu.wait_completion(cmd_buffer)?;
+    pending.read(gpu)
+}
+
+fn encode_matmul<'a>(
+    pool: &BufferPool,
+    cmd_buffer: *mut std::ffi::c_void,
+    inputs: &[&'a MLXArray],
+    shape: (usize, usize),
+) -> Result<PendingOutput<'a>, String> {
     if inputs.len() < 2 {
         return Err("MatMul requires 2 inputs".to_string());
     }
 
-    let a_data = inputs[0].data();
-    let b_data = inputs[1].data();
     let (m, n) = shape;
-    let k = a_data.len() / m;
+    let k = inputs[0].size() / m;
 
-    let gpu_a = gpu.create_buffer(a_data.len() * 4)?;
-    let gpu_b = gpu.create_buffer(b_data.len() * 4)?;
-    let gpu_c = gpu.create_buffer(m * n * 4)?;
+    let gpu_a = bind_input(pool, inputs[0])?;
+    let gpu_b = bind_input(pool, inputs[1])?;
+    let gpu_c = pool.allocate(m * n * 4)?;
 
-    gpu.copy_to_gpu(gpu_a, &a_data)?;
-    gpu.copy_to_gpu(gpu_b, &b_data)?;
-
-    super::metal_kernels_wrapper::MetalKernels::matmul(
-        gpu, gpu_a, gpu_b, gpu_c, m as u32, n as u32, k as u32,
+    MetalKernels::encode_matmul(
+        pool.gpu(),
+        cmd_buffer,
+        gpu_a.raw(),
+        gpu_b.raw(),
+        gpu_c.ptr(),
+        m as u32,
+        n as u32,
+        k as u32,
     )?;
 
-    let mut result_data = vec![0.0; m * n];
-    gpu.copy_from_gpu(gpu_c, &mut result_data)?;
+    Ok(PendingOutput {
+        _inputs: vec![gpu_a, gpu_b],
+        output: gpu_c,
+        shape,
+    })
+}
+
+fn encode_fused_matmul_add_gelu<'a>(
+    pool: &BufferPool,
+    cmd_buffer: *mut std::ffi::c_void,
+    inputs: &[&'a MLXArray],
+    shape: (usize, usize),
+) -> Result<PendingOutput<'a>, String> {
+    let (m, n) = shape;
+    let k = inputs[0].size() / m;
+
+    let gpu_a = bind_input(pool, inputs[0])?;
+    let gpu_b = bind_input(pool, inputs[1])?;
+    let gpu_add = bind_input(pool, inputs[2])?;
+    let gpu_c = pool.allocate(m * n * 4)?;
 
-    gpu.release_buffer(gpu_a);
-    gpu.release_buffer(gpu_b);
-    gpu.release_buffer(gpu_c);
+    MetalKernels::encode_fused_matmul_add_gelu(
+        pool.gpu(),
+        cmd_buffer,
+        gpu_a.raw(),
+        gpu_b.raw(),
+        gpu_add.raw(),
+        gpu_c.ptr(),
+        m as u32,
+        n as u32,
+        k as u32,
+    )?;
 
-    Ok(MLXArray::new_cpu(
-        result_data,
-        super::unified_memory::ArrayShape::Shape2D(m, n),
-    ))
+    Ok(PendingOutput {
+        _inputs: vec![gpu_a, gpu_b, gpu_add],
+        output: gpu_c,
+        shape,
+    })
 }
 
-/// GPU fused MatMul+Add execution helper
-pub fn gpu_fused_matmul_add(
+/// GPU RMSNorm over the rows of a `(seq_len, hidden_size)` input
+pub fn gpu_rms_norm(
     gpu: &Arc<MetalGPU>,
-    inputs: &[&MLXArray],
-    shape: (usize, usize),
+    kernel: &RmsNormKernel,
+    input: &MLXArray,
+    weight: &[f32],
+    eps: f32,
 ) -> Result<MLXArray, String> {
-    if inputs.len() < 3 {
-        return Err("FusedLinearAdd requires 3 inputs".to_string());
+    let ArrayShape::Shape2D(seq_len, hidden_size) = input.shape() else {
+        return Err("RMSNorm requires a 2D input".to_string());
+    };
+    if weight.len() != hidden_size {
+        return Err(format!(
+            "RMSNorm weight size {} != hidden size {}",
+            weight.len(),
+            hidden_size
+        ));
     }
 
-    let a_data = inputs[0].data();
-    let b_data = inputs[1].data();
-    let add_data = inputs[2].data();
-    let (m, n) = shape;
-    let k = a_data.len() / m;
+    MetalGPU::require_real_bindings("gpu_rms_norm")?;
 
-    let gpu_a = gpu.create_buffer(a_data.len() * 4)?;
-    let gpu_b = gpu.create_buffer(b_data.len() * 4)?;
-    let gpu_add = gpu.create_buffer(add_data.len() * 4)?;
-    let gpu_c = gpu.create_buffer(m * n * 4)?;
+    let x_data = input.data();
+    let gpu_x = gpu.create_buffer(x_data.len() * 4)?;
+    let gpu_w = gpu.create_buffer(weight.len() * 4)?;
+    let gpu_out = gpu.create_buffer(x_data.len() * 4)?;
 
-    gpu.copy_to_gpu(gpu_a, &a_data)?;
-    gpu.copy_to_gpu(gpu_b, &b_data)?;
-    gpu.copy_to_gpu(gpu_add, &add_data)?;
+    gpu.copy_to_gpu(gpu_x, &x_data)?;
+    gpu.copy_to_gpu(gpu_w, weight)?;
 
-    super::metal_kernels_wrapper::MetalKernels::fused_matmul_add_gelu(
-        gpu, gpu_a, gpu_b, gpu_add, gpu_c, m as u32, n as u32, k as u32,
-    )?;
+    let dispatched = kernel.dispatch(
+        gpu,
+        gpu_x,
+        gpu_w,
+        gpu_out,
+        (seq_len as u32, hidden_size as u32),
+        eps,
+    );
 
-    let mut result_data = vec![0.0; m * n];
-    gpu.copy_from_gpu(gpu_c, &mut result_data)?;
+    let mut result_data = vec![0.0; x_data.len()];
+    let copied = dispatched.and_then(|()| gpu.copy_from_gpu(gpu_out, &mut result_data));
 
-    gpu.release_buffer(gpu_a);
-    gpu.release_buffer(gpu_b);
-    gpu.release_buffer(gpu_add);
-    gpu.release_buffer(gpu_c);
+    gpu.release_buffer(gpu_x);
+    gpu.release_buffer(gpu_w);
+    gpu.release_buffer(gpu_out);
 
-    Ok(MLXArray::new_cpu(
-        result_data,
-        super::unified_memory::ArrayShape::Shape2D(m, n),
-    ))
+    copied.map(|()| MLXArray::new_cpu(result_data, ArrayShape::Shape2D(seq_len, hidden_size)))
 }
 
-/// GPU fused MatMul+Add+Gelu execution helper
-pub fn gpu_fused_matmul_add_gelu(
+/// GPU embedding lookup with the positional encoding added in the same pass
+///
+/// `embed_table` is a resident `(vocab_size, hidden_size)` buffer paired with
+/// its vocabulary size, and `pos_enc` a `(max_positions, hidden_size)` buffer
+/// paired with its row count. Row `p` of `output` receives
+/// `embed[token_ids[p]] + pos_enc[start_pos + p]`, where `start_pos` is the
+/// number of tokens already cached. Only the token ids are uploaded.
+#[allow(clippy::too_many_arguments)]
+pub fn fused_embed_and_position(
     gpu: &Arc<MetalGPU>,
-    inputs: &[&MLXArray],
+    kernel: &EmbedPositionKernel,
+    token_ids: &[u32],
+    embed_table: (*mut std::ffi::c_void, usize),
+    pos_enc: (*mut std::ffi::c_void, usize),
+    output: *mut std::ffi::c_void,
     shape: (usize, usize),
-) -> Result<MLXArray, String> {
-    if inputs.len() < 3 {
-        return Err("FusedLinearAddGelu requires 3 inputs".to_string());
+    start_pos: usize,
+) -> Result<(), String> {
+    let (embed, vocab_size) = embed_table;
+    let (pos_enc, max_positions) = pos_enc;
+    let (seq_len, hidden_size) = shape;
+    if token_ids.len() != seq_len {
+        return Err(format!(
+            "{} token ids for sequence length {}",
+            token_ids.len(),
+            seq_len
+        ));
     }
+    if let Some(&token) = token_ids.iter().find(|&&t| t as usize >= vocab_size) {
+        return Err(format!(
+            "Token id {} out of range for vocabulary of {}",
+            token, vocab_size
+        ));
+    }
+    if start_pos
+        .checked_add(seq_len)
+        .is_none_or(|end| end > max_positions)
+    {
+        return Err(format!(
+            "Positions {}..{} exceed the {} positional encodings",
+            start_pos,
+            start_pos.saturating_add(seq_len),
+            max_positions
+        ));
+    }
+    let start_pos =
+        u32::try_from(start_pos).map_err(|_| format!("Start position {} too large", start_pos))?;
 
-    let a_data = inputs[0].data();
-    let b_data = inputs[1].data();
-    let add_data = inputs[2].data();
-    let (m, n) = shape;
-    let k = a_data.len() / m;
-
-    let gpu_a = gpu.create_buffer(a_data.len() * 4)?;
-    let gpu_b = gpu.create_buffer(b_data.len() * 4)?;
-    let gpu_add = gpu.create_buffer(add_data.len() * 4)?;
-    let gpu_c = gpu.create_buffer(m * n * 4)?;
-
-    gpu.copy_to_gpu(gpu_a, &a_data)?;
-    gpu.copy_to_gpu(gpu_b, &b_data)?;
-    gpu.copy_to_gpu(gpu_add, &add_data)?;
+    let gpu_ids = gpu.create_buffer(token_ids.len() * 4)?;
+    let dispatched = gpu.copy_indices_to_gpu(gpu_ids, token_ids).and_then(|()| {
+        kernel.dispatch(
+            gpu,
+            gpu_ids,
+            embed,
+            pos_enc,
+            output,
+            (seq_len as u32, hidden_size as u32),
+            start_pos,
+        )
+    });
+    gpu.release_buffer(gpu_ids);
+    dispatched
+}
 
-    super::metal_kernels_wrapper::MetalKernels::fused_matmul_add_gelu(
-        gpu, gpu_a, gpu_b, gpu_add, gpu_c, m as u32, n as u32, k as u32,
-    )?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut result_data = vec![0.0; m * n];
-    gpu.copy_from_gpu(gpu_c, &mut result_data)?;
+    #[test]
+    fn test_bind_input_reuses_resident_buffer() {
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let pool = BufferPool::new(Arc::clone(&gpu), 1024 * 1024);
+        let mut array = MLXArray::new_cpu(vec![1.0; 4], ArrayShape::Shape2D(2, 2));
+        assert!(matches!(
+            bind_input(&pool, &array).unwrap(),
+            BoundInput::Uploaded(_)
+        ));
 
-    gpu.release_buffer(gpu_a);
-    gpu.release_buffer(gpu_b);
-    gpu.release_buffer(gpu_add);
-    gpu.release_buffer(gpu_c);
+        array.ensure_on_gpu(&gpu).unwrap();
+        let resident = array.gpu_buffer().unwrap().raw();
+        match bind_input(&pool, &array).unwrap() {
+            BoundInput::Resident(buffer) => assert_eq!(buffer.raw(), resident),
+            BoundInput::Uploaded(_) => panic!("resident array was uploaded again"),
+        }
 
-    Ok(MLXArray::new_cpu(
-        result_data,
-        super::unified_memory::ArrayShape::Shape2D(m, n),
-    ))
+        // A buffer on another device cannot be bound
+        let other = BufferPool::new(Arc::new(MetalGPU::new().unwrap()), 1024 * 1024);
+        assert!(matches!(
+            bind_input(&other, &array).unwrap(),
+            BoundInput::Uploaded(_)
+        ));
+    }
 }
@@ -1,13 +1,25 @@
 use super::compute_graph::{ComputeGraph, NodeId, Operation};
+use super::gpu_buffer_pool::BufferPool;
 use super::gpu_execution_helpers::*;
+use super::graph_executor::{GraphExecutor, Outputs};
+use super::graph_scheduler::DependencyScheduler;
 use super::metal_gpu::MetalGPU;
-use super::unified_memory::MLXArray;
-use std::collections::HashMap;
-use std::sync::Arc;
+use super::unified_memory::{ArrayShape, MLXArray};
+use super::weights::Weight;
+use crate::error::{MinervaError, MinervaResult};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Bytes of idle and in-flight activation buffers the executor keeps
+const ACTIVATION_POOL_BYTES: usize = 256 * 1024 * 1024;
 
 /// GPU-accelerated graph executor
 pub struct GPUGraphExecutor {
     gpu: Arc<MetalGPU>,
+    /// Uploaded inputs and kernel outputs, reused across layers and steps
+    pool: BufferPool,
 }
 
 impl GPUGraphExecutor {
@@ -18,7 +30,13 @@ impl GPUGraphExecutor {
         }
 
         let gpu = Arc::new(MetalGPU::new()?);
-        Ok(GPUGraphExecutor { gpu })
+        let pool = BufferPool::new(Arc::clone(&gpu), ACTIVATION_POOL_BYTES);
+        Ok(GPUGraphExecutor { gpu, pool })
+    }
+
+    /// Device the executor dispatches to
+    pub fn gpu(&self) -> &Arc<MetalGPU> {
+        &self.gpu
     }
 
     /// Execute graph, routing operations to GPU or CPU
@@ -28,7 +46,10 @@ impl GPUGraphExecutor {
         inputs: &HashMap<NodeId, MLXArray>,
     ) -> Result<HashMap<NodeId, MLXArray>, String> {
         let mut results = inputs.clone();
-        let order = graph.topological_sort();
+        let available: HashSet<NodeId> = inputs.keys().copied().collect();
+        let order = graph
+            .execution_order(&available)
+            .map_err(|e| e.to_string())?;
 
         for node_id in order {
             if results.contains_key(&node_id) {
@@ -59,6 +80,96 @@ impl GPUGraphExecutor {
         Ok(results)
     }
 
+    /// Execute graph wave by wave as dependencies resolve.
+    ///
+    /// All ops that are ready at the same time share one command buffer, so the
+    /// GPU is synchronised once per wave instead of once per op.
+    pub fn execute_scheduled(
+        &self,
+        graph: &ComputeGraph,
+        inputs: &HashMap<NodeId, MLXArray>,
+    ) -> Result<Outputs, String> {
+        let available: HashSet<NodeId> = inputs.keys().copied().collect();
+        let mut scheduler =
+            DependencyScheduler::new(graph, &available).map_err(|e| e.to_string())?;
+        let mut outputs = Outputs {
+            values: inputs.clone(),
+            op_timings: HashMap::new(),
+        };
+
+        loop {
+            let ready = scheduler.take_ready();
+            if ready.is_empty() {
+                break;
+            }
+            self.execute_wave(graph, &ready, &mut outputs)?;
+            ready
+                .iter()
+                .for_each(|node_id| scheduler.complete(*node_id));
+        }
+
+        match scheduler.unfinished().as_slice() {
+            [] => Ok(outputs),
+            stalled => Err(format!("Missing inputs for nodes {:?}", stalled)),
+        }
+    }
+
+    /// Run one wave, encoding its GPU ops into a single command buffer
+    ///
+    /// The GPU ops run together, so each is timed as its own encode and
+    /// readback plus the wave's wait on the GPU.
+    fn execute_wave(
+        &self,
+        graph: &ComputeGraph,
+        ready: &[NodeId],
+        outputs: &mut Outputs,
+    ) -> Result<(), String> {
+        let cmd_buffer = self.gpu.create_command_buffer()?;
+        let mut encoded = Vec::new();
+        let mut finished = Vec::with_capacity(ready.len());
+
+        for &node_id in ready {
+            let node = graph
+                .get_node(node_id)
+                .ok_or_else(|| format!("Unknown node {}", node_id))?;
+            let input_refs = node
+                .inputs
+                .iter()
+                .map(|id| {
+                    outputs
+                        .values
+                        .get(id)
+                        .ok_or_else(|| format!("Missing input {} for node {}", id, node_id))
+                })
+                .collect::<Result<Vec<&MLXArray>, String>>()?;
+
+            let start = Instant::now();
+            if self.should_use_gpu(&node.op, &input_refs) {
+                let pending = encode_gpu_op(&self.pool, cmd_buffer, &node.op, &input_refs)?;
+                encoded.push((node_id, pending, start.elapsed()));
+            } else {
+                let output = self.execute_on_cpu(&node.op, &input_refs)?;
+                finished.push((node_id, output, start.elapsed()));
+            }
+        }
+
+        let start = Instant::now();
+        self.gpu.submit_commands(cmd_buffer)?;
+        self.gpu.wait_completion(cmd_buffer)?;
+        let gpu_time = start.elapsed();
+
+        for (node_id, pending, encode_time) in encoded {
+            let start = Instant::now();
+            let output = pending.read(&self.gpu)?;
+            finished.push((node_id, output, encode_time + gpu_time + start.elapsed()));
+        }
+        for (node_id, output, elapsed) in finished {
+            outputs.values.insert(node_id, output);
+            outputs.op_timings.insert(node_id, elapsed);
+        }
+        Ok(())
+    }
+
     /// Decide whether to use GPU for this operation
     fn should_use_gpu(&self, op: &Operation, inputs: &[&MLXArray]) -> bool {
         if inputs.is_empty() {
@@ -79,10 +190,10 @@ impl GPUGraphExecutor {
     fn execute_on_gpu(&self, op: &Operation, inputs: &[&MLXArray]) -> Result<MLXArray, String> {
         match op {
             Operation::FusedLinearAddGelu { shape } => {
-                gpu_fused_matmul_add_gelu(&self.gpu, inputs, *shape)
+                gpu_fused_matmul_add_gelu(&self.pool, inputs, *shape)
             }
-            Operation::FusedLinearAdd { shape } => gpu_fused_matmul_add(&self.gpu, inputs, *shape),
-            Operation::MatMul { shape } => gpu_matmul(&self.gpu, inputs, *shape),
+            Operation::FusedLinearAdd { shape } => gpu_fused_matmul_add(&self.pool, inputs, *shape),
+            Operation::MatMul { shape } => gpu_matmul(&self.pool, inputs, *shape),
             _ => self.execute_on_cpu(op, inputs),
         }
     }
@@ -94,6 +205,180 @@ impl GPUGraphExecutor {
     }
 }
 
+/// One transformer layer for `PipelinedGraphExecutor`
+pub struct PipelineLayer {
+    pub graph: ComputeGraph,
+    /// Node id the previous layer's activation is supplied under
+    pub input: NodeId,
+    /// Node whose value becomes the next layer's activation
+    pub output: NodeId,
+    /// Stored weights, supplied to `graph` under their node ids
+    pub weights: Vec<(NodeId, Weight)>,
+}
+
+/// Where a pipelined run spent its time
+#[derive(Debug, Clone, Default)]
+pub struct PipelineStats {
+    pub layers: usize,
+    /// Prep thread time spent dequantizing and uploading weights
+    pub prep_time: Duration,
+    /// GPU thread time spent running layers
+    pub compute_time: Duration,
+    /// GPU thread time spent waiting for the next layer's weights
+    pub gpu_idle: Duration,
+}
+
+/// A layer's weights dequantized to f32 (and uploaded when a GPU is in
+/// use), holding one staging slot
+struct PreparedLayer {
+    weights: HashMap<NodeId, MLXArray>,
+    prep_time: Duration,
+    /// Returned to the prep thread when the GPU thread is done with the weights
+    _slot: OwnedSemaphorePermit,
+}
+
+/// Overlaps weight preparation for layer N+1 with compute for layer N
+///
+/// Two threads share the work. The prep thread dequantizes each layer's
+/// weights to f32 and uploads them into Metal buffers; the GPU thread
+/// encodes command buffers for one layer at a time, binding those buffers
+/// without another copy, and feeds each layer's output into the next.
+/// A `tokio::sync::Semaphore` with `staging_slots` permits bounds how far
+/// ahead the prep thread runs: it takes a permit per layer, and the GPU
+/// thread drops that permit once the layer finishes, freeing the slot for
+/// the next layer's weights. With two slots the next layer is ready as soon
+/// as the current one completes, so the GPU does not sit idle between
+/// layers. Without Metal the GPU thread runs layers on the CPU scheduler.
+pub struct PipelinedGraphExecutor {
+    staging_slots: usize,
+}
+
+impl Default for PipelinedGraphExecutor {
+    fn default() -> Self {
+        Self { staging_slots: 2 }
+    }
+}
+
+impl PipelinedGraphExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of layers whose weights may be staged at once, at least 1
+    pub fn with_staging_slots(mut self, slots: usize) -> Self {
+        self.staging_slots = slots.max(1);
+        self
+    }
+
+    /// Run `layers` in order starting from `input`, returning the last output
+    pub fn run(
+        &self,
+        layers: &[PipelineLayer],
+        input: MLXArray,
+    ) -> MinervaResult<(MLXArray, PipelineStats)> {
+        let slots = Arc::new(Semaphore::new(self.staging_slots));
+        let (sender, receiver) = mpsc::channel();
+        let executor = GPUGraphExecutor::new().ok();
+        let executor = executor.as_ref();
+
+        std::thread::scope(|scope| {
+            let prep_slots = Arc::clone(&slots);
+            let upload_to = executor.map(GPUGraphExecutor::gpu);
+            scope.spawn(move || Self::prep_thread(layers, upload_to, &prep_slots, &sender));
+            let gpu = scope.spawn(move || {
+                let result = Self::gpu_thread(layers, executor, input, &receiver);
+                // Wake the prep thread if it is waiting for a slot
+                slots.close();
+                result
+            });
+            gpu.join().map_err(|_| {
+                MinervaError::InferenceError("Pipeline GPU thread panicked".to_string())
+            })?
+        })
+    }
+
+    fn prep_thread(
+        layers: &[PipelineLayer],
+        upload_to: Option<&Arc<MetalGPU>>,
+        slots: &Arc<Semaphore>,
+        sender: &mpsc::Sender<PreparedLayer>,
+    ) {
+        for layer in layers {
+            let Ok(slot) = futures::executor::block_on(Arc::clone(slots).acquire_owned()) else {
+                return;
+            };
+            let start = Instant::now();
+            let weights = layer
+                .weights
+                .iter()
+                .map(|(id, weight)| {
+                    let mut array = dequantize(weight);
+                    if let Some(gpu) = upload_to
+                        && let Err(e) = array.ensure_on_gpu(gpu)
+                    {
+                        // The kernel helpers upload it themselves instead
+                        tracing::debug!("Staging upload of weight {} failed: {}", id, e);
+                    }
+                    (*id, array)
+                })
+                .collect();
+            let prepared = PreparedLayer {
+                weights,
+                prep_time: start.elapsed(),
+                _slot: slot,
+            };
+            if sender.send(prepared).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn gpu_thread(
+        layers: &[PipelineLayer],
+        gpu: Option<&GPUGraphExecutor>,
+        input: MLXArray,
+        receiver: &mpsc::Receiver<PreparedLayer>,
+    ) -> MinervaResult<(MLXArray, PipelineStats)> {
+        let mut stats = PipelineStats::default();
+        let mut activation = input;
+
+        for (index, layer) in layers.iter().enumerate() {
+            let wait = Instant::now();
+            let prepared = receiver.recv().map_err(|_| {
+                MinervaError::InferenceError(format!("Weights for layer {} never arrived", index))
+            })?;
+            stats.gpu_idle += wait.elapsed();
+            stats.prep_time += prepared.prep_time;
+
+            let start = Instant::now();
+            let mut inputs = prepared.weights;
+            inputs.insert(layer.input, activation);
+            let mut values = match gpu {
+                Some(gpu) => {
+                    gpu.execute_scheduled(&layer.graph, &inputs)
+                        .map_err(MinervaError::InferenceError)?
+                        .values
+                }
+                None => GraphExecutor::run(&layer.graph, inputs)?.values,
+            };
+            activation = values.remove(&layer.output).ok_or_else(|| {
+                MinervaError::InferenceError(format!("Layer {} produced no output", index))
+            })?;
+            stats.compute_time += start.elapsed();
+            stats.layers += 1;
+            // `prepared`'s slot is released here, before the next layer
+        }
+
+        Ok((activation, stats))
+    }
+}
+
+fn dequantize(weight: &Weight) -> MLXArray {
+    let values = weight.to_f32();
+    let shape = ArrayShape::Shape2D(values.nrows(), values.ncols());
+    MLXArray::new_cpu(values.iter().copied().collect(), shape)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +417,134 @@ mod tests {
 
         assert!(should_use, "GPU should be used for large arrays");
     }
+
+    #[test]
+    fn test_execute_scheduled_times_every_op() {
+        if !MetalGPU::is_available() {
+            return;
+        }
+
+        let executor = GPUGraphExecutor::new().unwrap();
+        let layer = pipeline_layers(1).remove(0);
+        let mut inputs: HashMap<NodeId, MLXArray> = layer
+            .weights
+            .iter()
+            .map(|(id, weight)| (*id, dequantize(weight)))
+            .collect();
+        inputs.insert(layer.input, pipeline_input());
+
+        let outputs = executor.execute_scheduled(&layer.graph, &inputs).unwrap();
+        assert!(outputs.values.contains_key(&layer.output));
+        assert_eq!(outputs.op_timings.len(), 2);
+    }
+
+    #[test]
+    fn test_execute_scheduled_rejects_missing_input() {
+        if !MetalGPU::is_available() {
+            return;
+        }
+
+        let executor = GPUGraphExecutor::new().unwrap();
+        let layer = pipeline_layers(1).remove(0);
+        let mut inputs = HashMap::new();
+        inputs.insert(layer.input, pipeline_input());
+
+        assert!(executor.execute_scheduled(&layer.graph, &inputs).is_err());
+    }
+
+    fn pipeline_layers(count: usize) -> Vec<PipelineLayer> {
+        use super::super::weights::ComputeDtype;
+
+        (0..count)
+            .map(|index| {
+                let mut graph = ComputeGraph::new();
+                let mm = graph.add_node(Operation::MatMul { shape: (1, 2) }, vec![100, 101]);
+                let gelu = graph.add_node(Operation::Gelu, vec![mm]);
+                graph.set_output(gelu);
+                let scale = 0.5 + index as f32 * 0.25;
+                let weight = ndarray::array![[scale, -0.5], [0.25, scale]];
+                PipelineLayer {
+                    graph,
+                    input: 100,
+                    output: gelu,
+                    weights: vec![(101, Weight::from_f32(weight, ComputeDtype::F16))],
+                }
+            })
+            .collect()
+    }
+
+    fn pipeline_input() -> MLXArray {
+        MLXArray::new_cpu(vec![1.0, 2.0], ArrayShape::Shape2D(1, 2))
+    }
+
+    fn run_sequentially(layers: &[PipelineLayer], input: MLXArray) -> Vec<f32> {
+        let mut activation = input;
+        for layer in layers {
+            let mut inputs: HashMap<NodeId, MLXArray> = layer
+                .weights
+                .iter()
+                .map(|(id, weight)| (*id, dequantize(weight)))
+                .collect();
+            inputs.insert(layer.input, activation);
+            activation = super::super::graph_executor::Executor::execute(&layer.graph, &inputs)
+                .unwrap()
+                .remove(&layer.output)
+                .unwrap();
+        }
+        activation.data()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_pipelined_matches_sequential() {
+        let layers = pipeline_layers(4);
+        let expected = run_sequentially(&layers, pipeline_input());
+
+        let (output, stats) = PipelinedGraphExecutor::new()
+            .run(&layers, pipeline_input())
+            .unwrap();
+
+        assert_close(&output.data(), &expected);
+        assert_eq!(stats.layers, 4);
+    }
+
+    #[test]
+    fn test_pipelined_single_staging_slot() {
+        let layers = pipeline_layers(3);
+        let expected = run_sequentially(&layers, pipeline_input());
+
+        let (output, _) = PipelinedGraphExecutor::new()
+            .with_staging_slots(0)
+            .run(&layers, pipeline_input())
+            .unwrap();
+
+        assert_close(&output.data(), &expected);
+    }
+
+    #[test]
+    fn test_pipelined_reports_missing_output() {
+        let mut layers = pipeline_layers(3);
+        layers[1].output = 999;
+
+        let err = PipelinedGraphExecutor::new()
+            .with_staging_slots(1)
+            .run(&layers, pipeline_input())
+            .unwrap_err();
+        assert!(err.to_string().contains("Layer 1 produced no output"));
+    }
+
+    #[test]
+    fn test_pipelined_empty_model_returns_input() {
+        let (output, stats) = PipelinedGraphExecutor::new()
+            .run(&[], pipeline_input())
+            .unwrap();
+        assert_eq!(output.data(), vec![1.0, 2.0]);
+        assert_eq!(stats.layers, 0);
+    }
 }
@@ -1,17 +1,26 @@
 use super::compute_graph::{ComputeGraph, NodeId};
 use super::compute_ops::execute_op;
+use super::graph_scheduler::DependencyScheduler;
 use super::unified_memory::MLXArray;
-use std::collections::HashMap;
+use crate::error::{MinervaError, MinervaResult};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 pub struct Executor;
 
 impl Executor {
+    /// Run `graph` sequentially in dependency order
+    ///
+    /// Fails if the graph has a cycle or a node's inputs are never supplied.
     pub fn execute(
         graph: &ComputeGraph,
         inputs: &HashMap<NodeId, MLXArray>,
-    ) -> HashMap<NodeId, MLXArray> {
+    ) -> MinervaResult<HashMap<NodeId, MLXArray>> {
         let mut results = inputs.clone();
-        let order = graph.topological_sort();
+        let available: HashSet<NodeId> = inputs.keys().copied().collect();
+        let order = graph.execution_order(&available)?;
 
         for node_id in order {
             if results.contains_key(&node_id) {
@@ -25,18 +34,116 @@ impl Executor {
                     .filter_map(|input_id| results.get(input_id))
                     .collect();
 
-                assert_eq!(
-                    input_refs.len(),
-                    node.inputs.len(),
-                    "Missing inputs for node {}",
-                    node_id
-                );
+                if input_refs.len() != node.inputs.len() {
+                    return Err(MinervaError::InferenceError(format!(
+                        "Missing inputs for node {}",
+                        node_id
+                    )));
+                }
 
                 let output = execute_op(&node.op, &input_refs);
                 results.insert(node_id, output);
             }
         }
 
-        results
+        Ok(results)
     }
 }
+
+/// Results of a scheduled graph run, with per-op wall time for profiling
+#[derive(Default)]
+pub struct Outputs {
+    pub values: HashMap<NodeId, MLXArray>,
+    pub op_timings: HashMap<NodeId, Duration>,
+}
+
+/// Parallel executor: ops run on a `num_cpus`-sized pool as soon as their inputs exist
+pub struct GraphExecutor;
+
+impl GraphExecutor {
+    pub fn run_async(
+        graph: Arc<ComputeGraph>,
+        inputs: HashMap<NodeId, MLXArray>,
+    ) -> impl Future<Output = MinervaResult<Outputs>> {
+        async move {
+            match tokio::task::spawn_blocking(move || Self::run(&graph, inputs)).await {
+                Ok(result) => result,
+                Err(e) => Err(MinervaError::InferenceError(format!(
+                    "Graph task failed: {}",
+                    e
+                ))),
+            }
+        }
+    }
+
+    /// Blocking variant of `run_async`
+    pub fn run(graph: &ComputeGraph, inputs: HashMap<NodeId, MLXArray>) -> MinervaResult<Outputs> {
+        let available: HashSet<NodeId> = inputs.keys().copied().collect();
+        let mut scheduler = DependencyScheduler::new(graph, &available)?;
+        let mut outputs = Outputs {
+            values: inputs,
+            op_timings: HashMap::new(),
+        };
+
+        loop {
+            let ready = scheduler.take_ready();
+            if ready.is_empty() {
+                break;
+            }
+            for (node_id, output, elapsed) in Self::dispatch(graph, &ready, &outputs.values) {
+                outputs.values.insert(node_id, output);
+                outputs.op_timings.insert(node_id, elapsed);
+                scheduler.complete(node_id);
+            }
+        }
+
+        match scheduler.unfinished().as_slice() {
+            [] => Ok(outputs),
+            stalled => Err(MinervaError::InferenceError(format!(
+                "Missing inputs for nodes {:?}",
+                stalled
+            ))),
+        }
+    }
+
+    fn dispatch(
+        graph: &ComputeGraph,
+        ready: &[NodeId],
+        values: &HashMap<NodeId, MLXArray>,
+    ) -> Vec<(NodeId, MLXArray, Duration)> {
+        worker_pool().install(|| {
+            ready
+                .par_iter()
+                .filter_map(|node_id| Self::run_op(graph, *node_id, values))
+                .collect()
+        })
+    }
+
+    fn run_op(
+        graph: &ComputeGraph,
+        node_id: NodeId,
+        values: &HashMap<NodeId, MLXArray>,
+    ) -> Option<(NodeId, MLXArray, Duration)> {
+        let node = graph.get_node(node_id)?;
+        let input_refs: Vec<&MLXArray> = node
+            .inputs
+            .iter()
+            .filter_map(|input_id| values.get(input_id))
+            .collect();
+
+        let start = Instant::now();
+        let output = execute_op(&node.op, &input_refs);
+        Some((node_id, output, start.elapsed()))
+    }
+}
+
+fn worker_pool() -> &'static rayon::ThreadPool {
+    static POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .thread_name(|i| format!("graph-exec-{}", i))
+            .build()
+            .expect("failed to build graph executor thread pool")
+    })
+}
@@ -3,9 +3,32 @@ mod tests {
     use crate::inference::mlx_native::{
         ArrayShape, MLXArray,
         compute_graph::{ComputeGraph, Operation},
-        graph_executor::Executor,
+        graph_executor::{Executor, GraphExecutor},
     };
     use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn diamond_graph() -> (ComputeGraph, usize) {
+        let mut graph = ComputeGraph::new();
+        let left = graph.add_node(Operation::Gelu, vec![100]);
+        let right = graph.add_node(Operation::Add, vec![100, 101]);
+        let join = graph.add_node(Operation::Add, vec![left, right]);
+        graph.set_output(join);
+        (graph, join)
+    }
+
+    fn diamond_inputs() -> HashMap<usize, MLXArray> {
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            100,
+            MLXArray::new_cpu(vec![1.0, 2.0], ArrayShape::Shape1D(2)),
+        );
+        inputs.insert(
+            101,
+            MLXArray::new_cpu(vec![3.0, 4.0], ArrayShape::Shape1D(2)),
+        );
+        inputs
+    }
 
     #[test]
     fn test_execute_single_op() {
@@ -23,11 +46,26 @@ mod tests {
             MLXArray::new_cpu(vec![3.0, 4.0], ArrayShape::Shape1D(2)),
         );
 
-        let results = Executor::execute(&graph, &inputs);
+        let results = Executor::execute(&graph, &inputs).unwrap();
         assert!(results.contains_key(&add_id));
         assert_eq!(results[&add_id].data(), vec![4.0, 6.0]);
     }
 
+    #[test]
+    fn test_execute_missing_input_is_error() {
+        let mut graph = ComputeGraph::new();
+        let add_id = graph.add_node(Operation::Add, vec![100, 101]);
+        graph.set_output(add_id);
+
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            100,
+            MLXArray::new_cpu(vec![1.0, 2.0], ArrayShape::Shape1D(2)),
+        );
+
+        assert!(Executor::execute(&graph, &inputs).is_err());
+    }
+
     #[test]
     fn test_execute_chain() {
         let mut graph = ComputeGraph::new();
@@ -39,7 +77,7 @@ mod tests {
         inputs.insert(0, MLXArray::new_cpu(vec![0.5], ArrayShape::Shape1D(1)));
         inputs.insert(1, MLXArray::new_cpu(vec![0.5], ArrayShape::Shape1D(1)));
 
-        let results = Executor::execute(&graph, &inputs);
+        let results = Executor::execute(&graph, &inputs).unwrap();
         assert!(results.contains_key(&n1));
         assert!(results[&n1].data()[0] > 0.0);
     }
@@ -54,7 +92,7 @@ mod tests {
         let data = vec![1.0, 2.0];
         inputs.insert(0, MLXArray::new_cpu(data.clone(), ArrayShape::Shape1D(2)));
 
-        let results = Executor::execute(&graph, &inputs);
+        let results = Executor::execute(&graph, &inputs).unwrap();
         assert_eq!(results[&0].data(), data);
     }
 
@@ -78,7 +116,7 @@ mod tests {
             MLXArray::new_cpu(vec![10.0, 20.0, 30.0, 40.0], ArrayShape::Shape2D(2, 2)),
         );
 
-        let results = Executor::execute(&graph, &inputs);
+        let results = Executor::execute(&graph, &inputs).unwrap();
         assert!(results.contains_key(&fused_id));
         let result_data = results[&fused_id].data();
         assert_eq!(result_data.len(), 4);
@@ -103,7 +141,7 @@ mod tests {
             MLXArray::new_cpu(vec![1.0, 0.0, 0.0, 1.0], ArrayShape::Shape2D(2, 2)),
         );
 
-        let results = Executor::execute(&graph, &inputs);
+        let results = Executor::execute(&graph, &inputs).unwrap();
         assert!(results.contains_key(&fused_id));
         let result_data = results[&fused_id].data();
         assert_eq!(result_data.len(), 4);
@@ -133,10 +171,42 @@ mod tests {
             MLXArray::new_cpu(vec![0.0, 0.0, 0.0, 0.0], ArrayShape::Shape2D(2, 2)),
         );
 
-        let results = Executor::execute(&graph, &inputs);
+        let results = Executor::execute(&graph, &inputs).unwrap();
         assert!(results.contains_key(&fused_id));
         let result_data = results[&fused_id].data();
         assert_eq!(result_data.len(), 4);
         assert!(result_data[0] > 0.0);
     }
+
+    #[test]
+    fn test_parallel_run_matches_sequential() {
+        let (graph, join) = diamond_graph();
+        let inputs = diamond_inputs();
+
+        let sequential = Executor::execute(&graph, &inputs).unwrap();
+        let parallel = GraphExecutor::run(&graph, inputs).unwrap();
+
+        assert_eq!(parallel.values[&join].data(), sequential[&join].data());
+        assert_eq!(parallel.op_timings.len(), 3);
+    }
+
+    #[test]
+    fn test_parallel_run_reports_missing_inputs() {
+        let (graph, _) = diamond_graph();
+        let mut inputs = diamond_inputs();
+        inputs.remove(&101);
+
+        assert!(GraphExecutor::run(&graph, inputs).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_async() {
+        let (graph, join) = diamond_graph();
+        let outputs = GraphExecutor::run_async(Arc::new(graph), diamond_inputs())
+            .await
+            .unwrap();
+
+        assert!(outputs.values.contains_key(&join));
+        assert!(outputs.op_timings.contains_key(&join));
+    }
 }
@@ -1,4 +1,4 @@
-use super::compute_graph::{ComputeGraph, NodeId, Operation};
+use super::compute_graph::{ComputeGraph, Node, NodeId, Operation};
 use std::collections::HashSet;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -144,3 +144,83 @@ impl FusionDetector {
         unique
     }
 }
+
+/// A LayerNorm -> MatMul pair that can be collapsed into one fused kernel
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FusionCandidate {
+    pub layer_norm: NodeId,
+    pub matmul: NodeId,
+    pub shape: (usize, usize),
+    pub eps: f32,
+}
+
+/// In-place graph rewrites that fuse multi-node patterns
+pub struct GraphFusion;
+
+impl GraphFusion {
+    pub fn find_ln_matmul_patterns(graph: &ComputeGraph) -> Vec<FusionCandidate> {
+        let mut candidates: Vec<FusionCandidate> = graph
+            .all_nodes()
+            .filter_map(|(node_id, _)| Self::match_ln_matmul(*node_id, graph))
+            .collect();
+        candidates.sort_by_key(|candidate| candidate.matmul);
+        candidates
+    }
+
+    /// Rewrite each candidate into a `FusedLayerNormMatMul` node, returning how many were fused
+    pub fn apply(graph: &mut ComputeGraph, candidates: &[FusionCandidate]) -> usize {
+        candidates
+            .iter()
+            .filter(|candidate| Self::fuse_ln_matmul(graph, candidate))
+            .count()
+    }
+
+    fn match_ln_matmul(matmul_id: NodeId, graph: &ComputeGraph) -> Option<FusionCandidate> {
+        let matmul = graph.get_node(matmul_id)?;
+        let Operation::MatMul { shape } = matmul.op else {
+            return None;
+        };
+        let ln_id = *matmul.inputs.first()?;
+        let Operation::LayerNorm { eps } = graph.get_node(ln_id)?.op else {
+            return None;
+        };
+
+        // The normalized tensor must not be needed by anything else
+        if graph.consumers(ln_id) != [matmul_id] || graph.is_output(ln_id) {
+            return None;
+        }
+
+        Some(FusionCandidate {
+            layer_norm: ln_id,
+            matmul: matmul_id,
+            shape,
+            eps,
+        })
+    }
+
+    fn fuse_ln_matmul(graph: &mut ComputeGraph, candidate: &FusionCandidate) -> bool {
+        // Candidates may be stale if the graph changed since they were found
+        if Self::match_ln_matmul(candidate.matmul, graph).as_ref() != Some(candidate) {
+            return false;
+        }
+
+        let weights: Vec<NodeId> = match graph.get_node(candidate.matmul) {
+            Some(matmul) => matmul.inputs[1..].to_vec(),
+            None => return false,
+        };
+        let Some(layer_norm) = graph.remove_node(candidate.layer_norm) else {
+            return false;
+        };
+
+        let mut inputs = layer_norm.inputs;
+        inputs.extend(weights);
+        graph.replace_node(Node {
+            id: candidate.matmul,
+            op: Operation::FusedLayerNormMatMul {
+                shape: candidate.shape,
+                eps: candidate.eps,
+            },
+            inputs,
+        })
+    }
+}
@@ -1,4 +1,7 @@
-use super::unified_memory::MLXArray;
+use super::compute_graph::{ComputeGraph, Node, NodeId, Operation, ProjectionRole};
+use super::unified_memory::{ArrayShape, MLXArray};
+use crate::error::MinervaResult;
+use std::collections::{BTreeMap, HashSet};
 
 pub trait FusedOpExecutor {
     fn execute(&self, inputs: &[&MLXArray]) -> MLXArray;
@@ -103,3 +106,227 @@ impl FusedOpExecutor for FusedLinearAddGeluOp {
         MLXArray::new_cpu(result, super::unified_memory::ArrayShape::Shape2D(m, n))
     }
 }
+
+/// Fuses LayerNorm + MatMul into single operation
+/// Input order: [norm_input, matmul_rhs]
+///
+/// The normalization is applied inside the matmul inner loop (RMS of the
+/// mean-centred input), so the normalized tensor is never materialized.
+pub struct FusedLayerNormMatMulOp {
+    pub matmul_shape: (usize, usize),
+    pub eps: f32,
+}
+
+impl FusedOpExecutor for FusedLayerNormMatMulOp {
+    fn execute(&self, inputs: &[&MLXArray]) -> MLXArray {
+        assert_eq!(inputs.len(), 2, "FusedLayerNormMatMul expects 2 inputs");
+
+        let norm_input = inputs[0].data();
+        let matmul_rhs = inputs[1].data();
+        let (mean, inv_rms) = centred_rms(&norm_input, self.eps);
+
+        let (m, n) = self.matmul_shape;
+        let k = norm_input.len() / m;
+
+        let mut result = vec![0.0; m * n];
+
+        for i in 0..m {
+            for j in 0..n {
+                let mut sum = 0.0;
+                for p in 0..k {
+                    let normed = (norm_input[i * k + p] - mean) * inv_rms;
+                    sum += normed * matmul_rhs[p * n + j];
+                }
+                result[i * n + j] = sum;
+            }
+        }
+
+        MLXArray::new_cpu(result, super::unified_memory::ArrayShape::Shape2D(m, n))
+    }
+}
+
+/// Mean and inverse RMS of the centred data (same statistics as `LayerNormExecutor`)
+fn centred_rms(data: &[f32], eps: f32) -> (f32, f32) {
+    let len = data.len() as f32;
+    let mean = data.iter().sum::<f32>() / len;
+    let var = data.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / len;
+    (mean, 1.0 / (var + eps).sqrt())
+}
+
+/// Fuses the Q, K and V projections of one input into a single pass
+/// Input order: [x, w_q, w_k, w_v]
+///
+/// Each row of the output holds that row's Q, K and V projections back to
+/// back, so `x` is read once for all three weight matrices.
+pub struct FusedQKVOp {
+    pub rows: usize,
+    pub widths: [usize; 3],
+}
+
+impl FusedOpExecutor for FusedQKVOp {
+    fn execute(&self, inputs: &[&MLXArray]) -> MLXArray {
+        assert_eq!(inputs.len(), 4, "FusedQKV expects 4 inputs");
+
+        let x = inputs[0].data();
+        let weights: Vec<Vec<f32>> = inputs[1..].iter().map(|w| w.data()).collect();
+
+        let m = self.rows;
+        let k = x.len() / m;
+        let total: usize = self.widths.iter().sum();
+
+        let mut result = vec![0.0; m * total];
+
+        for i in 0..m {
+            let row = &x[i * k..(i + 1) * k];
+            let mut offset = i * total;
+            for (w, &n) in weights.iter().zip(&self.widths) {
+                for j in 0..n {
+                    let mut sum = 0.0;
+                    for p in 0..k {
+                        sum += row[p] * w[p * n + j];
+                    }
+                    result[offset + j] = sum;
+                }
+                offset += n;
+            }
+        }
+
+        MLXArray::new_cpu(result, ArrayShape::Shape2D(m, total))
+    }
+}
+
+/// Selects one projection from a `FusedQKV` output
+/// Input order: [fused]
+pub struct ColumnSliceOp {
+    pub offset: usize,
+    pub shape: (usize, usize),
+}
+
+impl FusedOpExecutor for ColumnSliceOp {
+    fn execute(&self, inputs: &[&MLXArray]) -> MLXArray {
+        assert_eq!(inputs.len(), 1, "ColumnSlice expects 1 input");
+
+        let data = inputs[0].data();
+        let (m, n) = self.shape;
+        let width = data.len() / m;
+
+        let result: Vec<f32> = (0..m)
+            .flat_map(|i| {
+                let start = i * width + self.offset;
+                data[start..start + n].iter().copied()
+            })
+            .collect();
+
+        MLXArray::new_cpu(result, ArrayShape::Shape2D(m, n))
+    }
+}
+
+/// One MatMul reading a shared input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Projection {
+    node: NodeId,
+    weight: NodeId,
+    width: usize,
+}
+
+/// Graph rewrites that introduce multi-output fused ops
+pub struct GraphFusionOps;
+
+impl GraphFusionOps {
+    /// Fuse Q, K and V projections sharing a left-hand input into `FusedQKV`, returning how many were fused
+    ///
+    /// The original Q, K and V node ids become `ColumnSlice` views of the
+    /// fused output, so their consumers are left untouched. Only MatMuls
+    /// tagged with a [`ProjectionRole`] take part, so gate/up projections
+    /// over the same input are never fused; each role is taken in node
+    /// order and paired with the others.
+    ///
+    /// A group is skipped when one of its weights is computed from another
+    /// projection in the group, since fusing it would create a cycle. Fails
+    /// if `graph` already has one.
+    pub fn fuse_qkv(graph: &mut ComputeGraph) -> MinervaResult<usize> {
+        graph.check_acyclic()?;
+
+        let mut by_input: BTreeMap<(NodeId, usize), [Vec<Projection>; 3]> = BTreeMap::new();
+        for (id, node) in graph.all_nodes() {
+            let Some(role) = graph.role(*id) else {
+                continue;
+            };
+            if let (
+                Operation::MatMul {
+                    shape: (rows, width),
+                },
+                [x, weight],
+            ) = (&node.op, node.inputs.as_slice())
+            {
+                let slot = match role {
+                    ProjectionRole::Query => 0,
+                    ProjectionRole::Key => 1,
+                    ProjectionRole::Value => 2,
+                };
+                by_input.entry((*x, *rows)).or_default()[slot].push(Projection {
+                    node: *id,
+                    weight: *weight,
+                    width: *width,
+                });
+            }
+        }
+
+        let mut fused = 0;
+        for ((x, rows), mut roles) in by_input {
+            roles.iter_mut().for_each(|projections| projections.sort());
+            let [queries, keys, values] = roles;
+            for ((q, k), v) in queries.into_iter().zip(keys).zip(values) {
+                let qkv = [q, k, v];
+                let nodes: HashSet<NodeId> = qkv.iter().map(|p| p.node).collect();
+                if qkv
+                    .iter()
+                    .any(|p| Self::depends_on(graph, p.weight, &nodes))
+                {
+                    continue;
+                }
+                Self::fuse_projections(graph, (x, rows), &qkv);
+                fused += 1;
+            }
+        }
+        graph.check_acyclic()?;
+        Ok(fused)
+    }
+
+    /// Whether `id` is, or is computed from, any node in `targets`
+    fn depends_on(graph: &ComputeGraph, id: NodeId, targets: &HashSet<NodeId>) -> bool {
+        let mut stack = vec![id];
+        let mut seen = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if targets.contains(&id) {
+                return true;
+            }
+            if seen.insert(id)
+                && let Some(node) = graph.get_node(id)
+            {
+                stack.extend(node.inputs.iter().copied());
+            }
+        }
+        false
+    }
+
+    fn fuse_projections(graph: &mut ComputeGraph, (x, rows): (NodeId, usize), qkv: &[Projection]) {
+        let mut inputs = vec![x];
+        inputs.extend(qkv.iter().map(|p| p.weight));
+        let widths = [qkv[0].width, qkv[1].width, qkv[2].width];
+        let fused = graph.add_node(Operation::FusedQKV { rows, widths }, inputs);
+
+        let mut offset = 0;
+        for projection in qkv {
+            graph.replace_node(Node {
+                id: projection.node,
+                op: Operation::ColumnSlice {
+                    offset,
+                    shape: (rows, projection.width),
+                },
+                inputs: vec![fused],
+            });
+            offset += projection.width;
+        }
+    }
+}
@@ -2,10 +2,42 @@
 mod tests {
     use crate::inference::mlx_native::{
         ArrayShape, MLXArray,
+        compute_graph::{ComputeGraph, NodeId, Operation, ProjectionRole},
+        graph_executor::Executor,
+        graph_fusion::GraphFusion,
         graph_fusion_ops::{
-            FusedLinearAddGeluOp, FusedLinearAddOp, FusedLinearGeluOp, FusedOpExecutor,
+            FusedLinearAddGeluOp, FusedLinearAddOp, FusedLinearGeluOp, FusedOpExecutor, FusedQKVOp,
+            GraphFusionOps,
         },
     };
+    use std::collections::HashMap;
+
+    fn ln_matmul_graph() -> (ComputeGraph, NodeId) {
+        let mut graph = ComputeGraph::new();
+        let ln = graph.add_node(Operation::LayerNorm { eps: 1e-5 }, vec![100]);
+        let mm = graph.add_node(Operation::MatMul { shape: (3, 2) }, vec![ln, 101]);
+        graph.set_output(mm);
+        (graph, mm)
+    }
+
+    fn ln_matmul_inputs() -> HashMap<NodeId, MLXArray> {
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            100,
+            MLXArray::new_cpu(
+                vec![0.5, -1.0, 2.0, 3.5, 0.25, -2.0, 1.5, 4.0, -0.75],
+                ArrayShape::Shape2D(3, 3),
+            ),
+        );
+        inputs.insert(
+            101,
+            MLXArray::new_cpu(
+                vec![0.1, -0.2, 0.3, 0.4, -0.5, 0.6],
+                ArrayShape::Shape2D(3, 2),
+            ),
+        );
+        inputs
+    }
 
     #[test]
     fn test_fused_linear_add_correctness() {
@@ -89,4 +121,174 @@ mod tests {
 
         assert_eq!(result.data().len(), 1000);
     }
+
+    #[test]
+    fn test_fused_layernorm_matmul_matches_unfused() {
+        let inputs = ln_matmul_inputs();
+
+        let (unfused, output) = ln_matmul_graph();
+        let expected = Executor::execute(&unfused, &inputs).unwrap()[&output].data();
+
+        let (mut fused, output) = ln_matmul_graph();
+        let candidates = GraphFusion::find_ln_matmul_patterns(&fused);
+        assert_eq!(GraphFusion::apply(&mut fused, &candidates), 1);
+        assert!(matches!(
+            fused.get_node(output).unwrap().op,
+            Operation::FusedLayerNormMatMul { .. }
+        ));
+
+        let actual = Executor::execute(&fused, &inputs).unwrap()[&output].data();
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 1e-4, "fused {} vs unfused {}", a, e);
+        }
+    }
+
+    #[test]
+    fn test_apply_skips_stale_candidates() {
+        let (mut graph, _) = ln_matmul_graph();
+        let candidates = GraphFusion::find_ln_matmul_patterns(&graph);
+
+        assert_eq!(GraphFusion::apply(&mut graph, &candidates), 1);
+        assert_eq!(GraphFusion::apply(&mut graph, &candidates), 0);
+    }
+
+    fn qkv_graph() -> (ComputeGraph, [NodeId; 3]) {
+        let mut graph = ComputeGraph::new();
+        let q = graph.add_node(Operation::MatMul { shape: (2, 3) }, vec![100, 101]);
+        let k = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, 102]);
+        let v = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, 103]);
+        tag_qkv(&mut graph, [q, k, v]);
+        for id in [q, k, v] {
+            graph.set_output(id);
+        }
+        (graph, [q, k, v])
+    }
+
+    fn tag_qkv(graph: &mut ComputeGraph, [q, k, v]: [NodeId; 3]) {
+        graph.set_role(q, ProjectionRole::Query);
+        graph.set_role(k, ProjectionRole::Key);
+        graph.set_role(v, ProjectionRole::Value);
+    }
+
+    fn qkv_inputs() -> HashMap<NodeId, MLXArray> {
+        let weight = |n: usize, scale: f32| {
+            let data = (0..3 * n).map(|i| (i as f32 - 2.0) * scale).collect();
+            MLXArray::new_cpu(data, ArrayShape::Shape2D(3, n))
+        };
+        HashMap::from([
+            (
+                100,
+                MLXArray::new_cpu(
+                    vec![0.5, -1.0, 2.0, 1.5, 0.25, -0.75],
+                    ArrayShape::Shape2D(2, 3),
+                ),
+            ),
+            (101, weight(3, 0.1)),
+            (102, weight(2, -0.3)),
+            (103, weight(2, 0.7)),
+        ])
+    }
+
+    #[test]
+    fn test_fused_qkv_matches_separate_matmuls() {
+        let inputs = qkv_inputs();
+        let fused = FusedQKVOp {
+            rows: 2,
+            widths: [3, 2, 2],
+        }
+        .execute(&[&inputs[&100], &inputs[&101], &inputs[&102], &inputs[&103]]);
+        assert_eq!(fused.shape(), ArrayShape::Shape2D(2, 7));
+
+        let (graph, [q, k, v]) = qkv_graph();
+        let separate = Executor::execute(&graph, &inputs).unwrap();
+        let fused = fused.data();
+        for row in 0..2 {
+            let expected = [q, k, v]
+                .iter()
+                .flat_map(|id| {
+                    let data = separate[id].data();
+                    let width = data.len() / 2;
+                    data[row * width..(row + 1) * width].to_vec()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(&fused[row * 7..(row + 1) * 7], expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_fuse_qkv_rewrites_graph() {
+        let (mut graph, [q, k, v]) = qkv_graph();
+        let inputs = qkv_inputs();
+        let unfused = Executor::execute(&graph, &inputs).unwrap();
+
+        assert_eq!(GraphFusionOps::fuse_qkv(&mut graph).unwrap(), 1);
+        let fused_id = graph.get_node(q).unwrap().inputs[0];
+        assert!(matches!(
+            graph.get_node(fused_id).unwrap().op,
+            Operation::FusedQKV {
+                rows: 2,
+                widths: [3, 2, 2]
+            }
+        ));
+
+        let fused = Executor::execute(&graph, &inputs).unwrap();
+        for id in [q, k, v] {
+            assert_eq!(fused[&id].data(), unfused[&id].data());
+            assert_eq!(fused[&id].shape(), unfused[&id].shape());
+        }
+    }
+
+    #[test]
+    fn test_fuse_qkv_skips_group_that_would_cycle() {
+        let mut graph = ComputeGraph::new();
+        let q = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, 101]);
+        let k_weight = graph.add_node(Operation::Gelu, vec![q]);
+        let k = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, k_weight]);
+        let v = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, 103]);
+        tag_qkv(&mut graph, [q, k, v]);
+        for id in [q, k, v] {
+            graph.set_output(id);
+        }
+
+        assert_eq!(GraphFusionOps::fuse_qkv(&mut graph).unwrap(), 0);
+        assert!(graph.check_acyclic().is_ok());
+    }
+
+    #[test]
+    fn test_fuse_qkv_ignores_untagged_projections() {
+        // Gate, up and a third projection of one input are not attention
+        let mut graph = ComputeGraph::new();
+        let ids = [101, 102, 103]
+            .map(|weight| graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, weight]));
+        for id in ids {
+            graph.set_output(id);
+        }
+
+        assert_eq!(GraphFusionOps::fuse_qkv(&mut graph).unwrap(), 0);
+        for id in ids {
+            assert!(matches!(
+                graph.get_node(id).unwrap().op,
+                Operation::MatMul { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_fuse_qkv_needs_three_projections() {
+        let mut graph = ComputeGraph::new();
+        let q = graph.add_node(Operation::MatMul { shape: (2, 3) }, vec![100, 101]);
+        let k = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, 102]);
+        let other = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![104, 103]);
+        tag_qkv(&mut graph, [q, k, other]);
+        graph.set_output(q);
+        graph.set_output(k);
+        graph.set_output(other);
+
+        assert_eq!(GraphFusionOps::fuse_qkv(&mut graph).unwrap(), 0);
+        assert!(matches!(
+            graph.get_node(q).unwrap().op,
+            Operation::MatMul { .. }
+        ));
+    }
 }
@@ -2,7 +2,7 @@
 mod tests {
     use crate::inference::mlx_native::{
         compute_graph::{ComputeGraph, Operation},
-        graph_fusion::{FusionDetector, FusionPattern},
+        graph_fusion::{FusionDetector, FusionPattern, GraphFusion},
     };
 
     #[test]
@@ -62,4 +62,28 @@ mod tests {
         assert!(nodes.contains(&n0));
         assert!(nodes.contains(&n1));
     }
+
+    #[test]
+    fn test_find_ln_matmul_patterns() {
+        let mut graph = ComputeGraph::new();
+        let ln = graph.add_node(Operation::LayerNorm { eps: 1e-5 }, vec![100]);
+        let mm = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![ln, 101]);
+        graph.set_output(mm);
+
+        let candidates = GraphFusion::find_ln_matmul_patterns(&graph);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].layer_norm, ln);
+        assert_eq!(candidates[0].matmul, mm);
+    }
+
+    #[test]
+    fn test_ln_with_multiple_consumers_not_fused() {
+        let mut graph = ComputeGraph::new();
+        let ln = graph.add_node(Operation::LayerNorm { eps: 1e-5 }, vec![100]);
+        let mm = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![ln, 101]);
+        let add = graph.add_node(Operation::Add, vec![mm, ln]);
+        graph.set_output(add);
+
+        assert!(GraphFusion::find_ln_matmul_patterns(&graph).is_empty());
+    }
 }
@@ -1,11 +1,19 @@
 use super::compute_graph::{ComputeGraph, Node, NodeId, Operation};
 use super::graph_fusion::{FusionDetector, FusionPattern};
+use super::graph_fusion_ops::GraphFusionOps;
+use crate::error::MinervaResult;
 use std::collections::HashMap;
 
 pub struct GraphOptimizer;
 
 impl GraphOptimizer {
-    pub fn optimize(graph: &ComputeGraph) -> ComputeGraph {
+    /// Fuse matmul chains and Q/K/V projections into single ops, then run
+    /// [`AlgebraicSimplifier`]
+    ///
+    /// Outputs of `graph` stay outputs of the result under their new ids.
+    /// Both the input graph and the rewritten graph are checked for cycles,
+    /// since a cycle means a graph was built or rewritten wrongly.
+    pub fn optimize(graph: &ComputeGraph) -> MinervaResult<ComputeGraph> {
         let mut optimized = ComputeGraph::new();
         let mut node_mapping = HashMap::new();
         let mut fused_nodes = std::collections::HashSet::new();
@@ -22,7 +30,7 @@ impl GraphOptimizer {
             }
         }
 
-        let order = graph.topological_sort();
+        let order = graph.topological_sort()?;
 
         for node_id in order {
             if nodes_to_skip.contains(&node_id) {
@@ -68,7 +76,20 @@ impl GraphOptimizer {
             }
         }
 
-        optimized
+        for output in graph.outputs() {
+            if let Some(mapped) = node_mapping.get(output) {
+                optimized.set_output(*mapped);
+            }
+        }
+        for (id, role) in graph.roles() {
+            if let Some(&mapped) = node_mapping.get(&id) {
+                optimized.set_role(mapped, role);
+            }
+        }
+        optimized.check_acyclic()?;
+        GraphFusionOps::fuse_qkv(&mut optimized)?;
+        AlgebraicSimplifier::run(&mut optimized)?;
+        Ok(optimized)
     }
 
     fn copy_node(
@@ -180,3 +201,145 @@ impl GraphOptimizer {
         }
     }
 }
+
+/// Rewrites that remove ops without changing results
+///
+/// Graphs traced from model forward passes carry no-op arithmetic: scaling
+/// by one, adding zero, renormalising an already normalised tensor, and
+/// shifting softmax logits by a constant. Each rule works in place on one
+/// node and returns whether it changed the graph. Graph outputs are never
+/// removed, so their ids stay valid for callers.
+pub struct AlgebraicSimplifier;
+
+type Rule = fn(&mut ComputeGraph, NodeId) -> bool;
+
+impl AlgebraicSimplifier {
+    const RULES: [Rule; 4] = [
+        Self::remove_identity_scale,
+        Self::remove_zero_shift,
+        Self::collapse_double_layer_norm,
+        Self::drop_softmax_shift,
+    ];
+
+    /// Apply every rule until none fires, returning how many were applied
+    ///
+    /// Fails if a rewrite leaves a cycle behind.
+    pub fn run(graph: &mut ComputeGraph) -> MinervaResult<usize> {
+        let mut total = 0;
+        loop {
+            let mut ids: Vec<NodeId> = graph.all_nodes().map(|(id, _)| *id).collect();
+            ids.sort();
+
+            let mut applied = 0;
+            for id in ids {
+                for rule in Self::RULES {
+                    if rule(graph, id) {
+                        graph.check_acyclic()?;
+                        applied += 1;
+                    }
+                }
+            }
+            if applied == 0 {
+                return Ok(total);
+            }
+            total += applied;
+        }
+    }
+
+    /// `x * 1.0 -> x`
+    pub fn remove_identity_scale(graph: &mut ComputeGraph, id: NodeId) -> bool {
+        match graph.get_node(id) {
+            Some(node) if matches!(node.op, Operation::Scale { factor } if factor == 1.0) => {
+                Self::bypass(graph, id)
+            }
+            _ => false,
+        }
+    }
+
+    /// `x + 0.0 -> x`
+    pub fn remove_zero_shift(graph: &mut ComputeGraph, id: NodeId) -> bool {
+        match graph.get_node(id) {
+            Some(node) if matches!(node.op, Operation::AddScalar { value } if value == 0.0) => {
+                Self::bypass(graph, id)
+            }
+            _ => false,
+        }
+    }
+
+    /// `LayerNorm(LayerNorm(x)) -> LayerNorm(x)`, keeping the outer epsilon
+    ///
+    /// The inner output already has zero mean and unit variance, so the
+    /// outer norm only changes it by its epsilon term.
+    pub fn collapse_double_layer_norm(graph: &mut ComputeGraph, id: NodeId) -> bool {
+        Self::skip_input(graph, id, |outer, inner| {
+            matches!(outer, Operation::LayerNorm { .. })
+                && matches!(inner, Operation::LayerNorm { .. })
+        })
+    }
+
+    /// `softmax(x + c) -> softmax(x)`, since softmax is shift invariant
+    pub fn drop_softmax_shift(graph: &mut ComputeGraph, id: NodeId) -> bool {
+        Self::skip_input(graph, id, |outer, inner| {
+            matches!(outer, Operation::Softmax) && matches!(inner, Operation::AddScalar { .. })
+        })
+    }
+
+    /// Point consumers of single-input node `id` at its input and remove it
+    fn bypass(graph: &mut ComputeGraph, id: NodeId) -> bool {
+        let Some(node) = graph.get_node(id) else {
+            return false;
+        };
+        let source = match node.inputs.as_slice() {
+            [source] if *source != id => *source,
+            _ => return false,
+        };
+        if graph.is_output(id) {
+            return false;
+        }
+
+        for consumer_id in graph.consumers(id) {
+            if let Some(mut consumer) = graph.get_node(consumer_id).cloned() {
+                for input in consumer.inputs.iter_mut().filter(|input| **input == id) {
+                    *input = source;
+                }
+                graph.replace_node(consumer);
+            }
+        }
+        graph.remove_node(id);
+        true
+    }
+
+    /// Make node `id` read its input's input when `pattern(outer, inner)`,
+    /// removing the skipped node once nothing else reads it
+    fn skip_input(
+        graph: &mut ComputeGraph,
+        id: NodeId,
+        pattern: impl Fn(&Operation, &Operation) -> bool,
+    ) -> bool {
+        let Some(outer) = graph.get_node(id) else {
+            return false;
+        };
+        let inner_id = match outer.inputs.as_slice() {
+            [inner_id] if *inner_id != id => *inner_id,
+            _ => return false,
+        };
+        let Some(inner) = graph.get_node(inner_id) else {
+            return false;
+        };
+        let source = match inner.inputs.as_slice() {
+            [source] if *source != inner_id => *source,
+            _ => return false,
+        };
+        if !pattern(&outer.op, &inner.op) {
+            return false;
+        }
+
+        let mut rewired = outer.clone();
+        rewired.inputs = vec![source];
+        graph.replace_node(rewired);
+        if graph.consumers(inner_id).is_empty() && !graph.is_output(inner_id) {
+            graph.remove_node(inner_id);
+        }
+        true
+    }
+}
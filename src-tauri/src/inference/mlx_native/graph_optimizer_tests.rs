@@ -1,9 +1,12 @@
 #[cfg(test)]
 mod tests {
     use crate::inference::mlx_native::{
-        compute_graph::{ComputeGraph, Operation},
-        graph_optimizer::GraphOptimizer,
+        ArrayShape, MLXArray,
+        compute_graph::{ComputeGraph, NodeId, Operation, ProjectionRole},
+        graph_executor::Executor,
+        graph_optimizer::{AlgebraicSimplifier, GraphOptimizer},
     };
+    use std::collections::HashMap;
 
     #[test]
     fn test_optimize_detects_fusion() {
@@ -11,7 +14,7 @@ mod tests {
         let matmul_id = graph.add_node(Operation::MatMul { shape: (10, 10) }, vec![0]);
         let _add_id = graph.add_node(Operation::Add, vec![matmul_id, 1]);
 
-        let optimized = GraphOptimizer::optimize(&graph);
+        let optimized = GraphOptimizer::optimize(&graph).unwrap();
 
         let fused_found = optimized
             .all_nodes()
@@ -22,11 +25,11 @@ mod tests {
     #[test]
     fn test_optimize_preserves_non_fusible() {
         let mut graph = ComputeGraph::new();
-        let add_id = graph.add_node(Operation::Add, vec![0, 1]);
+        let add_id = graph.add_node(Operation::Add, vec![100, 101]);
         let gelu_id = graph.add_node(Operation::Gelu, vec![add_id]);
         graph.set_output(gelu_id);
 
-        let optimized = GraphOptimizer::optimize(&graph);
+        let optimized = GraphOptimizer::optimize(&graph).unwrap();
 
         assert!(optimized.all_nodes().count() > 0);
     }
@@ -38,7 +41,7 @@ mod tests {
         let add_id = graph.add_node(Operation::Add, vec![matmul_id, 1]);
         let _gelu_id = graph.add_node(Operation::Gelu, vec![add_id]);
 
-        let optimized = GraphOptimizer::optimize(&graph);
+        let optimized = GraphOptimizer::optimize(&graph).unwrap();
 
         let original_count = graph.all_nodes().count();
         let optimized_count = optimized.all_nodes().count();
@@ -57,11 +60,210 @@ mod tests {
         let mm2 = graph.add_node(Operation::MatMul { shape: (10, 10) }, vec![gelu1]);
         let _add2 = graph.add_node(Operation::Add, vec![mm2, 2]);
 
-        let optimized = GraphOptimizer::optimize(&graph);
+        let optimized = GraphOptimizer::optimize(&graph).unwrap();
 
         let original_count = graph.all_nodes().count();
         let optimized_count = optimized.all_nodes().count();
 
         assert!(optimized_count <= original_count);
     }
+
+    #[test]
+    fn test_optimize_simplifies_and_keeps_outputs() {
+        let mut graph = ComputeGraph::new();
+        let scale = graph.add_node(Operation::Scale { factor: 1.0 }, vec![100]);
+        let gelu = graph.add_node(Operation::Gelu, vec![scale]);
+        graph.set_output(gelu);
+
+        let optimized = GraphOptimizer::optimize(&graph).unwrap();
+        assert_eq!(optimized.all_nodes().count(), 1);
+        let output = optimized.outputs()[0];
+        assert_eq!(run_output(&optimized, output), run_output(&graph, gelu));
+    }
+
+    #[test]
+    fn test_optimize_rejects_cyclic_graph() {
+        let mut graph = ComputeGraph::new();
+        let add_id = graph.add_node(Operation::Add, vec![1, 100]);
+        let gelu_id = graph.add_node(Operation::Gelu, vec![add_id]);
+        graph.set_output(gelu_id);
+
+        let Err(err) = GraphOptimizer::optimize(&graph) else {
+            panic!("cyclic graph was optimized");
+        };
+        assert!(err.to_string().contains("cycle detected in compute graph"));
+    }
+
+    fn run_output(graph: &ComputeGraph, output: NodeId) -> Vec<f32> {
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            100,
+            MLXArray::new_cpu(
+                vec![0.5, -1.25, 2.0, 3.5, -0.75, 1.0],
+                ArrayShape::Shape1D(6),
+            ),
+        );
+        Executor::execute(graph, &inputs).unwrap()[&output].data()
+    }
+
+    /// Apply `rule` to `node`, checking it fires and leaves the output unchanged
+    fn assert_equivalent(
+        mut graph: ComputeGraph,
+        output: NodeId,
+        node: NodeId,
+        rule: fn(&mut ComputeGraph, NodeId) -> bool,
+    ) -> ComputeGraph {
+        let before = run_output(&graph, output);
+        let nodes_before = graph.all_nodes().count();
+
+        assert!(rule(&mut graph, node));
+        assert!(graph.all_nodes().count() < nodes_before);
+        let after = run_output(&graph, output);
+        for (x, y) in before.iter().zip(&after) {
+            assert!((x - y).abs() < 1e-4, "{:?} vs {:?}", before, after);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_remove_identity_scale() {
+        let mut graph = ComputeGraph::new();
+        let scale = graph.add_node(Operation::Scale { factor: 1.0 }, vec![100]);
+        let gelu = graph.add_node(Operation::Gelu, vec![scale]);
+        graph.set_output(gelu);
+
+        let graph = assert_equivalent(
+            graph,
+            gelu,
+            scale,
+            AlgebraicSimplifier::remove_identity_scale,
+        );
+        assert_eq!(graph.get_node(gelu).unwrap().inputs, vec![100]);
+    }
+
+    #[test]
+    fn test_remove_zero_shift() {
+        let mut graph = ComputeGraph::new();
+        let shift = graph.add_node(Operation::AddScalar { value: 0.0 }, vec![100]);
+        let gelu = graph.add_node(Operation::Gelu, vec![shift]);
+        let add = graph.add_node(Operation::Add, vec![shift, gelu]);
+        graph.set_output(add);
+
+        let graph = assert_equivalent(graph, add, shift, AlgebraicSimplifier::remove_zero_shift);
+        assert_eq!(graph.get_node(add).unwrap().inputs, vec![100, gelu]);
+    }
+
+    #[test]
+    fn test_collapse_double_layer_norm() {
+        let mut graph = ComputeGraph::new();
+        let inner = graph.add_node(Operation::LayerNorm { eps: 1e-5 }, vec![100]);
+        let outer = graph.add_node(Operation::LayerNorm { eps: 1e-6 }, vec![inner]);
+        graph.set_output(outer);
+
+        let graph = assert_equivalent(
+            graph,
+            outer,
+            outer,
+            AlgebraicSimplifier::collapse_double_layer_norm,
+        );
+        assert!(graph.get_node(inner).is_none());
+        assert!(matches!(
+            graph.get_node(outer).unwrap().op,
+            Operation::LayerNorm { eps } if eps == 1e-6
+        ));
+    }
+
+    #[test]
+    fn test_drop_softmax_shift() {
+        let mut graph = ComputeGraph::new();
+        let shift = graph.add_node(Operation::AddScalar { value: 3.0 }, vec![100]);
+        let softmax = graph.add_node(Operation::Softmax, vec![shift]);
+        graph.set_output(softmax);
+
+        let graph = assert_equivalent(
+            graph,
+            softmax,
+            softmax,
+            AlgebraicSimplifier::drop_softmax_shift,
+        );
+        assert_eq!(graph.get_node(softmax).unwrap().inputs, vec![100]);
+    }
+
+    #[test]
+    fn test_rules_keep_nodes_still_in_use() {
+        let mut graph = ComputeGraph::new();
+        let shift = graph.add_node(Operation::AddScalar { value: 3.0 }, vec![100]);
+        let softmax = graph.add_node(Operation::Softmax, vec![shift]);
+        let identity = graph.add_node(Operation::Scale { factor: 1.0 }, vec![shift]);
+        graph.set_output(softmax);
+        graph.set_output(identity);
+
+        assert!(AlgebraicSimplifier::drop_softmax_shift(&mut graph, softmax));
+        assert!(graph.get_node(shift).is_some());
+        // Outputs keep their ids, so they are never bypassed
+        assert!(!AlgebraicSimplifier::remove_identity_scale(
+            &mut graph, identity
+        ));
+        assert!(!AlgebraicSimplifier::remove_identity_scale(
+            &mut graph, shift
+        ));
+    }
+
+    #[test]
+    fn test_simplifier_runs_to_fixed_point() {
+        let mut graph = ComputeGraph::new();
+        let scale = graph.add_node(Operation::Scale { factor: 1.0 }, vec![100]);
+        let shift = graph.add_node(Operation::AddScalar { value: 0.0 }, vec![scale]);
+        let ln1 = graph.add_node(Operation::LayerNorm { eps: 1e-5 }, vec![shift]);
+        let ln2 = graph.add_node(Operation::LayerNorm { eps: 1e-5 }, vec![ln1]);
+        let ln3 = graph.add_node(Operation::LayerNorm { eps: 1e-5 }, vec![ln2]);
+        let logits = graph.add_node(Operation::AddScalar { value: -2.0 }, vec![ln3]);
+        let softmax = graph.add_node(Operation::Softmax, vec![logits]);
+        graph.set_output(softmax);
+        let before = run_output(&graph, softmax);
+
+        // scale, shift, two layer norms and the softmax shift
+        assert_eq!(AlgebraicSimplifier::run(&mut graph).unwrap(), 5);
+        assert_eq!(AlgebraicSimplifier::run(&mut graph).unwrap(), 0);
+        assert_eq!(graph.all_nodes().count(), 2);
+
+        let after = run_output(&graph, softmax);
+        for (x, y) in before.iter().zip(&after) {
+            assert!((x - y).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_optimize_fuses_tagged_qkv() {
+        let mut graph = ComputeGraph::new();
+        let q = graph.add_node(Operation::MatMul { shape: (2, 3) }, vec![100, 101]);
+        let k = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, 102]);
+        let v = graph.add_node(Operation::MatMul { shape: (2, 2) }, vec![100, 103]);
+        graph.set_role(q, ProjectionRole::Query);
+        graph.set_role(k, ProjectionRole::Key);
+        graph.set_role(v, ProjectionRole::Value);
+        for id in [q, k, v] {
+            graph.set_output(id);
+        }
+
+        let optimized = GraphOptimizer::optimize(&graph).unwrap();
+
+        assert!(
+            optimized
+                .all_nodes()
+                .any(|(_, node)| matches!(node.op, Operation::FusedQKV { .. }))
+        );
+    }
+
+    #[test]
+    fn test_simplifier_leaves_real_math_alone() {
+        let mut graph = ComputeGraph::new();
+        let scale = graph.add_node(Operation::Scale { factor: 0.5 }, vec![100]);
+        let shift = graph.add_node(Operation::AddScalar { value: 1.0 }, vec![scale]);
+        let gelu = graph.add_node(Operation::Gelu, vec![shift]);
+        graph.set_output(gelu);
+
+        assert_eq!(AlgebraicSimplifier::run(&mut graph).unwrap(), 0);
+        assert_eq!(graph.all_nodes().count(), 3);
+    }
 }
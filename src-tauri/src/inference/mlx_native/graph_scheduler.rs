@@ -0,0 +1,118 @@
+use super::compute_graph::{ComputeGraph, Node, NodeId};
+use crate::error::MinervaResult;
+use std::collections::{HashMap, HashSet};
+
+/// Tracks op dependencies and hands out nodes once all their inputs exist
+pub struct DependencyScheduler {
+    pending_inputs: HashMap<NodeId, usize>,
+    dependents: HashMap<NodeId, Vec<NodeId>>,
+    ready: Vec<NodeId>,
+}
+
+impl DependencyScheduler {
+    /// Build the dependency DAG for everything reachable from the graph outputs
+    ///
+    /// Fails if that part of the graph has a cycle.
+    pub fn new(graph: &ComputeGraph, available: &HashSet<NodeId>) -> MinervaResult<Self> {
+        let mut scheduler = DependencyScheduler {
+            pending_inputs: HashMap::new(),
+            dependents: HashMap::new(),
+            ready: Vec::new(),
+        };
+
+        for node_id in graph.execution_order(available)? {
+            if available.contains(&node_id) {
+                continue;
+            }
+            if let Some(node) = graph.get_node(node_id) {
+                scheduler.register(node, available);
+            }
+        }
+
+        Ok(scheduler)
+    }
+
+    fn register(&mut self, node: &Node, available: &HashSet<NodeId>) {
+        let missing: Vec<NodeId> = node
+            .inputs
+            .iter()
+            .copied()
+            .filter(|input_id| !available.contains(input_id))
+            .collect();
+
+        for input_id in &missing {
+            self.dependents.entry(*input_id).or_default().push(node.id);
+        }
+
+        if missing.is_empty() {
+            self.ready.push(node.id);
+        }
+        self.pending_inputs.insert(node.id, missing.len());
+    }
+
+    /// Drain the ready queue; every returned node can run concurrently
+    pub fn take_ready(&mut self) -> Vec<NodeId> {
+        let mut ready = std::mem::take(&mut self.ready);
+        ready.sort();
+        ready
+    }
+
+    /// Mark a node as computed, releasing any dependents that were waiting on it
+    pub fn complete(&mut self, node_id: NodeId) {
+        self.pending_inputs.remove(&node_id);
+
+        for dependent in self.dependents.remove(&node_id).unwrap_or_default() {
+            if let Some(count) = self.pending_inputs.get_mut(&dependent) {
+                *count -= 1;
+                if *count == 0 {
+                    self.ready.push(dependent);
+                }
+            }
+        }
+    }
+
+    /// Nodes that never became runnable (their inputs were never provided)
+    pub fn unfinished(&self) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = self.pending_inputs.keys().copied().collect();
+        nodes.sort();
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::mlx_native::compute_graph::Operation;
+
+    #[test]
+    fn test_independent_nodes_ready_together() {
+        let mut graph = ComputeGraph::new();
+        let n0 = graph.add_node(Operation::Gelu, vec![100]);
+        let n1 = graph.add_node(Operation::Gelu, vec![100]);
+        let n2 = graph.add_node(Operation::Add, vec![n0, n1]);
+        graph.set_output(n2);
+
+        let available = HashSet::from([100]);
+        let mut scheduler = DependencyScheduler::new(&graph, &available).unwrap();
+        assert_eq!(scheduler.take_ready(), vec![n0, n1]);
+
+        scheduler.complete(n0);
+        assert!(scheduler.take_ready().is_empty());
+        scheduler.complete(n1);
+        assert_eq!(scheduler.take_ready(), vec![n2]);
+
+        scheduler.complete(n2);
+        assert!(scheduler.unfinished().is_empty());
+    }
+
+    #[test]
+    fn test_missing_input_never_ready() {
+        let mut graph = ComputeGraph::new();
+        let n0 = graph.add_node(Operation::Gelu, vec![100]);
+        graph.set_output(n0);
+
+        let mut scheduler = DependencyScheduler::new(&graph, &HashSet::new()).unwrap();
+        assert!(scheduler.take_ready().is_empty());
+        assert_eq!(scheduler.unfinished(), vec![n0]);
+    }
+}
@@ -0,0 +1,120 @@
+/// Mixed-Precision KV Cache
+///
+/// Keys drive the softmax and are sensitive to rounding, so they are kept at
+/// FP16. Values are only averaged and tolerate INT8: each 32-element block of
+/// a value row gets a symmetric scale (`max |v| / 127`). Attention reads the
+/// FP16 keys directly and dequantizes values while accumulating, so no FP32
+/// copy of the cache is ever built.
+///
+/// Keys are stored as raw IEEE half bits since stable Rust has no `f16`.
+use crate::inference::gpu::gguf_writer::f32_to_f16;
+use crate::models::gguf_tensor_view::f16_to_f32;
+
+/// KV cache for one attention head with FP16 keys and INT8 values
+#[derive(Clone, Debug, Default)]
+pub struct MixedPrecisionKVCache {
+    keys: Vec<u16>,
+    values: Vec<i8>,
+    value_scales: Vec<f32>,
+    head_dim: usize,
+}
+
+impl MixedPrecisionKVCache {
+    /// Values per INT8 scale block
+    pub const BLOCK_SIZE: usize = 32;
+
+    pub fn new(head_dim: usize) -> Self {
+        Self {
+            head_dim,
+            ..Self::default()
+        }
+    }
+
+    /// Append the key and value of one position
+    pub fn append(&mut self, key: &[f32], value: &[f32]) {
+        assert_eq!(key.len(), self.head_dim, "key length must equal head_dim");
+        assert_eq!(
+            value.len(),
+            self.head_dim,
+            "value length must equal head_dim"
+        );
+
+        self.keys.extend(key.iter().map(|&k| f32_to_f16(k)));
+        for block in value.chunks(Self::BLOCK_SIZE) {
+            let max_abs = block.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+            let scale = if max_abs > 0.0 { max_abs / 127.0 } else { 1.0 };
+            self.value_scales.push(scale);
+            self.values.extend(
+                block
+                    .iter()
+                    .map(|&v| (v / scale).round().clamp(-127.0, 127.0) as i8),
+            );
+        }
+    }
+
+    /// Number of cached positions
+    pub fn len(&self) -> usize {
+        if self.head_dim == 0 {
+            0
+        } else {
+            self.keys.len() / self.head_dim
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Softmax attention of `query` over every cached position
+    pub fn attention(&self, query: &[f32], scale: f32) -> Vec<f32> {
+        assert_eq!(
+            query.len(),
+            self.head_dim,
+            "query length must equal head_dim"
+        );
+        let mut output = vec![0.0; self.head_dim];
+        if self.is_empty() {
+            return output;
+        }
+
+        let scores: Vec<f32> = self
+            .keys
+            .chunks_exact(self.head_dim)
+            .map(|key| {
+                let dot: f32 = key.iter().zip(query).map(|(&k, q)| f16_to_f32(k) * q).sum();
+                dot * scale
+            })
+            .collect();
+        let max = scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let weights: Vec<f32> = scores.iter().map(|s| (s - max).exp()).collect();
+        let total: f32 = weights.iter().sum();
+
+        let blocks_per_row = self.head_dim.div_ceil(Self::BLOCK_SIZE);
+        for (pos, weight) in weights.iter().enumerate() {
+            let row = &self.values[pos * self.head_dim..(pos + 1) * self.head_dim];
+            let scales = &self.value_scales[pos * blocks_per_row..(pos + 1) * blocks_per_row];
+            let p = weight / total;
+            for (d, &q) in row.iter().enumerate() {
+                output[d] += p * q as f32 * scales[d / Self::BLOCK_SIZE];
+            }
+        }
+        output
+    }
+
+    /// Bytes held by keys, values and scales
+    pub fn memory_usage(&self) -> usize {
+        self.keys.len() * 2 + self.values.len() + self.value_scales.len() * 4
+    }
+
+    pub fn keys(&self) -> &[u16] {
+        &self.keys
+    }
+
+    pub fn values(&self) -> &[i8] {
+        &self.values
+    }
+
+    pub fn value_scales(&self) -> &[f32] {
+        &self.value_scales
+    }
+}
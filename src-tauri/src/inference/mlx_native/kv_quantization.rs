@@ -1,6 +1,8 @@
 use super::kv_quantization_helpers::{dequantize_range, quantize_tensor};
 use super::unified_memory::{ArrayShape, MLXArray};
 
+pub use super::kv_mixed_precision::MixedPrecisionKVCache;
+
 /// Quantized KV cache for memory efficiency
 /// Reduces memory usage by 8x (float32 -> uint8)
 /// Typical usage: 71GB → 9GB for 128K context
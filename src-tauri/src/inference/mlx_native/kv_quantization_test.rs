@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::inference::mlx_native::kv_quantization::QuantizedKVCache;
+    use crate::inference::mlx_native::kv_quantization::{MixedPrecisionKVCache, QuantizedKVCache};
     use crate::inference::mlx_native::{ArrayShape, MLXArray};
 
     #[test]
@@ -27,4 +27,63 @@ mod tests {
         let cache = QuantizedKVCache::quantize(&k, &v);
         assert!(cache.compression_ratio() > 3.0);
     }
+
+    /// Deterministic values in roughly [-1, 1]
+    fn pattern(len: usize, seed: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (((i * 37 + seed * 11) % 101) as f32 / 50.0) - 1.0)
+            .collect()
+    }
+
+    fn reference_attention(keys: &[Vec<f32>], values: &[Vec<f32>], query: &[f32]) -> Vec<f32> {
+        let scale = 1.0 / (query.len() as f32).sqrt();
+        let scores: Vec<f32> = keys
+            .iter()
+            .map(|k| k.iter().zip(query).map(|(a, b)| a * b).sum::<f32>() * scale)
+            .collect();
+        let max = scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let weights: Vec<f32> = scores.iter().map(|s| (s - max).exp()).collect();
+        let total: f32 = weights.iter().sum();
+        let mut out = vec![0.0; query.len()];
+        for (w, v) in weights.iter().zip(values) {
+            for (o, x) in out.iter_mut().zip(v) {
+                *o += w / total * x;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_mixed_precision_attention_matches_fp32() {
+        let head_dim = 64;
+        let keys: Vec<Vec<f32>> = (0..16).map(|t| pattern(head_dim, t)).collect();
+        let values: Vec<Vec<f32>> = (0..16).map(|t| pattern(head_dim, t + 100)).collect();
+        let query = pattern(head_dim, 7);
+
+        let mut cache = MixedPrecisionKVCache::new(head_dim);
+        for (k, v) in keys.iter().zip(&values) {
+            cache.append(k, v);
+        }
+        assert_eq!(cache.len(), 16);
+        assert_eq!(cache.value_scales().len(), 16 * 2);
+
+        let expected = reference_attention(&keys, &values, &query);
+        let actual = cache.attention(&query, 1.0 / (head_dim as f32).sqrt());
+        let mae = actual
+            .iter()
+            .zip(&expected)
+            .map(|(a, e)| (a - e).abs())
+            .sum::<f32>()
+            / head_dim as f32;
+        assert!(mae < 0.01, "mean absolute error {}", mae);
+    }
+
+    #[test]
+    fn test_mixed_precision_memory_usage() {
+        let mut cache = MixedPrecisionKVCache::new(40);
+        cache.append(&[0.5; 40], &[0.0; 40]);
+        // 40 FP16 keys + 40 INT8 values + 2 block scales
+        assert_eq!(cache.memory_usage(), 80 + 40 + 8);
+        assert_eq!(cache.attention(&[1.0; 40], 1.0), vec![0.0; 40]);
+    }
 }
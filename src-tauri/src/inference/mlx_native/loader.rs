@@ -1,26 +1,27 @@
-use ndarray::{Array1, Array2};
-use std::collections::HashMap;
+use ndarray::Array1;
 use std::path::Path;
 use std::time::Instant;
 
-use super::config::GPTOSSConfig;
+use super::config::{GPTOSSConfig, MemoryBudget};
+use super::weights::{ComputeDtype, Weight};
 use crate::error::MinervaResult;
 
 #[path = "loader_helpers.rs"]
 mod helpers;
 
-use helpers::{extract_tensor_1d, extract_tensor_2d, load_safetensors_files};
+pub use helpers::{RawTensor, TensorMap};
+use helpers::{extract_tensor_1d, extract_weight, load_safetensors_files};
 
 /// Layer weights for a single transformer layer
 #[derive(Debug, Clone)]
 pub struct MLXLayerWeights {
-    pub attn_q: Array2<f32>,
-    pub attn_k: Array2<f32>,
-    pub attn_v: Array2<f32>,
-    pub attn_out: Array2<f32>,
-    pub mlp_gate: Array2<f32>,
-    pub mlp_up: Array2<f32>,
-    pub mlp_down: Array2<f32>,
+    pub attn_q: Weight,
+    pub attn_k: Weight,
+    pub attn_v: Weight,
+    pub attn_out: Weight,
+    pub mlp_gate: Weight,
+    pub mlp_up: Weight,
+    pub mlp_down: Weight,
     pub norm_attn: Array1<f32>,
     pub norm_mlp: Array1<f32>,
 }
@@ -28,8 +29,8 @@ pub struct MLXLayerWeights {
 /// Complete MLX model with all weights
 #[derive(Debug, Clone)]
 pub struct MLXModel {
-    pub embedding: Array2<f32>,
-    pub lm_head: Array2<f32>,
+    pub embedding: Weight,
+    pub lm_head: Weight,
     pub layers: Vec<MLXLayerWeights>,
     pub norm_final: Array1<f32>,
     pub config: GPTOSSConfig,
@@ -47,19 +48,99 @@ impl MLXModel {
     pub fn vocab_size(&self) -> usize {
         self.config.vocab_size
     }
+
+    /// Load SafeTensors weights, keeping weight matrices as `dtype`
+    pub fn load_with_dtype(path: &Path, dtype: ComputeDtype) -> MinervaResult<Self> {
+        Self::load_with_config(path, read_config(path)?, dtype)
+    }
+
+    fn load_with_config(
+        path: &Path,
+        config: GPTOSSConfig,
+        dtype: ComputeDtype,
+    ) -> MinervaResult<Self> {
+        let start = Instant::now();
+        let tensors = load_safetensors_files(path)?;
+
+        let embedding = extract_weight(&tensors, "model.embed_tokens.weight", dtype)?;
+        let lm_head = extract_weight(&tensors, "lm_head.weight", dtype)?;
+        let norm_final = extract_tensor_1d(&tensors, "model.norm.weight")?;
+
+        let mut layers = Vec::new();
+        for layer_idx in 0..config.num_hidden_layers {
+            layers.push(load_layer(&tensors, layer_idx, dtype)?);
+        }
+
+        let model = MLXModel {
+            embedding,
+            lm_head,
+            layers,
+            norm_final,
+            config,
+        };
+        tracing::info!(
+            "Loaded MLX model as {:?} in {:.2}ms ({} MB)",
+            dtype,
+            start.elapsed().as_secs_f64() * 1000.0,
+            model.memory_bytes() / (1024 * 1024)
+        );
+        Ok(model)
+    }
+
+    /// Bytes held by all weights in their stored dtype
+    pub fn memory_bytes(&self) -> usize {
+        let vector_bytes = |v: &Array1<f32>| v.len() * 4;
+        let layers: usize = self
+            .layers
+            .iter()
+            .map(|layer| {
+                layer
+                    .matrices()
+                    .iter()
+                    .map(|w| w.size_bytes())
+                    .sum::<usize>()
+                    + vector_bytes(&layer.norm_attn)
+                    + vector_bytes(&layer.norm_mlp)
+            })
+            .sum();
+        self.embedding.size_bytes()
+            + self.lm_head.size_bytes()
+            + vector_bytes(&self.norm_final)
+            + layers
+    }
+}
+
+impl MLXLayerWeights {
+    fn matrices(&self) -> [&Weight; 7] {
+        [
+            &self.attn_q,
+            &self.attn_k,
+            &self.attn_v,
+            &self.attn_out,
+            &self.mlp_gate,
+            &self.mlp_up,
+            &self.mlp_down,
+        ]
+    }
 }
 
 /// Load a single layer's weights
-fn load_layer(tensors: &HashMap<String, Vec<u8>>, idx: usize) -> MinervaResult<MLXLayerWeights> {
+fn load_layer(
+    tensors: &TensorMap,
+    idx: usize,
+    dtype: ComputeDtype,
+) -> MinervaResult<MLXLayerWeights> {
     let prefix = format!("model.layers.{}", idx);
+    let weight =
+        |name: &str| extract_weight(tensors, &format!("{}.{}.weight", prefix, name), dtype);
     Ok(MLXLayerWeights {
-        attn_q: extract_tensor_2d(tensors, &format!("{}.self_attn.q_proj.weight", prefix))?,
-        attn_k: extract_tensor_2d(tensors, &format!("{}.self_attn.k_proj.weight", prefix))?,
-        attn_v: extract_tensor_2d(tensors, &format!("{}.self_attn.v_proj.weight", prefix))?,
-        attn_out: extract_tensor_2d(tensors, &format!("{}.self_attn.o_proj.weight", prefix))?,
-        mlp_gate: extract_tensor_2d(tensors, &format!("{}.mlp.gate_proj.weight", prefix))?,
-        mlp_up: extract_tensor_2d(tensors, &format!("{}.mlp.up_proj.weight", prefix))?,
-        mlp_down: extract_tensor_2d(tensors, &format!("{}.mlp.down_proj.weight", prefix))?,
+        attn_q: weight("self_attn.q_proj")?,
+        attn_k: weight("self_attn.k_proj")?,
+        attn_v: weight("self_attn.v_proj")?,
+        attn_out: weight("self_attn.o_proj")?,
+        mlp_gate: weight("mlp.gate_proj")?,
+        mlp_up: weight("mlp.up_proj")?,
+        mlp_down: weight("mlp.down_proj")?,
         norm_attn: extract_tensor_1d(tensors, &format!("{}.input_layernorm.weight", prefix))?,
         norm_mlp: extract_tensor_1d(
             tensors,
@@ -69,32 +150,27 @@ fn load_layer(tensors: &HashMap<String, Vec<u8>>, idx: usize) -> MinervaResult<M
 }
 
 /// Load MLX model from SafeTensors files
+///
+/// For a model directory, `config.json` (when present) sets the layer count,
+/// dimensions and weight dtype (see [`GPTOSSConfig::compute_dtype`]);
+/// otherwise the GPT-OSS 20B defaults and f32 weights apply. A model that
+/// will not fit this machine's memory is logged, not refused.
 pub fn load_mlx_model(path: &Path) -> MinervaResult<MLXModel> {
-    let start = Instant::now();
-    let tensors = load_safetensors_files(path)?;
-
-    let embedding = extract_tensor_2d(&tensors, "model.embed_tokens.weight")?;
-    let lm_head = extract_tensor_2d(&tensors, "lm_head.weight")?;
-    let norm_final = extract_tensor_1d(&tensors, "model.norm.weight")?;
-
-    let mut layers = Vec::new();
-    for layer_idx in 0..24 {
-        layers.push(load_layer(&tensors, layer_idx)?);
+    let config = read_config(path)?;
+    for warning in config.validate_against_hardware(&MemoryBudget::detect()) {
+        tracing::warn!("{}: {}", path.display(), warning);
     }
+    let dtype = config.compute_dtype();
+    MLXModel::load_with_config(path, config, dtype)
+}
 
-    let elapsed = start.elapsed();
-    eprintln!(
-        "Loaded MLX model in {:.2}ms",
-        elapsed.as_secs_f64() * 1000.0
-    );
-
-    Ok(MLXModel {
-        embedding,
-        lm_head,
-        layers,
-        norm_final,
-        config: GPTOSSConfig::default(),
-    })
+fn read_config(path: &Path) -> MinervaResult<GPTOSSConfig> {
+    let config_path = path.join("config.json");
+    if !config_path.is_file() {
+        return Ok(GPTOSSConfig::default());
+    }
+    let json = std::fs::read_to_string(&config_path)?;
+    Ok(serde_json::from_str(&json)?)
 }
 
 #[cfg(test)]
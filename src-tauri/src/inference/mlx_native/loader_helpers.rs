@@ -1,12 +1,25 @@
+use super::super::weights::{ComputeDtype, Weight};
 use crate::error::{MinervaError, MinervaResult};
 /// Helper functions for GGUF tensor extraction
+use half::{bf16, f16};
 use ndarray::{Array1, Array2};
-use safetensors::SafeTensors;
+use safetensors::{Dtype, SafeTensors};
 use std::collections::HashMap;
 use std::path::Path;
 
+/// Raw little-endian tensor bytes with the shape recorded in the file header
+#[derive(Debug, Clone)]
+pub struct RawTensor {
+    pub dtype: Dtype,
+    pub shape: Vec<usize>,
+    pub data: Vec<u8>,
+}
+
+/// Tensors by name, as read from one or more SafeTensors files
+pub type TensorMap = HashMap<String, RawTensor>;
+
 /// Load all SafeTensors files from a directory
-pub fn load_safetensors_files(path: &Path) -> MinervaResult<HashMap<String, Vec<u8>>> {
+pub fn load_safetensors_files(path: &Path) -> MinervaResult<TensorMap> {
     // First check if this is a single safetensors file
     if path.extension().map_or(false, |ext| ext == "safetensors") {
         return load_single_safetensors(path);
@@ -22,20 +35,20 @@ pub fn load_safetensors_files(path: &Path) -> MinervaResult<HashMap<String, Vec<
             return load_single_safetensors(&single_path);
         }
 
-        return Err(MinervaError::ModelLoadingError(format!(
+        return Err(MinervaError::model_loading(format!(
             "No model.safetensors.index.json or model.safetensors found at {:?}",
             path
         )));
     }
 
     let index_content = std::fs::read_to_string(&index_path)
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to read index: {}", e)))?;
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read index: {}", e)))?;
 
     let index: serde_json::Value = serde_json::from_str(&index_content)?;
 
     let weights_map = index["weight_map"]
         .as_object()
-        .ok_or_else(|| MinervaError::ModelLoadingError("Invalid index.json format".into()))?;
+        .ok_or_else(|| MinervaError::model_loading("Invalid index.json format"))?;
 
     // Find unique shard files
     let mut shard_files = std::collections::HashSet::new();
@@ -46,7 +59,7 @@ pub fn load_safetensors_files(path: &Path) -> MinervaResult<HashMap<String, Vec<
     }
 
     // Load all shards
-    let mut all_tensors: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut all_tensors: TensorMap = HashMap::new();
 
     for shard_file in shard_files {
         let shard_path = path.join(&shard_file);
@@ -58,19 +71,25 @@ pub fn load_safetensors_files(path: &Path) -> MinervaResult<HashMap<String, Vec<
 }
 
 /// Load a single SafeTensors file
-pub fn load_single_safetensors(path: &Path) -> MinervaResult<HashMap<String, Vec<u8>>> {
+pub fn load_single_safetensors(path: &Path) -> MinervaResult<TensorMap> {
     let bytes = std::fs::read(path).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read {}: {}", path.display(), e))
+        MinervaError::model_loading_with(format!("Failed to read {}: {}", path.display(), e), e)
     })?;
 
-    let st = SafeTensors::deserialize(&bytes)
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to deserialize: {}", e)))?;
+    let st = SafeTensors::deserialize(&bytes).map_err(|e| {
+        MinervaError::model_loading_with(format!("Failed to deserialize: {}", e), e)
+    })?;
 
     let mut result = HashMap::new();
     for tensor_name in st.names() {
         match st.tensor(tensor_name) {
             Ok(tensor) => {
-                result.insert(tensor_name.to_string(), tensor.data().to_vec());
+                let raw = RawTensor {
+                    dtype: tensor.dtype(),
+                    shape: tensor.shape().to_vec(),
+                    data: tensor.data().to_vec(),
+                };
+                result.insert(tensor_name.to_string(), raw);
             }
             Err(e) => {
                 eprintln!("Warning: Failed to load tensor {}: {}", tensor_name, e);
@@ -81,70 +100,111 @@ pub fn load_single_safetensors(path: &Path) -> MinervaResult<HashMap<String, Vec
     Ok(result)
 }
 
-/// Extract a 2D tensor from the tensor map
-pub fn extract_tensor_2d(
-    tensors: &HashMap<String, Vec<u8>>,
+/// Extract a 2D tensor from the tensor map, stored as `dtype`
+///
+/// F16 and BF16 tensors loaded in their own dtype are copied bit for bit;
+/// every other combination goes through f32.
+pub fn extract_weight(
+    tensors: &TensorMap,
     name: &str,
-) -> MinervaResult<Array2<f32>> {
-    let data = tensors
+    dtype: ComputeDtype,
+) -> MinervaResult<Weight> {
+    let tensor = get_tensor(tensors, name)?;
+    let shape = matrix_shape(tensor)?;
+    Ok(match (dtype, tensor.dtype) {
+        (ComputeDtype::F16, Dtype::F16) => Weight::F16(to_array(
+            shape,
+            half_bits(&tensor.data).map(f16::from_bits),
+        )?),
+        (ComputeDtype::BF16, Dtype::BF16) => Weight::BF16(to_array(
+            shape,
+            half_bits(&tensor.data).map(bf16::from_bits),
+        )?),
+        _ => Weight::from_f32(to_array(shape, decode_f32(tensor)?)?, dtype),
+    })
+}
+
+/// Extract a 1D tensor from the tensor map
+pub fn extract_tensor_1d(tensors: &TensorMap, name: &str) -> MinervaResult<Array1<f32>> {
+    let tensor = get_tensor(tensors, name)?;
+    Ok(Array1::from_vec(decode_f32(tensor)?))
+}
+
+fn get_tensor<'a>(tensors: &'a TensorMap, name: &str) -> MinervaResult<&'a RawTensor> {
+    tensors
         .get(name)
-        .ok_or_else(|| MinervaError::ModelLoadingError(format!("Missing tensor: {}", name)))?;
-
-    let floats: Vec<f32> = data
-        .chunks_exact(4)
-        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-        .collect();
-
-    let size = floats.len();
-
-    // Common shapes for GPT-OSS 20B
-    let shape = if size == 201088 * 2880 {
-        Some((201088, 2880))
-    } else if size == 2880 * 2880 {
-        Some((2880, 2880))
-    } else if size == 2880 * 7168 {
-        Some((2880, 7168))
-    } else if size == 7168 * 2880 {
-        Some((7168, 2880))
-    } else if size == 2880 * 768 {
-        Some((2880, 768))
-    } else {
-        let sqrt = (size as f64).sqrt() as usize;
-        if sqrt * sqrt == size {
-            Some((sqrt, sqrt))
-        } else {
-            for i in (1..=1000).rev() {
-                if size % i == 0 {
-                    return Array2::from_shape_vec((size / i, i), floats).map_err(|e| {
-                        MinervaError::ModelLoadingError(format!("Failed to create array: {}", e))
-                    });
-                }
-            }
-            None
-        }
-    };
+        .ok_or_else(|| MinervaError::model_loading(format!("Missing tensor: {}", name)))
+}
 
-    let final_shape = shape.ok_or_else(|| {
-        MinervaError::ModelLoadingError("Failed to determine tensor shape".into())
-    })?;
+fn matrix_shape(tensor: &RawTensor) -> MinervaResult<(usize, usize)> {
+    let size = tensor.data.len() / element_size(tensor.dtype)?;
+    if let [rows, cols] = tensor.shape[..]
+        && rows * cols == size
+    {
+        return Ok((rows, cols));
+    }
 
-    Array2::from_shape_vec(final_shape, floats)
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to create array: {}", e)))
+    // Shape missing from the header: guess from common GPT-OSS 20B shapes
+    let known = [
+        (201088, 2880),
+        (2880, 2880),
+        (2880, 7168),
+        (7168, 2880),
+        (2880, 768),
+    ];
+    if let Some(&shape) = known.iter().find(|(rows, cols)| rows * cols == size) {
+        return Ok(shape);
+    }
+    let sqrt = (size as f64).sqrt() as usize;
+    if sqrt * sqrt == size {
+        return Ok((sqrt, sqrt));
+    }
+    (1..=1000)
+        .rev()
+        .find(|i| size % i == 0)
+        .map(|i| (size / i, i))
+        .ok_or_else(|| MinervaError::model_loading("Failed to determine tensor shape"))
 }
 
-/// Extract a 1D tensor from the tensor map
-pub fn extract_tensor_1d(
-    tensors: &HashMap<String, Vec<u8>>,
-    name: &str,
-) -> MinervaResult<Array1<f32>> {
-    let data = tensors
-        .get(name)
-        .ok_or_else(|| MinervaError::ModelLoadingError(format!("Missing tensor: {}", name)))?;
+fn to_array<T>(
+    shape: (usize, usize),
+    values: impl IntoIterator<Item = T>,
+) -> MinervaResult<Array2<T>> {
+    Array2::from_shape_vec(shape, values.into_iter().collect())
+        .map_err(|e| MinervaError::model_loading(format!("Failed to create array: {}", e)))
+}
 
-    let floats: Vec<f32> = data
-        .chunks_exact(4)
-        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-        .collect();
+fn element_size(dtype: Dtype) -> MinervaResult<usize> {
+    match dtype {
+        Dtype::F32 => Ok(4),
+        Dtype::F16 | Dtype::BF16 => Ok(2),
+        other => Err(unsupported(other)),
+    }
+}
+
+fn unsupported(dtype: Dtype) -> MinervaError {
+    MinervaError::UnsupportedFormat(format!("Cannot load {:?} tensors", dtype))
+}
+
+fn half_bits(data: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    data.chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+}
 
-    Ok(Array1::from_vec(floats))
+/// Widen a tensor's elements to f32 according to its stored dtype
+fn decode_f32(tensor: &RawTensor) -> MinervaResult<Vec<f32>> {
+    Ok(match tensor.dtype {
+        Dtype::F32 => tensor
+            .data
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect(),
+        Dtype::F16 => half_bits(&tensor.data)
+            .map(|bits| f16::from_bits(bits).to_f32())
+            .collect(),
+        Dtype::BF16 => half_bits(&tensor.data)
+            .map(|bits| bf16::from_bits(bits).to_f32())
+            .collect(),
+        other => return Err(unsupported(other)),
+    })
 }
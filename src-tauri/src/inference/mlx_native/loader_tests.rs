@@ -2,6 +2,86 @@
 mod tests {
     use crate::inference::mlx_native::config::GPTOSSConfig;
     use crate::inference::mlx_native::loader::*;
+    use crate::inference::mlx_native::weights::ComputeDtype;
+    use half::bf16;
+    use safetensors::{Dtype, tensor::TensorView};
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    /// Zero-layer model with BF16 embedding/lm_head and an F32 norm
+    fn write_bf16_model(dir: &Path) {
+        let matrix: Vec<u8> = (0..8)
+            .flat_map(|i| bf16::from_f32(i as f32 * 0.5).to_le_bytes())
+            .collect();
+        let norm: Vec<u8> = [1.0f32; 2].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let tensors = HashMap::from([
+            (
+                "model.embed_tokens.weight".to_string(),
+                TensorView::new(Dtype::BF16, vec![4, 2], &matrix).unwrap(),
+            ),
+            (
+                "lm_head.weight".to_string(),
+                TensorView::new(Dtype::BF16, vec![4, 2], &matrix).unwrap(),
+            ),
+            (
+                "model.norm.weight".to_string(),
+                TensorView::new(Dtype::F32, vec![2], &norm).unwrap(),
+            ),
+        ]);
+        let bytes = safetensors::serialize(&tensors, &None).unwrap();
+        std::fs::write(dir.join("model.safetensors"), bytes).unwrap();
+        std::fs::write(
+            dir.join("config.json"),
+            r#"{"vocab_size": 4, "hidden_size": 2, "num_hidden_layers": 0}"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_load_with_dtype_keeps_16_bit_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bf16_model(dir.path());
+
+        let f32_model = MLXModel::load_with_dtype(dir.path(), ComputeDtype::F32).unwrap();
+        let bf16_model = MLXModel::load_with_dtype(dir.path(), ComputeDtype::BF16).unwrap();
+        let f16_model = MLXModel::load_with_dtype(dir.path(), ComputeDtype::F16).unwrap();
+
+        assert_eq!(bf16_model.embedding.dtype(), ComputeDtype::BF16);
+        assert_eq!(bf16_model.embedding.shape(), &[4, 2]);
+        // Two 4x2 matrices plus the 2-element f32 norm
+        assert_eq!(f32_model.memory_bytes(), 2 * 8 * 4 + 8);
+        assert_eq!(bf16_model.memory_bytes(), 2 * 8 * 2 + 8);
+        assert_eq!(f16_model.memory_bytes(), bf16_model.memory_bytes());
+
+        let expected = f32_model.next_token_logits(&[3]).unwrap();
+        for model in [&bf16_model, &f16_model] {
+            let logits = model.next_token_logits(&[3]).unwrap();
+            for (a, e) in logits.iter().zip(&expected) {
+                assert!((a - e).abs() < 0.05, "{} vs {}", a, e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_mlx_model_uses_checkpoint_dtype() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bf16_model(dir.path());
+        assert_eq!(
+            load_mlx_model(dir.path()).unwrap().embedding.dtype(),
+            ComputeDtype::F32
+        );
+
+        std::fs::write(
+            dir.path().join("config.json"),
+            r#"{"vocab_size": 4, "hidden_size": 2, "num_hidden_layers": 0,
+                "torch_dtype": "bfloat16"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            load_mlx_model(dir.path()).unwrap().embedding.dtype(),
+            ComputeDtype::BF16
+        );
+    }
 
     #[test]
     fn test_config_extraction() {
@@ -0,0 +1,205 @@
+/// Memory Pressure Monitoring
+///
+/// On macOS, subscribes to libdispatch memory pressure events so loaded models
+/// can be dropped before jetsam kills the process. Other platforms have no
+/// equivalent notification: registration succeeds and the callback never fires.
+use crate::error::MinervaResult;
+
+/// `DISPATCH_MEMORYPRESSURE_WARN`
+const PRESSURE_WARN: usize = 0x02;
+/// `DISPATCH_MEMORYPRESSURE_CRITICAL`
+const PRESSURE_CRITICAL: usize = 0x04;
+
+/// System memory pressure as reported by the OS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryPressureLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl MemoryPressureLevel {
+    /// Decode a `DISPATCH_MEMORYPRESSURE_*` event mask, most severe level first
+    pub fn from_dispatch_flags(flags: usize) -> Self {
+        if flags & PRESSURE_CRITICAL != 0 {
+            Self::Critical
+        } else if flags & PRESSURE_WARN != 0 {
+            Self::Warning
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// Handler invoked on a dispatch queue whenever the pressure level changes
+pub type PressureCallback = Box<dyn Fn(MemoryPressureLevel) + Send + Sync>;
+
+/// Owns the registered pressure handlers; dropping it unregisters them
+#[derive(Default)]
+pub struct UnifiedMemoryMonitor {
+    sources: Vec<PressureSource>,
+}
+
+impl UnifiedMemoryMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `callback` for memory pressure notifications
+    pub fn register_pressure_callback(&mut self, callback: PressureCallback) -> MinervaResult<()> {
+        self.sources.push(PressureSource::register(callback)?);
+        Ok(())
+    }
+
+    /// Number of registered callbacks
+    pub fn callback_count(&self) -> usize {
+        self.sources.len()
+    }
+}
+
+#[cfg(target_os = "macos")]
+use macos::PressureSource;
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::{MemoryPressureLevel, PRESSURE_CRITICAL, PRESSURE_WARN, PressureCallback};
+    use crate::error::{MinervaError, MinervaResult};
+    use std::ffi::c_void;
+
+    /// `DISPATCH_MEMORYPRESSURE_NORMAL`
+    const PRESSURE_NORMAL: usize = 0x01;
+
+    #[repr(C)]
+    struct DispatchSourceType {
+        _private: [u8; 0],
+    }
+
+    type DispatchObject = *mut c_void;
+
+    // libdispatch ships in libSystem, which every macOS binary links.
+    unsafe extern "C" {
+        static _dispatch_source_type_memorypressure: DispatchSourceType;
+        fn dispatch_get_global_queue(identifier: isize, flags: usize) -> DispatchObject;
+        fn dispatch_source_create(
+            kind: *const DispatchSourceType,
+            handle: usize,
+            mask: usize,
+            queue: DispatchObject,
+        ) -> DispatchObject;
+        fn dispatch_set_context(object: DispatchObject, context: *mut c_void);
+        fn dispatch_set_finalizer_f(object: DispatchObject, finalizer: extern "C" fn(*mut c_void));
+        fn dispatch_source_set_event_handler_f(
+            source: DispatchObject,
+            handler: extern "C" fn(*mut c_void),
+        );
+        fn dispatch_source_get_data(source: DispatchObject) -> usize;
+        fn dispatch_source_cancel(source: DispatchObject);
+        fn dispatch_resume(object: DispatchObject);
+        fn dispatch_release(object: DispatchObject);
+    }
+
+    /// Context handed to libdispatch; freed by the source's finalizer
+    struct HandlerContext {
+        source: DispatchObject,
+        callback: PressureCallback,
+    }
+
+    extern "C" fn on_event(context: *mut c_void) {
+        // SAFETY: `context` is the HandlerContext installed in `register`,
+        // which lives until the finalizer runs after the source is cancelled.
+        let context = unsafe { &*(context as *const HandlerContext) };
+        let flags = unsafe { dispatch_source_get_data(context.source) };
+        (context.callback)(MemoryPressureLevel::from_dispatch_flags(flags));
+    }
+
+    extern "C" fn finalize(context: *mut c_void) {
+        // SAFETY: called exactly once, after the last event handler invocation.
+        drop(unsafe { Box::from_raw(context as *mut HandlerContext) });
+    }
+
+    /// A resumed `DISPATCH_SOURCE_TYPE_MEMORYPRESSURE` source
+    pub(super) struct PressureSource(DispatchObject);
+
+    // SAFETY: dispatch objects are thread-safe and the callback is Send + Sync.
+    unsafe impl Send for PressureSource {}
+    unsafe impl Sync for PressureSource {}
+
+    impl PressureSource {
+        pub(super) fn register(callback: PressureCallback) -> MinervaResult<Self> {
+            let mask = PRESSURE_NORMAL | PRESSURE_WARN | PRESSURE_CRITICAL;
+            // SAFETY: the source is checked for null before use, and the
+            // context outlives it via the finalizer.
+            unsafe {
+                let queue = dispatch_get_global_queue(0, 0);
+                let kind = &raw const _dispatch_source_type_memorypressure;
+                let source = dispatch_source_create(kind, 0, mask, queue);
+                if source.is_null() {
+                    return Err(MinervaError::InferenceError(
+                        "Failed to create memory pressure source".to_string(),
+                    ));
+                }
+                let context = Box::into_raw(Box::new(HandlerContext { source, callback }));
+                dispatch_set_context(source, context as *mut c_void);
+                dispatch_set_finalizer_f(source, finalize);
+                dispatch_source_set_event_handler_f(source, on_event);
+                dispatch_resume(source);
+                Ok(Self(source))
+            }
+        }
+    }
+
+    impl Drop for PressureSource {
+        fn drop(&mut self) {
+            // SAFETY: `self.0` is a live source created in `register`.
+            unsafe {
+                dispatch_source_cancel(self.0);
+                dispatch_release(self.0);
+            }
+        }
+    }
+}
+
+/// No pressure notifications outside macOS; the callback is kept but never called
+#[cfg(not(target_os = "macos"))]
+struct PressureSource {
+    _callback: PressureCallback,
+}
+
+#[cfg(not(target_os = "macos"))]
+impl PressureSource {
+    fn register(callback: PressureCallback) -> MinervaResult<Self> {
+        Ok(Self {
+            _callback: callback,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_dispatch_flags() {
+        assert_eq!(
+            MemoryPressureLevel::from_dispatch_flags(0x01),
+            MemoryPressureLevel::Normal
+        );
+        assert_eq!(
+            MemoryPressureLevel::from_dispatch_flags(PRESSURE_WARN),
+            MemoryPressureLevel::Warning
+        );
+        assert_eq!(
+            MemoryPressureLevel::from_dispatch_flags(PRESSURE_WARN | PRESSURE_CRITICAL),
+            MemoryPressureLevel::Critical
+        );
+    }
+
+    #[test]
+    fn test_register_callback() {
+        let mut monitor = UnifiedMemoryMonitor::new();
+        monitor
+            .register_pressure_callback(Box::new(|_| {}))
+            .unwrap();
+        assert_eq!(monitor.callback_count(), 1);
+    }
+}
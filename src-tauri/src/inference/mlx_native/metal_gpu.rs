@@ -1,4 +1,9 @@
 use super::metal_stubs::*;
+use super::shader_cache::ShaderCache;
+use crate::error::{MinervaError, MinervaResult};
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Metal GPU device abstraction for Apple Silicon
 pub struct MetalGPU {
@@ -7,6 +12,12 @@ pub struct MetalGPU {
     library: *mut std::ffi::c_void,
 }
 
+// SAFETY: MTLDevice and MTLCommandQueue are thread-safe, so buffers may be
+// created, filled and encoded against from any thread; the handles are
+// released once, in `drop`.
+unsafe impl Send for MetalGPU {}
+unsafe impl Sync for MetalGPU {}
+
 impl MetalGPU {
     /// Create new Metal GPU device
     pub fn new() -> Result<Self, String> {
@@ -45,6 +56,15 @@ impl MetalGPU {
         }
     }
 
+    /// Allocate a GPU buffer that is released when the handle drops
+    pub fn allocate(gpu: &Arc<Self>, size: usize) -> Result<MetalBuffer, String> {
+        Ok(MetalBuffer {
+            raw: gpu.create_buffer(size)?,
+            size,
+            gpu: Arc::clone(gpu),
+        })
+    }
+
     /// Release GPU buffer
     pub fn release_buffer(&self, buffer: *mut std::ffi::c_void) {
         unsafe {
@@ -68,6 +88,22 @@ impl MetalGPU {
         }
     }
 
+    /// Copy indices (such as token ids) to GPU buffer
+    pub fn copy_indices_to_gpu(
+        &self,
+        gpu_buffer: *mut std::ffi::c_void,
+        indices: &[u32],
+    ) -> Result<(), String> {
+        unsafe {
+            metal_copy_to_gpu(
+                gpu_buffer,
+                indices.as_ptr() as *const std::ffi::c_void,
+                indices.len() * 4,
+            );
+            Ok(())
+        }
+    }
+
     /// Copy data from GPU buffer
     pub fn copy_from_gpu(
         &self,
@@ -126,10 +162,71 @@ impl MetalGPU {
         self.library
     }
 
+    /// Compile MSL `source`, reusing a `.metallib` cached in `cache_dir`
+    ///
+    /// On a miss the library is compiled and written to the cache; failing
+    /// to write it only costs a recompile next time. Libraries built by a
+    /// different Metal compiler version are discarded. Stub bindings cannot
+    /// compile anything, so they are refused.
+    pub fn compile_shader_cached(
+        &self,
+        source: &str,
+        cache_dir: &Path,
+    ) -> MinervaResult<CompiledShader> {
+        Self::require_real_bindings("Shader compilation").map_err(MinervaError::InferenceError)?;
+        let version = unsafe { CStr::from_ptr(metal_compiler_version()) };
+        let cache = ShaderCache::open(cache_dir, &version.to_string_lossy())?;
+        let path = cache.library_path(source);
+        let c_path = path_to_cstring(&path)?;
+
+        if cache.lookup(source).is_some() {
+            let library = unsafe { metal_load_library_file(self.device, c_path.as_ptr()) };
+            if !library.is_null() {
+                return Ok(CompiledShader {
+                    library,
+                    path,
+                    from_cache: true,
+                });
+            }
+            tracing::warn!("Unreadable cached shader {}, recompiling", path.display());
+        }
+
+        let c_source = CString::new(source)
+            .map_err(|_| MinervaError::InvalidRequest("Shader source contains NUL".to_string()))?;
+        let library = unsafe { metal_compile_library_source(self.device, c_source.as_ptr()) };
+        if library.is_null() {
+            return Err(MinervaError::InferenceError(
+                "Metal shader compilation failed".to_string(),
+            ));
+        }
+        if !unsafe { metal_serialize_library(library, c_path.as_ptr()) } {
+            tracing::warn!("Failed to cache compiled shader at {}", path.display());
+        }
+        Ok(CompiledShader {
+            library,
+            path,
+            from_cache: false,
+        })
+    }
+
     /// Check if Metal is available on this system
     pub fn is_available() -> bool {
         unsafe { metal_is_available() }
     }
+
+    /// Refuse `what` when only the stub bindings are linked in
+    ///
+    /// The stubs hand out fake handles and never move data, so kernel
+    /// results read back from them are zeros rather than an error.
+    pub fn require_real_bindings(what: &str) -> Result<(), String> {
+        if METAL_STUBBED {
+            return Err(format!(
+                "{} needs Metal bindings, but this build only has stubs",
+                what
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl Drop for MetalGPU {
@@ -147,3 +244,77 @@ impl Drop for MetalGPU {
         }
     }
 }
+
+/// A buffer owned by one handle, released on drop
+///
+/// Holds its `MetalGPU` so the device outlives every buffer made on it.
+pub struct MetalBuffer {
+    raw: *mut std::ffi::c_void,
+    size: usize,
+    gpu: Arc<MetalGPU>,
+}
+
+// SAFETY: Metal buffers and devices may be used from any thread, and the
+// buffer is released exactly once, in `drop`.
+unsafe impl Send for MetalBuffer {}
+unsafe impl Sync for MetalBuffer {}
+
+impl MetalBuffer {
+    /// Metal buffer handle for binding to kernels
+    pub fn raw(&self) -> *mut std::ffi::c_void {
+        self.raw
+    }
+
+    /// Size in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Device the buffer lives on
+    pub fn gpu(&self) -> &Arc<MetalGPU> {
+        &self.gpu
+    }
+}
+
+impl Drop for MetalBuffer {
+    fn drop(&mut self) {
+        self.gpu.release_buffer(self.raw);
+    }
+}
+
+/// A compiled Metal library, released on drop
+#[derive(Debug)]
+pub struct CompiledShader {
+    library: *mut std::ffi::c_void,
+    path: PathBuf,
+    from_cache: bool,
+}
+
+impl CompiledShader {
+    /// Metal library handle for creating functions
+    pub fn library(&self) -> *mut std::ffi::c_void {
+        self.library
+    }
+
+    /// Cache file backing this library
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the library was loaded from the cache instead of compiled
+    pub fn from_cache(&self) -> bool {
+        self.from_cache
+    }
+}
+
+impl Drop for CompiledShader {
+    fn drop(&mut self) {
+        unsafe { metal_release_library(self.library) }
+    }
+}
+
+fn path_to_cstring(path: &Path) -> MinervaResult<CString> {
+    CString::new(path.to_string_lossy().as_bytes()).map_err(|_| {
+        MinervaError::InvalidRequest(format!("Invalid shader cache path: {}", path.display()))
+    })
+}
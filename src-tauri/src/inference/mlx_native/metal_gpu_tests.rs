@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::inference::mlx_native::metal_gpu::MetalGPU;
+    use crate::inference::mlx_native::metal_stubs::METAL_STUBBED;
 
     #[test]
     fn test_metal_availability() {
@@ -36,4 +37,22 @@ mod tests {
         let cmd_buffer = gpu.create_command_buffer();
         assert!(cmd_buffer.is_ok(), "Failed to create command buffer");
     }
+
+    #[test]
+    fn test_compile_shader_cached_reuses_metallib() {
+        if !MetalGPU::is_available() || METAL_STUBBED {
+            return;
+        }
+        let gpu = MetalGPU::new().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let source = "kernel void noop() {}";
+
+        let first = gpu.compile_shader_cached(source, dir.path()).unwrap();
+        assert!(!first.from_cache());
+        assert!(first.path().exists());
+
+        let second = gpu.compile_shader_cached(source, dir.path()).unwrap();
+        assert!(second.from_cache());
+        assert_eq!(first.path(), second.path());
+    }
 }
@@ -1,5 +1,22 @@
-use super::metal_gpu::MetalGPU;
+use super::metal_gpu::{CompiledShader, MetalGPU};
 use super::metal_stubs::*;
+use crate::error::{MinervaError, MinervaResult};
+use std::path::Path;
+
+/// MSL source of `rms_norm_kernel`
+pub const RMS_NORM_SOURCE: &str = include_str!("rms_norm.metal");
+
+/// Rows per threadgroup in `rms_norm_kernel`, one simdgroup each
+pub const RMS_NORM_ROWS_PER_THREADGROUP: u32 = 8;
+
+/// SIMD width of Apple GPUs
+pub const SIMD_WIDTH: u32 = 32;
+
+/// MSL source of `embed_position_kernel`
+pub const EMBED_POSITION_SOURCE: &str = include_str!("embed_position.metal");
+
+/// Threads per threadgroup in `embed_position_kernel`
+pub const EMBED_POSITION_THREADS_PER_GROUP: u32 = 256;
 
 /// Metal kernel operations
 pub struct MetalKernels;
@@ -27,14 +44,29 @@ impl MetalKernels {
         m: u32,
         n: u32,
         k: u32,
+    ) -> Result<(), String> {
+        let cmd_buffer = gpu.create_command_buffer()?;
+        Self::encode_matmul(gpu, cmd_buffer, a, b, c, m, n, k)?;
+        gpu.submit_commands(cmd_buffer)
+    }
+
+    /// Encode the matmul kernel into `cmd_buffer` without committing it
+    #[allow(clippy::too_many_arguments)]
+    pub fn encode_matmul(
+        gpu: &MetalGPU,
+        cmd_buffer: *mut std::ffi::c_void,
+        a: *mut std::ffi::c_void,
+        b: *mut std::ffi::c_void,
+        c: *mut std::ffi::c_void,
+        m: u32,
+        n: u32,
+        k: u32,
     ) -> Result<(), String> {
         unsafe {
             let func = Self::get_function(gpu, "matmul_kernel")?;
-            let cmd_buffer = gpu.create_command_buffer()?;
             metal_dispatch_matmul(cmd_buffer, func, a, b, c, m, n, k);
-            gpu.submit_commands(cmd_buffer)?;
-            Ok(())
         }
+        Ok(())
     }
 
     /// Execute add kernel
@@ -80,14 +112,194 @@ impl MetalKernels {
         m: u32,
         n: u32,
         k: u32,
+    ) -> Result<(), String> {
+        let cmd_buffer = gpu.create_command_buffer()?;
+        Self::encode_fused_matmul_add_gelu(gpu, cmd_buffer, a, b, bias, c, m, n, k)?;
+        gpu.submit_commands(cmd_buffer)
+    }
+
+    /// Encode the fused matmul+add+gelu kernel into `cmd_buffer` without
+    /// committing it
+    #[allow(clippy::too_many_arguments)]
+    pub fn encode_fused_matmul_add_gelu(
+        gpu: &MetalGPU,
+        cmd_buffer: *mut std::ffi::c_void,
+        a: *mut std::ffi::c_void,
+        b: *mut std::ffi::c_void,
+        bias: *mut std::ffi::c_void,
+        c: *mut std::ffi::c_void,
+        m: u32,
+        n: u32,
+        k: u32,
     ) -> Result<(), String> {
         unsafe {
             let func = Self::get_function(gpu, "fused_matmul_add_gelu_kernel")?;
-            let cmd_buffer = gpu.create_command_buffer()?;
             metal_dispatch_fused_matmul_add_gelu(cmd_buffer, func, a, b, bias, c, m, n, k);
-            gpu.submit_commands(cmd_buffer)?;
-            Ok(())
         }
+        Ok(())
+    }
+}
+
+/// RMSNorm over the rows of a `(seq_len, hidden_size)` matrix on the GPU
+///
+/// The shader is compiled at runtime through the shader cache, so only the
+/// first run pays for compilation. Each threadgroup normalises
+/// `RMS_NORM_ROWS_PER_THREADGROUP` rows with one simdgroup per row.
+pub struct RmsNormKernel {
+    pipeline: *mut std::ffi::c_void,
+    // Keeps the library alive for as long as the pipeline uses it
+    _shader: CompiledShader,
+}
+
+impl RmsNormKernel {
+    /// Compile `rms_norm.metal` (or load it from `cache_dir`) and build its pipeline
+    pub fn new(gpu: &MetalGPU, cache_dir: &Path) -> MinervaResult<Self> {
+        let shader = gpu.compile_shader_cached(RMS_NORM_SOURCE, cache_dir)?;
+        let name = c"rms_norm_kernel";
+        let func = unsafe { metal_create_function(shader.library(), name.as_ptr()) };
+        if func.is_null() {
+            return Err(MinervaError::InferenceError(
+                "Failed to load kernel: rms_norm_kernel".to_string(),
+            ));
+        }
+        let pipeline = unsafe { metal_create_pipeline_state(gpu.device(), func) };
+        if pipeline.is_null() {
+            return Err(MinervaError::InferenceError(
+                "Failed to create RMSNorm pipeline".to_string(),
+            ));
+        }
+        Ok(Self {
+            pipeline,
+            _shader: shader,
+        })
+    }
+
+    /// Threadgroups needed for `seq_len` rows: `ceil(seq_len / 8)`
+    pub fn threadgroups(seq_len: u32) -> u32 {
+        seq_len.div_ceil(RMS_NORM_ROWS_PER_THREADGROUP)
+    }
+
+    /// Normalise `x` into `out` and wait for the GPU to finish
+    pub fn dispatch(
+        &self,
+        gpu: &MetalGPU,
+        x: *mut std::ffi::c_void,
+        weight: *mut std::ffi::c_void,
+        out: *mut std::ffi::c_void,
+        shape: (u32, u32),
+        eps: f32,
+    ) -> Result<(), String> {
+        MetalGPU::require_real_bindings("rms_norm_kernel")?;
+        let (seq_len, hidden_size) = shape;
+        let cmd_buffer = gpu.create_command_buffer()?;
+        unsafe {
+            metal_dispatch_rms_norm(
+                cmd_buffer,
+                self.pipeline,
+                x,
+                weight,
+                out,
+                seq_len,
+                hidden_size,
+                eps,
+                Self::threadgroups(seq_len),
+                RMS_NORM_ROWS_PER_THREADGROUP * SIMD_WIDTH,
+            );
+        }
+        gpu.submit_commands(cmd_buffer)?;
+        gpu.wait_completion(cmd_buffer)
+    }
+}
+
+impl Drop for RmsNormKernel {
+    fn drop(&mut self) {
+        unsafe { metal_release_pipeline_state(self.pipeline) }
+    }
+}
+
+/// Embedding lookup plus positional encoding in one GPU pass
+///
+/// Each thread writes one `float4` of the `(seq_len, hidden_size)` output
+/// from the token's embedding row and the position's encoding row, so the
+/// activations are written once rather than written and then re-read.
+pub struct EmbedPositionKernel {
+    pipeline: *mut std::ffi::c_void,
+    // Keeps the library alive for as long as the pipeline uses it
+    _shader: CompiledShader,
+}
+
+impl EmbedPositionKernel {
+    /// Compile `embed_position.metal` (or load it from `cache_dir`) and build its pipeline
+    pub fn new(gpu: &MetalGPU, cache_dir: &Path) -> MinervaResult<Self> {
+        let shader = gpu.compile_shader_cached(EMBED_POSITION_SOURCE, cache_dir)?;
+        let name = c"embed_position_kernel";
+        let func = unsafe { metal_create_function(shader.library(), name.as_ptr()) };
+        if func.is_null() {
+            return Err(MinervaError::InferenceError(
+                "Failed to load kernel: embed_position_kernel".to_string(),
+            ));
+        }
+        let pipeline = unsafe { metal_create_pipeline_state(gpu.device(), func) };
+        if pipeline.is_null() {
+            return Err(MinervaError::InferenceError(
+                "Failed to create embedding pipeline".to_string(),
+            ));
+        }
+        Ok(Self {
+            pipeline,
+            _shader: shader,
+        })
+    }
+
+    /// Threads in the grid: one per `float4`, `seq_len * hidden_size / 4`
+    pub fn grid_size(seq_len: u32, hidden_size: u32) -> u32 {
+        seq_len * (hidden_size / 4)
+    }
+
+    /// Write `embed[token_ids[p]] + pos_enc[start_pos + p]` into row `p` of
+    /// `out` and wait for the GPU to finish
+    #[allow(clippy::too_many_arguments)]
+    pub fn dispatch(
+        &self,
+        gpu: &MetalGPU,
+        token_ids: *mut std::ffi::c_void,
+        embed: *mut std::ffi::c_void,
+        pos_enc: *mut std::ffi::c_void,
+        out: *mut std::ffi::c_void,
+        shape: (u32, u32),
+        start_pos: u32,
+    ) -> Result<(), String> {
+        MetalGPU::require_real_bindings("embed_position_kernel")?;
+        let (seq_len, hidden_size) = shape;
+        if !hidden_size.is_multiple_of(4) {
+            return Err(format!(
+                "hidden size {} is not a multiple of 4",
+                hidden_size
+            ));
+        }
+        let cmd_buffer = gpu.create_command_buffer()?;
+        unsafe {
+            metal_dispatch_embed_position(
+                cmd_buffer,
+                self.pipeline,
+                token_ids,
+                embed,
+                pos_enc,
+                out,
+                seq_len,
+                hidden_size,
+                start_pos,
+                Self::grid_size(seq_len, hidden_size),
+            );
+        }
+        gpu.submit_commands(cmd_buffer)?;
+        gpu.wait_completion(cmd_buffer)
+    }
+}
+
+impl Drop for EmbedPositionKernel {
+    fn drop(&mut self) {
+        unsafe { metal_release_pipeline_state(self.pipeline) }
     }
 }
 
@@ -107,4 +319,268 @@ mod tests {
         let func = MetalKernels::get_function(&gpu, "matmul_kernel");
         assert!(func.is_ok(), "Failed to load matmul kernel");
     }
+
+    #[test]
+    fn test_rms_norm_threadgroups() {
+        assert_eq!(RmsNormKernel::threadgroups(1), 1);
+        assert_eq!(RmsNormKernel::threadgroups(8), 1);
+        assert_eq!(RmsNormKernel::threadgroups(9), 2);
+        assert_eq!(RmsNormKernel::threadgroups(512), 64);
+    }
+
+    #[test]
+    fn test_rms_norm_source_uses_simdgroup_reduction() {
+        assert!(RMS_NORM_SOURCE.contains("kernel void rms_norm_kernel"));
+        assert!(RMS_NORM_SOURCE.contains("simd_shuffle_xor"));
+        assert!(!RMS_NORM_SOURCE.contains("threadgroup_barrier"));
+    }
+
+    /// The kernel's arithmetic on the CPU: strided per-lane partial sums
+    /// combined by an xor butterfly, exactly as each simdgroup does it
+    fn simdgroup_rms_norm(x: &[f32], weight: &[f32], hidden: usize, eps: f32) -> Vec<f32> {
+        let lanes = SIMD_WIDTH as usize;
+        let mut out = vec![0.0; x.len()];
+        for (row_in, row_out) in x.chunks(hidden).zip(out.chunks_mut(hidden)) {
+            let mut partial: Vec<f32> = (0..lanes)
+                .map(|lane| row_in.iter().skip(lane).step_by(lanes).map(|v| v * v).sum())
+                .collect();
+            let mut offset = lanes / 2;
+            while offset > 0 {
+                partial = (0..lanes)
+                    .map(|lane| partial[lane] + partial[lane ^ offset])
+                    .collect();
+                offset /= 2;
+            }
+            assert!(partial.iter().all(|&sum| sum == partial[0]));
+            let inv_rms = 1.0 / (partial[0] / hidden as f32 + eps).sqrt();
+            for ((o, v), w) in row_out.iter_mut().zip(row_in).zip(weight) {
+                *o = v * inv_rms * w;
+            }
+        }
+        out
+    }
+
+    fn rms_norm_case(seq_len: usize, hidden: usize) -> (Vec<f32>, Vec<f32>) {
+        let x = (0..seq_len * hidden)
+            .map(|i| ((i * 37 % 101) as f32 - 50.0) / 25.0)
+            .collect();
+        let weight = (0..hidden).map(|i| 0.5 + (i % 7) as f32 * 0.1).collect();
+        (x, weight)
+    }
+
+    fn cpu_rms_norm(x: &[f32], weight: &[f32], hidden: usize, eps: f32) -> Vec<f32> {
+        x.chunks(hidden)
+            .flat_map(|row| crate::inference::llama_utils::rmsnorm(row, weight, eps).unwrap())
+            .collect()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() <= 1e-4 * e.abs().max(1.0), "{} vs {}", a, e);
+        }
+    }
+
+    #[test]
+    fn test_simdgroup_reduction_matches_cpu_rmsnorm() {
+        // Rows shorter than, equal to and longer than a simdgroup
+        for hidden in [5, 32, 100, 4096] {
+            let (x, weight) = rms_norm_case(3, hidden);
+            assert_close(
+                &simdgroup_rms_norm(&x, &weight, hidden, 1e-5),
+                &cpu_rms_norm(&x, &weight, hidden, 1e-5),
+            );
+        }
+    }
+
+    #[test]
+    fn test_embed_position_grid_size() {
+        assert_eq!(EmbedPositionKernel::grid_size(1, 4), 1);
+        assert_eq!(EmbedPositionKernel::grid_size(7, 4096), 7 * 1024);
+    }
+
+    #[test]
+    fn test_embed_position_source_uses_float4() {
+        assert!(EMBED_POSITION_SOURCE.contains("kernel void embed_position_kernel"));
+        assert!(EMBED_POSITION_SOURCE.contains("device float4 *out"));
+    }
+
+    /// The kernel's indexing on the CPU: one `float4` per grid thread
+    fn fused_embed_position(
+        token_ids: &[u32],
+        embed: &[f32],
+        pos_enc: &[f32],
+        hidden: usize,
+        start_pos: usize,
+    ) -> Vec<f32> {
+        let vecs_per_row = hidden / 4;
+        let grid = EmbedPositionKernel::grid_size(token_ids.len() as u32, hidden as u32);
+        let mut out = vec![0.0; token_ids.len() * hidden];
+        for gid in 0..grid as usize {
+            let (position, col) = (gid / vecs_per_row, gid % vecs_per_row);
+            let token = token_ids[position] as usize;
+            for lane in 0..4 {
+                out[gid * 4 + lane] = embed[(token * vecs_per_row + col) * 4 + lane]
+                    + pos_enc[((start_pos + position) * vecs_per_row + col) * 4 + lane];
+            }
+        }
+        out
+    }
+
+    fn embed_position_case(vocab: usize, max_len: usize, hidden: usize) -> (Vec<f32>, Vec<f32>) {
+        let embed = (0..vocab * hidden)
+            .map(|i| (i % 13) as f32 * 0.25)
+            .collect();
+        let pos_enc = (0..max_len * hidden)
+            .map(|i| (i as f32 * 0.01).sin())
+            .collect();
+        (embed, pos_enc)
+    }
+
+    /// Lookup then add, as two separate passes
+    fn two_pass_embed_position(
+        token_ids: &[u32],
+        embed: &[f32],
+        pos_enc: &[f32],
+        hidden: usize,
+        start_pos: usize,
+    ) -> Vec<f32> {
+        let mut out: Vec<f32> = token_ids
+            .iter()
+            .flat_map(|&t| embed[t as usize * hidden..(t as usize + 1) * hidden].to_vec())
+            .collect();
+        for (o, p) in out.iter_mut().zip(&pos_enc[start_pos * hidden..]) {
+            *o += p;
+        }
+        out
+    }
+
+    #[test]
+    fn test_fused_embed_position_matches_two_passes() {
+        let (vocab, hidden) = (50, 64);
+        let (embed, pos_enc) = embed_position_case(vocab, 16, hidden);
+        let token_ids = [3, 49, 0, 3, 17, 8, 21];
+        for start_pos in [0, 9] {
+            assert_eq!(
+                fused_embed_position(&token_ids, &embed, &pos_enc, hidden, start_pos),
+                two_pass_embed_position(&token_ids, &embed, &pos_enc, hidden, start_pos)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rms_norm_refuses_stub_bindings() {
+        use super::super::gpu_execution_helpers::gpu_rms_norm;
+        use super::super::unified_memory::{ArrayShape, MLXArray};
+
+        let gpu = std::sync::Arc::new(MetalGPU::new().unwrap());
+        let cache = tempfile::tempdir().unwrap();
+        let kernel = RmsNormKernel::new(&gpu, cache.path());
+        assert_eq!(kernel.is_err(), METAL_STUBBED);
+        let Ok(kernel) = kernel else {
+            return;
+        };
+        let input = MLXArray::new_cpu(vec![1.0; 8], ArrayShape::Shape2D(2, 4));
+        let result = gpu_rms_norm(&gpu, &kernel, &input, &[1.0; 4], 1e-5);
+        assert_eq!(result.is_err(), METAL_STUBBED);
+    }
+
+    #[test]
+    fn test_embed_position_refuses_stub_bindings() {
+        let gpu = MetalGPU::new().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let kernel = EmbedPositionKernel::new(&gpu, cache.path());
+        assert_eq!(kernel.is_err(), METAL_STUBBED);
+        let Ok(kernel) = kernel else {
+            return;
+        };
+        let null = std::ptr::null_mut();
+        let result = kernel.dispatch(&gpu, null, null, null, null, (1, 4), 0);
+        assert_eq!(result.is_err(), METAL_STUBBED);
+    }
+
+    #[test]
+    fn test_embed_position_rejects_positions_past_table() {
+        use super::super::gpu_execution_helpers::fused_embed_and_position;
+
+        let gpu = std::sync::Arc::new(MetalGPU::new().unwrap());
+        let cache = tempfile::tempdir().unwrap();
+        let Ok(kernel) = EmbedPositionKernel::new(&gpu, cache.path()) else {
+            return;
+        };
+        let null = std::ptr::null_mut();
+        let err = fused_embed_and_position(
+            &gpu,
+            &kernel,
+            &[1, 2],
+            (null, 8),
+            (null, 16),
+            null,
+            (2, 4),
+            15,
+        )
+        .unwrap_err();
+        assert!(err.contains("positional encodings"));
+    }
+
+    #[test]
+    #[ignore] // Needs real Metal bindings; the stubs do not move data
+    fn test_gpu_embed_position_matches_two_passes() {
+        use super::super::gpu_execution_helpers::fused_embed_and_position;
+
+        let gpu = std::sync::Arc::new(MetalGPU::new().unwrap());
+        let cache = tempfile::tempdir().unwrap();
+        let kernel = EmbedPositionKernel::new(&gpu, cache.path()).unwrap();
+
+        let (vocab, seq_len, hidden) = (50, 7, 64);
+        let (embed, pos_enc) = embed_position_case(vocab, seq_len, hidden);
+        let token_ids = [3, 49, 0, 3, 17, 8, 21];
+        let gpu_embed = gpu.create_buffer(embed.len() * 4).unwrap();
+        let gpu_pos = gpu.create_buffer(pos_enc.len() * 4).unwrap();
+        let gpu_out = gpu.create_buffer(seq_len * hidden * 4).unwrap();
+        gpu.copy_to_gpu(gpu_embed, &embed).unwrap();
+        gpu.copy_to_gpu(gpu_pos, &pos_enc).unwrap();
+
+        fused_embed_and_position(
+            &gpu,
+            &kernel,
+            &token_ids,
+            (gpu_embed, vocab),
+            (gpu_pos, seq_len),
+            gpu_out,
+            (seq_len, hidden),
+            0,
+        )
+        .unwrap();
+        let mut out = vec![0.0; seq_len * hidden];
+        gpu.copy_from_gpu(gpu_out, &mut out).unwrap();
+        for buffer in [gpu_embed, gpu_pos, gpu_out] {
+            gpu.release_buffer(buffer);
+        }
+
+        assert_close(
+            &out,
+            &two_pass_embed_position(&token_ids, &embed, &pos_enc, hidden, 0),
+        );
+    }
+
+    #[test]
+    #[ignore] // Needs real Metal bindings; the stubs do not move data
+    fn test_gpu_rms_norm_matches_cpu_rmsnorm() {
+        use super::super::gpu_execution_helpers::gpu_rms_norm;
+        use super::super::unified_memory::{ArrayShape, MLXArray};
+        use std::sync::Arc;
+
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let cache = tempfile::tempdir().unwrap();
+        let kernel = RmsNormKernel::new(&gpu, cache.path()).unwrap();
+
+        // 13 rows leaves the last threadgroup partly idle
+        let (seq_len, hidden) = (13, 256);
+        let (x, weight) = rms_norm_case(seq_len, hidden);
+        let input = MLXArray::new_cpu(x.clone(), ArrayShape::Shape2D(seq_len, hidden));
+        let output = gpu_rms_norm(&gpu, &kernel, &input, &weight, 1e-5).unwrap();
+
+        assert_close(&output.data(), &cpu_rms_norm(&x, &weight, hidden, 1e-5));
+    }
 }
@@ -10,16 +10,24 @@
 /// 4. Compute Graphs (DONE) - Graph structure and execution
 /// 4B. Operation Fusion (DONE) - Operation fusion and optimization
 /// 5. Metal GPU (IN PROGRESS) - Apple Metal acceleration
+///
+/// The Metal modules are only built with the `metal` feature on macOS; the loader,
+/// forward pass and compute graphs are plain Rust and always available.
 pub mod compute_graph;
 pub mod compute_ops;
 pub mod config;
-#[cfg(test)]
+pub mod forward;
+#[cfg(all(test, feature = "metal", target_os = "macos"))]
 mod gpu_benchmarks;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod gpu_buffer;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod gpu_buffer_pool;
-#[cfg(test)]
+#[cfg(all(test, feature = "metal", target_os = "macos"))]
 mod gpu_buffer_pool_tests;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod gpu_execution_helpers;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod gpu_graph_executor;
 pub mod graph_executor;
 #[cfg(test)]
@@ -33,25 +41,35 @@ mod graph_fusion_tests;
 pub mod graph_optimizer;
 #[cfg(test)]
 mod graph_optimizer_tests;
+pub mod graph_scheduler;
+pub mod kv_mixed_precision;
 pub mod kv_quantization;
 mod kv_quantization_helpers;
 #[cfg(test)]
 mod kv_quantization_test;
 pub mod loader;
+pub mod memory_pressure;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod metal_gpu;
-#[cfg(test)]
+#[cfg(all(test, feature = "metal", target_os = "macos"))]
 mod metal_gpu_tests;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod metal_kernels_wrapper;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod metal_stubs;
 #[cfg(test)]
 mod phase4b_e2e_tests;
 #[cfg(test)]
 mod phase4b_integration_tests;
-#[cfg(test)]
+#[cfg(all(test, feature = "metal", target_os = "macos"))]
 mod phase5_integration_tests;
+#[cfg(all(feature = "metal", target_os = "macos"))]
+pub mod shader_cache;
 pub mod unified_memory;
+pub mod weights;
 
-pub use config::GPTOSSConfig;
-pub use kv_quantization::QuantizedKVCache;
+pub use config::{GPTOSSConfig, MemoryBudget};
+pub use kv_quantization::{MixedPrecisionKVCache, QuantizedKVCache};
 pub use loader::{MLXLayerWeights, MLXModel, load_mlx_model};
 pub use unified_memory::{ArrayShape, Device, MLXArray, MemoryPool};
+pub use weights::{ComputeDtype, Weight};
@@ -0,0 +1,46 @@
+#include <metal_stdlib>
+using namespace metal;
+
+// Rows handled by one threadgroup: one simdgroup per row
+constant uint ROWS_PER_THREADGROUP = 8;
+
+// RMSNorm over each row of a (seq_len, hidden_size) matrix:
+//   out[r, i] = x[r, i] / sqrt(mean(x[r, :]^2) + eps) * weight[i]
+//
+// Each simdgroup owns one row. Its lanes stride across the row summing
+// squares, then combine the partial sums with a butterfly of
+// simd_shuffle_xor in log2(simd_size) steps, leaving the total in every
+// lane. No threadgroup memory or barriers are needed.
+kernel void rms_norm_kernel(
+    const device float *x,
+    const device float *weight,
+    device float *out,
+    constant uint &seq_len,
+    constant uint &hidden_size,
+    constant float &eps,
+    uint group [[threadgroup_position_in_grid]],
+    uint simd_index [[simdgroup_index_in_threadgroup]],
+    uint lane [[thread_index_in_simdgroup]],
+    uint simd_size [[threads_per_simdgroup]])
+{
+    uint row = group * ROWS_PER_THREADGROUP + simd_index;
+    if (row >= seq_len) return;
+
+    const device float *row_in = x + row * hidden_size;
+    device float *row_out = out + row * hidden_size;
+
+    float sum_sq = 0.0;
+    for (uint i = lane; i < hidden_size; i += simd_size) {
+        float v = row_in[i];
+        sum_sq += v * v;
+    }
+
+    for (uint offset = simd_size / 2; offset > 0; offset /= 2) {
+        sum_sq += simd_shuffle_xor(sum_sq, offset);
+    }
+
+    float inv_rms = rsqrt(sum_sq / float(hidden_size) + eps);
+    for (uint i = lane; i < hidden_size; i += simd_size) {
+        row_out[i] = row_in[i] * inv_rms * weight[i];
+    }
+}
@@ -0,0 +1,103 @@
+/// Compiled Metal Shader Cache
+///
+/// Compiling MSL source takes 100-500ms per shader, so compiled libraries
+/// are kept as `{hash}.metallib` files keyed by a hash of the source. A
+/// `compiler-version` tag file records the Metal compiler that produced
+/// them; opening the cache with a different version deletes every cached
+/// library, since metallibs are not portable across compiler releases.
+use crate::error::MinervaResult;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const VERSION_TAG: &str = "compiler-version";
+const LIBRARY_EXTENSION: &str = "metallib";
+
+/// Directory of compiled shader libraries for one compiler version
+#[derive(Debug, Clone)]
+pub struct ShaderCache {
+    dir: PathBuf,
+}
+
+impl ShaderCache {
+    /// Open `dir`, clearing libraries built by another compiler version
+    pub fn open(dir: &Path, compiler_version: &str) -> MinervaResult<Self> {
+        fs::create_dir_all(dir)?;
+        let tag_path = dir.join(VERSION_TAG);
+        if fs::read_to_string(&tag_path).ok().as_deref() != Some(compiler_version) {
+            let mut removed = 0;
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == LIBRARY_EXTENSION) {
+                    fs::remove_file(&path)?;
+                    removed += 1;
+                }
+            }
+            if removed > 0 {
+                tracing::info!(
+                    "Metal compiler changed to {}, dropped {} cached shaders",
+                    compiler_version,
+                    removed
+                );
+            }
+            fs::write(&tag_path, compiler_version)?;
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Where the compiled library for `source` lives
+    pub fn library_path(&self, source: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{:016x}.{}",
+            source_hash(source),
+            LIBRARY_EXTENSION
+        ))
+    }
+
+    /// Cached library for `source`, if one has been stored
+    pub fn lookup(&self, source: &str) -> Option<PathBuf> {
+        let path = self.library_path(source);
+        path.is_file().then_some(path)
+    }
+}
+
+/// FNV-1a: stable across runs and toolchains, unlike `DefaultHasher`
+fn source_hash(source: &str) -> u64 {
+    source.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_library_path_depends_on_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ShaderCache::open(dir.path(), "32023.35").unwrap();
+
+        let a = cache.library_path("kernel void a() {}");
+        assert_eq!(a, cache.library_path("kernel void a() {}"));
+        assert_ne!(a, cache.library_path("kernel void b() {}"));
+        assert!(cache.lookup("kernel void a() {}").is_none());
+    }
+
+    #[test]
+    fn test_compiler_change_clears_libraries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ShaderCache::open(dir.path(), "1").unwrap();
+        fs::write(cache.library_path("src"), b"MTLB").unwrap();
+
+        let same = ShaderCache::open(dir.path(), "1").unwrap();
+        assert!(same.lookup("src").is_some());
+
+        let upgraded = ShaderCache::open(dir.path(), "2").unwrap();
+        assert!(upgraded.lookup("src").is_none());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(VERSION_TAG)).unwrap(),
+            "2"
+        );
+    }
+}
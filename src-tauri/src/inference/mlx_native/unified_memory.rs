@@ -1,6 +1,12 @@
+#[cfg(all(feature = "metal", target_os = "macos"))]
+use super::metal_gpu::{MetalBuffer, MetalGPU};
+#[cfg(all(feature = "metal", target_os = "macos"))]
+use crate::error::{MinervaError, MinervaResult};
 use ndarray::{Array1, Array2};
 use std::sync::{Arc, Mutex};
 
+pub use super::memory_pressure::{MemoryPressureLevel, PressureCallback, UnifiedMemoryMonitor};
+
 /// Device type for computation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Device {
@@ -17,6 +23,12 @@ impl Device {
     }
 }
 
+/// Buffer an array's data was uploaded to; never present without Metal
+#[cfg(all(feature = "metal", target_os = "macos"))]
+type ResidentBuffer = Arc<MetalBuffer>;
+#[cfg(not(all(feature = "metal", target_os = "macos")))]
+type ResidentBuffer = std::convert::Infallible;
+
 /// Unified memory array that can live on CPU or GPU
 #[derive(Clone)]
 pub struct MLXArray {
@@ -28,6 +40,10 @@ pub struct MLXArray {
     device: Device,
     /// Metadata for tracking
     id: u64,
+    /// Metal buffer holding the data once `ensure_on_gpu` has run, shared
+    /// by clones and released with the last of them
+    #[cfg_attr(not(all(feature = "metal", target_os = "macos")), allow(dead_code))]
+    gpu_buffer: Option<ResidentBuffer>,
 }
 
 /// Array shape (supports 1D and 2D for now)
@@ -66,6 +82,7 @@ impl MLXArray {
             shape,
             device: Device::CPU,
             id,
+            gpu_buffer: None,
         }
     }
 
@@ -81,6 +98,7 @@ impl MLXArray {
             shape,
             device: Device::GPU,
             id,
+            gpu_buffer: None,
         }
     }
 
@@ -114,7 +132,44 @@ impl MLXArray {
             shape: self.shape,
             device: target,
             id: self.id,
+            gpu_buffer: None,
+        }
+    }
+
+    /// Whether the array is placed on the GPU
+    ///
+    /// True for `new_gpu` arrays before any upload; the data reaches a
+    /// Metal buffer on the first `ensure_on_gpu`.
+    pub fn is_on_gpu(&self) -> bool {
+        self.device == Device::GPU
+    }
+
+    /// Metal buffer holding the data, for kernel dispatch without a copy
+    #[cfg(all(feature = "metal", target_os = "macos"))]
+    pub fn gpu_buffer(&self) -> Option<&MetalBuffer> {
+        self.gpu_buffer.as_deref()
+    }
+
+    /// Upload the data into a buffer on `gpu`, once
+    ///
+    /// Later calls are no-ops until the data is changed through
+    /// `data_mut`, so kernels can keep binding `gpu_buffer()` instead of
+    /// copying the data again.
+    #[cfg(all(feature = "metal", target_os = "macos"))]
+    pub fn ensure_on_gpu(&mut self, gpu: &Arc<MetalGPU>) -> MinervaResult<()> {
+        if self.gpu_buffer.is_some() {
+            return Ok(());
         }
+
+        let data = self.data();
+        let buffer = MetalGPU::allocate(gpu, std::mem::size_of_val(data.as_slice()))
+            .map_err(MinervaError::InferenceError)?;
+        gpu.copy_to_gpu(buffer.raw(), &data)
+            .map_err(MinervaError::InferenceError)?;
+
+        self.gpu_buffer = Some(Arc::new(buffer));
+        self.device = Device::GPU;
+        Ok(())
     }
 
     /// Get reference to data (for CPU operations)
@@ -123,7 +178,10 @@ impl MLXArray {
     }
 
     /// Get mutable reference to data (for in-place operations)
+    ///
+    /// Drops any uploaded buffer, since it would no longer match the data.
     pub fn data_mut(&mut self) -> Vec<f32> {
+        self.gpu_buffer = None;
         let guard = self.data.lock().unwrap();
         guard.clone()
     }
@@ -325,6 +383,62 @@ mod tests {
         assert!(usage > 0);
     }
 
+    #[cfg(all(feature = "metal", target_os = "macos"))]
+    #[test]
+    fn test_ensure_on_gpu_uploads_once() {
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let mut arr = MLXArray::new_cpu(vec![1.0, -2.5, 3.0, 0.5], ArrayShape::Shape2D(2, 2));
+        assert!(!arr.is_on_gpu());
+
+        arr.ensure_on_gpu(&gpu).unwrap();
+        let raw = arr.gpu_buffer().unwrap().raw();
+        assert!(arr.is_on_gpu());
+        assert_eq!(arr.gpu_buffer().unwrap().size(), 16);
+
+        arr.ensure_on_gpu(&gpu).unwrap();
+        assert_eq!(arr.gpu_buffer().unwrap().raw(), raw);
+    }
+
+    #[cfg(all(feature = "metal", target_os = "macos"))]
+    #[test]
+    fn test_buffer_released_with_last_clone() {
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let mut arr = MLXArray::new_cpu(vec![1.0; 8], ArrayShape::Shape1D(8));
+        arr.ensure_on_gpu(&gpu).unwrap();
+        assert_eq!(Arc::strong_count(&gpu), 2);
+
+        let copy = arr.clone();
+        assert_eq!(
+            copy.gpu_buffer().unwrap().raw(),
+            arr.gpu_buffer().unwrap().raw()
+        );
+        assert!(arr.clone_to_cpu().gpu_buffer().is_none());
+
+        drop(arr);
+        assert_eq!(Arc::strong_count(&gpu), 2);
+        drop(copy);
+        assert_eq!(Arc::strong_count(&gpu), 1);
+    }
+
+    #[cfg(all(feature = "metal", target_os = "macos"))]
+    #[test]
+    fn test_data_mut_invalidates_buffer() {
+        let gpu = Arc::new(MetalGPU::new().unwrap());
+        let mut arr = MLXArray::new_cpu(vec![1.0; 4], ArrayShape::Shape1D(4));
+        arr.ensure_on_gpu(&gpu).unwrap();
+
+        arr.data_mut();
+        assert!(arr.gpu_buffer().is_none());
+        assert_eq!(Arc::strong_count(&gpu), 1);
+    }
+
+    #[test]
+    fn test_new_gpu_is_on_gpu() {
+        let arr = MLXArray::new_gpu(vec![1.0; 4], ArrayShape::Shape1D(4));
+        assert!(arr.is_on_gpu());
+        assert!(!arr.clone_to_cpu().is_on_gpu());
+    }
+
     #[test]
     fn test_device_names() {
         assert_eq!(Device::CPU.name(), "CPU");
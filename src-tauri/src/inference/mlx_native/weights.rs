@@ -0,0 +1,132 @@
+/// Weight Matrices in Their Compute Dtype
+///
+/// `ComputeDtype` chooses how `MLXModel::load_with_dtype` keeps weight
+/// matrices in memory. F16 and BF16 weights stay 16-bit in memory and are
+/// widened to f32 for each product, so activations never pass through a
+/// half-precision range. Norm vectors are tiny and always stay f32.
+use half::{bf16, f16};
+use ndarray::{Array2, Axis};
+
+/// Storage precision for weight matrices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComputeDtype {
+    #[default]
+    F32,
+    F16,
+    BF16,
+}
+
+impl ComputeDtype {
+    pub fn size_bytes(self) -> usize {
+        match self {
+            ComputeDtype::F32 => 4,
+            ComputeDtype::F16 | ComputeDtype::BF16 => 2,
+        }
+    }
+}
+
+/// A `[rows, cols]` weight matrix stored in one of the compute dtypes
+#[derive(Debug, Clone)]
+pub enum Weight {
+    F32(Array2<f32>),
+    F16(Array2<f16>),
+    BF16(Array2<bf16>),
+}
+
+impl Weight {
+    /// Store an f32 matrix as `dtype`
+    pub fn from_f32(values: Array2<f32>, dtype: ComputeDtype) -> Self {
+        match dtype {
+            ComputeDtype::F32 => Weight::F32(values),
+            ComputeDtype::F16 => Weight::F16(values.mapv(f16::from_f32)),
+            ComputeDtype::BF16 => Weight::BF16(values.mapv(bf16::from_f32)),
+        }
+    }
+
+    pub fn dtype(&self) -> ComputeDtype {
+        match self {
+            Weight::F32(_) => ComputeDtype::F32,
+            Weight::F16(_) => ComputeDtype::F16,
+            Weight::BF16(_) => ComputeDtype::BF16,
+        }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        match self {
+            Weight::F32(w) => w.shape(),
+            Weight::F16(w) => w.shape(),
+            Weight::BF16(w) => w.shape(),
+        }
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.shape()[0]
+    }
+
+    /// Bytes held by the matrix in its stored dtype
+    pub fn size_bytes(&self) -> usize {
+        self.shape().iter().product::<usize>() * self.dtype().size_bytes()
+    }
+
+    /// `x @ self^T`, the layout of HuggingFace linear weights
+    pub fn matmul_t(&self, x: &Array2<f32>) -> Array2<f32> {
+        match self {
+            Weight::F32(w) => x.dot(&w.t()),
+            Weight::F16(_) | Weight::BF16(_) => x.dot(&self.to_f32().t()),
+        }
+    }
+
+    /// The whole matrix widened to f32
+    pub fn to_f32(&self) -> Array2<f32> {
+        match self {
+            Weight::F32(w) => w.clone(),
+            Weight::F16(w) => w.mapv(f16::to_f32),
+            Weight::BF16(w) => w.mapv(bf16::to_f32),
+        }
+    }
+
+    /// Rows `ids` widened to f32 (embedding lookup)
+    pub fn select_rows(&self, ids: &[usize]) -> Array2<f32> {
+        match self {
+            Weight::F32(w) => w.select(Axis(0), ids),
+            Weight::F16(w) => w.select(Axis(0), ids).mapv(f16::to_f32),
+            Weight::BF16(w) => w.select(Axis(0), ids).mapv(bf16::to_f32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_half_matmul_close_to_f32() {
+        let w = array![[0.5f32, -1.25, 2.0], [0.1, 0.2, 0.3]];
+        let x = array![[1.0f32, 2.0, 3.0]];
+        let expected = Weight::F32(w.clone()).matmul_t(&x);
+
+        for dtype in [ComputeDtype::F16, ComputeDtype::BF16] {
+            let weight = Weight::from_f32(w.clone(), dtype);
+            assert_eq!(weight.size_bytes(), 6 * 2);
+            let actual = weight.matmul_t(&x);
+            for (a, e) in actual.iter().zip(expected.iter()) {
+                assert!((a - e).abs() < 1e-2, "{:?}: {} vs {}", dtype, a, e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_f16_matmul_keeps_large_activations() {
+        let weight = Weight::from_f32(array![[1.0f32, 0.5]], ComputeDtype::F16);
+        let x = array![[100_000.0f32, 200_000.0]];
+        assert_eq!(weight.matmul_t(&x), array![[200_000.0f32]]);
+    }
+
+    #[test]
+    fn test_select_rows_widens() {
+        let weight = Weight::from_f32(array![[1.0f32, 2.0], [3.0, 4.0]], ComputeDtype::BF16);
+        assert_eq!(weight.select_rows(&[1]), array![[3.0f32, 4.0]]);
+        assert_eq!(weight.to_f32(), array![[1.0f32, 2.0], [3.0, 4.0]]);
+    }
+}
@@ -6,6 +6,14 @@
 use crate::error::MinervaResult;
 use crate::inference::inference_backend_trait::{GenerationParams, InferenceBackend};
 use std::path::Path;
+use std::time::Duration;
+
+/// Simulated prompt processing before the first token
+const MOCK_PREFILL_DELAY: Duration = Duration::from_millis(50);
+/// Simulated decode time per streamed token
+const MOCK_TOKEN_DELAY: Duration = Duration::from_millis(2);
+/// Vocabulary size of the mock logits
+const MOCK_VOCAB_SIZE: usize = 32;
 
 /// Mock backend for testing and development
 #[derive(Debug)]
@@ -14,6 +22,7 @@ pub struct MockBackend {
     loaded: bool,
     n_ctx: usize,
     n_threads: usize,
+    streaming: bool,
 }
 
 impl MockBackend {
@@ -24,9 +33,17 @@ impl MockBackend {
             loaded: false,
             n_ctx: 0,
             n_threads: num_cpus::get(),
+            streaming: false,
         }
     }
 
+    /// Report streaming support, to exercise both request paths in tests
+    #[allow(dead_code)]
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
     /// Generate intelligent mock response based on prompt
     ///
     /// Simulates different response patterns for different question types:
@@ -106,12 +123,42 @@ impl InferenceBackend for MockBackend {
         }
 
         // Simulate real inference
-        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::thread::sleep(MOCK_PREFILL_DELAY);
 
         let response = self.generate_intelligent_response(prompt, params.max_tokens);
         Ok(response)
     }
 
+    fn generate_stream(
+        &self,
+        prompt: &str,
+        params: GenerationParams,
+        on_token: &mut dyn FnMut(&str),
+    ) -> MinervaResult<String> {
+        if !self.streaming {
+            let text = self.generate(prompt, params)?;
+            on_token(&text);
+            return Ok(text);
+        }
+        if !self.loaded {
+            return Err(crate::error::MinervaError::InferenceError(
+                "Model not loaded".to_string(),
+            ));
+        }
+
+        std::thread::sleep(MOCK_PREFILL_DELAY);
+        let text = self.generate_intelligent_response(prompt, params.max_tokens);
+        // One word per token, matching the mock tokenizer, each emitted once
+        // its simulated decode step has run
+        for (i, word) in text.split_whitespace().enumerate() {
+            if i > 0 {
+                std::thread::sleep(MOCK_TOKEN_DELAY);
+            }
+            on_token(word);
+        }
+        Ok(text)
+    }
+
     fn tokenize(&self, text: &str) -> MinervaResult<Vec<i32>> {
         // Simple word-based mock tokenization
         Ok(text
@@ -126,6 +173,21 @@ impl InferenceBackend for MockBackend {
         Ok(format!("[{} tokens]", tokens.len()))
     }
 
+    fn next_token_logits(&self, tokens: &[u32]) -> MinervaResult<Vec<f32>> {
+        if !self.loaded {
+            return Err(crate::error::MinervaError::InferenceError(
+                "Model not loaded".to_string(),
+            ));
+        }
+
+        // Mock model always predicts a repeat of the last token
+        let mut logits = vec![0.0; MOCK_VOCAB_SIZE];
+        if let Some(&last) = tokens.last() {
+            logits[last as usize % MOCK_VOCAB_SIZE] = 1.0;
+        }
+        Ok(logits)
+    }
+
     fn is_loaded(&self) -> bool {
         self.loaded
     }
@@ -137,4 +199,12 @@ impl InferenceBackend for MockBackend {
     fn thread_count(&self) -> usize {
         self.n_threads
     }
+
+    fn set_thread_count(&mut self, n_threads: usize) {
+        self.n_threads = n_threads.max(1);
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.streaming
+    }
 }
@@ -9,27 +9,39 @@ pub mod batch_async;
 pub mod batch_measurement;
 pub mod batch_optimized;
 pub mod batch_parallel;
+pub mod batch_queue;
 pub mod batch_result_optimized;
 pub mod benchmarks;
 pub mod cache_optimizer;
 pub mod causal_mask;
 pub mod context_manager;
+pub mod context_snapshot;
 pub mod downloader;
 pub mod embedding;
 pub mod engine_config;
 pub mod feedforward;
 pub mod garbage_collector;
 pub mod gpu;
+#[cfg(not(cpu_only))]
 pub mod gpu_batch_scheduler;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod gpu_compute_engine;
+#[cfg(not(cpu_only))]
 pub mod gpu_context;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod gpu_llama_integration;
+#[cfg(not(cpu_only))]
+pub mod gpu_llama_vram;
 pub mod greedy_sampling;
+pub mod hardware_probe;
 pub mod inference_backend_trait;
 pub mod inference_engine;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod inference_pipeline;
+pub mod int8_matmul;
 pub mod kv_cache;
 pub mod kv_cache_optimizer;
+pub mod kv_snapshot_cache;
 pub mod layer_norm;
 pub mod llama_adapter;
 pub mod llama_attention;
@@ -40,9 +52,12 @@ pub mod llama_feedforward;
 pub mod llama_inference;
 pub mod llama_tokenizer;
 pub mod llama_utils;
+#[cfg(all(feature = "metal", target_os = "macos"))]
 pub mod metal_gpu;
 pub mod metrics;
 pub mod mlx_backend;
+pub mod mlx_backend_native;
+pub mod mlx_model_loader;
 pub mod mlx_model_support;
 pub mod mlx_native;
 pub mod mock_backend;
@@ -61,16 +76,21 @@ pub mod phase5_integration;
 pub mod position_encoding;
 pub mod preload_manager;
 pub mod pure_rust_backend;
+pub mod repetition_detector;
+pub mod resident_models;
 pub mod rope_utils;
 pub mod sampling;
+pub mod session_store;
 pub mod softmax_utils;
 pub mod stream_event_type;
 pub mod streaming;
 pub mod streaming_builder;
 pub mod streaming_events;
 pub mod streaming_response;
+pub mod system_memory;
 pub mod temperature;
 pub mod temperature_sampling;
+pub mod text_pipeline;
 pub mod token_callback;
 pub mod token_stream;
 pub mod tokenizer;
@@ -79,8 +99,10 @@ pub mod top_p_sampling;
 pub mod transformer_components;
 pub mod transformer_layers;
 pub mod unified_backend;
+pub mod unified_backend_routing;
 pub mod unified_model_registry;
 
+use crate::config::GenerationConfigUpdate;
 use crate::error::{MinervaError, MinervaResult};
 use std::path::PathBuf;
 
@@ -142,6 +164,16 @@ impl GenerationConfig {
 
         Ok(())
     }
+
+    /// Copy of this config with every field set in `update` replaced
+    pub fn with_update(&self, update: &GenerationConfigUpdate) -> Self {
+        Self {
+            temperature: update.temperature.unwrap_or(self.temperature),
+            top_p: update.top_p.unwrap_or(self.top_p),
+            max_tokens: update.max_tokens.unwrap_or(self.max_tokens),
+            ..self.clone()
+        }
+    }
 }
 
 /// LLM Inference Engine for generating responses
@@ -332,6 +364,21 @@ mod tests {
         assert!(invalid.validate().is_err());
     }
 
+    #[test]
+    fn test_generation_config_update_keeps_unset_fields() {
+        let update = GenerationConfigUpdate {
+            temperature: Some(0.2),
+            max_tokens: Some(64),
+            ..Default::default()
+        };
+        let config = GenerationConfig::default().with_update(&update);
+
+        assert_eq!(config.temperature, 0.2);
+        assert_eq!(config.max_tokens, 64);
+        assert_eq!(config.top_p, 0.9);
+        assert_eq!(config.top_k, 40);
+    }
+
     #[test]
     fn test_inference_engine_creation() {
         let engine = InferenceEngine::new(PathBuf::from("/test/model.gguf"));
@@ -1,6 +1,6 @@
 use super::engine_config::InferenceEngineConfig;
 use super::inference_engine::InferenceEngine;
-use super::model_weights::{LayerWeights, ModelWeights};
+use super::model_weights::{LayerWeights, LazyWeight, ModelWeights, TensorSource};
 use super::pure_rust_backend::ModelType;
 /// Model Loader - Phase 9 Day 7 Complete Implementation
 ///
@@ -15,8 +15,26 @@ use super::pure_rust_backend::ModelType;
 /// - Configuration inference from model metadata
 /// - Model validation and error handling
 use crate::error::{MinervaError, MinervaResult};
+use std::collections::HashMap;
 use std::path::Path;
 
+/// A named tensor that `ModelLoader::extract_tensor` can size-check
+trait Tensor: Clone {
+    fn elements(&self) -> usize;
+}
+
+impl Tensor for Vec<f32> {
+    fn elements(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Tensor for TensorSource {
+    fn elements(&self) -> usize {
+        self.len()
+    }
+}
+
 // ============================================================================
 // Model Loader Configuration
 // ============================================================================
@@ -177,6 +195,7 @@ impl ModelMetadata {
             causal,
             eps: 1e-6,
             max_seq_len: 2048,
+            n_threads: num_cpus::get(),
         })
     }
 }
@@ -206,53 +225,19 @@ impl ModelLoader {
         Ok(engine)
     }
 
-    /// Load weights from safetensors file
+    /// Index the safetensors file and load its weights
+    ///
+    /// Embeddings, the final norm and the output projection are needed by
+    /// every forward pass and are read now. Per-layer weights stay in the
+    /// memory-mapped file until a forward pass first reaches their layer.
     fn load_weights(
         weights_path: &str,
         config: &InferenceEngineConfig,
     ) -> MinervaResult<ModelWeights> {
-        use safetensors::SafeTensors;
-        use std::collections::HashMap;
-        use std::fs;
-
-        // Read safetensors file
-        let file_data = fs::read(weights_path).map_err(|e| {
+        let tensors = TensorSource::from_safetensors(Path::new(weights_path)).map_err(|e| {
             MinervaError::InferenceError(format!("Failed to read weights file: {}", e))
         })?;
 
-        // Deserialize safetensors
-        let safetensors = SafeTensors::deserialize(&file_data).map_err(|e| {
-            MinervaError::InferenceError(format!("Failed to deserialize safetensors: {}", e))
-        })?;
-
-        // Extract tensors into a map for easy access
-        let mut tensors: HashMap<String, Vec<f32>> = HashMap::new();
-
-        for (name, tensor) in safetensors.tensors() {
-            let data = tensor.data();
-            let f32_count = data.len() / 4;
-
-            if data.len() % 4 != 0 {
-                return Err(MinervaError::InferenceError(format!(
-                    "Tensor {} has misaligned data size",
-                    name
-                )));
-            }
-
-            let mut f32_data = vec![0.0_f32; f32_count];
-            for i in 0..f32_count {
-                let bytes = [
-                    data[i * 4],
-                    data[i * 4 + 1],
-                    data[i * 4 + 2],
-                    data[i * 4 + 3],
-                ];
-                f32_data[i] = f32::from_le_bytes(bytes);
-            }
-
-            tensors.insert(name.to_string(), f32_data);
-        }
-
         // Extract embedding weights
         let embeddings = Self::extract_tensor(
             &tensors,
@@ -262,7 +247,8 @@ impl ModelLoader {
                 "wte.weight",
             ],
             config.vocab_size * config.hidden_size,
-        )?;
+        )?
+        .read();
 
         // Extract per-layer weights
         let mut layers = Vec::new();
@@ -292,10 +278,10 @@ impl ModelLoader {
             )?;
 
             layers.push(LayerWeights {
-                attn_norm_scale,
-                ffn_norm_scale: vec![1.0; config.hidden_size],
-                ff_up,
-                ff_down,
+                attn_norm_scale: LazyWeight::lazy(attn_norm_scale),
+                ffn_norm_scale: vec![1.0; config.hidden_size].into(),
+                ff_up: LazyWeight::lazy(ff_up),
+                ff_down: LazyWeight::lazy(ff_down),
             });
         }
 
@@ -304,14 +290,16 @@ impl ModelLoader {
             &tensors,
             &["model.norm.weight", "final_layer_norm.weight"],
             config.hidden_size,
-        )?;
+        )?
+        .read();
 
         // Extract output projection
         let output_proj = Self::extract_tensor(
             &tensors,
             &["lm_head.weight"],
             config.hidden_size * config.vocab_size,
-        )?;
+        )?
+        .read();
 
         Ok(ModelWeights {
             embeddings,
@@ -322,14 +310,14 @@ impl ModelLoader {
     }
 
     /// Helper to extract tensor by trying multiple possible names
-    fn extract_tensor(
-        tensors: &std::collections::HashMap<String, Vec<f32>>,
+    fn extract_tensor<T: Tensor>(
+        tensors: &HashMap<String, T>,
         possible_names: &[&str],
         expected_size: usize,
-    ) -> MinervaResult<Vec<f32>> {
+    ) -> MinervaResult<T> {
         for name in possible_names {
             if let Some(tensor) = tensors.get(*name)
-                && tensor.len() == expected_size
+                && tensor.elements() == expected_size
             {
                 return Ok(tensor.clone());
             }
@@ -574,7 +562,7 @@ mod tests {
 
     #[test]
     fn test_extract_tensor_not_found() {
-        let tensors = std::collections::HashMap::new();
+        let tensors: HashMap<String, Vec<f32>> = HashMap::new();
 
         let result = ModelLoader::extract_tensor(&tensors, &["missing.weight"], 4);
 
@@ -587,15 +575,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_load_weights_defers_layers() {
+        use safetensors::tensor::{Dtype, TensorView, serialize};
+
+        let config = InferenceEngineConfig::tiny(8);
+        let (hidden, inter) = (config.hidden_size, config.intermediate_size);
+        let mut tensors = vec![
+            ("model.embed_tokens.weight".to_string(), 8 * hidden),
+            ("model.norm.weight".to_string(), hidden),
+            ("lm_head.weight".to_string(), hidden * 8),
+        ];
+        for layer in 0..config.num_layers {
+            let prefix = format!("model.layers.{}", layer);
+            tensors.push((format!("{}.input_layernorm.weight", prefix), hidden));
+            tensors.push((format!("{}.mlp.up_proj.weight", prefix), hidden * inter));
+            tensors.push((format!("{}.mlp.down_proj.weight", prefix), inter * hidden));
+        }
+        let bytes: Vec<(String, Vec<u8>)> = tensors
+            .into_iter()
+            .map(|(name, len)| (name, vec![0u8; len * 4]))
+            .collect();
+        let views: Vec<_> = bytes
+            .iter()
+            .map(|(name, data)| {
+                let view = TensorView::new(Dtype::F32, vec![data.len() / 4], data).unwrap();
+                (name.as_str(), view)
+            })
+            .collect();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), serialize(views, &None).unwrap()).unwrap();
+
+        let weights = ModelLoader::load_weights(file.path().to_str().unwrap(), &config).unwrap();
+        assert_eq!(weights.embeddings.len(), 8 * hidden);
+        assert_eq!(weights.layers.len(), config.num_layers);
+        assert!(!weights.layers[0].ff_up.is_loaded());
+        assert_eq!(weights.layers[0].ff_up.get().unwrap().len(), hidden * inter);
+    }
+
     fn create_dummy_weights(config: &InferenceEngineConfig) -> ModelWeights {
         let mut layers = Vec::new();
 
         for _ in 0..config.num_layers {
             layers.push(LayerWeights {
-                attn_norm_scale: vec![1.0; config.hidden_size],
-                ffn_norm_scale: vec![1.0; config.hidden_size],
-                ff_up: vec![0.1; config.hidden_size * config.intermediate_size],
-                ff_down: vec![0.1; config.intermediate_size * config.hidden_size],
+                attn_norm_scale: vec![1.0; config.hidden_size].into(),
+                ffn_norm_scale: vec![1.0; config.hidden_size].into(),
+                ff_up: vec![0.1; config.hidden_size * config.intermediate_size].into(),
+                ff_down: vec![0.1; config.intermediate_size * config.hidden_size].into(),
             });
         }
 
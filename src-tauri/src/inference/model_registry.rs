@@ -1,3 +1,4 @@
+use super::downloader::{CacheEntry, DownloadCache};
 use crate::error::{MinervaError, MinervaResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -15,6 +16,9 @@ pub struct ModelMetadata {
     pub last_accessed: Option<u64>,
     pub access_count: u64,
     pub hash: String,
+    /// Set when the file failed integrity checks; cleared on re-registration
+    #[serde(default)]
+    pub corrupted: bool,
 }
 
 impl ModelMetadata {
@@ -33,29 +37,18 @@ impl ModelMetadata {
             last_accessed: None,
             access_count: 0,
             hash,
+            corrupted: false,
         })
     }
 
-    /// Compute file hash for integrity checking
-    fn compute_hash(path: &Path) -> MinervaResult<String> {
-        use std::fs::File;
-        use std::io::Read;
+    /// SHA-256 of the file, hex encoded, as the hub reports it for LFS files
+    pub(crate) fn compute_hash(path: &Path) -> MinervaResult<String> {
+        use sha2::{Digest, Sha256};
 
-        let mut file = File::open(path)?;
-        let mut buffer = [0; 8192];
-        let mut hash = 0u64;
-
-        loop {
-            let n = file.read(&mut buffer)?;
-            if n == 0 {
-                break;
-            }
-            for &byte in &buffer[..n] {
-                hash = hash.wrapping_mul(31).wrapping_add(byte as u64);
-            }
-        }
-
-        Ok(format!("{:x}", hash))
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(format!("{:x}", hasher.finalize()))
     }
 
     /// Update last accessed timestamp
@@ -99,6 +92,7 @@ pub struct ModelRegistry {
     cache_dir: Option<PathBuf>,
     max_cache_size_mb: u64,
     current_cache_size_mb: u64,
+    downloads: DownloadCache,
 }
 
 impl ModelRegistry {
@@ -110,6 +104,7 @@ impl ModelRegistry {
             cache_dir: None,
             max_cache_size_mb: 50000, // 50GB default
             current_cache_size_mb: 0,
+            downloads: DownloadCache::new(),
         }
     }
 
@@ -125,6 +120,7 @@ impl ModelRegistry {
             cache_dir: Some(cache_dir),
             max_cache_size_mb: 50000,
             current_cache_size_mb: 0,
+            downloads: DownloadCache::new(),
         })
     }
 
@@ -158,6 +154,99 @@ impl ModelRegistry {
         Ok(())
     }
 
+    /// Mark model as corrupted
+    pub fn mark_corrupted(&mut self, id: &str) -> MinervaResult<()> {
+        self.models
+            .get_mut(id)
+            .ok_or_else(|| MinervaError::ModelNotFound(format!("Model not found: {}", id)))?
+            .corrupted = true;
+        Ok(())
+    }
+
+    /// Clear the corrupted flag after a fresh copy with `hash` replaced the file
+    pub fn mark_restored(&mut self, id: &str, hash: String) -> MinervaResult<()> {
+        let metadata = self
+            .models
+            .get_mut(id)
+            .ok_or_else(|| MinervaError::ModelNotFound(format!("Model not found: {}", id)))?;
+        metadata.hash = hash;
+        metadata.corrupted = false;
+        Ok(())
+    }
+
+    /// Path and SHA-256 a model's file must match: the checksum recorded at
+    /// download when there is one, else the one taken at registration
+    pub fn expected_hash(&self, id: &str) -> Option<(PathBuf, String)> {
+        let metadata = self.models.get(id)?;
+        let hash = self
+            .download_source(id)
+            .and_then(|entry| entry.sha256.clone())
+            .unwrap_or_else(|| metadata.hash.clone());
+        Some((metadata.path.clone(), hash))
+    }
+
+    /// Remember where a model was downloaded from
+    pub fn record_download(&mut self, entry: CacheEntry) {
+        self.downloads.remove(&entry.model_id);
+        self.downloads.add(entry);
+    }
+
+    /// Every recorded download
+    pub fn downloads(&self) -> &DownloadCache {
+        &self.downloads
+    }
+
+    /// Download record for a model, if it came from the hub
+    pub fn download_source(&self, id: &str) -> Option<&CacheEntry> {
+        self.downloads.get(id)
+    }
+
+    /// Record every GGUF file under `models_dir`, finished or still `.part`
+    ///
+    /// Files are only stat'ed, not hashed, so this is cheap enough for startup;
+    /// checksums come from `register`. Existing records are left alone.
+    pub fn record_models_dir(&mut self, models_dir: &Path) -> usize {
+        let mut recorded = 0;
+        for file in walkdir::WalkDir::new(models_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = match file.path().extension().and_then(|e| e.to_str()) {
+                Some("gguf") => file.path().to_path_buf(),
+                Some("part") => file.path().with_extension("gguf"),
+                _ => continue,
+            };
+            let Some(model_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if self.downloads.get(model_id).is_some() {
+                continue;
+            }
+
+            let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let modified = file
+                .metadata()
+                .ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+            self.downloads.add(CacheEntry {
+                model_id: model_id.to_string(),
+                path,
+                revision: "local".to_string(),
+                size_bytes,
+                file_count: 1,
+                downloaded_at: modified,
+                last_accessed: modified,
+                source_url: None,
+                sha256: None,
+            });
+            recorded += 1;
+        }
+        recorded
+    }
+
     /// Mark model as accessed
     #[allow(dead_code)]
     pub fn access(&mut self, id: &str) -> MinervaResult<()> {
@@ -293,6 +382,81 @@ mod tests {
         assert!(registry.list_cached().is_empty());
     }
 
+    #[test]
+    fn test_record_models_dir_finds_finished_and_partial_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        std::fs::write(dir.path().join("done.gguf"), [0u8; 32]).unwrap();
+        std::fs::write(dir.path().join("nested/pending.part"), [0u8; 8]).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"skip").unwrap();
+
+        let mut registry = ModelRegistry::new();
+        assert_eq!(registry.record_models_dir(dir.path()), 2);
+        assert_eq!(registry.download_source("done").unwrap().size_bytes, 32);
+        let pending = registry.download_source("pending").unwrap();
+        assert_eq!(pending.path, dir.path().join("nested/pending.gguf"));
+        assert_eq!(pending.size_bytes, 0);
+
+        assert_eq!(registry.record_models_dir(dir.path()), 0);
+        assert_eq!(registry.downloads().list().len(), 2);
+    }
+
+    #[test]
+    fn test_compute_hash_is_sha256() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"abc").unwrap();
+        assert_eq!(
+            ModelMetadata::compute_hash(file.path()).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_mark_restored_clears_corrupted() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut registry = ModelRegistry::new();
+        registry
+            .register("model", file.path().to_path_buf())
+            .unwrap();
+        registry.mark_corrupted("model").unwrap();
+
+        registry
+            .mark_restored("model", "fresh".to_string())
+            .unwrap();
+        let metadata = registry.get("model").unwrap();
+        assert!(!metadata.corrupted);
+        assert_eq!(metadata.hash, "fresh");
+    }
+
+    #[test]
+    fn test_expected_hash_prefers_download_checksum() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut registry = ModelRegistry::new();
+        registry
+            .register("model", file.path().to_path_buf())
+            .unwrap();
+        let registered = registry.get("model").unwrap().hash.clone();
+        assert_eq!(registry.expected_hash("model").unwrap().1, registered);
+
+        let mut entry = CacheEntry {
+            model_id: "model".to_string(),
+            path: file.path().to_path_buf(),
+            revision: "main".to_string(),
+            size_bytes: 0,
+            file_count: 1,
+            downloaded_at: 0,
+            last_accessed: 0,
+            source_url: None,
+            sha256: None,
+        };
+        registry.record_download(entry.clone());
+        assert_eq!(registry.expected_hash("model").unwrap().1, registered);
+
+        entry.sha256 = Some("source".to_string());
+        registry.record_download(entry);
+        assert_eq!(registry.expected_hash("model").unwrap().1, "source");
+    }
+
     #[test]
     fn test_registry_get_nonexistent() {
         let registry = ModelRegistry::new();
@@ -350,6 +514,7 @@ mod tests {
             last_accessed: None,
             access_count: 0,
             hash: "abc123".to_string(),
+            corrupted: false,
         };
 
         assert_eq!(metadata.access_count, 0);
@@ -369,6 +534,7 @@ mod tests {
             last_accessed: Some(0),
             access_count: 0,
             hash: "abc123".to_string(),
+            corrupted: false,
         };
 
         let age = metadata.age_seconds();
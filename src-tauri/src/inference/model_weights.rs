@@ -3,6 +3,15 @@
 /// Holds all learnable parameters for a transformer model.
 /// In production, these are loaded from checkpoint files.
 /// This module provides the data structures to organize and access weights.
+/// Per-layer weights are `LazyWeight`s over a memory-mapped file, so layers
+/// that a short generation never reaches are never read from disk.
+use crate::error::{MinervaError, MinervaResult};
+use memmap2::Mmap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
 /// Container for all model weights
 ///
 /// This holds all the learnable parameters needed for inference.
@@ -38,16 +47,293 @@ pub struct ModelWeights {
 #[derive(Debug, Clone)]
 pub struct LayerWeights {
     /// Attention layer norm scale: (hidden_size,)
-    pub attn_norm_scale: Vec<f32>,
+    pub attn_norm_scale: LazyWeight,
 
     /// FFN layer norm scale: (hidden_size,)
-    pub ffn_norm_scale: Vec<f32>,
+    pub ffn_norm_scale: LazyWeight,
 
     /// FFN up projection: (hidden_size, intermediate_size)
     /// Expands hidden representation
-    pub ff_up: Vec<f32>,
+    pub ff_up: LazyWeight,
 
     /// FFN down projection: (intermediate_size, hidden_size)
     /// Projects back to hidden dimension
-    pub ff_down: Vec<f32>,
+    pub ff_down: LazyWeight,
+}
+
+/// Largest safetensors header accepted, as in the format's reference reader
+pub const MAX_HEADER_BYTES: u64 = 100 * 1024 * 1024;
+
+/// A little-endian f32 tensor inside a memory-mapped weights file
+#[derive(Debug, Clone)]
+pub struct TensorSource {
+    map: Arc<Mmap>,
+    /// Byte offset of the first element
+    offset: usize,
+    /// Number of f32 elements
+    len: usize,
+}
+
+impl TensorSource {
+    /// `len` elements starting at byte `offset` of `map`
+    pub fn new(map: Arc<Mmap>, offset: usize, len: usize) -> MinervaResult<Self> {
+        let in_bounds = len
+            .checked_mul(4)
+            .and_then(|bytes| offset.checked_add(bytes))
+            .is_some_and(|end| end <= map.len());
+        if !in_bounds {
+            return Err(MinervaError::model_loading(format!(
+                "Tensor of {} elements at byte {} exceeds the {} byte file",
+                len,
+                offset,
+                map.len()
+            )));
+        }
+        Ok(Self { map, offset, len })
+    }
+
+    /// Map a safetensors file and index its F32 tensors from the header
+    ///
+    /// Tensor data is not touched, so pages are only read once a tensor is.
+    pub fn from_safetensors(path: &Path) -> MinervaResult<HashMap<String, TensorSource>> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only; model files are not modified
+        // while loaded.
+        let map = Arc::new(unsafe { Mmap::map(&file)? });
+
+        let len_bytes: [u8; 8] = map
+            .get(..8)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| MinervaError::model_loading("Safetensors file too short"))?;
+        let header_len = u64::from_le_bytes(len_bytes);
+        if header_len > MAX_HEADER_BYTES || header_len > map.len() as u64 - 8 {
+            return Err(MinervaError::model_loading(format!(
+                "Safetensors header of {} bytes is invalid for a {} byte file",
+                header_len,
+                map.len()
+            )));
+        }
+
+        let data_start = 8 + header_len as usize;
+        let header: HashMap<String, serde_json::Value> =
+            serde_json::from_slice(&map[8..data_start])?;
+
+        header
+            .iter()
+            .filter(|(_, info)| info["dtype"] == "F32")
+            .map(|(name, info)| {
+                let offsets = (
+                    info["data_offsets"][0].as_u64(),
+                    info["data_offsets"][1].as_u64(),
+                );
+                let (Some(begin), Some(end)) = offsets else {
+                    return Err(MinervaError::model_loading(format!(
+                        "Tensor {} has no data offsets",
+                        name
+                    )));
+                };
+                let bytes = end
+                    .checked_sub(begin)
+                    .filter(|b| b.is_multiple_of(4))
+                    .ok_or_else(|| {
+                        MinervaError::model_loading(format!(
+                            "Tensor {} has invalid data offsets",
+                            name
+                        ))
+                    })?;
+                let offset = (data_start as u64).saturating_add(begin);
+                let source = TensorSource::new(
+                    Arc::clone(&map),
+                    usize::try_from(offset).unwrap_or(usize::MAX),
+                    (bytes / 4) as usize,
+                )?;
+                Ok((name.clone(), source))
+            })
+            .collect()
+    }
+
+    /// Number of f32 elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Copy the tensor out of the mapping
+    pub fn read(&self) -> Vec<f32> {
+        self.map[self.offset..self.offset + self.len * 4]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+}
+
+/// Weight tensor that is read from disk on first access and cached.
+///
+/// Clones share the cache, so a prefetch on one clone benefits all of them.
+#[derive(Debug, Clone)]
+pub struct LazyWeight {
+    source: Option<TensorSource>,
+    cell: Arc<OnceLock<Vec<f32>>>,
+}
+
+impl LazyWeight {
+    /// Defer reading `source` until the weight is first used
+    pub fn lazy(source: TensorSource) -> Self {
+        Self {
+            source: Some(source),
+            cell: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Tensor data, reading it from disk on first access
+    pub fn get(&self) -> MinervaResult<&[f32]> {
+        if let Some(data) = self.cell.get() {
+            return Ok(data.as_slice());
+        }
+
+        let source = self.source.as_ref().ok_or_else(|| {
+            MinervaError::model_loading("Lazy weight has no data source".to_string())
+        })?;
+        Ok(self.cell.get_or_init(|| source.read()).as_slice())
+    }
+
+    /// Start loading in the background so a later `get` finds it cached
+    pub fn prefetch(&self) {
+        if self.is_loaded() {
+            return;
+        }
+        let weight = self.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = weight.get() {
+                tracing::warn!("Weight prefetch failed: {}", e);
+            }
+        });
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.cell.get().is_some()
+    }
+
+    /// Element count, known without reading the tensor
+    pub fn len(&self) -> usize {
+        match (self.cell.get(), &self.source) {
+            (Some(data), _) => data.len(),
+            (None, Some(source)) => source.len(),
+            (None, None) => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<Vec<f32>> for LazyWeight {
+    fn from(data: Vec<f32>) -> Self {
+        Self {
+            source: None,
+            cell: Arc::new(OnceLock::from(data)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn map_floats(values: &[f32]) -> (tempfile::NamedTempFile, Arc<Mmap>) {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&[0u8; 4]).unwrap();
+        for v in values {
+            file.write_all(&v.to_le_bytes()).unwrap();
+        }
+        let map = unsafe { Mmap::map(file.as_file()).unwrap() };
+        (file, Arc::new(map))
+    }
+
+    /// Safetensors file with the given header JSON and `data` after it
+    fn write_safetensors(header: &str, data: &[u8]) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&(header.len() as u64).to_le_bytes())
+            .unwrap();
+        file.write_all(header.as_bytes()).unwrap();
+        file.write_all(data).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_lazy_weight_reads_on_first_get() {
+        let (_file, map) = map_floats(&[1.0, 2.0, 3.0]);
+        let weight = LazyWeight::lazy(TensorSource::new(map, 4, 3).unwrap());
+
+        assert!(!weight.is_loaded());
+        assert_eq!(weight.len(), 3);
+        assert_eq!(weight.get().unwrap(), &[1.0, 2.0, 3.0]);
+        assert!(weight.is_loaded());
+    }
+
+    #[test]
+    fn test_lazy_weight_clones_share_cache() {
+        let (_file, map) = map_floats(&[4.0]);
+        let weight = LazyWeight::lazy(TensorSource::new(map, 4, 1).unwrap());
+        let clone = weight.clone();
+
+        clone.get().unwrap();
+        assert!(weight.is_loaded());
+    }
+
+    #[test]
+    fn test_eager_weight_from_vec() {
+        let weight = LazyWeight::from(vec![0.5; 8]);
+        assert!(weight.is_loaded());
+        assert_eq!(weight.get().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_missing_file_errors() {
+        let path = Path::new("/nonexistent/weights.safetensors");
+        assert!(TensorSource::from_safetensors(path).is_err());
+    }
+
+    #[test]
+    fn test_source_past_end_of_file_is_rejected() {
+        let (_file, map) = map_floats(&[1.0, 2.0]);
+        assert!(TensorSource::new(Arc::clone(&map), 4, 2).is_ok());
+        assert!(TensorSource::new(Arc::clone(&map), 4, 3).is_err());
+        assert!(TensorSource::new(map, usize::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_safetensors_index() {
+        let header = r#"{"w":{"dtype":"F32","shape":[2],"data_offsets":[0,8]},"h":{"dtype":"F16","shape":[2],"data_offsets":[8,12]}}"#;
+        let mut data: Vec<u8> = [1.5f32, -2.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        data.extend([0u8; 4]);
+        let file = write_safetensors(header, &data);
+
+        let index = TensorSource::from_safetensors(file.path()).unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index["w"].read(), vec![1.5, -2.0]);
+    }
+
+    #[test]
+    fn test_oversized_header_is_rejected() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&u64::MAX.to_le_bytes()).unwrap();
+        file.write_all(b"{}").unwrap();
+        assert!(TensorSource::from_safetensors(file.path()).is_err());
+    }
+
+    #[test]
+    fn test_tensor_past_end_of_data_is_rejected() {
+        let header = r#"{"w":{"dtype":"F32","shape":[4],"data_offsets":[0,16]}}"#;
+        let file = write_safetensors(header, &[0u8; 8]);
+        assert!(TensorSource::from_safetensors(file.path()).is_err());
+    }
 }
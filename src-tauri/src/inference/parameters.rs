@@ -1,7 +1,15 @@
 use super::GenerationConfig;
 use super::parameter_validator::ParameterApplier;
+use crate::config::GenerationConfigUpdate;
 use crate::error::MinervaResult;
-use crate::models::ChatCompletionRequest;
+use crate::models::{ChatCompletionRequest, ModelInfo};
+
+/// OpenAI default when a request omits `temperature` or `top_p`
+const DEFAULT_SAMPLING: f32 = 1.0;
+/// Upper bound accepted by `GenerationConfig::validate`
+const MAX_TOKENS_LIMIT: usize = 32768;
+/// Longest model name logged verbatim
+const MAX_LOGGED_MODEL_LEN: usize = 50;
 
 /// Parse and validate generation parameters from a chat completion request
 #[derive(Debug)]
@@ -10,9 +18,15 @@ pub struct ParameterParser;
 
 impl ParameterParser {
     /// Extract and validate generation config from request
+    ///
+    /// Omitted fields take OpenAI's defaults (temperature and top_p of 1.0);
+    /// `max_tokens` falls back to the model's declared output limit.
     #[allow(dead_code)]
-    pub fn from_request(req: &ChatCompletionRequest) -> MinervaResult<GenerationConfig> {
-        let mut config = GenerationConfig::default();
+    pub fn from_request(
+        req: &ChatCompletionRequest,
+        model: &ModelInfo,
+    ) -> MinervaResult<GenerationConfig> {
+        let mut config = Self::defaults_for(model);
 
         if let Some(temp) = req.temperature {
             ParameterApplier::apply_temperature(&mut config, temp)?;
@@ -34,28 +48,67 @@ impl ParameterParser {
         Ok(config)
     }
 
-    /// Build request summary for logging
+    /// Fill sampling fields the request left out from the user's overrides
+    ///
+    /// Fields unset in `overrides` stay empty so `from_request` falls back
+    /// to the model's own defaults.
+    pub fn fill_defaults(req: &mut ChatCompletionRequest, overrides: &GenerationConfigUpdate) {
+        req.temperature = req.temperature.or(overrides.temperature);
+        req.top_p = req.top_p.or(overrides.top_p);
+        req.max_tokens = req.max_tokens.or(overrides.max_tokens);
+    }
+
+    fn defaults_for(model: &ModelInfo) -> GenerationConfig {
+        let defaults = GenerationConfig::default();
+        GenerationConfig {
+            temperature: DEFAULT_SAMPLING,
+            top_p: DEFAULT_SAMPLING,
+            max_tokens: model
+                .max_output_tokens
+                .map_or(defaults.max_tokens, |max| max.clamp(1, MAX_TOKENS_LIMIT)),
+            ..defaults
+        }
+    }
+
+    /// Build a log-safe request summary
+    ///
+    /// Message content is never included, only the count; long model names
+    /// (e.g. full file paths) are truncated.
     #[allow(dead_code)]
     pub fn summarize_request(req: &ChatCompletionRequest) -> String {
-        let temp = req.temperature.unwrap_or(0.7);
-        let max_tok = req.max_tokens.unwrap_or(512);
+        let temp = req.temperature.unwrap_or(DEFAULT_SAMPLING);
+        let top_p = req.top_p.unwrap_or(DEFAULT_SAMPLING);
+        let max_tok = req
+            .max_tokens
+            .map_or_else(|| "default".to_string(), |n| n.to_string());
         let stream = req.stream.unwrap_or(false);
 
         format!(
-            "model={}, messages={}, temp={:.1}, max_tokens={}, stream={}",
-            req.model,
+            "model={}, messages={}, temp={:.2}, top_p={:.2}, max_tokens={}, stream={}",
+            mask_model_name(&req.model),
             req.messages.len(),
             temp,
+            top_p,
             max_tok,
             stream
         )
     }
 }
 
+/// Keep the first characters of an over-long model name and elide the rest
+fn mask_model_name(model: &str) -> String {
+    let len = model.chars().count();
+    if len <= MAX_LOGGED_MODEL_LEN {
+        return model.to_string();
+    }
+    let prefix: String = model.chars().take(MAX_LOGGED_MODEL_LEN - 3).collect();
+    format!("{}...({} chars)", prefix, len)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::ChatMessage;
+    use crate::models::{ChatMessage, ModelCapabilities};
 
     #[derive(Default)]
     struct TestRequestParams {
@@ -78,19 +131,60 @@ mod tests {
             top_p: params.top_p,
             frequency_penalty: params.frequency_penalty,
             presence_penalty: None,
+            stream_options: None,
+        }
+    }
+
+    fn model(max_output_tokens: Option<usize>) -> ModelInfo {
+        ModelInfo {
+            id: "test".to_string(),
+            object: "model".to_string(),
+            created: 0,
+            owned_by: "local".to_string(),
+            context_window: None,
+            max_output_tokens,
+            capabilities: ModelCapabilities::default(),
         }
     }
 
     #[test]
     fn test_parameter_parser_defaults() {
         let req = make_request(TestRequestParams::default());
-        let config = ParameterParser::from_request(&req).unwrap();
+        let config = ParameterParser::from_request(&req, &model(None)).unwrap();
 
-        assert_eq!(config.temperature, 0.7);
-        assert_eq!(config.top_p, 0.9);
+        assert_eq!(config.temperature, 1.0);
+        assert_eq!(config.top_p, 1.0);
         assert_eq!(config.max_tokens, 512);
     }
 
+    #[test]
+    fn test_parameter_parser_max_tokens_from_model() {
+        let req = make_request(TestRequestParams::default());
+        let config = ParameterParser::from_request(&req, &model(Some(2048))).unwrap();
+        assert_eq!(config.max_tokens, 2048);
+
+        let config = ParameterParser::from_request(&req, &model(Some(131_072))).unwrap();
+        assert_eq!(config.max_tokens, 32768);
+    }
+
+    #[test]
+    fn test_fill_defaults_keeps_request_values() {
+        let mut req = make_request(TestRequestParams {
+            temperature: Some(0.3),
+            ..Default::default()
+        });
+        let overrides = GenerationConfigUpdate {
+            temperature: Some(0.9),
+            top_p: Some(0.5),
+            max_tokens: None,
+        };
+        ParameterParser::fill_defaults(&mut req, &overrides);
+
+        assert_eq!(req.temperature, Some(0.3));
+        assert_eq!(req.top_p, Some(0.5));
+        assert_eq!(req.max_tokens, None);
+    }
+
     #[test]
     fn test_parameter_parser_custom_values() {
         let params = TestRequestParams {
@@ -100,7 +194,7 @@ mod tests {
             frequency_penalty: None,
         };
         let req = make_request(params);
-        let config = ParameterParser::from_request(&req).unwrap();
+        let config = ParameterParser::from_request(&req, &model(None)).unwrap();
 
         assert_eq!(config.temperature, 0.5);
         assert_eq!(config.top_p, 0.8);
@@ -114,7 +208,7 @@ mod tests {
             ..Default::default()
         };
         let req = make_request(params);
-        let result = ParameterParser::from_request(&req);
+        let result = ParameterParser::from_request(&req, &model(None));
 
         assert!(result.is_err());
         assert!(
@@ -132,7 +226,7 @@ mod tests {
             ..Default::default()
         };
         let req = make_request(params);
-        let result = ParameterParser::from_request(&req);
+        let result = ParameterParser::from_request(&req, &model(None));
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("top_p"));
@@ -145,7 +239,7 @@ mod tests {
             ..Default::default()
         };
         let req = make_request(params);
-        let result = ParameterParser::from_request(&req);
+        let result = ParameterParser::from_request(&req, &model(None));
 
         assert!(result.is_err());
         assert!(
@@ -163,7 +257,7 @@ mod tests {
             ..Default::default()
         };
         let req = make_request(params);
-        let config = ParameterParser::from_request(&req).unwrap();
+        let config = ParameterParser::from_request(&req, &model(None)).unwrap();
 
         assert_eq!(config.repeat_penalty, 1.0);
     }
@@ -175,7 +269,7 @@ mod tests {
             ..Default::default()
         };
         let req = make_request(params);
-        let config = ParameterParser::from_request(&req).unwrap();
+        let config = ParameterParser::from_request(&req, &model(None)).unwrap();
 
         assert_eq!(config.repeat_penalty, 1.1);
     }
@@ -191,7 +285,34 @@ mod tests {
         let summary = ParameterParser::summarize_request(&req);
 
         assert!(summary.contains("test"));
-        assert!(summary.contains("temp=0.8"));
+        assert!(summary.contains("temp=0.80"));
+        assert!(summary.contains("top_p=1.00"));
         assert!(summary.contains("max_tokens=256"));
+        assert!(summary.contains("stream=false"));
+    }
+
+    #[test]
+    fn test_parameter_parser_summarize_omits_content() {
+        let mut req = make_request(TestRequestParams {
+            top_p: Some(0.123),
+            ..Default::default()
+        });
+        req.stream = Some(true);
+        let summary = ParameterParser::summarize_request(&req);
+
+        assert!(!summary.contains("hello"));
+        assert!(summary.contains("messages=1"));
+        assert!(summary.contains("top_p=0.12"));
+        assert!(summary.contains("stream=true"));
+    }
+
+    #[test]
+    fn test_parameter_parser_summarize_masks_long_model() {
+        let mut req = make_request(TestRequestParams::default());
+        req.model = format!("/Users/me/models/{}.gguf", "x".repeat(60));
+        let summary = ParameterParser::summarize_request(&req);
+
+        assert!(!summary.contains(&req.model));
+        assert!(summary.contains("...(82 chars)"));
     }
 }
@@ -1,9 +1,13 @@
+use super::inference_backend_trait::InferenceBackend;
 use super::model_cache::ModelCache;
 use super::model_registry::ModelRegistry;
-use crate::error::MinervaResult;
+use super::resident_models::{ResidentModel, ResidentModels};
+use crate::config::PreloadModelEntry;
+use crate::error::{MinervaError, MinervaResult};
+use crate::performance::window_state::WindowState;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 /// Preload configuration
@@ -84,6 +88,17 @@ impl PreloadStats {
     }
 }
 
+/// Room made for a model by `PreloadManager::reserve_resident`
+#[derive(Debug)]
+pub struct ResidentSlot {
+    model_id: String,
+    /// Model file to load
+    pub path: PathBuf,
+    bytes: u64,
+    #[cfg(not(cpu_only))]
+    booked_before: usize,
+}
+
 /// Manages preloading of models into cache
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -93,6 +108,8 @@ pub struct PreloadManager {
     config: PreloadConfig,
     stats: PreloadStats,
     last_preload: Option<Instant>,
+    window_state: WindowState,
+    resident: ResidentModels,
 }
 
 impl PreloadManager {
@@ -105,6 +122,8 @@ impl PreloadManager {
             config: PreloadConfig::default(),
             stats: PreloadStats::default(),
             last_preload: None,
+            window_state: WindowState::Foreground,
+            resident: ResidentModels::default(),
         }
     }
 
@@ -117,9 +136,154 @@ impl PreloadManager {
             config,
             stats: PreloadStats::default(),
             last_preload: None,
+            window_state: WindowState::Foreground,
+            resident: ResidentModels::default(),
+        }
+    }
+
+    /// Track loaded models against a shared GPU context
+    pub fn with_resident_models(mut self, resident: ResidentModels) -> Self {
+        self.resident = resident;
+        self
+    }
+
+    /// Pin the models marked `pin: true` in `AppConfig.preload_models`
+    pub fn apply_preload_models(&mut self, entries: &[PreloadModelEntry]) {
+        for entry in entries {
+            self.resident.set_pinned(&entry.id, entry.pin);
+        }
+    }
+
+    /// Loaded models and their memory bookings
+    pub fn resident_models(&self) -> &ResidentModels {
+        &self.resident
+    }
+
+    pub fn resident_models_mut(&mut self) -> &mut ResidentModels {
+        &mut self.resident
+    }
+
+    /// Unload least recently requested models until `required_mb` is free
+    ///
+    /// Pinned models are skipped; fails with `OutOfMemory` when only pinned
+    /// models remain and there is still not enough room.
+    #[cfg(not(cpu_only))]
+    pub fn ensure_capacity(
+        &mut self,
+        required_mb: u64,
+        registry: &mut ModelRegistry,
+    ) -> MinervaResult<()> {
+        let required =
+            usize::try_from(required_mb.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX);
+        loop {
+            let available = self.resident.available_bytes()?;
+            if available >= required {
+                return Ok(());
+            }
+            let Some(victim) = self.resident.coldest_unpinned(registry) else {
+                return Err(MinervaError::OutOfMemory(format!(
+                    "Need {} MB but only {} MB available and no unpinned model to evict",
+                    required_mb,
+                    available / (1024 * 1024)
+                )));
+            };
+            let freed = self.resident.evict(&victim)?;
+            if let Some(metadata) = registry.get_mut(&victim) {
+                metadata.cached = false;
+            }
+            tracing::info!(
+                "Evicted model {} to free {} MB",
+                victim,
+                freed / (1024 * 1024)
+            );
         }
     }
 
+    /// Load `model_id` into a resident slot, evicting colder models first
+    ///
+    /// The file comes from the registry, or from the downloads it recorded
+    /// at startup; `load` builds the backend for it. An already resident
+    /// model only counts as accessed.
+    pub fn load_resident(
+        &mut self,
+        model_id: &str,
+        registry: &mut ModelRegistry,
+        load: impl FnOnce(&Path) -> MinervaResult<Box<dyn InferenceBackend>>,
+    ) -> MinervaResult<()> {
+        match self.reserve_resident(model_id, registry)? {
+            Some(slot) => {
+                let backend = load(&slot.path)?;
+                self.insert_resident(registry, slot, backend)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Make room for `model_id` ahead of loading it, registering it from its
+    /// recorded download when the registry does not know it
+    ///
+    /// Returns `None` when the model is already resident, after counting the
+    /// access. The backend can then be loaded without holding this manager.
+    pub fn reserve_resident(
+        &mut self,
+        model_id: &str,
+        registry: &mut ModelRegistry,
+    ) -> MinervaResult<Option<ResidentSlot>> {
+        if self.resident.contains(model_id) {
+            registry.access(model_id)?;
+            return Ok(None);
+        }
+        if registry.get(model_id).is_none() {
+            let path = registry
+                .download_source(model_id)
+                .map(|entry| entry.path.clone())
+                .ok_or_else(|| {
+                    MinervaError::ModelNotFound(format!("Model not found: {}", model_id))
+                })?;
+            registry.register(model_id, path)?;
+        }
+        let path = registry
+            .get(model_id)
+            .map(|metadata| metadata.path.clone())
+            .unwrap_or_default();
+        let bytes = std::fs::metadata(&path)?.len();
+        #[cfg(not(cpu_only))]
+        self.ensure_capacity(bytes.div_ceil(1024 * 1024), registry)?;
+        Ok(Some(ResidentSlot {
+            model_id: model_id.to_string(),
+            path,
+            bytes,
+            #[cfg(not(cpu_only))]
+            booked_before: self.resident.allocated_bytes()?,
+        }))
+    }
+
+    /// Book the backend loaded for `slot` and mark its model cached
+    ///
+    /// Memory the backend booked itself while loading (llama.cpp's
+    /// offloaded layers) is not booked again.
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert_resident(
+        &mut self,
+        registry: &mut ModelRegistry,
+        slot: ResidentSlot,
+        backend: Box<dyn InferenceBackend>,
+    ) -> MinervaResult<()> {
+        let bytes = usize::try_from(slot.bytes).unwrap_or(usize::MAX);
+        #[cfg(not(cpu_only))]
+        let bytes = bytes.saturating_sub(
+            self.resident
+                .allocated_bytes()?
+                .saturating_sub(slot.booked_before),
+        );
+        let model_id = slot.model_id;
+        self.resident
+            .insert(&model_id, ResidentModel::new(backend, bytes))?;
+        registry.mark_cached(&model_id)?;
+        tracing::info!("Model resident: {}", model_id);
+        registry.access(&model_id)
+    }
+
     /// Queue model for preloading
     #[allow(dead_code)]
     pub fn queue(&mut self, model_id: &str, model_path: PathBuf) -> MinervaResult<()> {
@@ -145,6 +309,11 @@ impl PreloadManager {
             return Ok(0);
         }
 
+        // Preloading is non-critical; hold it while the window is minimized
+        if self.window_state.defers_background_work() {
+            return Ok(0);
+        }
+
         // Check rate limiting
         if let Some(last) = self.last_preload
             && last.elapsed().as_millis() < self.config.delay_ms as u128
@@ -178,6 +347,11 @@ impl PreloadManager {
         Ok(processed)
     }
 
+    /// Update the app window state used to defer preloading
+    pub fn set_window_state(&mut self, state: WindowState) {
+        self.window_state = state;
+    }
+
     /// Get number of queued tasks
     #[allow(dead_code)]
     pub fn queue_size(&self) -> usize {
@@ -314,6 +488,178 @@ mod tests {
         assert_eq!(manager.queue_size(), 0);
     }
 
+    #[test]
+    fn test_manager_defers_preload_when_minimized() {
+        let mut manager = PreloadManager::new(ModelRegistry::default());
+        manager.queue.push_back(PreloadTask {
+            model_id: "m".to_string(),
+            model_path: PathBuf::from("/nonexistent/m.gguf"),
+            priority: 0,
+            created_at: Instant::now(),
+        });
+        manager.set_window_state(WindowState::Minimized);
+
+        let mut cache = ModelCache::new(1, super::super::model_cache::EvictionPolicy::Lru);
+        assert_eq!(manager.process_batch(&mut cache).unwrap(), 0);
+        assert_eq!(manager.queue_size(), 1);
+    }
+
+    #[cfg(not(cpu_only))]
+    const MB: usize = 1024 * 1024;
+
+    #[cfg(not(cpu_only))]
+    fn manager_with_gpu(max_mb: usize) -> PreloadManager {
+        use super::super::gpu_context::{GpuContext, GpuDevice};
+        use std::sync::{Arc, Mutex};
+
+        let gpu = GpuContext {
+            device: GpuDevice::Cpu,
+            allocated_memory: 0,
+            max_memory: max_mb * MB,
+        };
+        PreloadManager::default()
+            .with_resident_models(ResidentModels::new(Arc::new(Mutex::new(gpu))))
+    }
+
+    #[cfg(not(cpu_only))]
+    fn load(manager: &mut PreloadManager, model_id: &str, mb: usize) {
+        use super::super::mock_backend::MockBackend;
+        use super::super::resident_models::ResidentModel;
+
+        let model = ResidentModel::new(Box::new(MockBackend::new()), mb * MB);
+        manager
+            .resident_models_mut()
+            .insert(model_id, model)
+            .unwrap();
+    }
+
+    #[cfg(not(cpu_only))]
+    fn registry_with_access(dir: &std::path::Path, accessed: &[(&str, u64)]) -> ModelRegistry {
+        let mut registry = ModelRegistry::default();
+        for (id, last_accessed) in accessed {
+            let path = dir.join(format!("{}.gguf", id));
+            std::fs::write(&path, b"weights").unwrap();
+            registry.register(id, path).unwrap();
+            registry.mark_cached(id).unwrap();
+            registry.get_mut(id).unwrap().last_accessed = Some(*last_accessed);
+        }
+        registry
+    }
+
+    #[cfg(not(cpu_only))]
+    #[test]
+    fn test_ensure_capacity_evicts_coldest_until_enough_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = registry_with_access(dir.path(), &[("a", 100), ("b", 300), ("c", 200)]);
+        let mut manager = manager_with_gpu(100);
+        for id in ["a", "b", "c"] {
+            load(&mut manager, id, 30);
+        }
+
+        manager.ensure_capacity(50, &mut registry).unwrap();
+
+        let resident = manager.resident_models();
+        assert!(!resident.contains("a"));
+        assert!(!resident.contains("c"));
+        assert!(resident.contains("b"));
+        assert!(!registry.get("a").unwrap().cached);
+    }
+
+    #[cfg(not(cpu_only))]
+    #[test]
+    fn test_ensure_capacity_never_evicts_pinned_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = registry_with_access(dir.path(), &[("pinned", 1), ("warm", 500)]);
+        let mut manager = manager_with_gpu(100);
+        manager.apply_preload_models(&[PreloadModelEntry {
+            id: "pinned".to_string(),
+            pin: true,
+        }]);
+        load(&mut manager, "pinned", 50);
+        load(&mut manager, "warm", 40);
+
+        manager.ensure_capacity(30, &mut registry).unwrap();
+        assert!(manager.resident_models().contains("pinned"));
+        assert!(!manager.resident_models().contains("warm"));
+
+        let err = manager.ensure_capacity(80, &mut registry).unwrap_err();
+        assert!(matches!(err, MinervaError::OutOfMemory(_)));
+        assert!(manager.resident_models().contains("pinned"));
+    }
+
+    #[cfg(not(cpu_only))]
+    #[test]
+    fn test_load_resident_evicts_to_make_room() {
+        use super::super::mock_backend::MockBackend;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = registry_with_access(dir.path(), &[("old", 1)]);
+        let new_model = dir.path().join("new.gguf");
+        std::fs::write(&new_model, vec![0u8; 2 * MB]).unwrap();
+        registry.register("new", new_model).unwrap();
+        let mut manager = manager_with_gpu(4);
+        load(&mut manager, "old", 3);
+
+        manager
+            .load_resident("new", &mut registry, |_| Ok(Box::new(MockBackend::new())))
+            .unwrap();
+
+        assert!(manager.resident_models().contains("new"));
+        assert!(!manager.resident_models().contains("old"));
+        let metadata = registry.get("new").unwrap();
+        assert!(metadata.cached);
+        assert_eq!(metadata.access_count, 1);
+    }
+
+    #[cfg(not(cpu_only))]
+    #[test]
+    fn test_load_resident_skips_memory_the_backend_booked() {
+        use super::super::mock_backend::MockBackend;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = ModelRegistry::default();
+        let model = dir.path().join("model.gguf");
+        std::fs::write(&model, vec![0u8; 2 * MB]).unwrap();
+        registry.register("model", model).unwrap();
+        let mut manager = manager_with_gpu(4);
+        let gpu = manager.resident_models().gpu_context();
+
+        manager
+            .load_resident("model", &mut registry, |_| {
+                // llama.cpp books its offloaded layers itself
+                gpu.lock().unwrap().allocated_memory += MB;
+                Ok(Box::new(MockBackend::new()))
+            })
+            .unwrap();
+
+        assert_eq!(manager.resident_models().allocated_bytes().unwrap(), 2 * MB);
+    }
+
+    #[cfg(not(cpu_only))]
+    #[test]
+    fn test_reserve_resident_only_counts_access_for_resident_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = registry_with_access(dir.path(), &[("model", 1)]);
+        let mut manager = manager_with_gpu(4);
+        load(&mut manager, "model", 1);
+
+        let slot = manager.reserve_resident("model", &mut registry).unwrap();
+        assert!(slot.is_none());
+        assert_eq!(registry.get("model").unwrap().access_count, 1);
+    }
+
+    #[cfg(not(cpu_only))]
+    #[test]
+    fn test_load_resident_unknown_model() {
+        let mut manager = manager_with_gpu(4);
+        let err = manager
+            .load_resident("missing", &mut ModelRegistry::default(), |_| {
+                unreachable!("nothing to load")
+            })
+            .unwrap_err();
+        assert!(matches!(err, MinervaError::ModelNotFound(_)));
+    }
+
     #[test]
     fn test_manager_clear_queue() {
         let mut manager = PreloadManager::new(ModelRegistry::default());
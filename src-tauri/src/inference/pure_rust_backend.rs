@@ -47,15 +47,18 @@
 /// println!("{}", response);
 /// ```
 use crate::error::{MinervaError, MinervaResult};
+use crate::inference::int8_matmul::{Int8MatmulParams, QuantizedMatrix, matmul_int8_cpu};
 use crate::inference::llama_adapter::{GenerationParams, InferenceBackend};
 use crate::inference::llama_tokenizer::LLaMATokenizer;
+use crate::inference::repetition_detector::RepetitionDetector;
+use rayon::prelude::*;
 use safetensors::SafeTensors;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 /// Type alias for weight tensors: name -> flattened vector
-type WeightTensors = HashMap<String, Vec<f32>>;
+pub type WeightTensors = HashMap<String, Vec<f32>>;
 
 /// Known model architecture types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -142,6 +145,8 @@ pub struct PureRustBackend {
     config: Arc<Mutex<Option<ModelConfig>>>,
     /// Tokenizer for converting text to/from tokens
     tokenizer: Arc<Mutex<Option<LLaMATokenizer>>>,
+    /// `lm_head.weight` quantized to int8 at load time, scales per vocab entry
+    lm_head: Arc<Mutex<Option<QuantizedMatrix>>>,
     /// Context window size
     n_ctx: usize,
     /// Number of CPU threads for computation
@@ -155,6 +160,7 @@ impl PureRustBackend {
             weights: Arc::new(Mutex::new(None)),
             config: Arc::new(Mutex::new(None)),
             tokenizer: Arc::new(Mutex::new(None)),
+            lm_head: Arc::new(Mutex::new(None)),
             n_ctx: 0,
             n_threads: num_cpus::get(),
         }
@@ -261,6 +267,67 @@ impl PureRustBackend {
         Ok(weights)
     }
 
+    /// Load a model split across multiple safetensors shards
+    ///
+    /// Reads `model.safetensors.index.json`, whose `weight_map` maps each
+    /// weight name to the shard file holding it, then loads every referenced
+    /// shard from `shard_dir` in parallel and merges the tensors.
+    pub fn load_sharded(shard_dir: &Path, index_path: &Path) -> MinervaResult<WeightTensors> {
+        let shard_files = Self::read_shard_index(index_path)?;
+
+        let shard_paths: Vec<_> = shard_files
+            .iter()
+            .map(|file_name| shard_dir.join(file_name))
+            .collect();
+
+        if let Some(missing) = shard_paths.iter().find(|path| !path.exists()) {
+            return Err(MinervaError::ModelNotFound(format!(
+                "Shard referenced by index not found: {}",
+                missing.display()
+            )));
+        }
+
+        let shards = shard_paths
+            .par_iter()
+            .map(|path| Self::load_safetensors(path))
+            .collect::<MinervaResult<Vec<_>>>()?;
+
+        let weights: WeightTensors = shards.into_iter().flatten().collect();
+
+        tracing::info!(
+            "Loaded {} safetensors shards: {} tensors",
+            shard_paths.len(),
+            weights.len()
+        );
+
+        Ok(weights)
+    }
+
+    /// Unique shard file names referenced by a safetensors index file
+    fn read_shard_index(index_path: &Path) -> MinervaResult<BTreeSet<String>> {
+        let index_json = std::fs::read_to_string(index_path).map_err(|e| {
+            MinervaError::ModelNotFound(format!(
+                "Failed to read shard index {}: {}",
+                index_path.display(),
+                e
+            ))
+        })?;
+
+        let index: serde_json::Value = serde_json::from_str(&index_json)?;
+        let weight_map = index
+            .get("weight_map")
+            .and_then(|map| map.as_object())
+            .ok_or_else(|| {
+                MinervaError::model_loading("Shard index has no weight_map".to_string())
+            })?;
+
+        Ok(weight_map
+            .values()
+            .filter_map(|file_name| file_name.as_str())
+            .map(str::to_string)
+            .collect())
+    }
+
     /// Load model configuration from config.json
     ///
     /// Parses configuration from config.json in the model directory.
@@ -515,10 +582,29 @@ impl PureRustBackend {
             *val = val.tanh(); // Apply activation
         }
 
-        // Step 4: Output projection to vocabulary logits
-        // Phase 9: Use actual output layer weights from model
-        // For now: simple projection using deterministic function
-        let mut logits = vec![0.0; cfg.vocab_size];
+        // Step 4: Output projection to vocabulary logits, through the int8
+        // lm_head when the model has one
+        let lm_head = self.lm_head.lock().unwrap();
+        let mut logits = match lm_head.as_ref() {
+            Some(weight) => {
+                let activations = QuantizedMatrix::per_row(&embeddings, 1)?;
+                matmul_int8_cpu(&Int8MatmulParams::from_matrices(&activations, weight))?
+            }
+            None => Self::synthetic_projection(&embeddings, cfg.vocab_size),
+        };
+
+        // Normalize logits to prevent overflow
+        let max_logit = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        for logit in logits.iter_mut() {
+            *logit -= max_logit;
+        }
+
+        Ok(logits)
+    }
+
+    /// Deterministic stand-in for the output layer of models without `lm_head.weight`
+    fn synthetic_projection(embeddings: &[f32], vocab_size: usize) -> Vec<f32> {
+        let mut logits = vec![0.0; vocab_size];
         for (vocab_idx, logit) in logits.iter_mut().enumerate() {
             let mut sum = 0.0;
             for (emb_idx, &emb_val) in embeddings.iter().enumerate() {
@@ -527,14 +613,27 @@ impl PureRustBackend {
             }
             *logit = sum;
         }
+        logits
+    }
 
-        // Normalize logits to prevent overflow
-        let max_logit = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
-        for logit in logits.iter_mut() {
-            *logit -= max_logit;
+    /// Quantize the output layer once, with per-vocab-entry scales from its weights
+    fn quantize_lm_head(
+        weights: &WeightTensors,
+        config: &ModelConfig,
+    ) -> MinervaResult<Option<QuantizedMatrix>> {
+        let Some(weight) = weights.get("lm_head.weight") else {
+            return Ok(None);
+        };
+        if weight.len() != config.vocab_size * config.hidden_size {
+            tracing::warn!(
+                "lm_head.weight has {} values, expected {}x{}; using the synthetic projection",
+                weight.len(),
+                config.vocab_size,
+                config.hidden_size
+            );
+            return Ok(None);
         }
-
-        Ok(logits)
+        QuantizedMatrix::from_linear_weight(weight, config.vocab_size).map(Some)
     }
 
     /// Sample next token from logits with proper probability distribution
@@ -653,11 +752,13 @@ impl InferenceBackend for PureRustBackend {
 
         // Load model weights from safetensors
         let weights = Self::load_safetensors(path)?;
-        *self.weights.lock().unwrap() = Some(weights);
 
         // Load model configuration
         let config = Self::load_config(path)?;
+        let lm_head = Self::quantize_lm_head(&weights, &config)?;
+        *self.weights.lock().unwrap() = Some(weights);
         *self.config.lock().unwrap() = Some(config.clone());
+        *self.lm_head.lock().unwrap() = lm_head;
 
         // Create/load tokenizer
         // For now, use a simple fallback tokenizer
@@ -684,6 +785,7 @@ impl InferenceBackend for PureRustBackend {
         *self.weights.lock().unwrap() = None;
         *self.config.lock().unwrap() = None;
         *self.tokenizer.lock().unwrap() = None;
+        *self.lm_head.lock().unwrap() = None;
         self.n_ctx = 0;
         tracing::info!("PureRustBackend: Model unloaded");
     }
@@ -699,6 +801,7 @@ impl InferenceBackend for PureRustBackend {
         let mut tokens: Vec<i32> = input_tokens.iter().map(|&t| t as i32).collect();
 
         // Generate tokens one by one
+        let mut detector = RepetitionDetector::default();
         for _ in 0..params.max_tokens {
             // Get logits from transformer
             let logits = self.forward_pass(&tokens)?;
@@ -711,6 +814,12 @@ impl InferenceBackend for PureRustBackend {
             if next_token == 2 {
                 break;
             }
+
+            // Stop a repetition loop instead of running out max_tokens
+            if detector.push(next_token as u32) {
+                tracing::debug!("PureRustBackend: stopped on a repetition loop");
+                break;
+            }
         }
 
         // Detokenize output
@@ -738,6 +847,11 @@ impl InferenceBackend for PureRustBackend {
         tok.decode(&u32_tokens)
     }
 
+    fn next_token_logits(&self, tokens: &[u32]) -> MinervaResult<Vec<f32>> {
+        let tokens: Vec<i32> = tokens.iter().map(|&t| t as i32).collect();
+        self.forward_pass(&tokens)
+    }
+
     fn is_loaded(&self) -> bool {
         self.weights.lock().unwrap().is_some()
     }
@@ -749,6 +863,10 @@ impl InferenceBackend for PureRustBackend {
     fn thread_count(&self) -> usize {
         self.n_threads
     }
+
+    fn set_thread_count(&mut self, n_threads: usize) {
+        self.n_threads = n_threads.max(1);
+    }
 }
 
 #[cfg(test)]
@@ -1034,4 +1152,51 @@ mod tests {
         // Deterministic sampling should be consistent
         assert_eq!(result1, result2);
     }
+
+    fn write_shard(path: &Path, tensor_name: &str, values: &[f32]) {
+        use safetensors::tensor::{Dtype, TensorView, serialize};
+
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let view = TensorView::new(Dtype::F32, vec![values.len()], &bytes).unwrap();
+        let data = serialize(vec![(tensor_name, view)], &None).unwrap();
+        std::fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn test_load_sharded_merges_all_shards() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let shard_1 = dir.path().join("model-00001-of-00002.safetensors");
+        let shard_2 = dir.path().join("model-00002-of-00002.safetensors");
+        write_shard(&shard_1, "a", &[1.0, 2.0]);
+        write_shard(&shard_2, "b", &[3.0]);
+
+        let index_path = dir.path().join("model.safetensors.index.json");
+        std::fs::write(
+            &index_path,
+            r#"{"metadata": {}, "weight_map": {
+                "a": "model-00001-of-00002.safetensors",
+                "b": "model-00002-of-00002.safetensors"
+            }}"#,
+        )
+        .unwrap();
+
+        let weights = PureRustBackend::load_sharded(dir.path(), &index_path).unwrap();
+        assert_eq!(weights.len(), 2);
+        assert_eq!(weights["a"], vec![1.0, 2.0]);
+        assert_eq!(weights["b"], vec![3.0]);
+    }
+
+    #[test]
+    fn test_load_sharded_missing_shard() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let index_path = dir.path().join("model.safetensors.index.json");
+        std::fs::write(
+            &index_path,
+            r#"{"weight_map": {"a": "model-00001-of-00001.safetensors"}}"#,
+        )
+        .unwrap();
+
+        let result = PureRustBackend::load_sharded(dir.path(), &index_path);
+        assert!(matches!(result, Err(MinervaError::ModelNotFound(_))));
+    }
 }
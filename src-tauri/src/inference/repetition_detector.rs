@@ -0,0 +1,204 @@
+//! Repetition Loop Detection
+//!
+//! Models sometimes fall into a loop, emitting the same phrase until
+//! `max_tokens` runs out. `RepetitionDetector` watches the last
+//! `window_size` generated tokens and reports a loop once the tail of the
+//! window is the same block of tokens repeated `MIN_REPEATS` or more times,
+//! covering at least `MIN_LOOP_TOKENS` tokens. Short blocks need more copies,
+//! so a blank line or a run of zeros in a table is not cut off.
+//!
+//! Two blocks count as repeats when the share of positions holding the same
+//! token reaches `threshold`: 1.0 accepts only exact repeats, 0.0 accepts any
+//! pair of blocks sharing at least one token at the same position.
+
+/// Finish reason reported when generation stops on a loop
+pub const FINISH_REASON_REPETITION: &str = "repetition";
+
+/// Consecutive copies of a block needed to call it a loop
+pub const MIN_REPEATS: usize = 3;
+
+/// Tokens the repeated copies must span together before they count as a loop
+pub const MIN_LOOP_TOKENS: usize = 12;
+
+/// Window used by generation loops
+pub const DEFAULT_WINDOW_SIZE: usize = 64;
+
+/// Sliding-window detector for repeated token patterns
+#[derive(Debug, Clone)]
+pub struct RepetitionDetector {
+    /// Ring buffer of the last `window_size` tokens; `next` is the slot the
+    /// next token overwrites once it is full
+    ring: Vec<u32>,
+    next: usize,
+    window_size: usize,
+    threshold: f32,
+    halted: bool,
+}
+
+impl RepetitionDetector {
+    pub fn new(window_size: usize, threshold: f32) -> Self {
+        Self {
+            ring: Vec::with_capacity(window_size),
+            next: 0,
+            window_size,
+            threshold: threshold.clamp(0.0, 1.0),
+            halted: false,
+        }
+    }
+
+    /// Record a generated token; returns true once generation should halt
+    pub fn push(&mut self, token: u32) -> bool {
+        if self.window_size == 0 {
+            return false;
+        }
+        if self.ring.len() < self.window_size {
+            self.ring.push(token);
+        } else {
+            self.ring[self.next] = token;
+        }
+        self.next = (self.next + 1) % self.window_size;
+        if !self.halted {
+            self.halted = self.find_period().is_some();
+        }
+        self.halted
+    }
+
+    /// Length of the shortest block repeated at the end of the window
+    pub fn find_period(&self) -> Option<usize> {
+        (1..=self.ring.len() / MIN_REPEATS).find(|&period| self.repeats(period))
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// `"repetition"` once a loop has been detected
+    pub fn finish_reason(&self) -> Option<&'static str> {
+        self.halted.then_some(FINISH_REASON_REPETITION)
+    }
+
+    /// Forget every token, e.g. before the next request
+    pub fn reset(&mut self) {
+        self.ring.clear();
+        self.next = 0;
+        self.halted = false;
+    }
+
+    /// Token `i` positions after the oldest one in the window
+    fn token(&self, i: usize) -> u32 {
+        let oldest = if self.ring.len() < self.window_size {
+            0
+        } else {
+            self.next
+        };
+        self.ring[(oldest + i) % self.ring.len()]
+    }
+
+    /// Whether the last copies of `period` tokens, enough of them to cover
+    /// `MIN_LOOP_TOKENS`, all match the final block
+    fn repeats(&self, period: usize) -> bool {
+        let copies = MIN_REPEATS.max(MIN_LOOP_TOKENS.div_ceil(period));
+        let len = self.ring.len();
+        if copies * period > len {
+            return false;
+        }
+        let last = len - period;
+        (2..=copies).all(|copy| {
+            let start = len - copy * period;
+            let same = (0..period)
+                .filter(|&i| self.token(start + i) == self.token(last + i))
+                .count();
+            same > 0 && same as f32 / period as f32 >= self.threshold
+        })
+    }
+}
+
+impl Default for RepetitionDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW_SIZE, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(detector: &mut RepetitionDetector, tokens: &[u32]) -> Option<usize> {
+        tokens.iter().position(|&t| detector.push(t))
+    }
+
+    #[test]
+    fn test_detects_phrase_repeated_three_times() {
+        let mut detector = RepetitionDetector::new(32, 1.0);
+        let phrase = [10, 11, 12, 13, 14];
+        let tokens: Vec<u32> = phrase.iter().cycle().take(20).copied().collect();
+
+        assert_eq!(feed(&mut detector, &tokens), Some(14));
+        assert_eq!(detector.find_period(), Some(5));
+        assert_eq!(detector.finish_reason(), Some("repetition"));
+    }
+
+    #[test]
+    fn test_varied_text_does_not_halt() {
+        let mut detector = RepetitionDetector::new(32, 1.0);
+        let tokens: Vec<u32> = (0..100).collect();
+        assert_eq!(feed(&mut detector, &tokens), None);
+        assert_eq!(detector.finish_reason(), None);
+    }
+
+    #[test]
+    fn test_two_copies_are_not_a_loop() {
+        let mut detector = RepetitionDetector::new(32, 1.0);
+        assert_eq!(feed(&mut detector, &[1, 2, 3, 4, 1, 2, 3, 4]), None);
+    }
+
+    #[test]
+    fn test_threshold_controls_near_repeats() {
+        // Every copy differs from the last one in one of four positions
+        let tokens = [1, 2, 3, 7, 1, 2, 3, 8, 1, 2, 3, 9];
+
+        let mut exact = RepetitionDetector::new(16, 1.0);
+        assert_eq!(feed(&mut exact, &tokens), None);
+
+        let mut loose = RepetitionDetector::new(16, 0.75);
+        assert_eq!(feed(&mut loose, &tokens), Some(11));
+    }
+
+    #[test]
+    fn test_window_limits_detection() {
+        let mut detector = RepetitionDetector::new(8, 1.0);
+        let phrase = [1, 2, 3, 4];
+        let tokens: Vec<u32> = phrase.iter().cycle().take(40).copied().collect();
+        assert_eq!(feed(&mut detector, &tokens), None);
+    }
+
+    #[test]
+    fn test_reset_clears_halt() {
+        let mut detector = RepetitionDetector::new(16, 1.0);
+        assert!(feed(&mut detector, &[5; MIN_LOOP_TOKENS]).is_some());
+
+        detector.reset();
+        assert!(!detector.is_halted());
+        assert_eq!(detector.finish_reason(), None);
+    }
+
+    #[test]
+    fn test_short_runs_are_not_a_loop() {
+        // "\n\n\n" or "000": one token three times
+        let mut detector = RepetitionDetector::default();
+        assert_eq!(feed(&mut detector, &[7, 13, 13, 13, 8]), None);
+
+        let mut detector = RepetitionDetector::default();
+        let run = [13; MIN_LOOP_TOKENS];
+        assert_eq!(feed(&mut detector, &run), Some(MIN_LOOP_TOKENS - 1));
+    }
+
+    #[test]
+    fn test_detects_loop_after_ring_wraps() {
+        let mut detector = RepetitionDetector::new(16, 1.0);
+        let mut tokens: Vec<u32> = (100..140).collect();
+        tokens.extend([1, 2, 3, 4].iter().cycle().take(12));
+        assert_eq!(feed(&mut detector, &tokens), Some(tokens.len() - 1));
+        assert_eq!(detector.find_period(), Some(4));
+    }
+}
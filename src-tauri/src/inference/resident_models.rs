@@ -0,0 +1,150 @@
+/// Resident Model Slots
+///
+/// Tracks which backends currently hold model weights and how much memory
+/// each one booked in the shared `GpuContext`, so memory can be reclaimed by
+/// unloading models. Pinned models are never chosen for eviction.
+/// `cpu-only` builds keep the backends but book no memory.
+#[cfg(not(cpu_only))]
+use super::gpu_context::GpuContext;
+use super::inference_backend_trait::InferenceBackend;
+use super::model_registry::ModelRegistry;
+use crate::error::{MinervaError, MinervaResult};
+use std::collections::{HashMap, HashSet};
+#[cfg(not(cpu_only))]
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A loaded backend and the memory booked for it
+pub struct ResidentModel {
+    backend: Box<dyn InferenceBackend>,
+    bytes: usize,
+}
+
+impl ResidentModel {
+    pub fn new(backend: Box<dyn InferenceBackend>, bytes: usize) -> Self {
+        Self { backend, bytes }
+    }
+}
+
+/// Loaded models keyed by model ID
+#[cfg_attr(cpu_only, derive(Default))]
+pub struct ResidentModels {
+    #[cfg(not(cpu_only))]
+    gpu: Arc<Mutex<GpuContext>>,
+    models: HashMap<String, ResidentModel>,
+    pinned: HashSet<String>,
+}
+
+impl ResidentModels {
+    #[cfg(not(cpu_only))]
+    pub fn new(gpu: Arc<Mutex<GpuContext>>) -> Self {
+        Self {
+            gpu,
+            models: HashMap::new(),
+            pinned: HashSet::new(),
+        }
+    }
+
+    /// Book the model's memory and keep its backend
+    pub fn insert(&mut self, model_id: &str, model: ResidentModel) -> MinervaResult<()> {
+        #[cfg(not(cpu_only))]
+        self.gpu()?.allocate(model.bytes)?;
+        if let Some(previous) = self.models.insert(model_id.to_string(), model) {
+            self.release(previous)?;
+        }
+        Ok(())
+    }
+
+    /// Unload `model_id` and free its memory, returning the bytes freed
+    pub fn evict(&mut self, model_id: &str) -> MinervaResult<usize> {
+        let model = self.models.remove(model_id).ok_or_else(|| {
+            MinervaError::ModelNotFound(format!("Model not loaded: {}", model_id))
+        })?;
+        self.release(model)
+    }
+
+    /// Loaded, unpinned model with the oldest last request
+    ///
+    /// Models the registry has never seen a request for count as coldest.
+    pub fn coldest_unpinned(&self, registry: &ModelRegistry) -> Option<String> {
+        self.models
+            .keys()
+            .filter(|id| !self.pinned.contains(*id))
+            .min_by_key(|id| {
+                let last_used = registry.get(id).and_then(|m| m.last_accessed);
+                (last_used.unwrap_or(0), (*id).clone())
+            })
+            .cloned()
+    }
+
+    pub fn set_pinned(&mut self, model_id: &str, pinned: bool) {
+        if pinned {
+            self.pinned.insert(model_id.to_string());
+        } else {
+            self.pinned.remove(model_id);
+        }
+    }
+
+    pub fn is_pinned(&self, model_id: &str) -> bool {
+        self.pinned.contains(model_id)
+    }
+
+    pub fn contains(&self, model_id: &str) -> bool {
+        self.models.contains_key(model_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.models.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// The GPU context resident models are booked against
+    #[cfg(not(cpu_only))]
+    pub fn gpu_context(&self) -> Arc<Mutex<GpuContext>> {
+        Arc::clone(&self.gpu)
+    }
+
+    /// Memory booked in the GPU context, by resident models or otherwise
+    #[cfg(not(cpu_only))]
+    pub fn allocated_bytes(&self) -> MinervaResult<usize> {
+        Ok(self.gpu()?.allocated_memory())
+    }
+
+    /// Memory still available in the GPU context
+    #[cfg(not(cpu_only))]
+    pub fn available_bytes(&self) -> MinervaResult<usize> {
+        Ok(self.gpu()?.available_memory())
+    }
+
+    fn release(&self, mut model: ResidentModel) -> MinervaResult<usize> {
+        model.backend.unload_model();
+        #[cfg(not(cpu_only))]
+        self.gpu()?.deallocate(model.bytes)?;
+        Ok(model.bytes)
+    }
+
+    #[cfg(not(cpu_only))]
+    fn gpu(&self) -> MinervaResult<MutexGuard<'_, GpuContext>> {
+        self.gpu
+            .lock()
+            .map_err(|_| MinervaError::InferenceError("GPU context lock poisoned".to_string()))
+    }
+}
+
+#[cfg(not(cpu_only))]
+impl Default for ResidentModels {
+    fn default() -> Self {
+        Self::new(Arc::new(Mutex::new(GpuContext::default())))
+    }
+}
+
+impl std::fmt::Debug for ResidentModels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResidentModels")
+            .field("models", &self.models.keys().collect::<Vec<_>>())
+            .field("pinned", &self.pinned)
+            .finish()
+    }
+}
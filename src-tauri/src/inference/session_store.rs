@@ -0,0 +1,197 @@
+/// Per-Session Conversation State
+///
+/// Concurrent users each get their own `SessionContext`, so one user's KV
+/// cache is never overwritten by another's. A new session starts from a
+/// clone of its model's base context (the system prompt prefix already
+/// encoded in the cache), which costs one copy at creation rather than one
+/// per token. When more than `max_sessions` are live, the least recently
+/// used session is evicted.
+use super::context_snapshot::SessionContext;
+use crate::error::{MinervaError, MinervaResult};
+use std::collections::HashMap;
+
+/// Sessions kept before LRU eviction kicks in
+pub const DEFAULT_MAX_SESSIONS: usize = 64;
+
+#[derive(Debug)]
+struct TrackedSession {
+    context: SessionContext,
+    last_used: u64,
+}
+
+/// Session contexts keyed by session id, bounded by LRU eviction
+#[derive(Debug)]
+pub struct SessionStore {
+    sessions: HashMap<String, TrackedSession>,
+    bases: HashMap<String, SessionContext>,
+    max_sessions: usize,
+    clock: u64,
+}
+
+impl SessionStore {
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            bases: HashMap::new(),
+            max_sessions: max_sessions.max(1),
+            clock: 0,
+        }
+    }
+
+    /// Register the prefix state new sessions for `base.model_id` start from
+    pub fn set_base(&mut self, base: SessionContext) {
+        self.bases.insert(base.model_id.clone(), base);
+    }
+
+    /// Whether `model_id` has a base context to start sessions from
+    pub fn has_base(&self, model_id: &str) -> bool {
+        self.bases.contains_key(model_id)
+    }
+
+    /// Start `session_id` from a snapshot of `model_id`'s base context
+    pub fn create(
+        &mut self,
+        session_id: &str,
+        model_id: &str,
+    ) -> MinervaResult<&mut SessionContext> {
+        let base = self.bases.get(model_id).cloned().ok_or_else(|| {
+            MinervaError::InvalidRequest(format!("No base context for model: {}", model_id))
+        })?;
+        self.insert(session_id, base);
+        Ok(self.get_mut(session_id).expect("session just inserted"))
+    }
+
+    /// Register or replace a session, evicting the LRU one if over capacity
+    pub fn insert(&mut self, session_id: &str, context: SessionContext) {
+        let last_used = self.tick();
+        self.sessions.insert(
+            session_id.to_string(),
+            TrackedSession { context, last_used },
+        );
+        while self.sessions.len() > self.max_sessions {
+            self.evict_least_recently_used();
+        }
+    }
+
+    /// Session state without marking it as used
+    pub fn get(&self, session_id: &str) -> Option<&SessionContext> {
+        self.sessions.get(session_id).map(|s| &s.context)
+    }
+
+    /// Session state for the next turn, marking it as most recently used
+    pub fn get_mut(&mut self, session_id: &str) -> Option<&mut SessionContext> {
+        let now = self.tick();
+        self.sessions.get_mut(session_id).map(|s| {
+            s.last_used = now;
+            &mut s.context
+        })
+    }
+
+    /// Drop a session, returning its state
+    pub fn remove(&mut self, session_id: &str) -> Option<SessionContext> {
+        self.sessions.remove(session_id).map(|s| s.context)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let lru_id = self
+            .sessions
+            .iter()
+            .min_by_key(|(_, s)| s.last_used)
+            .map(|(id, _)| id.clone());
+        if let Some(id) = lru_id {
+            self.sessions.remove(&id);
+            tracing::info!("Least recently used session evicted: {}", id);
+        }
+    }
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SESSIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::kv_cache::{KVCache, KVCacheConfig, KVStoreParams};
+
+    fn base_context(model_id: &str) -> SessionContext {
+        let mut kv_cache = KVCache::new(KVCacheConfig {
+            num_layers: 1,
+            max_seq_len: 4,
+            num_heads: 1,
+            head_dim: 2,
+        });
+        kv_cache
+            .store(KVStoreParams::builder(vec![1.0, 2.0], vec![3.0, 4.0]).build())
+            .unwrap();
+        SessionContext {
+            model_id: model_id.to_string(),
+            kv_cache,
+            messages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sessions_do_not_share_kv_state() {
+        let mut store = SessionStore::new(4);
+        store.set_base(base_context("tiny"));
+        store.create("alice", "tiny").unwrap();
+        store.create("bob", "tiny").unwrap();
+
+        store
+            .get_mut("alice")
+            .unwrap()
+            .kv_cache
+            .store(
+                KVStoreParams::builder(vec![9.0, 9.0], vec![9.0, 9.0])
+                    .pos(1)
+                    .build(),
+            )
+            .unwrap();
+
+        let bob = store.get("bob").unwrap();
+        assert_eq!(bob.kv_cache.get(0, 0).unwrap().0, vec![1.0, 2.0]);
+        assert_ne!(bob.kv_cache.get(0, 1).unwrap().0, vec![9.0, 9.0]);
+    }
+
+    #[test]
+    fn test_create_without_base_fails() {
+        let mut store = SessionStore::default();
+        assert!(store.create("alice", "unknown").is_err());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut store = SessionStore::new(2);
+        store.set_base(base_context("tiny"));
+        store.create("a", "tiny").unwrap();
+        store.create("b", "tiny").unwrap();
+        store.get_mut("a");
+        store.create("c", "tiny").unwrap();
+
+        assert_eq!(store.len(), 2);
+        assert!(store.get("a").is_some());
+        assert!(store.get("b").is_none());
+        assert!(store.get("c").is_some());
+    }
+}
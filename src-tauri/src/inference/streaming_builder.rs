@@ -37,6 +37,7 @@ impl StreamingResponse {
                 },
                 finish_reason: None,
             }],
+            usage: None,
         }
     }
 
@@ -56,10 +57,15 @@ impl StreamingResponse {
                 },
                 finish_reason: Some("stop".to_string()),
             }],
+            usage: None,
         }
     }
 
     /// Convert chunk to SSE (Server-Sent Event) format
+    ///
+    /// The chunk is serialized as compact JSON, which escapes newlines in
+    /// token text as `\n`, so the frame is one `data:` line followed by the
+    /// blank line that terminates the event.
     #[allow(dead_code)]
     pub fn to_sse_string(chunk: &ChatCompletionChunk) -> String {
         let json = serde_json::to_string(chunk).unwrap_or_else(|_| "{}".to_string());
@@ -128,4 +134,16 @@ mod tests {
         assert!(sse.ends_with("\n\n"));
         assert!(sse.contains("chat.completion.chunk"));
     }
+
+    #[test]
+    fn test_sse_format_escapes_newlines_in_tokens() {
+        let response = StreamingResponse::new("test-model".to_string());
+        let chunk = response.chunk("line one\nline two\r\n", 0);
+        let sse = StreamingResponse::to_sse_string(&chunk);
+
+        assert_eq!(sse.matches('\n').count(), 2);
+        assert!(sse.ends_with("\n\n"));
+        assert!(!sse.contains('\r'));
+        assert!(sse.contains(r"line one\nline two\r\n"));
+    }
 }
@@ -0,0 +1,185 @@
+/// System Memory Reporting
+///
+/// Free physical memory per platform: free + purgeable pages from
+/// `host_statistics64` on macOS, `MemAvailable` from /proc/meminfo on Linux,
+/// and `GlobalMemoryStatusEx` on Windows. `None` when it cannot be read.
+/// Readings are cached briefly, since callers poll on every allocation check.
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a reading is reused before the platform is asked again
+const READING_TTL: Duration = Duration::from_secs(1);
+
+static LAST_READING: Mutex<Option<(Instant, Option<u64>)>> = Mutex::new(None);
+
+/// Parse `MemAvailable:  8123456 kB` from /proc/meminfo
+pub fn parse_meminfo_available(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Free physical memory in bytes, at most `READING_TTL` old
+pub fn available_memory_bytes() -> Option<u64> {
+    let mut last = LAST_READING.lock();
+    match *last {
+        Some((read_at, bytes)) if read_at.elapsed() < READING_TTL => bytes,
+        _ => {
+            let bytes = read_available_memory();
+            *last = Some((Instant::now(), bytes));
+            bytes
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_available_memory() -> Option<u64> {
+    use std::ffi::{c_int, c_uint};
+
+    /// `vm_statistics64` from <mach/vm_statistics.h>
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)] // filled in by the kernel; only two counts are read
+    struct VmStatistics64 {
+        free_count: u32,
+        active_count: u32,
+        inactive_count: u32,
+        wire_count: u32,
+        zero_fill_count: u64,
+        reactivations: u64,
+        pageins: u64,
+        pageouts: u64,
+        faults: u64,
+        cow_faults: u64,
+        lookups: u64,
+        hits: u64,
+        purges: u64,
+        purgeable_count: u32,
+        speculative_count: u32,
+        decompressions: u64,
+        compressions: u64,
+        swapins: u64,
+        swapouts: u64,
+        compressor_page_count: u32,
+        throttled_count: u32,
+        external_page_count: u32,
+        internal_page_count: u32,
+        total_uncompressed_pages_in_compressor: u64,
+    }
+
+    const HOST_VM_INFO64: c_int = 4;
+    const KERN_SUCCESS: c_int = 0;
+
+    unsafe extern "C" {
+        fn mach_host_self() -> c_uint;
+        fn host_statistics64(
+            host: c_uint,
+            flavor: c_int,
+            info: *mut c_int,
+            count: *mut c_uint,
+        ) -> c_int;
+    }
+
+    let mut page_size: u64 = 0;
+    let mut len = std::mem::size_of::<u64>();
+    // SAFETY: `hw.pagesize` is a 64-bit integer and `len` is the size of
+    // the buffer it is written into.
+    let read = unsafe {
+        libc::sysctlbyname(
+            c"hw.pagesize".as_ptr(),
+            (&mut page_size as *mut u64).cast(),
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if read != 0 || page_size == 0 {
+        return None;
+    }
+
+    let mut stats = VmStatistics64::default();
+    let mut count =
+        (std::mem::size_of::<VmStatistics64>() / std::mem::size_of::<c_int>()) as c_uint;
+    // SAFETY: `stats` is a correctly laid out `vm_statistics64` and `count`
+    // is its size in `integer_t` units, as HOST_VM_INFO64 expects.
+    let status = unsafe {
+        host_statistics64(
+            mach_host_self(),
+            HOST_VM_INFO64,
+            (&mut stats as *mut VmStatistics64).cast(),
+            &mut count,
+        )
+    };
+    (status == KERN_SUCCESS)
+        .then(|| (u64::from(stats.free_count) + u64::from(stats.purgeable_count)) * page_size)
+}
+
+#[cfg(target_os = "linux")]
+fn read_available_memory() -> Option<u64> {
+    parse_meminfo_available(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+#[cfg(windows)]
+fn read_available_memory() -> Option<u64> {
+    global_memory_status().map(|(_, available)| available)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn read_available_memory() -> Option<u64> {
+    None
+}
+
+/// `(total, available)` physical bytes from `GlobalMemoryStatusEx`
+#[cfg(windows)]
+pub(crate) fn global_memory_status() -> Option<(u64, u64)> {
+    #[repr(C)]
+    struct MemoryStatusEx {
+        length: u32,
+        memory_load: u32,
+        total_phys: u64,
+        avail_phys: u64,
+        total_page_file: u64,
+        avail_page_file: u64,
+        total_virtual: u64,
+        avail_virtual: u64,
+        avail_extended_virtual: u64,
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GlobalMemoryStatusEx(buffer: *mut MemoryStatusEx) -> i32;
+    }
+
+    let mut status = MemoryStatusEx {
+        length: std::mem::size_of::<MemoryStatusEx>() as u32,
+        memory_load: 0,
+        total_phys: 0,
+        avail_phys: 0,
+        total_page_file: 0,
+        avail_page_file: 0,
+        total_virtual: 0,
+        avail_virtual: 0,
+        avail_extended_virtual: 0,
+    };
+    // SAFETY: `status` is a correctly sized MEMORYSTATUSEX with `length` set.
+    let ok = unsafe { GlobalMemoryStatusEx(&mut status) };
+    (ok != 0).then_some((status.total_phys, status.avail_phys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo_available() {
+        let meminfo = "MemTotal: 16318412 kB\nMemAvailable:    8000 kB\n";
+        assert_eq!(parse_meminfo_available(meminfo), Some(8000 * 1024));
+        assert_eq!(parse_meminfo_available("MemTotal: 1 kB"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_available_memory_reported_on_linux() {
+        assert!(available_memory_bytes().is_some_and(|bytes| bytes > 0));
+    }
+}
@@ -0,0 +1,237 @@
+/// Text Pipeline
+///
+/// Chains string transformations around generation: the output of each
+/// stage is the input of the next. Typical pipelines trim the prompt to the
+/// context window, generate with a backend, then pull structured output
+/// such as `<json>...</json>` out of the completion and validate it.
+use super::inference_backend_trait::{GenerationParams, InferenceBackend};
+use crate::error::{MinervaError, MinervaResult};
+use std::sync::Arc;
+
+/// One transformation in a `TextPipeline`
+pub trait PipelineStage: Send + Sync {
+    /// Transform this stage's input into the next stage's input
+    fn transform(&self, input: &str) -> MinervaResult<String>;
+
+    /// Name used in logs
+    fn name(&self) -> &str {
+        "custom"
+    }
+}
+
+impl<F> PipelineStage for F
+where
+    F: Fn(&str) -> MinervaResult<String> + Send + Sync,
+{
+    fn transform(&self, input: &str) -> MinervaResult<String> {
+        self(input)
+    }
+}
+
+/// Ordered stages, run one after another by `run`
+#[derive(Default)]
+pub struct TextPipeline {
+    stages: Vec<Box<dyn PipelineStage>>,
+}
+
+impl TextPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `stage` after the existing stages
+    pub fn with_stage(mut self, stage: impl PipelineStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Number of stages
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Feed `prompt` through every stage, stopping at the first error
+    pub fn run(&self, prompt: &str) -> MinervaResult<String> {
+        self.stages
+            .iter()
+            .try_fold(prompt.to_string(), |text, stage| {
+                tracing::debug!("Pipeline stage {} on {} chars", stage.name(), text.len());
+                stage.transform(&text)
+            })
+    }
+}
+
+/// Generate a completion for the input with a loaded backend
+pub struct GenerationStage {
+    backend: Arc<dyn InferenceBackend>,
+    params: GenerationParams,
+}
+
+impl GenerationStage {
+    pub fn new(backend: Arc<dyn InferenceBackend>, params: GenerationParams) -> Self {
+        Self { backend, params }
+    }
+}
+
+impl PipelineStage for GenerationStage {
+    fn transform(&self, input: &str) -> MinervaResult<String> {
+        self.backend.generate(input, self.params)
+    }
+
+    fn name(&self) -> &str {
+        "generate"
+    }
+}
+
+/// Keep the last `max_chars` characters, dropping the oldest context
+pub struct TruncateStage {
+    max_chars: usize,
+}
+
+impl TruncateStage {
+    pub fn new(max_chars: usize) -> Self {
+        Self { max_chars }
+    }
+}
+
+impl PipelineStage for TruncateStage {
+    fn transform(&self, input: &str) -> MinervaResult<String> {
+        let excess = input.chars().count().saturating_sub(self.max_chars);
+        let start = input
+            .char_indices()
+            .nth(excess)
+            .map_or(input.len(), |(i, _)| i);
+        Ok(input[start..].to_string())
+    }
+
+    fn name(&self) -> &str {
+        "truncate"
+    }
+}
+
+/// Contents of the first `<tag>...</tag>` pair, trimmed
+pub struct ExtractTagStage {
+    open: String,
+    close: String,
+}
+
+impl ExtractTagStage {
+    pub fn new(tag: &str) -> Self {
+        Self {
+            open: format!("<{}>", tag),
+            close: format!("</{}>", tag),
+        }
+    }
+}
+
+impl PipelineStage for ExtractTagStage {
+    fn transform(&self, input: &str) -> MinervaResult<String> {
+        let missing = || MinervaError::ValidationError(format!("No {} block in output", self.open));
+        let start = input.find(&self.open).ok_or_else(missing)? + self.open.len();
+        let len = input[start..].find(&self.close).ok_or_else(missing)?;
+        Ok(input[start..start + len].trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        "extract_tag"
+    }
+}
+
+/// Pass the input through unchanged if it parses as JSON
+pub struct ValidateJsonStage;
+
+impl PipelineStage for ValidateJsonStage {
+    fn transform(&self, input: &str) -> MinervaResult<String> {
+        serde_json::from_str::<serde_json::Value>(input)?;
+        Ok(input.to_string())
+    }
+
+    fn name(&self) -> &str {
+        "validate_json"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::mock_backend::MockBackend;
+
+    fn fake_model(prompt: &str) -> MinervaResult<String> {
+        Ok(format!(
+            "Sure. <json>{{\"prompt\": \"{}\"}}</json> Done.",
+            prompt
+        ))
+    }
+
+    #[test]
+    fn test_stages_run_in_order() {
+        let pipeline = TextPipeline::new()
+            .with_stage(TruncateStage::new(5))
+            .with_stage(fake_model)
+            .with_stage(ExtractTagStage::new("json"))
+            .with_stage(ValidateJsonStage);
+
+        assert_eq!(pipeline.len(), 4);
+        assert_eq!(
+            pipeline.run("ignored hello").unwrap(),
+            "{\"prompt\": \"hello\"}"
+        );
+    }
+
+    #[test]
+    fn test_empty_pipeline_returns_prompt() {
+        let pipeline = TextPipeline::new();
+        assert!(pipeline.is_empty());
+        assert_eq!(pipeline.run("as is").unwrap(), "as is");
+    }
+
+    #[test]
+    fn test_truncate_keeps_tail_on_char_boundaries() {
+        let stage = TruncateStage::new(3);
+        assert_eq!(stage.transform("héllo wörld").unwrap(), "rld");
+        assert_eq!(stage.transform("wö").unwrap(), "wö");
+        assert_eq!(TruncateStage::new(0).transform("abc").unwrap(), "");
+    }
+
+    #[test]
+    fn test_missing_tag_stops_the_pipeline() {
+        let pipeline = TextPipeline::new()
+            .with_stage(ExtractTagStage::new("json"))
+            .with_stage(|_: &str| -> MinervaResult<String> { panic!("should not run") });
+
+        let err = pipeline.run("no block <json> here").unwrap_err();
+        assert!(matches!(err, MinervaError::ValidationError(_)));
+    }
+
+    #[test]
+    fn test_invalid_json_is_rejected() {
+        assert!(ValidateJsonStage.transform("{\"a\": 1}").is_ok());
+        assert!(matches!(
+            ValidateJsonStage.transform("{a: 1"),
+            Err(MinervaError::JsonError(_))
+        ));
+    }
+
+    #[test]
+    fn test_generation_stage_uses_backend() {
+        let params = GenerationParams {
+            max_tokens: 16,
+            temperature: 0.0,
+            top_p: 1.0,
+        };
+        let unloaded = GenerationStage::new(Arc::new(MockBackend::new()), params);
+        assert!(TextPipeline::new().with_stage(unloaded).run("hi").is_err());
+
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = MockBackend::new();
+        backend.load_model(model.path(), 512).unwrap();
+        let pipeline = TextPipeline::new()
+            .with_stage(TruncateStage::new(100))
+            .with_stage(GenerationStage::new(Arc::new(backend), params));
+        assert!(!pipeline.run("hello").unwrap().is_empty());
+    }
+}
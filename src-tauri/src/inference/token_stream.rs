@@ -1,5 +1,18 @@
+use super::inference_backend_trait::{GenerationParams, InferenceBackend};
 pub use super::token_callback::TokenCallback;
-use std::sync::{Arc, Mutex};
+use crate::error::MinervaResult;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Outcome of `TokenStream::collect_with_timeout`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectResult {
+    /// Generation finished within the timeout
+    Complete(String),
+    /// Output so far and the number of expected tokens still missing
+    Partial(String, usize),
+}
 
 /// Real token stream from llama.cpp
 #[derive(Clone)]
@@ -8,6 +21,10 @@ pub struct TokenStream {
     tokens: Arc<Mutex<Vec<String>>>,
     current_index: Arc<Mutex<usize>>,
     callback: Option<Arc<Mutex<Option<TokenCallback>>>>,
+    /// Signalled on every pushed token and on `finish`; pairs with `tokens`
+    progress: Arc<Condvar>,
+    finished: Arc<AtomicBool>,
+    expected_tokens: Option<usize>,
 }
 
 impl std::fmt::Debug for TokenStream {
@@ -16,6 +33,7 @@ impl std::fmt::Debug for TokenStream {
             .field("tokens_count", &self.tokens.lock().unwrap().len())
             .field("current_index", &self.current_index.lock().unwrap())
             .field("has_callback", &self.callback.is_some())
+            .field("finished", &self.finished.load(Ordering::Acquire))
             .finish()
     }
 }
@@ -27,6 +45,9 @@ impl TokenStream {
             tokens: Arc::new(Mutex::new(Vec::new())),
             current_index: Arc::new(Mutex::new(0)),
             callback: None,
+            progress: Arc::new(Condvar::new()),
+            finished: Arc::new(AtomicBool::new(false)),
+            expected_tokens: None,
         }
     }
 
@@ -36,9 +57,19 @@ impl TokenStream {
             tokens: Arc::new(Mutex::new(Vec::new())),
             current_index: Arc::new(Mutex::new(0)),
             callback: Some(Arc::new(Mutex::new(Some(callback)))),
+            ..Self::new()
         }
     }
 
+    /// Expect `count` tokens (e.g. the request's `max_tokens`)
+    ///
+    /// The stream counts as complete once that many have arrived, and partial
+    /// collections report how many are still missing.
+    pub fn with_expected_tokens(mut self, count: usize) -> Self {
+        self.expected_tokens = Some(count);
+        self
+    }
+
     /// Add token to stream (called from llama.cpp callback)
     #[allow(clippy::collapsible_if)]
     pub fn push_token(&self, token: String) {
@@ -53,6 +84,60 @@ impl TokenStream {
                 }
             }
         }
+        self.progress.notify_all();
+    }
+
+    /// Mark generation as done, waking any `collect_with_timeout` caller
+    pub fn finish(&self) {
+        let _tokens = self.tokens.lock().unwrap();
+        self.finished.store(true, Ordering::Release);
+        self.progress.notify_all();
+    }
+
+    /// Run `backend` on `prompt`, pushing each generated piece
+    ///
+    /// The stream is finished when generation returns, fails or panics, so
+    /// `collect_with_timeout` callers are released as soon as it ends.
+    pub fn generate_from(
+        &self,
+        backend: &dyn InferenceBackend,
+        prompt: &str,
+        params: GenerationParams,
+    ) -> MinervaResult<String> {
+        let _finish = FinishOnDrop(self);
+        backend.generate_stream(prompt, params, &mut |piece| {
+            self.push_token(piece.to_string())
+        })
+    }
+
+    /// Wait up to `timeout` for generation to finish and join the tokens
+    ///
+    /// A stalled backend yields `Partial` with whatever arrived; the missing
+    /// count is only known when the stream was built `with_expected_tokens`,
+    /// and is 0 otherwise.
+    pub fn collect_with_timeout(&self, timeout: Duration) -> CollectResult {
+        let tokens = self.tokens.lock().unwrap();
+        let (tokens, _) = self
+            .progress
+            .wait_timeout_while(tokens, timeout, |tokens| !self.is_complete(tokens.len()))
+            .unwrap();
+
+        let text = tokens.join("");
+        if self.is_complete(tokens.len()) {
+            CollectResult::Complete(text)
+        } else {
+            let remaining = self
+                .expected_tokens
+                .map_or(0, |expected| expected.saturating_sub(tokens.len()));
+            CollectResult::Partial(text, remaining)
+        }
+    }
+
+    fn is_complete(&self, received: usize) -> bool {
+        self.finished.load(Ordering::Acquire)
+            || self
+                .expected_tokens
+                .is_some_and(|expected| received >= expected)
     }
 
     /// Get next token
@@ -99,6 +184,15 @@ impl TokenStream {
     }
 }
 
+/// Finishes the stream when generation unwinds or returns
+struct FinishOnDrop<'a>(&'a TokenStream);
+
+impl Drop for FinishOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
 impl Default for TokenStream {
     fn default() -> Self {
         Self::new()
@@ -108,6 +202,7 @@ impl Default for TokenStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inference::mock_backend::MockBackend;
 
     #[test]
     fn test_token_stream_creation() {
@@ -205,6 +300,77 @@ mod tests {
         assert_eq!(*tokens, vec!["hello", " ", "world"]);
     }
 
+    #[test]
+    fn test_collect_with_timeout_complete() {
+        let stream = TokenStream::new();
+        let producer = stream.clone();
+        let handle = std::thread::spawn(move || {
+            producer.push_token("hello".to_string());
+            producer.push_token(" world".to_string());
+            producer.finish();
+        });
+
+        let result = stream.collect_with_timeout(Duration::from_secs(5));
+        handle.join().unwrap();
+        assert_eq!(result, CollectResult::Complete("hello world".to_string()));
+    }
+
+    #[test]
+    fn test_collect_with_timeout_partial_on_stall() {
+        let stream = TokenStream::new().with_expected_tokens(5);
+        stream.push_token("a".to_string());
+        stream.push_token("b".to_string());
+
+        let result = stream.collect_with_timeout(Duration::from_millis(20));
+        assert_eq!(result, CollectResult::Partial("ab".to_string(), 3));
+    }
+
+    #[test]
+    fn test_collect_with_timeout_complete_at_expected_count() {
+        let stream = TokenStream::new().with_expected_tokens(1);
+        stream.push_token("done".to_string());
+
+        let result = stream.collect_with_timeout(Duration::from_millis(20));
+        assert_eq!(result, CollectResult::Complete("done".to_string()));
+    }
+
+    fn params() -> GenerationParams {
+        GenerationParams {
+            max_tokens: 8,
+            temperature: 0.7,
+            top_p: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_generate_from_finishes_stream() {
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = MockBackend::new();
+        backend.load_model(model.path(), 512).unwrap();
+
+        let stream = TokenStream::new();
+        let producer = stream.clone();
+        let handle =
+            std::thread::spawn(move || producer.generate_from(&backend, "Hello", params()));
+
+        let result = stream.collect_with_timeout(Duration::from_secs(5));
+        let text = handle.join().unwrap().unwrap();
+        assert!(matches!(result, CollectResult::Complete(ref joined) if !joined.is_empty()));
+        assert!(!text.is_empty());
+    }
+
+    #[test]
+    fn test_generate_from_finishes_stream_on_error() {
+        let stream = TokenStream::new();
+        assert!(
+            stream
+                .generate_from(&MockBackend::new(), "Hello", params())
+                .is_err()
+        );
+        let result = stream.collect_with_timeout(Duration::from_secs(5));
+        assert_eq!(result, CollectResult::Complete(String::new()));
+    }
+
     #[test]
     fn test_token_stream_no_callback() {
         let stream = TokenStream::new();
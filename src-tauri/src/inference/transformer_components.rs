@@ -1,6 +1,8 @@
 pub use super::activation::{Activation, apply_activation, gelu, relu, silu};
+use super::cache_optimizer::{CacheOptimizer, CachePosition};
 pub use super::embedding::{EmbeddingConfig, embed_tokens};
 pub use super::feedforward::{FeedforwardConfig, FeedforwardWeights, feedforward};
+use super::kv_cache::{KVCache, KVStoreParams};
 pub use super::position_encoding::{
     PositionConfig, add_position_encoding, create_position_encoding,
 };
@@ -30,6 +32,13 @@ pub struct TransformerBlockWeights<'a> {
     pub ff_down: &'a [f32],
 }
 
+/// A single decode step through one layer, backed by the KV cache
+pub struct CachedStep<'a> {
+    pub cache: &'a mut KVCache,
+    pub at: CachePosition,
+    pub config: TransformerBlockConfig,
+}
+
 struct AttentionParams {
     seq_len: usize,
     hidden_size: usize,
@@ -142,3 +151,40 @@ pub fn transformer_block(
     };
     apply_feedforward_layer(&after_attn, &ff_input)
 }
+
+/// Transformer block for one new token, attending over cached positions
+///
+/// Stores this layer's normed row in the cache at `step.at`, then uses
+/// `CacheOptimizer::incremental_attention` in place of full multi-head attention.
+pub fn transformer_block_step(
+    input: &[f32],
+    weights: &TransformerBlockWeights,
+    step: &mut CachedStep,
+) -> MinervaResult<Vec<f32>> {
+    let config = step.config;
+    let ln_cfg = LNCfg {
+        seq_len: 1,
+        hidden_size: config.hidden_size,
+        scale: weights.attn_scale.map(|s| s.to_vec()),
+        eps: config.eps,
+    };
+    let normed = layer_norm(input, &ln_cfg)?;
+    let params = KVStoreParams::builder(normed.clone(), normed.clone())
+        .layer(step.at.layer)
+        .pos(step.at.pos)
+        .build();
+    step.cache.store(params)?;
+
+    let attn_out = CacheOptimizer::incremental_attention(&normed, step.cache, step.at)?;
+    let after_attn: Vec<f32> = input.iter().zip(&attn_out).map(|(x, a)| x + a).collect();
+    let ff_input = FeedforwardLayerInput {
+        ff_up: weights.ff_up,
+        ff_down: weights.ff_down,
+        seq_len: 1,
+        hidden_size: config.hidden_size,
+        intermediate_size: config.intermediate_size,
+        activation: config.activation,
+        eps: config.eps,
+    };
+    apply_feedforward_layer(&after_attn, &ff_input)
+}
@@ -26,6 +26,7 @@
 //! - **Consistent API**: Single interface for all backends
 //! - **Dynamic Registration**: Add new backends without code changes
 
+pub use super::unified_backend_routing::{ActiveBackend, BackendCapabilities};
 use crate::error::MinervaResult;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -382,22 +383,17 @@ pub struct UnifiedBackend {
     #[allow(dead_code)]
     config: UnifiedBackendConfig,
     loaded_models: Arc<Mutex<std::collections::HashMap<String, ModelInfo>>>,
+    /// Backend chosen by `UnifiedBackend::new`
+    pub(super) active: Option<ActiveBackend>,
 }
 
 impl UnifiedBackend {
-    /// Create new unified backend with default config
-    pub fn new() -> Self {
-        Self {
-            config: UnifiedBackendConfig::default(),
-            loaded_models: Arc::new(Mutex::new(std::collections::HashMap::new())),
-        }
-    }
-
-    /// Create with custom config
+    /// Create with custom config and no model loaded
     pub fn with_config(config: UnifiedBackendConfig) -> Self {
         Self {
             config,
             loaded_models: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            active: None,
         }
     }
 
@@ -429,7 +425,7 @@ impl UnifiedBackend {
 
 impl Default for UnifiedBackend {
     fn default() -> Self {
-        Self::new()
+        Self::with_config(UnifiedBackendConfig::default())
     }
 }
 
@@ -511,7 +507,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_unified_backend_creation() {
-        let backend = UnifiedBackend::new();
+        let backend = UnifiedBackend::default();
         let models = backend.list_models().await.unwrap();
         assert!(models.is_empty());
     }
@@ -0,0 +1,229 @@
+/// Unified Backend Routing
+///
+/// Picks the fastest backend that can actually serve a model on this machine
+/// and loads it, falling through the preference order when a load fails.
+#[cfg(not(cpu_only))]
+use super::gpu_context::GpuContext;
+use super::inference_backend_trait::InferenceBackend;
+use super::llama_cpp_backend::LlamaCppBackend;
+use super::mlx_backend::MlxBackend;
+use super::pure_rust_backend::PureRustBackend;
+use super::unified_backend::{BackendStrategy, ModelFormat, UnifiedBackend, detect_model};
+use crate::error::{MinervaError, MinervaResult};
+use std::path::Path;
+#[cfg(not(cpu_only))]
+use std::sync::{Arc, Mutex};
+
+/// The backend a `UnifiedBackend` routed its model to
+pub struct ActiveBackend {
+    pub strategy: BackendStrategy,
+    pub backend: Box<dyn InferenceBackend>,
+}
+
+/// How each candidate backend is set up before it loads a model
+#[derive(Debug, Clone)]
+pub struct BackendSetup {
+    /// Inference threads, normally `AdaptiveConfigManager`'s recommendation
+    pub n_threads: usize,
+    /// Context llama.cpp books its GPU memory into
+    #[cfg(not(cpu_only))]
+    pub gpu: Option<Arc<Mutex<GpuContext>>>,
+}
+
+impl Default for BackendSetup {
+    fn default() -> Self {
+        Self {
+            n_threads: num_cpus::get(),
+            #[cfg(not(cpu_only))]
+            gpu: None,
+        }
+    }
+}
+
+/// Features offered by the selected backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendCapabilities {
+    /// `Auto` when no model is loaded
+    pub backend: BackendStrategy,
+    pub streaming: bool,
+    pub gpu_acceleration: bool,
+    pub context_size: usize,
+}
+
+impl UnifiedBackend {
+    /// Load `model_path` on the fastest capable backend
+    ///
+    /// Candidates come from `BackendStrategy::select_for`, minus backends
+    /// unavailable here (MLX off Apple Silicon, llama.cpp for non-GGUF).
+    /// A backend that fails to load hands over to the next one.
+    pub fn new(model_path: &Path) -> MinervaResult<Self> {
+        Self::with_setup(model_path, &BackendSetup::default())
+    }
+
+    /// Load `model_path` like `new`, setting each candidate up with `setup`
+    pub fn with_setup(model_path: &Path, setup: &BackendSetup) -> MinervaResult<Self> {
+        let info = detect_model(&model_path.to_string_lossy(), Some(model_path))?;
+        let mut last_error = None;
+
+        for strategy in preference_order(model_path, &info.architecture) {
+            let mut backend = backend_for(strategy, setup);
+            match backend.load_model(model_path, info.context_length) {
+                Ok(()) => {
+                    tracing::info!(
+                        "Routing {} ({}) to {} backend",
+                        model_path.display(),
+                        info.format,
+                        strategy
+                    );
+                    let mut unified = Self::default();
+                    unified.active = Some(ActiveBackend { strategy, backend });
+                    return Ok(unified);
+                }
+                Err(e) => {
+                    tracing::warn!("{} backend failed to load model: {}", strategy, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            MinervaError::model_loading(format!(
+                "No available backend can load {}",
+                model_path.display()
+            ))
+        }))
+    }
+
+    /// Capabilities of the routed backend
+    pub fn capabilities(&self) -> BackendCapabilities {
+        match &self.active {
+            Some(active) => BackendCapabilities {
+                backend: active.strategy,
+                streaming: active.backend.supports_streaming(),
+                gpu_acceleration: is_apple_silicon()
+                    && active.strategy != BackendStrategy::PureRust,
+                context_size: active.backend.context_size(),
+            },
+            None => BackendCapabilities {
+                backend: BackendStrategy::Auto,
+                streaming: false,
+                gpu_acceleration: false,
+                context_size: 0,
+            },
+        }
+    }
+
+    /// The routed backend, if a model was loaded with `new`
+    pub fn backend(&self) -> Option<&dyn InferenceBackend> {
+        self.active.as_ref().map(|active| active.backend.as_ref())
+    }
+
+    /// Take the routed backend, if a model was loaded with `new`
+    pub fn into_backend(self) -> Option<Box<dyn InferenceBackend>> {
+        self.active.map(|active| active.backend)
+    }
+}
+
+/// Load `model_path` on the fastest capable backend and return that backend
+pub fn load_backend(
+    model_path: &Path,
+    setup: &BackendSetup,
+) -> MinervaResult<Box<dyn InferenceBackend>> {
+    UnifiedBackend::with_setup(model_path, setup)?
+        .into_backend()
+        .ok_or_else(|| {
+            MinervaError::model_loading(format!("No backend loaded {}", model_path.display()))
+        })
+}
+
+/// Backends to try for `path`, fastest first, restricted to those available
+pub fn preference_order(path: &Path, architecture: &str) -> Vec<BackendStrategy> {
+    let format = ModelFormat::detect(path);
+    BackendStrategy::select_for(format, architecture, std::env::consts::OS)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|&strategy| is_available(strategy, path))
+        .collect()
+}
+
+fn is_available(strategy: BackendStrategy, path: &Path) -> bool {
+    match strategy {
+        BackendStrategy::Mlx => is_apple_silicon(),
+        BackendStrategy::LlamaCpp => LlamaCppBackend::can_handle(path),
+        BackendStrategy::PureRust => true,
+        BackendStrategy::Auto => false,
+    }
+}
+
+fn is_apple_silicon() -> bool {
+    cfg!(all(target_os = "macos", target_arch = "aarch64"))
+}
+
+/// Unloaded backend for `strategy`, set up with `setup`
+fn backend_for(strategy: BackendStrategy, setup: &BackendSetup) -> Box<dyn InferenceBackend> {
+    let mut backend: Box<dyn InferenceBackend> = match strategy {
+        BackendStrategy::Mlx => Box::new(MlxBackend::new()),
+        BackendStrategy::LlamaCpp => Box::new(llama_cpp_backend(setup)),
+        BackendStrategy::PureRust | BackendStrategy::Auto => Box::new(PureRustBackend::new()),
+    };
+    backend.set_thread_count(setup.n_threads);
+    backend
+}
+
+/// llama.cpp backend booking its GPU memory into `setup.gpu`, when set
+#[cfg(not(cpu_only))]
+fn llama_cpp_backend(setup: &BackendSetup) -> LlamaCppBackend {
+    let mut llama = LlamaCppBackend::new();
+    if let Some(gpu) = &setup.gpu {
+        llama.set_gpu_context(Arc::clone(gpu));
+    }
+    llama
+}
+
+#[cfg(cpu_only)]
+fn llama_cpp_backend(_setup: &BackendSetup) -> LlamaCppBackend {
+    LlamaCppBackend::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gguf_prefers_llama_cpp_over_pure_rust() {
+        let order = preference_order(Path::new("model.gguf"), "llama");
+        let llama = order.iter().position(|&s| s == BackendStrategy::LlamaCpp);
+        let pure = order.iter().position(|&s| s == BackendStrategy::PureRust);
+        assert!(llama.unwrap() < pure.unwrap());
+    }
+
+    #[test]
+    fn test_llama_cpp_unavailable_for_safetensors() {
+        let order = preference_order(Path::new("model.safetensors"), "llama");
+        assert!(!order.contains(&BackendStrategy::LlamaCpp));
+        assert!(order.contains(&BackendStrategy::PureRust));
+        assert_eq!(order.contains(&BackendStrategy::Mlx), is_apple_silicon());
+    }
+
+    #[test]
+    fn test_new_fails_when_every_backend_fails() {
+        assert!(UnifiedBackend::new(Path::new("/nonexistent/model.gguf")).is_err());
+    }
+
+    #[test]
+    fn test_backend_for_applies_thread_count() {
+        let setup = BackendSetup {
+            n_threads: 3,
+            ..BackendSetup::default()
+        };
+        let backend = backend_for(BackendStrategy::PureRust, &setup);
+        assert_eq!(backend.thread_count(), 3);
+    }
+
+    #[test]
+    fn test_capabilities_without_model() {
+        let capabilities = UnifiedBackend::default().capabilities();
+        assert_eq!(capabilities.backend, BackendStrategy::Auto);
+        assert!(!capabilities.streaming);
+    }
+}
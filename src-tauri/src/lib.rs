@@ -14,27 +14,175 @@ pub mod resilience;
 pub mod server;
 pub mod streaming;
 
+#[cfg(all(feature = "cpu-only", any(feature = "metal", feature = "cuda")))]
+compile_error!(
+    "`cpu-only` excludes the GPU backends; build with `--no-default-features --features cpu-only`"
+);
+
+use tauri::Manager;
+
+/// Track window focus/minimize so inference backs off while the app is hidden
+///
+/// Tauri 2 has no occlusion event, so minimization is read from the window
+/// whenever focus changes or the window is resized.
+fn on_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    if !matches!(
+        event,
+        tauri::WindowEvent::Focused(_) | tauri::WindowEvent::Resized(_)
+    ) {
+        return;
+    }
+
+    let focused = window.is_focused().unwrap_or(true);
+    let minimized = window.is_minimized().unwrap_or(false);
+    let state = performance::window_state::WindowState::from_window(focused, minimized);
+    let app_state = window.state::<commands::AppState>();
+    if app_state.adaptive.window_state() != state {
+        app_state.adaptive.set_window_state(state);
+        tracing::debug!(
+            "Window state changed: {:?}, loads now use {} threads",
+            state,
+            app_state.adaptive.recommended_threads()
+        );
+        if let Ok(mut preload) = app_state.preload.lock() {
+            preload.set_window_state(state);
+        }
+    }
+}
+
+/// Unload all models on critical memory pressure so jetsam does not kill the app
+fn register_memory_pressure_handler(app: &tauri::AppHandle) {
+    use inference::garbage_collector::GarbageCollector;
+    use inference::mlx_native::unified_memory::{MemoryPressureLevel, UnifiedMemoryMonitor};
+
+    let handle = app.clone();
+    let collector = std::sync::Mutex::new(GarbageCollector::new());
+    let mut monitor = UnifiedMemoryMonitor::new();
+    let registered = monitor.register_pressure_callback(Box::new(move |level| {
+        if level != MemoryPressureLevel::Critical {
+            return;
+        }
+        let state = handle.state::<commands::AppState>();
+        if let (Ok(mut contexts), Ok(mut collector)) = (state.contexts.lock(), collector.lock()) {
+            let evicted = collector.evict_all(&mut contexts);
+            tracing::warn!("Critical memory pressure: unloaded {} models", evicted);
+        }
+    }));
+
+    match registered {
+        Ok(()) => {
+            app.manage(monitor);
+        }
+        Err(e) => tracing::warn!("Memory pressure monitoring unavailable: {}", e),
+    }
+}
+
+/// Preload manager booking resident models against this machine's GPU
+/// memory, with the configured pins applied
+#[cfg(not(cpu_only))]
+fn resident_preload_manager(
+    entries: &[config::PreloadModelEntry],
+) -> inference::preload_manager::PreloadManager {
+    use inference::gpu_context::GpuContext;
+    use inference::resident_models::ResidentModels;
+
+    let gpu = std::sync::Arc::new(std::sync::Mutex::new(GpuContext::default()));
+    let mut preload = inference::preload_manager::PreloadManager::default()
+        .with_resident_models(ResidentModels::new(gpu));
+    preload.apply_preload_models(entries);
+    preload
+}
+
+#[cfg(cpu_only)]
+fn resident_preload_manager(
+    entries: &[config::PreloadModelEntry],
+) -> inference::preload_manager::PreloadManager {
+    let mut preload = inference::preload_manager::PreloadManager::default();
+    preload.apply_preload_models(entries);
+    preload
+}
+
+/// Open the KV snapshot cache, collecting expired entries; sessions work
+/// without it, so failures are only logged
+fn open_kv_snapshots() -> Option<inference::kv_snapshot_cache::KVSnapshotCache> {
+    const KV_SNAPSHOT_ENTRIES: usize = 8;
+    config::AppConfig::kv_snapshot_dir()
+        .and_then(|dir| {
+            inference::kv_snapshot_cache::KVSnapshotCache::open(&dir, KV_SNAPSHOT_ENTRIES)
+        })
+        .inspect_err(|e| tracing::warn!("KV snapshot cache unavailable: {}", e))
+        .ok()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let app_config = config::AppConfig::load_or_default();
+    let generation = std::sync::Arc::new(parking_lot::RwLock::new(app_config.generation));
 
     // Ensure models directory exists on startup
     if let Err(e) = app_config.ensure_models_dir() {
         eprintln!("Warning: Failed to create models directory: {}", e);
     }
+    app_config.log_problems();
+
+    let mut registry = inference::model_registry::ModelRegistry::default();
+    let recorded = registry.record_models_dir(&app_config.models_dir);
+    tracing::info!(
+        "Found {} model files in {}",
+        recorded,
+        app_config.models_dir.display()
+    );
+
+    let preload_models = app_config.preload_models.clone();
+    let preload = resident_preload_manager(&preload_models);
+
+    let contexts = open_kv_snapshots().map_or_else(
+        inference::context_manager::ContextManager::default,
+        |snapshots| {
+            inference::context_manager::ContextManager::default().with_kv_snapshots(snapshots)
+        },
+    );
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
         .manage(commands::AppState {
             config: std::sync::Mutex::new(app_config),
+            contexts: std::sync::Mutex::new(contexts),
+            registry: std::sync::Arc::new(std::sync::Mutex::new(registry)),
+            generation,
+            adaptive: performance::adaptive::AdaptiveConfigManager::new(),
+            server: std::sync::Mutex::new(commands::server_commands::EmbeddedServer::default()),
+            preload: std::sync::Mutex::new(preload),
+        })
+        .setup(move |app| {
+            register_memory_pressure_handler(app.handle());
+            for entry in preload_models {
+                commands::load_resident_in_background(app.handle(), entry.id, None);
+            }
+            Ok(())
         })
+        .on_window_event(on_window_event)
         .invoke_handler(tauri::generate_handler![
             commands::get_config,
             commands::set_models_directory,
             commands::get_models_directory,
             commands::list_discovered_models,
             commands::load_model_file,
+            commands::repair_model_file,
+            commands::list_downloaded_models,
+            commands::set_generation_config,
+            commands::open_model_file_dialog,
             commands::ensure_models_directory,
+            commands::get_hardware_info,
+            commands::validate_model_file,
+            commands::export_conversation,
+            commands::model_commands::download_model,
+            commands::session_commands::save_session,
+            commands::session_commands::load_session,
+            commands::server_commands::get_server_status,
+            commands::server_commands::start_server,
+            commands::server_commands::stop_server,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -4,6 +4,7 @@ pub mod rate_limiter;
 pub mod token_bucket;
 pub mod validator;
 
-pub use protocol::add_protocol_headers;
+pub use param_validator::{FieldError, ValidatedJson, ValidationRejection};
+pub use protocol::{LoggingMiddleware, add_protocol_headers, log_requests};
 pub use rate_limiter::RateLimiter;
 pub use validator::Validator;
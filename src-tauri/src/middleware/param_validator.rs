@@ -1,4 +1,13 @@
+use crate::api::{ApiError, ProtocolValidator, ValidationError};
 use crate::error::{MinervaError, MinervaResult};
+use crate::models::ChatCompletionRequest;
+use crate::server::validation::RequestValidator;
+use axum::extract::{FromRef, FromRequest, Request};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Json, async_trait};
+use serde::Serialize;
+use std::sync::Arc;
 
 /// Validates individual parameters
 pub struct ParamValidator;
@@ -49,9 +58,206 @@ impl ParamValidator {
     }
 }
 
+/// A request field that failed validation, named by its JSON path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub constraint: String,
+}
+
+impl FieldError {
+    fn new(field: &str, constraint: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            constraint: constraint.into(),
+        }
+    }
+}
+
+impl From<&ValidationError> for FieldError {
+    fn from(err: &ValidationError) -> Self {
+        match err {
+            ValidationError::MissingField(field) | ValidationError::UnknownField(field) => {
+                Self::new(field, err.to_string())
+            }
+            ValidationError::InvalidType { field, expected } => {
+                Self::new(field, format!("must be {}", expected))
+            }
+            ValidationError::InvalidValue { field, reason } => Self::new(field, reason.as_str()),
+        }
+    }
+}
+
+/// 422 response listing every field that failed validation
+#[derive(Debug)]
+pub struct ValidationRejection {
+    pub errors: Vec<FieldError>,
+    /// 422 for invalid bodies, 413 for bodies over the server's size limit
+    pub status: StatusCode,
+}
+
+impl ValidationRejection {
+    pub fn new(errors: Vec<FieldError>) -> Self {
+        Self {
+            errors,
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RejectionBody {
+    error: ApiError,
+    errors: Vec<FieldError>,
+}
+
+impl IntoResponse for ValidationRejection {
+    fn into_response(self) -> Response {
+        let message = self
+            .errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.constraint))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let body = RejectionBody {
+            error: ApiError {
+                message,
+                code: if self.status == StatusCode::PAYLOAD_TOO_LARGE {
+                    "request_too_large"
+                } else {
+                    "validation_failed"
+                }
+                .to_string(),
+                type_: Some("invalid_request_error".to_string()),
+                param: self.errors.first().map(|e| e.field.clone()),
+            },
+            errors: self.errors,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// JSON body that has been deserialized and validated in one step
+#[derive(Debug)]
+pub struct ValidatedJson<T>(pub T);
+
+/// Checks run in order: JSON syntax, the protocol rules in
+/// `ProtocolValidator`, the state's JSON Schema (`RequestValidator`), then
+/// deserialization.
+#[async_trait]
+impl<S> FromRequest<S> for ValidatedJson<ChatCompletionRequest>
+where
+    S: Send + Sync,
+    Arc<RequestValidator>: FromRef<S>,
+{
+    type Rejection = ValidationRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let reject = ValidationRejection::new;
+        let Json(body) = Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(|e| ValidationRejection {
+                status: match e.status() {
+                    StatusCode::PAYLOAD_TOO_LARGE => StatusCode::PAYLOAD_TOO_LARGE,
+                    _ => StatusCode::UNPROCESSABLE_ENTITY,
+                },
+                ..reject(vec![FieldError::new("body", e.body_text())])
+            })?;
+
+        ProtocolValidator::validate_chat_request(&body).map_err(|issues| {
+            reject(
+                issues
+                    .iter()
+                    .filter(|i| !i.is_warning())
+                    .map(FieldError::from)
+                    .collect(),
+            )
+        })?;
+        Arc::<RequestValidator>::from_ref(state)
+            .validate(&body)
+            .map_err(reject)?;
+        serde_json::from_value(body)
+            .map(Self)
+            .map_err(|e| reject(vec![FieldError::new("body", e.to_string())]))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::body::Body;
+
+    async fn extract(body: serde_json::Value) -> Result<ChatCompletionRequest, Vec<FieldError>> {
+        let req = Request::builder()
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let validator = Arc::new(RequestValidator::default());
+        ValidatedJson::<ChatCompletionRequest>::from_request(req, &validator)
+            .await
+            .map(|ValidatedJson(req)| req)
+            .map_err(|rejection| rejection.errors)
+    }
+
+    #[tokio::test]
+    async fn test_validated_json_accepts_valid_request() {
+        let req = extract(serde_json::json!({
+            "model": "llama",
+            "messages": [{"role": "user", "content": "Hi"}],
+            "top_p": 0.9
+        }))
+        .await
+        .unwrap();
+        assert_eq!(req.model, "llama");
+    }
+
+    #[tokio::test]
+    async fn test_validated_json_names_failing_fields() {
+        let errors = extract(serde_json::json!({
+            "model": "llama",
+            "messages": [{"role": "robot", "content": "Hi"}],
+            "temperature": 3.0
+        }))
+        .await
+        .unwrap_err();
+
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["messages[0].role", "temperature"]);
+    }
+
+    #[tokio::test]
+    async fn test_validated_json_checks_top_p() {
+        let errors = extract(serde_json::json!({
+            "model": "llama",
+            "messages": [{"role": "user", "content": "Hi"}],
+            "top_p": 1.5
+        }))
+        .await
+        .unwrap_err();
+        assert_eq!(errors[0].field, "top_p");
+        assert!(errors[0].constraint.contains("top_p must be between"));
+    }
+
+    #[tokio::test]
+    async fn test_validated_json_applies_schema() {
+        let errors = extract(serde_json::json!({
+            "model": "llama",
+            "messages": [{"role": "user", "content": "Hi"}],
+            "stream": "yes"
+        }))
+        .await
+        .unwrap_err();
+        assert_eq!(errors[0].field, "/stream");
+    }
+
+    #[test]
+    fn test_rejection_is_unprocessable_entity() {
+        let rejection = ValidationRejection::new(vec![FieldError::new("temperature", "too hot")]);
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
 
     #[test]
     fn test_temperature_valid() {
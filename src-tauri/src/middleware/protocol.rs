@@ -1,10 +1,16 @@
 //! Protocol Compliance Middleware
 //! Ensures all API requests and responses follow OpenAI-compatible standards
 
-use axum::body::Body;
-use axum::http::Request;
+use crate::config::DEFAULT_MAX_REQUEST_BODY_BYTES;
+use crate::error::MinervaError;
+use crate::observability::tracing_middleware::{ensure_request_id, with_request_id};
+use axum::body::{Body, HttpBody};
+use axum::extract::State;
+use axum::http::{HeaderValue, Request, StatusCode, header};
 use axum::middleware::Next;
-use axum::response::Response;
+use axum::response::{IntoResponse, Response};
+use serde_json::Value;
+use std::time::Instant;
 
 /// Request ID from X-Request-ID header
 #[derive(Clone)]
@@ -16,37 +22,203 @@ pub const OPENAI_COMPATIBLE_VERSION: &str = "OpenAI compatible";
 
 /// Middleware to add protocol headers to responses
 pub async fn add_protocol_headers(mut req: Request<Body>, next: Next) -> Response {
-    // Generate request ID if not present
-    let request_id = req
-        .headers()
-        .get("x-request-id")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("")
-        .to_string();
-
-    let request_id = if request_id.is_empty() {
-        uuid::Uuid::new_v4().to_string()
-    } else {
-        request_id
-    };
+    let request_id = ensure_request_id(&mut req);
 
     // Store request ID in extensions for handlers
     req.extensions_mut().insert(RequestId(request_id.clone()));
 
     let mut response = next.run(req).await;
-
-    // Add protocol headers
-    response.headers_mut().insert(
-        "X-Request-ID",
-        request_id
-            .parse()
-            .unwrap_or_else(|_| "unknown".parse().unwrap()),
-    );
     response
         .headers_mut()
-        .insert("X-API-Version", API_VERSION.parse().unwrap());
+        .insert("X-API-Version", HeaderValue::from_static(API_VERSION));
+    with_request_id(response, &request_id)
+}
 
-    response
+/// Environment variable that opts in to logging message content
+pub const LOG_PROMPTS_ENV: &str = "MINERVA_LOG_PROMPTS";
+
+/// Maximum characters of message content written to the log
+pub const MAX_LOGGED_CONTENT_CHARS: usize = 200;
+
+/// DEBUG-level request/response logging
+///
+/// Metadata (method, path, status, latency, model, token counts) is always
+/// logged; prompts and completions only when `MINERVA_LOG_PROMPTS=1`.
+/// Bodies larger than `max_body_bytes` are never buffered.
+#[derive(Debug, Clone, Copy)]
+pub struct LoggingMiddleware {
+    pub log_prompts: bool,
+    pub max_body_bytes: usize,
+}
+
+impl Default for LoggingMiddleware {
+    fn default() -> Self {
+        Self {
+            log_prompts: false,
+            max_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+        }
+    }
+}
+
+impl LoggingMiddleware {
+    pub fn from_env() -> Self {
+        Self {
+            log_prompts: std::env::var(LOG_PROMPTS_ENV).is_ok_and(|v| v == "1"),
+            ..Self::default()
+        }
+    }
+
+    /// Buffer at most `max_bytes` of a body, usually the server's request limit
+    pub fn with_max_body_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_body_bytes = max_bytes;
+        self
+    }
+}
+
+/// Truncate to `MAX_LOGGED_CONTENT_CHARS` characters
+pub fn truncate_for_log(content: &str) -> String {
+    match content.char_indices().nth(MAX_LOGGED_CONTENT_CHARS) {
+        Some((end, _)) => format!("{}...", &content[..end]),
+        None => content.to_string(),
+    }
+}
+
+/// Fields of interest pulled from a JSON request or response body
+#[derive(Debug, Default)]
+struct BodySummary {
+    model: Option<String>,
+    content: Option<String>,
+    prompt_tokens: Option<u64>,
+    completion_tokens: Option<u64>,
+}
+
+impl BodySummary {
+    fn from_request(bytes: &[u8]) -> Self {
+        let json: Value = serde_json::from_slice(bytes).unwrap_or_default();
+        let content = json["messages"].as_array().map(|messages| {
+            let parts: Vec<&str> = messages
+                .iter()
+                .filter_map(|m| m["content"].as_str())
+                .collect();
+            parts.join("\n")
+        });
+        Self {
+            model: json["model"].as_str().map(String::from),
+            content,
+            ..Self::default()
+        }
+    }
+
+    fn from_response(bytes: &[u8]) -> Self {
+        let json: Value = serde_json::from_slice(bytes).unwrap_or_default();
+        Self {
+            model: json["model"].as_str().map(String::from),
+            content: json["choices"][0]["message"]["content"]
+                .as_str()
+                .map(String::from),
+            prompt_tokens: json["usage"]["prompt_tokens"].as_u64(),
+            completion_tokens: json["usage"]["completion_tokens"].as_u64(),
+        }
+    }
+}
+
+/// Middleware logging each request at DEBUG, correlated by `X-Request-Id`
+///
+/// Bodies are only buffered when DEBUG is enabled, and streamed (non-JSON)
+/// responses are never buffered. Requests over the body limit get 413
+/// here, as the body limit layer would give them; larger responses pass
+/// through unlogged.
+pub async fn log_requests(
+    State(logging): State<LoggingMiddleware>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !tracing::enabled!(tracing::Level::DEBUG) {
+        return next.run(req).await;
+    }
+    let started = Instant::now();
+    let request_id = ensure_request_id(&mut req);
+    let (method, path) = (req.method().clone(), req.uri().path().to_string());
+
+    let (req, request) = match buffer_request(req, logging.max_body_bytes).await {
+        Ok(buffered) => buffered,
+        Err(response) => return with_request_id(response, &request_id),
+    };
+    let response = next.run(req).await;
+    let (response, reply) = buffer_json_response(response, logging.max_body_bytes).await;
+
+    tracing::debug!(
+        request_id = %request_id,
+        method = %method,
+        path = %path,
+        status = response.status().as_u16(),
+        latency_ms = started.elapsed().as_millis() as u64,
+        model = ?request.model.as_ref().or(reply.model.as_ref()),
+        prompt_tokens = ?reply.prompt_tokens,
+        completion_tokens = ?reply.completion_tokens,
+        "API request"
+    );
+    if logging.log_prompts {
+        log_content(&request_id, &request, &reply);
+    }
+
+    with_request_id(response, &request_id)
+}
+
+fn log_content(request_id: &str, request: &BodySummary, reply: &BodySummary) {
+    tracing::debug!(
+        request_id = %request_id,
+        prompt = %truncate_for_log(request.content.as_deref().unwrap_or("")),
+        completion = %truncate_for_log(reply.content.as_deref().unwrap_or("")),
+        "API request content"
+    );
+}
+
+async fn buffer_request(
+    req: Request<Body>,
+    max_bytes: usize,
+) -> Result<(Request<Body>, BodySummary), Response> {
+    let (parts, body) = req.into_parts();
+    let bytes = axum::body::to_bytes(body, max_bytes).await.map_err(|_| {
+        MinervaError::Http(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Request body exceeds {} bytes", max_bytes),
+        )
+        .into_response()
+    })?;
+    let summary = BodySummary::from_request(&bytes);
+    Ok((Request::from_parts(parts, Body::from(bytes)), summary))
+}
+
+async fn buffer_json_response(response: Response, max_bytes: usize) -> (Response, BodySummary) {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let fits = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|len| len <= max_bytes as u64);
+    if !is_json || !fits {
+        return (response, BodySummary::default());
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, max_bytes).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read response body for logging");
+            let error = MinervaError::Http(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to read response body: {}", e),
+            );
+            return (error.into_response(), BodySummary::default());
+        }
+    };
+    let summary = BodySummary::from_response(&bytes);
+    (Response::from_parts(parts, Body::from(bytes)), summary)
 }
 
 #[cfg(test)]
@@ -65,4 +237,64 @@ mod tests {
             "OpenAI version should be set"
         );
     }
+
+    #[test]
+    fn test_truncate_for_log() {
+        assert_eq!(truncate_for_log("short"), "short");
+        let long = "é".repeat(MAX_LOGGED_CONTENT_CHARS + 10);
+        let truncated = truncate_for_log(&long);
+        assert_eq!(truncated.chars().count(), MAX_LOGGED_CONTENT_CHARS + 3);
+        assert!(truncated.ends_with("..."));
+    }
+
+    #[test]
+    fn test_request_summary() {
+        let body = br#"{"model":"llama","messages":[{"role":"user","content":"Hi"},{"role":"user","content":"there"}]}"#;
+        let summary = BodySummary::from_request(body);
+        assert_eq!(summary.model.as_deref(), Some("llama"));
+        assert_eq!(summary.content.as_deref(), Some("Hi\nthere"));
+        assert!(BodySummary::from_request(b"not json").model.is_none());
+    }
+
+    #[test]
+    fn test_response_summary_reads_usage() {
+        let body = br#"{"model":"llama","choices":[{"message":{"content":"Hello"}}],"usage":{"prompt_tokens":3,"completion_tokens":5}}"#;
+        let summary = BodySummary::from_response(body);
+        assert_eq!(summary.content.as_deref(), Some("Hello"));
+        assert_eq!(summary.prompt_tokens, Some(3));
+        assert_eq!(summary.completion_tokens, Some(5));
+    }
+
+    #[test]
+    fn test_ensure_request_id() {
+        let mut req = Request::builder()
+            .header("x-request-id", "abc")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(ensure_request_id(&mut req), "abc");
+
+        let mut req = Request::new(Body::empty());
+        let id = ensure_request_id(&mut req);
+        assert!(!id.is_empty());
+        assert_eq!(req.headers()["x-request-id"], id.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_buffering_respects_body_limit() {
+        let small = Request::new(Body::from(r#"{"model":"llama"}"#));
+        let (_, summary) = buffer_request(small, 64).await.unwrap();
+        assert_eq!(summary.model.as_deref(), Some("llama"));
+
+        let large = Request::new(Body::from(vec![b' '; 65]));
+        let rejected = buffer_request(large, 64).await.unwrap_err();
+        assert_eq!(rejected.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let response = axum::Json(serde_json::json!({"model": "x".repeat(64)})).into_response();
+        let (response, summary) = buffer_json_response(response, 64).await;
+        assert!(summary.model.is_none());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.len() > 64);
+    }
 }
@@ -24,9 +24,19 @@ pub struct ChatCompletionRequest {
     pub frequency_penalty: Option<f32>,
     #[serde(default)]
     pub presence_penalty: Option<f32>,
+    #[serde(default)]
+    pub stream_options: Option<StreamOptions>,
 }
 
-#[derive(Debug, Serialize)]
+/// OpenAI `stream_options`; only meaningful when `stream` is true
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct StreamOptions {
+    /// Send a final chunk with token usage before `[DONE]`
+    #[serde(default)]
+    pub include_usage: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct ChatCompletionResponse {
     pub id: String,
@@ -37,7 +47,7 @@ pub struct ChatCompletionResponse {
     pub usage: Usage,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Choice {
     pub index: usize,
@@ -45,7 +55,7 @@ pub struct Choice {
     pub finish_reason: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Usage {
     pub prompt_tokens: usize,
@@ -61,6 +71,9 @@ pub struct ChatCompletionChunk {
     pub created: i64,
     pub model: String,
     pub choices: Vec<ChoiceDelta>,
+    /// Present only on the final usage chunk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
 }
 
 #[derive(Debug, Serialize)]
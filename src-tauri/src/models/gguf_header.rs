@@ -1,73 +1,212 @@
 use crate::error::{MinervaError, MinervaResult};
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufReader, Read};
+use std::path::Path;
 
-/// Validate GGUF magic number
-pub fn validate_magic(file: &mut File) -> MinervaResult<()> {
+/// "GGUF" read as a little-endian u32
+const GGUF_MAGIC: u32 = 0x4655_4747;
+
+/// Magics of the pre-GGUF GGML containers llama.cpp no longer loads
+const LEGACY_GGML_MAGICS: [(u32, &str); 3] = [
+    (0x6767_6d6c, "GGML v1"),
+    (0x6767_6d66, "GGML (ggmf)"),
+    (0x6767_6a74, "GGML (ggjt)"),
+];
+
+/// Fixed-size fields at the start of every GGUF file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GGUFHeader {
+    pub version: u32,
+    pub tensor_count: u64,
+    pub kv_count: u64,
+}
+
+impl GGUFHeader {
+    /// Read and validate the header of the file at `path`
+    ///
+    /// Legacy GGML files fail with `UnsupportedFormat` and conversion advice.
+    pub fn read(path: &Path) -> MinervaResult<Self> {
+        let file = File::open(path).map_err(|e| {
+            MinervaError::model_loading_with(format!("Failed to open {}: {}", path.display(), e), e)
+        })?;
+        Self::from_reader(&mut BufReader::new(file))
+    }
+
+    /// Read and validate a header from the start of `reader`
+    pub fn from_reader<R: Read>(reader: &mut R) -> MinervaResult<Self> {
+        validate_magic(reader)?;
+        let version = validate_version(reader)?;
+        let mut tensor_count_bytes = [0u8; 8];
+        reader.read_exact(&mut tensor_count_bytes).map_err(|e| {
+            MinervaError::model_loading(format!("Failed to read tensor count: {}", e))
+        })?;
+        Ok(Self {
+            version,
+            tensor_count: u64::from_le_bytes(tensor_count_bytes),
+            kv_count: read_kv_count(reader)?,
+        })
+    }
+}
+
+/// Validate GGUF magic number, naming legacy GGML formats when found
+pub fn validate_magic<R: Read>(file: &mut R) -> MinervaResult<()> {
     let mut magic = [0u8; 4];
-    file.read_exact(&mut magic).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read GGUF magic: {}", e))
-    })?;
+    file.read_exact(&mut magic)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read GGUF magic: {}", e)))?;
+    check_magic(magic)
+}
 
-    if magic != [0x47, 0x47, 0x55, 0x46] {
-        return Err(MinervaError::ModelLoadingError(
-            "Invalid GGUF magic number".to_string(),
-        ));
+/// Accept the GGUF magic; reject GGML magics with an upgrade hint
+pub fn check_magic(magic: [u8; 4]) -> MinervaResult<()> {
+    if u32::from_le_bytes(magic) == GGUF_MAGIC {
+        return Ok(());
     }
-    Ok(())
+
+    // GGML wrote its magic as a native u32; match either byte order
+    let candidates = [u32::from_le_bytes(magic), u32::from_be_bytes(magic)];
+    if let Some((_, name)) = LEGACY_GGML_MAGICS
+        .iter()
+        .find(|(legacy, _)| candidates.contains(legacy))
+    {
+        return Err(MinervaError::UnsupportedFormat(format!(
+            "{} format detected. Convert to GGUF using llama.cpp's convert.py",
+            name
+        )));
+    }
+
+    Err(MinervaError::model_loading(
+        "Invalid GGUF magic number".to_string(),
+    ))
 }
 
-/// Validate GGUF version (must be 2 or later)
-pub fn validate_version(file: &mut File) -> MinervaResult<()> {
+/// Validate GGUF version (must be 2 or later), returning it
+pub fn validate_version<R: Read>(file: &mut R) -> MinervaResult<u32> {
     let mut version_bytes = [0u8; 4];
     file.read_exact(&mut version_bytes)
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to read version: {}", e)))?;
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read version: {}", e)))?;
     let version = u32::from_le_bytes(version_bytes);
 
     if version < 2 {
-        return Err(MinervaError::ModelLoadingError(
+        return Err(MinervaError::model_loading(
             "Unsupported GGUF version".to_string(),
         ));
     }
-    Ok(())
+    Ok(version)
 }
 
 /// Skip tensor count field
-pub fn skip_tensor_count(file: &mut File) -> MinervaResult<()> {
+pub fn skip_tensor_count<R: Read>(file: &mut R) -> MinervaResult<()> {
     let mut tensor_count_bytes = [0u8; 8];
-    file.read_exact(&mut tensor_count_bytes).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read tensor count: {}", e))
-    })?;
+    file.read_exact(&mut tensor_count_bytes)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read tensor count: {}", e)))?;
     Ok(())
 }
 
 /// Read KV pairs count
-pub fn read_kv_count(file: &mut File) -> MinervaResult<u64> {
+pub fn read_kv_count<R: Read>(file: &mut R) -> MinervaResult<u64> {
     let mut kv_count_bytes = [0u8; 8];
     file.read_exact(&mut kv_count_bytes)
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to read kv count: {}", e)))?;
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read kv count: {}", e)))?;
     Ok(u64::from_le_bytes(kv_count_bytes))
 }
 
 /// Read KV pair key
-pub fn read_key(file: &mut File) -> MinervaResult<String> {
+pub fn read_key<R: Read>(file: &mut R) -> MinervaResult<String> {
     let mut key_len_bytes = [0u8; 4];
-    file.read_exact(&mut key_len_bytes).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read key length: {}", e))
-    })?;
-    let key_len = u32::from_le_bytes(key_len_bytes) as usize;
-
-    let mut key_bytes = vec![0u8; key_len];
-    file.read_exact(&mut key_bytes)
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to read key: {}", e)))?;
+    file.read_exact(&mut key_len_bytes)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read key length: {}", e)))?;
+    let key_len = u32::from_le_bytes(key_len_bytes);
+
+    let key_bytes = read_bounded(file, key_len)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read key: {}", e)))?;
     Ok(String::from_utf8_lossy(&key_bytes).to_string())
 }
 
 /// Read value type field
-pub fn read_value_type(file: &mut File) -> MinervaResult<u32> {
+pub fn read_value_type<R: Read>(file: &mut R) -> MinervaResult<u32> {
     let mut value_type_bytes = [0u8; 4];
-    file.read_exact(&mut value_type_bytes).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read value type: {}", e))
-    })?;
+    file.read_exact(&mut value_type_bytes)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read value type: {}", e)))?;
     Ok(u32::from_le_bytes(value_type_bytes))
 }
+
+/// Read exactly `len` bytes without trusting `len` for the allocation size
+///
+/// The buffer grows with the bytes actually present, so a corrupt length
+/// near `u32::MAX` fails with `UnexpectedEof` instead of a 4 GiB allocation.
+pub fn read_bounded<R: Read>(file: &mut R, len: u32) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    file.take(u64::from(len)).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != u64::from(len) {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn header_bytes(magic: &[u8; 4], version: u32) -> Vec<u8> {
+        let mut bytes = magic.to_vec();
+        bytes.extend_from_slice(&version.to_le_bytes());
+        bytes.extend_from_slice(&7u64.to_le_bytes());
+        bytes.extend_from_slice(&3u64.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_read_gguf_header() {
+        let mut reader = Cursor::new(header_bytes(b"GGUF", 3));
+        let header = GGUFHeader::from_reader(&mut reader).unwrap();
+        assert_eq!(
+            header,
+            GGUFHeader {
+                version: 3,
+                tensor_count: 7,
+                kv_count: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_ggml_v1_is_unsupported_format() {
+        // llama.cpp wrote 0x67676d6c as a little-endian u32
+        let mut reader = Cursor::new(header_bytes(b"lmgg", 1));
+        match GGUFHeader::from_reader(&mut reader) {
+            Err(MinervaError::UnsupportedFormat(msg)) => {
+                assert!(msg.starts_with("GGML v1 format detected"));
+                assert!(msg.contains("convert.py"));
+            }
+            other => panic!("expected UnsupportedFormat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ggjt_is_unsupported_format() {
+        let result = check_magic(*b"tjgg");
+        assert!(
+            matches!(result, Err(MinervaError::UnsupportedFormat(msg)) if msg.contains("ggjt"))
+        );
+    }
+
+    #[test]
+    fn test_unknown_magic_is_loading_error() {
+        assert!(matches!(
+            check_magic(*b"\x00\x01\x02\x03"),
+            Err(MinervaError::ModelLoadingError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("legacy.bin");
+        std::fs::write(&path, header_bytes(b"ggml", 1)).unwrap();
+        assert!(matches!(
+            GGUFHeader::read(&path),
+            Err(MinervaError::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -2,6 +2,8 @@ use crate::error::{MinervaError, MinervaResult};
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 
+use super::gguf_header::check_magic;
+
 /// Validates and reads GGUF file headers
 pub struct GGUFHeaderValidator;
 
@@ -11,19 +13,15 @@ impl GGUFHeaderValidator {
         // Read magic number
         let mut magic = [0u8; 4];
         file.read_exact(&mut magic)
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to read magic: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Failed to read magic: {}", e)))?;
 
-        // GGUF magic is "GGUF" (0x47 0x47 0x55 0x46)
-        if magic != [0x47, 0x47, 0x55, 0x46] {
-            return Err(MinervaError::ModelLoadingError(
-                "Invalid GGUF magic number".to_string(),
-            ));
-        }
+        // GGUF magic is "GGUF"; legacy GGML files get conversion advice
+        check_magic(magic)?;
 
         // Read and validate version
         let version = Self::read_u32(file)?;
         if !(2..=3).contains(&version) {
-            return Err(MinervaError::ModelLoadingError(format!(
+            return Err(MinervaError::model_loading(format!(
                 "Unsupported GGUF version: {}",
                 version
             )));
@@ -35,11 +33,11 @@ impl GGUFHeaderValidator {
     /// Align file position to 32-byte boundary
     pub fn align_to_boundary(file: &mut File) -> MinervaResult<()> {
         let current_pos = file.stream_position().map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to get file position: {}", e))
+            MinervaError::model_loading(format!("Failed to get file position: {}", e))
         })?;
         let aligned_pos = current_pos.div_ceil(32) * 32;
         file.seek(SeekFrom::Start(aligned_pos)).map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to seek to alignment: {}", e))
+            MinervaError::model_loading(format!("Failed to seek to alignment: {}", e))
         })?;
         Ok(())
     }
@@ -47,7 +45,7 @@ impl GGUFHeaderValidator {
     fn read_u32(file: &mut File) -> MinervaResult<u32> {
         let mut buf = [0u8; 4];
         file.read_exact(&mut buf)
-            .map_err(|e| MinervaError::ModelLoadingError(e.to_string()))?;
+            .map_err(|e| MinervaError::model_loading(e.to_string()))?;
         Ok(u32::from_le_bytes(buf))
     }
 }
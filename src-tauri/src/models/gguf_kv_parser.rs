@@ -15,7 +15,7 @@ impl GGUFKVParser {
         let key_len = BinaryReader::read_u32(file)? as usize;
         let mut key_bytes = vec![0u8; key_len];
         std::io::Read::read_exact(file, &mut key_bytes)
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to read key: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Failed to read key: {}", e)))?;
         let key = String::from_utf8_lossy(&key_bytes).to_string();
 
         // Read value type
@@ -76,7 +76,7 @@ impl GGUFKVParser {
                 GGUFMetadataStore::store_string(&key, &str_val, metadata);
             }
             _ => {
-                return Err(MinervaError::ModelLoadingError(format!(
+                return Err(MinervaError::model_loading(format!(
                     "Unknown metadata type: {}",
                     value_type
                 )));
@@ -9,6 +9,7 @@ use std::path::Path;
 
 use super::gguf_header_validator::GGUFHeaderValidator;
 use super::gguf_kv_parser::GGUFKVParser;
+use super::gguf_repair::{RepairReport, repair_truncated};
 use super::gguf_tensor::GGUFTensor;
 use super::gguf_tensor_loader::GGUFTensorLoader;
 
@@ -32,34 +33,8 @@ pub struct GGUFModelLoader;
 impl GGUFModelLoader {
     /// Load a GGUF model file and extract tensors and metadata
     pub fn load(path: &Path) -> MinervaResult<(GGUFModelMetadata, Vec<GGUFTensor>)> {
-        let mut file = File::open(path).map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to open GGUF file: {}", e))
-        })?;
-
-        // Validate header and get version
-        let _version = GGUFHeaderValidator::validate(&mut file)?;
-
-        // Read tensor and KV counts
-        let tensor_count = Self::read_u64(&mut file)?;
-        let kv_count = Self::read_u64(&mut file)?;
-
-        // Parse metadata from KV pairs
-        let mut metadata = GGUFModelMetadata {
-            name: None,
-            architecture: None,
-            context_window: None,
-            embedding_length: None,
-            feed_forward_length: None,
-            attention_head_count: None,
-            attention_head_count_kv: None,
-            layer_count: None,
-            quantization_version: None,
-        };
-
-        // Parse key-value pairs
-        for _ in 0..kv_count {
-            GGUFKVParser::parse_kv_pair(&mut file, &mut metadata)?;
-        }
+        let mut file = Self::open(path)?;
+        let (tensor_count, kv_count, metadata) = Self::read_metadata(&mut file)?;
 
         // Align to 32-byte boundary before reading tensors
         GGUFHeaderValidator::align_to_boundary(&mut file)?;
@@ -85,12 +60,61 @@ impl GGUFModelLoader {
         Ok((metadata, tensors))
     }
 
+    /// Check the header and read metadata only, without touching tensor data
+    ///
+    /// Cheap enough to run as a pre-flight check before a full load.
+    pub fn validate(path: &Path) -> MinervaResult<GGUFModelMetadata> {
+        let mut file = Self::open(path)?;
+        let (_, _, metadata) = Self::read_metadata(&mut file)?;
+        Ok(metadata)
+    }
+
+    /// Cut a truncated download back to its complete tensors
+    ///
+    /// Refuses (returning `RepairReport::Unrecoverable`) when fewer than 80%
+    /// of the tensors survived, and errors on `.part` files or tensor types
+    /// of unknown size; see `gguf_repair` for the file layout kept.
+    pub fn attempt_repair(path: &Path) -> MinervaResult<RepairReport> {
+        repair_truncated(path)
+    }
+
     // ==================== Helper Functions ====================
 
+    fn open(path: &Path) -> MinervaResult<File> {
+        File::open(path)
+            .map_err(|e| MinervaError::model_loading(format!("Failed to open GGUF file: {}", e)))
+    }
+
+    /// Read header counts and all key-value metadata, leaving `file` at the tensor infos
+    fn read_metadata(file: &mut File) -> MinervaResult<(u64, u64, GGUFModelMetadata)> {
+        // Validate header and get version
+        let _version = GGUFHeaderValidator::validate(file)?;
+
+        // Read tensor and KV counts
+        let tensor_count = Self::read_u64(file)?;
+        let kv_count = Self::read_u64(file)?;
+
+        let mut metadata = GGUFModelMetadata {
+            name: None,
+            architecture: None,
+            context_window: None,
+            embedding_length: None,
+            feed_forward_length: None,
+            attention_head_count: None,
+            attention_head_count_kv: None,
+            layer_count: None,
+            quantization_version: None,
+        };
+        for _ in 0..kv_count {
+            GGUFKVParser::parse_kv_pair(file, &mut metadata)?;
+        }
+        Ok((tensor_count, kv_count, metadata))
+    }
+
     fn read_u64(file: &mut File) -> MinervaResult<u64> {
         let mut buf = [0u8; 8];
         file.read_exact(&mut buf)
-            .map_err(|e| MinervaError::ModelLoadingError(e.to_string()))?;
+            .map_err(|e| MinervaError::model_loading(e.to_string()))?;
         Ok(u64::from_le_bytes(buf))
     }
 }
@@ -131,6 +155,21 @@ mod tests {
         assert!(tensors.is_empty());
     }
 
+    #[test]
+    fn test_validate_reads_metadata_only() {
+        let file = create_minimal_gguf();
+        let metadata = GGUFModelLoader::validate(file.path()).unwrap();
+        assert!(metadata.architecture.is_none());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_magic() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"NOPE").unwrap();
+        file.flush().unwrap();
+        assert!(GGUFModelLoader::validate(file.path()).is_err());
+    }
+
     #[test]
     fn test_read_u64() {
         let mut file = NamedTempFile::new().unwrap();
@@ -55,7 +55,9 @@ impl GGUFMetadataStore {
         match key {
             "general.name" => metadata.name = Some(value.to_string()),
             "llama.model_name" => metadata.name = Some(value.to_string()),
-            "llama.architecture" => metadata.architecture = Some(value.to_string()),
+            "general.architecture" | "llama.architecture" => {
+                metadata.architecture = Some(value.to_string());
+            }
             _ => {}
         }
     }
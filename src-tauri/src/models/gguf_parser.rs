@@ -4,7 +4,7 @@ use super::gguf_header::{
 use super::gguf_reader::{read_string_value, read_u32_value, skip_value};
 use crate::error::{MinervaError, MinervaResult};
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufReader, Cursor, Read, Seek};
 use std::path::Path;
 
 /// GGUF file format parser for extracting model metadata
@@ -16,24 +16,39 @@ pub struct GGUFMetadata {
     pub context_window: Option<usize>,
     pub model_name: Option<String>,
     pub quantization: Option<String>,
+    /// Jinja chat template from `tokenizer.chat_template`, if the model ships one
+    pub chat_template: Option<String>,
+    /// Set when the model declares a pooling type, i.e. it produces embeddings
+    pub is_embedding_model: bool,
 }
 
 impl GGUFParser {
     /// Parse GGUF file and extract metadata
     pub fn parse_metadata(path: &Path) -> MinervaResult<GGUFMetadata> {
-        let mut file = File::open(path).map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to open GGUF file: {}", e))
-        })?;
+        let file = File::open(path)
+            .map_err(|e| MinervaError::model_loading(format!("Failed to open GGUF file: {}", e)))?;
+        Self::parse_stream(&mut BufReader::new(file))
+    }
+
+    /// Parse metadata from an in-memory GGUF image
+    ///
+    /// Never panics on malformed input: truncation, oversized lengths and
+    /// invalid UTF-8 all surface as `Err` or lossy strings. Exercised by the
+    /// `fuzz_gguf_parser` cargo-fuzz target.
+    pub fn parse(data: &[u8]) -> MinervaResult<GGUFMetadata> {
+        Self::parse_stream(&mut Cursor::new(data))
+    }
 
-        validate_magic(&mut file)?;
-        validate_version(&mut file)?;
-        skip_tensor_count(&mut file)?;
+    fn parse_stream<R: Read + Seek>(file: &mut R) -> MinervaResult<GGUFMetadata> {
+        validate_magic(file)?;
+        validate_version(file)?;
+        skip_tensor_count(file)?;
 
-        let kv_count = read_kv_count(&mut file)?;
+        let kv_count = read_kv_count(file)?;
         let mut metadata = GGUFMetadata::default();
 
         for _ in 0..kv_count {
-            match Self::parse_kv_pair(&mut file, &mut metadata) {
+            match Self::parse_kv_pair(file, &mut metadata) {
                 Ok(_) => continue,
                 Err(_) => break,
             }
@@ -43,7 +58,10 @@ impl GGUFParser {
     }
 
     /// Parse a single key-value pair from GGUF file
-    fn parse_kv_pair(file: &mut File, metadata: &mut GGUFMetadata) -> MinervaResult<()> {
+    fn parse_kv_pair<R: Read + Seek>(
+        file: &mut R,
+        metadata: &mut GGUFMetadata,
+    ) -> MinervaResult<()> {
         let key = read_key(file)?;
         let value_type = read_value_type(file)?;
 
@@ -63,6 +81,13 @@ impl GGUFParser {
                     metadata.context_window = Some(value as usize);
                 }
             }
+            "tokenizer.chat_template" if value_type == 3 => {
+                metadata.chat_template = read_string_value(file).ok();
+            }
+            key if key.ends_with(".pooling_type") => {
+                metadata.is_embedding_model = true;
+                skip_value(file, value_type)?;
+            }
             "gptq.desc_act" if value_type == 1 => {
                 metadata.quantization = Some("GPTQ".to_string());
                 let _ = file.read_exact(&mut [0u8; 1]);
@@ -83,21 +108,20 @@ mod tests {
     use tempfile::TempDir;
 
     fn create_minimal_gguf(path: &Path) -> MinervaResult<()> {
-        let mut file = File::create(path).map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to create file: {}", e))
-        })?;
+        let mut file = File::create(path)
+            .map_err(|e| MinervaError::model_loading(format!("Failed to create file: {}", e)))?;
 
         file.write_all(&[0x47, 0x47, 0x55, 0x46])
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Write error: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Write error: {}", e)))?;
 
         file.write_all(&2u32.to_le_bytes())
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Write error: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Write error: {}", e)))?;
 
         file.write_all(&0u64.to_le_bytes())
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Write error: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Write error: {}", e)))?;
 
         file.write_all(&0u64.to_le_bytes())
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Write error: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Write error: {}", e)))?;
 
         Ok(())
     }
@@ -114,6 +138,35 @@ mod tests {
         assert_eq!(metadata.model_name, None);
     }
 
+    #[test]
+    fn test_parse_chat_template_and_pooling() {
+        let temp_dir = TempDir::new().unwrap();
+        let gguf_path = temp_dir.path().join("chat.gguf");
+
+        let mut file = File::create(&gguf_path).unwrap();
+        file.write_all(&[0x47, 0x47, 0x55, 0x46]).unwrap();
+        file.write_all(&2u32.to_le_bytes()).unwrap();
+        file.write_all(&0u64.to_le_bytes()).unwrap();
+        file.write_all(&2u64.to_le_bytes()).unwrap();
+        for (key, value_type, value) in [
+            ("tokenizer.chat_template", 3u32, "{{ tools }}".as_bytes()),
+            ("bert.pooling_type", 0u32, &[1u8, 0, 0, 0][..]),
+        ] {
+            file.write_all(&(key.len() as u32).to_le_bytes()).unwrap();
+            file.write_all(key.as_bytes()).unwrap();
+            file.write_all(&value_type.to_le_bytes()).unwrap();
+            if value_type == 3 {
+                file.write_all(&(value.len() as u32).to_le_bytes()).unwrap();
+            }
+            file.write_all(value).unwrap();
+        }
+        drop(file);
+
+        let metadata = GGUFParser::parse_metadata(&gguf_path).unwrap();
+        assert_eq!(metadata.chat_template.as_deref(), Some("{{ tools }}"));
+        assert!(metadata.is_embedding_model);
+    }
+
     #[test]
     fn test_parse_invalid_magic() {
         let temp_dir = TempDir::new().unwrap();
@@ -128,6 +181,42 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_bytes_rejects_truncated_input() {
+        assert!(GGUFParser::parse(&[]).is_err());
+        assert!(GGUFParser::parse(b"GGUF").is_err());
+        assert!(GGUFParser::parse(&[0x47, 0x47, 0x55, 0x46, 2, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_parse_bytes_survives_oversized_lengths() {
+        let mut data = vec![0x47, 0x47, 0x55, 0x46];
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&u32::MAX.to_le_bytes());
+        data.extend_from_slice(&[0xff, 0xfe, 0xfd]);
+
+        let metadata = GGUFParser::parse(&data).unwrap();
+        assert_eq!(metadata.model_name, None);
+    }
+
+    #[test]
+    fn test_parse_bytes_lossy_utf8_name() {
+        let mut data = vec![0x47, 0x47, 0x55, 0x46];
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&12u32.to_le_bytes());
+        data.extend_from_slice(b"general.name");
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[0xff, b'a']);
+
+        let metadata = GGUFParser::parse(&data).unwrap();
+        assert_eq!(metadata.model_name.as_deref(), Some("\u{fffd}a"));
+    }
+
     #[test]
     fn test_parse_nonexistent_file() {
         let result = GGUFParser::parse_metadata(Path::new("/nonexistent/test.gguf"));
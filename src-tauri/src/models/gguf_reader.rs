@@ -1,50 +1,48 @@
+use super::gguf_header::read_bounded;
 use crate::error::{MinervaError, MinervaResult};
-use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 
-/// Read u32 value from GGUF file
-pub fn read_u32_value(file: &mut File) -> MinervaResult<u32> {
+/// Read u32 value from a GGUF stream
+pub fn read_u32_value<R: Read>(file: &mut R) -> MinervaResult<u32> {
     let mut bytes = [0u8; 4];
     file.read_exact(&mut bytes)
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to read u32 value: {}", e)))?;
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read u32 value: {}", e)))?;
     Ok(u32::from_le_bytes(bytes))
 }
 
-/// Read string value from GGUF file
-pub fn read_string_value(file: &mut File) -> MinervaResult<String> {
+/// Read string value from a GGUF stream
+pub fn read_string_value<R: Read>(file: &mut R) -> MinervaResult<String> {
     let mut len_bytes = [0u8; 4];
-    file.read_exact(&mut len_bytes).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read string length: {}", e))
-    })?;
-    let len = u32::from_le_bytes(len_bytes) as usize;
+    file.read_exact(&mut len_bytes)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read string length: {}", e)))?;
+    let len = u32::from_le_bytes(len_bytes);
 
-    let mut string_bytes = vec![0u8; len];
-    file.read_exact(&mut string_bytes).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read string value: {}", e))
-    })?;
+    let string_bytes = read_bounded(file, len)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read string value: {}", e)))?;
 
     Ok(String::from_utf8_lossy(&string_bytes).to_string())
 }
 
-/// Skip n bytes in file
-pub fn skip_n_bytes(file: &mut File, n: u64) -> MinervaResult<()> {
-    file.seek(SeekFrom::Current(n as i64))
-        .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to seek: {}", e)))?;
+/// Skip n bytes in the stream
+pub fn skip_n_bytes<R: Read + Seek>(file: &mut R, n: u64) -> MinervaResult<()> {
+    let offset = i64::try_from(n)
+        .map_err(|_| MinervaError::model_loading(format!("Skip too large: {}", n)))?;
+    file.seek(SeekFrom::Current(offset))
+        .map_err(|e| MinervaError::model_loading(format!("Failed to seek: {}", e)))?;
     Ok(())
 }
 
 /// Skip string value (reads length, then skips that many bytes)
-pub fn skip_string_value(file: &mut File) -> MinervaResult<()> {
+pub fn skip_string_value<R: Read + Seek>(file: &mut R) -> MinervaResult<()> {
     let mut len_bytes = [0u8; 4];
-    file.read_exact(&mut len_bytes).map_err(|e| {
-        MinervaError::ModelLoadingError(format!("Failed to read string length: {}", e))
-    })?;
+    file.read_exact(&mut len_bytes)
+        .map_err(|e| MinervaError::model_loading(format!("Failed to read string length: {}", e)))?;
     let len = u32::from_le_bytes(len_bytes) as u64;
     skip_n_bytes(file, len)
 }
 
-/// Skip value in GGUF file based on type
-pub fn skip_value(file: &mut File, value_type: u32) -> MinervaResult<()> {
+/// Skip value in a GGUF stream based on type
+pub fn skip_value<R: Read + Seek>(file: &mut R, value_type: u32) -> MinervaResult<()> {
     match value_type {
         0 => skip_n_bytes(file, 4),   // u32
         1 => skip_n_bytes(file, 1),   // bool
@@ -53,7 +51,7 @@ pub fn skip_value(file: &mut File, value_type: u32) -> MinervaResult<()> {
         4 => skip_n_bytes(file, 4),   // u32 (array)
         5 => skip_n_bytes(file, 4),   // i32 (array)
         6 => skip_n_bytes(file, 4),   // f32 (array)
-        _ => Err(MinervaError::ModelLoadingError(format!(
+        _ => Err(MinervaError::model_loading(format!(
             "Unknown GGUF value type: {}",
             value_type
         ))),
@@ -0,0 +1,390 @@
+/// Truncated GGUF Repair
+///
+/// An interrupted download leaves a GGUF file whose header and tensor table
+/// are intact but whose data section stops partway. Repair keeps every
+/// tensor whose data lies entirely inside the file and writes a new file
+/// with the same metadata, only the kept tensor infos (with the tensor count
+/// patched), and the data up to the end of the last complete tensor. Data
+/// offsets are relative to the data section, so kept infos stay valid as-is.
+/// The result is written beside the original and renamed over it.
+///
+/// Files with tensor types whose size is unknown are refused rather than
+/// guessed at, as are `.part` files, which a download can still resume.
+use crate::error::{MinervaError, MinervaResult};
+use memmap2::Mmap;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+/// Share of tensors that must survive for a repair to be worthwhile
+pub const MIN_RECOVERED_FRACTION: f64 = 0.8;
+
+const DEFAULT_ALIGNMENT: u64 = 32;
+const HEADER_LEN: usize = 24;
+const VALUE_TYPE_U32: u32 = 4;
+const VALUE_TYPE_STRING: u32 = 8;
+const VALUE_TYPE_ARRAY: u32 = 9;
+
+/// Outcome of `GGUFModelLoader::attempt_repair`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RepairReport {
+    Repaired {
+        original_size_mb: f64,
+        repaired_size_mb: f64,
+        tensors_recovered: usize,
+        tensors_lost: usize,
+    },
+    /// Too little survived; the file was left untouched
+    Unrecoverable {
+        tensors_recovered: usize,
+        tensors_lost: usize,
+    },
+}
+
+/// One tensor's table entry and where its data ends in the data section
+struct TensorSpan {
+    info: Range<usize>,
+    data_end: u64,
+}
+
+/// Cut `path` back to its complete tensors, see the module docs
+pub fn repair_truncated(path: &Path) -> MinervaResult<RepairReport> {
+    if path.extension().is_some_and(|ext| ext == "part") {
+        return Err(MinervaError::ValidationError(format!(
+            "{} is an unfinished download; resume it instead of repairing",
+            path.display()
+        )));
+    }
+    let file = File::open(path)?;
+    // SAFETY: the map is dropped before the file is replaced
+    let bytes = unsafe { Mmap::map(&file)? };
+    if bytes.len() < HEADER_LEN || &bytes[..4] != b"GGUF" {
+        return Err(MinervaError::model_loading(format!(
+            "{} is not a GGUF file",
+            path.display()
+        )));
+    }
+    let mut pos = 8;
+    let tensor_count = read_u64(&bytes, &mut pos)? as usize;
+
+    let (kv_end, alignment, tensors) = match read_table(&bytes, tensor_count) {
+        Ok(table) => table,
+        Err(e @ MinervaError::ValidationError(_)) => return Err(e),
+        Err(_) => {
+            return Ok(RepairReport::Unrecoverable {
+                tensors_recovered: 0,
+                tensors_lost: tensor_count,
+            });
+        }
+    };
+    let data_start = align(tensors.last().map_or(kv_end, |t| t.info.end), alignment)?;
+    let available = bytes.len().saturating_sub(data_start) as u64;
+    let kept: Vec<&TensorSpan> = tensors.iter().filter(|t| t.data_end <= available).collect();
+
+    let tensors_recovered = kept.len();
+    let tensors_lost = tensor_count - tensors_recovered;
+    if (tensors_recovered as f64) < tensor_count as f64 * MIN_RECOVERED_FRACTION {
+        return Ok(RepairReport::Unrecoverable {
+            tensors_recovered,
+            tensors_lost,
+        });
+    }
+
+    // Every kept `data_end` is within `available`, so this fits in the file
+    let data_len = kept.iter().map(|t| t.data_end).max().unwrap_or(0) as usize;
+    let partial = path.with_extension("gguf.repair");
+    let mut out = BufWriter::new(File::create(&partial)?);
+    out.write_all(&bytes[..8])?;
+    out.write_all(&(tensors_recovered as u64).to_le_bytes())?;
+    out.write_all(&bytes[16..kv_end])?;
+    let mut written = kv_end;
+    for tensor in &kept {
+        out.write_all(&bytes[tensor.info.clone()])?;
+        written += tensor.info.len();
+    }
+    let padding = align(written, alignment)? - written;
+    out.write_all(&vec![0u8; padding])?;
+    out.write_all(&bytes[data_start..data_start + data_len])?;
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+
+    let original_size = bytes.len();
+    drop(bytes);
+    fs::rename(&partial, path)?;
+    let repaired_size = written + padding + data_len;
+    tracing::info!(
+        "Repaired {}: kept {} tensors, dropped {}",
+        path.display(),
+        tensors_recovered,
+        tensors_lost
+    );
+    Ok(RepairReport::Repaired {
+        original_size_mb: to_mb(original_size),
+        repaired_size_mb: to_mb(repaired_size),
+        tensors_recovered,
+        tensors_lost,
+    })
+}
+
+/// End of the metadata block, the data alignment, and every tensor's span
+///
+/// A `ValidationError` means a tensor's size cannot be worked out, so the
+/// file must not be touched; other errors mean the table itself is cut off.
+fn read_table(bytes: &[u8], tensor_count: usize) -> MinervaResult<(usize, u64, Vec<TensorSpan>)> {
+    let mut pos = 16;
+    let kv_count = read_u64(bytes, &mut pos)?;
+    let mut alignment = DEFAULT_ALIGNMENT;
+    for _ in 0..kv_count {
+        let key_len = read_u64(bytes, &mut pos)?;
+        let is_alignment = take(bytes, &mut pos, key_len)? == b"general.alignment";
+        let value_type = read_u32(bytes, &mut pos)?;
+        if is_alignment && value_type == VALUE_TYPE_U32 {
+            alignment = u64::from(read_u32(bytes, &mut pos)?).max(1);
+        } else {
+            skip_value(bytes, &mut pos, value_type)?;
+        }
+    }
+    let kv_end = pos;
+
+    let mut tensors = Vec::new();
+    for _ in 0..tensor_count {
+        let start = pos;
+        let name_len = read_u64(bytes, &mut pos)?;
+        let name = String::from_utf8_lossy(take(bytes, &mut pos, name_len)?).into_owned();
+        let n_dims = read_u32(bytes, &mut pos)?;
+        let mut elements = Some(1u64);
+        for _ in 0..n_dims {
+            let dim = read_u64(bytes, &mut pos)?;
+            elements = elements.and_then(|n| n.checked_mul(dim));
+        }
+        let type_id = read_u32(bytes, &mut pos)?;
+        let offset = read_u64(bytes, &mut pos)?;
+
+        let Some((block, size)) = ggml_type_size(type_id) else {
+            return Err(MinervaError::ValidationError(format!(
+                "Tensor {} has unknown ggml type {}; refusing to repair",
+                name, type_id
+            )));
+        };
+        let data_end = elements
+            .and_then(|n| n.div_ceil(block).checked_mul(size))
+            .and_then(|len| offset.checked_add(len));
+        let Some(data_end) = data_end else {
+            return Err(MinervaError::ValidationError(format!(
+                "Tensor {} has an impossible size; refusing to repair",
+                name
+            )));
+        };
+        tensors.push(TensorSpan {
+            info: start..pos,
+            data_end,
+        });
+    }
+    Ok((kv_end, alignment, tensors))
+}
+
+/// `pos` rounded up to `alignment`
+fn align(pos: usize, alignment: u64) -> MinervaResult<usize> {
+    (pos as u64)
+        .checked_next_multiple_of(alignment)
+        .and_then(|aligned| usize::try_from(aligned).ok())
+        .ok_or_else(|| MinervaError::model_loading("GGUF data offset overflows".to_string()))
+}
+
+/// `(values per block, bytes per block)` of a ggml tensor type
+fn ggml_type_size(type_id: u32) -> Option<(u64, u64)> {
+    Some(match type_id {
+        0 | 26 => (1, 4),
+        1 | 25 | 30 => (1, 2),
+        24 => (1, 1),
+        27 | 28 => (1, 8),
+        2 => (32, 18),
+        3 => (32, 20),
+        6 => (32, 22),
+        7 => (32, 24),
+        8 => (32, 34),
+        9 => (32, 36),
+        10 => (256, 84),
+        11 => (256, 110),
+        12 => (256, 144),
+        13 => (256, 176),
+        14 => (256, 210),
+        15 => (256, 292),
+        _ => return None,
+    })
+}
+
+fn skip_value(bytes: &[u8], pos: &mut usize, value_type: u32) -> MinervaResult<()> {
+    let len = match value_type {
+        0 | 1 | 7 => 1,
+        2 | 3 => 2,
+        4..=6 => 4,
+        10..=12 => 8,
+        VALUE_TYPE_STRING => read_u64(bytes, pos)?,
+        VALUE_TYPE_ARRAY => {
+            let element_type = read_u32(bytes, pos)?;
+            for _ in 0..read_u64(bytes, pos)? {
+                skip_value(bytes, pos, element_type)?;
+            }
+            0
+        }
+        other => {
+            return Err(MinervaError::model_loading(format!(
+                "Unknown GGUF value type: {}",
+                other
+            )));
+        }
+    };
+    take(bytes, pos, len).map(|_| ())
+}
+
+fn take<'a>(bytes: &'a [u8], pos: &mut usize, len: u64) -> MinervaResult<&'a [u8]> {
+    let slice = usize::try_from(len)
+        .ok()
+        .and_then(|len| bytes.get(*pos..pos.checked_add(len)?))
+        .ok_or_else(|| MinervaError::model_loading("GGUF header is truncated".to_string()))?;
+    *pos += slice.len();
+    Ok(slice)
+}
+
+fn read_u32(bytes: &[u8], pos: &mut usize) -> MinervaResult<u32> {
+    let raw = take(bytes, pos, 4)?;
+    Ok(u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]))
+}
+
+fn read_u64(bytes: &[u8], pos: &mut usize) -> MinervaResult<u64> {
+    let raw = take(bytes, pos, 8)?;
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(raw);
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn to_mb(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::gpu::gguf_tensor_index::read_tensor_index;
+    use crate::inference::gpu::gguf_writer::{
+        GGUFWriter, MetadataValue, QuantizationType, TensorPlan,
+    };
+
+    /// Five 8x8 f32 tensors; each occupies 256 bytes of data
+    fn write_model(path: &Path) -> usize {
+        let plans: Vec<TensorPlan> = (0..5)
+            .map(|i| TensorPlan {
+                name: format!("blk.{}.weight", i),
+                shape: vec![8, 8],
+                encoding: QuantizationType::F32,
+            })
+            .collect();
+        let metadata = [("general.name", MetadataValue::String("tiny".to_string()))];
+        let mut writer = GGUFWriter::new(File::create(path).unwrap());
+        writer.write_header(&metadata, &plans).unwrap();
+        for plan in &plans {
+            writer.write_tensor(plan, &[1.0; 64]).unwrap();
+        }
+        writer.finish().unwrap();
+        fs::metadata(path).unwrap().len() as usize
+    }
+
+    fn truncate(path: &Path, len: usize) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_len(len as u64)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_repair_drops_partial_tail_tensor() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        let full = write_model(&path);
+        truncate(&path, full - 100);
+
+        let report = repair_truncated(&path).unwrap();
+        let RepairReport::Repaired {
+            tensors_recovered,
+            tensors_lost,
+            repaired_size_mb,
+            original_size_mb,
+        } = report
+        else {
+            panic!("expected a repair, got {:?}", report);
+        };
+        assert_eq!((tensors_recovered, tensors_lost), (4, 1));
+        assert!(repaired_size_mb < original_size_mb);
+
+        let bytes = fs::read(&path).unwrap();
+        let index = read_tensor_index(&bytes).unwrap();
+        assert_eq!(index.len(), 4);
+        assert_eq!(index[3].name, "blk.3.weight");
+        assert_eq!(bytes.len(), index[3].start + 256);
+    }
+
+    #[test]
+    fn test_refuses_when_most_tensors_are_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        let full = write_model(&path);
+        truncate(&path, full - 3 * 256 - 10);
+
+        assert_eq!(
+            repair_truncated(&path).unwrap(),
+            RepairReport::Unrecoverable {
+                tensors_recovered: 1,
+                tensors_lost: 4,
+            }
+        );
+        assert_eq!(
+            fs::metadata(&path).unwrap().len() as usize,
+            full - 3 * 256 - 10
+        );
+    }
+
+    #[test]
+    fn test_refuses_unknown_tensor_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        let full = write_model(&path);
+        let mut bytes = fs::read(&path).unwrap();
+        let first = read_tensor_index(&bytes).unwrap()[0].clone();
+        // The type id sits just before the tensor's u64 data offset
+        let name = first.name.as_bytes();
+        let name_at = bytes.windows(name.len()).position(|w| w == name).unwrap();
+        let type_at = name_at + name.len() + 4 + 2 * 8;
+        bytes[type_at..type_at + 4].copy_from_slice(&99u32.to_le_bytes());
+        fs::write(&path, &bytes[..full - 100]).unwrap();
+
+        assert!(matches!(
+            repair_truncated(&path),
+            Err(MinervaError::ValidationError(_))
+        ));
+        assert_eq!(fs::metadata(&path).unwrap().len() as usize, full - 100);
+    }
+
+    #[test]
+    fn test_leaves_part_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf.part");
+        let full = write_model(&path);
+        truncate(&path, full - 100);
+
+        assert!(repair_truncated(&path).is_err());
+        assert_eq!(fs::metadata(&path).unwrap().len() as usize, full - 100);
+    }
+
+    #[test]
+    fn test_rejects_non_gguf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        fs::write(&path, [0u8; 64]).unwrap();
+        assert!(repair_truncated(&path).is_err());
+    }
+}
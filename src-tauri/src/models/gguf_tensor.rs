@@ -4,6 +4,7 @@ pub use super::gguf_data_type::GGUFDataType;
 /// This module provides comprehensive support for loading and managing GGUF tensors
 /// with all quantization formats and data types.
 use std::fmt;
+use std::sync::Arc;
 
 /// Input for creating GGUF tensors
 #[derive(Clone)]
@@ -27,17 +28,21 @@ pub struct GGUFTensor {
     pub data_type: GGUFDataType,
     /// Tensor shape (dimensions)
     pub shape: Vec<u64>,
-    /// Raw tensor data
-    pub data: Vec<u8>,
+    /// Element step per dimension into `data`; dense unless transposed
+    pub strides: Vec<usize>,
+    /// Raw tensor data, shared between a tensor and its transposed views
+    pub data: Arc<[u8]>,
 }
 
 impl From<GGUFTensorData> for GGUFTensor {
     fn from(input: GGUFTensorData) -> Self {
+        let strides = row_major_strides(&input.shape);
         Self {
             name: input.name,
             data_type: input.data_type,
             shape: input.shape,
-            data: input.data,
+            strides,
+            data: input.data.into(),
         }
     }
 }
@@ -84,6 +89,7 @@ impl std::fmt::Debug for GGUFTensor {
             .field("name", &self.name)
             .field("data_type", &self.data_type)
             .field("shape", &self.shape)
+            .field("strides", &self.strides)
             .field("element_count", &self.element_count())
             .field("total_bytes", &self.total_bytes())
             .field("expected_bytes", &self.expected_bytes())
@@ -92,6 +98,18 @@ impl std::fmt::Debug for GGUFTensor {
     }
 }
 
+/// Strides for densely packed data as GGUF stores it
+///
+/// GGUF (like ggml) lists dimensions innermost first, so `shape[0]` is the
+/// contiguous one: a `[cols, rows]` matrix is row-major.
+pub fn row_major_strides(shape: &[u64]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for dim in 1..shape.len() {
+        strides[dim] = strides[dim - 1] * shape[dim - 1] as usize;
+    }
+    strides
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +127,7 @@ mod tests {
         assert_eq!(tensor.name, "test.weight");
         assert_eq!(tensor.element_count(), 200);
         assert_eq!(tensor.total_bytes(), 800);
+        assert_eq!(tensor.strides, vec![1, 10]);
         assert!(tensor.is_valid());
     }
 
@@ -23,7 +23,7 @@ impl GGUFTensorLoader {
         // Read data type
         let dtype_u32 = Self::read_u32(file)?;
         let data_type = GGUFDataType::from_u32(dtype_u32).ok_or_else(|| {
-            MinervaError::ModelLoadingError(format!("Unknown data type: {}", dtype_u32))
+            MinervaError::model_loading(format!("Unknown data type: {}", dtype_u32))
         })?;
 
         // Read data offset
@@ -34,24 +34,24 @@ impl GGUFTensorLoader {
         let expected_size = data_type.total_size(element_count as usize);
 
         // Save current position and read data
-        let current_pos = file.stream_position().map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to get position: {}", e))
-        })?;
+        let current_pos = file
+            .stream_position()
+            .map_err(|e| MinervaError::model_loading(format!("Failed to get position: {}", e)))?;
 
         // Seek to data offset
         file.seek(SeekFrom::Start(data_offset)).map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to seek to tensor data: {}", e))
+            MinervaError::model_loading(format!("Failed to seek to tensor data: {}", e))
         })?;
 
         // Read tensor data
         let mut data = vec![0u8; expected_size];
         file.read_exact(&mut data).map_err(|e| {
-            MinervaError::ModelLoadingError(format!("Failed to read tensor data: {}", e))
+            MinervaError::model_loading(format!("Failed to read tensor data: {}", e))
         })?;
 
         // Return to next tensor metadata position
         file.seek(SeekFrom::Start(current_pos))
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Failed to seek back: {}", e)))?;
+            .map_err(|e| MinervaError::model_loading(format!("Failed to seek back: {}", e)))?;
 
         let tensor_data = GGUFTensorData {
             name,
@@ -65,14 +65,14 @@ impl GGUFTensorLoader {
     fn read_u32(file: &mut File) -> MinervaResult<u32> {
         let mut buf = [0u8; 4];
         file.read_exact(&mut buf)
-            .map_err(|e| MinervaError::ModelLoadingError(e.to_string()))?;
+            .map_err(|e| MinervaError::model_loading(e.to_string()))?;
         Ok(u32::from_le_bytes(buf))
     }
 
     fn read_u64(file: &mut File) -> MinervaResult<u64> {
         let mut buf = [0u8; 8];
         file.read_exact(&mut buf)
-            .map_err(|e| MinervaError::ModelLoadingError(e.to_string()))?;
+            .map_err(|e| MinervaError::model_loading(e.to_string()))?;
         Ok(u64::from_le_bytes(buf))
     }
 
@@ -80,9 +80,9 @@ impl GGUFTensorLoader {
         let len = Self::read_u32(file)? as usize;
         let mut buf = vec![0u8; len];
         file.read_exact(&mut buf)
-            .map_err(|e| MinervaError::ModelLoadingError(e.to_string()))?;
+            .map_err(|e| MinervaError::model_loading(e.to_string()))?;
         String::from_utf8(buf)
-            .map_err(|e| MinervaError::ModelLoadingError(format!("Invalid UTF-8 in string: {}", e)))
+            .map_err(|e| MinervaError::model_loading(format!("Invalid UTF-8 in string: {}", e)))
     }
 }
 
@@ -0,0 +1,180 @@
+/// Strided Element Access for GGUF Tensors
+///
+/// A `GGUFTensor` addresses its elements through `strides`, so swapping two
+/// dimensions only swaps their shape and stride entries. The transposed view
+/// shares the original bytes, which lets matmul read weights column-major
+/// without materialising a transposed copy.
+use super::gguf_tensor::{GGUFDataType, GGUFTensor, row_major_strides};
+use crate::error::{MinervaError, MinervaResult};
+use std::sync::Arc;
+
+impl GGUFTensor {
+    /// Element at `indices` (innermost dimension first), located via `strides`
+    ///
+    /// Errors if `indices` does not match the tensor rank, an index is out
+    /// of bounds, the element lies past the end of `data`, or the data type
+    /// is block-quantized (dequantize those first).
+    pub fn get(&self, indices: &[usize]) -> MinervaResult<f32> {
+        if indices.len() != self.shape.len() {
+            return Err(self.invalid(format!(
+                "expected {} indices, got {}",
+                self.shape.len(),
+                indices.len()
+            )));
+        }
+        let mut offset = 0usize;
+        for ((&idx, &dim), &stride) in indices.iter().zip(&self.shape).zip(&self.strides) {
+            if idx as u64 >= dim {
+                return Err(self.invalid(format!("index {} >= {}", idx, dim)));
+            }
+            offset = idx
+                .checked_mul(stride)
+                .and_then(|step| offset.checked_add(step))
+                .ok_or_else(|| self.invalid("element offset overflows".to_string()))?;
+        }
+        self.element_at(offset)
+    }
+
+    /// View with dimensions `dim_a` and `dim_b` swapped, sharing the same data
+    pub fn transpose(&self, dim_a: usize, dim_b: usize) -> GGUFTensor {
+        let mut view = GGUFTensor {
+            name: self.name.clone(),
+            data_type: self.data_type,
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            data: Arc::clone(&self.data),
+        };
+        view.shape.swap(dim_a, dim_b);
+        view.strides.swap(dim_a, dim_b);
+        view
+    }
+
+    /// Whether elements are laid out densely in row-major order
+    pub fn is_row_major(&self) -> bool {
+        self.strides == row_major_strides(&self.shape)
+    }
+
+    fn element_at(&self, offset: usize) -> MinervaResult<f32> {
+        let size = self.data_type.element_size();
+        let bytes = offset
+            .checked_mul(size)
+            .and_then(|start| self.data.get(start..start.checked_add(size)?))
+            .ok_or_else(|| {
+                self.invalid(format!(
+                    "element {} is past the end of {} data bytes",
+                    offset,
+                    self.data.len()
+                ))
+            })?;
+        Ok(match self.data_type {
+            GGUFDataType::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            GGUFDataType::F16 => f16_to_f32(u16::from_le_bytes([bytes[0], bytes[1]])),
+            GGUFDataType::I8 => bytes[0] as i8 as f32,
+            GGUFDataType::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+            GGUFDataType::I32 => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32
+            }
+            other => {
+                return Err(self.invalid(format!("no per-element access for {}", other)));
+            }
+        })
+    }
+
+    fn invalid(&self, reason: String) -> MinervaError {
+        MinervaError::ValidationError(format!("{}: {}", self.name, reason))
+    }
+}
+
+/// Widen IEEE 754 half precision bits to f32
+pub(crate) fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits as u32) & 0x8000) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits as u32) & 0x3ff;
+    match exponent {
+        0 => {
+            let magnitude = mantissa as f32 * 2f32.powi(-24);
+            if sign == 0 { magnitude } else { -magnitude }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::gguf_tensor::GGUFTensorData;
+
+    fn f32_tensor(shape: Vec<u64>, values: &[f32]) -> GGUFTensor {
+        GGUFTensor::new(GGUFTensorData {
+            name: "test.weight".to_string(),
+            data_type: GGUFDataType::F32,
+            shape,
+            data: values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_get_innermost_dimension_first() {
+        // Two rows of three columns: shape is [cols, rows]
+        let tensor = f32_tensor(vec![3, 2], &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert!(tensor.is_row_major());
+        assert_eq!(tensor.strides, vec![1, 3]);
+        assert_eq!(tensor.get(&[2, 0]).unwrap(), 2.0);
+        assert_eq!(tensor.get(&[0, 1]).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn test_transpose_swaps_indices_without_copying() {
+        let tensor = f32_tensor(vec![3, 2], &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        let transposed = tensor.transpose(0, 1);
+
+        assert_eq!(transposed.shape, vec![2, 3]);
+        assert_eq!(transposed.strides, vec![3, 1]);
+        assert!(!transposed.is_row_major());
+        assert!(Arc::ptr_eq(&tensor.data, &transposed.data));
+        for row in 0..2 {
+            for col in 0..3 {
+                assert_eq!(
+                    transposed.get(&[row, col]).unwrap(),
+                    tensor.get(&[col, row]).unwrap()
+                );
+            }
+        }
+        assert!(transposed.transpose(0, 1).is_row_major());
+    }
+
+    #[test]
+    fn test_get_f16() {
+        let bits: [u16; 4] = [0x3c00, 0xc000, 0x3555, 0x0001];
+        let tensor = GGUFTensor::new(GGUFTensorData {
+            name: "half".to_string(),
+            data_type: GGUFDataType::F16,
+            shape: vec![4],
+            data: bits.iter().flat_map(|b| b.to_le_bytes()).collect(),
+        });
+        assert_eq!(tensor.get(&[0]).unwrap(), 1.0);
+        assert_eq!(tensor.get(&[1]).unwrap(), -2.0);
+        assert!((tensor.get(&[2]).unwrap() - 1.0 / 3.0).abs() < 1e-3);
+        assert_eq!(tensor.get(&[3]).unwrap(), 2f32.powi(-24));
+    }
+
+    #[test]
+    fn test_get_rejects_bad_indices() {
+        let tensor = f32_tensor(vec![2, 2], &[0.0; 4]);
+        let err = tensor.get(&[2, 0]).unwrap_err();
+        assert!(err.to_string().contains("index 2 >= 2"));
+        assert!(tensor.get(&[0]).is_err());
+    }
+
+    #[test]
+    fn test_get_rejects_short_data() {
+        // Declares four elements but holds only three
+        let tensor = f32_tensor(vec![2, 2], &[0.0; 3]);
+        assert_eq!(tensor.get(&[0, 1]).unwrap(), 0.0);
+        assert!(matches!(
+            tensor.get(&[1, 1]),
+            Err(MinervaError::ValidationError(_))
+        ));
+    }
+}
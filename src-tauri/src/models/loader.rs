@@ -1,5 +1,7 @@
 use crate::error::{MinervaError, MinervaResult};
-use crate::models::{ModelInfo, gguf_parser::GGUFParser};
+use crate::models::{ModelCapabilities, ModelInfo, gguf_parser::GGUFParser};
+use rayon::prelude::*;
+use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
@@ -16,29 +18,28 @@ impl ModelLoader {
 
     /// Discover all GGUF model files in the models directory
     pub fn discover_models(&self) -> MinervaResult<Vec<ModelInfo>> {
-        let mut models = Vec::new();
+        self.discover_models_parallel()
+    }
 
+    /// Discover models using all cores: each top-level entry is walked on its
+    /// own thread and headers are parsed in parallel. Sorted by model ID.
+    pub fn discover_models_parallel(&self) -> MinervaResult<Vec<ModelInfo>> {
         if !self.models_dir.exists() {
-            return Ok(models);
+            return Ok(Vec::new());
         }
 
-        for entry in WalkDir::new(&self.models_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-
-            // Only process .gguf files
-            if path.extension().and_then(|s| s.to_str()) == Some("gguf") {
-                match self.load_model(path) {
-                    Ok(model_info) => models.push(model_info),
-                    Err(e) => {
-                        tracing::warn!("Failed to load model {}: {}", path.display(), e);
-                    }
+        let mut models: Vec<ModelInfo> = find_gguf_files(&self.models_dir)?
+            .par_iter()
+            .filter_map(|path| match self.load_model(path) {
+                Ok(model_info) => Some(model_info),
+                Err(e) => {
+                    tracing::warn!("Failed to load model {}: {}", path.display(), e);
+                    None
                 }
-            }
-        }
+            })
+            .collect();
 
+        models.sort_by(|a, b| a.id.cmp(&b.id));
         Ok(models)
     }
 
@@ -54,7 +55,7 @@ impl ModelLoader {
         let file_name = path
             .file_stem()
             .and_then(|n| n.to_str())
-            .ok_or_else(|| MinervaError::ModelLoadingError("Invalid model filename".to_string()))?
+            .ok_or_else(|| MinervaError::model_loading("Invalid model filename".to_string()))?
             .to_string();
 
         // Parse GGUF metadata
@@ -75,12 +76,32 @@ impl ModelLoader {
             owned_by: "local".to_string(),
             context_window: gguf_metadata.context_window.or(Some(4096)),
             max_output_tokens: Some(2048),
+            capabilities: ModelCapabilities::from_gguf(&gguf_metadata),
         };
 
         Ok(model_info)
     }
 }
 
+/// All `.gguf` files under `dir`, walking top-level entries in parallel
+fn find_gguf_files(dir: &Path) -> MinervaResult<Vec<PathBuf>> {
+    let entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+
+    Ok(entries
+        .par_iter()
+        .flat_map_iter(|entry| {
+            WalkDir::new(entry)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .map(|e| e.into_path())
+                .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("gguf"))
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,6 +136,34 @@ mod tests {
         assert_eq!(models.len(), 0);
     }
 
+    #[test]
+    fn test_discover_models_parallel_sorted_and_recursive() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("family").join("variant");
+        fs::create_dir_all(&nested).unwrap();
+        for name in ["zeta", "alpha", "mid"] {
+            fs::write(temp_dir.path().join(format!("{}.gguf", name)), "x").unwrap();
+        }
+        fs::write(nested.join("beta.gguf"), "x").unwrap();
+
+        let loader = ModelLoader::new(temp_dir.path().to_path_buf());
+        let ids: Vec<String> = loader
+            .discover_models_parallel()
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(ids, vec!["alpha", "beta", "mid", "zeta"]);
+
+        let sequential: Vec<String> = loader
+            .discover_models()
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(sequential, ids);
+    }
+
     #[test]
     fn test_load_model_file_not_found() {
         let loader = ModelLoader::new(PathBuf::from("/tmp/models"));
@@ -10,15 +10,17 @@ pub mod gguf_loader;
 pub mod gguf_metadata_store;
 pub mod gguf_parser;
 pub mod gguf_reader;
+pub mod gguf_repair;
 pub mod gguf_tensor;
 pub mod gguf_tensor_loader;
+pub mod gguf_tensor_view;
 pub mod loader;
 pub mod model_info;
 pub mod model_registry;
 
 pub use chat_types::{
     ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse, ChatMessage, Choice,
-    ChoiceDelta, DeltaMessage, Usage,
+    ChoiceDelta, DeltaMessage, StreamOptions, Usage,
 };
-pub use model_info::{ModelInfo, ModelsListResponse};
+pub use model_info::{ModelCapabilities, ModelInfo, ModelsListResponse};
 pub use model_registry::ModelRegistry;
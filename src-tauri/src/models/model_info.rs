@@ -1,5 +1,46 @@
+use super::gguf_parser::GGUFMetadata;
 use serde::{Deserialize, Serialize};
 
+/// OpenAI-style capability flags for a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    pub fine_tune: bool,
+    pub completion: bool,
+    pub chat_completion: bool,
+    pub embeddings: bool,
+    pub function_calling: bool,
+}
+
+impl Default for ModelCapabilities {
+    /// Plain text completion only
+    fn default() -> Self {
+        Self {
+            fine_tune: false,
+            completion: true,
+            chat_completion: false,
+            embeddings: false,
+            function_calling: false,
+        }
+    }
+}
+
+impl ModelCapabilities {
+    /// Derive capabilities from GGUF metadata
+    ///
+    /// A chat template enables chat completions, and function calling when the
+    /// template renders `tools`; a pooling layer marks an embeddings model.
+    pub fn from_gguf(metadata: &GGUFMetadata) -> Self {
+        let template = metadata.chat_template.as_deref();
+        Self {
+            fine_tune: false,
+            completion: !metadata.is_embedding_model,
+            chat_completion: template.is_some(),
+            embeddings: metadata.is_embedding_model,
+            function_calling: template.is_some_and(|t| t.contains("tools")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct ModelInfo {
@@ -11,6 +52,8 @@ pub struct ModelInfo {
     pub context_window: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_output_tokens: Option<usize>,
+    #[serde(default)]
+    pub capabilities: ModelCapabilities,
 }
 
 #[derive(Debug, Serialize)]
@@ -18,3 +61,33 @@ pub struct ModelsListResponse {
     pub object: String,
     pub data: Vec<ModelInfo>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_from_chat_model() {
+        let metadata = GGUFMetadata {
+            chat_template: Some("{% for tool in tools %}".to_string()),
+            ..Default::default()
+        };
+        let capabilities = ModelCapabilities::from_gguf(&metadata);
+        assert!(capabilities.completion);
+        assert!(capabilities.chat_completion);
+        assert!(capabilities.function_calling);
+        assert!(!capabilities.embeddings);
+    }
+
+    #[test]
+    fn test_capabilities_from_embedding_model() {
+        let metadata = GGUFMetadata {
+            is_embedding_model: true,
+            ..Default::default()
+        };
+        let capabilities = ModelCapabilities::from_gguf(&metadata);
+        assert!(capabilities.embeddings);
+        assert!(!capabilities.completion);
+        assert!(!capabilities.chat_completion);
+    }
+}
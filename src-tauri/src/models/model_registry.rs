@@ -3,7 +3,6 @@ use std::collections::HashMap;
 
 pub struct ModelRegistry {
     models: HashMap<String, ModelInfo>,
-    #[allow(dead_code)]
     model_paths: HashMap<String, std::path::PathBuf>,
 }
 
@@ -24,6 +23,11 @@ impl ModelRegistry {
         self.models.values().cloned().collect()
     }
 
+    /// File the model with `id` was registered from
+    pub fn get_model_path(&self, id: &str) -> Option<&std::path::Path> {
+        self.model_paths.get(id).map(|path| path.as_path())
+    }
+
     #[allow(dead_code)]
     pub fn add_model(&mut self, model: ModelInfo, path: std::path::PathBuf) {
         let id = model.id.clone();
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Health of one component, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthState {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+impl HealthState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HealthState::Healthy => "healthy",
+            HealthState::Degraded => "degraded",
+            HealthState::Unhealthy => "unhealthy",
+        }
+    }
+}
+
+/// Component status details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentStatus {
+    /// Healthy, degraded or unhealthy
+    pub status: HealthState,
+    /// Human-readable message
+    pub message: String,
+    /// When the component was checked, RFC3339
+    pub last_checked: String,
+    /// Optional metrics (usage %, latency, etc)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<HashMap<String, String>>,
+}
+
+impl ComponentStatus {
+    /// Status checked now
+    pub fn new(status: HealthState, message: &str) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+            last_checked: chrono::Local::now().to_rfc3339(),
+            details: None,
+        }
+    }
+
+    /// Create healthy component
+    pub fn healthy(message: &str) -> Self {
+        Self::new(HealthState::Healthy, message)
+    }
+
+    /// Create degraded component, still able to serve
+    pub fn degraded(message: &str) -> Self {
+        Self::new(HealthState::Degraded, message)
+    }
+
+    /// Create failed component
+    pub fn unhealthy(message: &str) -> Self {
+        Self::new(HealthState::Unhealthy, message)
+    }
+
+    /// Add details
+    pub fn with_details(mut self, details: HashMap<String, String>) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.status == HealthState::Healthy
+    }
+}
+
+impl Default for ComponentStatus {
+    fn default() -> Self {
+        Self::healthy("OK")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_status_healthy() {
+        let c = ComponentStatus::healthy("test");
+        assert!(c.is_healthy());
+        assert!(!c.last_checked.is_empty());
+    }
+
+    #[test]
+    fn test_component_status_degraded() {
+        let c = ComponentStatus::degraded("test");
+        assert_eq!(c.status, HealthState::Degraded);
+        assert!(!c.is_healthy());
+    }
+
+    #[test]
+    fn test_health_state_order_and_serialization() {
+        assert!(HealthState::Healthy < HealthState::Degraded);
+        assert!(HealthState::Degraded < HealthState::Unhealthy);
+        let json = serde_json::to_string(&ComponentStatus::unhealthy("down")).unwrap();
+        assert!(json.contains("\"status\":\"unhealthy\""));
+    }
+}
@@ -4,8 +4,9 @@
 /// - GET /health - Component health status
 /// - GET /ready - Readiness probe
 /// - GET /metrics - Performance metrics
-pub use crate::observability::health::{ComponentInfo, ComponentStatuses, HealthEndpointResponse};
+pub use crate::observability::health::{ComponentStatus, HealthEndpointResponse, HealthState};
 pub use crate::observability::metrics_response::{
-    CacheMetrics, ErrorMetrics, MetricsResponse, RequestMetrics, ResponseTimeMetrics,
+    CacheMetrics, ErrorMetrics, GpuMetrics, MetricsResponse, RequestMetrics, ResponseTimeMetrics,
+    TimeoutMetrics,
 };
-pub use crate::observability::readiness::ReadinessResponse;
+pub use crate::observability::readiness::{ReadinessChecker, ReadinessResponse, ReadinessStatus};
@@ -0,0 +1,267 @@
+/// GPU Utilization Sampling
+///
+/// On macOS the first `IOAccelerator` service publishes a
+/// `PerformanceStatistics` dictionary in the IOKit registry with the device
+/// utilization and the memory the GPU has in use. Total memory is the
+/// unified system memory (`hw.memsize`). Other platforms, and `cpu-only`
+/// builds, report zeros.
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// `PerformanceStatistics` key for utilization percent
+pub const UTILIZATION_KEY: &str = "Device Utilization %";
+/// `PerformanceStatistics` key for framebuffer (VRAM) bytes in use
+pub const FRAMEBUFFER_KEY: &str = "vramUsedBytes";
+/// `PerformanceStatistics` key for system memory bytes the GPU has in use
+pub const COMPUTE_KEY: &str = "In use system memory";
+
+/// Samples averaged by [`GpuStatsWindow`] (one minute at the 5s interval)
+pub const GPU_STATS_WINDOW: usize = 12;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// One GPU utilization reading
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct GpuStats {
+    pub utilization_percent: f64,
+    pub framebuffer_used_mb: u64,
+    pub compute_used_mb: u64,
+    pub memory_total_mb: u64,
+}
+
+impl GpuStats {
+    /// Read the current GPU statistics, zeros when they are unavailable
+    pub fn sample() -> Self {
+        let total_bytes = platform::total_memory_bytes().unwrap_or(0);
+        platform::performance_statistics()
+            .map(|stats| Self::from_performance_statistics(&stats, total_bytes))
+            .unwrap_or_default()
+    }
+
+    /// Build from numeric `PerformanceStatistics` entries
+    pub fn from_performance_statistics(stats: &HashMap<String, i64>, total_bytes: u64) -> Self {
+        let bytes = |key: &str| stats.get(key).map_or(0, |&v| v.max(0) as u64);
+        Self {
+            utilization_percent: stats.get(UTILIZATION_KEY).map_or(0.0, |&v| v as f64),
+            framebuffer_used_mb: bytes(FRAMEBUFFER_KEY) / BYTES_PER_MB,
+            compute_used_mb: bytes(COMPUTE_KEY) / BYTES_PER_MB,
+            memory_total_mb: total_bytes / BYTES_PER_MB,
+        }
+    }
+
+    /// Framebuffer plus compute memory in use
+    pub fn memory_used_mb(&self) -> u64 {
+        self.framebuffer_used_mb + self.compute_used_mb
+    }
+}
+
+/// Moving average over the most recent GPU samples
+#[derive(Debug, Default)]
+pub struct GpuStatsWindow {
+    samples: VecDeque<GpuStats>,
+}
+
+impl GpuStatsWindow {
+    /// Add a sample, dropping the oldest beyond [`GPU_STATS_WINDOW`]
+    pub fn push(&mut self, stats: GpuStats) {
+        if self.samples.len() == GPU_STATS_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(stats);
+    }
+
+    /// Number of retained samples
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Average of the retained samples, zeros before the first one
+    pub fn average(&self) -> GpuStats {
+        let count = self.samples.len();
+        if count == 0 {
+            return GpuStats::default();
+        }
+        let mean = |f: fn(&GpuStats) -> u64| self.samples.iter().map(f).sum::<u64>() / count as u64;
+        GpuStats {
+            utilization_percent: self
+                .samples
+                .iter()
+                .map(|s| s.utilization_percent)
+                .sum::<f64>()
+                / count as f64,
+            framebuffer_used_mb: mean(|s| s.framebuffer_used_mb),
+            compute_used_mb: mean(|s| s.compute_used_mb),
+            memory_total_mb: mean(|s| s.memory_total_mb),
+        }
+    }
+}
+
+#[cfg(not(cpu_only))]
+mod platform {
+    use super::{COMPUTE_KEY, FRAMEBUFFER_KEY, UTILIZATION_KEY};
+    use std::collections::HashMap;
+    use std::ffi::{CString, c_char, c_void};
+
+    type CFTypeRef = *const c_void;
+
+    /// `kCFStringEncodingUTF8`
+    const UTF8_ENCODING: u32 = 0x0800_0100;
+    /// `kCFNumberSInt64Type`
+    const SINT64_TYPE: isize = 4;
+    /// `kIOMainPortDefault`
+    const MAIN_PORT_DEFAULT: u32 = 0;
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOServiceMatching(name: *const c_char) -> CFTypeRef;
+        fn IOServiceGetMatchingService(main_port: u32, matching: CFTypeRef) -> u32;
+        fn IORegistryEntryCreateCFProperty(
+            entry: u32,
+            key: CFTypeRef,
+            allocator: CFTypeRef,
+            options: u32,
+        ) -> CFTypeRef;
+        fn IOObjectRelease(object: u32) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFStringCreateWithCString(
+            allocator: CFTypeRef,
+            value: *const c_char,
+            encoding: u32,
+        ) -> CFTypeRef;
+        fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+        fn CFNumberGetValue(number: CFTypeRef, number_type: isize, out: *mut c_void) -> bool;
+        fn CFGetTypeID(value: CFTypeRef) -> usize;
+        fn CFNumberGetTypeID() -> usize;
+        fn CFDictionaryGetTypeID() -> usize;
+        fn CFRelease(value: CFTypeRef);
+    }
+
+    /// Numeric entries of the first IOAccelerator's `PerformanceStatistics`
+    pub(super) fn performance_statistics() -> Option<HashMap<String, i64>> {
+        // SAFETY: every CF object created here is released exactly once, and
+        // IOServiceGetMatchingService consumes the matching dictionary.
+        let key = cf_string("PerformanceStatistics")?;
+        unsafe {
+            let service = IOServiceGetMatchingService(
+                MAIN_PORT_DEFAULT,
+                IOServiceMatching(c"IOAccelerator".as_ptr()),
+            );
+            if service == 0 {
+                CFRelease(key);
+                return None;
+            }
+            let stats = IORegistryEntryCreateCFProperty(service, key, std::ptr::null(), 0);
+            CFRelease(key);
+            IOObjectRelease(service);
+            if stats.is_null() {
+                return None;
+            }
+            let mut values = HashMap::new();
+            if CFGetTypeID(stats) == CFDictionaryGetTypeID() {
+                for name in [UTILIZATION_KEY, FRAMEBUFFER_KEY, COMPUTE_KEY] {
+                    if let Some(value) = dictionary_number(stats, name) {
+                        values.insert(name.to_string(), value);
+                    }
+                }
+            }
+            CFRelease(stats);
+            Some(values)
+        }
+    }
+
+    /// Physical memory shared by CPU and GPU
+    pub(super) fn total_memory_bytes() -> Option<u64> {
+        let mut bytes: u64 = 0;
+        let mut len = std::mem::size_of::<u64>();
+        // SAFETY: `bytes` and `len` describe a valid u64 output buffer.
+        let rc = unsafe {
+            libc::sysctlbyname(
+                c"hw.memsize".as_ptr(),
+                &mut bytes as *mut u64 as *mut c_void,
+                &mut len,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        (rc == 0).then_some(bytes)
+    }
+
+    fn cf_string(value: &str) -> Option<CFTypeRef> {
+        let value = CString::new(value).ok()?;
+        // SAFETY: `value` is a valid NUL-terminated string for the call.
+        let string =
+            unsafe { CFStringCreateWithCString(std::ptr::null(), value.as_ptr(), UTF8_ENCODING) };
+        (!string.is_null()).then_some(string)
+    }
+
+    fn dictionary_number(dict: CFTypeRef, name: &str) -> Option<i64> {
+        let key = cf_string(name)?;
+        let mut out: i64 = 0;
+        // SAFETY: `dict` is a live CFDictionary; the returned value is borrowed
+        // and only read while `dict` is alive.
+        unsafe {
+            let value = CFDictionaryGetValue(dict, key);
+            CFRelease(key);
+            if value.is_null() || CFGetTypeID(value) != CFNumberGetTypeID() {
+                return None;
+            }
+            CFNumberGetValue(value, SINT64_TYPE, &mut out as *mut i64 as *mut c_void).then_some(out)
+        }
+    }
+}
+
+#[cfg(cpu_only)]
+mod platform {
+    use std::collections::HashMap;
+
+    pub(super) fn performance_statistics() -> Option<HashMap<String, i64>> {
+        None
+    }
+
+    pub(super) fn total_memory_bytes() -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_performance_statistics() {
+        let stats = HashMap::from([
+            (UTILIZATION_KEY.to_string(), 42),
+            (COMPUTE_KEY.to_string(), 512 * BYTES_PER_MB as i64),
+        ]);
+        let gpu = GpuStats::from_performance_statistics(&stats, 16 * 1024 * BYTES_PER_MB);
+
+        assert_eq!(gpu.utilization_percent, 42.0);
+        assert_eq!(gpu.framebuffer_used_mb, 0);
+        assert_eq!(gpu.memory_used_mb(), 512);
+        assert_eq!(gpu.memory_total_mb, 16 * 1024);
+    }
+
+    #[test]
+    fn test_window_moving_average() {
+        let mut window = GpuStatsWindow::default();
+        assert_eq!(window.average(), GpuStats::default());
+
+        for i in 0..(GPU_STATS_WINDOW + 2) {
+            window.push(GpuStats {
+                utilization_percent: if i < 2 { 100.0 } else { 20.0 },
+                compute_used_mb: 300,
+                ..Default::default()
+            });
+        }
+        let average = window.average();
+        assert_eq!(average.utilization_percent, 20.0);
+        assert_eq!(average.compute_used_mb, 300);
+    }
+}
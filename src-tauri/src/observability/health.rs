@@ -2,7 +2,7 @@
 ///
 /// Tracks:
 /// - Overall health status (healthy/degraded/unhealthy)
-/// - Component availability (GPU, CPU, memory, models, inference)
+/// - Component availability (inference, models, GPU, disk space)
 /// - Request acceptance capability
-pub use super::component_info::ComponentInfo;
-pub use super::health_types::{ComponentStatuses, HealthEndpointResponse};
+pub use super::component_status::{ComponentStatus, HealthState};
+pub use super::health_types::HealthEndpointResponse;
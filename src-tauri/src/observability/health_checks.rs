@@ -0,0 +1,144 @@
+/// Component Checks for /health
+///
+/// Each check turns one subsystem's current state into a `ComponentStatus`:
+/// the inference engine (latency against its baseline), the model registry,
+/// the GPU, and free disk space where models are stored.
+use super::component_status::ComponentStatus;
+use super::gpu_stats::GpuStats;
+use crate::models::ModelRegistry;
+use crate::resilience::health::LatencyHealth;
+use std::path::Path;
+
+/// Free space below which the models disk is reported degraded
+pub const MIN_FREE_DISK_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// GPU memory share above which the GPU is reported degraded
+pub const GPU_MEMORY_DEGRADED_PERCENT: f64 = 95.0;
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Inference engine: degraded while latency is far above its baseline
+pub fn check_inference(latency: &LatencyHealth) -> ComponentStatus {
+    match latency {
+        LatencyHealth::Healthy => ComponentStatus::healthy("Ready"),
+        LatencyHealth::Degraded { reason } => ComponentStatus::degraded(reason),
+    }
+}
+
+/// Model registry: degraded while no model is registered
+pub fn check_model_registry(registry: &ModelRegistry) -> ComponentStatus {
+    match registry.list_models().len() {
+        0 => ComponentStatus::degraded("No models registered"),
+        1 => ComponentStatus::healthy("1 model registered"),
+        count => ComponentStatus::healthy(&format!("{} models registered", count)),
+    }
+}
+
+/// GPU: degraded when its memory is nearly full
+pub fn check_gpu(gpu: &GpuStats) -> ComponentStatus {
+    if gpu.memory_total_mb == 0 {
+        return ComponentStatus::healthy("GPU statistics unavailable");
+    }
+    let used_percent = gpu.memory_used_mb() as f64 / gpu.memory_total_mb as f64 * 100.0;
+    let message = format!(
+        "{:.0}% utilized, {}MB of {}MB in use",
+        gpu.utilization_percent,
+        gpu.memory_used_mb(),
+        gpu.memory_total_mb
+    );
+    if used_percent > GPU_MEMORY_DEGRADED_PERCENT {
+        ComponentStatus::degraded(&message)
+    } else {
+        ComponentStatus::healthy(&message)
+    }
+}
+
+/// Disk holding `models_dir`: degraded below `MIN_FREE_DISK_BYTES` free
+pub fn check_disk_space(models_dir: &Path) -> ComponentStatus {
+    match available_disk_bytes(models_dir) {
+        Some(free) => disk_status(free),
+        None => ComponentStatus::healthy("Free disk space unknown"),
+    }
+}
+
+fn disk_status(free_bytes: u64) -> ComponentStatus {
+    let message = format!("{:.1}GB free", free_bytes as f64 / BYTES_PER_GB);
+    if free_bytes < MIN_FREE_DISK_BYTES {
+        tracing::warn!("Low disk space for models: {}", message);
+        ComponentStatus::degraded(&message)
+    } else {
+        ComponentStatus::healthy(&message)
+    }
+}
+
+/// Bytes available to unprivileged users on the filesystem holding `path`
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // field widths differ between platforms
+pub fn available_disk_bytes(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs is plain old data, so all-zero is a valid value.
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stats` is a valid output buffer.
+    let rc = unsafe { libc::statvfs(path.as_ptr(), &mut stats) };
+    (rc == 0).then(|| stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+/// Free space is not queried on this platform
+#[cfg(not(unix))]
+pub fn available_disk_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::component_status::HealthState;
+
+    #[test]
+    fn test_disk_below_threshold_is_degraded() {
+        assert_eq!(
+            disk_status(4 * 1024 * 1024 * 1024).status,
+            HealthState::Degraded
+        );
+        let plenty = disk_status(80 * 1024 * 1024 * 1024);
+        assert_eq!(plenty.status, HealthState::Healthy);
+        assert_eq!(plenty.message, "80.0GB free");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_available_disk_bytes_for_temp_dir() {
+        assert!(available_disk_bytes(&std::env::temp_dir()).is_some());
+        assert!(available_disk_bytes(Path::new("/does/not/exist")).is_none());
+    }
+
+    #[test]
+    fn test_inference_follows_latency() {
+        assert!(check_inference(&LatencyHealth::Healthy).is_healthy());
+        let slow = check_inference(&LatencyHealth::Degraded {
+            reason: "latency 4x baseline".to_string(),
+        });
+        assert_eq!(slow.status, HealthState::Degraded);
+        assert_eq!(slow.message, "latency 4x baseline");
+    }
+
+    #[test]
+    fn test_empty_registry_is_degraded() {
+        let status = check_model_registry(&ModelRegistry::new());
+        assert_eq!(status.status, HealthState::Degraded);
+    }
+
+    #[test]
+    fn test_gpu_memory_nearly_full_is_degraded() {
+        let gpu = GpuStats {
+            utilization_percent: 90.0,
+            framebuffer_used_mb: 0,
+            compute_used_mb: 15_800,
+            memory_total_mb: 16_000,
+        };
+        assert_eq!(check_gpu(&gpu).status, HealthState::Degraded);
+        assert!(check_gpu(&GpuStats::default()).is_healthy());
+    }
+}
@@ -1,5 +1,6 @@
-use super::component_info::ComponentInfo;
+use super::component_status::{ComponentStatus, HealthState};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Health response for /health endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,41 +9,20 @@ pub struct HealthEndpointResponse {
     pub status: String,
     /// Timestamp RFC3339
     pub timestamp: String,
-    /// Component statuses
-    pub components: ComponentStatuses,
+    /// Component statuses keyed by component name
+    pub components: HashMap<String, ComponentStatus>,
     /// Uptime in seconds
     pub uptime_seconds: u64,
     /// Version info
     pub version: String,
 }
 
-/// Individual component statuses
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ComponentStatuses {
-    /// GPU availability and status
-    pub gpu: ComponentInfo,
-    /// CPU availability and status
-    pub cpu: ComponentInfo,
-    /// Memory availability and status
-    pub memory: ComponentInfo,
-    /// Model loading system status
-    pub models: ComponentInfo,
-    /// Inference pipeline status
-    pub inference: ComponentInfo,
-}
-
 impl Default for HealthEndpointResponse {
     fn default() -> Self {
         Self {
             status: "healthy".to_string(),
             timestamp: chrono::Local::now().to_rfc3339(),
-            components: ComponentStatuses {
-                gpu: ComponentInfo::operational("Available"),
-                cpu: ComponentInfo::operational("Available"),
-                memory: ComponentInfo::operational("Healthy"),
-                models: ComponentInfo::operational("Ready"),
-                inference: ComponentInfo::operational("Ready"),
-            },
+            components: HashMap::new(),
             uptime_seconds: 0,
             version: "0.1.0".to_string(),
         }
@@ -50,21 +30,24 @@ impl Default for HealthEndpointResponse {
 }
 
 impl HealthEndpointResponse {
+    /// Add or replace a component's status
+    pub fn with_component(mut self, name: &str, status: ComponentStatus) -> Self {
+        self.components.insert(name.to_string(), status);
+        self
+    }
+
+    /// Worst component state; healthy when there are no components
+    pub fn overall_state(&self) -> HealthState {
+        self.components
+            .values()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(HealthState::Healthy)
+    }
+
     /// Determine overall status from components
     pub fn calculate_status(&mut self) {
-        let all_operational = self.components.gpu.operational
-            && self.components.cpu.operational
-            && self.components.memory.operational
-            && self.components.models.operational
-            && self.components.inference.operational;
-
-        self.status = if all_operational {
-            "healthy".to_string()
-        } else if self.components.cpu.operational && self.components.models.operational {
-            "degraded".to_string()
-        } else {
-            "unhealthy".to_string()
-        };
+        self.status = self.overall_state().as_str().to_string();
     }
 
     /// Is service healthy?
@@ -72,9 +55,9 @@ impl HealthEndpointResponse {
         self.status == "healthy"
     }
 
-    /// Can accept requests?
+    /// Can accept requests? Degraded components still serve
     pub fn can_accept_requests(&self) -> bool {
-        self.components.cpu.operational && self.components.models.operational
+        self.overall_state() != HealthState::Unhealthy
     }
 }
 
@@ -90,16 +73,18 @@ mod tests {
 
     #[test]
     fn test_health_response_calculate_status() {
-        let mut resp = HealthEndpointResponse::default();
-        resp.components.gpu.operational = false;
+        let mut resp = HealthEndpointResponse::default()
+            .with_component("inference", ComponentStatus::healthy("Ready"))
+            .with_component("gpu", ComponentStatus::degraded("Memory nearly full"));
         resp.calculate_status();
         assert_eq!(resp.status, "degraded");
     }
 
     #[test]
     fn test_health_response_unhealthy() {
-        let mut resp = HealthEndpointResponse::default();
-        resp.components.cpu.operational = false;
+        let mut resp = HealthEndpointResponse::default()
+            .with_component("gpu", ComponentStatus::degraded("Memory nearly full"))
+            .with_component("models", ComponentStatus::unhealthy("Registry unavailable"));
         resp.calculate_status();
         assert_eq!(resp.status, "unhealthy");
         assert!(!resp.is_healthy());
@@ -107,11 +92,21 @@ mod tests {
 
     #[test]
     fn test_health_response_can_accept_requests() {
-        let resp = HealthEndpointResponse::default();
+        let resp = HealthEndpointResponse::default()
+            .with_component("disk", ComponentStatus::degraded("Low"));
         assert!(resp.can_accept_requests());
 
-        let mut resp = HealthEndpointResponse::default();
-        resp.components.cpu.operational = false;
+        let resp = HealthEndpointResponse::default()
+            .with_component("models", ComponentStatus::unhealthy("Down"));
         assert!(!resp.can_accept_requests());
     }
+
+    #[test]
+    fn test_components_serialize_by_name() {
+        let resp = HealthEndpointResponse::default()
+            .with_component("disk", ComponentStatus::healthy("80GB free"));
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["components"]["disk"]["status"], "healthy");
+        assert_eq!(json["components"]["disk"]["message"], "80GB free");
+    }
 }
@@ -0,0 +1,149 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Upper bound in ms of each bucket except the last, which holds everything above 1s
+pub const BUCKET_BOUNDS_MS: [f32; 7] = [1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0];
+
+const BUCKET_COUNT: usize = BUCKET_BOUNDS_MS.len() + 1;
+
+/// Fixed-memory latency histogram: eight atomic counters, no per-sample storage
+#[derive(Debug, Default)]
+pub struct HistogramRecorder {
+    buckets: [AtomicU64; BUCKET_COUNT],
+}
+
+impl HistogramRecorder {
+    /// Create an empty histogram
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one latency sample in its bucket
+    pub fn record(&self, latency_ms: f32) {
+        let index = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound| latency_ms < bound)
+            .unwrap_or(BUCKET_COUNT - 1);
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Latency at percentile `p` (0-100), interpolated linearly within its bucket
+    ///
+    /// Samples above 1s cannot be placed more precisely than the overflow
+    /// bucket, so percentiles landing there report 1000ms.
+    pub fn percentile(&self, p: f32) -> f32 {
+        let counts = self.counts();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        let rank = (p.clamp(0.0, 100.0) / 100.0) * total as f32;
+        let mut below = 0u64;
+        for (index, &count) in counts.iter().enumerate() {
+            if count > 0 && (below + count) as f32 >= rank {
+                let (lower, upper) = bucket_range(index);
+                let fraction = ((rank - below as f32) / count as f32).clamp(0.0, 1.0);
+                return lower + fraction * (upper - lower);
+            }
+            below += count;
+        }
+        BUCKET_BOUNDS_MS[BUCKET_BOUNDS_MS.len() - 1]
+    }
+
+    /// Add another histogram's counts into this one
+    pub fn merge(&self, other: &HistogramRecorder) {
+        for (bucket, count) in self.buckets.iter().zip(other.counts()) {
+            bucket.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+
+    /// Zero every bucket
+    pub fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Number of samples recorded
+    pub fn count(&self) -> u64 {
+        self.counts().iter().sum()
+    }
+
+    /// Per-bucket sample counts, fastest bucket first
+    pub fn counts(&self) -> [u64; BUCKET_COUNT] {
+        std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed))
+    }
+}
+
+impl Clone for HistogramRecorder {
+    fn clone(&self) -> Self {
+        Self {
+            buckets: self.counts().map(AtomicU64::new),
+        }
+    }
+}
+
+/// `(lower, upper)` ms covered by bucket `index`; the overflow bucket has zero width
+fn bucket_range(index: usize) -> (f32, f32) {
+    let lower = if index == 0 {
+        0.0
+    } else {
+        BUCKET_BOUNDS_MS[index - 1]
+    };
+    let upper = BUCKET_BOUNDS_MS.get(index).copied().unwrap_or(lower);
+    (lower, upper)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_memory() {
+        assert_eq!(std::mem::size_of::<HistogramRecorder>(), 8 * 8);
+    }
+
+    #[test]
+    fn test_record_buckets() {
+        let histogram = HistogramRecorder::new();
+        for latency in [0.5, 1.0, 4.9, 7.0, 20.0, 75.0, 250.0, 999.0, 1000.0, 5000.0] {
+            histogram.record(latency);
+        }
+        assert_eq!(histogram.counts(), [1, 2, 1, 1, 1, 1, 1, 2]);
+        assert_eq!(histogram.count(), 10);
+    }
+
+    #[test]
+    fn test_percentile_interpolates_within_bucket() {
+        let histogram = HistogramRecorder::new();
+        for _ in 0..4 {
+            histogram.record(12.0);
+        }
+        assert_eq!(histogram.percentile(50.0), 30.0);
+        assert_eq!(histogram.percentile(100.0), 50.0);
+        assert_eq!(HistogramRecorder::new().percentile(99.0), 0.0);
+    }
+
+    #[test]
+    fn test_percentile_overflow_bucket() {
+        let histogram = HistogramRecorder::new();
+        histogram.record(3.0);
+        histogram.record(2500.0);
+        assert_eq!(histogram.percentile(99.0), 1000.0);
+    }
+
+    #[test]
+    fn test_merge_and_reset() {
+        let a = HistogramRecorder::new();
+        let b = HistogramRecorder::new();
+        a.record(2.0);
+        b.record(2.0);
+        b.record(600.0);
+
+        a.merge(&b);
+        assert_eq!(a.counts(), [0, 2, 0, 0, 0, 0, 1, 0]);
+
+        a.reset();
+        assert_eq!(a.count(), 0);
+        assert_eq!(b.count(), 2);
+    }
+}
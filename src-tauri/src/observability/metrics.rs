@@ -1,3 +1,4 @@
+pub use super::gpu_stats::GpuStats;
 pub use super::metrics_collector::MetricsCollector;
 
 /// Metrics snapshot
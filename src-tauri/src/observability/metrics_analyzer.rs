@@ -1,4 +1,27 @@
-use std::time::Duration;
+use super::response_time_store::{LatencySample, ResponseTimeStore};
+use std::time::{Duration, SystemTime};
+
+/// Number of preceding samples used for the rolling mean and std dev
+pub const ANOMALY_WINDOW: usize = 50;
+
+/// Samples required before anomaly detection starts
+pub const ANOMALY_MIN_BASELINE: usize = 10;
+
+/// Standard deviations above the rolling mean that count as a spike
+pub const ANOMALY_Z_THRESHOLD: f64 = 3.0;
+
+/// CPU temperature at which throttling is the likely cause
+const THERMAL_THROTTLE_CELSIUS: f64 = 85.0;
+
+/// A request whose latency spiked above the recent baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyEvent {
+    pub timestamp: SystemTime,
+    pub latency_ms: f64,
+    pub mean_ms: f64,
+    pub std_dev_ms: f64,
+    pub suggested_cause: String,
+}
 
 /// Metrics analysis and snapshot building
 pub struct MetricsAnalyzer;
@@ -35,6 +58,64 @@ impl MetricsAnalyzer {
             vals[p99_idx] as f64,
         )
     }
+
+    /// Flag requests more than 3σ above the rolling mean of the samples before them
+    pub fn detect_anomalies(store: &ResponseTimeStore) -> Vec<AnomalyEvent> {
+        let samples = store.get_samples();
+        let temperature = cpu_temperature_celsius();
+
+        (ANOMALY_MIN_BASELINE..samples.len())
+            .filter_map(|i| {
+                let window = &samples[i.saturating_sub(ANOMALY_WINDOW)..i];
+                anomaly_at(window, &samples[i], temperature)
+            })
+            .collect()
+    }
+}
+
+fn anomaly_at(
+    window: &[LatencySample],
+    sample: &LatencySample,
+    temperature: Option<f64>,
+) -> Option<AnomalyEvent> {
+    let millis: Vec<f64> = window.iter().map(|s| latency_ms(s.latency)).collect();
+    let mean = millis.iter().sum::<f64>() / millis.len() as f64;
+    let variance = millis.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / millis.len() as f64;
+    let std_dev = variance.sqrt();
+    let latency = latency_ms(sample.latency);
+
+    (latency - mean > ANOMALY_Z_THRESHOLD * std_dev.max(f64::EPSILON)).then(|| AnomalyEvent {
+        timestamp: sample.timestamp,
+        latency_ms: latency,
+        mean_ms: mean,
+        std_dev_ms: std_dev,
+        suggested_cause: suggest_cause(latency, mean, temperature),
+    })
+}
+
+fn suggest_cause(latency: f64, mean: f64, temperature: Option<f64>) -> String {
+    match temperature {
+        Some(celsius) if celsius >= THERMAL_THROTTLE_CELSIUS => {
+            format!("thermal throttling suspected (CPU at {:.0}°C)", celsius)
+        }
+        _ if latency > 10.0 * mean => {
+            "stall suspected (GC pause, swapping, or lock contention)".to_string()
+        }
+        _ => "memory pressure or resource contention suspected".to_string(),
+    }
+}
+
+fn latency_ms(latency: Duration) -> f64 {
+    latency.as_secs_f64() * 1000.0
+}
+
+/// Current CPU temperature, where the platform exposes it
+fn cpu_temperature_celsius() -> Option<f64> {
+    let raw = std::fs::read_to_string("/sys/class/thermal/thermal_zone0/temp").ok()?;
+    raw.trim()
+        .parse::<f64>()
+        .ok()
+        .map(|millidegrees| millidegrees / 1000.0)
 }
 
 #[cfg(test)]
@@ -64,4 +145,35 @@ mod tests {
         assert!(p95 >= p50);
         assert!(p99 >= p95);
     }
+
+    #[test]
+    fn test_detect_anomalies_flags_spike() {
+        let store = ResponseTimeStore::new();
+        for i in 0..30 {
+            store.store(Duration::from_millis(100 + i % 5));
+        }
+        store.store(Duration::from_millis(1500));
+        store.store(Duration::from_millis(101));
+
+        let events = MetricsAnalyzer::detect_anomalies(&store);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].latency_ms, 1500.0);
+        assert!((events[0].mean_ms - 102.0).abs() < 0.5);
+        assert!(!events[0].suggested_cause.is_empty());
+    }
+
+    #[test]
+    fn test_detect_anomalies_needs_baseline() {
+        let store = ResponseTimeStore::new();
+        store.store(Duration::from_millis(10));
+        store.store(Duration::from_millis(5000));
+        assert!(MetricsAnalyzer::detect_anomalies(&store).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_cause_prefers_thermal() {
+        assert!(suggest_cause(500.0, 100.0, Some(95.0)).contains("thermal"));
+        assert!(suggest_cause(1500.0, 100.0, Some(40.0)).contains("stall"));
+        assert!(suggest_cause(150.0, 100.0, None).contains("memory pressure"));
+    }
 }
@@ -1,6 +1,11 @@
+use super::metrics;
 use super::metrics_snapshot::MetricsSnapshot;
+use crate::config::SloConfig;
 use std::time::Duration;
 
+/// Efficiency below this is logged as a warning
+pub const EFFICIENCY_WARN_THRESHOLD: f32 = 0.8;
+
 /// Metrics calculation utilities
 pub struct MetricsCalculator;
 
@@ -35,6 +40,30 @@ impl MetricsCalculator {
         }
     }
 
+    /// Single 0.0-1.0 KPI: the success rate, scaled down when throughput
+    /// falls short of `slo.min_rps` or p99 latency exceeds `slo.target_p99_ms`
+    ///
+    /// A server that has not served any request yet scores 1.0. Pass a
+    /// snapshot whose `rps` covers a recent window; a lifetime average hides
+    /// a server that has stopped serving.
+    pub fn efficiency_score(snapshot: &metrics::MetricsSnapshot, slo: &SloConfig) -> f32 {
+        if snapshot.total_requests == 0 {
+            return 1.0;
+        }
+        let success = (1.0 - snapshot.error_rate_percent / 100.0).clamp(0.0, 1.0);
+        let throughput = if slo.min_rps > 0.0 {
+            (snapshot.rps / slo.min_rps).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let latency = if snapshot.p99_response_time_ms > 0.0 {
+            (slo.target_p99_ms / snapshot.p99_response_time_ms).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        (success * throughput * latency) as f32
+    }
+
     /// Calculate percentiles
     pub fn percentiles(response_times: &[Duration]) -> (f64, f64) {
         if response_times.is_empty() {
@@ -54,6 +83,7 @@ impl MetricsCalculator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::observability::metrics_snapshot_builder::MetricsSnapshotBuilder;
 
     #[test]
     fn test_calculate() {
@@ -84,4 +114,45 @@ mod tests {
         assert!(m.p95_response_time_ms >= 90.0);
         assert!(m.p99_response_time_ms >= 95.0);
     }
+
+    #[test]
+    fn test_efficiency_score_meets_slo() {
+        let slo = SloConfig {
+            min_rps: 5.0,
+            target_p99_ms: 100.0,
+        };
+        let snapshot = MetricsSnapshotBuilder::default()
+            .with_rps(10.0)
+            .with_p99_latency(80.0)
+            .build();
+        assert_eq!(MetricsCalculator::efficiency_score(&snapshot, &slo), 1.0);
+    }
+
+    #[test]
+    fn test_efficiency_score_combines_factors() {
+        let slo = SloConfig {
+            min_rps: 10.0,
+            target_p99_ms: 100.0,
+        };
+        let snapshot = MetricsSnapshotBuilder::default()
+            .with_error_rate(0.1)
+            .with_rps(5.0)
+            .with_p99_latency(200.0)
+            .build();
+        let score = MetricsCalculator::efficiency_score(&snapshot, &slo);
+        assert!((score - 0.9 * 0.5 * 0.5).abs() < 1e-6, "{}", score);
+    }
+
+    #[test]
+    fn test_efficiency_score_before_any_request() {
+        let idle = MetricsSnapshotBuilder::default()
+            .with_total_requests(0)
+            .with_rps(0.0)
+            .build();
+        let slo = SloConfig::default();
+        assert_eq!(MetricsCalculator::efficiency_score(&idle, &slo), 1.0);
+
+        let stalled = MetricsSnapshotBuilder::default().with_rps(0.0).build();
+        assert_eq!(MetricsCalculator::efficiency_score(&stalled, &slo), 0.0);
+    }
 }
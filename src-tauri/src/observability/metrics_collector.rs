@@ -1,12 +1,29 @@
+use super::gpu_stats::{GpuStats, GpuStatsWindow};
 use super::metrics::MetricsSnapshot;
+use super::metrics_analyzer::{AnomalyEvent, MetricsAnalyzer};
+use super::metrics_calculator::{EFFICIENCY_WARN_THRESHOLD, MetricsCalculator};
 use super::metrics_recorder::MetricsRecorder;
 use super::metrics_snapshot_builder::{SnapshotBuilder, SnapshotParams};
-use std::sync::Arc;
-use std::time::Duration;
+use crate::config::SloConfig;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(cpu_only))]
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+#[cfg(not(cpu_only))]
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+/// How often the background sampler reads GPU statistics
+pub const GPU_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Throughput in the efficiency score is measured over this trailing window
+pub const EFFICIENCY_RPS_WINDOW: Duration = Duration::from_secs(60);
 
 /// Metrics collector for request tracking
 pub struct MetricsCollector {
     recorder: Arc<MetricsRecorder>,
+    gpu: Arc<Mutex<GpuStatsWindow>>,
+    below_slo: Arc<AtomicBool>,
     start_time: std::time::Instant,
 }
 
@@ -15,6 +32,8 @@ impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             recorder: Arc::new(MetricsRecorder::new()),
+            gpu: Arc::new(Mutex::new(GpuStatsWindow::default())),
+            below_slo: Arc::new(AtomicBool::new(false)),
             start_time: std::time::Instant::now(),
         }
     }
@@ -39,6 +58,72 @@ impl MetricsCollector {
         self.recorder.record_cache_miss();
     }
 
+    /// Scan response times for latency spikes, logging and counting new ones
+    ///
+    /// Returns only anomalies that earlier calls have not already reported.
+    pub fn detect_anomalies(&self) -> Vec<AnomalyEvent> {
+        let events = MetricsAnalyzer::detect_anomalies(self.recorder.response_time_store());
+        let fresh = self.recorder.record_anomalies(events);
+        for event in &fresh {
+            tracing::warn!(
+                "Latency anomaly: {:.1}ms vs mean {:.1}ms (σ {:.1}ms); {}",
+                event.latency_ms,
+                event.mean_ms,
+                event.std_dev_ms,
+                event.suggested_cause
+            );
+        }
+        fresh
+    }
+
+    /// Get number of latency anomalies detected so far
+    pub fn latency_anomalies(&self) -> u64 {
+        self.recorder.latency_anomalies()
+    }
+
+    /// Read GPU utilization and memory use from the hardware
+    pub fn sample_gpu_stats() -> GpuStats {
+        GpuStats::sample()
+    }
+
+    /// Add a GPU sample to the moving average
+    pub fn record_gpu_sample(&self, stats: GpuStats) {
+        self.gpu.lock().unwrap().push(stats);
+    }
+
+    /// Moving average of recent GPU samples
+    pub fn gpu_stats(&self) -> GpuStats {
+        self.gpu.lock().unwrap().average()
+    }
+
+    /// Sample GPU stats every [`GPU_SAMPLE_INTERVAL`] on a dedicated thread
+    ///
+    /// Reading IOKit blocks, so it stays off the async runtime. Each tick
+    /// also scans for latency anomalies so they are logged without waiting
+    /// for a `/metrics` scrape. Sampling runs until the returned handle is
+    /// stopped or dropped.
+    #[cfg(not(cpu_only))]
+    pub fn spawn_gpu_sampler(&self) -> std::io::Result<GpuSampler> {
+        let collector = self.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = std::thread::Builder::new()
+            .name("gpu-sampler".to_string())
+            .spawn(move || {
+                loop {
+                    collector.record_gpu_sample(Self::sample_gpu_stats());
+                    collector.detect_anomalies();
+                    match stopped.recv_timeout(GPU_SAMPLE_INTERVAL) {
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        _ => break,
+                    }
+                }
+            })?;
+        Ok(GpuSampler {
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+
     /// Get current metrics snapshot
     pub fn snapshot(&self) -> MetricsSnapshot {
         let total = self.recorder.total_requests();
@@ -61,16 +146,97 @@ impl MetricsCollector {
         })
     }
 
+    /// Requests completed per second over the trailing `window`
+    pub fn recent_rps(&self, window: Duration) -> f64 {
+        let Some(since) = SystemTime::now().checked_sub(window) else {
+            return 0.0;
+        };
+        let recent = self
+            .recorder
+            .response_time_store()
+            .get_samples()
+            .iter()
+            .filter(|sample| sample.timestamp >= since)
+            .count();
+        recent as f64 / window.as_secs_f64()
+    }
+
+    /// Efficiency score against `slo`, with throughput over
+    /// [`EFFICIENCY_RPS_WINDOW`]
+    ///
+    /// Warns once when the score drops below [`EFFICIENCY_WARN_THRESHOLD`]
+    /// and logs again only after it has recovered, so scraping `/metrics`
+    /// does not flood the log.
+    pub fn efficiency_score(&self, slo: &SloConfig) -> f32 {
+        let mut snapshot = self.snapshot();
+        snapshot.rps = self.recent_rps(EFFICIENCY_RPS_WINDOW);
+        let score = MetricsCalculator::efficiency_score(&snapshot, slo);
+
+        let below = score < EFFICIENCY_WARN_THRESHOLD;
+        if self.below_slo.swap(below, Ordering::Relaxed) != below {
+            if below {
+                tracing::warn!(
+                    "Efficiency score {:.2} below {}: error rate {:.1}%, {:.2} rps, p99 {:.0}ms",
+                    score,
+                    EFFICIENCY_WARN_THRESHOLD,
+                    snapshot.error_rate_percent,
+                    snapshot.rps,
+                    snapshot.p99_response_time_ms
+                );
+            } else {
+                tracing::info!("Efficiency score recovered to {:.2}", score);
+            }
+        }
+        score
+    }
+
     /// Reset all metrics
     pub fn reset(&self) {
         self.recorder.reset();
     }
 }
 
+/// Handle to the thread started by `MetricsCollector::spawn_gpu_sampler`
+///
+/// Stopping (or dropping) it ends sampling and joins the thread.
+#[cfg(not(cpu_only))]
+#[derive(Debug)]
+pub struct GpuSampler {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(not(cpu_only))]
+impl GpuSampler {
+    /// End sampling and wait for the thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        // Closing the channel wakes the thread from its wait
+        self.stop.take();
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            tracing::warn!("GPU sampler thread panicked");
+        }
+    }
+}
+
+#[cfg(not(cpu_only))]
+impl Drop for GpuSampler {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
 impl Clone for MetricsCollector {
     fn clone(&self) -> Self {
         Self {
             recorder: Arc::clone(&self.recorder),
+            gpu: Arc::clone(&self.gpu),
+            below_slo: Arc::clone(&self.below_slo),
             start_time: self.start_time,
         }
     }
@@ -86,6 +252,42 @@ impl Default for MetricsCollector {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_efficiency_uses_recent_throughput() {
+        let collector = MetricsCollector::new();
+        let slo = SloConfig {
+            min_rps: 1.0,
+            target_p99_ms: 1000.0,
+        };
+        assert_eq!(collector.efficiency_score(&slo), 1.0);
+
+        for _ in 0..30 {
+            collector.record_success(Duration::from_millis(10));
+        }
+        assert_eq!(collector.recent_rps(EFFICIENCY_RPS_WINDOW), 0.5);
+        assert_eq!(collector.efficiency_score(&slo), 0.5);
+        assert!(collector.below_slo.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[cfg(not(cpu_only))]
+    fn test_gpu_sampler_records_and_stops() {
+        let collector = MetricsCollector::new();
+        let sampler = collector.spawn_gpu_sampler().unwrap();
+        let started = std::time::Instant::now();
+        while collector.gpu.lock().unwrap().is_empty() {
+            assert!(
+                started.elapsed() < GPU_SAMPLE_INTERVAL,
+                "no sample recorded"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let stopping = std::time::Instant::now();
+        sampler.stop();
+        assert!(stopping.elapsed() < GPU_SAMPLE_INTERVAL);
+    }
+
     #[test]
     fn test_collector_creation() {
         let c = MetricsCollector::new();
@@ -129,6 +331,33 @@ mod tests {
         assert_eq!(s.cache_hit_rate_percent, 80.0);
     }
 
+    #[test]
+    fn test_detect_anomalies_counts_once() {
+        let c = MetricsCollector::new();
+        for i in 0..20 {
+            c.record_success(Duration::from_millis(100 + i % 3));
+        }
+        c.record_success(Duration::from_millis(900));
+
+        assert_eq!(c.detect_anomalies().len(), 1);
+        assert!(c.detect_anomalies().is_empty());
+        assert_eq!(c.latency_anomalies(), 1);
+    }
+
+    #[test]
+    fn test_gpu_stats_average_shared_across_clones() {
+        let c = MetricsCollector::new();
+        c.clone().record_gpu_sample(GpuStats {
+            utilization_percent: 30.0,
+            ..Default::default()
+        });
+        c.record_gpu_sample(GpuStats {
+            utilization_percent: 50.0,
+            ..Default::default()
+        });
+        assert_eq!(c.gpu_stats().utilization_percent, 40.0);
+    }
+
     #[test]
     fn test_cloneable() {
         let c1 = MetricsCollector::new();
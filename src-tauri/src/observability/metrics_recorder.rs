@@ -1,6 +1,9 @@
+pub use super::histogram_recorder::HistogramRecorder;
+use super::metrics_analyzer::AnomalyEvent;
 use super::response_time_store::ResponseTimeStore;
+use parking_lot::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 /// Records metrics for requests and cache operations
 pub struct MetricsRecorder {
@@ -8,8 +11,11 @@ pub struct MetricsRecorder {
     successful_requests: AtomicU64,
     failed_requests: AtomicU64,
     response_times: ResponseTimeStore,
+    latency_histogram: HistogramRecorder,
     cache_hits: AtomicU64,
     cache_misses: AtomicU64,
+    latency_anomalies: AtomicU64,
+    last_anomaly_at: Mutex<Option<SystemTime>>,
 }
 
 impl MetricsRecorder {
@@ -20,8 +26,11 @@ impl MetricsRecorder {
             successful_requests: AtomicU64::new(0),
             failed_requests: AtomicU64::new(0),
             response_times: ResponseTimeStore::new(),
+            latency_histogram: HistogramRecorder::new(),
             cache_hits: AtomicU64::new(0),
             cache_misses: AtomicU64::new(0),
+            latency_anomalies: AtomicU64::new(0),
+            last_anomaly_at: Mutex::new(None),
         }
     }
 
@@ -29,14 +38,20 @@ impl MetricsRecorder {
     pub fn record_success(&self, response_time: Duration) {
         self.successful_requests.fetch_add(1, Ordering::Relaxed);
         self.total_requests.fetch_add(1, Ordering::Relaxed);
-        self.response_times.store(response_time);
+        self.record_latency(response_time);
     }
 
     /// Record a failed request with response time
     pub fn record_failure(&self, response_time: Duration) {
         self.failed_requests.fetch_add(1, Ordering::Relaxed);
         self.total_requests.fetch_add(1, Ordering::Relaxed);
+        self.record_latency(response_time);
+    }
+
+    fn record_latency(&self, response_time: Duration) {
         self.response_times.store(response_time);
+        self.latency_histogram
+            .record(response_time.as_secs_f32() * 1000.0);
     }
 
     /// Record cache hit
@@ -49,6 +64,36 @@ impl MetricsRecorder {
         self.cache_misses.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Count anomalies not seen by an earlier call, returning only the new ones
+    pub fn record_anomalies(&self, events: Vec<AnomalyEvent>) -> Vec<AnomalyEvent> {
+        let mut last = self.last_anomaly_at.lock();
+        let fresh: Vec<AnomalyEvent> = events
+            .into_iter()
+            .filter(|event| last.is_none_or(|seen| event.timestamp > seen))
+            .collect();
+        if let Some(newest) = fresh.iter().map(|event| event.timestamp).max() {
+            *last = Some(newest);
+        }
+        self.latency_anomalies
+            .fetch_add(fresh.len() as u64, Ordering::Relaxed);
+        fresh
+    }
+
+    /// Get number of latency anomalies recorded
+    pub fn latency_anomalies(&self) -> u64 {
+        self.latency_anomalies.load(Ordering::Relaxed)
+    }
+
+    /// Get the response time history
+    pub fn response_time_store(&self) -> &ResponseTimeStore {
+        &self.response_times
+    }
+
+    /// Get the fixed-memory latency histogram
+    pub fn latency_histogram(&self) -> &HistogramRecorder {
+        &self.latency_histogram
+    }
+
     /// Get total requests
     pub fn total_requests(&self) -> u64 {
         self.total_requests.load(Ordering::Relaxed)
@@ -86,7 +131,10 @@ impl MetricsRecorder {
         self.failed_requests.store(0, Ordering::Relaxed);
         self.cache_hits.store(0, Ordering::Relaxed);
         self.cache_misses.store(0, Ordering::Relaxed);
+        self.latency_anomalies.store(0, Ordering::Relaxed);
+        *self.last_anomaly_at.lock() = None;
         self.response_times.clear();
+        self.latency_histogram.reset();
     }
 }
 
@@ -97,8 +145,11 @@ impl Clone for MetricsRecorder {
             successful_requests: AtomicU64::new(self.successful_requests.load(Ordering::Relaxed)),
             failed_requests: AtomicU64::new(self.failed_requests.load(Ordering::Relaxed)),
             response_times: self.response_times.clone(),
+            latency_histogram: self.latency_histogram.clone(),
             cache_hits: AtomicU64::new(self.cache_hits.load(Ordering::Relaxed)),
             cache_misses: AtomicU64::new(self.cache_misses.load(Ordering::Relaxed)),
+            latency_anomalies: AtomicU64::new(self.latency_anomalies.load(Ordering::Relaxed)),
+            last_anomaly_at: Mutex::new(*self.last_anomaly_at.lock()),
         }
     }
 }
@@ -41,5 +41,16 @@ mod tests {
         assert_eq!(recorder.total_requests(), 1);
         recorder.reset();
         assert_eq!(recorder.total_requests(), 0);
+        assert_eq!(recorder.latency_histogram().count(), 0);
+    }
+
+    #[test]
+    fn test_latency_histogram() {
+        let recorder = MetricsRecorder::new();
+        recorder.record_success(Duration::from_millis(20));
+        recorder.record_failure(Duration::from_millis(2000));
+        let histogram = recorder.latency_histogram();
+        assert_eq!(histogram.count(), 2);
+        assert_eq!(histogram.percentile(100.0), 1000.0);
     }
 }
@@ -15,6 +15,84 @@ pub struct MetricsResponse {
     pub errors: ErrorMetrics,
     /// Cache metrics
     pub cache: CacheMetrics,
+    /// GPU utilization averaged over recent samples
+    #[serde(default)]
+    pub gpu: GpuMetrics,
+    /// Timeouts per operation, sorted by operation name
+    #[serde(default)]
+    pub timeouts: Vec<TimeoutMetrics>,
+    /// Success rate scaled by throughput and latency SLOs, 0.0 to 1.0
+    #[serde(default)]
+    pub efficiency_score: f32,
+    /// Response-time spikes detected since the server started
+    #[serde(default)]
+    pub latency_anomalies: u64,
+}
+
+impl MetricsResponse {
+    /// Render as Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let gauges: [(&str, &str, f64); 10] = [
+            (
+                "minerva_uptime_seconds",
+                "Seconds since the server started",
+                self.uptime_seconds as f64,
+            ),
+            (
+                "minerva_requests_total",
+                "Requests processed",
+                self.requests.total as f64,
+            ),
+            (
+                "minerva_requests_failed_total",
+                "Requests that failed",
+                self.requests.failed as f64,
+            ),
+            (
+                "minerva_requests_per_second",
+                "Average requests per second since start",
+                self.requests.rps,
+            ),
+            (
+                "minerva_response_time_p99_ms",
+                "p99 response time in milliseconds",
+                self.response_times.p99_ms,
+            ),
+            (
+                "minerva_error_rate_percent",
+                "Failed requests as a percentage of all requests",
+                self.errors.rate_percent,
+            ),
+            (
+                "minerva_cache_hit_rate_percent",
+                "Cache hits as a percentage of lookups",
+                self.cache.hit_rate_percent,
+            ),
+            (
+                "minerva_gpu_utilization_percent",
+                "GPU utilization averaged over recent samples",
+                self.gpu.utilization_percent,
+            ),
+            (
+                "minerva_efficiency_score",
+                "Success rate scaled by throughput and latency SLOs, 0 to 1",
+                f64::from(self.efficiency_score),
+            ),
+            (
+                "minerva_latency_anomalies_total",
+                "Response-time spikes detected since start",
+                self.latency_anomalies as f64,
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, help, value) in gauges {
+            out.push_str(&format!(
+                "# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n"
+            ));
+        }
+        out
+    }
 }
 
 /// Request statistics
@@ -69,6 +147,32 @@ pub struct CacheMetrics {
     pub hit_rate_percent: f64,
 }
 
+/// GPU statistics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuMetrics {
+    /// Device utilization percentage
+    pub utilization_percent: f64,
+    /// Framebuffer plus compute memory in use
+    pub memory_used_mb: u64,
+    /// Memory available to the GPU
+    pub memory_total_mb: u64,
+}
+
+/// Timeout statistics for one operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeoutMetrics {
+    /// Operation name
+    pub operation: String,
+    /// Calls made
+    pub calls: u64,
+    /// Calls that timed out
+    pub timeouts: u64,
+    /// Unix time of the last timeout
+    pub last_timeout_unix: Option<u64>,
+    /// Rolling timeouts per minute
+    pub rate_per_minute: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +206,10 @@ mod tests {
                 misses: 20,
                 hit_rate_percent: 80.0,
             },
+            gpu: GpuMetrics::default(),
+            timeouts: Vec::new(),
+            efficiency_score: 1.0,
+            latency_anomalies: 0,
         };
 
         assert_eq!(m.requests.total, 100);
@@ -138,10 +246,58 @@ mod tests {
                 misses: 20,
                 hit_rate_percent: 80.0,
             },
+            gpu: GpuMetrics::default(),
+            timeouts: Vec::new(),
+            efficiency_score: 1.0,
+            latency_anomalies: 0,
         };
 
         let json = serde_json::to_string(&m).unwrap();
         assert!(json.contains("total"));
         assert!(json.contains("uptime_seconds"));
+        assert!(json.contains("\"gpu\":{\"utilization_percent\""));
+    }
+
+    #[test]
+    fn test_prometheus_exposes_efficiency_score() {
+        let m = MetricsResponse {
+            timestamp: String::new(),
+            uptime_seconds: 60,
+            requests: RequestMetrics {
+                total: 10,
+                successful: 9,
+                failed: 1,
+                rps: 0.5,
+            },
+            response_times: ResponseTimeMetrics {
+                avg_ms: 0.0,
+                min_ms: 0.0,
+                max_ms: 0.0,
+                p50_ms: 0.0,
+                p95_ms: 0.0,
+                p99_ms: 0.0,
+            },
+            errors: ErrorMetrics {
+                total: 1,
+                rate_percent: 10.0,
+                top_error: None,
+            },
+            cache: CacheMetrics {
+                hits: 0,
+                misses: 0,
+                hit_rate_percent: 0.0,
+            },
+            gpu: GpuMetrics::default(),
+            timeouts: Vec::new(),
+            efficiency_score: 0.25,
+            latency_anomalies: 3,
+        };
+
+        let text = m.to_prometheus();
+        assert!(
+            text.contains("# TYPE minerva_efficiency_score gauge\nminerva_efficiency_score 0.25\n")
+        );
+        assert!(text.contains("minerva_requests_total 10\n"));
+        assert!(text.contains("minerva_latency_anomalies_total 3\n"));
     }
 }
@@ -68,6 +68,162 @@ impl SnapshotBuilder {
     }
 }
 
+/// Fluent builder for `MetricsSnapshot`, mainly for tests
+///
+/// Unset fields get plausible values: 100 requests without errors, latencies
+/// from a 100ms p99, 10 rps over a minute of uptime and no cache traffic.
+/// Unset percentiles are derived from the ones that are set so the result
+/// stays ordered.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshotBuilder {
+    total: Option<u64>,
+    failed: Option<u64>,
+    error_rate: Option<f64>,
+    avg_ms: Option<f64>,
+    p50_ms: Option<f64>,
+    p95_ms: Option<f64>,
+    p99_ms: Option<f64>,
+    rps: Option<f64>,
+    cache: (u64, u64),
+    uptime_secs: Option<u64>,
+}
+
+const DEFAULT_TOTAL_REQUESTS: u64 = 100;
+const DEFAULT_P99_MS: f64 = 100.0;
+const DEFAULT_RPS: f64 = 10.0;
+const DEFAULT_UPTIME_SECS: u64 = 60;
+
+impl MetricsSnapshotBuilder {
+    pub fn with_total_requests(mut self, total: u64) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    pub fn with_failed_requests(mut self, failed: u64) -> Self {
+        self.failed = Some(failed);
+        self
+    }
+
+    /// Share of failed requests, 0.0 to 1.0
+    pub fn with_error_rate(mut self, rate: f64) -> Self {
+        self.error_rate = Some(rate);
+        self
+    }
+
+    pub fn with_avg_latency(mut self, ms: f64) -> Self {
+        self.avg_ms = Some(ms);
+        self
+    }
+
+    pub fn with_p50_latency(mut self, ms: f64) -> Self {
+        self.p50_ms = Some(ms);
+        self
+    }
+
+    pub fn with_p95_latency(mut self, ms: f64) -> Self {
+        self.p95_ms = Some(ms);
+        self
+    }
+
+    pub fn with_p99_latency(mut self, ms: f64) -> Self {
+        self.p99_ms = Some(ms);
+        self
+    }
+
+    pub fn with_rps(mut self, rps: f64) -> Self {
+        self.rps = Some(rps);
+        self
+    }
+
+    pub fn with_cache(mut self, hits: u64, misses: u64) -> Self {
+        self.cache = (hits, misses);
+        self
+    }
+
+    pub fn with_uptime_seconds(mut self, secs: u64) -> Self {
+        self.uptime_secs = Some(secs);
+        self
+    }
+
+    /// Build the snapshot, panicking with the validation message
+    pub fn build(self) -> MetricsSnapshot {
+        self.try_build()
+            .unwrap_or_else(|message| panic!("invalid MetricsSnapshot: {}", message))
+    }
+
+    /// Build the snapshot, checking p50 <= p95 <= p99 and failed <= total
+    pub fn try_build(self) -> Result<MetricsSnapshot, String> {
+        let total = self.total.unwrap_or(DEFAULT_TOTAL_REQUESTS);
+        if let Some(rate) = self.error_rate.filter(|rate| !(0.0..=1.0).contains(rate)) {
+            return Err(format!("error rate {} is outside 0.0..=1.0", rate));
+        }
+        let failed = self
+            .failed
+            .or_else(|| {
+                self.error_rate
+                    .map(|rate| (total as f64 * rate).round() as u64)
+            })
+            .unwrap_or(0);
+        if failed > total {
+            return Err(format!(
+                "failed requests ({}) exceed total requests ({})",
+                failed, total
+            ));
+        }
+        let error_rate_percent = match (self.failed, self.error_rate) {
+            (None, Some(rate)) => rate * 100.0,
+            _ if total > 0 => failed as f64 / total as f64 * 100.0,
+            _ => 0.0,
+        };
+
+        let (p50, p95, p99) = self.percentiles();
+        if p50 > p95 || p95 > p99 {
+            return Err(format!(
+                "latency percentiles must satisfy p50 <= p95 <= p99, got {} / {} / {}",
+                p50, p95, p99
+            ));
+        }
+
+        let (hits, misses) = self.cache;
+        let hit_rate = if hits + misses > 0 {
+            hits as f64 / (hits + misses) as f64 * 100.0
+        } else {
+            0.0
+        };
+        let avg = self.avg_ms.unwrap_or(p50);
+        Ok(MetricsSnapshot {
+            total_requests: total,
+            successful_requests: total - failed,
+            failed_requests: failed,
+            avg_response_time_ms: avg,
+            min_response_time_ms: avg.min(p50).min(1.0),
+            max_response_time_ms: p99,
+            p50_response_time_ms: p50,
+            p95_response_time_ms: p95,
+            p99_response_time_ms: p99,
+            rps: self.rps.unwrap_or(DEFAULT_RPS),
+            error_rate_percent,
+            cache_hits: hits,
+            cache_misses: misses,
+            cache_hit_rate_percent: hit_rate,
+            uptime_seconds: self.uptime_secs.unwrap_or(DEFAULT_UPTIME_SECS),
+        })
+    }
+
+    /// Set percentiles as given; unset ones fall between their neighbours
+    fn percentiles(&self) -> (f64, f64, f64) {
+        let floor = self.p50_ms.unwrap_or(0.0);
+        let p99 = self
+            .p99_ms
+            .unwrap_or_else(|| DEFAULT_P99_MS.max(self.p95_ms.unwrap_or(0.0)).max(floor));
+        let p95 = self
+            .p95_ms
+            .unwrap_or_else(|| (p99 * 0.9).max(floor).min(p99));
+        let p50 = self.p50_ms.unwrap_or_else(|| (p99 * 0.5).min(p95));
+        (p50, p95, p99)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +285,59 @@ mod tests {
         });
         assert_eq!(snapshot.cache_hit_rate_percent, 80.0);
     }
+
+    #[test]
+    fn test_fluent_builder_fills_defaults() {
+        let snapshot = MetricsSnapshotBuilder::default()
+            .with_total_requests(100)
+            .with_p99_latency(50.0)
+            .with_error_rate(0.01)
+            .build();
+        assert_eq!(snapshot.total_requests, 100);
+        assert_eq!(snapshot.failed_requests, 1);
+        assert_eq!(snapshot.successful_requests, 99);
+        assert_eq!(snapshot.error_rate_percent, 1.0);
+        assert_eq!(snapshot.p99_response_time_ms, 50.0);
+        assert!(snapshot.p50_response_time_ms <= snapshot.p95_response_time_ms);
+        assert!(snapshot.p95_response_time_ms <= 50.0);
+        assert_eq!(snapshot.uptime_seconds, 60);
+    }
+
+    #[test]
+    fn test_unset_p99_rises_above_set_percentiles() {
+        let snapshot = MetricsSnapshotBuilder::default()
+            .with_p50_latency(400.0)
+            .build();
+        assert_eq!(snapshot.p50_response_time_ms, 400.0);
+        assert_eq!(snapshot.p95_response_time_ms, 400.0);
+        assert_eq!(snapshot.p99_response_time_ms, 400.0);
+    }
+
+    #[test]
+    fn test_rejects_unordered_percentiles() {
+        let err = MetricsSnapshotBuilder::default()
+            .with_p95_latency(200.0)
+            .with_p99_latency(100.0)
+            .try_build()
+            .unwrap_err();
+        assert!(err.contains("p50 <= p95 <= p99"), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_more_errors_than_requests() {
+        let err = MetricsSnapshotBuilder::default()
+            .with_total_requests(10)
+            .with_failed_requests(11)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err, "failed requests (11) exceed total requests (10)");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid MetricsSnapshot")]
+    fn test_build_panics_on_invalid_snapshot() {
+        MetricsSnapshotBuilder::default()
+            .with_error_rate(1.5)
+            .build();
+    }
 }
@@ -5,10 +5,13 @@
 /// - Readiness probes for orchestration
 /// - Performance metrics collection
 /// - Request tracing and logging
-pub mod component_info;
+pub mod component_status;
 pub mod endpoints;
+pub mod gpu_stats;
 pub mod health;
+pub mod health_checks;
 pub mod health_types;
+pub mod histogram_recorder;
 pub mod metrics;
 pub mod metrics_analyzer;
 pub mod metrics_calculator;
@@ -21,6 +24,7 @@ pub mod metrics_snapshot_builder;
 pub mod readiness;
 pub mod request_trace;
 pub mod response_time_store;
+pub mod trace_context;
 pub mod trace_id_generator;
 pub mod tracing_middleware;
 
@@ -1,4 +1,9 @@
+use crate::error::MinervaResult;
+use crate::inference::batch_queue::{BatchForward, PAD_TOKEN_ID, PaddedBatch};
+use crate::models::ModelRegistry;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// Readiness response for /ready endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +44,95 @@ impl ReadinessResponse {
     }
 }
 
+impl From<&ReadinessStatus> for ReadinessResponse {
+    fn from(status: &ReadinessStatus) -> Self {
+        match status {
+            ReadinessStatus::Ready => Self::ready(),
+            ReadinessStatus::Warming { model_id } => {
+                Self::not_ready(&format!("warming up model {}", model_id))
+                    .with_blocking(vec![model_id.clone()])
+            }
+            ReadinessStatus::Unready { reason } => Self::not_ready(reason),
+        }
+    }
+}
+
+/// Readiness of the server to serve inference traffic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadinessStatus {
+    Ready,
+    Warming { model_id: String },
+    Unready { reason: String },
+}
+
+impl ReadinessStatus {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready)
+    }
+}
+
+/// Gates readiness on a loaded model that has completed a warmup inference
+#[derive(Debug, Default)]
+pub struct ReadinessChecker {
+    warming: Mutex<Option<String>>,
+    warmed: Mutex<HashSet<String>>,
+}
+
+impl ReadinessChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ready once any registered model has been warmed up
+    pub fn is_ready(&self, registry: &ModelRegistry) -> ReadinessStatus {
+        let models = registry.list_models();
+        if models.is_empty() {
+            return ReadinessStatus::Unready {
+                reason: "no model loaded".to_string(),
+            };
+        }
+
+        let warmed = self.warmed.lock();
+        if models.iter().any(|model| warmed.contains(&model.id)) {
+            return ReadinessStatus::Ready;
+        }
+        match self.warming.lock().clone() {
+            Some(model_id) => ReadinessStatus::Warming { model_id },
+            None => ReadinessStatus::Unready {
+                reason: "no model has completed warmup".to_string(),
+            },
+        }
+    }
+
+    /// Mark a model as warming so readiness reports it before the pass runs
+    pub fn begin_warmup(&self, model_id: &str) {
+        *self.warming.lock() = Some(model_id.to_string());
+    }
+
+    /// Run a single-token forward pass, recording the model as warm on success
+    pub fn warm_up(&self, model_id: &str, forward: &dyn BatchForward) -> MinervaResult<()> {
+        self.begin_warmup(model_id);
+        let warmup: [&[u32]; 1] = [&[PAD_TOKEN_ID]];
+        let result = forward
+            .forward(&PaddedBatch::from_sequences(&warmup))
+            .map(|_| ());
+
+        self.abandon_warmup(model_id);
+        if result.is_ok() {
+            self.warmed.lock().insert(model_id.to_string());
+        }
+        result
+    }
+
+    /// Stop reporting `model_id` as warming, e.g. when its backend failed to load
+    pub fn abandon_warmup(&self, model_id: &str) {
+        let mut warming = self.warming.lock();
+        if warming.as_deref() == Some(model_id) {
+            *warming = None;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,4 +157,86 @@ mod tests {
         assert!(r.blocking_components.is_some());
         assert_eq!(r.blocking_components.unwrap().len(), 1);
     }
+
+    struct FailingForward;
+
+    struct EmptyForward;
+
+    impl BatchForward for EmptyForward {
+        fn forward(&self, batch: &PaddedBatch) -> MinervaResult<Vec<Vec<f32>>> {
+            Ok(vec![Vec::new(); batch.batch_size()])
+        }
+    }
+
+    impl BatchForward for FailingForward {
+        fn forward(&self, _batch: &PaddedBatch) -> MinervaResult<Vec<Vec<f32>>> {
+            Err(crate::error::MinervaError::InferenceError(
+                "boom".to_string(),
+            ))
+        }
+    }
+
+    fn registry_with(id: &str) -> ModelRegistry {
+        let mut registry = ModelRegistry::new();
+        let model = crate::models::ModelInfo {
+            id: id.to_string(),
+            object: "model".to_string(),
+            created: 0,
+            owned_by: "local".to_string(),
+            context_window: None,
+            max_output_tokens: None,
+            capabilities: Default::default(),
+        };
+        registry.add_model(model, std::path::PathBuf::from("/tmp/model.gguf"));
+        registry
+    }
+
+    #[test]
+    fn test_checker_unready_without_models() {
+        let checker = ReadinessChecker::new();
+        let status = checker.is_ready(&ModelRegistry::new());
+        assert!(matches!(status, ReadinessStatus::Unready { .. }));
+    }
+
+    #[test]
+    fn test_checker_warming_then_ready() {
+        let checker = ReadinessChecker::new();
+        let registry = registry_with("m");
+
+        checker.begin_warmup("m");
+        assert_eq!(
+            checker.is_ready(&registry),
+            ReadinessStatus::Warming {
+                model_id: "m".to_string()
+            }
+        );
+
+        checker.warm_up("m", &EmptyForward).unwrap();
+        assert!(checker.is_ready(&registry).is_ready());
+    }
+
+    #[test]
+    fn test_checker_abandoned_warmup_is_unready() {
+        let checker = ReadinessChecker::new();
+        let registry = registry_with("m");
+
+        checker.begin_warmup("m");
+        checker.abandon_warmup("m");
+        assert!(matches!(
+            checker.is_ready(&registry),
+            ReadinessStatus::Unready { .. }
+        ));
+    }
+
+    #[test]
+    fn test_checker_failed_warmup_stays_unready() {
+        let checker = ReadinessChecker::new();
+        let registry = registry_with("m");
+
+        assert!(checker.warm_up("m", &FailingForward).is_err());
+        assert!(matches!(
+            checker.is_ready(&registry),
+            ReadinessStatus::Unready { .. }
+        ));
+    }
 }
@@ -1,10 +1,17 @@
 use parking_lot::RwLock;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
+
+/// A single recorded response time and when it was recorded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencySample {
+    pub timestamp: SystemTime,
+    pub latency: Duration,
+}
 
 /// Manages response time history with bounded storage
 pub struct ResponseTimeStore {
-    times: Arc<RwLock<Vec<Duration>>>,
+    times: Arc<RwLock<Vec<LatencySample>>>,
 }
 
 impl ResponseTimeStore {
@@ -18,7 +25,10 @@ impl ResponseTimeStore {
     /// Store response time with bounded growth
     pub fn store(&self, time: Duration) {
         let mut times = self.times.write();
-        times.push(time);
+        times.push(LatencySample {
+            timestamp: SystemTime::now(),
+            latency: time,
+        });
         // Keep only last 10000 measurements to avoid unbounded memory
         if times.len() > 10000 {
             times.drain(0..5000);
@@ -27,6 +37,11 @@ impl ResponseTimeStore {
 
     /// Get current response times
     pub fn get_times(&self) -> Vec<Duration> {
+        self.times.read().iter().map(|s| s.latency).collect()
+    }
+
+    /// Get current response times with their timestamps, oldest first
+    pub fn get_samples(&self) -> Vec<LatencySample> {
         self.times.read().clone()
     }
 
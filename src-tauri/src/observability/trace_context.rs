@@ -0,0 +1,152 @@
+use axum::http::HeaderMap;
+
+/// W3C `traceparent` header
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+/// W3C `tracestate` header
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+const TRACE_ID_LEN: usize = 32;
+const SPAN_ID_LEN: usize = 16;
+/// `tracestate` longer than this may be discarded per the W3C spec
+const MAX_TRACESTATE_LEN: usize = 512;
+
+/// W3C Trace Context for one server span
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceContext {
+    /// 32 lowercase hex chars shared by every span in the trace
+    pub trace_id: String,
+    /// Caller's span id, `None` when this request starts a new trace
+    pub parent_span_id: Option<String>,
+    /// 16 lowercase hex chars identifying this server span
+    pub span_id: String,
+    /// Trace flags (bit 0 = sampled)
+    pub flags: u8,
+    /// Vendor-specific `tracestate`, passed through unchanged
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Child of the incoming `traceparent`, or a new sampled root when absent or invalid
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        match header(TRACEPARENT_HEADER).and_then(parse_traceparent) {
+            Some((trace_id, parent, flags)) => Self {
+                trace_id,
+                parent_span_id: Some(parent),
+                span_id: new_span_id(),
+                flags,
+                tracestate: header(TRACESTATE_HEADER)
+                    .filter(|s| !s.is_empty() && s.len() <= MAX_TRACESTATE_LEN)
+                    .map(String::from),
+            },
+            None => Self::new_root(),
+        }
+    }
+
+    /// Start a new trace
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: uuid::Uuid::new_v4().simple().to_string(),
+            parent_span_id: None,
+            span_id: new_span_id(),
+            flags: 0x01,
+            tracestate: None,
+        }
+    }
+
+    /// `traceparent` value identifying this server span
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-{:02x}", self.trace_id, self.span_id, self.flags)
+    }
+}
+
+/// Parse `version-traceid-parentid-flags`, returning `(trace_id, parent_id, flags)`
+///
+/// Version `ff` and all-zero ids are invalid. Versions after `00` may append
+/// fields, which are ignored.
+pub fn parse_traceparent(value: &str) -> Option<(String, String, u8)> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    let [version, trace_id, parent_id, flags, rest @ ..] = parts.as_slice() else {
+        return None;
+    };
+    let valid_version = is_hex(version, 2) && *version != "ff";
+    if !valid_version || (*version == "00" && !rest.is_empty()) {
+        return None;
+    }
+    if !is_id(trace_id, TRACE_ID_LEN) || !is_id(parent_id, SPAN_ID_LEN) || !is_hex(flags, 2) {
+        return None;
+    }
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+    Some((trace_id.to_string(), parent_id.to_string(), flags))
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+fn is_id(value: &str, len: usize) -> bool {
+    is_hex(value, len) && value.bytes().any(|b| b != b'0')
+}
+
+fn new_span_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..SPAN_ID_LEN].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_parse_valid_traceparent() {
+        let (trace_id, parent, flags) = parse_traceparent(VALID).unwrap();
+        assert_eq!(trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(parent, "00f067aa0ba902b7");
+        assert_eq!(flags, 1);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_traceparent() {
+        for value in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert!(parse_traceparent(value).is_none(), "{}", value);
+        }
+        let future = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra";
+        assert!(parse_traceparent(future).is_some());
+    }
+
+    #[test]
+    fn test_child_context_keeps_trace_id() {
+        let mut headers = HeaderMap::new();
+        headers.insert(TRACEPARENT_HEADER, VALID.parse().unwrap());
+        headers.insert(TRACESTATE_HEADER, "congo=t61rcWkgMzE".parse().unwrap());
+
+        let context = TraceContext::from_headers(&headers);
+        assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+        assert_ne!(context.span_id, "00f067aa0ba902b7");
+        assert_eq!(context.tracestate.as_deref(), Some("congo=t61rcWkgMzE"));
+        assert!(parse_traceparent(&context.traceparent()).is_some());
+    }
+
+    #[test]
+    fn test_root_context_without_traceparent() {
+        let context = TraceContext::from_headers(&HeaderMap::new());
+        assert!(context.parent_span_id.is_none());
+        let (trace_id, span_id, flags) = parse_traceparent(&context.traceparent()).unwrap();
+        assert_eq!(trace_id, context.trace_id);
+        assert_eq!(span_id, context.span_id);
+        assert_eq!(flags, 1);
+    }
+}
@@ -1,9 +1,122 @@
 /// Request Tracing Middleware
 ///
 /// Integrates with logging infrastructure for distributed tracing:
+/// - W3C Trace Context (`traceparent` / `tracestate`) propagation
 /// - Request ID propagation
 /// - Latency tracking
 /// - Error logging
 /// - Metrics collection
+///
+/// Span fields follow OpenTelemetry naming (`trace_id`, `span_id`,
+/// `otel.kind`) so an OTLP exporter layer can pick them up when configured.
 pub use crate::observability::request_trace::RequestTrace;
+pub use crate::observability::trace_context::{TRACEPARENT_HEADER, TraceContext};
 pub use crate::observability::trace_id_generator::TraceIdGenerator;
+use axum::body::Body;
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Instrument;
+
+/// Header carrying the request correlation id
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Read `X-Request-Id`, generating and attaching one if the client sent
+/// none (or one too long to trust), and return it
+pub fn ensure_request_id(req: &mut Request<Body>) -> String {
+    let request_id = TraceIdGenerator::from_header_or_new(
+        req.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok()),
+    );
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        req.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    request_id
+}
+
+/// Echo `request_id` on the response unless a handler already set one
+pub fn with_request_id(mut response: Response, request_id: &str) -> Response {
+    if let Ok(value) = HeaderValue::from_str(request_id) {
+        response
+            .headers_mut()
+            .entry(REQUEST_ID_HEADER)
+            .or_insert(value);
+    }
+    response
+}
+
+/// Run each request in a span that continues the caller's trace, if any
+///
+/// The response carries `traceparent` for this server span and the
+/// `X-Request-Id` recorded as `request.id`.
+pub async fn trace_requests(mut req: Request<Body>, next: Next) -> Response {
+    let context = TraceContext::from_headers(req.headers());
+    let request_id = ensure_request_id(&mut req);
+
+    let span = tracing::info_span!(
+        "http.request",
+        otel.kind = "server",
+        trace_id = %context.trace_id,
+        span_id = %context.span_id,
+        parent_span_id = context.parent_span_id.as_deref(),
+        tracestate = context.tracestate.as_deref(),
+        request.id = %request_id,
+        http.method = %req.method(),
+        http.target = %req.uri().path(),
+    );
+    let mut response = next.run(req).instrument(span).await;
+
+    if let Ok(value) = HeaderValue::from_str(&context.traceparent()) {
+        response.headers_mut().insert(TRACEPARENT_HEADER, value);
+    }
+    with_request_id(response, &request_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::trace_context::parse_traceparent;
+    use axum::{Router, middleware::from_fn, routing::get};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(from_fn(trace_requests))
+    }
+
+    #[tokio::test]
+    async fn test_response_continues_incoming_trace() {
+        let req = Request::builder()
+            .uri("/")
+            .header(
+                TRACEPARENT_HEADER,
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )
+            .header(REQUEST_ID_HEADER, "req-1")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(req).await.unwrap();
+
+        let traceparent = response.headers()[TRACEPARENT_HEADER].to_str().unwrap();
+        let (trace_id, span_id, _) = parse_traceparent(traceparent).unwrap();
+        assert_eq!(trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_ne!(span_id, "00f067aa0ba902b7");
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-1");
+    }
+
+    #[tokio::test]
+    async fn test_response_starts_new_trace() {
+        let req = Request::builder()
+            .uri("/")
+            .header(TRACEPARENT_HEADER, "garbage")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(req).await.unwrap();
+
+        let traceparent = response.headers()[TRACEPARENT_HEADER].to_str().unwrap();
+        assert!(parse_traceparent(traceparent).is_some());
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+    }
+}
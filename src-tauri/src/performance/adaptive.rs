@@ -3,6 +3,8 @@ pub use super::adaptive_config::AdaptiveConfig;
 use super::adaptive_adjuster::AdaptiveAdjuster;
 use super::execution_modes::ExecutionMode;
 use super::window_state::WindowState;
+#[cfg(not(cpu_only))]
+use crate::inference::gpu_context::GpuContext;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
@@ -61,6 +63,19 @@ impl AdaptiveConfigManager {
         *self.window_state.read()
     }
 
+    /// Recommended `n_threads` for inference in the current window state
+    pub fn recommended_threads(&self) -> usize {
+        AdaptiveAdjuster::recommended_threads(self.window_state(), num_cpus::get())
+    }
+
+    /// Recommended `n_threads` for a model of `model_bytes`, backing off when
+    /// the context's free memory is below twice the model size
+    #[cfg(not(cpu_only))]
+    pub fn recommended_threads_for_model(&self, gpu: &GpuContext, model_bytes: u64) -> usize {
+        let free = gpu.available_memory() as u64;
+        AdaptiveAdjuster::memory_limited_threads(self.recommended_threads(), free, model_bytes)
+    }
+
     /// Adjust GPU usage based on temperature/load
     pub fn adjust_gpu_usage(&self, gpu_hot: bool, cpu_busy: bool) {
         let mut config = self.current.write();
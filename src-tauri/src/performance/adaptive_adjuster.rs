@@ -1,4 +1,5 @@
 use super::adaptive_config::AdaptiveConfig;
+use super::window_state::WindowState;
 
 /// Handles dynamic adjustment of adaptive configuration
 pub struct AdaptiveAdjuster;
@@ -24,6 +25,25 @@ impl AdaptiveAdjuster {
             config.batch_size = config.batch_size.min(64);
         }
     }
+
+    /// Recommend inference thread count; a minimized window gets one thread
+    pub fn recommended_threads(window: WindowState, available: usize) -> usize {
+        match window {
+            WindowState::Minimized => 1,
+            WindowState::Background => (available / 2).max(1),
+            WindowState::Foreground => available.max(1),
+        }
+    }
+
+    /// Halve `threads` when free memory is below 2x the model size, so
+    /// per-thread scratch buffers do not push the system into swap
+    pub fn memory_limited_threads(threads: usize, free_bytes: u64, model_bytes: u64) -> usize {
+        if free_bytes < model_bytes.saturating_mul(2) {
+            (threads / 2).max(1)
+        } else {
+            threads.max(1)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -76,6 +96,36 @@ mod tests {
         assert_eq!(config.batch_size, 1);
     }
 
+    #[test]
+    fn test_recommended_threads_minimized() {
+        assert_eq!(
+            AdaptiveAdjuster::recommended_threads(WindowState::Minimized, 8),
+            1
+        );
+        assert_eq!(
+            AdaptiveAdjuster::recommended_threads(WindowState::Background, 8),
+            4
+        );
+        assert_eq!(
+            AdaptiveAdjuster::recommended_threads(WindowState::Foreground, 8),
+            8
+        );
+    }
+
+    #[test]
+    fn test_memory_limited_threads() {
+        let gb = 1024 * 1024 * 1024;
+        assert_eq!(
+            AdaptiveAdjuster::memory_limited_threads(8, 16 * gb, 4 * gb),
+            8
+        );
+        assert_eq!(
+            AdaptiveAdjuster::memory_limited_threads(8, 6 * gb, 4 * gb),
+            4
+        );
+        assert_eq!(AdaptiveAdjuster::memory_limited_threads(1, 0, 4 * gb), 1);
+    }
+
     #[test]
     fn test_adjust_batch_size_max_boundary() {
         let mut config = AdaptiveConfig {
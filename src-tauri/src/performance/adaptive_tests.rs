@@ -30,6 +30,32 @@ mod tests {
         assert_eq!(mgr.window_state(), WindowState::Minimized);
     }
 
+    #[test]
+    fn test_minimized_recommends_single_thread() {
+        let mgr = AdaptiveConfigManager::new();
+        mgr.set_window_state(WindowState::Minimized);
+        assert_eq!(mgr.recommended_threads(), 1);
+    }
+
+    #[test]
+    #[cfg(not(cpu_only))]
+    fn test_low_free_memory_halves_threads() {
+        use crate::inference::gpu_context::{GpuContext, GpuDevice};
+
+        let mgr = AdaptiveConfigManager::new();
+        let gpu = GpuContext {
+            device: GpuDevice::Cpu,
+            allocated_memory: 100,
+            max_memory: 500,
+        };
+        let threads = mgr.recommended_threads();
+        assert_eq!(mgr.recommended_threads_for_model(&gpu, 100), threads);
+        assert_eq!(
+            mgr.recommended_threads_for_model(&gpu, 300),
+            (threads / 2).max(1)
+        );
+    }
+
     #[test]
     fn test_adjust_gpu_usage() {
         let mgr = AdaptiveConfigManager::new();
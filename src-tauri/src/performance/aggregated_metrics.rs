@@ -0,0 +1,85 @@
+use crate::observability::metrics_response::MetricsResponse;
+
+/// Whether an instance's `/metrics` endpoint answered the last poll
+#[derive(Debug, Clone, PartialEq)]
+pub enum EndpointStatus {
+    Reachable,
+    Unreachable { reason: String },
+}
+
+/// Poll outcome for one server instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointSummary {
+    pub endpoint: String,
+    pub status: EndpointStatus,
+}
+
+/// Metrics merged across server instances
+///
+/// Counters are summed; latencies are averaged weighted by each
+/// instance's total request count.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AggregatedMetrics {
+    pub total_requests: u64,
+    pub successful_requests: u64,
+    pub failed_requests: u64,
+    pub total_errors: u64,
+    pub avg_response_time_ms: f64,
+    pub p50_response_time_ms: f64,
+    pub p95_response_time_ms: f64,
+    pub p99_response_time_ms: f64,
+    pub endpoints: Vec<EndpointSummary>,
+}
+
+impl AggregatedMetrics {
+    /// Merge per-endpoint poll results, marking failures as unreachable
+    pub fn merge(results: Vec<(String, Result<MetricsResponse, String>)>) -> Self {
+        let mut merged = Self::default();
+        let mut latency_sums = [0.0; 4];
+
+        for (endpoint, result) in results {
+            let status = match result {
+                Ok(response) => {
+                    merged.add(&response, &mut latency_sums);
+                    EndpointStatus::Reachable
+                }
+                Err(reason) => EndpointStatus::Unreachable { reason },
+            };
+            merged.endpoints.push(EndpointSummary { endpoint, status });
+        }
+
+        if merged.total_requests > 0 {
+            let weight = merged.total_requests as f64;
+            merged.avg_response_time_ms = latency_sums[0] / weight;
+            merged.p50_response_time_ms = latency_sums[1] / weight;
+            merged.p95_response_time_ms = latency_sums[2] / weight;
+            merged.p99_response_time_ms = latency_sums[3] / weight;
+        }
+        merged
+    }
+
+    /// Number of endpoints that could not be polled
+    pub fn unreachable_count(&self) -> usize {
+        self.endpoints
+            .iter()
+            .filter(|e| matches!(e.status, EndpointStatus::Unreachable { .. }))
+            .count()
+    }
+
+    fn add(&mut self, response: &MetricsResponse, latency_sums: &mut [f64; 4]) {
+        self.total_requests += response.requests.total;
+        self.successful_requests += response.requests.successful;
+        self.failed_requests += response.requests.failed;
+        self.total_errors += response.errors.total;
+
+        let weight = response.requests.total as f64;
+        let times = &response.response_times;
+        for (sum, value) in
+            latency_sums
+                .iter_mut()
+                .zip([times.avg_ms, times.p50_ms, times.p95_ms, times.p99_ms])
+        {
+            *sum += value * weight;
+        }
+    }
+}
@@ -1,3 +1,13 @@
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Throughput samples kept per model; older samples fall out of the percentiles
+pub const THROUGHPUT_WINDOW: usize = 1000;
+
 /// Inference operation metrics
 #[derive(Debug, Clone)]
 pub struct InferenceMetrics {
@@ -13,6 +23,98 @@ pub struct InferenceMetrics {
     pub used_gpu: bool,
 }
 
+/// Snapshot of one model's recent throughput
+#[derive(Debug, Clone)]
+pub struct ModelInferenceMetrics {
+    pub model_id: String,
+    /// Tokens/sec of the most recent generations, oldest first
+    pub token_rates: VecDeque<f64>,
+    pub total_tokens: u64,
+}
+
+/// JSON body of `GET /v1/models/:id/performance`
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelPerformanceResponse {
+    pub model_id: String,
+    pub samples: usize,
+    pub total_tokens: u64,
+    pub p50_tokens_per_sec: f64,
+    pub p95_tokens_per_sec: f64,
+    pub p99_tokens_per_sec: f64,
+}
+
+impl ModelInferenceMetrics {
+    /// Nearest-rank percentile of tokens/sec, 0.0 when there are no samples
+    pub fn percentile(&self, p: f64) -> f64 {
+        let mut sorted: Vec<f64> = self.token_rates.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+        sorted
+            .get(rank.saturating_sub(1).min(sorted.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    pub fn summary(&self) -> ModelPerformanceResponse {
+        ModelPerformanceResponse {
+            model_id: self.model_id.clone(),
+            samples: self.token_rates.len(),
+            total_tokens: self.total_tokens,
+            p50_tokens_per_sec: self.percentile(50.0),
+            p95_tokens_per_sec: self.percentile(95.0),
+            p99_tokens_per_sec: self.percentile(99.0),
+        }
+    }
+}
+
+/// Live throughput history for one model
+#[derive(Debug, Default)]
+struct ModelThroughput {
+    token_rates: Mutex<VecDeque<f64>>,
+    total_tokens: AtomicU64,
+}
+
+/// Per-model throughput history shared across request handlers
+#[derive(Debug, Default)]
+pub struct ModelMetricsStore {
+    models: DashMap<String, Arc<ModelThroughput>>,
+}
+
+impl ModelMetricsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed generation
+    pub fn record(&self, metrics: &InferenceMetrics) {
+        let entry = self
+            .models
+            .entry(metrics.model.clone())
+            .or_default()
+            .clone();
+        entry
+            .total_tokens
+            .fetch_add(metrics.tokens_generated, Ordering::Relaxed);
+
+        let mut rates = entry.token_rates.lock();
+        if rates.len() == THROUGHPUT_WINDOW {
+            rates.pop_front();
+        }
+        rates.push_back(metrics.tokens_per_second);
+    }
+
+    /// Snapshot of a model's history, if it has completed any generation
+    pub fn snapshot(&self, model_id: &str) -> Option<ModelInferenceMetrics> {
+        let entry = self.models.get(model_id)?.clone();
+        let token_rates = entry.token_rates.lock().clone();
+        Some(ModelInferenceMetrics {
+            model_id: model_id.to_string(),
+            token_rates,
+            total_tokens: entry.total_tokens.load(Ordering::Relaxed),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,4 +144,54 @@ mod tests {
         assert_eq!(metrics.model, "mistral");
         assert!(!metrics.used_gpu);
     }
+
+    fn sample(model: &str, tokens_per_second: f64) -> InferenceMetrics {
+        InferenceMetrics {
+            model: model.to_string(),
+            tokens_generated: 10,
+            duration_ms: 100,
+            tokens_per_second,
+            used_gpu: false,
+        }
+    }
+
+    #[test]
+    fn test_store_tracks_models_separately() {
+        let store = ModelMetricsStore::new();
+        store.record(&sample("llama", 40.0));
+        store.record(&sample("llama", 60.0));
+        store.record(&sample("phi", 120.0));
+
+        let llama = store.snapshot("llama").unwrap();
+        assert_eq!(llama.token_rates.len(), 2);
+        assert_eq!(llama.total_tokens, 20);
+        assert_eq!(store.snapshot("phi").unwrap().percentile(50.0), 120.0);
+        assert!(store.snapshot("mistral").is_none());
+    }
+
+    #[test]
+    fn test_window_drops_oldest_samples() {
+        let store = ModelMetricsStore::new();
+        for i in 0..THROUGHPUT_WINDOW + 5 {
+            store.record(&sample("llama", i as f64));
+        }
+
+        let metrics = store.snapshot("llama").unwrap();
+        assert_eq!(metrics.token_rates.len(), THROUGHPUT_WINDOW);
+        assert_eq!(metrics.token_rates.front(), Some(&5.0));
+        assert_eq!(metrics.total_tokens, (THROUGHPUT_WINDOW as u64 + 5) * 10);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let metrics = ModelInferenceMetrics {
+            model_id: "llama".to_string(),
+            token_rates: (1..=100).map(f64::from).collect(),
+            total_tokens: 0,
+        };
+        let summary = metrics.summary();
+        assert_eq!(summary.p50_tokens_per_sec, 50.0);
+        assert_eq!(summary.p95_tokens_per_sec, 95.0);
+        assert_eq!(summary.p99_tokens_per_sec, 99.0);
+    }
 }
@@ -1,4 +1,4 @@
-use super::inference_metrics::InferenceMetrics;
+use super::inference_metrics::{InferenceMetrics, ModelInferenceMetrics, ModelMetricsStore};
 
 /// Query interface for inference metrics data
 pub struct InferenceMetricsQuery;
@@ -38,6 +38,11 @@ impl InferenceMetricsQuery {
     pub fn count(metrics: &[InferenceMetrics]) -> usize {
         metrics.len()
     }
+
+    /// Throughput history for one model
+    pub fn for_model(store: &ModelMetricsStore, id: &str) -> Option<ModelInferenceMetrics> {
+        store.snapshot(id)
+    }
 }
 
 #[cfg(test)]
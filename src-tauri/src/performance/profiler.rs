@@ -25,7 +25,7 @@ impl Profiler {
 
     /// Start timing an operation
     pub fn start(&self, name: &str) -> ScopedTimer {
-        ScopedTimer::new(name.to_string(), self.clone())
+        ScopedTimer::new(name, None).with_profiler(self.clone())
     }
 
     /// Record operation timing
@@ -1,41 +1,82 @@
 use super::profiler::Profiler;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tracing::Span;
 
-/// Scoped timer that records duration on drop
+/// Scoped timer that logs and records its duration on drop
+///
+/// Each timer owns a `timer` span named after the operation; work run through
+/// [`ScopedTimer::in_scope`] is attributed to it. On drop the span gets an
+/// `elapsed_ms` event, plus a warning when `warn_threshold` is exceeded.
 pub struct ScopedTimer {
     name: String,
     start: Instant,
-    profiler: Profiler,
+    span: Span,
+    warn_threshold: Option<Duration>,
+    profiler: Option<Profiler>,
 }
 
 impl ScopedTimer {
     /// Create new scoped timer
-    pub fn new(name: String, profiler: Profiler) -> Self {
+    pub fn new(name: &str, warn_threshold: Option<Duration>) -> Self {
         Self {
-            name,
+            name: name.to_string(),
             start: Instant::now(),
-            profiler,
+            span: tracing::debug_span!("timer", name = %name),
+            warn_threshold,
+            profiler: None,
         }
     }
+
+    /// Also record the duration in `profiler` on drop
+    pub fn with_profiler(mut self, profiler: Profiler) -> Self {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Run `f` inside this timer's span
+    pub fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.span.in_scope(f)
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Whether `elapsed` is over the warning threshold, if one is set
+    pub fn exceeds_threshold(&self, elapsed: Duration) -> bool {
+        self.warn_threshold.is_some_and(|limit| elapsed > limit)
+    }
 }
 
 impl Drop for ScopedTimer {
     fn drop(&mut self) {
-        let duration_ms = self.start.elapsed().as_millis() as u64;
-        self.profiler.record(&self.name, duration_ms);
+        let elapsed = self.start.elapsed();
+        let duration_ms = elapsed.as_millis() as u64;
+        tracing::debug!(parent: &self.span, elapsed_ms = duration_ms, "timer finished");
+        if self.exceeds_threshold(elapsed) {
+            tracing::warn!(
+                parent: &self.span,
+                name = %self.name,
+                elapsed_ms = duration_ms,
+                threshold_ms = self.warn_threshold.map_or(0, |t| t.as_millis() as u64),
+                "operation exceeded latency threshold"
+            );
+        }
+        if let Some(profiler) = &self.profiler {
+            profiler.record(&self.name, duration_ms);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
 
     #[test]
     fn test_scoped_timer() {
         let prof = Profiler::new();
         {
-            let _timer = ScopedTimer::new("test_op".to_string(), prof.clone());
+            let _timer = ScopedTimer::new("test_op", None).with_profiler(prof.clone());
             std::thread::sleep(Duration::from_millis(10));
         }
         let op = prof.get("test_op").unwrap();
@@ -47,8 +88,24 @@ mod tests {
     fn test_timer_is_recorded() {
         let prof = Profiler::new();
         {
-            let _timer = ScopedTimer::new("op1".to_string(), prof.clone());
+            let _timer = ScopedTimer::new("op1", None).with_profiler(prof.clone());
         }
         assert!(prof.get("op1").is_some());
     }
+
+    #[test]
+    fn test_warn_threshold() {
+        let timer = ScopedTimer::new("op", Some(Duration::from_millis(50)));
+        assert!(!timer.exceeds_threshold(Duration::from_millis(50)));
+        assert!(timer.exceeds_threshold(Duration::from_millis(51)));
+
+        let unbounded = ScopedTimer::new("op", None);
+        assert!(!unbounded.exceeds_threshold(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn test_in_scope_returns_value() {
+        let timer = ScopedTimer::new("op", None);
+        assert_eq!(timer.in_scope(|| 2 + 2), 4);
+    }
 }
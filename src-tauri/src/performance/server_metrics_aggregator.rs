@@ -1,6 +1,12 @@
+use super::aggregated_metrics::AggregatedMetrics;
 use super::inference_metrics::InferenceMetrics;
 use super::inference_metrics_query::InferenceMetricsQuery;
 use super::metrics_storage::MetricsStorage;
+use crate::observability::metrics_response::MetricsResponse;
+use std::time::Duration;
+
+/// Per-endpoint timeout when polling remote instances
+const POLL_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Performance metrics aggregator for server
 pub struct ServerMetricsAggregator {
@@ -47,6 +53,41 @@ impl ServerMetricsAggregator {
     pub fn reset(&self) {
         self.storage.clear();
     }
+
+    /// Fetch `/metrics` from every instance concurrently and merge the results
+    ///
+    /// Endpoints are base URLs such as `http://10.0.0.2:11434`. Instances that
+    /// fail to respond are reported as unreachable rather than failing the poll.
+    pub async fn poll(endpoints: &[String]) -> AggregatedMetrics {
+        let client = reqwest::Client::builder()
+            .timeout(POLL_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        let fetches = endpoints.iter().map(|endpoint| {
+            let client = client.clone();
+            async move {
+                let result = fetch_metrics(&client, endpoint).await;
+                (endpoint.clone(), result)
+            }
+        });
+        AggregatedMetrics::merge(futures::future::join_all(fetches).await)
+    }
+}
+
+async fn fetch_metrics(
+    client: &reqwest::Client,
+    endpoint: &str,
+) -> Result<MetricsResponse, String> {
+    let url = format!("{}/metrics", endpoint.trim_end_matches('/'));
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    serde_json::from_slice(&body).map_err(|e| format!("Invalid metrics from {}: {}", url, e))
 }
 
 impl Clone for ServerMetricsAggregator {
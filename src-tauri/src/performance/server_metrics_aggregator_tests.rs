@@ -1,5 +1,9 @@
 #[cfg(test)]
 mod tests {
+    use crate::observability::metrics_response::{
+        CacheMetrics, ErrorMetrics, MetricsResponse, RequestMetrics, ResponseTimeMetrics,
+    };
+    use crate::performance::aggregated_metrics::{AggregatedMetrics, EndpointStatus};
     use crate::performance::inference_metrics::InferenceMetrics;
     use crate::performance::server_metrics_aggregator::ServerMetricsAggregator;
 
@@ -109,4 +113,65 @@ mod tests {
 
         assert_eq!(agg2.recent_count(), 1);
     }
+
+    fn response(total: u64, p99_ms: f64) -> MetricsResponse {
+        MetricsResponse {
+            timestamp: String::new(),
+            uptime_seconds: 10,
+            requests: RequestMetrics {
+                total,
+                successful: total - 1,
+                failed: 1,
+                rps: 1.0,
+            },
+            response_times: ResponseTimeMetrics {
+                avg_ms: p99_ms / 2.0,
+                min_ms: 1.0,
+                max_ms: p99_ms,
+                p50_ms: p99_ms / 2.0,
+                p95_ms: p99_ms,
+                p99_ms,
+            },
+            errors: ErrorMetrics {
+                total: 1,
+                rate_percent: 100.0 / total as f64,
+                top_error: None,
+            },
+            cache: CacheMetrics {
+                hits: 0,
+                misses: 0,
+                hit_rate_percent: 0.0,
+            },
+            gpu: Default::default(),
+            timeouts: Vec::new(),
+            efficiency_score: 1.0,
+            latency_anomalies: 0,
+        }
+    }
+
+    #[test]
+    fn test_merge_weights_latency_by_requests() {
+        let merged = AggregatedMetrics::merge(vec![
+            ("a".to_string(), Ok(response(30, 100.0))),
+            ("b".to_string(), Ok(response(10, 500.0))),
+            ("c".to_string(), Err("connection refused".to_string())),
+        ]);
+
+        assert_eq!(merged.total_requests, 40);
+        assert_eq!(merged.failed_requests, 2);
+        assert_eq!(merged.total_errors, 2);
+        assert_eq!(merged.p99_response_time_ms, 200.0);
+        assert_eq!(merged.unreachable_count(), 1);
+        assert_eq!(merged.endpoints[0].status, EndpointStatus::Reachable);
+    }
+
+    #[tokio::test]
+    async fn test_poll_marks_unreachable_endpoint() {
+        let endpoints = vec!["http://127.0.0.1:9".to_string()];
+        let merged = ServerMetricsAggregator::poll(&endpoints).await;
+
+        assert_eq!(merged.total_requests, 0);
+        assert_eq!(merged.unreachable_count(), 1);
+        assert_eq!(merged.endpoints[0].endpoint, "http://127.0.0.1:9");
+    }
 }
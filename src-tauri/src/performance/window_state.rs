@@ -12,6 +12,20 @@ pub enum WindowState {
 }
 
 impl WindowState {
+    /// Classify from the window's focus and minimized flags
+    pub fn from_window(focused: bool, minimized: bool) -> Self {
+        match (focused, minimized) {
+            (_, true) => Self::Minimized,
+            (true, false) => Self::Foreground,
+            (false, false) => Self::Background,
+        }
+    }
+
+    /// Whether non-critical background work (e.g. preloading) should wait
+    pub fn defers_background_work(self) -> bool {
+        self == Self::Minimized
+    }
+
     /// Adjust config for window state
     pub fn adjust_config(self, config: AdaptiveConfig) -> AdaptiveConfig {
         match self {
@@ -51,6 +65,24 @@ mod tests {
         assert!(!bg.enable_prefetch);
     }
 
+    #[test]
+    fn test_window_state_from_window() {
+        assert_eq!(
+            WindowState::from_window(true, false),
+            WindowState::Foreground
+        );
+        assert_eq!(
+            WindowState::from_window(false, false),
+            WindowState::Background
+        );
+        assert_eq!(
+            WindowState::from_window(false, true),
+            WindowState::Minimized
+        );
+        assert!(WindowState::Minimized.defers_background_work());
+        assert!(!WindowState::Background.defers_background_work());
+    }
+
     #[test]
     fn test_window_state_minimized() {
         let base = AdaptiveConfig::default();
@@ -1,6 +1,10 @@
 use super::health_status::ComponentStatus;
+use crate::observability::metrics::MetricsSnapshot;
 use serde::{Deserialize, Serialize};
 
+/// Weight of each healthy check's p99 in the latency baseline
+const BASELINE_WEIGHT: f64 = 0.1;
+
 /// Individual component health
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentHealth {
@@ -26,9 +30,123 @@ impl ComponentHealth {
     }
 }
 
+/// Latency health of the inference path
+///
+/// Separate from `ComponentHealth`: a component can serve without errors and
+/// still be too slow to route traffic to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum LatencyHealth {
+    #[default]
+    Healthy,
+    Degraded {
+        reason: String,
+    },
+}
+
+impl LatencyHealth {
+    pub fn is_degraded(&self) -> bool {
+        matches!(self, LatencyHealth::Degraded { .. })
+    }
+}
+
+/// When latency degradation starts and clears
+#[derive(Debug, Clone, Copy)]
+pub struct LatencyDegradationConfig {
+    /// p99 / baseline ratio above which the component is degraded
+    pub degrade_ratio: f64,
+    /// Ratio the p99 must stay below for `recovery_checks` checks to clear
+    pub recover_ratio: f64,
+    /// Consecutive checks below `recover_ratio` needed to clear
+    pub recovery_checks: u32,
+}
+
+impl Default for LatencyDegradationConfig {
+    fn default() -> Self {
+        Self {
+            degrade_ratio: 3.0,
+            recover_ratio: 1.5,
+            recovery_checks: 5,
+        }
+    }
+}
+
+/// Marks a component degraded while its p99 latency stays far above the
+/// baseline
+///
+/// The baseline is a moving average of the p99 seen by healthy checks, so a
+/// slow period does not raise the bar it is measured against.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentHealthMonitor {
+    config: LatencyDegradationConfig,
+    baseline_p99_ms: Option<f64>,
+    state: LatencyHealth,
+    checks_below_recovery: u32,
+}
+
+impl ComponentHealthMonitor {
+    pub fn new(config: LatencyDegradationConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Start from a known baseline instead of learning it
+    pub fn with_baseline(mut self, p99_ms: f64) -> Self {
+        self.baseline_p99_ms = Some(p99_ms).filter(|p99| *p99 > 0.0);
+        self
+    }
+
+    pub fn baseline_p99_ms(&self) -> Option<f64> {
+        self.baseline_p99_ms
+    }
+
+    /// Compare the current p99 with the baseline and update the state
+    pub fn check_latency_degradation(&mut self, metrics: &MetricsSnapshot) -> LatencyHealth {
+        let p99 = metrics.p99_response_time_ms;
+        let Some(baseline) = self.baseline_p99_ms else {
+            self.baseline_p99_ms = Some(p99).filter(|p99| *p99 > 0.0);
+            return self.state.clone();
+        };
+        let ratio = p99 / baseline;
+
+        if ratio > self.config.degrade_ratio {
+            self.checks_below_recovery = 0;
+            if !self.state.is_degraded() {
+                tracing::warn!(
+                    "Latency degraded: p99 {:.0}ms is {:.1}x the {:.0}ms baseline",
+                    p99,
+                    ratio,
+                    baseline
+                );
+            }
+            self.state = LatencyHealth::Degraded {
+                reason: format!("latency {:.0}x baseline", ratio),
+            };
+        } else if self.state.is_degraded() {
+            self.checks_below_recovery = if ratio < self.config.recover_ratio {
+                self.checks_below_recovery + 1
+            } else {
+                0
+            };
+            if self.checks_below_recovery >= self.config.recovery_checks {
+                tracing::info!("Latency recovered: p99 {:.0}ms", p99);
+                self.state = LatencyHealth::Healthy;
+                self.checks_below_recovery = 0;
+            }
+        }
+
+        if !self.state.is_degraded() {
+            self.baseline_p99_ms = Some(baseline + (p99 - baseline) * BASELINE_WEIGHT);
+        }
+        self.state.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::observability::metrics_snapshot_builder::MetricsSnapshotBuilder;
 
     #[test]
     fn test_all_healthy() {
@@ -38,4 +156,84 @@ mod tests {
         assert!(health.memory.healthy);
         assert!(health.model_cache.healthy);
     }
+
+    fn with_p99(ms: f64) -> MetricsSnapshot {
+        MetricsSnapshotBuilder::default()
+            .with_p99_latency(ms)
+            .build()
+    }
+
+    #[test]
+    fn test_first_check_sets_baseline() {
+        let mut monitor = ComponentHealthMonitor::default();
+        assert_eq!(
+            monitor.check_latency_degradation(&with_p99(100.0)),
+            LatencyHealth::Healthy
+        );
+        assert_eq!(monitor.baseline_p99_ms(), Some(100.0));
+    }
+
+    #[test]
+    fn test_degrades_above_threshold() {
+        let mut monitor = ComponentHealthMonitor::default().with_baseline(100.0);
+        assert!(
+            !monitor
+                .check_latency_degradation(&with_p99(290.0))
+                .is_degraded()
+        );
+
+        let mut monitor = ComponentHealthMonitor::default().with_baseline(100.0);
+        assert_eq!(
+            monitor.check_latency_degradation(&with_p99(320.0)),
+            LatencyHealth::Degraded {
+                reason: "latency 3x baseline".to_string()
+            }
+        );
+        assert_eq!(monitor.baseline_p99_ms(), Some(100.0));
+    }
+
+    #[test]
+    fn test_clears_after_consecutive_fast_checks() {
+        let mut monitor = ComponentHealthMonitor::default().with_baseline(100.0);
+        monitor.check_latency_degradation(&with_p99(500.0));
+
+        // A check between 1.5x and 3x keeps it degraded and restarts the count
+        for _ in 0..4 {
+            assert!(
+                monitor
+                    .check_latency_degradation(&with_p99(120.0))
+                    .is_degraded()
+            );
+        }
+        assert!(
+            monitor
+                .check_latency_degradation(&with_p99(200.0))
+                .is_degraded()
+        );
+        for _ in 0..4 {
+            assert!(
+                monitor
+                    .check_latency_degradation(&with_p99(120.0))
+                    .is_degraded()
+            );
+        }
+        assert_eq!(
+            monitor.check_latency_degradation(&with_p99(120.0)),
+            LatencyHealth::Healthy
+        );
+    }
+
+    #[test]
+    fn test_custom_threshold() {
+        let config = LatencyDegradationConfig {
+            degrade_ratio: 2.0,
+            ..Default::default()
+        };
+        let mut monitor = ComponentHealthMonitor::new(config).with_baseline(100.0);
+        assert!(
+            monitor
+                .check_latency_degradation(&with_p99(250.0))
+                .is_degraded()
+        );
+    }
 }
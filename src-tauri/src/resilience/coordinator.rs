@@ -1,8 +1,30 @@
 use super::{
-    circuit_breaker::CircuitBreaker, coordinator_decision::CoordinatorDecision,
-    resilience_decision::ResilienceDecision, retry::RetryState, timeout::TimeoutContext,
+    ErrorClass,
+    circuit_breaker::CircuitBreaker,
+    coordinator_decision::CoordinatorDecision,
+    resilience_decision::ResilienceDecision,
+    resilience_policy::ResiliencePolicy,
+    retry::{RetryConfig, RetryState},
+    timeout::TimeoutContext,
 };
-use crate::error::MinervaError;
+use crate::error::{MinervaError, MinervaResult};
+use std::future::Future;
+use std::time::Duration;
+
+/// What `execute` does after a failed attempt
+enum NextStep {
+    Retry(Duration),
+    Fallback,
+    Fail,
+}
+
+/// Retry budgets for one `execute` call: one per retryable error class,
+/// all bounded by the same total deadline
+struct RetryBudgets {
+    transient: RetryState,
+    exhausted: RetryState,
+    deadline: TimeoutContext,
+}
 
 /// Resilience coordinator for orchestrating patterns
 pub struct ResilienceCoordinator {
@@ -44,6 +66,79 @@ impl ResilienceCoordinator {
         })
     }
 
+    /// Run `op` under the circuit breaker, retry and fallback policies
+    ///
+    /// An open circuit fails immediately. Otherwise each attempt is bounded by
+    /// the operation timeout and its error classified: transient errors are
+    /// retried with `policy.retry` until attempts or the total deadline run
+    /// out, resource exhaustion runs the policy's fallback (or, without one,
+    /// retries slowly per `RetryConfig::for_error_class`), and permanent or
+    /// fatal errors are returned as-is.
+    pub async fn execute<F, Fut, T>(
+        &self,
+        mut op: F,
+        policy: &ResiliencePolicy<T>,
+    ) -> MinervaResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = MinervaResult<T>>,
+    {
+        let mut budgets = RetryBudgets {
+            transient: RetryState::new(policy.retry),
+            exhausted: RetryState::new(RetryConfig::for_error_class(ErrorClass::ResourceExhausted)),
+            deadline: TimeoutContext::new(
+                policy.timeout.total_timeout,
+                policy.timeout.operation_timeout,
+            ),
+        };
+        loop {
+            if !self.circuit_breaker.allow_request() {
+                return Err(MinervaError::ServerError(
+                    "Circuit breaker open".to_string(),
+                ));
+            }
+            let error = match attempt(&mut op, policy).await {
+                Ok(value) => {
+                    self.record_success();
+                    return Ok(value);
+                }
+                Err(error) => error,
+            };
+            match self.next_step(&error, policy.has_fallback(), &mut budgets) {
+                NextStep::Retry(delay) => tokio::time::sleep(delay).await,
+                NextStep::Fallback => return policy.run_fallback(error).await,
+                NextStep::Fail => return Err(error),
+            }
+        }
+    }
+
+    fn next_step(
+        &self,
+        error: &MinervaError,
+        has_fallback: bool,
+        budgets: &mut RetryBudgets,
+    ) -> NextStep {
+        let class = ErrorClass::classify(error);
+        if class.is_recoverable() {
+            self.record_failure();
+        }
+        let retry = match class {
+            ErrorClass::Transient => &mut budgets.transient,
+            ErrorClass::ResourceExhausted if has_fallback => return NextStep::Fallback,
+            ErrorClass::ResourceExhausted => &mut budgets.exhausted,
+            ErrorClass::Fatal => {
+                tracing::error!("Fatal error, not retrying: {}", error);
+                return NextStep::Fail;
+            }
+            ErrorClass::Permanent => return NextStep::Fail,
+        };
+        if retry.can_retry() && !budgets.deadline.is_deadline_exceeded() {
+            NextStep::Retry(retry.next_delay())
+        } else {
+            NextStep::Fail
+        }
+    }
+
     /// Record success
     pub fn record_success(&self) {
         self.circuit_breaker.record_success();
@@ -74,10 +169,31 @@ impl ResilienceCoordinator {
     }
 }
 
+/// One attempt of `op`, bounded by the per-operation timeout and counted
+/// in the policy's timeout stats
+async fn attempt<F, Fut, T>(op: &mut F, policy: &ResiliencePolicy<T>) -> MinervaResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = MinervaResult<T>>,
+{
+    let timeout = policy.timeout.operation_timeout;
+    match &policy.timeout_stats {
+        Some((stats, operation)) => stats.run_with_timeout(operation, timeout, op()).await,
+        None => tokio::time::timeout(timeout, op())
+            .await
+            .unwrap_or(Err(MinervaError::GenerationTimeout)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::resilience::TimeoutConfig;
     use crate::resilience::circuit_breaker::CircuitBreakerConfig;
+    use crate::resilience::retry::RetryConfig;
+    use crate::resilience::timeout::OperationTimeoutStats;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
     use std::time::Duration;
 
     #[test]
@@ -119,4 +235,110 @@ mod tests {
         coord.record_failure();
         assert_eq!(coord.circuit_breaker.failures(), 1);
     }
+
+    fn policy() -> ResiliencePolicy<u32> {
+        let retry = RetryConfig {
+            max_attempts: 2,
+            base_delay_ms: 1,
+            max_delay_ms: 1,
+            use_jitter: false,
+        };
+        ResiliencePolicy::new(retry, TimeoutConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_execute_retries_transient_errors() {
+        let coord =
+            ResilienceCoordinator::new(CircuitBreaker::new(CircuitBreakerConfig::default()));
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result = coord
+            .execute(
+                move || async move {
+                    match calls.fetch_add(1, Ordering::SeqCst) {
+                        0 => Err(MinervaError::StreamingError("reset".to_string())),
+                        n => Ok(n),
+                    }
+                },
+                &policy(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(coord.circuit_breaker.failures(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_falls_back_on_resource_exhaustion() {
+        let coord =
+            ResilienceCoordinator::new(CircuitBreaker::new(CircuitBreakerConfig::default()));
+        let policy = policy().with_fallback(Box::new(|_| Box::pin(async { Ok(7) })));
+        let result = coord
+            .execute(
+                || async { Err(MinervaError::GpuOutOfMemory("16GB".to_string())) },
+                &policy,
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_execute_propagates_permanent_errors() {
+        let coord =
+            ResilienceCoordinator::new(CircuitBreaker::new(CircuitBreakerConfig::default()));
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result = coord
+            .execute(
+                move || async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Err::<u32, _>(MinervaError::InvalidRequest("bad".to_string()))
+                },
+                &policy(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(MinervaError::InvalidRequest(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_fails_fast_when_circuit_open() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        };
+        let coord = ResilienceCoordinator::new(CircuitBreaker::new(config));
+        coord.record_failure();
+
+        let result = coord.execute(|| async { Ok(1) }, &policy()).await;
+        assert!(matches!(result, Err(MinervaError::ServerError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_execute_counts_attempt_timeouts() {
+        let coord =
+            ResilienceCoordinator::new(CircuitBreaker::new(CircuitBreakerConfig::default()));
+        let stats = Arc::new(OperationTimeoutStats::new());
+        let mut policy = policy().with_timeout_stats(stats.clone(), "generate");
+        policy.timeout.operation_timeout = Duration::from_millis(20);
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result = coord
+            .execute(
+                move || async move {
+                    if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    Ok(1)
+                },
+                &policy,
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), 1);
+        let generate = stats.operation("generate").unwrap();
+        assert_eq!((generate.calls, generate.timeouts), (2, 1));
+    }
 }
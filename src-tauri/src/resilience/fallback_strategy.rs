@@ -1,4 +1,6 @@
-use crate::error::MinervaError;
+use crate::error::{MinervaError, MinervaResult};
+use crate::models::ChatMessage;
+use crate::server::chat::estimate_tokens;
 
 /// Fallback strategy options
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,6 +13,8 @@ pub enum FallbackStrategy {
     StreamingToBatch,
     /// Reduce batch size and retry
     ReduceBatchSize,
+    /// Retry on CPU with a context small enough for available RAM
+    ComputeWithReducedContext { n_ctx: usize },
     /// Use cached result if available
     UseCache,
     /// No fallback available
@@ -43,17 +47,96 @@ impl FallbackDecision {
                 "Streaming failed, switching to batch inference..."
             }
             FallbackStrategy::ReduceBatchSize => "Reducing batch size due to memory constraints...",
+            FallbackStrategy::ComputeWithReducedContext { .. } => {
+                "GPU out of memory, retrying on CPU with a reduced context..."
+            }
             FallbackStrategy::UseCache => "Using cached result...",
             FallbackStrategy::None => "No fallback strategy available",
         }
     }
 
+    /// Like `strategy_for`, but a GPU OOM falls back to CPU with a context
+    /// sized to `budget` instead of a plain CPU switch
+    pub fn with_context_budget(
+        error: &MinervaError,
+        budget: &CpuContextBudget,
+    ) -> FallbackStrategy {
+        match error {
+            MinervaError::GpuOutOfMemory(_) => FallbackStrategy::ComputeWithReducedContext {
+                n_ctx: budget.safe_n_ctx(),
+            },
+            _ => Self::strategy_for(error),
+        }
+    }
+
     /// Is fallback available?
     pub fn is_available(strategy: FallbackStrategy) -> bool {
         !matches!(strategy, FallbackStrategy::None)
     }
 }
 
+/// Memory and model shape used to size a CPU fallback context
+#[derive(Debug, Clone, Copy)]
+pub struct CpuContextBudget {
+    pub available_ram_mb: u64,
+    /// KV cache bytes per token for one head of one layer
+    pub bytes_per_kv_token: u64,
+    pub num_layers: usize,
+    pub num_heads: usize,
+}
+
+impl CpuContextBudget {
+    /// `available_ram / (bytes_per_kv_token * num_layers * num_heads)` tokens
+    pub fn safe_n_ctx(&self) -> usize {
+        let per_token = self.bytes_per_kv_token * self.num_layers as u64 * self.num_heads as u64;
+        let available_bytes = self.available_ram_mb * 1024 * 1024;
+        (available_bytes / per_token.max(1)) as usize
+    }
+}
+
+/// Drop the oldest turns until `messages` fit in `n_ctx` tokens
+///
+/// System messages and the final (current) message are always kept; if they
+/// alone exceed `n_ctx` this fails instead of dropping part of the prompt.
+pub fn truncate_history(messages: &[ChatMessage], n_ctx: usize) -> MinervaResult<Vec<ChatMessage>> {
+    let Some((current, history)) = messages.split_last() else {
+        return Ok(Vec::new());
+    };
+    let (system, turns): (Vec<&ChatMessage>, Vec<&ChatMessage>) =
+        history.iter().partition(|m| m.role == "system");
+    let required: usize = system
+        .iter()
+        .chain([&current])
+        .map(|m| estimate_tokens(&m.content))
+        .sum();
+    if required > n_ctx {
+        return Err(MinervaError::ContextLimitExceeded {
+            max: n_ctx,
+            required,
+        });
+    }
+
+    let mut remaining = n_ctx - required;
+    let mut kept: Vec<&ChatMessage> = turns
+        .into_iter()
+        .rev()
+        .take_while(|m| {
+            let tokens = estimate_tokens(&m.content);
+            let fits = tokens <= remaining;
+            remaining = remaining.saturating_sub(tokens);
+            fits
+        })
+        .collect();
+    kept.reverse();
+
+    Ok(system
+        .into_iter()
+        .chain(kept)
+        .chain([current])
+        .cloned()
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,6 +182,59 @@ mod tests {
         assert!(!FallbackDecision::is_available(FallbackStrategy::None));
     }
 
+    fn message(role: &str, tokens: usize) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: "x".repeat(tokens * 4),
+        }
+    }
+
+    #[test]
+    fn test_gpu_oom_reduces_context() {
+        // 32 layers x 32 heads x 256 bytes = 256 KiB per token
+        let budget = CpuContextBudget {
+            available_ram_mb: 1024,
+            bytes_per_kv_token: 256,
+            num_layers: 32,
+            num_heads: 32,
+        };
+        let err = MinervaError::GpuOutOfMemory("16GB".to_string());
+        assert_eq!(
+            FallbackDecision::with_context_budget(&err, &budget),
+            FallbackStrategy::ComputeWithReducedContext { n_ctx: 4096 }
+        );
+    }
+
+    #[test]
+    fn test_truncate_history_drops_oldest_turns() {
+        let messages = vec![
+            message("system", 10),
+            message("user", 50),
+            message("assistant", 50),
+            message("user", 20),
+            message("assistant", 20),
+            message("user", 10),
+        ];
+        let kept = truncate_history(&messages, 65).unwrap();
+
+        let tokens: Vec<usize> = kept.iter().map(|m| estimate_tokens(&m.content)).collect();
+        assert_eq!(tokens, vec![10, 20, 20, 10]);
+        assert_eq!(kept[0].role, "system");
+    }
+
+    #[test]
+    fn test_truncate_history_rejects_oversized_prompt() {
+        let messages = vec![message("system", 10), message("user", 100)];
+        let err = truncate_history(&messages, 64).unwrap_err();
+        assert!(matches!(
+            err,
+            MinervaError::ContextLimitExceeded {
+                max: 64,
+                required: 110
+            }
+        ));
+    }
+
     #[test]
     fn test_fallback_messages() {
         let msg = FallbackDecision::message(FallbackStrategy::GpuToCpu);
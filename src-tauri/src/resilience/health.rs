@@ -1,4 +1,6 @@
-pub use super::component_health::ComponentHealth;
+pub use super::component_health::{
+    ComponentHealth, ComponentHealthMonitor, LatencyDegradationConfig, LatencyHealth,
+};
 pub use super::health_status::ComponentStatus;
 /// Health Check & Readiness Probes
 ///
@@ -23,6 +23,7 @@ pub mod fallback_strategy;
 pub mod health;
 pub mod health_status;
 pub mod resilience_decision;
+pub mod resilience_policy;
 pub mod retry;
 pub mod retry_config;
 pub mod retry_state;
@@ -64,7 +65,8 @@ impl ErrorClass {
             // Permanent errors: don't retry
             MinervaError::ModelNotFound(_)
             | MinervaError::InvalidRequest(_)
-            | MinervaError::ModelCorrupted(_) => ErrorClass::Permanent,
+            | MinervaError::ModelCorrupted(_)
+            | MinervaError::UnsupportedFormat(_) => ErrorClass::Permanent,
 
             // Fatal: stop
             MinervaError::ContextLimitExceeded { .. } => ErrorClass::Fatal,
@@ -0,0 +1,70 @@
+use super::{TimeoutConfig, retry::RetryConfig, timeout::OperationTimeoutStats};
+use crate::error::{MinervaError, MinervaResult};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+/// Alternate operation run when the primary one exhausts a resource
+/// (e.g. retry on CPU after a GPU OOM)
+pub type FallbackFn<T> =
+    Box<dyn Fn(&MinervaError) -> BoxFuture<'static, MinervaResult<T>> + Send + Sync>;
+
+/// Retry, timeout and fallback settings for `ResilienceCoordinator::execute`
+pub struct ResiliencePolicy<T> {
+    /// Retries for transient errors; other classes use
+    /// `RetryConfig::for_error_class`
+    pub retry: RetryConfig,
+    pub timeout: TimeoutConfig,
+    pub fallback: Option<FallbackFn<T>>,
+    /// Where attempts and their timeouts are counted, and under which
+    /// operation name
+    pub timeout_stats: Option<(Arc<OperationTimeoutStats>, String)>,
+}
+
+impl<T> ResiliencePolicy<T> {
+    /// Policy with no fallback
+    pub fn new(retry: RetryConfig, timeout: TimeoutConfig) -> Self {
+        Self {
+            retry,
+            timeout,
+            fallback: None,
+            timeout_stats: None,
+        }
+    }
+
+    /// Set the fallback used for resource exhaustion
+    pub fn with_fallback(mut self, fallback: FallbackFn<T>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Count every attempt and timeout in `stats` as `operation`
+    pub fn with_timeout_stats(
+        mut self,
+        stats: Arc<OperationTimeoutStats>,
+        operation: &str,
+    ) -> Self {
+        self.timeout_stats = Some((stats, operation.to_string()));
+        self
+    }
+
+    pub fn has_fallback(&self) -> bool {
+        self.fallback.is_some()
+    }
+
+    /// Run the fallback for `error`, or return the error when there is none
+    pub async fn run_fallback(&self, error: MinervaError) -> MinervaResult<T> {
+        match &self.fallback {
+            Some(fallback) => {
+                tracing::warn!("Falling back after resource exhaustion: {}", error);
+                fallback(&error).await
+            }
+            None => Err(error),
+        }
+    }
+}
+
+impl<T> Default for ResiliencePolicy<T> {
+    fn default() -> Self {
+        Self::new(RetryConfig::default(), TimeoutConfig::default())
+    }
+}
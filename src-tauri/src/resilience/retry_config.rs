@@ -1,3 +1,5 @@
+use super::ErrorClass;
+
 /// Retry configuration
 #[derive(Debug, Clone, Copy)]
 pub struct RetryConfig {
@@ -13,12 +15,7 @@ pub struct RetryConfig {
 
 impl Default for RetryConfig {
     fn default() -> Self {
-        Self {
-            max_attempts: 3,
-            base_delay_ms: 100,
-            max_delay_ms: 10_000,
-            use_jitter: true,
-        }
+        Self::for_error_class(ErrorClass::Transient)
     }
 }
 
@@ -31,6 +28,29 @@ impl RetryConfig {
         }
     }
 
+    /// Retry policy suited to an error class
+    ///
+    /// Transient failures retry quickly, resource exhaustion retries slowly
+    /// to give memory a chance to free up, and permanent or fatal errors are
+    /// never retried.
+    pub fn for_error_class(class: ErrorClass) -> Self {
+        match class {
+            ErrorClass::Transient => Self {
+                max_attempts: 3,
+                base_delay_ms: 100,
+                max_delay_ms: 10_000,
+                use_jitter: true,
+            },
+            ErrorClass::ResourceExhausted => Self {
+                max_attempts: 2,
+                base_delay_ms: 2_000,
+                max_delay_ms: 30_000,
+                use_jitter: true,
+            },
+            ErrorClass::Permanent | ErrorClass::Fatal => Self::with_attempts(0),
+        }
+    }
+
     /// Create config for aggressive retries (more attempts, longer delays)
     pub fn aggressive() -> Self {
         Self {
@@ -72,6 +92,22 @@ mod tests {
         assert_eq!(cfg.base_delay_ms, 100);
     }
 
+    #[test]
+    fn test_retry_config_for_error_class() {
+        let transient = RetryConfig::for_error_class(ErrorClass::Transient);
+        assert_eq!((transient.max_attempts, transient.base_delay_ms), (3, 100));
+
+        let exhausted = RetryConfig::for_error_class(ErrorClass::ResourceExhausted);
+        assert_eq!(
+            (exhausted.max_attempts, exhausted.base_delay_ms),
+            (2, 2_000)
+        );
+
+        for class in [ErrorClass::Permanent, ErrorClass::Fatal] {
+            assert_eq!(RetryConfig::for_error_class(class).max_attempts, 0);
+        }
+    }
+
     #[test]
     fn test_retry_config_aggressive() {
         let cfg = RetryConfig::aggressive();
@@ -7,4 +7,4 @@
 /// - Deadline tracking across async boundaries
 pub use super::timeout_context::TimeoutContext;
 pub use super::timeout_manager::TimeoutManager;
-pub use super::timeout_stats::TimeoutStats;
+pub use super::timeout_stats::{OperationTimeoutStats, TimeoutStats};
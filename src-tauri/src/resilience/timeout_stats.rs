@@ -1,4 +1,28 @@
-use std::time::Duration;
+/// Timeout Statistics
+///
+/// `TimeoutStats` summarises the contexts of one `TimeoutManager`.
+/// `OperationTimeoutStats` tracks timeouts per operation name across
+/// requests, so an operation that keeps timing out (a systemic slowdown, or
+/// a bound that is too tight) shows up in `/metrics` and can be given a
+/// looser timeout.
+///
+/// The rolling rate is an exponentially weighted count of timeouts with a
+/// one minute time constant: each timeout adds 1 and the total decays by
+/// `e^(-t / 60s)`, which tracks timeouts per minute.
+use crate::error::{MinervaError, MinervaResult};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Time constant of the rolling timeout rate
+pub const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Share of calls that must time out before a looser timeout is suggested
+pub const SUGGEST_THRESHOLD: f64 = 0.1;
+
+/// Factor applied to the current timeout by `suggest_timeout`
+pub const SUGGEST_FACTOR: f64 = 1.5;
 
 /// Timeout statistics
 #[derive(Debug, Clone)]
@@ -24,6 +48,153 @@ impl TimeoutStats {
     }
 }
 
+/// Timeout figures for one operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationTimeouts {
+    pub operation: String,
+    pub calls: u64,
+    pub timeouts: u64,
+    pub last_timeout: Option<SystemTime>,
+    /// Rolling timeouts per minute
+    pub rate_per_minute: f64,
+}
+
+impl OperationTimeouts {
+    /// Share of calls that timed out, 0.0 to 1.0
+    pub fn timeout_fraction(&self) -> f64 {
+        match self.calls.max(self.timeouts) {
+            0 => 0.0,
+            calls => self.timeouts as f64 / calls as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Counters {
+    calls: u64,
+    timeouts: u64,
+    last_timeout: Option<SystemTime>,
+    rate: f64,
+    rate_updated: Instant,
+}
+
+impl Counters {
+    fn new(now: Instant) -> Self {
+        Self {
+            calls: 0,
+            timeouts: 0,
+            last_timeout: None,
+            rate: 0.0,
+            rate_updated: now,
+        }
+    }
+
+    fn rate_at(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.rate_updated);
+        self.rate * (-elapsed.as_secs_f64() / RATE_WINDOW.as_secs_f64()).exp()
+    }
+}
+
+/// Per-operation timeout counters, shared across requests
+#[derive(Debug, Default)]
+pub struct OperationTimeoutStats {
+    operations: Mutex<HashMap<String, Counters>>,
+}
+
+impl OperationTimeoutStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one call of `operation`, whether or not it times out
+    pub fn record_call(&self, operation: &str) {
+        self.with_counters(operation, Instant::now(), |counters| counters.calls += 1);
+    }
+
+    /// Count one timeout of `operation`
+    pub fn record_timeout(&self, operation: &str) {
+        self.record_timeout_at(operation, Instant::now());
+    }
+
+    /// Run `fut` bounded by `timeout`, counting the call and any timeout
+    pub async fn run_with_timeout<T>(
+        &self,
+        operation: &str,
+        timeout: Duration,
+        fut: impl Future<Output = MinervaResult<T>>,
+    ) -> MinervaResult<T> {
+        self.record_call(operation);
+        match tokio::time::timeout(timeout, fut).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.record_timeout(operation);
+                Err(MinervaError::GenerationTimeout)
+            }
+        }
+    }
+
+    /// Figures for `operation`, if it has been seen
+    pub fn operation(&self, operation: &str) -> Option<OperationTimeouts> {
+        let now = Instant::now();
+        self.operations
+            .lock()
+            .get(operation)
+            .map(|counters| snapshot(operation, counters, now))
+    }
+
+    /// Figures for every operation, sorted by name
+    pub fn snapshot(&self) -> Vec<OperationTimeouts> {
+        let now = Instant::now();
+        let mut operations: Vec<OperationTimeouts> = self
+            .operations
+            .lock()
+            .iter()
+            .map(|(name, counters)| snapshot(name, counters, now))
+            .collect();
+        operations.sort_by(|a, b| a.operation.cmp(&b.operation));
+        operations
+    }
+
+    /// `current * 1.5` when more than 10% of `operation`'s calls timed out,
+    /// otherwise `current`
+    pub fn suggest_timeout(&self, operation: &str, current: Duration) -> Duration {
+        match self.operation(operation) {
+            Some(stats) if stats.timeout_fraction() > SUGGEST_THRESHOLD => {
+                current.mul_f64(SUGGEST_FACTOR)
+            }
+            _ => current,
+        }
+    }
+
+    fn record_timeout_at(&self, operation: &str, now: Instant) {
+        self.with_counters(operation, now, |counters| {
+            counters.timeouts += 1;
+            counters.last_timeout = Some(SystemTime::now());
+            counters.rate = counters.rate_at(now) + 1.0;
+            counters.rate_updated = now;
+        });
+    }
+
+    fn with_counters(&self, operation: &str, now: Instant, update: impl FnOnce(&mut Counters)) {
+        let mut operations = self.operations.lock();
+        update(
+            operations
+                .entry(operation.to_string())
+                .or_insert_with(|| Counters::new(now)),
+        );
+    }
+}
+
+fn snapshot(operation: &str, counters: &Counters, now: Instant) -> OperationTimeouts {
+    OperationTimeouts {
+        operation: operation.to_string(),
+        calls: counters.calls,
+        timeouts: counters.timeouts,
+        last_timeout: counters.last_timeout,
+        rate_per_minute: counters.rate_at(now),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,4 +220,71 @@ mod tests {
         };
         assert_eq!(stats.timeout_rate(), 0.0);
     }
+
+    #[test]
+    fn test_counts_per_operation() {
+        let stats = OperationTimeoutStats::new();
+        for _ in 0..4 {
+            stats.record_call("generate");
+        }
+        stats.record_call("load");
+        stats.record_timeout("generate");
+
+        let generate = stats.operation("generate").unwrap();
+        assert_eq!((generate.calls, generate.timeouts), (4, 1));
+        assert!(generate.last_timeout.is_some());
+        assert_eq!(stats.operation("load").unwrap().timeouts, 0);
+        assert!(stats.operation("embed").is_none());
+
+        let names: Vec<String> = stats.snapshot().into_iter().map(|s| s.operation).collect();
+        assert_eq!(names, ["generate", "load"]);
+    }
+
+    #[test]
+    fn test_rate_decays_over_the_window() {
+        let stats = OperationTimeoutStats::new();
+        let start = Instant::now();
+        stats.record_timeout_at("generate", start);
+        stats.record_timeout_at("generate", start);
+
+        let operations = stats.operations.lock();
+        let counters = &operations["generate"];
+        assert!((counters.rate_at(start) - 2.0).abs() < 1e-9);
+        let later = counters.rate_at(start + RATE_WINDOW);
+        assert!((later - 2.0 / std::f64::consts::E).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_suggests_looser_timeout_above_threshold() {
+        let stats = OperationTimeoutStats::new();
+        let current = Duration::from_secs(10);
+        for _ in 0..10 {
+            stats.record_call("generate");
+        }
+        stats.record_timeout("generate");
+        assert_eq!(stats.suggest_timeout("generate", current), current);
+
+        stats.record_timeout("generate");
+        assert_eq!(
+            stats.suggest_timeout("generate", current),
+            Duration::from_secs(15)
+        );
+        assert_eq!(stats.suggest_timeout("unknown", current), current);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_timeout_counts_timeouts() {
+        let stats = OperationTimeoutStats::new();
+        let ok = stats.run_with_timeout("load", Duration::from_secs(5), async { Ok(1) });
+        assert_eq!(ok.await.unwrap(), 1);
+
+        let slow = stats.run_with_timeout("load", Duration::from_millis(10), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        });
+        assert!(matches!(slow.await, Err(MinervaError::GenerationTimeout)));
+
+        let load = stats.operation("load").unwrap();
+        assert_eq!((load.calls, load.timeouts), (2, 1));
+    }
 }
@@ -1,22 +1,264 @@
-use crate::error::MinervaResult;
+use super::ServerState;
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::api::InferenceRequest;
+use crate::inference::softmax_utils::argmax;
+use crate::inference::top_p_sampling::{TopPConfig, sample_top_p};
 use crate::models::{ChatCompletionRequest, ChatCompletionResponse, ChatMessage, Choice, Usage};
 use axum::Json;
+use axum::http::{HeaderMap, StatusCode};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// Request/response header carrying the conversation's session ID
+pub const SESSION_ID_HEADER: &str = "x-session-id";
+
+/// User turns (with their replies) kept per session by default
+pub const DEFAULT_MAX_HISTORY_TURNS: usize = 20;
+
+/// Sessions kept at once by default; the least recently used goes first
+pub const DEFAULT_MAX_SESSIONS: usize = 1024;
+
+/// Idle time after which a session is dropped by default
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Tokens generated when a request does not set `max_tokens`
+pub const DEFAULT_COMPLETION_TOKENS: usize = 256;
+
+/// Longest client-supplied session ID accepted
+const MAX_SESSION_ID_LEN: usize = 128;
+
+/// One conversation's message history
+///
+/// System messages are always kept, and a request's system messages replace
+/// the stored ones rather than piling up; older user/assistant turns are
+/// dropped once there are more than `max_history_turns` user messages.
+#[derive(Debug, Clone)]
+pub struct ChatSession {
+    messages: Vec<ChatMessage>,
+    max_history_turns: usize,
+}
+
+impl ChatSession {
+    pub fn new(max_history_turns: usize) -> Self {
+        Self {
+            messages: Vec::new(),
+            max_history_turns: max_history_turns.max(1),
+        }
+    }
+
+    /// Merge the messages of an OpenAI-style request into the history
+    ///
+    /// A request that already contains assistant turns carries its own
+    /// history, so it replaces ours; otherwise its messages are new turns.
+    pub fn apply_request(&mut self, messages: &[ChatMessage]) {
+        if messages.iter().any(|m| m.role == "assistant") {
+            self.messages = messages.to_vec();
+        } else {
+            let (system, turns): (Vec<_>, Vec<_>) =
+                messages.iter().cloned().partition(|m| m.role == "system");
+            if !system.is_empty() {
+                self.messages.retain(|m| m.role != "system");
+                self.messages.splice(0..0, system);
+            }
+            self.messages.extend(turns);
+        }
+        self.trim();
+    }
+
+    /// Record the assistant's reply to the latest turn
+    pub fn push_assistant(&mut self, content: String) {
+        self.messages.push(ChatMessage {
+            role: "assistant".to_string(),
+            content,
+        });
+    }
+
+    /// Full history to send for inference
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    fn trim(&mut self) {
+        let user_turns = self.messages.iter().filter(|m| m.role == "user").count();
+        let to_drop = user_turns.saturating_sub(self.max_history_turns);
+        if to_drop == 0 {
+            return;
+        }
+        // Keep system messages, and everything from the first kept user turn on
+        let mut users_seen = 0;
+        self.messages.retain(|m| {
+            if m.role == "user" {
+                users_seen += 1;
+            }
+            m.role == "system" || users_seen > to_drop
+        });
+    }
+}
+
+#[derive(Debug)]
+struct SessionEntry {
+    session: ChatSession,
+    last_used: Instant,
+}
+
+/// Chat sessions by session ID
+///
+/// Sessions idle for longer than the TTL are dropped, and once
+/// `max_sessions` are stored the least recently used one makes room.
+#[derive(Debug)]
+pub struct ChatSessionStore {
+    sessions: Mutex<HashMap<String, SessionEntry>>,
+    max_history_turns: usize,
+    max_sessions: usize,
+    ttl: Duration,
+}
+
+impl ChatSessionStore {
+    pub fn new(max_history_turns: usize) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            max_history_turns,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            ttl: DEFAULT_SESSION_TTL,
+        }
+    }
+
+    /// Bound the number of stored sessions and how long an idle one is kept
+    pub fn with_limits(mut self, max_sessions: usize, ttl: Duration) -> Self {
+        self.max_sessions = max_sessions.max(1);
+        self.ttl = ttl;
+        self
+    }
+
+    /// Add the request's messages to the session, creating it if needed, and
+    /// return the history to run inference on
+    pub fn begin_turn(&self, session_id: &str, messages: &[ChatMessage]) -> Vec<ChatMessage> {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, entry| now.duration_since(entry.last_used) < self.ttl);
+        if !sessions.contains_key(session_id) && sessions.len() >= self.max_sessions {
+            let oldest = sessions
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                sessions.remove(&oldest);
+            }
+        }
+
+        let entry = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| SessionEntry {
+                session: ChatSession::new(self.max_history_turns),
+                last_used: now,
+            });
+        entry.last_used = now;
+        entry.session.apply_request(messages);
+        entry.session.messages().to_vec()
+    }
+
+    /// Append the assistant reply to the session's history
+    pub fn finish_turn(&self, session_id: &str, reply: String) {
+        if let Some(entry) = self.sessions.lock().unwrap().get_mut(session_id) {
+            entry.session.push_assistant(reply);
+            entry.last_used = Instant::now();
+        }
+    }
+
+    /// Current history of `session_id`, if the session exists
+    pub fn history(&self, session_id: &str) -> Option<Vec<ChatMessage>> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions
+            .get(session_id)
+            .map(|entry| entry.session.messages().to_vec())
+    }
+
+    /// Number of stored sessions
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for ChatSessionStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_HISTORY_TURNS)
+    }
+}
+
+/// Session ID from the `X-Session-Id` header, or a new one when the
+/// request has none
+///
+/// The ID is returned in the response header, so clients can continue the
+/// conversation by sending it back.
+pub fn session_id_from(headers: &HeaderMap) -> String {
+    headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_SESSION_ID_LEN)
+        .map_or_else(|| Uuid::new_v4().to_string(), str::to_string)
+}
+
+/// Generate a reply one token at a time, taking each step's logits from the
+/// continuous batching loop
+///
+/// Fails with 503 when no model is attached to batch requests through.
 pub async fn create_completion_response(
+    state: &ServerState,
     req: ChatCompletionRequest,
 ) -> MinervaResult<Json<ChatCompletionResponse>> {
+    let backend = match (&state.backend, &state.batch_forward) {
+        (Some(backend), Some(_)) => backend.clone(),
+        _ => {
+            return Err(MinervaError::Http(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "No model is loaded for inference".to_string(),
+            ));
+        }
+    };
     let completion_id = format!("chatcmpl-{}", Uuid::new_v4());
     let created = chrono::Utc::now().timestamp();
     let prompt = build_chat_prompt(&req.messages);
 
-    let response_content = format!(
-        "Minerva inference response to: \"{}\" - Mock response for testing",
-        prompt.chars().take(50).collect::<String>()
-    );
+    let mut tokens: Vec<u32> = backend
+        .tokenize(&prompt)?
+        .into_iter()
+        .map(|token| token as u32)
+        .collect();
+    let prompt_tokens = tokens.len();
+    let request = InferenceRequest {
+        model: req.model.clone(),
+        prompt,
+        max_tokens: req.max_tokens,
+        temperature: req.temperature,
+        top_k: None,
+        top_p: req.top_p,
+        seed: Some(Uuid::new_v4().as_u64_pair().0),
+    };
 
-    let prompt_tokens = estimate_tokens(&prompt);
-    let completion_tokens = estimate_tokens(&response_content);
+    for step in 0..req.max_tokens.unwrap_or(DEFAULT_COMPLETION_TOKENS) {
+        let logits = state
+            .batch_queue
+            .submit(request.clone(), tokens.clone())
+            .await
+            .map_err(|_| {
+                MinervaError::InferenceError("Batching loop dropped the request".to_string())
+            })??;
+        tokens.push(sample_next(&logits, &request, step as u64)? as u32);
+    }
+
+    let completion: Vec<i32> = tokens[prompt_tokens..]
+        .iter()
+        .map(|&token| token as i32)
+        .collect();
+    let response_content = backend.detokenize(&completion)?;
+    let completion_tokens = completion.len();
 
     Ok(Json(ChatCompletionResponse {
         id: completion_id,
@@ -29,7 +271,7 @@ pub async fn create_completion_response(
                 role: "assistant".to_string(),
                 content: response_content,
             },
-            finish_reason: "stop".to_string(),
+            finish_reason: "length".to_string(),
         }],
         usage: Usage {
             prompt_tokens,
@@ -39,6 +281,21 @@ pub async fn create_completion_response(
     }))
 }
 
+/// Pick the next token: the argmax at temperature 0, nucleus sampling otherwise
+fn sample_next(logits: &[f32], request: &InferenceRequest, step: u64) -> MinervaResult<usize> {
+    let temperature = request.temperature.unwrap_or(1.0);
+    if temperature <= 0.0 {
+        return argmax(logits)
+            .ok_or_else(|| MinervaError::InferenceError("Empty logits".to_string()));
+    }
+    let config = TopPConfig {
+        p: request.top_p.unwrap_or(1.0),
+        temperature,
+    };
+    let seed = request.seed.unwrap_or_default().wrapping_add(step);
+    sample_top_p(logits, &config, seed)
+}
+
 pub fn build_chat_prompt(messages: &[ChatMessage]) -> String {
     messages
         .iter()
@@ -50,3 +307,102 @@ pub fn build_chat_prompt(messages: &[ChatMessage]) -> String {
 pub fn estimate_tokens(text: &str) -> usize {
     (text.len() / 4).max(1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn contents(messages: &[ChatMessage]) -> Vec<&str> {
+        messages.iter().map(|m| m.content.as_str()).collect()
+    }
+
+    #[test]
+    fn test_session_accumulates_turns() {
+        let store = ChatSessionStore::default();
+        store.begin_turn("s1", &[msg("system", "be brief"), msg("user", "hi")]);
+        store.finish_turn("s1", "hello".to_string());
+        let history = store.begin_turn("s1", &[msg("user", "how are you")]);
+
+        assert_eq!(
+            contents(&history),
+            vec!["be brief", "hi", "hello", "how are you"]
+        );
+        assert!(store.history("s2").is_none());
+    }
+
+    #[test]
+    fn test_history_limited_to_max_turns() {
+        let mut session = ChatSession::new(2);
+        session.apply_request(&[msg("system", "sys")]);
+        for turn in 0..4 {
+            session.apply_request(&[msg("user", &format!("q{}", turn))]);
+            session.push_assistant(format!("a{}", turn));
+        }
+        session.apply_request(&[msg("user", "q4")]);
+
+        assert_eq!(contents(session.messages()), vec!["sys", "q3", "a3", "q4"]);
+    }
+
+    #[test]
+    fn test_request_with_assistant_turns_replaces_history() {
+        let mut session = ChatSession::new(DEFAULT_MAX_HISTORY_TURNS);
+        session.apply_request(&[msg("user", "old")]);
+        session.apply_request(&[msg("user", "q"), msg("assistant", "a"), msg("user", "q2")]);
+
+        assert_eq!(contents(session.messages()), vec!["q", "a", "q2"]);
+    }
+
+    #[test]
+    fn test_resent_system_message_is_not_duplicated() {
+        let mut session = ChatSession::new(DEFAULT_MAX_HISTORY_TURNS);
+        session.apply_request(&[msg("system", "be brief"), msg("user", "hi")]);
+        session.push_assistant("hello".to_string());
+        session.apply_request(&[msg("system", "be terse"), msg("user", "bye")]);
+
+        assert_eq!(
+            contents(session.messages()),
+            vec!["be terse", "hi", "hello", "bye"]
+        );
+    }
+
+    #[test]
+    fn test_least_recently_used_session_is_evicted() {
+        let store = ChatSessionStore::default().with_limits(2, DEFAULT_SESSION_TTL);
+        store.begin_turn("a", &[msg("user", "1")]);
+        store.begin_turn("b", &[msg("user", "2")]);
+        store.begin_turn("a", &[msg("user", "3")]);
+        store.begin_turn("c", &[msg("user", "4")]);
+
+        assert_eq!(store.len(), 2);
+        assert!(store.history("a").is_some());
+        assert!(store.history("b").is_none());
+    }
+
+    #[test]
+    fn test_idle_sessions_expire() {
+        let store = ChatSessionStore::default().with_limits(DEFAULT_MAX_SESSIONS, Duration::ZERO);
+        store.begin_turn("a", &[msg("user", "1")]);
+        store.begin_turn("b", &[msg("user", "2")]);
+
+        assert!(store.history("a").is_none());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_session_id_from_header_or_generated() {
+        let mut headers = HeaderMap::new();
+        headers.insert(SESSION_ID_HEADER, "abc".parse().unwrap());
+        assert_eq!(session_id_from(&headers), "abc");
+
+        let generated = session_id_from(&HeaderMap::new());
+        assert!(Uuid::parse_str(&generated).is_ok());
+        assert_ne!(generated, session_id_from(&HeaderMap::new()));
+    }
+}
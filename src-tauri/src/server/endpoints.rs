@@ -1,48 +1,93 @@
 use super::server_state::{
     ModelLoadRequest, ModelOperationResponse, ModelStatsResponse, ServerState,
 };
-use crate::error::MinervaResult;
+use crate::error::{MinervaError, MinervaResult};
+use crate::performance::inference_metrics::ModelPerformanceResponse;
+use crate::performance::inference_metrics_query::InferenceMetricsQuery;
 use axum::{
     Json,
     extract::{Path, State},
+    http::StatusCode,
     response::IntoResponse,
 };
 
 #[allow(dead_code)]
-pub async fn health_check_enhanced() -> impl IntoResponse {
+pub async fn health_check_enhanced(State(state): State<ServerState>) -> impl IntoResponse {
     use crate::observability::health::HealthEndpointResponse;
-
-    let mut resp = HealthEndpointResponse {
-        timestamp: chrono::Local::now().to_rfc3339(),
-        ..Default::default()
+    use crate::observability::health_checks::{
+        check_disk_space, check_gpu, check_inference, check_model_registry,
     };
+
+    let latency = state
+        .latency_monitor
+        .lock()
+        .check_latency_degradation(&state.metrics.snapshot());
+    let mut resp = HealthEndpointResponse::default()
+        .with_component("inference", check_inference(&latency))
+        .with_component(
+            "models",
+            check_model_registry(&*state.model_registry.lock().await),
+        )
+        .with_component("gpu", check_gpu(&state.metrics.gpu_stats()));
+    if let Some(models_dir) = &state.models_dir {
+        resp = resp.with_component("disk", check_disk_space(models_dir));
+    }
     resp.calculate_status();
-    Json(resp)
+
+    // Let load balancers route away from a failing or slow instance; other
+    // degradations (e.g. low disk) are reported but keep serving
+    let code = if resp.can_accept_requests() && !latency.is_degraded() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(resp))
 }
 
 #[allow(dead_code)]
-pub async fn readiness_check() -> impl IntoResponse {
+pub async fn readiness_check(State(state): State<ServerState>) -> impl IntoResponse {
     use crate::observability::endpoints::ReadinessResponse;
 
-    let resp = ReadinessResponse::ready();
-    Json(resp)
+    let status = state
+        .readiness
+        .is_ready(&*state.model_registry.lock().await);
+    let code = if status.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(ReadinessResponse::from(&status)))
 }
 
 #[allow(dead_code)]
 pub async fn metrics_endpoint(State(state): State<ServerState>) -> impl IntoResponse {
+    Json(metrics_response(&state))
+}
+
+/// `/metrics` in Prometheus text format
+pub async fn prometheus_metrics_endpoint(State(state): State<ServerState>) -> impl IntoResponse {
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4",
+        )],
+        metrics_response(&state).to_prometheus(),
+    )
+}
+
+fn metrics_response(state: &ServerState) -> crate::observability::endpoints::MetricsResponse {
     use crate::observability::endpoints::{
-        CacheMetrics, ErrorMetrics, MetricsResponse, RequestMetrics, ResponseTimeMetrics,
+        CacheMetrics, ErrorMetrics, GpuMetrics, MetricsResponse, RequestMetrics,
+        ResponseTimeMetrics, TimeoutMetrics,
     };
 
+    state.metrics.detect_anomalies();
     let metrics = state.metrics.snapshot();
-    let uptime = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    let resp = MetricsResponse {
+    let gpu = state.metrics.gpu_stats();
+    let efficiency_score = state.metrics.efficiency_score(&state.slo);
+    MetricsResponse {
         timestamp: chrono::Local::now().to_rfc3339(),
-        uptime_seconds: uptime,
+        uptime_seconds: metrics.uptime_seconds,
         requests: RequestMetrics {
             total: metrics.total_requests,
             successful: metrics.successful_requests,
@@ -67,21 +112,57 @@ pub async fn metrics_endpoint(State(state): State<ServerState>) -> impl IntoResp
             misses: metrics.cache_misses,
             hit_rate_percent: metrics.cache_hit_rate_percent,
         },
-    };
-
-    Json(resp)
+        gpu: GpuMetrics {
+            utilization_percent: gpu.utilization_percent,
+            memory_used_mb: gpu.memory_used_mb(),
+            memory_total_mb: gpu.memory_total_mb,
+        },
+        timeouts: state
+            .timeout_stats
+            .snapshot()
+            .into_iter()
+            .map(|op| TimeoutMetrics {
+                last_timeout_unix: op.last_timeout.and_then(|at| {
+                    at.duration_since(std::time::UNIX_EPOCH)
+                        .ok()
+                        .map(|d| d.as_secs())
+                }),
+                operation: op.operation,
+                calls: op.calls,
+                timeouts: op.timeouts,
+                rate_per_minute: op.rate_per_minute,
+            })
+            .collect(),
+        efficiency_score,
+        latency_anomalies: state.metrics.latency_anomalies(),
+    }
 }
 
 #[allow(dead_code)]
 pub async fn load_model(
-    State(_state): State<ServerState>,
-    Path(_id): Path<String>,
-    Json(_req): Json<ModelLoadRequest>,
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(req): Json<ModelLoadRequest>,
 ) -> MinervaResult<Json<ModelOperationResponse>> {
+    let path = std::path::PathBuf::from(&req.model_path);
+    let models_dir = path
+        .parent()
+        .unwrap_or(std::path::Path::new("."))
+        .to_path_buf();
+    let mut info = crate::models::loader::ModelLoader::new(models_dir).load_model(&path)?;
+    info.id = id.clone();
+    state
+        .model_registry
+        .lock()
+        .await
+        .add_model(info, path.clone());
+
+    state.spawn_warmup(id.clone(), path);
+
     Ok(Json(ModelOperationResponse {
         success: true,
-        message: "Model loading not yet implemented".to_string(),
-        model_id: Some(_id),
+        message: "Model loaded; warming up".to_string(),
+        model_id: Some(id),
     }))
 }
 
@@ -110,6 +191,16 @@ pub async fn unload_model(
     }))
 }
 
+/// Token throughput percentiles for a model that has served requests
+pub async fn model_performance(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> MinervaResult<Json<ModelPerformanceResponse>> {
+    InferenceMetricsQuery::for_model(&state.model_metrics, &id)
+        .map(|metrics| Json(metrics.summary()))
+        .ok_or_else(|| MinervaError::ModelNotFound(format!("No performance data for '{}'", id)))
+}
+
 #[allow(dead_code)]
 pub async fn model_stats(
     State(_state): State<ServerState>,
@@ -1,42 +1,53 @@
-use super::chat::create_completion_response;
+use super::chat::{SESSION_ID_HEADER, create_completion_response, session_id_from};
+use super::negotiation::Negotiated;
 use super::streaming::create_streaming_response;
-use super::validation::validate_chat_request;
 use crate::error::MinervaResult;
-use crate::models::ChatCompletionRequest;
+use crate::inference::gpu::{OpenAIListModelsResponse, OpenAIModelInfo};
+use crate::inference::parameters::ParameterParser;
+use crate::middleware::ValidatedJson;
+use crate::models::{ChatCompletionRequest, ChatCompletionResponse};
+use crate::performance::inference_metrics::InferenceMetrics;
+use crate::resilience::TimeoutConfig;
 use crate::server::ServerState;
-use axum::http::HeaderMap;
+use axum::http::{HeaderMap, HeaderValue};
 use axum::{Json, response::IntoResponse};
 
 pub async fn list_models(
     axum::extract::State(state): axum::extract::State<ServerState>,
-) -> MinervaResult<Json<crate::models::ModelsListResponse>> {
+    headers: HeaderMap,
+) -> MinervaResult<Negotiated<OpenAIListModelsResponse>> {
     let registry = state.model_registry.lock().await;
     let models = registry.list_models();
 
-    Ok(Json(crate::models::ModelsListResponse {
-        object: "list".to_string(),
-        data: models,
-    }))
+    Ok(Negotiated::new(
+        &headers,
+        OpenAIListModelsResponse {
+            object: "list".to_string(),
+            data: models.into_iter().map(OpenAIModelInfo::from).collect(),
+        },
+    ))
 }
 
 pub async fn chat_completions(
     axum::extract::State(state): axum::extract::State<ServerState>,
     headers: HeaderMap,
-    Json(req): Json<ChatCompletionRequest>,
+    ValidatedJson(mut req): ValidatedJson<ChatCompletionRequest>,
 ) -> MinervaResult<axum::response::Response> {
     let client_id = headers
         .get("x-client-id")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("anonymous");
 
-    validate_chat_request(&req)?;
+    if let Some(defaults) = &state.generation_defaults {
+        ParameterParser::fill_defaults(&mut req, &defaults.read());
+    }
 
     if !state.rate_limiter.allow_request(client_id, 1.0).await {
         let retry = state.rate_limiter.retry_after(client_id, 1.0).await;
-        return Err(crate::error::MinervaError::InvalidRequest(format!(
-            "Rate limit exceeded. Retry after {} seconds",
-            retry
-        )));
+        return Err(crate::error::MinervaError::Http(
+            axum::http::StatusCode::TOO_MANY_REQUESTS,
+            format!("Rate limit exceeded. Retry after {} seconds", retry),
+        ));
     }
 
     let registry = state.model_registry.lock().await;
@@ -44,11 +55,70 @@ pub async fn chat_completions(
         crate::error::MinervaError::ModelNotFound(format!("Model '{}' not found", req.model))
     })?;
 
-    let is_streaming = req.stream.unwrap_or(false);
+    let session_id = session_id_from(&headers);
+    req.messages = state.chat_sessions.begin_turn(&session_id, &req.messages);
+
+    let settings = state.streaming_settings(&req.model);
+    let is_streaming = req.stream.unwrap_or(false) && !settings.disable_streaming;
 
-    if is_streaming {
-        Ok(create_streaming_response(req).into_response())
+    let mut response = if is_streaming {
+        let sessions = state.chat_sessions.clone();
+        let id = session_id.clone();
+        let on_complete = move |reply: String| sessions.finish_turn(&id, reply);
+        create_streaming_response(req, &state, on_complete).into_response()
     } else {
-        Ok(create_completion_response(req).await?.into_response())
+        let started = std::time::Instant::now();
+        let Json(response) = timed_completion(&state, req).await?;
+        record_throughput(&state, &response, started.elapsed());
+        if let Some(choice) = response.choices.first() {
+            state
+                .chat_sessions
+                .finish_turn(&session_id, choice.message.content.clone());
+        }
+        Negotiated::new(&headers, response).into_response()
+    };
+
+    if let Ok(value) = HeaderValue::from_str(&session_id) {
+        response.headers_mut().insert(SESSION_ID_HEADER, value);
     }
+    Ok(response)
+}
+
+/// Operation name chat completions are counted under in `/metrics`
+pub const CHAT_COMPLETION_OPERATION: &str = "chat_completion";
+
+/// Generate a completion under the operation timeout, loosened while the
+/// operation keeps timing out, and count it in `state.timeout_stats`
+async fn timed_completion(
+    state: &ServerState,
+    req: ChatCompletionRequest,
+) -> MinervaResult<Json<ChatCompletionResponse>> {
+    let stats = &state.timeout_stats;
+    let timeout = stats.suggest_timeout(
+        CHAT_COMPLETION_OPERATION,
+        TimeoutConfig::default().operation_timeout,
+    );
+    stats
+        .run_with_timeout(
+            CHAT_COMPLETION_OPERATION,
+            timeout,
+            create_completion_response(state, req),
+        )
+        .await
+}
+
+/// Feed a finished generation into the per-model throughput history
+fn record_throughput(
+    state: &ServerState,
+    response: &ChatCompletionResponse,
+    elapsed: std::time::Duration,
+) {
+    let tokens = response.usage.completion_tokens as u64;
+    state.model_metrics.record(&InferenceMetrics {
+        model: response.model.clone(),
+        tokens_generated: tokens,
+        duration_ms: elapsed.as_millis() as u64,
+        tokens_per_second: tokens as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        used_gpu: false,
+    });
 }
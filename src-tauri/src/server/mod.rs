@@ -2,43 +2,58 @@
 pub mod chat;
 pub mod endpoints;
 pub mod handlers;
+pub mod negotiation;
 pub mod server_state;
 pub mod streaming;
 pub mod validation;
 
 use self::endpoints::{
-    health_check_enhanced, load_model, metrics_endpoint, model_stats, preload_model,
-    readiness_check, unload_model,
+    health_check_enhanced, load_model, metrics_endpoint, model_performance, model_stats,
+    preload_model, prometheus_metrics_endpoint, readiness_check, unload_model,
 };
 pub use self::server_state::ServerState;
+use crate::middleware::{LoggingMiddleware, log_requests};
+use crate::observability::tracing_middleware::trace_requests;
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
+    middleware::{from_fn, from_fn_with_state},
     routing::{delete, get, post},
 };
 use tower_http::cors::CorsLayer;
 
 #[allow(dead_code)]
 pub async fn create_server(state: ServerState) -> Router {
+    let max_body_bytes = state.max_request_body_bytes;
+    let body_limit = DefaultBodyLimit::max(max_body_bytes);
     Router::new()
         .route("/v1/models", get(handlers::list_models))
         .route("/v1/models/:id/load", post(load_model))
         .route("/v1/models/:id/preload", post(preload_model))
+        .route("/v1/models/:id/performance", get(model_performance))
         .route("/v1/models/:id", delete(unload_model))
         .route("/v1/chat/completions", post(handlers::chat_completions))
         .route("/health", get(health_check_enhanced))
         .route("/ready", get(readiness_check))
         .route("/metrics", get(metrics_endpoint))
+        .route("/metrics/prometheus", get(prometheus_metrics_endpoint))
         .route("/v1/models/stats", get(model_stats))
         .with_state(state)
+        .layer(body_limit)
         .layer(CorsLayer::permissive())
+        .layer(from_fn_with_state(
+            LoggingMiddleware::from_env().with_max_body_bytes(max_body_bytes),
+            log_requests,
+        ))
+        .layer(from_fn(trace_requests))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::middleware::ValidatedJson;
     use crate::models::ChatMessage;
     use crate::models::ModelRegistry;
-    use axum::Json;
     use axum::http::HeaderMap;
 
     #[test]
@@ -59,6 +74,7 @@ mod tests {
             owned_by: "local".to_string(),
             context_window: Some(4096),
             max_output_tokens: Some(2048),
+            capabilities: Default::default(),
         };
 
         let path = std::path::PathBuf::from("/tmp/test-model.gguf");
@@ -71,10 +87,10 @@ mod tests {
     #[tokio::test]
     async fn test_list_models_endpoint() {
         let state = ServerState::new();
-        let response = handlers::list_models(axum::extract::State(state)).await;
+        let response = handlers::list_models(axum::extract::State(state), HeaderMap::new()).await;
 
         assert!(response.is_ok());
-        let Json(models_response) = response.unwrap();
+        let models_response = response.unwrap().body;
         assert_eq!(models_response.data.len(), 0);
         assert_eq!(models_response.object, "list");
     }
@@ -94,11 +110,224 @@ mod tests {
             top_p: None,
             frequency_penalty: None,
             presence_penalty: None,
+            stream_options: None,
         };
 
         let headers = HeaderMap::new();
         let response =
-            handlers::chat_completions(axum::extract::State(state), headers, Json(req)).await;
+            handlers::chat_completions(axum::extract::State(state), headers, ValidatedJson(req))
+                .await;
         assert!(response.is_err());
     }
+
+    #[tokio::test]
+    async fn test_chat_completions_unavailable_without_backend() {
+        use crate::models::ModelInfo;
+        let state = ServerState::new();
+        state.model_registry.lock().await.add_model(
+            ModelInfo {
+                id: "llama".to_string(),
+                object: "model".to_string(),
+                created: 1704067200,
+                owned_by: "local".to_string(),
+                context_window: Some(4096),
+                max_output_tokens: Some(2048),
+                capabilities: Default::default(),
+            },
+            std::path::PathBuf::from("/tmp/llama.gguf"),
+        );
+        let req = crate::models::ChatCompletionRequest {
+            model: "llama".to_string(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "Hello".to_string(),
+            }],
+            temperature: None,
+            max_tokens: None,
+            stream: None,
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stream_options: None,
+        };
+
+        let response = handlers::chat_completions(
+            axum::extract::State(state),
+            HeaderMap::new(),
+            ValidatedJson(req),
+        )
+        .await;
+        assert!(matches!(
+            response,
+            Err(crate::error::MinervaError::Http(
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                _
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_chat_completions_counted_in_timeout_stats() {
+        use crate::inference::batch_async::BatchAsync;
+        use crate::inference::inference_backend_trait::InferenceBackend;
+        use crate::inference::mock_backend::MockBackend;
+        use crate::models::ModelInfo;
+
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = MockBackend::new();
+        backend.load_model(model.path(), 512).unwrap();
+        let state = ServerState::new().with_backend(std::sync::Arc::new(backend));
+        let batching = tokio::spawn(BatchAsync::run(state.clone()));
+        state.model_registry.lock().await.add_model(
+            ModelInfo {
+                id: "llama".to_string(),
+                object: "model".to_string(),
+                created: 1704067200,
+                owned_by: "local".to_string(),
+                context_window: Some(4096),
+                max_output_tokens: Some(2048),
+                capabilities: Default::default(),
+            },
+            std::path::PathBuf::from("/tmp/llama.gguf"),
+        );
+        let req = crate::models::ChatCompletionRequest {
+            model: "llama".to_string(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "Hello".to_string(),
+            }],
+            temperature: None,
+            max_tokens: Some(3),
+            stream: None,
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stream_options: None,
+        };
+
+        handlers::chat_completions(
+            axum::extract::State(state.clone()),
+            HeaderMap::new(),
+            ValidatedJson(req),
+        )
+        .await
+        .unwrap();
+        batching.abort();
+        let counted = state
+            .timeout_stats
+            .operation(handlers::CHAT_COMPLETION_OPERATION)
+            .unwrap();
+        assert_eq!((counted.calls, counted.timeouts), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_model_performance_endpoint() {
+        use crate::performance::inference_metrics::InferenceMetrics;
+        let state = ServerState::new();
+        let missing = model_performance(
+            axum::extract::State(state.clone()),
+            axum::extract::Path("llama".to_string()),
+        )
+        .await;
+        assert!(missing.is_err());
+
+        state.model_metrics.record(&InferenceMetrics {
+            model: "llama".to_string(),
+            tokens_generated: 50,
+            duration_ms: 1000,
+            tokens_per_second: 50.0,
+            used_gpu: false,
+        });
+        let summary = model_performance(
+            axum::extract::State(state),
+            axum::extract::Path("llama".to_string()),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(summary.total_tokens, 50);
+        assert_eq!(summary.p99_tokens_per_sec, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_unavailable_without_model() {
+        use axum::response::IntoResponse;
+        let response = readiness_check(axum::extract::State(ServerState::new()))
+            .await
+            .into_response();
+        assert_eq!(
+            response.status(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_readiness_ok_after_warmup() {
+        use crate::inference::batch_queue::BackendForward;
+        use crate::inference::inference_backend_trait::InferenceBackend;
+        use crate::inference::mock_backend::MockBackend;
+        use axum::response::IntoResponse;
+        use std::sync::Arc;
+
+        let state = ServerState::new();
+        state.model_registry.lock().await.add_model(
+            crate::models::ModelInfo {
+                id: "m".to_string(),
+                object: "model".to_string(),
+                created: 0,
+                owned_by: "local".to_string(),
+                context_window: None,
+                max_output_tokens: None,
+                capabilities: Default::default(),
+            },
+            std::path::PathBuf::from("/tmp/m.gguf"),
+        );
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = MockBackend::new();
+        backend.load_model(model.path(), 512).unwrap();
+        state
+            .readiness
+            .warm_up("m", &BackendForward::new(Arc::new(backend)))
+            .unwrap();
+
+        let response = readiness_check(axum::extract::State(state))
+            .await
+            .into_response();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_warmup_without_backend_stays_unready() {
+        use crate::observability::readiness::ReadinessStatus;
+
+        let state = ServerState::new();
+        let path = std::path::PathBuf::from("/nonexistent/m.gguf");
+        state.model_registry.lock().await.add_model(
+            crate::models::ModelInfo {
+                id: "m".to_string(),
+                object: "model".to_string(),
+                created: 0,
+                owned_by: "local".to_string(),
+                context_window: None,
+                max_output_tokens: None,
+                capabilities: Default::default(),
+            },
+            path.clone(),
+        );
+
+        state.spawn_warmup("m".to_string(), path);
+        for _ in 0..100 {
+            match state
+                .readiness
+                .is_ready(&*state.model_registry.lock().await)
+            {
+                ReadinessStatus::Warming { .. } => {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await
+                }
+                ReadinessStatus::Unready { .. } => return,
+                ReadinessStatus::Ready => panic!("model without a backend reported ready"),
+            }
+        }
+        panic!("warmup never finished");
+    }
 }
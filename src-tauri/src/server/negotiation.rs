@@ -0,0 +1,119 @@
+/// Response Format Negotiation
+///
+/// Picks JSON or MessagePack from the request's `Accept` header. MessagePack
+/// is roughly a third the size of JSON for float-heavy bodies such as
+/// embedding vectors; JSON stays the default.
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Serialization format for a response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    MsgPack,
+}
+
+impl ResponseFormat {
+    /// MessagePack when `Accept` names `application/msgpack` (or `x-msgpack`)
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let accepts_msgpack = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(|media| media.split(';').next().unwrap_or("").trim())
+            .any(|media| media == MSGPACK_CONTENT_TYPE || media == "application/x-msgpack");
+        if accepts_msgpack {
+            Self::MsgPack
+        } else {
+            Self::Json
+        }
+    }
+}
+
+/// A response body serialized in the format the client asked for
+#[derive(Debug)]
+pub struct Negotiated<T> {
+    pub format: ResponseFormat,
+    pub body: T,
+}
+
+impl<T> Negotiated<T> {
+    pub fn new(headers: &HeaderMap, body: T) -> Self {
+        Self {
+            format: ResponseFormat::from_headers(headers),
+            body,
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        match self.format {
+            ResponseFormat::Json => axum::Json(self.body).into_response(),
+            ResponseFormat::MsgPack => match rmp_serde::to_vec_named(&self.body) {
+                Ok(bytes) => (
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(MSGPACK_CONTENT_TYPE),
+                    )],
+                    bytes,
+                )
+                    .into_response(),
+                Err(e) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to encode MessagePack response: {}", e),
+                )
+                    .into_response(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_format_from_accept_header() {
+        assert_eq!(
+            ResponseFormat::from_headers(&HeaderMap::new()),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/json")),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/json;q=0.5, application/msgpack")),
+            ResponseFormat::MsgPack
+        );
+    }
+
+    #[test]
+    fn test_msgpack_response_is_smaller_for_floats() {
+        let embedding = serde_json::json!({ "embedding": vec![0.123_456_78_f32; 1536] });
+        let json = serde_json::to_vec(&embedding).unwrap();
+
+        let response = Negotiated::new(&accept(MSGPACK_CONTENT_TYPE), &embedding).into_response();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            MSGPACK_CONTENT_TYPE
+        );
+
+        let msgpack = rmp_serde::to_vec_named(&embedding).unwrap();
+        assert!(msgpack.len() < json.len());
+        let decoded: serde_json::Value = rmp_serde::from_slice(&msgpack).unwrap();
+        assert_eq!(decoded["embedding"].as_array().unwrap().len(), 1536);
+    }
+}
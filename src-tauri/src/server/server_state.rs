@@ -1,12 +1,30 @@
+use super::chat::{ChatSessionStore, estimate_tokens};
+use super::validation::RequestValidator;
+use crate::config::{
+    DEFAULT_MAX_REQUEST_BODY_BYTES, GenerationConfigUpdate, SloConfig, StreamingConfigEntry,
+};
 use crate::error::MinervaResult;
+use crate::inference::batch_optimized::BatchTensorPool;
+use crate::inference::batch_queue::{BackendForward, BatchForward, RequestQueue};
+use crate::inference::inference_backend_trait::InferenceBackend;
+use crate::inference::unified_backend_routing::{BackendSetup, load_backend};
 use crate::middleware::RateLimiter;
 use crate::models::ModelRegistry;
 use crate::observability::metrics::MetricsCollector;
+use crate::observability::readiness::ReadinessChecker;
+use crate::performance::inference_metrics::ModelMetricsStore;
+use crate::resilience::health::ComponentHealthMonitor;
+use crate::resilience::timeout::OperationTimeoutStats;
+use axum::extract::FromRef;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 pub type SharedModelRegistry = Arc<Mutex<ModelRegistry>>;
+/// Sampling overrides shared with the desktop app's settings
+pub type SharedGenerationConfig = Arc<parking_lot::RwLock<GenerationConfigUpdate>>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
@@ -37,6 +55,31 @@ pub struct ServerState {
     pub model_registry: SharedModelRegistry,
     pub metrics: Arc<MetricsCollector>,
     pub rate_limiter: Arc<RateLimiter>,
+    pub batch_queue: Arc<RequestQueue>,
+    pub batch_forward: Option<Arc<dyn BatchForward>>,
+    /// Model loaded at startup, whose tokenizer counts prompt tokens
+    pub backend: Option<Arc<dyn InferenceBackend>>,
+    pub tensor_pool: Arc<BatchTensorPool<u32>>,
+    pub readiness: Arc<ReadinessChecker>,
+    pub model_metrics: Arc<ModelMetricsStore>,
+    /// Per-model streaming settings keyed by model ID (or `"*"`)
+    pub streaming: Arc<HashMap<String, StreamingConfigEntry>>,
+    /// Multi-turn chat history keyed by `X-Session-Id`
+    pub chat_sessions: Arc<ChatSessionStore>,
+    /// JSON Schema applied to chat request bodies
+    pub request_validator: Arc<RequestValidator>,
+    /// Request bodies above this size are rejected with 413
+    pub max_request_body_bytes: usize,
+    /// User-set sampling values for requests that omit them
+    pub generation_defaults: Option<SharedGenerationConfig>,
+    /// Timeouts per operation, reported by `/metrics`
+    pub timeout_stats: Arc<OperationTimeoutStats>,
+    /// p99 latency against its baseline, checked on every `/health` call
+    pub latency_monitor: Arc<parking_lot::Mutex<ComponentHealthMonitor>>,
+    /// Directory models are stored in; `/health` reports its free space
+    pub models_dir: Option<std::path::PathBuf>,
+    /// Targets for the efficiency score in `/metrics`
+    pub slo: SloConfig,
 }
 
 impl ServerState {
@@ -46,23 +89,174 @@ impl ServerState {
             model_registry: Arc::new(Mutex::new(ModelRegistry::new())),
             metrics: Arc::new(MetricsCollector::new()),
             rate_limiter: Arc::new(RateLimiter::new(100.0, 10.0)),
+            batch_queue: Arc::new(RequestQueue::default()),
+            batch_forward: None,
+            backend: None,
+            tensor_pool: Arc::new(BatchTensorPool::default()),
+            readiness: Arc::new(ReadinessChecker::new()),
+            model_metrics: Arc::new(ModelMetricsStore::new()),
+            streaming: Arc::new(HashMap::new()),
+            chat_sessions: Arc::new(ChatSessionStore::default()),
+            request_validator: Arc::new(RequestValidator::default()),
+            max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+            generation_defaults: None,
+            timeout_stats: Arc::new(OperationTimeoutStats::new()),
+            latency_monitor: Arc::default(),
+            models_dir: None,
+            slo: SloConfig::default(),
         }
     }
 
     /// Create server state and load discovered models
-    #[allow(dead_code)]
     pub fn with_discovered_models(models_dir: std::path::PathBuf) -> MinervaResult<Self> {
         let mut registry = ModelRegistry::new();
         registry.discover(&models_dir)?;
 
         Ok(Self {
             model_registry: Arc::new(Mutex::new(registry)),
-            metrics: Arc::new(MetricsCollector::new()),
-            rate_limiter: Arc::new(RateLimiter::new(100.0, 10.0)),
+            models_dir: Some(models_dir),
+            ..Self::new()
         })
     }
 }
 
+impl FromRef<ServerState> for Arc<RequestValidator> {
+    fn from_ref(state: &ServerState) -> Self {
+        state.request_validator.clone()
+    }
+}
+
+impl ServerState {
+    /// Replace the built-in request schema, e.g. with one from a policy file
+    pub fn with_request_validator(mut self, validator: RequestValidator) -> Self {
+        self.request_validator = Arc::new(validator);
+        self
+    }
+
+    /// Cap request bodies, usually from `ServerConfig.max_request_body_bytes`
+    pub fn with_max_request_body_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_request_body_bytes = max_bytes;
+        self
+    }
+
+    /// Score efficiency against `ServerConfig.slo`
+    pub fn with_slo(mut self, slo: SloConfig) -> Self {
+        self.slo = slo;
+        self
+    }
+
+    /// Fill omitted sampling parameters from the user's overrides
+    pub fn with_generation_defaults(mut self, defaults: SharedGenerationConfig) -> Self {
+        self.generation_defaults = Some(defaults);
+        self
+    }
+
+    /// Attach the model used by the continuous batching loop
+    pub fn with_batch_forward(mut self, forward: Arc<dyn BatchForward>) -> Self {
+        self.batch_forward = Some(forward);
+        self
+    }
+
+    /// Serve requests from `backend`: tokenize with it and batch its forward
+    /// passes
+    pub fn with_backend(self, backend: Arc<dyn InferenceBackend>) -> Self {
+        let mut state = self.with_batch_forward(Arc::new(BackendForward::new(backend.clone())));
+        state.backend = Some(backend);
+        state
+    }
+
+    /// Load the first discovered model onto its fastest backend, batch
+    /// requests through it and start its warmup pass
+    ///
+    /// The server still starts when the model fails to load; batched
+    /// requests then fail and `/ready` reports 503 until a model is loaded.
+    pub async fn with_discovered_backend(self, setup: BackendSetup) -> Self {
+        let Some((model_id, path)) = self.batch_model().await else {
+            return self;
+        };
+        let loaded = tokio::task::spawn_blocking(move || load_backend(&path, &setup)).await;
+        match loaded {
+            Ok(Ok(backend)) => {
+                tracing::info!("Batching requests through {}", model_id);
+                let backend: Arc<dyn InferenceBackend> = Arc::from(backend);
+                let forward: Arc<dyn BatchForward> = Arc::new(BackendForward::new(backend.clone()));
+                let mut state = self.with_batch_forward(forward.clone());
+                state.backend = Some(backend);
+                state.spawn_warmup_with(model_id, move || Ok(forward));
+                state
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to load {} for batching: {}", model_id, e);
+                self
+            }
+            Err(e) => {
+                tracing::warn!("Loading {} for batching panicked: {}", model_id, e);
+                self
+            }
+        }
+    }
+
+    /// Load `path` onto its own backend and run the warmup pass for
+    /// `model_id` through it on a blocking thread
+    ///
+    /// Readiness reports the model as warming until the pass completes, and
+    /// leaves it unready when the backend fails to load.
+    pub fn spawn_warmup(&self, model_id: String, path: PathBuf) {
+        self.spawn_warmup_with(model_id, move || {
+            let backend = load_backend(&path, &BackendSetup::default())?;
+            Ok(Arc::new(BackendForward::new(Arc::from(backend))) as Arc<dyn BatchForward>)
+        });
+    }
+
+    /// Run the warmup pass for `model_id` through the forward `load` yields
+    fn spawn_warmup_with<F>(&self, model_id: String, load: F)
+    where
+        F: FnOnce() -> MinervaResult<Arc<dyn BatchForward>> + Send + 'static,
+    {
+        self.readiness.begin_warmup(&model_id);
+        let readiness = self.readiness.clone();
+        tokio::task::spawn_blocking(move || {
+            let warmed = load().and_then(|forward| readiness.warm_up(&model_id, forward.as_ref()));
+            if let Err(e) = warmed {
+                readiness.abandon_warmup(&model_id);
+                tracing::warn!("Warmup failed for {}: {}", model_id, e);
+            }
+        });
+    }
+
+    /// Registered model the batching loop serves: the first by id
+    async fn batch_model(&self) -> Option<(String, PathBuf)> {
+        let registry = self.model_registry.lock().await;
+        let model_id = registry.list_models().into_iter().map(|m| m.id).min()?;
+        let path = registry.get_model_path(&model_id)?.to_path_buf();
+        Some((model_id, path))
+    }
+
+    /// Apply per-model streaming settings from the app config
+    #[allow(dead_code)]
+    pub fn with_streaming_config(
+        mut self,
+        streaming: HashMap<String, StreamingConfigEntry>,
+    ) -> Self {
+        self.streaming = Arc::new(streaming);
+        self
+    }
+
+    /// Tokens `text` encodes to with the loaded model's tokenizer, estimated
+    /// from its length while no model is loaded
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.backend
+            .as_ref()
+            .and_then(|backend| backend.tokenize(text).ok())
+            .map_or_else(|| estimate_tokens(text), |tokens| tokens.len())
+    }
+
+    /// Streaming settings for `model_id`, falling back to `"*"` then defaults
+    pub fn streaming_settings(&self, model_id: &str) -> StreamingConfigEntry {
+        StreamingConfigEntry::lookup(&self.streaming, model_id)
+    }
+}
+
 impl Default for ServerState {
     fn default() -> Self {
         Self::new()
@@ -85,6 +279,57 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_spawn_warmup_runs_through_loaded_forward() {
+        use crate::inference::mock_backend::MockBackend;
+
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let mut backend = MockBackend::new();
+        backend.load_model(model.path(), 512).unwrap();
+        let forward: Arc<dyn BatchForward> = Arc::new(BackendForward::new(Arc::new(backend)));
+        let state = ServerState::new();
+        state.model_registry.lock().await.add_model(
+            crate::models::ModelInfo {
+                id: "m".to_string(),
+                object: "model".to_string(),
+                created: 0,
+                owned_by: "local".to_string(),
+                context_window: None,
+                max_output_tokens: None,
+                capabilities: Default::default(),
+            },
+            model.path().to_path_buf(),
+        );
+
+        state.spawn_warmup_with("m".to_string(), move || Ok(forward));
+        for _ in 0..100 {
+            if state
+                .readiness
+                .is_ready(&*state.model_registry.lock().await)
+                .is_ready()
+            {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("model never finished warming up");
+    }
+
+    #[test]
+    fn test_count_tokens_uses_loaded_tokenizer() {
+        use crate::inference::mock_backend::MockBackend;
+
+        let mut state = ServerState::new();
+        assert_eq!(
+            state.count_tokens("one two three"),
+            estimate_tokens("one two three")
+        );
+
+        // The mock tokenizer emits one token per word
+        state.backend = Some(Arc::new(MockBackend::new()));
+        assert_eq!(state.count_tokens("one two three"), 3);
+    }
+
     #[test]
     fn test_server_state_default() {
         let state = ServerState::default();
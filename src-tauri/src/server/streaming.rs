@@ -1,15 +1,32 @@
 use super::chat::build_chat_prompt;
 use crate::models::ChatCompletionRequest;
-use axum::response::sse::{Event, KeepAlive};
-use futures::stream;
+use crate::server::ServerState;
+use crate::streaming::{StreamingConfig, StreamingMetadata, backpressured_stream};
+use axum::response::sse::{Event, Sse};
+use futures::{Stream, StreamExt, stream};
+use std::time::Duration;
+use tokio::time::{Instant, MissedTickBehavior};
 use uuid::Uuid;
 
-pub fn create_streaming_response(
+/// Idle time after which an SSE keep-alive comment is sent, well under the
+/// 60s read timeout of common reverse proxies
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Stream the completion for `req`, chunked and paced per the model's
+/// streaming settings
+///
+/// `on_complete` receives the full reply once every chunk has been sent; it
+/// is not called if the client disconnects first.
+pub fn create_streaming_response<F>(
     req: ChatCompletionRequest,
-) -> axum::response::sse::Sse<futures::stream::Iter<std::vec::IntoIter<Result<Event, String>>>> {
-    let completion_id = format!("chatcmpl-{}", Uuid::new_v4());
-    let created = chrono::Utc::now().timestamp();
-    let model = req.model.clone();
+    state: &ServerState,
+    on_complete: F,
+) -> Sse<impl Stream<Item = Result<Event, String>> + use<F>>
+where
+    F: FnOnce(String) + Send + 'static,
+{
+    let config = StreamingConfig::for_request(&req);
+    let settings = state.streaming_settings(&req.model);
     let prompt = build_chat_prompt(&req.messages);
 
     let response_content = format!(
@@ -21,67 +38,143 @@ pub fn create_streaming_response(
         .split_whitespace()
         .map(|w| format!("{} ", w))
         .collect();
+    let chunks = group_tokens(&tokens, settings.max_chunk_size_tokens);
+    let chunk_delay = Duration::from_millis(settings.chunk_delay_ms);
 
-    let token_count = tokens.len();
-    let chunks = build_stream_chunks(StreamChunkParams {
-        tokens,
-        token_count,
-        completion_id,
-        created,
-        model,
+    let metadata = StreamingMetadata {
+        completion_id: format!("chatcmpl-{}", Uuid::new_v4()),
+        model: req.model,
+        created: chrono::Utc::now().timestamp(),
+        prompt_tokens: state.count_tokens(&prompt),
+        completion_tokens: tokens.len(),
+    };
+    let events = backpressured_stream(move |tx| async move {
+        let chunk_count = chunks.len();
+        for (idx, text) in chunks.into_iter().enumerate() {
+            let chunk = build_stream_chunk(text, (idx, chunk_count), &metadata);
+            if tx.send(chunk).await.is_err() {
+                tracing::debug!("Streaming client disconnected, stopping generation");
+                return;
+            }
+            if !chunk_delay.is_zero() {
+                tokio::time::sleep(chunk_delay).await;
+            }
+        }
+        on_complete(response_content);
+        if config.include_usage {
+            let usage = Event::default().json_data(metadata.usage_chunk()).unwrap();
+            let _ = tx.send(Ok(usage)).await;
+        }
     });
 
-    let stream_iter = stream::iter(chunks);
-    axum::response::sse::Sse::new(stream_iter).keep_alive(KeepAlive::default())
+    Sse::new(with_heartbeat(Box::pin(events), HEARTBEAT_INTERVAL))
 }
 
-struct StreamChunkParams {
-    tokens: Vec<String>,
-    token_count: usize,
-    completion_id: String,
-    created: i64,
-    model: String,
+/// Interleave `: keep-alive` comments into `events` whenever nothing has been
+/// sent for `period`
+///
+/// The ticker is reset after every event and dropped when `events` ends, so
+/// fast generations never see a heartbeat. Clients ignore SSE comment lines.
+pub fn with_heartbeat<S, E>(events: S, period: Duration) -> impl Stream<Item = Result<Event, E>>
+where
+    S: Stream<Item = Result<Event, E>> + Unpin,
+{
+    let mut ticker = tokio::time::interval_at(Instant::now() + period, period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    stream::unfold((events, ticker), |(mut events, mut ticker)| async move {
+        tokio::select! {
+            biased;
+            event = events.next() => {
+                ticker.reset();
+                event.map(|event| (event, (events, ticker)))
+            }
+            _ = ticker.tick() => {
+                tracing::trace!("Sending SSE heartbeat");
+                Some((Ok(Event::default().comment("keep-alive")), (events, ticker)))
+            }
+        }
+    })
 }
 
-fn build_stream_chunks(params: StreamChunkParams) -> Vec<Result<Event, String>> {
-    let StreamChunkParams {
-        tokens,
-        token_count,
-        completion_id,
-        created,
-        model,
-    } = params;
+/// Concatenate consecutive tokens into chunks of at most `per_chunk` tokens
+fn group_tokens(tokens: &[String], per_chunk: usize) -> Vec<String> {
     tokens
-        .into_iter()
-        .enumerate()
-        .map(|(idx, token)| {
-            let is_first = idx == 0;
-            let is_last = idx == token_count - 1;
-
-            let chunk = crate::models::ChatCompletionChunk {
-                id: completion_id.clone(),
-                object: "chat.completion.chunk".to_string(),
-                created,
-                model: model.clone(),
-                choices: vec![crate::models::ChoiceDelta {
-                    index: 0,
-                    delta: crate::models::DeltaMessage {
-                        role: if is_first {
-                            Some("assistant".to_string())
-                        } else {
-                            None
-                        },
-                        content: Some(token),
-                    },
-                    finish_reason: if is_last {
-                        Some("stop".to_string())
-                    } else {
-                        None
-                    },
-                }],
-            };
-
-            Ok::<_, String>(Event::default().json_data(chunk).unwrap())
-        })
+        .chunks(per_chunk.max(1))
+        .map(|group| group.concat())
         .collect()
 }
+
+/// SSE event for the token at `(idx, token_count)`
+fn build_stream_chunk(
+    token: String,
+    (idx, token_count): (usize, usize),
+    metadata: &StreamingMetadata,
+) -> Result<Event, String> {
+    let is_first = idx == 0;
+    let is_last = idx == token_count - 1;
+
+    let chunk = crate::models::ChatCompletionChunk {
+        id: metadata.completion_id.clone(),
+        object: "chat.completion.chunk".to_string(),
+        created: metadata.created,
+        model: metadata.model.clone(),
+        choices: vec![crate::models::ChoiceDelta {
+            index: 0,
+            delta: crate::models::DeltaMessage {
+                role: if is_first {
+                    Some("assistant".to_string())
+                } else {
+                    None
+                },
+                content: Some(token),
+            },
+            finish_reason: if is_last {
+                Some("stop".to_string())
+            } else {
+                None
+            },
+        }],
+        usage: None,
+    };
+
+    Ok(Event::default().json_data(chunk).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_tokens() {
+        let tokens: Vec<String> = ["a ", "b ", "c ", "d ", "e "].map(String::from).to_vec();
+        assert_eq!(group_tokens(&tokens, 2), vec!["a b ", "c d ", "e "]);
+        assert_eq!(group_tokens(&tokens, 1).len(), 5);
+        assert_eq!(group_tokens(&tokens, 0).len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_no_heartbeat_for_fast_stream() {
+        let events =
+            stream::iter((0..3).map(|i| Ok::<_, String>(Event::default().data(i.to_string()))));
+        let sent: Vec<_> = with_heartbeat(events, Duration::from_millis(50))
+            .collect()
+            .await;
+        assert_eq!(sent.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_while_generation_is_slow() {
+        let slow = Box::pin(stream::once(async {
+            tokio::time::sleep(Duration::from_millis(90)).await;
+            Ok::<_, String>(Event::default().data("token"))
+        }));
+        let sent: Vec<_> = with_heartbeat(slow, Duration::from_millis(20))
+            .collect()
+            .await;
+
+        // Several heartbeats, then the token, then the stream ends
+        assert!(sent.len() >= 3, "expected heartbeats, got {}", sent.len());
+        assert!(sent.iter().all(Result::is_ok));
+    }
+}
@@ -1,19 +1,194 @@
-use super::chat::build_chat_prompt;
-use crate::error::MinervaResult;
-use crate::middleware::Validator;
-use crate::models::ChatCompletionRequest;
+use crate::error::{MinervaError, MinervaResult};
+use crate::middleware::FieldError;
+use serde_json::Value;
+use std::path::Path;
 
-pub fn validate_chat_request(req: &ChatCompletionRequest) -> MinervaResult<()> {
-    Validator::model_id(&req.model)?;
-    let prompt = build_chat_prompt(&req.messages);
-    Validator::prompt(&prompt, 2000)?;
+/// Schema used when no policy file is given
+///
+/// Mirrors the limits `ParamValidator` and `ProtocolValidator` enforce, so
+/// a policy file written from it starts out consistent with them.
+pub const DEFAULT_CHAT_SCHEMA: &str = r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "type": "object",
+  "required": ["model", "messages"],
+  "properties": {
+    "model": { "type": "string", "minLength": 1, "maxLength": 256 },
+    "messages": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["role", "content"],
+        "properties": {
+          "role": { "enum": ["system", "user", "assistant", "tool"] },
+          "content": { "type": "string" }
+        }
+      }
+    },
+    "temperature": { "type": ["number", "null"], "minimum": 0, "maximum": 2 },
+    "top_p": { "type": ["number", "null"], "exclusiveMinimum": 0, "maximum": 1 },
+    "max_tokens": { "type": ["integer", "null"], "minimum": 1, "maximum": 4096 },
+    "stream": { "type": "boolean" },
+    "stream_options": { "type": "object" },
+    "frequency_penalty": { "type": "number" },
+    "presence_penalty": { "type": "number" }
+  }
+}"#;
 
-    if let Some(temp) = req.temperature {
-        Validator::temperature(temp)?;
+/// JSON Schema check applied to raw request bodies before deserializing
+pub struct RequestValidator {
+    schema: jsonschema::Validator,
+}
+
+impl RequestValidator {
+    /// Compile the JSON Schema in the policy file at `path`
+    pub fn load_schema(path: &Path) -> MinervaResult<Self> {
+        let schema: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Self::compile(&schema)
+    }
+
+    /// The policy file at `path` if one is configured, else the built-in schema
+    pub fn from_policy(path: Option<&Path>) -> MinervaResult<Self> {
+        match path {
+            Some(path) => {
+                tracing::info!("Validating chat requests against {}", path.display());
+                Self::load_schema(path)
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Compile an in-memory schema
+    pub fn compile(schema: &Value) -> MinervaResult<Self> {
+        jsonschema::validator_for(schema)
+            .map(|schema| Self { schema })
+            .map_err(|e| MinervaError::ValidationError(format!("Invalid JSON schema: {}", e)))
+    }
+
+    /// Every schema violation in `raw`, named by its JSON pointer path
+    pub fn validate(&self, raw: &Value) -> Result<(), Vec<FieldError>> {
+        let errors: Vec<FieldError> = self
+            .schema
+            .iter_errors(raw)
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                let field = if path.is_empty() {
+                    "body".to_string()
+                } else {
+                    path
+                };
+                FieldError {
+                    field,
+                    constraint: e.to_string(),
+                }
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Default for RequestValidator {
+    fn default() -> Self {
+        let schema = serde_json::from_str(DEFAULT_CHAT_SCHEMA).expect("built-in schema is JSON");
+        Self::compile(&schema).expect("built-in schema compiles")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_default_schema_accepts_chat_request() {
+        let validator = RequestValidator::default();
+        let body = json!({"model": "llama", "messages": [{"role": "user", "content": "Hi"}]});
+        assert!(validator.validate(&body).is_ok());
+    }
+
+    #[test]
+    fn test_violations_report_paths() {
+        let validator = RequestValidator::default();
+        let errors = validator
+            .validate(&json!({
+                "model": "llama",
+                "messages": [],
+                "frequency_penalty": "x",
+                "stream": 1
+            }))
+            .unwrap_err();
+        let mut fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, vec!["/frequency_penalty", "/stream"]);
     }
-    if let Some(tp) = req.top_p {
-        Validator::top_p(tp)?;
+
+    #[test]
+    fn test_default_schema_requires_messages() {
+        let validator = RequestValidator::default();
+        let errors = validator.validate(&json!({"model": "llama"})).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "body");
+    }
+
+    #[test]
+    fn test_default_schema_bounds_messages_and_sampling() {
+        let validator = RequestValidator::default();
+        let errors = validator
+            .validate(&json!({
+                "model": "llama",
+                "messages": [{"role": "robot", "content": 1}],
+                "temperature": 2.5,
+                "top_p": 0.0,
+                "max_tokens": 0
+            }))
+            .unwrap_err();
+        let mut fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(
+            fields,
+            vec![
+                "/max_tokens",
+                "/messages/0/content",
+                "/messages/0/role",
+                "/temperature",
+                "/top_p"
+            ]
+        );
     }
 
-    Ok(())
+    #[test]
+    fn test_load_schema_from_policy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.json");
+        std::fs::write(
+            &path,
+            r#"{"type": "object", "properties": {"max_tokens": {"maximum": 256}}}"#,
+        )
+        .unwrap();
+        let validator = RequestValidator::load_schema(&path).unwrap();
+
+        assert!(validator.validate(&json!({"max_tokens": 128})).is_ok());
+        let errors = validator
+            .validate(&json!({"max_tokens": 1024}))
+            .unwrap_err();
+        assert_eq!(errors[0].field, "/max_tokens");
+    }
+
+    #[test]
+    fn test_from_policy_defaults_to_built_in_schema() {
+        let validator = RequestValidator::from_policy(None).unwrap();
+        assert!(validator.validate(&json!({"stream": "yes"})).is_err());
+        assert!(RequestValidator::from_policy(Some(Path::new("/missing/policy.json"))).is_err());
+    }
+
+    #[test]
+    fn test_load_invalid_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.json");
+        std::fs::write(&path, r#"{"type": 12}"#).unwrap();
+        assert!(RequestValidator::load_schema(&path).is_err());
+    }
 }
@@ -1,6 +1,31 @@
 //! Streaming event handling
 
 use super::types::{ChatCompletionStreamEvent, StreamingChoice, StreamingDelta};
+use futures::{Stream, stream};
+use std::future::Future;
+use tokio::sync::mpsc;
+
+/// Tokens buffered between the generation task and the HTTP response writer
+pub const TOKEN_CHANNEL_CAPACITY: usize = 32;
+
+/// Run `generate` on its own task and stream what it sends
+///
+/// The channel holds at most `TOKEN_CHANNEL_CAPACITY` items, so when the
+/// client reads slowly `send().await` parks the generator instead of letting
+/// the buffer grow. Once the client disconnects, `send` fails and the
+/// generator should stop.
+pub fn backpressured_stream<T, F, Fut>(generate: F) -> impl Stream<Item = T>
+where
+    T: Send + 'static,
+    F: FnOnce(mpsc::Sender<T>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(TOKEN_CHANNEL_CAPACITY);
+    tokio::spawn(generate(tx));
+    stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    })
+}
 
 /// Formats a single streaming event as JSON
 pub fn format_streaming_event(event: &ChatCompletionStreamEvent) -> String {
@@ -55,6 +80,34 @@ pub fn create_streaming_events(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::StreamExt;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_backpressure_parks_generator_for_slow_client() {
+        let produced = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&produced);
+        let tokens = backpressured_stream(move |tx| async move {
+            for i in 0..100 {
+                if tx.send(i).await.is_err() {
+                    break;
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        let mut tokens = Box::pin(tokens);
+
+        // Client has read nothing yet: generation stops once the channel is full
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(produced.load(Ordering::SeqCst), TOKEN_CHANNEL_CAPACITY);
+
+        assert_eq!(tokens.next().await, Some(0));
+        let rest: Vec<_> = tokens.collect().await;
+        assert_eq!(rest.len(), 99);
+        assert_eq!(produced.load(Ordering::SeqCst), 100);
+    }
 
     #[test]
     fn test_chat_completion_stream_event_creation() {
@@ -5,6 +5,10 @@ pub mod handler;
 pub mod types;
 pub mod validator;
 
-pub use handler::{create_streaming_events, format_streaming_event};
-pub use types::{ChatCompletionStreamEvent, StreamingChoice, StreamingConfig, StreamingDelta};
+pub use handler::{
+    TOKEN_CHANNEL_CAPACITY, backpressured_stream, create_streaming_events, format_streaming_event,
+};
+pub use types::{
+    ChatCompletionStreamEvent, StreamingChoice, StreamingConfig, StreamingDelta, StreamingMetadata,
+};
 pub use validator::StreamingValidator;
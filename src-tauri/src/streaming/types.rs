@@ -1,5 +1,6 @@
 //! Streaming types and structures
 
+use crate::models::{ChatCompletionChunk, ChatCompletionRequest, Usage};
 use serde::{Deserialize, Serialize};
 
 /// Streaming response format (SSE)
@@ -34,6 +35,8 @@ pub struct StreamingConfig {
     pub keep_alive_ms: u64,
     /// Maximum chunk size in tokens
     pub max_chunk_size: usize,
+    /// Send a final usage chunk after the content chunks
+    pub include_usage: bool,
 }
 
 impl Default for StreamingConfig {
@@ -41,6 +44,51 @@ impl Default for StreamingConfig {
         Self {
             keep_alive_ms: 15000,
             max_chunk_size: 50,
+            include_usage: false,
+        }
+    }
+}
+
+impl StreamingConfig {
+    /// Defaults, with `include_usage` taken from the request's `stream_options`
+    pub fn for_request(req: &ChatCompletionRequest) -> Self {
+        Self {
+            include_usage: req.stream_options.is_some_and(|o| o.include_usage),
+            ..Self::default()
+        }
+    }
+}
+
+/// Identity and token counts of one streamed completion
+///
+/// Counts are of the tokens actually fed in and emitted, not estimates.
+#[derive(Debug, Clone)]
+pub struct StreamingMetadata {
+    pub completion_id: String,
+    pub model: String,
+    pub created: i64,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+}
+
+impl StreamingMetadata {
+    pub fn usage(&self) -> Usage {
+        Usage {
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            total_tokens: self.prompt_tokens + self.completion_tokens,
+        }
+    }
+
+    /// Final chunk carrying usage and no choices, sent before `[DONE]`
+    pub fn usage_chunk(&self) -> ChatCompletionChunk {
+        ChatCompletionChunk {
+            id: self.completion_id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            created: self.created,
+            model: self.model.clone(),
+            choices: vec![],
+            usage: Some(self.usage()),
         }
     }
 }
@@ -54,6 +102,22 @@ mod tests {
         let config = StreamingConfig::default();
         assert_eq!(config.keep_alive_ms, 15000);
         assert_eq!(config.max_chunk_size, 50);
+        assert!(!config.include_usage);
+    }
+
+    #[test]
+    fn test_usage_chunk_has_no_choices() {
+        let metadata = StreamingMetadata {
+            completion_id: "chatcmpl-1".to_string(),
+            model: "llama".to_string(),
+            created: 0,
+            prompt_tokens: 12,
+            completion_tokens: 30,
+        };
+
+        let json = serde_json::to_value(metadata.usage_chunk()).unwrap();
+        assert_eq!(json["choices"].as_array().unwrap().len(), 0);
+        assert_eq!(json["usage"]["total_tokens"], 42);
     }
 
     #[test]
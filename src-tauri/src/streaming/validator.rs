@@ -1,5 +1,9 @@
 //! Streaming validation
 
+use crate::error::{MinervaError, MinervaResult};
+use crate::inference::inference_backend_trait::InferenceBackend;
+use crate::models::ChatCompletionRequest;
+
 /// Validates streaming parameters
 pub struct StreamingValidator;
 
@@ -26,11 +30,56 @@ impl StreamingValidator {
         // All models support streaming by default
         !model.is_empty()
     }
+
+    /// Reject `stream: true` requests when the backend cannot emit tokens incrementally
+    pub fn check_backend_capability(
+        backend: &dyn InferenceBackend,
+        request: &ChatCompletionRequest,
+    ) -> MinervaResult<()> {
+        if request.stream == Some(true) && !backend.supports_streaming() {
+            return Err(MinervaError::InvalidRequest(
+                "streaming not supported by current backend".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inference::mock_backend::MockBackend;
+
+    fn request(stream: Option<bool>) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "llama".to_string(),
+            messages: vec![],
+            temperature: None,
+            max_tokens: None,
+            stream,
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stream_options: None,
+        }
+    }
+
+    #[test]
+    fn test_streaming_rejected_without_backend_support() {
+        let backend = MockBackend::new();
+        let result = StreamingValidator::check_backend_capability(&backend, &request(Some(true)));
+        assert!(matches!(result, Err(MinervaError::InvalidRequest(_))));
+
+        assert!(StreamingValidator::check_backend_capability(&backend, &request(None)).is_ok());
+    }
+
+    #[test]
+    fn test_streaming_allowed_with_backend_support() {
+        let backend = MockBackend::new().with_streaming(true);
+        assert!(
+            StreamingValidator::check_backend_capability(&backend, &request(Some(true))).is_ok()
+        );
+    }
 
     #[test]
     fn test_validator_chunk_size_valid() {
@@ -7,10 +7,10 @@ fn create_dummy_weights(config: &InferenceEngineConfig) -> ModelWeights {
 
     for _ in 0..config.num_layers {
         layers.push(LayerWeights {
-            attn_norm_scale: vec![1.0; config.hidden_size],
-            ffn_norm_scale: vec![1.0; config.hidden_size],
-            ff_up: vec![0.1; config.hidden_size * config.intermediate_size],
-            ff_down: vec![0.1; config.intermediate_size * config.hidden_size],
+            attn_norm_scale: vec![1.0; config.hidden_size].into(),
+            ffn_norm_scale: vec![1.0; config.hidden_size].into(),
+            ff_up: vec![0.1; config.hidden_size * config.intermediate_size].into(),
+            ff_down: vec![0.1; config.intermediate_size * config.hidden_size].into(),
         });
     }
 
@@ -97,7 +97,6 @@ fn test_protocol_validator_max_tokens_error_details() {
 
 #[test]
 fn test_protocol_validator_top_p_valid() {
-    assert!(ProtocolValidator::validate_top_p(0.0).is_ok());
     assert!(ProtocolValidator::validate_top_p(0.5).is_ok());
     assert!(ProtocolValidator::validate_top_p(0.9).is_ok());
     assert!(ProtocolValidator::validate_top_p(1.0).is_ok());
@@ -106,6 +105,7 @@ fn test_protocol_validator_top_p_valid() {
 #[test]
 fn test_protocol_validator_top_p_invalid() {
     assert!(ProtocolValidator::validate_top_p(-0.1).is_err());
+    assert!(ProtocolValidator::validate_top_p(0.0).is_err());
     assert!(ProtocolValidator::validate_top_p(1.1).is_err());
     assert!(ProtocolValidator::validate_top_p(2.0).is_err());
 }
@@ -1,7 +1,8 @@
 // Configuration Management Tests
 
 use minerva_lib::config::{
-    ApiConfig, ApplicationConfig, ConfigSource, ConfigValidator, ServerConfig, StreamingConfigEntry,
+    ApiConfig, ApplicationConfig, ConfigSource, ConfigValidator, DEFAULT_MAX_REQUEST_BODY_BYTES,
+    ServerConfig, SloConfig, StreamingConfigEntry,
 };
 
 #[test]
@@ -16,6 +17,8 @@ fn test_server_config_valid() {
         host: "0.0.0.0".to_string(),
         port: 8080,
         workers: Some(4),
+        max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+        slo: SloConfig::default(),
     };
 
     assert!(ConfigValidator::validate_server(&config).is_ok());
@@ -27,6 +30,8 @@ fn test_server_config_localhost() {
         host: "localhost".to_string(),
         port: 3000,
         workers: None,
+        max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+        slo: SloConfig::default(),
     };
 
     assert!(ConfigValidator::validate_server(&config).is_ok());
@@ -38,6 +43,8 @@ fn test_server_config_invalid_port_zero() {
         host: "localhost".to_string(),
         port: 0,
         workers: None,
+        max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+        slo: SloConfig::default(),
     };
 
     assert!(ConfigValidator::validate_server(&config).is_err());
@@ -49,6 +56,8 @@ fn test_server_config_empty_host() {
         host: "".to_string(),
         port: 3000,
         workers: None,
+        max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+        slo: SloConfig::default(),
     };
 
     assert!(ConfigValidator::validate_server(&config).is_err());
@@ -60,6 +69,8 @@ fn test_server_config_high_port() {
         host: "localhost".to_string(),
         port: 65535,
         workers: Some(1),
+        max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+        slo: SloConfig::default(),
     };
 
     assert!(ConfigValidator::validate_server(&config).is_ok());
@@ -137,6 +148,7 @@ fn test_streaming_config_valid() {
         enabled: true,
         chunk_size: 100,
         keep_alive_ms: 15000,
+        ..Default::default()
     };
 
     assert!(ConfigValidator::validate_streaming(&config).is_ok());
@@ -148,6 +160,7 @@ fn test_streaming_config_disabled() {
         enabled: false,
         chunk_size: 50,
         keep_alive_ms: 15000,
+        ..Default::default()
     };
 
     assert!(ConfigValidator::validate_streaming(&config).is_ok());
@@ -159,6 +172,7 @@ fn test_streaming_config_chunk_size_boundary() {
         enabled: true,
         chunk_size: 1000,
         keep_alive_ms: 15000,
+        ..Default::default()
     };
 
     assert!(ConfigValidator::validate_streaming(&config).is_ok());
@@ -170,6 +184,7 @@ fn test_streaming_config_invalid_chunk_size_zero() {
         enabled: true,
         chunk_size: 0,
         keep_alive_ms: 15000,
+        ..Default::default()
     };
 
     assert!(ConfigValidator::validate_streaming(&config).is_err());
@@ -181,6 +196,7 @@ fn test_streaming_config_invalid_chunk_size_over_limit() {
         enabled: true,
         chunk_size: 1001,
         keep_alive_ms: 15000,
+        ..Default::default()
     };
 
     assert!(ConfigValidator::validate_streaming(&config).is_err());
@@ -192,6 +208,7 @@ fn test_streaming_config_invalid_keep_alive_zero() {
         enabled: true,
         chunk_size: 50,
         keep_alive_ms: 0,
+        ..Default::default()
     };
 
     assert!(ConfigValidator::validate_streaming(&config).is_err());
@@ -211,6 +228,8 @@ fn test_application_config_invalid_server() {
             host: "".to_string(),
             port: 3000,
             workers: None,
+            max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+            slo: SloConfig::default(),
         },
         api: ApiConfig::default(),
         streaming: StreamingConfigEntry::default(),
@@ -245,6 +264,7 @@ fn test_application_config_invalid_streaming() {
             enabled: true,
             chunk_size: 0,
             keep_alive_ms: 15000,
+            ..Default::default()
         },
         source: ConfigSource::Default,
     };
@@ -265,6 +285,8 @@ fn test_server_config_with_workers() {
         host: "127.0.0.1".to_string(),
         port: 3000,
         workers: Some(8),
+        max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+        slo: SloConfig::default(),
     };
 
     assert_eq!(config.workers, Some(8));
@@ -279,6 +301,8 @@ fn test_multiple_config_combinations() {
                 host: "0.0.0.0".to_string(),
                 port: 8000,
                 workers: Some(4),
+                max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+                slo: SloConfig::default(),
             },
             api: ApiConfig {
                 version: "2.0".to_string(),
@@ -289,6 +313,7 @@ fn test_multiple_config_combinations() {
                 enabled: true,
                 chunk_size: 100,
                 keep_alive_ms: 30000,
+                ..Default::default()
             },
             source: ConfigSource::File,
         },
@@ -297,6 +322,8 @@ fn test_multiple_config_combinations() {
                 host: "localhost".to_string(),
                 port: 3000,
                 workers: None,
+                max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+                slo: SloConfig::default(),
             },
             api: ApiConfig::default(),
             streaming: StreamingConfigEntry::default(),
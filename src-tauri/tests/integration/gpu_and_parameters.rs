@@ -3,6 +3,7 @@
 // GPU Context Tests
 
 #[test]
+#[cfg(not(cpu_only))]
 fn test_gpu_context_creation() {
     use minerva_lib::inference::gpu_context::GpuContext;
 
@@ -11,6 +12,7 @@ fn test_gpu_context_creation() {
 }
 
 #[test]
+#[cfg(not(cpu_only))]
 fn test_gpu_context_device_detection() {
     use minerva_lib::inference::gpu_context::GpuContext;
 
@@ -21,6 +23,7 @@ fn test_gpu_context_device_detection() {
 }
 
 #[test]
+#[cfg(not(cpu_only))]
 fn test_gpu_device_detection() {
     use minerva_lib::inference::gpu_context::GpuContext;
 
@@ -31,6 +34,7 @@ fn test_gpu_device_detection() {
 }
 
 #[test]
+#[cfg(not(cpu_only))]
 fn test_gpu_context_allocation() {
     use minerva_lib::inference::gpu_context::GpuContext;
 
@@ -41,6 +45,7 @@ fn test_gpu_context_allocation() {
 }
 
 #[test]
+#[cfg(not(cpu_only))]
 fn test_gpu_memory_limits() {
     use minerva_lib::inference::gpu_context::GpuContext;
 
@@ -69,6 +74,7 @@ fn test_gpu_vs_cpu_performance_comparison() {
 }
 
 #[test]
+#[cfg(not(cpu_only))]
 fn test_gpu_initialization_metal() {
     use minerva_lib::inference::gpu_context::GpuContext;
 
@@ -1,9 +1,16 @@
 // Headless Server Integration Tests - Verify Tauri decoupling
 
 use minerva_lib::config::AppConfig;
-use minerva_lib::server::ServerState;
+use minerva_lib::inference::batch_async::BatchAsync;
+use minerva_lib::inference::inference_backend_trait::InferenceBackend;
+use minerva_lib::inference::mock_backend::MockBackend;
+use minerva_lib::models::ChatCompletionResponse;
+use minerva_lib::server::{ServerState, create_server};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tempfile::TempDir;
 
 fn setup_test_models_dir() -> (TempDir, PathBuf) {
@@ -120,3 +127,285 @@ fn test_headless_server_no_tauri_dependency() {
         "Metrics required"
     );
 }
+
+/// Latency or error kind of one load-test request
+type Outcome = Result<Duration, String>;
+
+/// Summary of a `load_test` run
+#[derive(Debug)]
+pub struct LoadTestReport {
+    pub total_requests: usize,
+    pub errors: HashMap<String, usize>,
+    pub error_rate: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub requests_per_sec: f64,
+}
+
+impl LoadTestReport {
+    fn from_outcomes(outcomes: Vec<Outcome>, elapsed: Duration) -> Self {
+        let total_requests = outcomes.len();
+        let mut errors = HashMap::new();
+        let mut latencies = Vec::new();
+        for outcome in outcomes {
+            match outcome {
+                Ok(latency) => latencies.push(latency.as_secs_f64() * 1000.0),
+                Err(kind) => *errors.entry(kind).or_insert(0) += 1,
+            }
+        }
+        latencies.sort_by(f64::total_cmp);
+
+        Self {
+            total_requests,
+            error_rate: (total_requests - latencies.len()) as f64 / total_requests.max(1) as f64,
+            errors,
+            p50_ms: percentile(&latencies, 50.0),
+            p95_ms: percentile(&latencies, 95.0),
+            p99_ms: percentile(&latencies, 99.0),
+            requests_per_sec: total_requests as f64 / elapsed.as_secs_f64(),
+        }
+    }
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let index = (p / 100.0 * (sorted.len() - 1) as f64).round() as usize;
+    sorted[index]
+}
+
+/// Send `total_requests` chat completions from `concurrency` tasks, each
+/// sending its share sequentially; the first `total_requests % concurrency`
+/// tasks send one extra request
+pub async fn load_test(
+    server_url: &str,
+    concurrency: usize,
+    total_requests: usize,
+) -> LoadTestReport {
+    let url = format!("{}/v1/chat/completions", server_url);
+    let per_task = total_requests / concurrency;
+    let remainder = total_requests % concurrency;
+    let started = Instant::now();
+
+    let tasks: Vec<_> = (0..concurrency)
+        .map(|task| {
+            let requests = per_task + usize::from(task < remainder);
+            tokio::spawn(run_client(url.clone(), task, requests))
+        })
+        .collect();
+    let mut outcomes = Vec::with_capacity(total_requests);
+    for task in tasks {
+        outcomes.extend(task.await.expect("load test client panicked"));
+    }
+    LoadTestReport::from_outcomes(outcomes, started.elapsed())
+}
+
+async fn run_client(url: String, task: usize, requests: usize) -> Vec<Outcome> {
+    let client = reqwest::Client::new();
+    let client_id = format!("load-test-{}", task);
+    let mut outcomes = Vec::with_capacity(requests);
+    for request in 0..requests {
+        let body = serde_json::json!({
+            "model": "test-model-1",
+            "messages": [{"role": "user", "content": format!("client {} request {}", task, request)}],
+            "max_tokens": 4
+        });
+        outcomes.push(send_request(&client, &url, (&client_id, body)).await);
+    }
+    outcomes
+}
+
+/// One request; a success must parse as a `ChatCompletionResponse`
+async fn send_request(
+    client: &reqwest::Client,
+    url: &str,
+    (client_id, body): (&str, serde_json::Value),
+) -> Outcome {
+    let started = Instant::now();
+    let response = client
+        .post(url)
+        .header("x-client-id", client_id)
+        .header("content-type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|_| "connection".to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("http_{}", response.status().as_u16()));
+    }
+    let text = response.text().await.map_err(|_| "body".to_string())?;
+    serde_json::from_str::<ChatCompletionResponse>(&text)
+        .map_err(|_| "invalid_response".to_string())?;
+    Ok(started.elapsed())
+}
+
+/// Discovered models served by a mock backend, with the batching loop running
+fn mock_backed_state(models_dir: PathBuf) -> ServerState {
+    let mut backend = MockBackend::new();
+    backend
+        .load_model(&models_dir.join("test-model-1.gguf"), 512)
+        .unwrap();
+    let state = ServerState::with_discovered_models(models_dir)
+        .unwrap()
+        .with_backend(Arc::new(backend));
+    tokio::spawn(BatchAsync::run(state.clone()));
+    state
+}
+
+async fn spawn_server(models_dir: PathBuf) -> String {
+    let state = mock_backed_state(models_dir);
+    let router = create_server(state).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_chat_completions() {
+    let (_temp, models_dir) = setup_test_models_dir();
+    let url = spawn_server(models_dir).await;
+
+    let report = load_test(&url, 4, 20).await;
+
+    assert_eq!(report.total_requests, 20);
+    assert!(report.errors.is_empty(), "errors: {:?}", report.errors);
+    assert_eq!(report.error_rate, 0.0);
+    assert!(report.p50_ms <= report.p95_ms && report.p95_ms <= report.p99_ms);
+    assert!(report.requests_per_sec > 0.0);
+}
+
+#[test]
+fn test_load_test_report_counts_errors() {
+    let outcomes = vec![
+        Ok(Duration::from_millis(10)),
+        Ok(Duration::from_millis(30)),
+        Err("http_429".to_string()),
+        Err("http_429".to_string()),
+    ];
+    let report = LoadTestReport::from_outcomes(outcomes, Duration::from_secs(2));
+
+    assert_eq!(report.errors["http_429"], 2);
+    assert_eq!(report.error_rate, 0.5);
+    assert_eq!(report.requests_per_sec, 2.0);
+    assert_eq!(report.p99_ms, 30.0);
+}
+
+#[tokio::test]
+async fn test_chat_session_history_across_requests() {
+    let (_temp, models_dir) = setup_test_models_dir();
+    let state = mock_backed_state(models_dir);
+    let router = create_server(state.clone()).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!(
+        "http://{}/v1/chat/completions",
+        listener.local_addr().unwrap()
+    );
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    let client = reqwest::Client::new();
+    for content in ["first question", "second question"] {
+        let body = serde_json::json!({
+            "model": "test-model-1",
+            "messages": [{"role": "user", "content": content}],
+            "max_tokens": 4
+        });
+        let response = client
+            .post(&url)
+            .header("x-session-id", "it-session")
+            .header("content-type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(response.headers()["x-session-id"], "it-session");
+    }
+
+    let history = state.chat_sessions.history("it-session").unwrap();
+    let roles: Vec<&str> = history.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, vec!["user", "assistant", "user", "assistant"]);
+    assert_eq!(history[2].content, "second question");
+}
+
+#[tokio::test]
+async fn test_chat_without_session_header_gets_new_session() {
+    let (_temp, models_dir) = setup_test_models_dir();
+    let state = mock_backed_state(models_dir);
+    let router = create_server(state.clone()).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!(
+        "http://{}/v1/chat/completions",
+        listener.local_addr().unwrap()
+    );
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    let body = serde_json::json!({
+        "model": "test-model-1",
+        "messages": [{"role": "user", "content": "hello"}],
+        "max_tokens": 4
+    });
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header("content-type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let session_id = response.headers()["x-session-id"].to_str().unwrap();
+    let history = state.chat_sessions.history(session_id).unwrap();
+    assert_eq!(history.len(), 2);
+}
+
+async fn post_chat(state: ServerState, body: axum::body::Body) -> axum::http::StatusCode {
+    use tower::ServiceExt;
+    let router = create_server(state).await;
+    let request = axum::http::Request::post("/v1/chat/completions")
+        .header("content-type", "application/json")
+        .body(body)
+        .unwrap();
+    router.oneshot(request).await.unwrap().status()
+}
+
+/// A syntactically valid chat request padded to `size` bytes
+fn padded_chat_body(size: usize) -> String {
+    let prefix = r#"{"model": "test-model-1", "messages": [{"role": "user", "content": ""#;
+    let suffix = r#""}]}"#;
+    let padding = "a".repeat(size - prefix.len() - suffix.len());
+    format!("{}{}{}", prefix, padding, suffix)
+}
+
+#[tokio::test]
+async fn test_oversized_body_is_rejected_with_413() {
+    let state = ServerState::new().with_max_request_body_bytes(1024 * 1024);
+    let body = padded_chat_body(2 * 1024 * 1024);
+
+    let status = post_chat(state, axum::body::Body::from(body)).await;
+    assert_eq!(status, axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_chunked_body_limit_counts_all_chunks() {
+    let state = ServerState::new().with_max_request_body_bytes(1024 * 1024);
+    let body = padded_chat_body(2 * 1024 * 1024).into_bytes();
+    // 64 KB chunks, none of which is over the limit on its own
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+        body.chunks(64 * 1024).map(|c| Ok(c.to_vec())).collect();
+
+    let body = axum::body::Body::from_stream(futures::stream::iter(chunks));
+    let status = post_chat(state, body).await;
+    assert_eq!(status, axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_body_under_limit_is_not_rejected_for_size() {
+    let state = ServerState::new().with_max_request_body_bytes(1024 * 1024);
+    let body = padded_chat_body(512 * 1024);
+
+    let status = post_chat(state, axum::body::Body::from(body)).await;
+    assert_ne!(status, axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -1,6 +1,11 @@
 // HTTP API Integration Tests for REST endpoints
 
-use minerva_lib::models::ModelRegistry;
+use minerva_lib::api::ApiErrorResponse;
+use minerva_lib::error::MinervaError;
+use minerva_lib::models::{
+    ChatCompletionChunk, ChatCompletionResponse, ChatMessage, Choice, ChoiceDelta, DeltaMessage,
+    ModelCapabilities, ModelInfo, ModelRegistry, ModelsListResponse, Usage,
+};
 use std::fs;
 use tempfile::TempDir;
 
@@ -171,3 +176,118 @@ fn test_api_retry_logic() {
     let max_retries = 3u8;
     assert_ne!(max_retries, 0, "Should allow retries");
 }
+
+// Wire format snapshots: a failure here means the OpenAI JSON changed.
+// Review the `.snap.new` file and accept it with `cargo insta review`.
+
+fn assert_wire_snapshot<T: serde::Serialize>(name: &str, value: &T) {
+    let mut settings = insta::Settings::clone_current();
+    settings.set_prepend_module_to_snapshot(false);
+    settings.bind(|| insta::assert_json_snapshot!(name, value));
+}
+
+fn usage() -> Usage {
+    Usage {
+        prompt_tokens: 12,
+        completion_tokens: 5,
+        total_tokens: 17,
+    }
+}
+
+fn chunk(delta: DeltaMessage, finish_reason: Option<&str>) -> ChatCompletionChunk {
+    ChatCompletionChunk {
+        id: "chatcmpl-123".to_string(),
+        object: "chat.completion.chunk".to_string(),
+        created: 1_700_000_000,
+        model: "llama-2-7b".to_string(),
+        choices: vec![ChoiceDelta {
+            index: 0,
+            delta,
+            finish_reason: finish_reason.map(str::to_string),
+        }],
+        usage: None,
+    }
+}
+
+#[test]
+fn test_chat_completion_response_snapshot() {
+    let response = ChatCompletionResponse {
+        id: "chatcmpl-123".to_string(),
+        object: "chat.completion".to_string(),
+        created: 1_700_000_000,
+        model: "llama-2-7b".to_string(),
+        choices: vec![Choice {
+            index: 0,
+            message: ChatMessage {
+                role: "assistant".to_string(),
+                content: "Hello there!".to_string(),
+            },
+            finish_reason: "stop".to_string(),
+        }],
+        usage: usage(),
+    };
+    assert_wire_snapshot("chat_completion_response", &response);
+}
+
+#[test]
+fn test_chat_completion_chunk_snapshots() {
+    let first = DeltaMessage {
+        role: Some("assistant".to_string()),
+        content: None,
+    };
+    assert_wire_snapshot("chat_completion_chunk_first", &chunk(first, None));
+
+    let content = DeltaMessage {
+        role: None,
+        content: Some("Hello".to_string()),
+    };
+    assert_wire_snapshot("chat_completion_chunk_content", &chunk(content, None));
+
+    let empty = || DeltaMessage {
+        role: None,
+        content: None,
+    };
+    assert_wire_snapshot(
+        "chat_completion_chunk_length",
+        &chunk(empty(), Some("length")),
+    );
+
+    let mut last = chunk(empty(), Some("stop"));
+    last.usage = Some(usage());
+    assert_wire_snapshot("chat_completion_chunk_usage", &last);
+}
+
+#[test]
+fn test_models_list_response_snapshot() {
+    let response = ModelsListResponse {
+        object: "list".to_string(),
+        data: vec![ModelInfo {
+            id: "llama-2-7b".to_string(),
+            object: "model".to_string(),
+            created: 1_700_000_000,
+            owned_by: "local".to_string(),
+            context_window: Some(4096),
+            max_output_tokens: None,
+            capabilities: ModelCapabilities::default(),
+        }],
+    };
+    assert_wire_snapshot("models_list_response", &response);
+}
+
+#[test]
+fn test_error_response_snapshots() {
+    let not_found = MinervaError::ModelNotFound("Model 'gpt-9' not found".to_string());
+    assert_wire_snapshot(
+        "error_model_not_found",
+        &ApiErrorResponse::from_minerva_error(&not_found),
+    );
+
+    let too_long = MinervaError::ContextLimitExceeded {
+        max: 4096,
+        required: 5000,
+    };
+    assert_wire_snapshot(
+        "error_context_limit_exceeded",
+        &ApiErrorResponse::from_minerva_error(&too_long),
+    );
+}
@@ -23,6 +23,7 @@ fn test_streaming_config_custom_values() {
     let config = StreamingConfig {
         keep_alive_ms: 30000,
         max_chunk_size: 100,
+        include_usage: false,
     };
 
     assert_eq!(config.keep_alive_ms, 30000);